@@ -0,0 +1,90 @@
+/// The `addr2line` subcommand: finds the source file and line of addresses
+/// from the DWARF line programs, printing them as binutils' addr2line does.
+///
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+
+use elf::dwarf::line::LineTable;
+use elf::{ElfError, ElfFile};
+
+#[derive(clap::Args, Debug)]
+pub struct Addr2lineArgs {
+    /// ELF file with line information (a .debug_line section).
+    pub file: PathBuf,
+
+    /// Addresses to look up, in hex (with or without 0x). Without
+    /// any, they're read from standard input, one per line.
+    #[arg(value_parser = parse_address)]
+    pub addresses: Vec<u64>,
+
+    /// Print the address before each location.
+    #[arg(short = 'a', long = "addresses")]
+    pub show_addresses: bool,
+
+    /// Print the column after the line, as `file:line:column`.
+    #[arg(long)]
+    pub column: bool,
+}
+
+pub fn parse_address(address: &str) -> Result<u64, String> {
+    let digits = address
+        .strip_prefix("0x")
+        .or_else(|| address.strip_prefix("0X"))
+        .unwrap_or(address);
+
+    u64::from_str_radix(digits, 16).map_err(|_| format!("'{address}' isn't a hex address"))
+}
+
+/// Prints the location of each address in `args`, or on standard input.
+pub fn addr2line(path: &Path, args: &Addr2lineArgs) -> Result<(), ElfError> {
+    let table = ElfFile::open(path)?.line_table()?;
+    let mut out = io::stdout().lock();
+
+    if !args.addresses.is_empty() {
+        for &address in &args.addresses {
+            print_location(&mut out, &table, Some(address), args)?;
+        }
+        return Ok(());
+    }
+
+    for line in io::stdin().lock().lines() {
+        let line = line?;
+        if !line.trim().is_empty() {
+            print_location(&mut out, &table, parse_address(line.trim()).ok(), args)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Prints where `address` is, with "??" for an unknown file and "?" for an
+/// unknown line, as addr2line does. Addresses that don't parse are unknown.
+fn print_location(
+    out: &mut impl Write,
+    table: &LineTable,
+    address: Option<u64>,
+    args: &Addr2lineArgs,
+) -> io::Result<()> {
+    if args.show_addresses {
+        writeln!(out, "{:#018x}", address.unwrap_or(0))?;
+    }
+
+    let location = address.and_then(|address| table.source_location(address));
+
+    let Some(location) = location else {
+        return writeln!(out, "??:?");
+    };
+
+    let mut line = match location.line {
+        0 => format!("{}:?", location.file),
+        number => format!("{}:{number}", location.file),
+    };
+    if args.column {
+        line.push_str(&format!(":{}", location.column));
+    }
+    if location.discriminator != 0 {
+        line.push_str(&format!(" (discriminator {})", location.discriminator));
+    }
+
+    writeln!(out, "{line}")
+}
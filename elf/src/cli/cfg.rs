@@ -0,0 +1,146 @@
+/// The `cfg` subcommand: disassembles the code by following it from the
+/// entry point and the function symbols, and shows each function's basic
+/// blocks and where control goes between them, or writes their graphs in
+/// Graphviz's DOT language.
+///
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use elf::demangle::demangle_symbol;
+use elf::disasm::cfg::{ControlFlow, EdgeKind, Function};
+use elf::disasm::{Disassembler, Syntax};
+use elf::{ElfError, ElfFile};
+
+use super::disasm::{labels, symbolize};
+
+#[derive(clap::Args, Debug)]
+pub struct CfgArgs {
+    /// ELF file to disassemble.
+    pub file: PathBuf,
+
+    /// Only show the functions with these names (demangled, with
+    /// `--demangle`).
+    #[arg(short, long = "function")]
+    pub functions: Vec<String>,
+
+    /// Write each function's control flow graph as a Graphviz digraph.
+    #[arg(long)]
+    pub dot: bool,
+
+    /// Write x86 instructions in Intel syntax, instead of AT&T syntax.
+    #[arg(long)]
+    pub intel: bool,
+
+    /// Show C++ and Rust function and symbol names demangled.
+    #[arg(long)]
+    pub demangle: bool,
+}
+
+/// Prints the functions of the file at `path`, with their basic blocks.
+pub fn cfg(path: &Path, args: &CfgArgs) -> Result<(), ElfError> {
+    let mut elf_file = ElfFile::open(path)?;
+
+    let syntax = match args.intel {
+        true => Syntax::Intel,
+        false => Syntax::Att,
+    };
+    let disassembler = Disassembler::for_file(&mut elf_file, syntax)?;
+    let mut control_flow = ControlFlow::for_file(&mut elf_file, disassembler)?;
+    if args.demangle {
+        for function in &mut control_flow.functions {
+            function.name = demangle_symbol(&function.name).into_owned();
+        }
+    }
+
+    let functions: Vec<&Function> = match args.functions.is_empty() {
+        true => control_flow.functions.iter().collect(),
+        false => args
+            .functions
+            .iter()
+            .map(|name| {
+                control_flow
+                    .function(name)
+                    .ok_or_else(|| ElfError::UnknownFunction { name: name.clone() })
+            })
+            .collect::<Result<_, _>>()?,
+    };
+
+    let labels = labels(&mut elf_file, args.demangle)?;
+    let symbolize = |address| symbolize(&labels, &elf_file, address);
+    let mut out = io::stdout().lock();
+
+    for function in functions {
+        if args.dot {
+            write!(out, "{}", function.to_dot(&symbolize))?;
+        } else {
+            print_function(&mut out, function, &symbolize)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes an address with the name `symbolize` gives it, as in
+/// `0x401126 <main+0x6>`.
+fn named(address: u64, symbolize: &dyn Fn(u64) -> Option<String>) -> String {
+    match symbolize(address) {
+        Some(name) => format!("{address:#x} <{name}>"),
+        None => format!("{address:#x}"),
+    }
+}
+
+fn print_function(
+    out: &mut impl Write,
+    function: &Function,
+    symbolize: &dyn Fn(u64) -> Option<String>,
+) -> io::Result<()> {
+    let count = match function.blocks.len() {
+        1 => "1 block".to_owned(),
+        n => format!("{n} blocks"),
+    };
+    writeln!(
+        out,
+        "\n{} at {:#x}, {count}",
+        function.name, function.address
+    )?;
+    if !function.calls.is_empty() {
+        let calls: Vec<String> = function
+            .calls
+            .iter()
+            .map(|&address| named(address, symbolize))
+            .collect();
+        writeln!(out, "  calls {}", calls.join(", "))?;
+    }
+
+    for block in &function.blocks {
+        writeln!(out, "\n  {}:", named(block.start, symbolize))?;
+        for instruction in &block.instructions {
+            writeln!(
+                out,
+                "    {:x}:\t{}",
+                instruction.address(),
+                instruction.format(symbolize)
+            )?;
+        }
+
+        let successors: Vec<String> = block
+            .successors
+            .iter()
+            .map(|edge| {
+                let kind = match edge.kind {
+                    EdgeKind::Jump => "jump",
+                    EdgeKind::Taken => "taken",
+                    EdgeKind::NotTaken => "not taken",
+                    EdgeKind::Fallthrough => "fallthrough",
+                };
+                format!("{:#x} ({kind})", edge.target)
+            })
+            .collect();
+        match successors.is_empty() {
+            true => writeln!(out, "    -> none")?,
+            false => writeln!(out, "    -> {}", successors.join(", "))?,
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,192 @@
+/// The `functions` and `layout` subcommands, which show what's in the DWARF
+/// debugging information: the functions with their addresses, and the memory
+/// layout of types in the format of `pahole`.
+///
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use elf::dwarf::layout::Layout;
+use elf::{ElfError, ElfFile};
+
+/// The size of a cache line, which `pahole` marks the boundaries of.
+const CACHE_LINE: u64 = 64;
+
+#[derive(clap::Args, Debug)]
+pub struct LayoutArgs {
+    /// ELF file with debugging information (a .debug_info section).
+    pub file: PathBuf,
+
+    /// Structures, classes, or unions to show, by name. Without
+    /// any, all of the named ones are shown.
+    pub types: Vec<String>,
+}
+
+/// Lists the functions in the file at `path`, in order of address: the
+/// start and size of their code, and their names. A function whose code
+/// is in more than one piece is listed for each.
+pub fn functions(path: &Path) -> Result<(), ElfError> {
+    let functions = ElfFile::open(path)?.debug_info()?.functions()?;
+
+    let mut lines = vec![];
+    for function in &functions {
+        let name = function
+            .name
+            .as_deref()
+            .or(function.linkage_name.as_deref())
+            .unwrap_or("??");
+
+        for range in &function.ranges {
+            lines.push((range.start, range.end - range.start, name));
+        }
+    }
+    lines.sort();
+
+    let mut out = io::stdout().lock();
+    for (start, size, name) in lines {
+        writeln!(out, "{start:#018x} {size:>6}  {name}")?;
+    }
+
+    Ok(())
+}
+
+/// Prints the layout of the types in `args` in the file at `path`.
+pub fn layout(path: &Path, args: &LayoutArgs) -> Result<(), ElfError> {
+    let layouts = ElfFile::open(path)?.debug_info()?.layouts();
+
+    let selected: Vec<&Layout> = match args.types.as_slice() {
+        [] => layouts.iter().collect(),
+
+        names => names
+            .iter()
+            .map(|name| {
+                layouts
+                    .iter()
+                    .find(|layout| layout.name.as_deref() == Some(name))
+                    .ok_or_else(|| ElfError::UnknownType { name: name.clone() })
+            })
+            .collect::<Result<_, _>>()?,
+    };
+
+    let mut out = io::stdout().lock();
+    for (i, layout) in selected.iter().enumerate() {
+        if i > 0 {
+            writeln!(out)?;
+        }
+        print_layout(&mut out, layout)?;
+    }
+
+    Ok(())
+}
+
+/// Describes a number of bits as bytes when it's whole bytes.
+fn bits_or_bytes(bits: u64) -> String {
+    match bits % 8 {
+        0 => format!("{} bytes", bits / 8),
+        _ => format!("{bits} bits"),
+    }
+}
+
+fn print_layout(out: &mut impl Write, layout: &Layout) -> io::Result<()> {
+    let name = layout.name.as_deref().unwrap_or("{...}");
+    writeln!(out, "{} {name} {{", layout.kind)?;
+
+    let holes = layout.holes();
+    let mut next_cache_line = CACHE_LINE;
+
+    for (index, member) in layout.members.iter().enumerate() {
+        if let Some(hole) = holes.iter().find(|hole| hole.before == index) {
+            writeln!(out)?;
+            writeln!(
+                out,
+                "\t/* XXX {} hole, try to pack */",
+                bits_or_bytes(hole.bits)
+            )?;
+            writeln!(out)?;
+        }
+
+        while member.byte_offset() >= next_cache_line {
+            writeln!(
+                out,
+                "\t/* --- cacheline {} boundary ({next_cache_line} bytes) --- */",
+                next_cache_line / CACHE_LINE
+            )?;
+            next_cache_line += CACHE_LINE;
+        }
+
+        let name = match (member.is_base_class, member.is_bit_field) {
+            (true, _) => "<ancestor>;".to_owned(),
+            (false, true) => format!("{}:{};", member.name, member.bit_size),
+            (false, false) => format!("{};", member.name),
+        };
+
+        // Bit fields show the bit they start at, after the byte.
+        let position = match member.is_bit_field {
+            true => format!(
+                "{:5}:{:2} {:5}",
+                member.byte_offset(),
+                member.bit_offset % 8,
+                member.byte_size
+            ),
+            false => format!("{:5} {:5}", member.byte_offset(), member.bit_size / 8),
+        };
+
+        writeln!(
+            out,
+            "\t{:<26} {name:<21} /* {position} */",
+            member.type_name
+        )?;
+    }
+
+    // Holes of whole bytes are counted apart from those between bit fields.
+    let (byte_holes, bit_holes): (Vec<_>, Vec<_>) = holes
+        .iter()
+        .map(|hole| hole.bits)
+        .partition(|bits| bits.is_multiple_of(8));
+    let holes_bits: u64 = holes.iter().map(|hole| hole.bits).sum();
+    let padding_bits = layout.padding_bits();
+
+    if !layout.members.is_empty() {
+        writeln!(out)?;
+    }
+    writeln!(
+        out,
+        "\t/* size: {}, cachelines: {}, members: {} */",
+        layout.size,
+        layout.size.div_ceil(CACHE_LINE),
+        layout.members.len()
+    )?;
+
+    if !byte_holes.is_empty() {
+        let member_bits = (layout.size * 8).saturating_sub(holes_bits + padding_bits);
+
+        writeln!(
+            out,
+            "\t/* sum members: {}, holes: {}, sum holes: {} */",
+            member_bits / 8,
+            byte_holes.len(),
+            byte_holes.iter().sum::<u64>() / 8
+        )?;
+    }
+    if !bit_holes.is_empty() {
+        writeln!(
+            out,
+            "\t/* bit holes: {}, sum bit holes: {} bits */",
+            bit_holes.len(),
+            bit_holes.iter().sum::<u64>()
+        )?;
+    }
+
+    if padding_bits >= 8 {
+        writeln!(out, "\t/* padding: {} */", padding_bits / 8)?;
+    }
+    if !padding_bits.is_multiple_of(8) {
+        writeln!(out, "\t/* bit_padding: {} bits */", padding_bits % 8)?;
+    }
+
+    let last = layout.size % CACHE_LINE;
+    if last != 0 {
+        writeln!(out, "\t/* last cacheline: {last} bytes */")?;
+    }
+
+    writeln!(out, "}};")
+}
@@ -0,0 +1,476 @@
+/// The `disasm` subcommand: disassembles the executable sections, laid out
+/// as `objdump -d` lays them out, with a label at each symbol and branch
+/// targets named after the symbol they're in.
+///
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use elf::demangle::demangle_symbol;
+use elf::disasm::{aarch64, Disassembler, Syntax};
+use elf::parse::symbols::*;
+use elf::parse::types::*;
+use elf::parse::versions::*;
+use elf::parse::{SHF_ALLOC, SHF_EXECINSTR};
+use elf::{ElfError, ElfFile};
+
+#[derive(clap::Args, Debug)]
+pub struct DisasmArgs {
+    /// ELF file to disassemble.
+    pub file: PathBuf,
+
+    /// Write instructions in Intel syntax, as `objdump -M intel` does,
+    /// instead of AT&T syntax.
+    #[arg(long)]
+    pub intel: bool,
+
+    /// Only disassemble the sections with these names (and they needn't
+    /// be executable).
+    #[arg(short = 'j', long = "section")]
+    pub sections: Vec<String>,
+
+    /// Show C++ and Rust symbol names demangled, as `objdump -C` does.
+    #[arg(long)]
+    pub demangle: bool,
+}
+
+/// As many bytes of an instruction as objdump shows on one line.
+const BYTES_PER_LINE: usize = 7;
+
+/// How wide objdump leaves the bytes of RISC-V instructions, of either size.
+const RISCV_BYTES_PER_LINE: usize = 8;
+
+/// objdump leaves out runs of zeros this long (and short ones at the end
+/// of a symbol's code), as padding rather than code.
+const SKIP_ZEROS: usize = 8;
+const SKIP_ZEROS_AT_END: usize = 3;
+
+/// A name for an address, for labels and branch targets.
+pub(super) struct Label {
+    address: u64,
+    name: String,
+    section: usize,
+}
+
+/// The labels, in order of address, and the names of the GOT slots that
+/// imports are loaded from, which only name their own addresses.
+pub(super) struct Labels {
+    labels: Vec<Label>,
+    got: BTreeMap<u64, String>,
+}
+
+/// Disassembles the file at `path`.
+pub fn disasm(path: &Path, args: &DisasmArgs) -> Result<(), ElfError> {
+    let mut elf_file = ElfFile::open(path)?;
+
+    let syntax = match args.intel {
+        true => Syntax::Intel,
+        false => Syntax::Att,
+    };
+    let disassembler = Disassembler::for_file(&mut elf_file, syntax)?;
+
+    for name in &args.sections {
+        if elf_file.section_index_by_name(name).is_none() {
+            return Err(ElfError::UnknownSection { name: name.clone() });
+        }
+    }
+
+    let labels = labels(&mut elf_file, args.demangle)?;
+    let mapping = mapping_symbols(&mut elf_file)?;
+    let sections: Vec<usize> = (0..elf_file.section_headers.len())
+        .filter(|&index| {
+            let section = &elf_file.section_headers[index];
+            match args.sections.is_empty() {
+                true => {
+                    let flags = section.header_data.flags;
+                    flags & SHF_EXECINSTR != 0 && flags & SHF_ALLOC != 0
+                }
+                false => args.sections.contains(&section.name),
+            }
+        })
+        .collect();
+
+    let format = match disassembler {
+        Disassembler::X86_64(_) => "elf64-x86-64",
+        Disassembler::Aarch64 => "elf64-littleaarch64",
+        Disassembler::Riscv(_) => "elf64-littleriscv",
+    };
+    let mut out = io::stdout().lock();
+    writeln!(out, "\n{}:     file format {format}\n", path.display())?;
+
+    for index in sections {
+        let section = &elf_file.section_headers[index];
+        if section.header_data.type_id() == SHT_NOBITS || section.header_data.size == 0 {
+            continue;
+        }
+        let name = section.name.clone();
+        let start = section.header_data.addr;
+        let Some(end) = start.checked_add(section.header_data.size) else {
+            continue;
+        };
+
+        let code = elf_file.section_bytes(index)?.to_vec();
+        writeln!(out, "\nDisassembly of section {name}:")?;
+
+        // The section is disassembled a symbol at a time, and from its
+        // start if no symbol is there.
+        // From its start, it's named after the first symbol, if it has
+        // any, as in `printf@plt-0x10`.
+        let mut starts: Vec<(u64, String)> = labels
+            .labels
+            .iter()
+            .filter(|label| label.section == index && (start..end).contains(&label.address))
+            .map(|label| (label.address, label.name.clone()))
+            .collect();
+        match starts.first() {
+            Some((address, _)) if *address == start => {}
+            Some((address, first)) => {
+                let label = format!("{first}-{:#x}", address - start);
+                starts.insert(0, (start, label));
+            }
+            None => starts.insert(0, (start, name.clone())),
+        }
+
+        let width = address_width(end);
+        let symbolize = |address| symbolize(&labels, &elf_file, address);
+        let is_data = |address| is_data(&mapping, index, address);
+
+        for (i, (address, label)) in starts.iter().enumerate() {
+            let address = *address;
+            let stop = starts.get(i + 1).map_or(end, |(next, _)| *next);
+            writeln!(out, "\n{address:016x} <{label}>:")?;
+
+            let bytes = &code[(address - start) as usize..(stop - start) as usize];
+            print_code(
+                &mut out,
+                bytes,
+                address,
+                disassembler,
+                width,
+                &symbolize,
+                &is_data,
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+/// The labels of a file's code and data: its symbols, and a symbol for
+/// each PLT stub, named after the import it's for (as in `printf@plt`).
+/// The GOT slots of imports are named after their symbols, with their
+/// versions (as in `printf@GLIBC_2.2.5`). With `demangle`, the names are
+/// demangled, keeping those suffixes.
+pub(super) fn labels(elf_file: &mut ElfFile, demangle: bool) -> Result<Labels, ElfError> {
+    let mut labels = symbol_labels(elf_file)?;
+    let imports = elf_file.imports()?;
+
+    for import in &imports.imports {
+        let Some(plt) = import.plt else {
+            continue;
+        };
+        let section = elf_file.section_headers.iter().position(|section| {
+            let header = &section.header_data;
+            header.flags & SHF_ALLOC != 0
+                && header
+                    .addr
+                    .checked_add(header.size)
+                    .is_some_and(|end| (header.addr..end).contains(&plt))
+        });
+        let at = labels.partition_point(|label| label.address < plt);
+        match (section, labels.get(at)) {
+            (None, _) => {}
+            (Some(_), Some(label)) if label.address == plt => {}
+            (Some(section), _) => labels.insert(
+                at,
+                Label {
+                    address: plt,
+                    name: format!("{}@plt", import.name),
+                    section,
+                },
+            ),
+        }
+    }
+
+    let mut got = BTreeMap::new();
+    let dynsym = elf_file
+        .section_indices_by_type(SHT_DYNSYM)
+        .first()
+        .copied();
+    let versions = elf_file.symbol_versions()?;
+    for import in &imports.imports {
+        let mut name = import.name.clone();
+        if let (Some(dynsym), Some(versions)) = (dynsym, &versions) {
+            if import.symbol_index != 0 {
+                let symbol = elf_file.symbol(dynsym, import.symbol_index)?;
+                name += &version_suffix(versions, import.symbol_index, &symbol.symbol_data);
+            }
+        }
+        got.insert(import.got, name);
+    }
+
+    if demangle {
+        for label in &mut labels {
+            label.name = demangle_symbol(&label.name).into_owned();
+        }
+        for name in got.values_mut() {
+            *name = demangle_symbol(name).into_owned();
+        }
+    }
+
+    Ok(Labels { labels, got })
+}
+
+/// The symbols that name code and data, one for each address, from the
+/// symbol table or, if the file has been stripped, the dynamic one (with
+/// their versions, as in `memcpy@@GLIBC_2.14`).
+fn symbol_labels(elf_file: &mut ElfFile) -> Result<Vec<Label>, ElfError> {
+    let (table, versions) = match elf_file.section_indices_by_type(SHT_SYMTAB).first() {
+        Some(&index) => (index, None),
+        None => match elf_file.section_indices_by_type(SHT_DYNSYM).first() {
+            Some(&index) => (index, elf_file.symbol_versions()?),
+            None => return Ok(vec![]),
+        },
+    };
+
+    let mut symbols: Vec<_> = elf_file
+        .symbols(table)?
+        .into_iter()
+        .enumerate()
+        .filter(|(_, symbol)| {
+            let entry = &symbol.symbol_data;
+            let section = entry.section_index as usize;
+            !symbol.name.is_empty()
+                && !is_mapping_symbol(&symbol.name)
+                && !matches!(entry.symbol_type(), STT_SECTION | STT_FILE | STT_TLS)
+                && section != 0
+                && section < elf_file.section_headers.len()
+        })
+        .map(|(i, mut symbol)| {
+            if let Some(versions) = &versions {
+                symbol.name += &version_suffix(versions, i, &symbol.symbol_data);
+            }
+            symbol
+        })
+        .collect();
+
+    // Where symbols share an address, the one objdump would show: a
+    // function over anything else, then a global symbol over a weak one
+    // over a local one.
+    let rank = |symbol: &Elf64SymbolInfo| {
+        let entry = &symbol.symbol_data;
+        let binding = match entry.bind() {
+            STB_GLOBAL => 0,
+            STB_WEAK => 1,
+            _ => 2,
+        };
+        (entry.symbol_type() != STT_FUNC, binding)
+    };
+    symbols.sort_by(|a, b| {
+        let key = |symbol: &Elf64SymbolInfo| (symbol.symbol_data.value, rank(symbol));
+        key(a).cmp(&key(b)).then_with(|| a.name.cmp(&b.name))
+    });
+    symbols.dedup_by_key(|symbol| symbol.symbol_data.value);
+
+    Ok(symbols
+        .into_iter()
+        .map(|symbol| Label {
+            address: symbol.symbol_data.value,
+            name: symbol.name,
+            section: symbol.symbol_data.section_index as usize,
+        })
+        .collect())
+}
+
+/// The mapping symbols, as the section, address and whether data (`$d`)
+/// rather than code starts there, in order.
+fn mapping_symbols(elf_file: &mut ElfFile) -> Result<Vec<(usize, u64, bool)>, ElfError> {
+    let Some(&table) = elf_file.section_indices_by_type(SHT_SYMTAB).first() else {
+        return Ok(vec![]);
+    };
+    let mut mapping: Vec<_> = elf_file
+        .symbols(table)?
+        .into_iter()
+        .filter(|symbol| is_mapping_symbol(&symbol.name))
+        .map(|symbol| {
+            let entry = &symbol.symbol_data;
+            (
+                entry.section_index as usize,
+                entry.value,
+                symbol.name.starts_with("$d"),
+            )
+        })
+        .collect();
+    mapping.sort();
+    Ok(mapping)
+}
+
+/// Whether the mapping symbols say that `address`, in the section at
+/// `section`, holds data, as literal pools in AArch64 code do.
+fn is_data(mapping: &[(usize, u64, bool)], section: usize, address: u64) -> bool {
+    let closest =
+        mapping.partition_point(|&(index, start, _)| (index, start) <= (section, address));
+    matches!(closest.checked_sub(1).map(|i| mapping[i]), Some((index, _, true)) if index == section)
+}
+
+/// The version suffix objdump gives a dynamic symbol: as readelf's, except
+/// that symbols of the file's own base version are "@@Base" (or "@Base",
+/// if hidden or undefined).
+fn version_suffix(versions: &SymbolVersions, index: usize, symbol: &Elf64SymbolEntry) -> String {
+    if let Some(version) = versions.symbol_version(index, symbol) {
+        return version.suffix();
+    }
+    match versions.versym.get(index) {
+        Some(&versym) if versym & VERSYM_VERSION == VER_NDX_GLOBAL => {
+            match versym & VERSYM_HIDDEN != 0 || symbol.section_index == SHN_UNDEF {
+                false => "@@Base".to_owned(),
+                true => "@Base".to_owned(),
+            }
+        }
+        _ => String::new(),
+    }
+}
+
+/// Names an address after the closest label at or before it, as in
+/// `main+0x1c`, or after the section it's in if there's none. A GOT slot
+/// is named after its import. Without any symbols at all, addresses go
+/// unnamed.
+pub(super) fn symbolize(labels: &Labels, elf_file: &ElfFile, address: u64) -> Option<String> {
+    if let Some(name) = labels.got.get(&address) {
+        return Some(name.clone());
+    }
+    let labels = &labels.labels;
+    if labels.is_empty() {
+        return None;
+    }
+    let closest = labels.partition_point(|label| label.address <= address);
+
+    let (base, name) = match closest {
+        0 => {
+            let index = elf_file.section_headers.iter().position(|section| {
+                let header = &section.header_data;
+                header.flags & SHF_ALLOC != 0
+                    && header
+                        .addr
+                        .checked_add(header.size)
+                        .is_some_and(|end| (header.addr..end).contains(&address))
+            })?;
+            // Before the first label in the same section, it's named from
+            // that, as in `printf@plt-0x10`.
+            let first = &labels[0];
+            if first.section == index {
+                return Some(format!("{}-{:#x}", first.name, first.address - address));
+            }
+            let section = &elf_file.section_headers[index];
+            (section.header_data.addr, section.name.as_str())
+        }
+        closest => (
+            labels[closest - 1].address,
+            labels[closest - 1].name.as_str(),
+        ),
+    };
+
+    Some(match address - base {
+        0 => name.to_owned(),
+        offset => format!("{name}+{offset:#x}"),
+    })
+}
+
+/// How many digits of addresses objdump shows for a section ending at
+/// `end`: leading zeros go in fours, leaving at least one.
+fn address_width(end: u64) -> usize {
+    let zeros = format!("{end:016x}")
+        .bytes()
+        .take_while(|&b| b == b'0')
+        .count();
+    match zeros {
+        0 | 16 => 16,
+        zeros => 16 - ((zeros - 1) & !3),
+    }
+}
+
+/// Prints the instructions in `bytes`, which start at `address`.
+fn print_code(
+    out: &mut impl Write,
+    bytes: &[u8],
+    address: u64,
+    disassembler: Disassembler,
+    width: usize,
+    symbolize: &dyn Fn(u64) -> Option<String>,
+    is_data: &dyn Fn(u64) -> bool,
+) -> io::Result<()> {
+    let mut offset = 0;
+
+    while offset < bytes.len() {
+        let zeros = bytes[offset..].iter().take_while(|&&b| b == 0).count();
+        let at_end = offset + zeros == bytes.len();
+        if zeros >= SKIP_ZEROS || (at_end && zeros < SKIP_ZEROS_AT_END && zeros > 0) {
+            writeln!(out, "\t...")?;
+            // Before more code, skip a multiple of four, in case the last
+            // zeros start an instruction.
+            offset += if at_end { zeros } else { zeros & !3 };
+            continue;
+        }
+
+        let here = address + offset as u64;
+        // Data among the code is shown as words, rather than decoded.
+        if let (Disassembler::Aarch64, Some(word)) = (disassembler, bytes[offset..].first_chunk()) {
+            if is_data(here) {
+                let word = u32::from_le_bytes(*word);
+                writeln!(out, "{here:>width$x}:\t{word:08x} \t.word\t{word:#010x}")?;
+                offset += aarch64::INSTRUCTION_LEN;
+                continue;
+            }
+        }
+        let instruction = disassembler.decode(&bytes[offset..], here);
+        let encoding = &bytes[offset..offset + instruction.size()];
+
+        let mut line = format!("{here:>width$x}:\t");
+        match disassembler {
+            Disassembler::X86_64(_) => {
+                for byte in &encoding[..encoding.len().min(BYTES_PER_LINE)] {
+                    line.push_str(&format!("{byte:02x} "));
+                }
+                for _ in encoding.len()..BYTES_PER_LINE {
+                    line.push_str("   ");
+                }
+            }
+            // Instructions of a fixed size are shown as the word they are.
+            Disassembler::Aarch64 => {
+                for byte in encoding.iter().rev() {
+                    line.push_str(&format!("{byte:02x}"));
+                }
+                line.push(' ');
+            }
+            // Instructions of either size are shown as the number they
+            // are, padded to eight bytes' width as objdump pads them.
+            Disassembler::Riscv(_) => {
+                for byte in encoding.iter().rev() {
+                    line.push_str(&format!("{byte:02x}"));
+                }
+                line.push(' ');
+                let chunk = encoding.len().max(1);
+                for _ in (encoding.len()..RISCV_BYTES_PER_LINE).step_by(chunk) {
+                    line.push_str(&" ".repeat(chunk * 2 + 1));
+                }
+            }
+        }
+        line.push('\t');
+        line.push_str(&instruction.format(symbolize));
+
+        if let Disassembler::X86_64(_) = disassembler {
+            for (i, chunk) in encoding.chunks(BYTES_PER_LINE).enumerate().skip(1) {
+                let continued = here + (i * BYTES_PER_LINE) as u64;
+                line.push_str(&format!("\n{continued:>width$x}:\t"));
+                for byte in chunk {
+                    line.push_str(&format!("{byte:02x} "));
+                }
+            }
+        }
+        writeln!(out, "{line}")?;
+
+        offset += instruction.size();
+    }
+
+    Ok(())
+}
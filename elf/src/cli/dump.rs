@@ -0,0 +1,116 @@
+/// The `dump` subcommand: writes out the contents of a section
+/// or segment, as raw bytes or as a hex dump.
+///
+use std::fs;
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+
+use clap::ArgGroup;
+
+use elf::{ElfError, ElfFile};
+
+#[derive(clap::Args, Debug)]
+#[command(group(ArgGroup::new("part").required(true).args(["section", "segment"])))]
+pub struct DumpArgs {
+    /// ELF file to dump from.
+    pub file: PathBuf,
+
+    /// Section to dump, by name or index. NOBITS sections (like .bss) are zeros.
+    #[arg(long)]
+    pub section: Option<String>,
+
+    /// Segment to dump, by index. The part of it that's
+    /// only in memory (past its size in the file) is zeros.
+    #[arg(long)]
+    pub segment: Option<usize>,
+
+    /// File to write to, instead of standard output.
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+
+    /// Write a hex dump rather than the raw bytes. This is the
+    /// default when writing to a terminal.
+    #[arg(long)]
+    pub hex: bool,
+}
+
+/// Dumps the section or segment selected by `args`.
+pub fn dump(path: &Path, args: &DumpArgs) -> Result<(), ElfError> {
+    let mut elf_file = ElfFile::open(path)?;
+
+    let (data, address) = match (&args.section, args.segment) {
+        (Some(section), _) => {
+            let index = section_index(&elf_file, section)?;
+            let address = elf_file.section(index)?.addr;
+
+            (elf_file.section_data(index)?, address)
+        }
+
+        (None, Some(index)) => {
+            let address = elf_file.segment(index)?.virtual_address;
+
+            (elf_file.segment_data(index)?, address)
+        }
+
+        // Clap requires one or the other.
+        (None, None) => unreachable!(),
+    };
+
+    let hex = args.hex || (args.output.is_none() && io::stdout().is_terminal());
+    let contents = if hex {
+        hex_dump(&data, address).into_bytes()
+    } else {
+        data.into_owned()
+    };
+
+    match &args.output {
+        Some(output) => fs::write(output, contents)?,
+        None => io::stdout().lock().write_all(&contents)?,
+    }
+
+    Ok(())
+}
+
+/// Finds a section by index if `section` is a number, or else by name.
+fn section_index(elf_file: &ElfFile, section: &str) -> Result<usize, ElfError> {
+    if let Ok(index) = section.parse::<usize>() {
+        return Ok(index);
+    }
+
+    elf_file
+        .section_index_by_name(section)
+        .ok_or_else(|| ElfError::UnknownSection {
+            name: section.to_string(),
+        })
+}
+
+/// Formats `data` the way `readelf -x` does: 16 bytes per line, labelled with
+/// their address (starting at `address`), as hex words and then as ASCII.
+fn hex_dump(data: &[u8], address: u64) -> String {
+    let mut dump = String::new();
+
+    for (line, bytes) in data.chunks(16).enumerate() {
+        dump.push_str(&format!("  {:#010x} ", address + line as u64 * 16));
+
+        for word in 0..4 {
+            for column in word * 4..word * 4 + 4 {
+                match bytes.get(column) {
+                    Some(byte) => dump.push_str(&format!("{byte:02x}")),
+                    None => dump.push_str("  "),
+                }
+            }
+            dump.push(' ');
+        }
+
+        for &byte in bytes {
+            dump.push(if byte.is_ascii_graphic() || byte == b' ' {
+                byte as char
+            } else {
+                '.'
+            });
+        }
+        dump.push('\n');
+    }
+
+    dump
+}
@@ -0,0 +1,47 @@
+/// The `lint` subcommand: parses files leniently, and lists the
+/// problems found in their headers and symbol hash tables.
+///
+use std::io::{self, Write};
+use std::path::Path;
+
+use colored::Colorize;
+
+use elf::{Diagnostic, ElfError, ElfFile, Severity};
+
+/// Lists the problems in the file at `path`. Returns whether
+/// it is free of errors (warnings alone don't count).
+pub fn lint(path: &Path) -> Result<bool, ElfError> {
+    let mut elf_file = ElfFile::open_lenient(path)?;
+    let mut out = io::stdout().lock();
+
+    let mut diagnostics = elf_file.diagnostics.clone();
+    diagnostics.extend(elf_file.check_hash_tables());
+
+    if diagnostics.is_empty() {
+        writeln!(out, "{}: no problems found", path.display())?;
+    }
+
+    for diagnostic in &diagnostics {
+        writeln!(out, "{}", diagnostic_line(path, diagnostic))?;
+    }
+
+    Ok(diagnostics
+        .iter()
+        .all(|diagnostic| diagnostic.severity < Severity::Error))
+}
+
+/// Formats a diagnostic in the `path: severity: offset: message` form that
+/// compilers use, so that the output can be searched and filtered.
+pub fn diagnostic_line(path: &Path, diagnostic: &Diagnostic) -> String {
+    let severity = match diagnostic.severity {
+        Severity::Warning => "warning".yellow().bold(),
+        Severity::Error => "error".red().bold(),
+    };
+
+    format!(
+        "{}: {severity}: {:#x}: {}",
+        path.display(),
+        diagnostic.offset,
+        diagnostic.message
+    )
+}
@@ -0,0 +1,279 @@
+/// Command-line arguments, and the driver that prints
+/// the requested parts of each file.
+///
+mod addr2line;
+mod cfg;
+mod debug_info;
+mod disasm;
+mod dump;
+mod lint;
+mod print;
+mod readelf;
+mod symbolize;
+mod unwind;
+
+pub use addr2line::{addr2line, Addr2lineArgs};
+pub use cfg::{cfg, CfgArgs};
+pub use debug_info::{functions, layout, LayoutArgs};
+pub use disasm::{disasm, DisasmArgs};
+pub use dump::{dump, DumpArgs};
+pub use lint::lint;
+pub use symbolize::{symbolize, SymbolizeArgs};
+pub use unwind::{unwind, UnwindArgs};
+
+use std::borrow::Cow;
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+
+use clap::{Parser, Subcommand, ValueEnum};
+
+use elf::demangle::demangle_symbol;
+use elf::{ElfError, ElfFile};
+
+#[derive(Parser, Debug)]
+#[command(
+    name = "elf",
+    version,
+    about = "Display information about ELF files.",
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
+pub struct Args {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// ELF files to display.
+    #[arg(required = true)]
+    pub files: Vec<PathBuf>,
+
+    /// Display the main ELF header.
+    #[arg(long)]
+    pub header: bool,
+
+    /// Display the program headers and the sections in each segment.
+    #[arg(long)]
+    pub segments: bool,
+
+    /// Display the section headers.
+    #[arg(long)]
+    pub sections: bool,
+
+    /// Display the symbol tables.
+    #[arg(long)]
+    pub symbols: bool,
+
+    /// Display the dynamic section.
+    #[arg(long)]
+    pub dynamic: bool,
+
+    /// Display the notes.
+    #[arg(long)]
+    pub notes: bool,
+
+    /// Display the relocations.
+    #[arg(long)]
+    pub relocs: bool,
+
+    /// Display the symbol versions needed from other files, with the
+    /// newest from each, and the versions this file defines.
+    #[arg(long)]
+    pub versions: bool,
+
+    /// Display everything; this is the default when nothing else is selected.
+    #[arg(long)]
+    pub all: bool,
+
+    /// Format the output exactly as `readelf -h -l -S -s -d -r -n -V` would,
+    /// for the selected parts. The output is never colored.
+    #[arg(long)]
+    pub readelf_compat: bool,
+
+    /// Show C++ and Rust symbol names demangled, in the symbol tables and
+    /// the relocations, as `readelf -C` does.
+    #[arg(long)]
+    pub demangle: bool,
+
+    /// Parse damaged or malformed files as far as possible, and report
+    /// the problems found in their headers as warnings.
+    #[arg(long)]
+    pub lenient: bool,
+
+    /// When to color the output.
+    #[arg(long, value_enum, default_value_t = ColorChoice::Auto, global = true)]
+    pub color: ColorChoice,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Parse files leniently, and list the problems found in their headers.
+    ///
+    /// Exits with a failure status if any of them are errors.
+    Lint {
+        /// ELF files to check.
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+    },
+
+    /// Write out the contents of a section or segment.
+    Dump(DumpArgs),
+
+    /// Find the source file and line of addresses, from the DWARF line information.
+    Addr2line(Addr2lineArgs),
+
+    /// Name addresses after the symbols they're in, as `main+0x1c`.
+    Symbolize(SymbolizeArgs),
+
+    /// List the functions in the DWARF debugging information, with their addresses.
+    Functions {
+        /// ELF file with debugging information (a .debug_info section).
+        file: PathBuf,
+    },
+
+    /// Show the memory layout of structures, classes, and unions, as pahole does.
+    Layout(LayoutArgs),
+
+    /// Show the call frame information that unwinding the stack uses.
+    Unwind(UnwindArgs),
+
+    /// Disassemble the code in executable sections, as `objdump -d` does.
+    Disasm(DisasmArgs),
+
+    /// Disassemble the code by following it from the entry point and the
+    /// function symbols, and show each function's control flow graph.
+    Cfg(CfgArgs),
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum ColorChoice {
+    /// Color when writing to a terminal.
+    Auto,
+    Always,
+    Never,
+}
+
+impl Args {
+    fn nothing_selected(&self) -> bool {
+        !(self.header
+            || self.segments
+            || self.sections
+            || self.symbols
+            || self.dynamic
+            || self.notes
+            || self.relocs
+            || self.versions)
+    }
+
+    fn show_all(&self) -> bool {
+        self.all || self.nothing_selected()
+    }
+}
+
+/// Applies the color choice; `colored` itself also honors NO_COLOR and CLICOLOR.
+pub fn configure_color(choice: ColorChoice) {
+    match choice {
+        ColorChoice::Always => colored::control::set_override(true),
+        ColorChoice::Never => colored::control::set_override(false),
+
+        ColorChoice::Auto => {
+            if !io::stdout().is_terminal() {
+                colored::control::set_override(false);
+            }
+        }
+    }
+}
+
+/// A symbol's name as it's shown: demangled, if `demangle` is set and it's
+/// a C++ or Rust name.
+fn symbol_name(name: &str, demangle: bool) -> Cow<'_, str> {
+    match demangle {
+        true => demangle_symbol(name),
+        false => Cow::Borrowed(name),
+    }
+}
+
+/// Prints the parts of the file selected by `args`.
+pub fn run(path: &Path, args: &Args) -> Result<(), ElfError> {
+    let mut elf_file = if args.lenient {
+        ElfFile::open_lenient(path)?
+    } else {
+        ElfFile::open(path)?
+    };
+    let all = args.show_all();
+    let mut out = io::stdout().lock();
+
+    if args.files.len() > 1 {
+        writeln!(out, "\nFile: {}", path.display())?;
+    }
+
+    for diagnostic in &elf_file.diagnostics {
+        eprintln!("{}", lint::diagnostic_line(path, diagnostic));
+    }
+
+    if args.readelf_compat {
+        return run_readelf_compat(&mut out, &mut elf_file, args);
+    }
+
+    if all || args.header {
+        print::header(&mut out, &mut elf_file)?;
+    }
+    if all || args.segments {
+        print::segments(&mut out, &elf_file)?;
+    }
+    if all || args.sections {
+        print::sections(&mut out, &elf_file)?;
+    }
+    if all || args.dynamic {
+        print::dynamic(&mut out, &mut elf_file)?;
+    }
+    if all || args.relocs {
+        print::relocations(&mut out, &mut elf_file, args.demangle)?;
+    }
+    if all || args.symbols {
+        print::symbols(&mut out, &mut elf_file, args.demangle)?;
+    }
+    if all || args.versions {
+        print::versions(&mut out, &mut elf_file)?;
+    }
+    if all || args.notes {
+        print::notes(&mut out, &mut elf_file)?;
+    }
+
+    Ok(())
+}
+
+/// Prints the selected parts in readelf's format, and in readelf's order.
+fn run_readelf_compat(
+    out: &mut impl Write,
+    elf_file: &mut ElfFile,
+    args: &Args,
+) -> Result<(), ElfError> {
+    let all = args.show_all();
+    let header_shown = all || args.header;
+
+    if header_shown {
+        readelf::header(out, elf_file)?;
+    }
+    if all || args.sections {
+        readelf::sections(out, elf_file, header_shown)?;
+    }
+    if all || args.segments {
+        readelf::segments(out, elf_file, header_shown)?;
+    }
+    if all || args.dynamic {
+        readelf::dynamic(out, elf_file)?;
+    }
+    if all || args.relocs {
+        readelf::relocations(out, elf_file, args.demangle)?;
+    }
+    if all || args.symbols {
+        readelf::symbols(out, elf_file, args.demangle)?;
+    }
+    if all || args.versions {
+        readelf::versions(out, elf_file)?;
+    }
+    if all || args.notes {
+        readelf::notes(out, elf_file)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,435 @@
+/// Printing for each part of an ELF file that the command line can select.
+///
+use std::io::{self, Write};
+
+use elf::parse::dynamic::*;
+use elf::parse::header::*;
+use elf::parse::mapping::*;
+use elf::parse::notes::*;
+use elf::parse::relocs::*;
+use elf::parse::symbols::*;
+use elf::parse::types::*;
+use elf::parse::versions::*;
+use elf::{ElfError, ElfFile};
+
+use colored::Colorize;
+
+use super::symbol_name;
+
+fn banner(out: &mut impl Write, title: &str) -> io::Result<()> {
+    writeln!(out, "\n>> {} <<\n", title.red())
+}
+
+// ----------------
+// Main ELF header.
+
+pub fn header(out: &mut impl Write, elf_file: &mut ElfFile) -> Result<(), ElfError> {
+    banner(out, "ELF main header.")?;
+    let is_pie = elf_file.is_pie()?;
+    write!(out, "{}", header_summary(&elf_file.header, is_pie))?;
+
+    Ok(())
+}
+
+// ---------------------
+// Program headers.
+
+pub fn segments(out: &mut impl Write, elf_file: &ElfFile) -> Result<(), ElfError> {
+    banner(out, "Program headers.")?;
+
+    writeln!(
+        out,
+        "{}",
+        format!(
+            "  {:<20} {:<10} {:<18} {:<18} {:<10} {:<10} {:<3} {}",
+            "Type", "Offset", "VirtAddr", "PhysAddr", "FileSiz", "MemSiz", "Flg", "Align"
+        )
+        .bold()
+    )?;
+
+    for segment in &elf_file.program_headers {
+        let data = &segment.header_data;
+
+        writeln!(
+            out,
+            "  {:<20} {:#010x} {:#018x} {:#018x} {:#010x} {:#010x} {:<3} {:#x}",
+            segment.type_string.blue(),
+            data.offset,
+            data.virtual_address,
+            data.physical_address,
+            data.file_size,
+            data.mem_size,
+            data.flags_string(),
+            data.align
+        )?;
+    }
+
+    banner(out, "Section to segment mapping.")?;
+
+    let mapping = section_segment_mapping(&elf_file.program_headers, &elf_file.section_headers);
+
+    for (i, section_indices) in mapping.iter().enumerate() {
+        let names: Vec<&str> = section_indices
+            .iter()
+            .map(|&j| elf_file.section_headers[j].name.as_str())
+            .collect();
+
+        writeln!(
+            out,
+            "  {} {i:02} ({}): {}",
+            "Segment".cyan().bold(),
+            elf_file.program_headers[i].type_string,
+            names.join(" ")
+        )?;
+    }
+
+    Ok(())
+}
+
+// ---------------------
+// Section headers.
+
+pub fn sections(out: &mut impl Write, elf_file: &ElfFile) -> Result<(), ElfError> {
+    banner(out, "Section headers.")?;
+
+    writeln!(
+        out,
+        "{}",
+        format!(
+            "  [{:>2}] {:<20} {:<20} {:<18} {:<10} {:<10} {:<10} {:<5} {:>4} {:>4} {:>5}",
+            "Nr",
+            "Name",
+            "Type",
+            "Address",
+            "Offset",
+            "Size",
+            "EntSize",
+            "Flags",
+            "Link",
+            "Info",
+            "Align"
+        )
+        .bold()
+    )?;
+
+    for (i, section) in elf_file.section_headers.iter().enumerate() {
+        let data = &section.header_data;
+
+        writeln!(
+            out,
+            "  [{i:>2}] {:<20} {:<20} {:#018x} {:#010x} {:#010x} {:#010x} {:<5} {:>4} {:>4} {:>5}",
+            section.name.yellow(),
+            section.type_string,
+            data.addr,
+            data.offset,
+            data.size,
+            data.entry_size,
+            data.flags_string(),
+            data.link,
+            data.info,
+            data.addr_align
+        )?;
+    }
+
+    Ok(())
+}
+
+// ----------------
+// Dynamic section.
+
+pub fn dynamic(out: &mut impl Write, elf_file: &mut ElfFile) -> Result<(), ElfError> {
+    banner(out, "Dynamic section.")?;
+
+    let Some(dynamic) = elf_file.dynamic()? else {
+        writeln!(out, "There is no dynamic section in this file.")?;
+        return Ok(());
+    };
+
+    let machine = elf_file.header.machine_type();
+
+    writeln!(
+        out,
+        "{}",
+        format!("  {:<18} {:<24} {}", "Tag", "Type", "Name/Value").bold()
+    )?;
+
+    for entry in &dynamic.entries {
+        writeln!(
+            out,
+            "  {:#018x} {:<24} {}",
+            entry.tag,
+            dynamic_tag_string(entry.tag, machine).magenta(),
+            dynamic_value_string(entry, &dynamic.strings())
+        )?;
+    }
+
+    Ok(())
+}
+
+// ------------
+// Relocations.
+
+pub fn relocations(
+    out: &mut impl Write,
+    elf_file: &mut ElfFile,
+    demangle: bool,
+) -> Result<(), ElfError> {
+    banner(out, "Relocations.")?;
+
+    let machine = elf_file.header.machine_type();
+    let mut indices = elf_file.section_indices_by_type(SHT_RELA);
+    indices.extend(elf_file.section_indices_by_type(SHT_REL));
+    indices.extend(elf_file.section_indices_by_type(SHT_RELR));
+    indices.sort();
+
+    if indices.is_empty() {
+        writeln!(out, "There are no relocations in this file.")?;
+    }
+
+    for index in indices {
+        // Relative relocations packed into SHT_RELR are only addresses.
+        if elf_file.section_headers[index].header_data.type_id() == SHT_RELR {
+            let offsets = elf_file.relr_offsets(index)?;
+
+            let section = &elf_file.section_headers[index];
+            writeln!(
+                out,
+                "\n{} '{}' at offset {:#x} contains {} relative offsets:",
+                "Relocation section".green().bold(),
+                section.name,
+                section.header_data.offset,
+                offsets.len()
+            )?;
+            for offset in offsets {
+                writeln!(out, "  {offset:#018x}")?;
+            }
+            continue;
+        }
+
+        let relocations = elf_file.relocations(index)?;
+
+        // Symbol names come from the symbol table the section links to.
+        let link = elf_file.section_headers[index].header_data.link as usize;
+        let symbols = if link != 0 {
+            elf_file.symbols(link)?
+        } else {
+            vec![]
+        };
+
+        let section = &elf_file.section_headers[index];
+        writeln!(
+            out,
+            "\n{} '{}' at offset {:#x} contains {} entries:",
+            "Relocation section".green().bold(),
+            section.name,
+            section.header_data.offset,
+            relocations.len()
+        )?;
+        writeln!(
+            out,
+            "{}",
+            format!(
+                "  {:<18} {:<18} {:<26} {:<18} {}",
+                "Offset", "Info", "Type", "Sym. Value", "Sym. Name + Addend"
+            )
+            .bold()
+        )?;
+
+        for relocation in &relocations {
+            let symbol = symbols.get(relocation.symbol_index() as usize);
+            let (value, name) = match symbol {
+                Some(symbol) if relocation.symbol_index() != 0 => (
+                    format!("{:#018x}", symbol.symbol_data.value),
+                    symbol_name(&symbol.name, demangle).into_owned(),
+                ),
+                _ => (String::new(), String::new()),
+            };
+
+            let addend = match relocation.addend {
+                Some(addend) if addend < 0 => format!(" - {:#x}", addend.unsigned_abs()),
+                Some(addend) => format!(" + {addend:#x}"),
+                None => String::new(),
+            };
+
+            writeln!(
+                out,
+                "  {:#018x} {:#018x} {:<26} {:<18} {name}{addend}",
+                relocation.offset,
+                relocation.info,
+                relocation_type_string(relocation.reloc_type(), machine),
+                value
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+// --------------
+// Symbol tables.
+
+pub fn symbols(
+    out: &mut impl Write,
+    elf_file: &mut ElfFile,
+    demangle: bool,
+) -> Result<(), ElfError> {
+    banner(out, "Symbol tables.")?;
+
+    let mut indices = elf_file.section_indices_by_type(SHT_DYNSYM);
+    indices.extend(elf_file.section_indices_by_type(SHT_SYMTAB));
+
+    if indices.is_empty() {
+        writeln!(out, "There are no symbol tables in this file.")?;
+    }
+
+    let versions = elf_file.symbol_versions()?;
+
+    for index in indices {
+        let symbols = elf_file.symbols(index)?;
+        let is_dynsym = elf_file.section_headers[index].header_data.type_id() == SHT_DYNSYM;
+
+        writeln!(
+            out,
+            "\n{} '{}' contains {} entries:",
+            "Symbol table".green().bold(),
+            elf_file.section_headers[index].name,
+            symbols.len()
+        )?;
+        writeln!(
+            out,
+            "{}",
+            format!(
+                "  {:>6} {:<18} {:>6} {:<13} {:<14} {:<13} {:>6} {}",
+                "Num", "Value", "Size", "Type", "Bind", "Vis", "Ndx", "Name"
+            )
+            .bold()
+        )?;
+
+        for (i, symbol) in symbols.iter().enumerate() {
+            let data = &symbol.symbol_data;
+
+            // Only dynamic symbols have versions, like memcpy@GLIBC_2.14.
+            let version = versions
+                .as_ref()
+                .filter(|_| is_dynsym)
+                .and_then(|versions| versions.symbol_version(i, data))
+                .map(|version| version.suffix().dimmed().to_string())
+                .unwrap_or_default();
+
+            writeln!(
+                out,
+                "  {i:>6} {:#018x} {:>6} {:<13} {:<14} {:<13} {:>6} {}{version}",
+                data.value,
+                data.size,
+                symbol_type_string(data.symbol_type()),
+                symbol_bind_string(data.bind()),
+                symbol_visibility_string(data.visibility()),
+                symbol_section_string(data.section_index),
+                symbol_name(&symbol.name, demangle)
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+// ----------------
+// Symbol versions.
+
+pub fn versions(out: &mut impl Write, elf_file: &mut ElfFile) -> Result<(), ElfError> {
+    banner(out, "Symbol versions.")?;
+
+    let Some(versions) = elf_file.symbol_versions()? else {
+        writeln!(out, "There is no version information in this file.")?;
+        return Ok(());
+    };
+
+    // The dynamic symbols that use each version, to show why it's needed.
+    let symbols = match elf_file.section_indices_by_type(SHT_DYNSYM).first() {
+        Some(&index) => elf_file.symbols(index)?,
+        None => vec![],
+    };
+    let users = |index: u16| -> Vec<&str> {
+        symbols
+            .iter()
+            .enumerate()
+            .filter(|(i, symbol)| {
+                versions
+                    .symbol_version(*i, &symbol.symbol_data)
+                    .is_some_and(|version| version.needed && version.index == index)
+            })
+            .map(|(_, symbol)| symbol.name.as_str())
+            .collect()
+    };
+
+    for need in &versions.needs {
+        writeln!(
+            out,
+            "{} {}",
+            "Versions needed from".green().bold(),
+            need.file
+        )?;
+
+        let names: Vec<&str> = need.versions.iter().map(|v| v.name.as_str()).collect();
+        writeln!(out, "  {}", names.join(" "))?;
+
+        for newest in need.newest_versions() {
+            let mut line = format!("  {} {}", "Newest:".bold(), newest.name);
+
+            let users = users(newest.index & VERSYM_VERSION);
+            if !users.is_empty() {
+                line.push_str(&format!(" (for {})", users.join(", ")));
+            }
+            writeln!(out, "{line}")?;
+        }
+    }
+
+    if !versions.defs.is_empty() {
+        writeln!(out, "{}", "Versions defined".green().bold())?;
+    }
+
+    for def in &versions.defs {
+        let name = def.names.first().map_or("", |name| name.as_str());
+        let parents = def.names.get(1..).unwrap_or_default();
+        let mut line = format!("  {:>4} {name}", def.index);
+
+        if !parents.is_empty() {
+            line.push_str(&format!(" (inherits {})", parents.join(", ")));
+        }
+        if def.flags & VER_FLG_BASE != 0 {
+            line.push_str(" (base)");
+        }
+        if def.flags & VER_FLG_WEAK != 0 {
+            line.push_str(" (weak)");
+        }
+        writeln!(out, "{line}")?;
+    }
+
+    Ok(())
+}
+
+// ------
+// Notes.
+
+pub fn notes(out: &mut impl Write, elf_file: &mut ElfFile) -> Result<(), ElfError> {
+    banner(out, "Notes.")?;
+
+    let machine = elf_file.header.machine_id();
+
+    for index in elf_file.section_indices_by_type(SHT_NOTE) {
+        let name = elf_file.section_headers[index].name.clone();
+        let align = elf_file.section_headers[index].header_data.addr_align;
+
+        writeln!(out, "{} {}", "Notes in section".magenta().bold(), name)?;
+
+        for note in read_notes(elf_file.section_bytes(index)?, align) {
+            let note = note?;
+            writeln!(out, "  Owner: {}, type: {}", note.name, note.type_string())?;
+            writeln!(out, "  {}", note.descriptor(machine))?;
+        }
+
+        writeln!(out)?;
+    }
+
+    Ok(())
+}
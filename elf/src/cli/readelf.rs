@@ -0,0 +1,1488 @@
+/// Output in the format of GNU readelf, for scripts written against
+/// `readelf -h -l -S -s -d -r -n -V`. Column widths, truncation, and the
+/// names of types and flags follow binutils 2.40, so that the output
+/// can be compared textually with readelf's.
+///
+use std::io::{self, Write};
+
+use elf::parse::dynamic::*;
+use elf::parse::header::*;
+use elf::parse::mapping::*;
+use elf::parse::notes::*;
+use elf::parse::relocs::*;
+use elf::parse::strings::StringTable;
+use elf::parse::symbols::*;
+use elf::parse::types::*;
+use elf::parse::versions::*;
+use elf::parse::*;
+use elf::{ElfError, ElfFile};
+
+// OS/ABI values that change how readelf names flags and symbols.
+const ELFOSABI_NONE: u8 = 0;
+const ELFOSABI_GNU: u8 = 3;
+const ELFOSABI_FREEBSD: u8 = 9;
+
+// ----------
+// Utilities.
+
+/// Formats a string the way readelf's `print_symbol` does: a string longer than
+/// `width` is cut short and ends in "[...]", and a negative `width` means the
+/// string is also padded to that many columns. Control characters print as ^X.
+fn print_symbol(width: i32, symbol: &str) -> String {
+    print_symbol_as(width, symbol, symbol)
+}
+
+/// Formats a symbol's name as `print_symbol` does, demangled if `demangle`
+/// is set. As in `readelf -C`, whether it's cut short still depends on the
+/// mangled name's length, and a name with a version in it (as in
+/// `_Znwm@GLIBCXX_3.4`) isn't demangled.
+fn print_symbol_name(width: i32, symbol: &str, demangle: bool) -> String {
+    match demangle.then(|| elf::demangle::demangle(symbol)).flatten() {
+        Some(demangled) => print_symbol_as(width, symbol, &demangled),
+        None => print_symbol(width, symbol),
+    }
+}
+
+/// Formats `shown` in place of `symbol`, cut short if `symbol` is too long.
+/// A width of zero leaves no room for any of it.
+fn print_symbol_as(width: i32, symbol: &str, shown: &str) -> String {
+    if width == 0 {
+        return String::new();
+    }
+
+    let pad = width < 0;
+    let mut width = width.unsigned_abs() as usize;
+
+    let truncate = symbol.chars().count() > width;
+    if truncate {
+        width = width.saturating_sub(5);
+    }
+
+    let mut string = String::new();
+    let mut printed = 0;
+
+    for c in shown.chars() {
+        if c.is_ascii_control() {
+            if printed + 2 > width {
+                break;
+            }
+            string.push('^');
+            string.push(((c as u8) ^ 0x40) as char);
+            printed += 2;
+        } else {
+            if printed + 1 > width {
+                break;
+            }
+            string.push(c);
+            printed += 1;
+        }
+    }
+
+    if truncate {
+        string.push_str("[...]");
+        printed += 5;
+    }
+    if pad && printed < width {
+        string.push_str(&" ".repeat(width - printed));
+    }
+
+    string
+}
+
+/// Hex with C's `%#x` conventions, where zero has no prefix.
+fn alternate_hex(value: u64) -> String {
+    if value == 0 {
+        "0".to_owned()
+    } else {
+        format!("{value:#x}")
+    }
+}
+
+fn plural<'a>(count: usize, one: &'a str, many: &'a str) -> &'a str {
+    if count == 1 {
+        one
+    } else {
+        many
+    }
+}
+
+/// Bytes as "xx xx ", as readelf prints undecoded note data.
+fn spaced_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x} ")).collect()
+}
+
+// ----------------
+// Main ELF header.
+
+pub fn header(out: &mut impl Write, elf_file: &mut ElfFile) -> Result<(), ElfError> {
+    let is_pie = elf_file.is_pie()?;
+    write!(out, "{}", header_summary(&elf_file.header, is_pie))?;
+
+    Ok(())
+}
+
+// ---------------------
+// Section headers.
+
+/// Section type names, which drop the "SHT_" prefix.
+fn section_type_name(section_type: u32, machine: u16) -> String {
+    let name = match section_type {
+        SHT_NULL => "NULL",
+        SHT_PROGBITS => "PROGBITS",
+        SHT_SYMTAB => "SYMTAB",
+        SHT_STRTAB => "STRTAB",
+        SHT_RELA => "RELA",
+        SHT_HASH => "HASH",
+        SHT_DYNAMIC => "DYNAMIC",
+        SHT_NOTE => "NOTE",
+        SHT_NOBITS => "NOBITS",
+        SHT_REL => "REL",
+        SHT_SHLIB => "SHLIB",
+        SHT_DYNSYM => "DYNSYM",
+        SHT_INIT_ARRAY => "INIT_ARRAY",
+        SHT_FINI_ARRAY => "FINI_ARRAY",
+        SHT_PREINIT_ARRAY => "PREINIT_ARRAY",
+        SHT_GROUP => "GROUP",
+        SHT_SYMTAB_SHNDX => "SYMTAB SECTION INDICES",
+        SHT_RELR => "RELR",
+
+        SHT_GNU_INCREMENTAL_INPUTS => "GNU_INCREMENTAL_INPUTS",
+        SHT_GNU_ATTRIBUTES => "GNU_ATTRIBUTES",
+        SHT_GNU_HASH => "GNU_HASH",
+        SHT_GNU_LIBLIST => "GNU_LIBLIST",
+        SHT_SUNW_SYMINFO | SHT_GNU_VERDEF => "VERDEF",
+        SHT_GNU_VERNEED => "VERNEED",
+        SHT_GNU_VERSYM | 0x6ffffff0 => "VERSYM",
+        0x7ffffffd => "AUXILIARY",
+        0x7fffffff => "FILTER",
+
+        SHT_LOPROC..=SHT_HIPROC => {
+            // readelf knows only the attributes section of AArch64's types.
+            let known = match machine {
+                EM_AARCH64 => section_type == SHT_AARCH64_ATTRIBUTES,
+                EM_ARM | EM_MIPS | EM_RISCV | EM_X86_64 => true,
+                _ => false,
+            };
+
+            let string = section_header_type_string(&section_type.to_le_bytes(), machine);
+            return match string.strip_prefix("SHT_") {
+                Some(name) if known => name.to_owned(),
+                _ => format!(
+                    "LOPROC+{}",
+                    alternate_hex((section_type - SHT_LOPROC) as u64)
+                ),
+            };
+        }
+
+        SHT_LOOS..=SHT_HIOS => {
+            return format!("LOOS+{}", alternate_hex((section_type - SHT_LOOS) as u64));
+        }
+        SHT_LOUSER..=SHT_HIUSER => {
+            return format!(
+                "LOUSER+{}",
+                alternate_hex((section_type - SHT_LOUSER) as u64)
+            );
+        }
+
+        _ => return format!("{section_type:08x}: <unknown>"),
+    };
+
+    name.to_owned()
+}
+
+/// Flag letters, from the lowest bit up. OS- and processor-specific bits
+/// without a letter of their own are shown once, as 'o' or 'p'.
+fn section_flags(flags: u64, os_abi: u8, machine: u16) -> String {
+    const SHF_MASKOS: u64 = 0x0ff00000;
+    const SHF_MASKPROC: u64 = 0xf0000000;
+    const SHF_GNU_RETAIN: u64 = 0x200000;
+    const SHF_GNU_MBIND: u64 = 0x01000000;
+    const SHF_X86_64_LARGE: u64 = 0x10000000;
+    const SHF_ARM_PURECODE: u64 = 0x20000000;
+
+    let gnu = os_abi == ELFOSABI_GNU || os_abi == ELFOSABI_FREEBSD;
+
+    let mut string = String::new();
+    let mut rest = flags;
+
+    while rest != 0 {
+        let flag = rest & rest.wrapping_neg();
+        rest &= !flag;
+
+        let letter = match flag {
+            SHF_WRITE => 'W',
+            SHF_ALLOC => 'A',
+            SHF_EXECINSTR => 'X',
+            SHF_MERGE => 'M',
+            SHF_STRINGS => 'S',
+            SHF_INFO_LINK => 'I',
+            SHF_LINK_ORDER => 'L',
+            SHF_OS_NONCONFORMING => 'O',
+            SHF_GROUP => 'G',
+            SHF_TLS => 'T',
+            SHF_EXCLUDE => 'E',
+            SHF_COMPRESSED => 'C',
+
+            SHF_X86_64_LARGE if machine == EM_X86_64 => 'l',
+            SHF_ARM_PURECODE if machine == EM_ARM => 'y',
+
+            SHF_GNU_RETAIN if gnu => 'R',
+            SHF_GNU_MBIND if gnu || os_abi == ELFOSABI_NONE => 'D',
+            _ if flag & SHF_MASKOS != 0 => {
+                rest &= !SHF_MASKOS;
+                'o'
+            }
+            _ if flag & SHF_MASKPROC != 0 => {
+                rest &= !SHF_MASKPROC;
+                'p'
+            }
+
+            _ => 'x',
+        };
+
+        string.push(letter);
+    }
+
+    string
+}
+
+/// Prints the section headers. Without the main header, readelf
+/// first says how many there are and where they start.
+pub fn sections(
+    out: &mut impl Write,
+    elf_file: &ElfFile,
+    header_shown: bool,
+) -> Result<(), ElfError> {
+    let header = &elf_file.header;
+    let count = elf_file.section_headers.len();
+
+    if count == 0 {
+        writeln!(out, "\nThere are no sections in this file.")?;
+        return Ok(());
+    }
+
+    if !header_shown {
+        writeln!(
+            out,
+            "There {} {count} section {}, starting at offset {}:",
+            plural(count, "is", "are"),
+            plural(count, "header", "headers"),
+            alternate_hex(header.section_header_offset)
+        )?;
+    }
+
+    writeln!(
+        out,
+        "\n{}:",
+        plural(count, "Section Header", "Section Headers")
+    )?;
+    writeln!(
+        out,
+        "  [Nr] Name              Type             Address           Offset"
+    )?;
+    writeln!(
+        out,
+        "       Size              EntSize          Flags  Link  Info  Align"
+    )?;
+
+    let machine = header.machine_id();
+
+    for (i, section) in elf_file.section_headers.iter().enumerate() {
+        let data = &section.header_data;
+        let type_name = section_type_name(data.type_id(), machine);
+
+        writeln!(
+            out,
+            "  [{i:>2}] {} {:<15.15}  {:016x}  {:08x}",
+            print_symbol(-17, &section.name),
+            type_name,
+            data.addr,
+            data.offset
+        )?;
+        writeln!(
+            out,
+            "       {:016x}  {:016x} {:>3}      {:>2}   {:>3}     {}",
+            data.size,
+            data.entry_size,
+            section_flags(data.flags, header.abi_os, machine),
+            data.link,
+            data.info,
+            data.addr_align
+        )?;
+    }
+
+    let os_abi = header.abi_os;
+
+    write!(
+        out,
+        "Key to Flags:\n  \
+         W (write), A (alloc), X (execute), M (merge), S (strings), I (info),\n  \
+         L (link order), O (extra OS processing required), G (group), T (TLS),\n  \
+         C (compressed), x (unknown), o (OS specific), E (exclude),\n  "
+    )?;
+    if os_abi == ELFOSABI_GNU || os_abi == ELFOSABI_FREEBSD {
+        write!(out, "R (retain), ")?;
+    }
+    if os_abi == ELFOSABI_NONE || os_abi == ELFOSABI_GNU || os_abi == ELFOSABI_FREEBSD {
+        write!(out, "D (mbind), ")?;
+    }
+    match machine {
+        EM_X86_64 => write!(out, "l (large), ")?,
+        EM_ARM => write!(out, "y (purecode), ")?,
+        _ => {}
+    }
+    writeln!(out, "p (processor specific)")?;
+
+    Ok(())
+}
+
+// ---------------------
+// Program headers.
+
+/// Segment type names, which drop the "PT_" prefix.
+fn segment_type_name(segment_type: u32, machine: u16) -> String {
+    let name = match segment_type {
+        PT_NULL => "NULL",
+        PT_LOAD => "LOAD",
+        PT_DYNAMIC => "DYNAMIC",
+        PT_INTERP => "INTERP",
+        PT_NOTE => "NOTE",
+        PT_SHLIB => "SHLIB",
+        PT_PHDR => "PHDR",
+        PT_TLS => "TLS",
+
+        PT_GNU_EH_FRAME => "GNU_EH_FRAME",
+        PT_GNU_STACK => "GNU_STACK",
+        PT_GNU_RELRO => "GNU_RELRO",
+        PT_GNU_PROPERTY => "GNU_PROPERTY",
+        PT_GNU_SFRAME => "GNU_SFRAME",
+        PT_OPENBSD_RANDOMIZE => "OPENBSD_RANDOMIZE",
+        PT_OPENBSD_WXNEEDED => "OPENBSD_WXNEEDED",
+        PT_OPENBSD_BOOTDATA => "OPENBSD_BOOTDATA",
+
+        PT_LOPROC..=PT_HIPROC => {
+            let name = match (machine, segment_type) {
+                (EM_ARM, PT_ARM_EXIDX) => Some("EXIDX"),
+
+                (EM_AARCH64, PT_AARCH64_ARCHEXT) => Some("AARCH64_ARCHEXT"),
+                (EM_AARCH64, PT_AARCH64_MEMTAG_MTE) => Some("AARCH64_MEMTAG_MTE"),
+
+                (EM_MIPS, PT_MIPS_REGINFO) => Some("REGINFO"),
+                (EM_MIPS, PT_MIPS_RTPROC) => Some("RTPROC"),
+                (EM_MIPS, PT_MIPS_OPTIONS) => Some("OPTIONS"),
+                (EM_MIPS, PT_MIPS_ABIFLAGS) => Some("ABIFLAGS"),
+
+                (EM_RISCV, PT_RISCV_ATTRIBUTES) => Some("RISCV_ATTRIBUTES"),
+
+                _ => None,
+            };
+
+            return match name {
+                Some(name) => name.to_owned(),
+                None => format!(
+                    "LOPROC+{}",
+                    alternate_hex((segment_type - PT_LOPROC) as u64)
+                ),
+            };
+        }
+
+        PT_LOOS..=PT_HIOS => {
+            return format!("LOOS+{}", alternate_hex((segment_type - PT_LOOS) as u64));
+        }
+
+        _ => return format!("<unknown>: {segment_type:x}"),
+    };
+
+    name.to_owned()
+}
+
+/// The path named by the PT_INTERP segment.
+fn interpreter(elf_file: &mut ElfFile) -> Result<Option<String>, ElfError> {
+    let Some(segment) = elf_file
+        .program_headers
+        .iter()
+        .find(|segment| segment.header_data.type_id() == PT_INTERP)
+    else {
+        return Ok(None);
+    };
+
+    let (offset, size) = (segment.header_data.offset, segment.header_data.file_size);
+    let bytes = elf_file.bytes(offset, size)?;
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+
+    Ok(Some(String::from_utf8_lossy(&bytes[..end]).to_string()))
+}
+
+/// Prints the program headers and the section to segment mapping. Without
+/// the main header, readelf first repeats the parts of it that matter here.
+pub fn segments(
+    out: &mut impl Write,
+    elf_file: &mut ElfFile,
+    header_shown: bool,
+) -> Result<(), ElfError> {
+    let count = elf_file.program_headers.len();
+
+    if count == 0 {
+        writeln!(out, "\nThere are no program headers in this file.")?;
+        return Ok(());
+    }
+
+    if !header_shown {
+        let is_pie = elf_file.is_pie()?;
+        let header = &elf_file.header;
+
+        writeln!(
+            out,
+            "\nElf file type is {}",
+            object_type_string(header, is_pie)
+        )?;
+        writeln!(out, "Entry point {:#x}", header.entry_point)?;
+        writeln!(
+            out,
+            "There {} {count} program {}, starting at offset {}",
+            plural(count, "is", "are"),
+            plural(count, "header", "headers"),
+            header.program_header_offset
+        )?;
+    }
+
+    writeln!(
+        out,
+        "\n{}:",
+        plural(count, "Program Header", "Program Headers")
+    )?;
+    writeln!(
+        out,
+        "  Type           Offset             VirtAddr           PhysAddr"
+    )?;
+    writeln!(
+        out,
+        "                 FileSiz            MemSiz              Flags  Align"
+    )?;
+
+    let interpreter = interpreter(elf_file)?;
+    let machine = elf_file.header.machine_id();
+
+    for segment in &elf_file.program_headers {
+        let data = &segment.header_data;
+
+        writeln!(
+            out,
+            "  {:<14.14} {:#018x} {:#018x} {:#018x}",
+            segment_type_name(data.type_id(), machine),
+            data.offset,
+            data.virtual_address,
+            data.physical_address
+        )?;
+        writeln!(
+            out,
+            "                 {:#018x} {:#018x}  {}    {:#x}",
+            data.file_size,
+            data.mem_size,
+            data.flags_string(),
+            data.align
+        )?;
+
+        if data.type_id() == PT_INTERP {
+            if let Some(path) = &interpreter {
+                writeln!(out, "      [Requesting program interpreter: {path}]")?;
+            }
+        }
+    }
+
+    if elf_file.section_headers.is_empty() {
+        return Ok(());
+    }
+
+    writeln!(out, "\n Section to Segment mapping:")?;
+    writeln!(out, "  Segment Sections...")?;
+
+    let mapping = section_segment_mapping(&elf_file.program_headers, &elf_file.section_headers);
+
+    for (i, section_indices) in mapping.iter().enumerate() {
+        let names: String = section_indices
+            .iter()
+            .map(|&j| format!("{} ", elf_file.section_headers[j].name))
+            .collect();
+
+        writeln!(out, "   {i:02}     {names}")?;
+    }
+
+    Ok(())
+}
+
+// ----------------
+// Dynamic section.
+
+/// Dynamic tag names, which drop the "DT_" prefix.
+fn dynamic_tag_name(tag: i64, machine: Machine) -> String {
+    const DT_ADDRRNGLO: i64 = 0x6ffffe00;
+    const DT_USED: i64 = 0x7ffffffe;
+
+    match tag {
+        DT_ADDRRNGLO => return "ADDRRNGLO".to_owned(),
+        DT_USED => return "USED".to_owned(),
+        _ => {}
+    }
+
+    let string = dynamic_tag_string(tag, machine);
+    if let Some(name) = string.strip_prefix("DT_") {
+        return name.to_owned();
+    }
+
+    match tag {
+        0x70000000..=0x7fffffff => format!("Processor Specific: {tag:x}"),
+        0x6000000d..=0x6ffff000 => format!("Operating System specific: {tag:x}"),
+        _ => format!("<unknown>: {tag:x}"),
+    }
+}
+
+/// Flag names, each preceded by a space, with any bits left over in hex.
+fn flag_list(value: u64, names: &[(u64, &str)]) -> String {
+    if value == 0 {
+        return " None".to_owned();
+    }
+
+    let mut string = String::new();
+    let mut rest = value;
+
+    for (bit, name) in names {
+        if rest & bit != 0 {
+            string.push(' ');
+            string.push_str(name);
+            rest &= !bit;
+        }
+    }
+    if rest != 0 {
+        string.push_str(&format!(" {rest:x}"));
+    }
+
+    string
+}
+
+/// A UTC timestamp in the form readelf uses for DT_GNU_PRELINKED.
+fn utc_timestamp(seconds: u64) -> String {
+    let days = (seconds / 86400) as i64;
+    let time = seconds % 86400;
+
+    // Converts days since 1970-01-01 to a civil date, by Howard Hinnant's algorithm.
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let day_of_era = z.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}",
+        time / 3600,
+        time / 60 % 60,
+        time % 60
+    )
+}
+
+fn dynamic_value(
+    entry: &Elf64DynamicEntry,
+    string_table: &StringTable,
+    machine: Machine,
+    interpreter: Option<&str>,
+) -> String {
+    let value = entry.value;
+    let name = string_table.get_lossy(value as usize).ok();
+
+    let library = |label: &str| match &name {
+        Some(name) => format!("{label}: [{name}]"),
+        None => format!("{label}: {value:#x}"),
+    };
+
+    match entry.tag {
+        DT_NEEDED => match &name {
+            Some(name) if Some(name.as_ref()) == interpreter => {
+                format!("Shared library: [{name}] program interpreter")
+            }
+            Some(name) => format!("Shared library: [{name}]"),
+            None => format!("{value:#x}"),
+        },
+        DT_SONAME | DT_RPATH | DT_RUNPATH if name.is_none() => format!("{value:#x}"),
+        DT_SONAME => library("Library soname"),
+        DT_RPATH => library("Library rpath"),
+        DT_RUNPATH => library("Library runpath"),
+
+        DT_AUXILIARY => library("Auxiliary library"),
+        DT_FILTER => library("Filter library"),
+        DT_CONFIG => library("Configuration file"),
+        DT_DEPAUDIT => library("Dependency audit library"),
+        DT_AUDIT => library("Audit library"),
+        0x7ffffffe => match &name {
+            Some(name) => format!("Not needed object: [{name}]"),
+            None => format!("{value:#x}"),
+        },
+
+        DT_FLAGS => {
+            let names = [
+                (DF_ORIGIN, "ORIGIN"),
+                (DF_SYMBOLIC, "SYMBOLIC"),
+                (DF_TEXTREL, "TEXTREL"),
+                (DF_BIND_NOW, "BIND_NOW"),
+                (DF_STATIC_TLS, "STATIC_TLS"),
+            ];
+
+            let mut words = vec![];
+            let mut rest = value;
+            while rest != 0 {
+                let flag = rest & rest.wrapping_neg();
+                rest &= !flag;
+
+                let word = names.iter().find(|(bit, _)| *bit == flag);
+                words.push(word.map_or("unknown", |(_, name)| *name));
+            }
+            words.join(" ")
+        }
+
+        DT_FLAGS_1 => {
+            let names: Vec<(u64, &str)> = DF_1_NAMES
+                .iter()
+                .enumerate()
+                .map(|(bit, name)| (1 << bit, *name))
+                .collect();
+            format!("Flags:{}", flag_list(value, &names))
+        }
+        DT_FEATURE => format!(
+            "Flags:{}",
+            flag_list(value, &[(1, "PARINIT"), (2, "CONFEXP")])
+        ),
+        DT_POSFLAG_1 => format!(
+            "Flags:{}",
+            flag_list(value, &[(1, "LAZYLOAD"), (2, "GROUPPERM")])
+        ),
+
+        DT_PLTREL => dynamic_tag_name(value as i64, machine),
+
+        DT_PLTRELSZ | DT_RELASZ | DT_STRSZ | DT_RELSZ | DT_RELAENT | DT_SYMENT | DT_RELENT
+        | DT_INIT_ARRAYSZ | DT_FINI_ARRAYSZ | DT_PREINIT_ARRAYSZ | DT_RELRSZ | DT_RELRENT
+        | DT_MOVEENT | DT_MOVESZ | DT_GNU_CONFLICTSZ | DT_GNU_LIBLISTSZ | DT_PLTPADSZ => {
+            format!("{value} (bytes)")
+        }
+
+        DT_VERDEFNUM | DT_VERNEEDNUM | DT_RELACOUNT | DT_RELCOUNT => value.to_string(),
+
+        DT_GNU_PRELINKED => utc_timestamp(value),
+
+        DT_BIND_NOW => String::new(),
+        DT_AARCH64_BTI_PLT | DT_AARCH64_PAC_PLT if machine == Machine::AArch64 => String::new(),
+
+        _ => format!("{value:#x}"),
+    }
+}
+
+pub fn dynamic(out: &mut impl Write, elf_file: &mut ElfFile) -> Result<(), ElfError> {
+    let Some(dynamic) = elf_file.dynamic()? else {
+        writeln!(out, "\nThere is no dynamic section in this file.")?;
+        return Ok(());
+    };
+
+    // readelf gives the offset of the PT_DYNAMIC segment, if there is one.
+    let offset = match elf_file
+        .program_headers
+        .iter()
+        .find(|segment| segment.header_data.type_id() == PT_DYNAMIC)
+    {
+        Some(segment) => segment.header_data.offset,
+        None => {
+            let index = elf_file.section_indices_by_type(SHT_DYNAMIC)[0];
+            elf_file.section_headers[index].header_data.offset
+        }
+    };
+
+    let count = dynamic.entries.len();
+    writeln!(
+        out,
+        "\nDynamic section at offset {} contains {count} {}:",
+        alternate_hex(offset),
+        plural(count, "entry", "entries")
+    )?;
+    writeln!(out, "  Tag        Type                         Name/Value")?;
+
+    let interpreter = interpreter(elf_file)?;
+    let machine = elf_file.header.machine_type();
+
+    for entry in &dynamic.entries {
+        let name = dynamic_tag_name(entry.tag, machine);
+        let padding = (19 - name.len() as i64).unsigned_abs().max(1) as usize;
+
+        writeln!(
+            out,
+            " {:#018x} ({name}){} {}",
+            entry.tag,
+            " ".repeat(padding - 1),
+            dynamic_value(entry, &dynamic.strings(), machine, interpreter.as_deref())
+        )?;
+    }
+
+    Ok(())
+}
+
+// ------------
+// Relocations.
+
+pub fn relocations(
+    out: &mut impl Write,
+    elf_file: &mut ElfFile,
+    demangle: bool,
+) -> Result<(), ElfError> {
+    let machine = elf_file.header.machine_type();
+    let versions = elf_file.symbol_versions()?;
+
+    let mut found = false;
+
+    for index in 0..elf_file.section_headers.len() {
+        let data = &elf_file.section_headers[index].header_data;
+        let is_rela = data.type_id() == SHT_RELA;
+
+        if data.type_id() == SHT_RELR && data.size != 0 {
+            relr_relocations(out, elf_file, index)?;
+            found = true;
+            continue;
+        }
+        if !(is_rela || data.type_id() == SHT_REL) || data.size == 0 {
+            continue;
+        }
+
+        let offset = data.offset;
+        let link = data.link as usize;
+        let relocations = elf_file.relocations(index)?;
+
+        let count = relocations.len();
+        writeln!(
+            out,
+            "\nRelocation section '{}' at offset {} contains {count} {}:",
+            elf_file.section_headers[index].name,
+            alternate_hex(offset),
+            plural(count, "entry", "entries")
+        )?;
+        found = true;
+
+        // Symbols come from the linked table, and only dynamic symbols have versions.
+        let (symbols, is_dynsym) = match elf_file.section_headers.get(link) {
+            Some(table) if link != 0 => match table.header_data.type_id() {
+                SHT_DYNSYM => (elf_file.symbols(link)?, true),
+                SHT_SYMTAB => (elf_file.symbols(link)?, false),
+                _ => continue,
+            },
+            _ => (vec![], false),
+        };
+
+        if is_rela {
+            writeln!(
+                out,
+                "  Offset          Info           Type           Sym. Value    Sym. Name + Addend"
+            )?;
+        } else {
+            writeln!(
+                out,
+                "  Offset          Info           Type           Sym. Value    Sym. Name"
+            )?;
+        }
+
+        for relocation in &relocations {
+            let mut line = format!("{:012x}  {:012x} ", relocation.offset, relocation.info);
+
+            match relocation_type_name(relocation.reloc_type(), machine) {
+                Some(name) => line.push_str(&format!("{name:<17.17}")),
+                None => line.push_str(&format!("unrecognized: {:<7x}", relocation.reloc_type())),
+            }
+
+            let symbol_index = relocation.symbol_index() as usize;
+            let addend = relocation.addend.unwrap_or(0);
+
+            if symbol_index != 0 {
+                let Some(symbol) = symbols.get(symbol_index) else {
+                    writeln!(out, "{line}")?;
+                    continue;
+                };
+                let data = &symbol.symbol_data;
+
+                let version = versions
+                    .as_ref()
+                    .filter(|_| is_dynsym)
+                    .and_then(|versions| versions.symbol_version(symbol_index, data));
+                let suffix = version
+                    .as_ref()
+                    .map(SymbolVersion::suffix)
+                    .unwrap_or_default();
+
+                line.push(' ');
+
+                // The value of an IFUNC symbol isn't what's used, so readelf shows "name()".
+                if data.symbol_type() == STT_GNU_IFUNC {
+                    let name = print_symbol_name(14, &symbol.name, demangle);
+                    let width = name.chars().count();
+                    line.push_str(&format!("{name}{suffix}()"));
+                    line.push_str(&" ".repeat(if width <= 14 { 15 - width } else { 1 }));
+                } else {
+                    line.push_str(&format!("{:016x} ", data.value));
+                }
+
+                if data.name_offset == 0 {
+                    let name = if data.symbol_type() == STT_SECTION {
+                        match data.section_index {
+                            SHN_ABS => "ABS".to_owned(),
+                            SHN_COMMON => "COMMON".to_owned(),
+                            i => match elf_file.section_headers.get(i as usize) {
+                                Some(section) => section.name.clone(),
+                                None => format!("<section {i:#x}>"),
+                            },
+                        }
+                    } else {
+                        "<null>".to_owned()
+                    };
+                    line.push_str(&print_symbol(22, &name));
+                } else {
+                    line.push_str(&print_symbol_name(22, &symbol.name, demangle));
+                    line.push_str(&suffix);
+                }
+
+                if is_rela {
+                    if addend < 0 {
+                        line.push_str(&format!(" - {:x}", addend.unsigned_abs()));
+                    } else {
+                        line.push_str(&format!(" + {addend:x}"));
+                    }
+                }
+            } else if is_rela {
+                line.push_str(&" ".repeat(20));
+                if addend < 0 {
+                    line.push_str(&format!("-{:x}", addend.unsigned_abs()));
+                } else {
+                    line.push_str(&format!("{addend:x}"));
+                }
+            }
+
+            writeln!(out, "{line}")?;
+        }
+    }
+
+    if !found {
+        writeln!(out, "\nThere are no relocations in this file.")?;
+    }
+
+    Ok(())
+}
+
+/// Prints the addresses an SHT_RELR section relocates, after the number of
+/// entries that encode them.
+fn relr_relocations(
+    out: &mut impl Write,
+    elf_file: &mut ElfFile,
+    index: usize,
+) -> Result<(), ElfError> {
+    let section = &elf_file.section_headers[index];
+    let count = (section.header_data.size / 8) as usize;
+    writeln!(
+        out,
+        "\nRelocation section '{}' at offset {} contains {count} {}:",
+        section.name,
+        alternate_hex(section.header_data.offset),
+        plural(count, "entry", "entries")
+    )?;
+
+    let offsets = elf_file.relr_offsets(index)?;
+    writeln!(
+        out,
+        "  {} {}",
+        offsets.len(),
+        plural(offsets.len(), "offset", "offsets")
+    )?;
+    for offset in offsets {
+        writeln!(out, "{offset:016x}")?;
+    }
+
+    Ok(())
+}
+
+// --------------
+// Symbol tables.
+
+fn symbol_type_name(symbol_type: u8, os_abi: u8, machine: u16) -> String {
+    let name = match symbol_type {
+        STT_NOTYPE => "NOTYPE",
+        STT_OBJECT => "OBJECT",
+        STT_FUNC => "FUNC",
+        STT_SECTION => "SECTION",
+        STT_FILE => "FILE",
+        STT_COMMON => "COMMON",
+        STT_TLS => "TLS",
+        8 => "RELC",
+        9 => "SRELC",
+
+        STT_GNU_IFUNC if os_abi == ELFOSABI_GNU || os_abi == ELFOSABI_FREEBSD => "IFUNC",
+        13 if machine == EM_ARM => "THUMB_FUNC",
+
+        10..=12 => return format!("<OS specific>: {symbol_type}"),
+        13..=15 => return format!("<processor specific>: {symbol_type}"),
+        _ => return format!("<unknown>: {symbol_type}"),
+    };
+
+    name.to_owned()
+}
+
+fn symbol_bind_name(bind: u8, os_abi: u8) -> String {
+    let name = match bind {
+        STB_LOCAL => "LOCAL",
+        STB_GLOBAL => "GLOBAL",
+        STB_WEAK => "WEAK",
+
+        STB_GNU_UNIQUE if os_abi == ELFOSABI_GNU => "UNIQUE",
+
+        10..=12 => return format!("<OS specific>: {bind}"),
+        13..=15 => return format!("<processor specific>: {bind}"),
+        _ => return format!("<unknown>: {bind}"),
+    };
+
+    name.to_owned()
+}
+
+fn symbol_visibility_name(visibility: u8) -> &'static str {
+    match visibility {
+        STV_DEFAULT => "DEFAULT",
+        STV_INTERNAL => "INTERNAL",
+        STV_HIDDEN => "HIDDEN",
+        _ => "PROTECTED",
+    }
+}
+
+/// Names the bits of `st_other` besides the visibility.
+fn symbol_other_name(other: u8, machine: u16) -> String {
+    const STO_VARIANT: u8 = 0x80;
+
+    match (machine, other) {
+        (EM_AARCH64, STO_VARIANT) => "VARIANT_PCS".to_owned(),
+        (EM_RISCV, STO_VARIANT) => "VARIANT_CC".to_owned(),
+        (EM_RISCV, _) => format!("{other:x}"),
+        _ => format!("<other>: {other:x}"),
+    }
+}
+
+fn symbol_index_name(section_index: u16, section_count: usize, machine: u16) -> String {
+    const SHN_X86_64_LCOMMON: u16 = 0xff02;
+
+    match section_index {
+        SHN_UNDEF => "UND".to_owned(),
+        SHN_ABS => "ABS".to_owned(),
+        SHN_COMMON => "COM".to_owned(),
+
+        SHN_X86_64_LCOMMON if machine == EM_X86_64 => "LARGE_COM".to_owned(),
+        0xff00..=0xff1f => format!("PRC[{section_index:#06x}]"),
+        0xff20..=0xff3f => format!("OS [{section_index:#06x}]"),
+        0xff40.. => format!("RSV[{section_index:#06x}]"),
+
+        i if section_count != 0 && i as usize >= section_count => {
+            format!("bad section index[{i:>3}]")
+        }
+        i => format!("{i:>3}"),
+    }
+}
+
+pub fn symbols(
+    out: &mut impl Write,
+    elf_file: &mut ElfFile,
+    demangle: bool,
+) -> Result<(), ElfError> {
+    let header = &elf_file.header;
+    let (os_abi, machine) = (header.abi_os, header.machine_id());
+    let section_count = elf_file.section_headers.len();
+
+    let versions = elf_file.symbol_versions()?;
+
+    for index in 0..section_count {
+        let section_type = elf_file.section_headers[index].header_data.type_id();
+        if section_type != SHT_SYMTAB && section_type != SHT_DYNSYM {
+            continue;
+        }
+
+        let symbols = elf_file.symbols(index)?;
+
+        let count = symbols.len();
+        writeln!(
+            out,
+            "\nSymbol table '{}' contains {count} {}:",
+            elf_file.section_headers[index].name,
+            plural(count, "entry", "entries")
+        )?;
+        writeln!(
+            out,
+            "   Num:    Value          Size Type    Bind   Vis      Ndx Name"
+        )?;
+
+        for (i, symbol) in symbols.iter().enumerate() {
+            let data = &symbol.symbol_data;
+
+            let size = if data.size < 100000 {
+                format!("{:>5}", data.size)
+            } else {
+                format!("{:#x}", data.size)
+            };
+
+            let mut line = format!(
+                "{i:>6}: {:016x} {size} {:<7} {:<6} {:<7}",
+                data.value,
+                symbol_type_name(data.symbol_type(), os_abi, machine),
+                symbol_bind_name(data.bind(), os_abi),
+                symbol_visibility_name(data.visibility())
+            );
+
+            let other = data.other & !0x3;
+            if other != 0 {
+                line.push_str(&format!(" [{}] ", symbol_other_name(other, machine)));
+            }
+
+            line.push_str(&format!(
+                " {:>4} ",
+                symbol_index_name(data.section_index, section_count, machine)
+            ));
+
+            let name = if data.name_offset == 0
+                && data.symbol_type() == STT_SECTION
+                && (data.section_index as usize) < section_count
+            {
+                elf_file.section_headers[data.section_index as usize]
+                    .name
+                    .as_str()
+            } else {
+                symbol.name.as_str()
+            };
+
+            let version = versions
+                .as_ref()
+                .filter(|_| section_type == SHT_DYNSYM)
+                .and_then(|versions| versions.symbol_version(i, data));
+
+            // The version takes up some of the name's width.
+            match version {
+                Some(version) => {
+                    let mut width = 21 - (1 + version.name.len() as i32);
+                    let suffix = if version.needed {
+                        let index = format!(" ({})", version.index);
+                        width -= index.len() as i32;
+                        format!("@{}{index}", version.name)
+                    } else if version.hidden {
+                        format!("@{}", version.name)
+                    } else {
+                        width -= 1;
+                        format!("@@{}", version.name)
+                    };
+
+                    line.push_str(&print_symbol_name(width, name, demangle));
+                    line.push_str(&suffix);
+                }
+                None => line.push_str(&print_symbol_name(21, name, demangle)),
+            }
+
+            writeln!(out, "{line}")?;
+        }
+    }
+
+    Ok(())
+}
+
+// -----------------
+// Version sections.
+
+/// Version flags, as readelf's `get_ver_flags` names them.
+fn version_flags(flags: u16) -> String {
+    if flags == 0 {
+        return "none".to_owned();
+    }
+
+    let mut names = vec![];
+    if flags & VER_FLG_BASE != 0 {
+        names.push("BASE");
+    }
+    if flags & VER_FLG_WEAK != 0 {
+        names.push("WEAK");
+    }
+    if flags & VER_FLG_INFO != 0 {
+        names.push("INFO");
+    }
+    if flags & !(VER_FLG_BASE | VER_FLG_WEAK | VER_FLG_INFO) != 0 {
+        names.push("<unknown>");
+    }
+
+    names.join(" | ")
+}
+
+/// An offset into a version section, as readelf prints it with `%#06zx`.
+fn version_offset(offset: usize) -> String {
+    if offset == 0 {
+        "000000".to_owned()
+    } else {
+        format!("{offset:#06x}")
+    }
+}
+
+fn version_section_heading(
+    out: &mut impl Write,
+    elf_file: &ElfFile,
+    index: usize,
+    title: &str,
+    count: usize,
+) -> io::Result<()> {
+    let section = &elf_file.section_headers[index];
+    let data = &section.header_data;
+    let link = elf_file
+        .section_headers
+        .get(data.link as usize)
+        .map_or("", |linked| linked.name.as_str());
+
+    writeln!(
+        out,
+        "\n{title} section '{}' contains {count} {}:",
+        section.name,
+        plural(count, "entry", "entries")
+    )?;
+    writeln!(
+        out,
+        " Addr: {:#018x}  Offset: {:#010x}  Link: {} ({link})",
+        data.addr, data.offset, data.link
+    )
+}
+
+/// The version name readelf shows beside a versym entry: a definition for
+/// a defined symbol, or else a needed version. A defined symbol can have a
+/// needed version too, if its data is copied from a library.
+fn versym_name<'a>(
+    versions: &'a SymbolVersions,
+    versym: u16,
+    symbol: &Elf64SymbolEntry,
+) -> Option<&'a str> {
+    if symbol.section_index != SHN_UNDEF && versym != (VERSYM_HIDDEN | VER_NDX_GLOBAL) {
+        let def = versions
+            .defs
+            .iter()
+            .find(|def| def.index == versym & VERSYM_VERSION);
+
+        if let Some(name) = def.and_then(|def| def.names.first()) {
+            return Some(name);
+        }
+    }
+
+    versions
+        .needs
+        .iter()
+        .flat_map(|need| &need.versions)
+        .find(|needed| needed.index == versym)
+        .map(|needed| needed.name.as_str())
+}
+
+fn version_symbols(
+    out: &mut impl Write,
+    elf_file: &mut ElfFile,
+    index: usize,
+    versions: &SymbolVersions,
+) -> Result<(), ElfError> {
+    let versym = read_versym_64(elf_file.section_bytes(index)?);
+    let link = elf_file.section_headers[index].header_data.link as usize;
+    let symbols = match elf_file.section_headers.get(link) {
+        Some(_) if link != 0 => elf_file.symbols(link)?,
+        _ => vec![],
+    };
+
+    version_section_heading(out, elf_file, index, "Version symbols", versym.len())?;
+
+    for (row, entries) in versym.chunks(4).enumerate() {
+        let mut line = format!("  {:03x}:", row * 4);
+
+        for (column, &entry) in entries.iter().enumerate() {
+            match entry {
+                VER_NDX_LOCAL => line.push_str("   0 (*local*)    "),
+                VER_NDX_GLOBAL => line.push_str("   1 (*global*)   "),
+
+                _ => {
+                    let hidden = if entry & VERSYM_HIDDEN != 0 { 'h' } else { ' ' };
+                    let mut text = format!("{:4x}{hidden}", entry & VERSYM_VERSION);
+
+                    // readelf gives up on the entry, padding and all, without a symbol.
+                    let Some(symbol) = symbols.get(row * 4 + column) else {
+                        line.push_str(&text);
+                        continue;
+                    };
+
+                    if let Some(name) = versym_name(versions, entry, &symbol.symbol_data) {
+                        // This is printf's "(%s%-*s" with 12 - strlen(name) as the
+                        // width, and a negative width also pads, to the left.
+                        let width = (12 - name.len() as i64).unsigned_abs() as usize;
+                        text.push_str(&format!("({name}{:<width$}", ")"));
+                    }
+
+                    line.push_str(&format!("{text:<18}"));
+                }
+            }
+        }
+
+        writeln!(out, "{line}")?;
+    }
+
+    Ok(())
+}
+
+pub fn versions(out: &mut impl Write, elf_file: &mut ElfFile) -> Result<(), ElfError> {
+    let versions = elf_file.symbol_versions()?.unwrap_or_default();
+    let mut found = false;
+
+    for index in 0..elf_file.section_headers.len() {
+        let section = &elf_file.section_headers[index].header_data;
+        let (section_type, link, count) = (section.type_id(), section.link, section.info);
+
+        if ![SHT_GNU_VERDEF, SHT_GNU_VERNEED, SHT_GNU_VERSYM].contains(&section_type) {
+            continue;
+        }
+        found = true;
+
+        if section_type == SHT_GNU_VERSYM {
+            version_symbols(out, elf_file, index, &versions)?;
+            continue;
+        }
+
+        let strings = elf_file.section_bytes(link as usize)?.to_vec();
+        let strings = StringTable::new(&strings);
+
+        version_section_heading(
+            out,
+            elf_file,
+            index,
+            if section_type == SHT_GNU_VERDEF {
+                "Version definition"
+            } else {
+                "Version needs"
+            },
+            count as usize,
+        )?;
+
+        let bytes = elf_file.section_bytes(index)?;
+
+        if section_type == SHT_GNU_VERDEF {
+            for def in read_verdef_64(bytes, count as usize, &strings)? {
+                writeln!(
+                    out,
+                    "  {}: Rev: {}  Flags: {}  Index: {}  Cnt: {}  Name: {}",
+                    version_offset(def.offset),
+                    def.version,
+                    version_flags(def.flags),
+                    def.index,
+                    def.names.len(),
+                    def.names.first().map_or("", |name| name.as_str())
+                )?;
+
+                for (parent, (name, offset)) in def
+                    .names
+                    .iter()
+                    .zip(&def.name_entry_offsets)
+                    .enumerate()
+                    .skip(1)
+                {
+                    writeln!(
+                        out,
+                        "  {}: Parent {parent}: {name}",
+                        version_offset(*offset)
+                    )?;
+                }
+            }
+        } else {
+            for need in read_verneed_64(bytes, count as usize, &strings)? {
+                writeln!(
+                    out,
+                    "  {}: Version: {}  File: {}  Cnt: {}",
+                    version_offset(need.offset),
+                    need.version,
+                    need.file,
+                    need.versions.len()
+                )?;
+
+                for needed in &need.versions {
+                    writeln!(
+                        out,
+                        "  {}:   Name: {}  Flags: {}  Version: {}",
+                        version_offset(needed.offset),
+                        needed.name,
+                        version_flags(needed.flags),
+                        needed.index
+                    )?;
+                }
+            }
+        }
+    }
+
+    if !found {
+        writeln!(out, "\nNo version information found in this file.")?;
+    }
+
+    Ok(())
+}
+
+// ------
+// Notes.
+
+fn note_type_name(note: &ElfNote) -> String {
+    const NT_VERSION: u32 = 1;
+    const NT_ARCH: u32 = 2;
+    const NT_GNU_BUILD_ATTRIBUTE_OPEN: u32 = 0x100;
+    const NT_GNU_BUILD_ATTRIBUTE_FUNC: u32 = 0x101;
+
+    let name = match (note.name.as_str(), note.note_type) {
+        ("GNU", NT_GNU_ABI_TAG) => "NT_GNU_ABI_TAG (ABI version tag)",
+        ("GNU", NT_GNU_HWCAP) => "NT_GNU_HWCAP (DSO-supplied software HWCAP info)",
+        ("GNU", NT_GNU_BUILD_ID) => "NT_GNU_BUILD_ID (unique build ID bitstring)",
+        ("GNU", NT_GNU_GOLD_VERSION) => "NT_GNU_GOLD_VERSION (gold version)",
+        ("GNU", NT_GNU_PROPERTY_TYPE_0) => "NT_GNU_PROPERTY_TYPE_0",
+        ("GNU", NT_GNU_BUILD_ATTRIBUTE_OPEN) => "NT_GNU_BUILD_ATTRIBUTE_OPEN",
+        ("GNU", NT_GNU_BUILD_ATTRIBUTE_FUNC) => "NT_GNU_BUILD_ATTRIBUTE_FUNC",
+        ("GNU", _) => return format!("Unknown note type: ({:#010x})", note.note_type),
+
+        ("stapsdt", NT_STAPSDT) => "NT_STAPSDT (SystemTap probe descriptors)",
+        ("stapsdt", _) => return format!("Unknown note type: ({:#010x})", note.note_type),
+
+        (_, NT_VERSION) => "NT_VERSION (version)",
+        (_, NT_ARCH) => "NT_ARCH (architecture)",
+        (_, NT_GNU_BUILD_ATTRIBUTE_OPEN) => "OPEN",
+        (_, NT_GNU_BUILD_ATTRIBUTE_FUNC) => "func",
+        (_, NT_GO_BUILD_ID) => "GO BUILDID",
+        (_, NT_FDO_PACKAGING_METADATA) => "FDO_PACKAGING_METADATA",
+
+        _ => return format!("Unknown note type: ({:#010x})", note.note_type),
+    };
+
+    name.to_owned()
+}
+
+fn gnu_property(property: &GnuProperty) -> String {
+    const GNU_PROPERTY_LOPROC: u32 = 0xc0000000;
+    const GNU_PROPERTY_LOUSER: u32 = 0xe0000000;
+
+    match property {
+        GnuProperty::StackSize(size) => format!("stack size: {}", alternate_hex(*size)),
+        GnuProperty::NoCopyOnProtected => "no copy on protected ".to_owned(),
+
+        GnuProperty::Other { pr_type, data } => {
+            let kind = match *pr_type {
+                ..GNU_PROPERTY_LOPROC => "unknown",
+                GNU_PROPERTY_LOPROC..GNU_PROPERTY_LOUSER => "processor-specific",
+                _ => "application-specific",
+            };
+            format!(
+                "<{kind} type {} data: {}>",
+                alternate_hex(*pr_type as u64),
+                spaced_hex(data)
+            )
+        }
+
+        other => other.to_string(),
+    }
+}
+
+/// The lines describing a note's contents, each ending in a newline.
+fn note_body(note: &ElfNote, machine: u16) -> String {
+    let desc = note.desc;
+
+    match note.name.as_str() {
+        "GNU" => match note.note_type {
+            NT_GNU_HWCAP => {
+                if desc.len() < 8 {
+                    return "      Hardware Capabilities: <corrupt GNU_HWCAP>\n".to_owned();
+                }
+                let count = u32::from_le_bytes(desc[0..4].try_into().unwrap());
+                let mask = u32::from_le_bytes(desc[4..8].try_into().unwrap());
+                format!(
+                    "      Hardware Capabilities: num entries: {count}, enabled mask: {mask:x}\n"
+                )
+            }
+
+            NT_GNU_ABI_TAG if desc.len() < 16 => "    <corrupt GNU_ABI_TAG>\n".to_owned(),
+
+            NT_GNU_PROPERTY_TYPE_0 => match note.descriptor(machine) {
+                NoteDescriptor::GnuProperties(properties) => {
+                    let lines: Vec<String> = properties.iter().map(gnu_property).collect();
+                    format!("      Properties: {}\n", lines.join("\n\t"))
+                }
+                _ => format!(
+                    "      Properties: <corrupt GNU_PROPERTY_TYPE, size = {}>\n",
+                    alternate_hex(desc.len() as u64)
+                ),
+            },
+
+            NT_GNU_ABI_TAG | NT_GNU_BUILD_ID => format!("    {}\n", note.descriptor(machine)),
+
+            NT_GNU_GOLD_VERSION => {
+                let end = desc.iter().position(|&b| b == 0).unwrap_or(desc.len());
+                format!("    Version: {}\n", String::from_utf8_lossy(&desc[..end]))
+            }
+
+            _ => format!("    Description data: {}\n", spaced_hex(desc)),
+        },
+
+        "FDO" if note.note_type == NT_FDO_PACKAGING_METADATA && !desc.is_empty() => {
+            let end = desc.iter().position(|&b| b == 0).unwrap_or(desc.len());
+            format!(
+                "    Packaging Metadata: {}\n",
+                String::from_utf8_lossy(&desc[..end])
+            )
+        }
+
+        "stapsdt" if note.note_type == NT_STAPSDT => match note.descriptor(machine) {
+            NoteDescriptor::StapSdt {
+                location,
+                base,
+                semaphore,
+                provider,
+                name,
+                arguments,
+            } => format!(
+                "    Provider: {provider}\n    Name: {name}\n    \
+                 Location: {location:#018x}, Base: {base:#018x}, Semaphore: {semaphore:#018x}\n    \
+                 Arguments: {arguments}\n"
+            ),
+            _ => "  <corrupt - note is too small>\n".to_owned(),
+        },
+
+        _ if !desc.is_empty() => format!("   description data: {}\n", spaced_hex(desc)),
+        _ => String::new(),
+    }
+}
+
+pub fn notes(out: &mut impl Write, elf_file: &mut ElfFile) -> Result<(), ElfError> {
+    let machine = elf_file.header.machine_id();
+
+    for index in elf_file.section_indices_by_type(SHT_NOTE) {
+        let section = &elf_file.section_headers[index];
+        if section.header_data.size == 0 {
+            continue;
+        }
+
+        let align = section.header_data.addr_align;
+        writeln!(out, "\nDisplaying notes found in: {}", section.name)?;
+        writeln!(out, "  {:<20} {:<10}\tDescription", "Owner", "Data size")?;
+
+        for note in read_notes(elf_file.section_bytes(index)?, align) {
+            let note = note?;
+            let owner = if note.name.is_empty() {
+                "(NONE)"
+            } else {
+                note.name.as_str()
+            };
+
+            writeln!(
+                out,
+                "  {} {:#010x}\t{}",
+                print_symbol(-20, owner),
+                note.desc.len(),
+                note_type_name(&note)
+            )?;
+            write!(out, "{}", note_body(&note, machine))?;
+        }
+    }
+
+    Ok(())
+}
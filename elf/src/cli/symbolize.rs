@@ -0,0 +1,77 @@
+/// The `symbolize` subcommand: names addresses after the symbols they're
+/// in, as `main+0x1c`, for going through addresses from logs in bulk.
+///
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+
+use elf::demangle::demangle_symbol;
+use elf::parse::symbol_map::SymbolMap;
+use elf::{ElfError, ElfFile};
+
+use super::addr2line::parse_address;
+
+#[derive(clap::Args, Debug)]
+pub struct SymbolizeArgs {
+    /// ELF file with a symbol table, or debugging information.
+    pub file: PathBuf,
+
+    /// Addresses to look up, in hex (with or without 0x). Without
+    /// any, they're read from standard input, one per line.
+    #[arg(value_parser = parse_address)]
+    pub addresses: Vec<u64>,
+
+    /// Show C++ and Rust symbol names demangled.
+    #[arg(long)]
+    pub demangle: bool,
+}
+
+/// Prints each address in `args`, or on standard input, with its symbol.
+pub fn symbolize(path: &Path, args: &SymbolizeArgs) -> Result<(), ElfError> {
+    let map = ElfFile::open(path)?.symbol_map()?;
+    let mut out = io::stdout().lock();
+
+    if !args.addresses.is_empty() {
+        for &address in &args.addresses {
+            let given = format!("{address:#x}");
+            print_symbol(&mut out, &map, &given, Some(address), args.demangle)?;
+        }
+        return Ok(());
+    }
+
+    for line in io::stdin().lock().lines() {
+        let line = line?;
+        let line = line.trim();
+        if !line.is_empty() {
+            match parse_address(line) {
+                Ok(address) => {
+                    let given = format!("{address:#x}");
+                    print_symbol(&mut out, &map, &given, Some(address), args.demangle)?;
+                }
+                Err(_) => print_symbol(&mut out, &map, line, None, args.demangle)?,
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Prints the address as it's given and then its symbol, or "??" if it
+/// isn't in one (or isn't an address), so that each line of output
+/// matches a line of input.
+fn print_symbol(
+    out: &mut impl Write,
+    map: &SymbolMap,
+    given: &str,
+    address: Option<u64>,
+    demangle: bool,
+) -> io::Result<()> {
+    match address.and_then(|address| map.lookup(address)) {
+        Some(mut symbol) => {
+            if demangle {
+                symbol.name = demangle_symbol(&symbol.name).into_owned();
+            }
+            writeln!(out, "{given} {symbol}")
+        }
+        None => writeln!(out, "{given} ??"),
+    }
+}
@@ -0,0 +1,334 @@
+/// The `unwind` subcommand: shows the call frame information in .eh_frame
+/// and .debug_frame as tables of rules, as `readelf --debug-dump=frames-interp`
+/// does, or the SFrame information as `readelf --sframe` does, or the rules
+/// for unwinding the frames of given addresses.
+///
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use elf::dwarf::cfa::{CfaRule, RegisterRule, UnwindRow, UnwindTable};
+use elf::dwarf::frame::{CallFrameInfo, FrameEntry, FrameSection};
+use elf::dwarf::registers::register_name;
+use elf::parse::sframe::*;
+use elf::{ElfError, ElfFile};
+
+use super::addr2line::parse_address;
+
+#[derive(clap::Args, Debug)]
+pub struct UnwindArgs {
+    /// ELF file with call frame information (an .eh_frame or .debug_frame section).
+    pub file: PathBuf,
+
+    /// Addresses to show the rules at, in hex (with or without 0x). Without
+    /// any, every CIE and FDE is shown, with its table of rules.
+    #[arg(value_parser = parse_address)]
+    pub addresses: Vec<u64>,
+
+    /// Use the SFrame information in .sframe instead.
+    #[arg(long)]
+    pub sframe: bool,
+}
+
+/// Prints the call frame information of the file at `path`.
+pub fn unwind(path: &Path, args: &UnwindArgs) -> Result<(), ElfError> {
+    let mut elf_file = ElfFile::open(path)?;
+    let machine = elf_file.header.machine_id();
+    let mut out = io::stdout().lock();
+
+    if args.sframe {
+        let sframe = elf_file.sframe()?.ok_or_else(|| ElfError::UnknownSection {
+            name: ".sframe".to_owned(),
+        })?;
+        return print_sframe(&mut out, &sframe, &args.addresses);
+    }
+
+    if !args.addresses.is_empty() {
+        for &address in &args.addresses {
+            match elf_file.unwind_row(address)? {
+                Some(row) => writeln!(out, "{address:#018x}: {}", describe_row(machine, &row))?,
+                None => writeln!(out, "{address:#018x}: no unwind information")?,
+            }
+        }
+        return Ok(());
+    }
+
+    // In the order of the sections, as readelf goes through them.
+    let mut sections = vec![];
+    for section in [FrameSection::EhFrame, FrameSection::DebugFrame] {
+        if let Some(info) = elf_file.call_frame_info(section)? {
+            let index = elf_file.section_index_by_name(section.name());
+            sections.push((index, info));
+        }
+    }
+    sections.sort_by_key(|(index, _)| *index);
+
+    for (_, info) in &sections {
+        print_section(&mut out, machine, info)?;
+    }
+
+    Ok(())
+}
+
+/// The name of a register, as readelf writes it.
+fn register(machine: u16, register: u16) -> String {
+    register_name(machine, register).unwrap_or_else(|| format!("r{register}"))
+}
+
+fn describe_cfa(machine: u16, cfa: &CfaRule) -> String {
+    match cfa {
+        CfaRule::RegisterOffset {
+            register: number,
+            offset,
+        } => {
+            format!("{}{offset:+}", register(machine, *number))
+        }
+        CfaRule::Expression(_) => "exp".to_owned(),
+    }
+}
+
+/// Describes a rule in readelf's shorthand: "c-8" is saved at the CFA - 8,
+/// "v" is a value rather than where it's saved, and "u" is undefined.
+fn describe_rule(machine: u16, rule: Option<&RegisterRule>) -> String {
+    match rule {
+        None | Some(RegisterRule::Undefined) => "u".to_owned(),
+        Some(RegisterRule::SameValue) => "s".to_owned(),
+        Some(RegisterRule::Offset(offset)) => format!("c{offset:+}"),
+        Some(RegisterRule::ValOffset(offset)) => format!("v{offset:+}"),
+        Some(RegisterRule::Register(number)) => match register_name(machine, *number) {
+            Some(name) => format!("r{number} ({name})"),
+            None => format!("r{number}"),
+        },
+        Some(RegisterRule::Expression(_)) => "exp".to_owned(),
+        Some(RegisterRule::ValExpression(_)) => "vexp".to_owned(),
+    }
+}
+
+/// Describes the rules of a row on one line, with the return address as "ra".
+fn describe_row(machine: u16, row: &UnwindRow) -> String {
+    let mut line = format!("CFA={}", describe_cfa(machine, &row.cfa));
+
+    for (&number, rule) in &row.registers {
+        let name = match number == row.return_address_register {
+            true => "ra".to_owned(),
+            false => register(machine, number),
+        };
+        line.push_str(&format!(" {name}={}", describe_rule(machine, Some(rule))));
+    }
+
+    line
+}
+
+fn print_section(out: &mut impl Write, machine: u16, info: &CallFrameInfo) -> Result<(), ElfError> {
+    if info.is_empty() {
+        writeln!(
+            out,
+            "\nSection '{}' has no debugging data.",
+            info.section().name()
+        )?;
+        return Ok(());
+    }
+
+    writeln!(out, "Contents of the {} section:\n", info.section().name())?;
+
+    for entry in info.entries()? {
+        match entry {
+            FrameEntry::Cie(cie) => {
+                writeln!(
+                    out,
+                    "\n{:08x} {:016x} {:0width$x} CIE \"{}\" cf={} df={} ra={}",
+                    cie.offset,
+                    cie.length,
+                    cie.id,
+                    cie.augmentation,
+                    cie.code_alignment_factor,
+                    cie.data_alignment_factor,
+                    cie.return_address_register,
+                    width = cie.offset_size * 2
+                )?;
+
+                if !only_nops(info.instructions(&cie.instructions)) {
+                    let table = info.initial_rules(&cie)?;
+                    print_table(out, machine, &table, cie.return_address_register)?;
+                }
+            }
+
+            FrameEntry::Fde(fde) => {
+                let cie = info.cie_at(fde.cie_offset)?;
+
+                writeln!(
+                    out,
+                    "\n{:08x} {:016x} {:0width$x} FDE cie={:08x} pc={:016x}..{:016x}",
+                    fde.offset,
+                    fde.length,
+                    fde.cie_pointer,
+                    fde.cie_offset,
+                    fde.range.start,
+                    fde.range.end,
+                    width = cie.offset_size * 2
+                )?;
+
+                if !only_nops(info.instructions(&fde.instructions)) {
+                    let table = info.unwind_table(&fde)?;
+                    print_table(out, machine, &table, cie.return_address_register)?;
+                }
+            }
+
+            FrameEntry::Terminator(offset) => writeln!(out, "\n{offset:08x} ZERO terminator\n")?,
+        }
+    }
+
+    writeln!(out)?;
+    Ok(())
+}
+
+/// True if instructions are all DW_CFA_nop (padding), which is when readelf
+/// leaves out the table.
+fn only_nops(instructions: &[u8]) -> bool {
+    instructions.iter().all(|&byte| byte == 0)
+}
+
+/// Prints a row for each address the instructions advance to, with
+/// a column for each register that they give rules for.
+fn print_table(
+    out: &mut impl Write,
+    machine: u16,
+    table: &UnwindTable,
+    return_address_register: u16,
+) -> io::Result<()> {
+    let mut header = "   LOC           CFA      ".to_owned();
+    for &number in &table.columns {
+        match number == return_address_register {
+            true => header.push_str("ra    "),
+            false => header.push_str(&format!("{:<5} ", register(machine, number))),
+        }
+    }
+    writeln!(out, "{header}")?;
+
+    for row in &table.rows {
+        let mut line = format!(
+            "{:016x} {:<8} ",
+            row.range.start,
+            describe_cfa(machine, &row.cfa)
+        );
+        for number in &table.columns {
+            let rule = describe_rule(machine, row.registers.get(number));
+            line.push_str(&format!("{rule:<5} "));
+        }
+        writeln!(out, "{line}")?;
+    }
+
+    Ok(())
+}
+
+fn describe_sframe_cfa(fre: &SframeFre) -> String {
+    let base = match fre.cfa_base {
+        CfaBase::StackPointer => "sp",
+        CfaBase::FramePointer => "fp",
+    };
+    // readelf writes a negative offset as "+-8".
+    format!("{base}+{}", fre.cfa_offset)
+}
+
+fn describe_sframe_offset(offset: Option<i32>) -> String {
+    match offset {
+        Some(offset) => format!("c{offset:+}"),
+        None => "u".to_owned(),
+    }
+}
+
+/// Prints the SFrame information, as readelf does, or the FRE at each of
+/// `addresses`.
+fn print_sframe(
+    out: &mut impl Write,
+    sframe: &SframeSection,
+    addresses: &[u64],
+) -> Result<(), ElfError> {
+    if !addresses.is_empty() {
+        for &address in addresses {
+            match sframe.find_fre(address)? {
+                Some((_, fre)) => writeln!(
+                    out,
+                    "{address:#018x}: CFA={} fp={} ra={}{}",
+                    describe_sframe_cfa(&fre),
+                    describe_sframe_offset(fre.fp_offset),
+                    describe_sframe_offset(fre.ra_offset),
+                    if fre.mangled_ra { " (signed)" } else { "" }
+                )?,
+                None => writeln!(out, "{address:#018x}: no SFrame information")?,
+            }
+        }
+        return Ok(());
+    }
+
+    let header = &sframe.header;
+    let flags: Vec<_> = [
+        (SFRAME_F_FDE_SORTED, "SFRAME_F_FDE_SORTED"),
+        (SFRAME_F_FRAME_POINTER, "SFRAME_F_FRAME_POINTER"),
+        (
+            SFRAME_F_FDE_FUNC_START_PCREL,
+            "SFRAME_F_FDE_FUNC_START_PCREL",
+        ),
+    ]
+    .into_iter()
+    .filter(|&(flag, _)| header.flags & flag != 0)
+    .map(|(_, name)| name)
+    .collect();
+
+    writeln!(out, "Contents of the SFrame section .sframe:")?;
+    writeln!(out, "  Header :\n")?;
+    writeln!(out, "    Version: SFRAME_VERSION_{}", header.version)?;
+    writeln!(
+        out,
+        "    Flags: {}",
+        if flags.is_empty() {
+            "NONE".to_owned()
+        } else {
+            flags.join(",")
+        }
+    )?;
+    writeln!(out, "    Num FDEs: {}", header.fde_count)?;
+    writeln!(out, "    Num FREs: {}", header.fre_count)?;
+    writeln!(out, "\n  Function Index :")?;
+
+    for (index, fde) in sframe.fdes.iter().enumerate() {
+        let pc_mask = fde.fde_type == SFRAME_FDE_TYPE_PCMASK;
+
+        writeln!(
+            out,
+            "\n    func idx [{index}]: pc = {:#x}, size = {} bytes",
+            fde.range.start,
+            fde.range.end - fde.range.start
+        )?;
+        writeln!(
+            out,
+            "    STARTPC{:<8} {:<10}{:<10}{:<13}",
+            if pc_mask { "[m]" } else { "" },
+            "CFA",
+            "FP",
+            "RA"
+        )?;
+
+        for fre in sframe.fres(fde)? {
+            let start = match pc_mask {
+                true => fre.start as u64,
+                false => fde.range.start + fre.start as u64,
+            };
+
+            // A return address at a fixed offset isn't shown.
+            let mut ra = match header.cfa_fixed_ra_offset {
+                0 => describe_sframe_offset(fre.ra_offset),
+                _ => "u".to_owned(),
+            };
+            ra.push_str(if fre.mangled_ra { "[s]" } else { "   " });
+
+            writeln!(
+                out,
+                "    {start:016x}  {:<10}{:<10}{ra:<13}",
+                describe_sframe_cfa(&fre),
+                describe_sframe_offset(fre.fp_offset)
+            )?;
+        }
+    }
+
+    Ok(())
+}
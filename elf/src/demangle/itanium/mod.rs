@@ -0,0 +1,182 @@
+/// Demangling C++ names mangled by the Itanium C++ ABI, as GCC and Clang
+/// mangle them on every ELF platform. `parse.rs` reads a name into a tree
+/// of `Node`s, and `print.rs` writes that out as C++. Template parameters
+/// (`T_`) are resolved to their arguments as they're read, so the tree
+/// holds the arguments themselves.
+///
+mod parse;
+mod print;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Demangles a `_Z` name, or a `_GLOBAL__I_`/`_GLOBAL__D_` one for the
+/// constructors or destructors of a file.
+pub fn demangle(mangled: &str) -> Option<String> {
+    let node = parse::parse(mangled)?;
+    print::print(&node)
+}
+
+/// The cv-qualifiers of a type, or of the `this` of a member function.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(super) struct Qualifiers {
+    pub is_const: bool,
+    pub is_volatile: bool,
+    pub is_restrict: bool,
+}
+
+/// What a member function's `this` must be: `&` for lvalues, `&&` for
+/// rvalues.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum RefQualifier {
+    LValue,
+    RValue,
+}
+
+/// The qualifiers of a member function, which go after its parameters.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(super) struct FunctionQualifiers {
+    pub cv: Qualifiers,
+    pub ref_qualifier: Option<RefQualifier>,
+    /// `noexcept`, maybe with its condition, or `throw` with its types.
+    pub exceptions: Option<Exceptions>,
+    pub transaction_safe: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum Exceptions {
+    Noexcept(Option<Rc<Node>>),
+    Throw(Vec<Rc<Node>>),
+}
+
+/// An operator, by its code in the mangling.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct Operator {
+    pub code: &'static str,
+    /// As written in an expression; new and delete, and `sizeof` and the
+    /// like, have a space on the end.
+    pub name: &'static str,
+    /// How many operands it takes in an expression.
+    pub arity: u8,
+}
+
+/// A part of a demangled name: the names, the types, and the expressions
+/// that template arguments and `decltype`s are made of.
+#[derive(Debug, PartialEq)]
+pub(super) enum Node {
+    /// An identifier, or something written as it is, like `std` or
+    /// `(anonymous namespace)`.
+    Name(String),
+    /// One of the abbreviations for the standard library (`Sa`, `Ss`).
+    Standard(&'static str),
+    /// `scope::name`.
+    Qualified(Rc<Node>, Rc<Node>),
+    /// `name<arguments>`.
+    Template(Rc<Node>, Vec<Rc<Node>>),
+    /// `name[abi:tag]`.
+    AbiTag(Rc<Node>, String),
+    /// An `operator+` or the like, by name.
+    Operator(Operator),
+    /// `operator "" _suffix`.
+    LiteralOperator(Rc<Node>),
+    /// A vendor's own operator.
+    VendorOperator(Rc<Node>),
+    /// `operator int`.
+    Conversion(Rc<Node>),
+    /// A constructor, with the name of its class.
+    Constructor(Rc<Node>),
+    Destructor(Rc<Node>),
+    /// A lambda, with its parameters and which in its scope it is.
+    Lambda(Vec<Rc<Node>>, u64),
+    /// An unnamed class or enum, by which in its scope it is.
+    UnnamedType(u64),
+    /// `[a, b]`, the names of a structured binding.
+    StructuredBinding(Vec<Rc<Node>>),
+    /// An entity local to a function: `function()::entity`.
+    Local(Rc<Node>, Rc<Node>),
+    /// An entity in a default argument: `{default arg#1}::entity`.
+    DefaultArgument(u64, Rc<Node>),
+    /// A member function's name, with the qualifiers that go after its
+    /// parameters.
+    MemberQualified(Rc<Node>, FunctionQualifiers),
+    /// A function, with its name and its type.
+    Encoding(Rc<Node>, Rc<Node>),
+    /// An `[clone .cold]` of the encoding.
+    Clone(Rc<Node>, String),
+    /// A name that isn't the entity itself, like `vtable for A`.
+    Special(&'static str, Rc<Node>),
+    /// `construction vtable for Base-in-Derived`.
+    ConstructionVtable(Rc<Node>, Rc<Node>),
+    /// `reference temporary #0 for x`.
+    ReferenceTemporary(Rc<Node>, u64),
+
+    /// `int`, `unsigned long` and the other built-in types.
+    Builtin(&'static str),
+    /// A type that's a name, with a vendor's qualifier after it.
+    VendorQualified(Rc<Node>, Rc<Node>),
+    /// A type with cv-qualifiers.
+    CvQualified(Rc<Node>, Qualifiers),
+    Pointer(Rc<Node>),
+    LValueReference(Rc<Node>),
+    RValueReference(Rc<Node>),
+    /// `type _Complex` or `type _Imaginary`.
+    Complex(Rc<Node>, &'static str),
+    /// A function type: the return type (if it's part of the mangling), the
+    /// parameters, and the qualifiers for member functions.
+    Function {
+        ret: Option<Rc<Node>>,
+        params: Vec<Rc<Node>>,
+        qualifiers: FunctionQualifiers,
+    },
+    /// An array of the element type, with its dimension, if it has one.
+    Array(Rc<Node>, Option<Rc<Node>>),
+    /// `int __vector(4)`.
+    Vector(Rc<Node>, Rc<Node>),
+    /// A pointer to a member of the class, of the type.
+    PointerToMember(Rc<Node>, Rc<Node>),
+    /// `decltype (expression)`.
+    Decltype(Rc<Node>),
+    /// A template parameter, by its index, as its argument.
+    Parameter(usize, Rc<Node>),
+    /// A template parameter used before its arguments are known, in the
+    /// type of a conversion operator or a lambda's parameters, filled in
+    /// once they are. In the lambda's own parameters, it's an `auto:1`.
+    Forward(RefCell<Option<Rc<Node>>>, usize),
+    /// An argument pack.
+    Pack(Vec<Rc<Node>>),
+    /// A pattern repeated for each argument in the packs it uses.
+    PackExpansion(Rc<Node>),
+
+    /// A literal: its type and its value, which is negative if it's written
+    /// with an `n`.
+    Literal(Rc<Node>, String, bool),
+    /// A function parameter, as `{parm#1}`, or `this` for 0.
+    FunctionParameter(u64),
+    Nullary(Operator),
+    Unary(Operator, Rc<Node>),
+    /// A postfix `++` or `--`.
+    Postfix(Operator, Rc<Node>),
+    Binary(Operator, Rc<Node>, Rc<Node>),
+    Trinary(Operator, Rc<Node>, Rc<Node>, Rc<Node>),
+    /// A C-style cast, to one of the expressions or a list of them.
+    Cast(Rc<Node>, Rc<Node>),
+    /// `static_cast<type>(expression)` and the others.
+    NamedCast(Operator, Rc<Node>, Rc<Node>),
+    Call(Rc<Node>, Vec<Rc<Node>>),
+    /// A list of expressions, in parentheses.
+    ExpressionList(Vec<Rc<Node>>),
+    /// `type{...}`, or just `{...}`.
+    InitializerList(Option<Rc<Node>>, Vec<Rc<Node>>),
+    /// `sizeof...(pack)`, which is written as the pack's length.
+    SizeofPack(Rc<Node>),
+    /// A fold over a pack, by the fold's code (`fl`, `fr`, `fL` or `fR`):
+    /// the operator, and the pack with the initial value, if there is one,
+    /// in the order they're written.
+    Fold(&'static str, Operator, Vec<Rc<Node>>),
+    /// `new (placement) type(initializer)`, or `new[]`.
+    New {
+        placement: Vec<Rc<Node>>,
+        ty: Rc<Node>,
+        initializer: Option<Rc<Node>>,
+    },
+}
@@ -0,0 +1,1559 @@
+/// Reading an Itanium-mangled name into `Node`s, production by production
+/// of the ABI's grammar, as libiberty's `cp-demangle.c` reads it: the
+/// same things are substitution candidates, and the same leniencies are
+/// allowed.
+///
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use super::{Exceptions, FunctionQualifiers, Node, Operator, RefQualifier};
+
+/// How deeply productions may nest, so that a name made to nest deeply
+/// can't overflow the stack.
+const MAX_DEPTH: usize = 256;
+
+/// Parses the whole of a mangled name.
+pub fn parse(mangled: &str) -> Option<Rc<Node>> {
+    let mut parser = Parser::new(mangled.as_bytes());
+    let node = match global_kind(mangled.as_bytes()) {
+        Some(kind) => {
+            parser.advance(11);
+            let keyed_to = match parser.peek_at(0..2) {
+                b"_Z" => parser.mangled_name(false)?,
+                _ => Rc::new(Node::Name(mangled[11..].to_string())),
+            };
+            parser.pos = mangled.len();
+            Rc::new(Node::Special(kind, keyed_to))
+        }
+        None => parser.mangled_name(true)?,
+    };
+    (parser.pos == mangled.len() && parser.forward.is_empty()).then_some(node)
+}
+
+/// For `_GLOBAL__I_` and `_GLOBAL__D_` (with `.` or `$` as the second
+/// `_`, on some targets), what the function does.
+fn global_kind(mangled: &[u8]) -> Option<&'static str> {
+    if mangled.len() < 11 || !mangled.starts_with(b"_GLOBAL_") {
+        return None;
+    }
+    if !matches!(mangled[8], b'.' | b'_' | b'$') || mangled[10] != b'_' {
+        return None;
+    }
+    match mangled[9] {
+        b'I' => Some("global constructors keyed to "),
+        b'D' => Some("global destructors keyed to "),
+        _ => None,
+    }
+}
+
+struct Parser<'a> {
+    input: &'a [u8],
+    pos: usize,
+    substitutions: Vec<Rc<Node>>,
+    /// The template arguments that template parameters refer to: those
+    /// of the last template in the name of the encoding being read.
+    template_args: Vec<Rc<Node>>,
+    /// Whether the template arguments being read are those.
+    tagging_templates: bool,
+    /// Template parameters in the type of a conversion operator, which
+    /// refer to the arguments that come after it.
+    forward: Vec<Rc<Node>>,
+    /// Template parameters in the parameters of a lambda, which are its
+    /// `auto`s there, but which the arguments of the template after it
+    /// fill in if the parameters are used again as substitutions.
+    lambda_forward: Vec<Rc<Node>>,
+    /// Whether a reference's type is being read, where a substitution for
+    /// a template parameter keeps the argument it had as the type of the
+    /// first reference to it.
+    in_reference: bool,
+    /// The template parameters that have been the type of a reference, with
+    /// the arguments they had there.
+    reference_scopes: Vec<(Rc<Node>, Rc<Node>)>,
+    /// Whether a template parameter may refer to arguments still to come.
+    in_conversion: bool,
+    in_expression: bool,
+    /// Whether a lambda's parameters are being read, where template
+    /// parameters are the lambda's `auto`s.
+    in_lambda: bool,
+    /// The last source name read, which constructors and destructors are
+    /// named after.
+    last_name: Option<Rc<Node>>,
+    depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a [u8]) -> Parser<'a> {
+        Parser {
+            input,
+            pos: 0,
+            substitutions: vec![],
+            template_args: vec![],
+            tagging_templates: false,
+            forward: vec![],
+            lambda_forward: vec![],
+            in_reference: false,
+            reference_scopes: vec![],
+            in_conversion: false,
+            in_expression: false,
+            in_lambda: false,
+            last_name: None,
+            depth: 0,
+        }
+    }
+
+    fn peek(&self) -> u8 {
+        self.input.get(self.pos).copied().unwrap_or(0)
+    }
+
+    fn peek_next(&self) -> u8 {
+        self.input.get(self.pos + 1).copied().unwrap_or(0)
+    }
+
+    /// The bytes in `range` from here, or fewer at the end.
+    fn peek_at(&self, range: std::ops::Range<usize>) -> &'a [u8] {
+        let end = (self.pos + range.end).min(self.input.len());
+        let start = (self.pos + range.start).min(end);
+        &self.input[start..end]
+    }
+
+    fn advance(&mut self, count: usize) {
+        self.pos += count;
+    }
+
+    fn next(&mut self) -> Option<u8> {
+        let byte = *self.input.get(self.pos)?;
+        self.pos += 1;
+        Some(byte)
+    }
+
+    fn eat(&mut self, byte: u8) -> bool {
+        let matched = self.peek() == byte && byte != 0;
+        if matched {
+            self.pos += 1;
+        }
+        matched
+    }
+
+    fn expect(&mut self, byte: u8) -> Option<()> {
+        self.eat(byte).then_some(())
+    }
+
+    fn add_substitution(&mut self, node: &Rc<Node>) {
+        self.substitutions.push(node.clone());
+    }
+
+    /// Runs a production that may recurse, failing if they nest too deeply.
+    fn nested<T>(&mut self, production: impl FnOnce(&mut Self) -> Option<T>) -> Option<T> {
+        if self.depth >= MAX_DEPTH {
+            return None;
+        }
+        self.depth += 1;
+        let result = production(self);
+        self.depth -= 1;
+        result
+    }
+
+    /// Runs a production that builds a chain of nodes in a loop, each
+    /// wrapping the one before, as deeply as `nested` would nest them. Each
+    /// `link` counts toward the depth until the production is done.
+    fn chain<T>(&mut self, production: impl FnOnce(&mut Self) -> Option<T>) -> Option<T> {
+        let depth = self.depth;
+        let result = production(self);
+        self.depth = depth;
+        result
+    }
+
+    /// Adds a link to the chain being built, failing if it's too long.
+    fn link(&mut self) -> Option<()> {
+        if self.depth >= MAX_DEPTH {
+            return None;
+        }
+        self.depth += 1;
+        Some(())
+    }
+
+    // <mangled-name> ::= _Z <encoding> [<clone-suffix>]*
+    fn mangled_name(&mut self, top_level: bool) -> Option<Rc<Node>> {
+        // Encodings in template arguments have their own template
+        // parameters, and G++ once left out their `_`.
+        if !self.eat(b'_') && top_level {
+            return None;
+        }
+        self.expect(b'Z')?;
+        let template_args = std::mem::take(&mut self.template_args);
+        let mut node = self.encoding(top_level);
+        self.template_args = template_args;
+
+        if top_level {
+            node = self.chain(|parser| {
+                let mut node = node?;
+                while parser.peek() == b'.'
+                    && matches!(parser.peek_next(), b'a'..=b'z' | b'_' | b'0'..=b'9')
+                {
+                    parser.link()?;
+                    node = parser.clone_suffix(node);
+                }
+                Some(node)
+            });
+        }
+        node
+    }
+
+    fn clone_suffix(&mut self, encoding: Rc<Node>) -> Rc<Node> {
+        let start = self.pos;
+        self.advance(2);
+        while matches!(self.peek(), b'a'..=b'z' | b'_' | b'0'..=b'9') {
+            self.advance(1);
+        }
+        while self.peek() == b'.' && self.peek_next().is_ascii_digit() {
+            self.advance(2);
+            while self.peek().is_ascii_digit() {
+                self.advance(1);
+            }
+        }
+        let suffix = String::from_utf8_lossy(&self.input[start..self.pos]).into_owned();
+        Rc::new(Node::Clone(encoding, suffix))
+    }
+
+    // <encoding> ::= <name> <bare-function-type> | <name> | <special-name>
+    fn encoding(&mut self, top_level: bool) -> Option<Rc<Node>> {
+        self.nested(|parser| {
+            if matches!(parser.peek(), b'G' | b'T') {
+                return parser.special_name();
+            }
+
+            let tagging = std::mem::replace(&mut parser.tagging_templates, true);
+            let name = parser.name();
+            parser.tagging_templates = tagging;
+            let name = name?;
+            parser.resolve_forward()?;
+
+            if matches!(parser.peek(), 0 | b'E') {
+                return Some(name);
+            }
+            let Node::Function { ret, params, .. } =
+                &*parser.bare_function_type(has_return_type(&name))?
+            else {
+                unreachable!();
+            };
+
+            // A local name's return type is left out, so as not to be
+            // taken for that of what it's in.
+            let ret = match *name {
+                Node::Local(..) if !top_level => None,
+                _ => ret.clone(),
+            };
+            let (name, qualifiers) = take_member_qualifiers(&name);
+            let function = Rc::new(Node::Function {
+                ret,
+                params: params.clone(),
+                qualifiers,
+            });
+            Some(Rc::new(Node::Encoding(name, function)))
+        })
+    }
+
+    /// Fills in the template parameters read ahead of their arguments,
+    /// from the arguments read since.
+    fn resolve_forward(&mut self) -> Option<()> {
+        for node in std::mem::take(&mut self.forward) {
+            let Node::Forward(ref cell, index) = *node else {
+                unreachable!();
+            };
+            *cell.borrow_mut() = Some(self.template_args.get(index)?.clone());
+        }
+        for node in std::mem::take(&mut self.lambda_forward) {
+            let Node::Forward(ref cell, index) = *node else {
+                unreachable!();
+            };
+            *cell.borrow_mut() = self.template_args.get(index).cloned();
+        }
+        Some(())
+    }
+
+    fn special_name(&mut self) -> Option<Rc<Node>> {
+        let special = |prefix, node| Some(Rc::new(Node::Special(prefix, node)));
+        match (self.next()?, self.next()?) {
+            (b'T', b'V') => special("vtable for ", self.ty()?),
+            (b'T', b'T') => special("VTT for ", self.ty()?),
+            (b'T', b'I') => special("typeinfo for ", self.ty()?),
+            (b'T', b'S') => special("typeinfo name for ", self.ty()?),
+            (b'T', b'F') => special("typeinfo fn for ", self.ty()?),
+            (b'T', b'J') => special("java Class for ", self.ty()?),
+            (b'T', b'h') => {
+                self.call_offset(b'h')?;
+                special("non-virtual thunk to ", self.encoding(false)?)
+            }
+            (b'T', b'v') => {
+                self.call_offset(b'v')?;
+                special("virtual thunk to ", self.encoding(false)?)
+            }
+            (b'T', b'c') => {
+                let kind = self.next()?;
+                self.call_offset(kind)?;
+                let kind = self.next()?;
+                self.call_offset(kind)?;
+                special("covariant return thunk to ", self.encoding(false)?)
+            }
+            (b'T', b'C') => {
+                let derived = self.ty()?;
+                if self.number()? < 0 {
+                    return None;
+                }
+                self.expect(b'_')?;
+                let base = self.ty()?;
+                Some(Rc::new(Node::ConstructionVtable(base, derived)))
+            }
+            (b'T', b'H') => special("TLS init function for ", self.name()?),
+            (b'T', b'W') => special("TLS wrapper function for ", self.name()?),
+            (b'T', b'A') => special("template parameter object for ", self.template_arg()?),
+            (b'G', b'V') => special("guard variable for ", self.name()?),
+            (b'G', b'R') => {
+                let name = self.name()?;
+                let number = self.number()?;
+                Some(Rc::new(Node::ReferenceTemporary(name, number as u64)))
+            }
+            (b'G', b'A') => special("hidden alias for ", self.encoding(false)?),
+            (b'G', b'T') => match self.next()? {
+                b'n' => special("non-transaction clone for ", self.encoding(false)?),
+                _ => special("transaction clone for ", self.encoding(false)?),
+            },
+            _ => None,
+        }
+    }
+
+    // <call-offset> ::= h <nv-offset> _ | v <v-offset> _
+    fn call_offset(&mut self, kind: u8) -> Option<()> {
+        match kind {
+            b'h' => {
+                self.number()?;
+            }
+            b'v' => {
+                self.number()?;
+                self.expect(b'_')?;
+                self.number()?;
+            }
+            _ => return None,
+        }
+        self.expect(b'_')
+    }
+
+    // <name> ::= <nested-name> | <local-name> | <unscoped-template-name>
+    //            <template-args> | <unscoped-name>
+    fn name(&mut self) -> Option<Rc<Node>> {
+        self.nested(|parser| match parser.peek() {
+            b'N' => parser.nested_name(),
+            b'Z' => parser.local_name(),
+            b'U' => parser.unqualified_name(None),
+            _ => {
+                let mut scope = None;
+                let mut substituted = false;
+                if parser.peek() == b'S' {
+                    if parser.peek_next() == b't' {
+                        parser.advance(2);
+                        scope = Some(Rc::new(Node::Name("std".to_string())));
+                    }
+                    if parser.peek() == b'S' {
+                        if scope.is_some() {
+                            return None;
+                        }
+                        scope = Some(parser.substitution(false)?);
+                        substituted = true;
+                    }
+                }
+                let mut name = match (scope, substituted) {
+                    (Some(substitution), true) => substitution,
+                    (scope, _) => parser.unqualified_name(scope)?,
+                };
+                if parser.peek() == b'I' {
+                    // An <unscoped-template-name>, which is a candidate.
+                    if !substituted {
+                        parser.add_substitution(&name);
+                    }
+                    name = Rc::new(Node::Template(name, parser.template_args()?));
+                }
+                Some(name)
+            }
+        })
+    }
+
+    // <nested-name> ::= N [<CV-qualifiers>] [<ref-qualifier>] <prefix> E
+    fn nested_name(&mut self) -> Option<Rc<Node>> {
+        self.expect(b'N')?;
+        let mut qualifiers = self.cv_qualifiers()?;
+        qualifiers.ref_qualifier = self.ref_qualifier();
+        let prefix = self.prefix(true)?;
+        self.expect(b'E')?;
+        if qualifiers == FunctionQualifiers::default() {
+            Some(prefix)
+        } else {
+            Some(Rc::new(Node::MemberQualified(prefix, qualifiers)))
+        }
+    }
+
+    fn ref_qualifier(&mut self) -> Option<RefQualifier> {
+        if self.eat(b'R') {
+            Some(RefQualifier::LValue)
+        } else if self.eat(b'O') {
+            Some(RefQualifier::RValue)
+        } else {
+            None
+        }
+    }
+
+    // <prefix> ::= <prefix> <unqualified-name> | <template-prefix>
+    //              <template-args> | <template-param> | <decltype>
+    //              | <substitution>
+    // Each prefix up to the last name is a substitution candidate.
+    fn prefix(&mut self, candidates: bool) -> Option<Rc<Node>> {
+        self.chain(|parser| parser.prefix_names(candidates))
+    }
+
+    fn prefix_names(&mut self, candidates: bool) -> Option<Rc<Node>> {
+        let mut prefix: Option<Rc<Node>> = None;
+        loop {
+            let peek = self.peek();
+            let node = if peek == b'D' && matches!(self.peek_next(), b'T' | b't') {
+                if prefix.is_some() {
+                    return None;
+                }
+                self.ty()?
+            } else if peek == b'I' {
+                Rc::new(Node::Template(prefix.take()?, self.template_args()?))
+            } else if peek == b'T' {
+                if prefix.is_some() {
+                    return None;
+                }
+                self.template_param()?
+            } else if peek == b'M' {
+                // The scope of a lambda in an initializer, which is already
+                // a candidate.
+                self.advance(1);
+                continue;
+            } else if peek == b'S' {
+                let substitution = self.substitution(true)?;
+                if prefix.is_some() {
+                    return None;
+                }
+                prefix = Some(substitution);
+                continue;
+            } else {
+                self.unqualified_name(prefix.take())?
+            };
+
+            if self.peek() == b'E' {
+                return Some(node);
+            }
+            if candidates {
+                self.add_substitution(&node);
+            }
+            self.link()?;
+            prefix = Some(node);
+        }
+    }
+
+    // <unqualified-name> ::= <operator-name> [<abi-tags>] | <ctor-dtor-name>
+    //                        | <source-name> [<abi-tags>] | <unnamed-type-name>
+    //                        | DC <source-name>+ E
+    fn unqualified_name(&mut self, scope: Option<Rc<Node>>) -> Option<Rc<Node>> {
+        let peek = self.peek();
+        let mut name = if peek.is_ascii_digit() {
+            self.source_name()?
+        } else if peek.is_ascii_lowercase() {
+            let in_expression = self.in_expression;
+            if peek == b'o' && self.peek_next() == b'n' {
+                self.advance(2);
+                self.in_expression = false;
+            }
+            let operator = self.operator_name();
+            self.in_expression = in_expression;
+            operator?
+        } else if peek == b'D' && self.peek_next() == b'C' {
+            self.advance(2);
+            let mut names = vec![];
+            while !self.eat(b'E') {
+                names.push(self.source_name()?);
+            }
+            Rc::new(Node::StructuredBinding(names))
+        } else if peek == b'C' || peek == b'D' {
+            self.ctor_dtor_name()?
+        } else if peek == b'L' {
+            self.advance(1);
+            let name = self.source_name()?;
+            self.discriminator()?;
+            name
+        } else if peek == b'U' {
+            match self.peek_next() {
+                b'l' => self.lambda()?,
+                b't' => self.unnamed_type()?,
+                _ => return None,
+            }
+        } else {
+            return None;
+        };
+
+        if self.peek() == b'B' {
+            name = self.abi_tags(name)?;
+        }
+        Some(match scope {
+            Some(scope) => Rc::new(Node::Qualified(scope, name)),
+            None => name,
+        })
+    }
+
+    // <source-name> ::= <positive length number> <identifier>
+    fn source_name(&mut self) -> Option<Rc<Node>> {
+        let name = Rc::new(Node::Name(self.identifier()?));
+        self.last_name = Some(name.clone());
+        Some(name)
+    }
+
+    fn identifier(&mut self) -> Option<String> {
+        let length = self.number()?;
+        if length <= 0 {
+            return None;
+        }
+        let start = self.pos;
+        let end = start.checked_add(length as usize)?;
+        let bytes = self.input.get(start..end)?;
+        self.pos = end;
+
+        // GCC names anonymous namespaces `_GLOBAL__N_1`, and once with `.`
+        // or `$` in place of the second `_`.
+        if bytes.len() >= 10
+            && bytes.starts_with(b"_GLOBAL_")
+            && matches!(bytes[8], b'.' | b'_' | b'$')
+            && bytes[9] == b'N'
+        {
+            return Some("(anonymous namespace)".to_string());
+        }
+        std::str::from_utf8(bytes).ok().map(str::to_string)
+    }
+
+    // <number> ::= [n] <non-negative decimal integer>
+    fn number(&mut self) -> Option<i64> {
+        let negative = self.eat(b'n');
+        let mut value: i64 = 0;
+        while self.peek().is_ascii_digit() {
+            value = value
+                .checked_mul(10)?
+                .checked_add((self.peek() - b'0') as i64)?;
+            self.advance(1);
+        }
+        Some(if negative { -value } else { value })
+    }
+
+    /// `_` for 0, or a number and `_` for one more than it.
+    fn compact_number(&mut self) -> Option<u64> {
+        if self.eat(b'_') {
+            return Some(0);
+        }
+        let number = self.number()?;
+        self.expect(b'_')?;
+        u64::try_from(number).ok()?.checked_add(1)
+    }
+
+    // <discriminator> ::= _ <digit> | __ <number> _
+    fn discriminator(&mut self) -> Option<()> {
+        if !self.eat(b'_') {
+            return Some(());
+        }
+        let long = self.eat(b'_');
+        let number = self.number()?;
+        if number < 0 {
+            return None;
+        }
+        if long && number >= 10 {
+            self.expect(b'_')?;
+        }
+        Some(())
+    }
+
+    fn abi_tags(&mut self, mut name: Rc<Node>) -> Option<Rc<Node>> {
+        let last_name = self.last_name.clone();
+        let name = self.chain(|parser| {
+            while parser.eat(b'B') {
+                parser.link()?;
+                let tag = parser.identifier()?;
+                name = Rc::new(Node::AbiTag(name, tag));
+            }
+            Some(name)
+        })?;
+        self.last_name = last_name;
+        Some(name)
+    }
+
+    // <ctor-dtor-name> ::= C1 | C2 | C3 | C4 | C5 | CI1 <type> | CI2 <type>
+    //                    | D0 | D1 | D2 | D4 | D5
+    fn ctor_dtor_name(&mut self) -> Option<Rc<Node>> {
+        if self.eat(b'C') {
+            let inheriting = self.eat(b'I');
+            if !matches!(self.next()?, b'1'..=b'5') {
+                return None;
+            }
+            // An inheriting constructor is named after the base class it's
+            // inherited from, if that can be read.
+            if inheriting {
+                let pos = self.pos;
+                if self.ty().is_none() {
+                    self.pos = pos;
+                }
+            }
+            Some(Rc::new(Node::Constructor(self.last_name.clone()?)))
+        } else {
+            let name = self.last_name.clone()?;
+            self.expect(b'D')?;
+            if !matches!(self.next()?, b'0' | b'1' | b'2' | b'4' | b'5') {
+                return None;
+            }
+            Some(Rc::new(Node::Destructor(name)))
+        }
+    }
+
+    // <closure-type-name> ::= Ul <lambda-sig> E [<number>] _
+    fn lambda(&mut self) -> Option<Rc<Node>> {
+        self.advance(2);
+        let in_lambda = std::mem::replace(&mut self.in_lambda, true);
+        let params = self.parameters();
+        self.in_lambda = in_lambda;
+        let params = params?;
+        self.expect(b'E')?;
+        let number = self.compact_number()?;
+        Some(Rc::new(Node::Lambda(params, number)))
+    }
+
+    // <unnamed-type-name> ::= Ut [<number>] _
+    fn unnamed_type(&mut self) -> Option<Rc<Node>> {
+        self.advance(2);
+        let number = self.compact_number()?;
+        let unnamed = Rc::new(Node::UnnamedType(number));
+        self.add_substitution(&unnamed);
+        Some(unnamed)
+    }
+
+    // <local-name> ::= Z <encoding> E <entity name> [<discriminator>]
+    //              ::= Z <encoding> E s [<discriminator>]
+    //              ::= Z <encoding> Ed [<number>] _ <entity name>
+    fn local_name(&mut self) -> Option<Rc<Node>> {
+        self.expect(b'Z')?;
+        // The function has template parameters of its own, which aren't
+        // those of what it's in.
+        let template_args = std::mem::take(&mut self.template_args);
+        let tagging = std::mem::replace(&mut self.tagging_templates, false);
+        let function = self.encoding(false);
+        self.template_args = template_args;
+        self.tagging_templates = tagging;
+        let function = function?;
+        self.expect(b'E')?;
+
+        let entity = if self.eat(b's') {
+            self.discriminator()?;
+            Rc::new(Node::Name("string literal".to_string()))
+        } else {
+            let default_argument = match self.eat(b'd') {
+                true => Some(self.compact_number()?),
+                false => None,
+            };
+            let entity = self.name()?;
+            if !matches!(*entity, Node::Lambda(..) | Node::UnnamedType(..)) {
+                self.discriminator()?;
+            }
+            match default_argument {
+                Some(number) => Rc::new(Node::DefaultArgument(number, entity)),
+                None => entity,
+            }
+        };
+
+        // The function's return type is left out, so as not to be taken
+        // for that of what's in it.
+        let function = match &*function {
+            Node::Encoding(name, ty) => match &**ty {
+                Node::Function {
+                    ret: Some(_),
+                    params,
+                    qualifiers,
+                } => Rc::new(Node::Encoding(
+                    name.clone(),
+                    Rc::new(Node::Function {
+                        ret: None,
+                        params: params.clone(),
+                        qualifiers: qualifiers.clone(),
+                    }),
+                )),
+                _ => function,
+            },
+            _ => function,
+        };
+        Some(Rc::new(Node::Local(function, entity)))
+    }
+
+    // <template-args> ::= I <template-arg>+ E
+    fn template_args(&mut self) -> Option<Vec<Rc<Node>>> {
+        if !matches!(self.next()?, b'I' | b'J') {
+            return None;
+        }
+        // Names in the arguments aren't what a constructor after them is
+        // named after.
+        let last_name = self.last_name.clone();
+        let tagging = std::mem::replace(&mut self.tagging_templates, false);
+        if tagging {
+            self.template_args.clear();
+        }
+
+        let mut args = vec![];
+        while !self.eat(b'E') {
+            let arg = self.template_arg();
+            let arg = match arg {
+                Some(arg) => arg,
+                None => {
+                    self.tagging_templates = tagging;
+                    return None;
+                }
+            };
+            if tagging {
+                self.template_args.push(arg.clone());
+            }
+            args.push(arg);
+        }
+        self.tagging_templates = tagging;
+        if tagging {
+            self.resolve_forward()?;
+        }
+        self.last_name = last_name;
+        Some(args)
+    }
+
+    // <template-arg> ::= <type> | X <expression> E | <expr-primary>
+    //                  | J <template-arg>* E
+    fn template_arg(&mut self) -> Option<Rc<Node>> {
+        match self.peek() {
+            b'X' => {
+                self.advance(1);
+                let expression = self.expression()?;
+                self.expect(b'E')?;
+                Some(expression)
+            }
+            b'L' => self.expr_primary(),
+            b'I' | b'J' => Some(Rc::new(Node::Pack(self.nested(Self::template_args)?))),
+            _ => self.ty(),
+        }
+    }
+
+    // <template-param> ::= T_ | T <number> _
+    fn template_param(&mut self) -> Option<Rc<Node>> {
+        self.expect(b'T')?;
+        let index = self.compact_number()? as usize;
+        if self.in_lambda {
+            let node = Rc::new(Node::Forward(RefCell::new(None), index));
+            self.lambda_forward.push(node.clone());
+            return Some(node);
+        }
+        if self.in_conversion {
+            let node = Rc::new(Node::Forward(RefCell::new(None), index));
+            self.forward.push(node.clone());
+            return Some(node);
+        }
+        let arg = self.template_args.get(index)?;
+        Some(Rc::new(Node::Parameter(index, arg.clone())))
+    }
+
+    // <substitution> ::= S_ | S <seq-id> _ | St | Sa | Sb | Ss | Si | So | Sd
+    fn substitution(&mut self, in_prefix: bool) -> Option<Rc<Node>> {
+        self.expect(b'S')?;
+        let code = self.next()?;
+        if code == b'_' || code.is_ascii_digit() || code.is_ascii_uppercase() {
+            let mut id: usize = 0;
+            if code != b'_' {
+                let mut digit = code;
+                loop {
+                    let value = match digit {
+                        b'0'..=b'9' => digit - b'0',
+                        b'A'..=b'Z' => digit - b'A' + 10,
+                        _ => return None,
+                    };
+                    id = id.checked_mul(36)?.checked_add(value as usize)?;
+                    digit = self.next()?;
+                    if digit == b'_' {
+                        break;
+                    }
+                }
+                id += 1;
+            }
+            let node = self.substitutions.get(id)?.clone();
+            // A template parameter stands for an argument of the template
+            // it's used in, which needn't be the one it was read in, except
+            // as the type of a reference after the first.
+            let Node::Parameter(index, _) = &*node else {
+                return Some(node);
+            };
+            if self.in_reference {
+                let scope = self.reference_scopes.iter();
+                if let Some((_, resolved)) = scope.clone().find(|(p, _)| Rc::ptr_eq(p, &node)) {
+                    return Some(resolved.clone());
+                }
+            }
+            let resolved = match self.template_args.get(*index) {
+                Some(arg) => Rc::new(Node::Parameter(*index, arg.clone())),
+                None => node.clone(),
+            };
+            if self.in_reference {
+                self.reference_scopes.push((node, resolved.clone()));
+            }
+            return Some(resolved);
+        }
+
+        // A constructor or destructor of one of these is named after the
+        // full type, not its typedef.
+        let full = in_prefix && matches!(self.peek(), b'C' | b'D');
+        let (simple, expanded, last_name) = match code {
+            b't' => ("std", "std", None),
+            b'a' => ("std::allocator", "std::allocator", Some("allocator")),
+            b'b' => (
+                "std::basic_string",
+                "std::basic_string",
+                Some("basic_string"),
+            ),
+            b's' => (
+                "std::string",
+                "std::basic_string<char, std::char_traits<char>, std::allocator<char> >",
+                Some("basic_string"),
+            ),
+            b'i' => (
+                "std::istream",
+                "std::basic_istream<char, std::char_traits<char> >",
+                Some("basic_istream"),
+            ),
+            b'o' => (
+                "std::ostream",
+                "std::basic_ostream<char, std::char_traits<char> >",
+                Some("basic_ostream"),
+            ),
+            b'd' => (
+                "std::iostream",
+                "std::basic_iostream<char, std::char_traits<char> >",
+                Some("basic_iostream"),
+            ),
+            _ => return None,
+        };
+        if let Some(name) = last_name {
+            self.last_name = Some(Rc::new(Node::Name(name.to_string())));
+        }
+        let mut node = Rc::new(Node::Standard(if full { expanded } else { simple }));
+        if self.peek() == b'B' {
+            // With ABI tags, it becomes a candidate.
+            node = self.abi_tags(node)?;
+            self.add_substitution(&node);
+        }
+        Some(node)
+    }
+
+    // <type>
+    fn ty(&mut self) -> Option<Rc<Node>> {
+        self.nested(Self::ty_inner)
+    }
+
+    fn ty_inner(&mut self) -> Option<Rc<Node>> {
+        let in_reference = std::mem::take(&mut self.in_reference);
+        if self.at_qualifier() {
+            let mut qualifiers = self.cv_qualifiers()?;
+            let node = if self.peek() == b'F' {
+                // They apply to `this`, and the unqualified function type
+                // isn't a candidate.
+                let function = self.function_type()?;
+                let Node::Function {
+                    ret,
+                    params,
+                    qualifiers: inner,
+                } = &*function
+                else {
+                    unreachable!();
+                };
+                qualifiers.ref_qualifier = inner.ref_qualifier;
+                Rc::new(Node::Function {
+                    ret: ret.clone(),
+                    params: params.clone(),
+                    qualifiers,
+                })
+            } else {
+                if qualifiers.exceptions.is_some() || qualifiers.transaction_safe {
+                    return None;
+                }
+                Rc::new(Node::CvQualified(self.ty()?, qualifiers.cv))
+            };
+            self.add_substitution(&node);
+            return Some(node);
+        }
+
+        let peek = self.peek();
+        if let Some(name) = builtin_type(peek) {
+            self.advance(1);
+            return Some(Rc::new(Node::Builtin(name)));
+        }
+        let node = match peek {
+            b'u' => {
+                self.advance(1);
+                let name = self.source_name()?;
+                match self.peek() {
+                    b'I' => Rc::new(Node::Template(name, self.template_args()?)),
+                    _ => name,
+                }
+            }
+            b'F' => self.function_type()?,
+            b'0'..=b'9' | b'N' | b'Z' => self.name()?,
+            b'A' => self.array_type()?,
+            b'M' => {
+                self.advance(1);
+                let class = self.ty()?;
+                let member = self.ty()?;
+                Rc::new(Node::PointerToMember(class, member))
+            }
+            b'T' => {
+                let param = self.template_param()?;
+                if self.peek() != b'I' {
+                    param
+                } else if !self.in_conversion {
+                    // A <template-template-param> with its arguments.
+                    self.add_substitution(&param);
+                    Rc::new(Node::Template(param, self.template_args()?))
+                } else {
+                    // In a conversion operator's type, the arguments may be
+                    // the operator's own instead, unless more follow them.
+                    let (pos, substitutions) = (self.pos, self.substitutions.len());
+                    let args = self.template_args()?;
+                    if self.peek() == b'I' {
+                        self.add_substitution(&param);
+                        Rc::new(Node::Template(param, args))
+                    } else {
+                        self.pos = pos;
+                        self.substitutions.truncate(substitutions);
+                        param
+                    }
+                }
+            }
+            b'P' => {
+                self.advance(1);
+                Rc::new(Node::Pointer(self.ty()?))
+            }
+            b'R' => {
+                self.advance(1);
+                Rc::new(Node::LValueReference(self.referent()?))
+            }
+            b'O' => {
+                self.advance(1);
+                Rc::new(Node::RValueReference(self.referent()?))
+            }
+            b'C' => {
+                self.advance(1);
+                Rc::new(Node::Complex(self.ty()?, " _Complex"))
+            }
+            b'G' => {
+                self.advance(1);
+                Rc::new(Node::Complex(self.ty()?, " _Imaginary"))
+            }
+            b'U' => {
+                self.advance(1);
+                let mut qualifier = self.source_name()?;
+                if self.peek() == b'I' {
+                    qualifier = Rc::new(Node::Template(qualifier, self.template_args()?));
+                }
+                Rc::new(Node::VendorQualified(self.ty()?, qualifier))
+            }
+            b'S' => {
+                if matches!(self.peek_next(), b'0'..=b'9' | b'_' | b'A'..=b'Z') {
+                    self.in_reference = in_reference;
+                    let substitution = self.substitution(false);
+                    self.in_reference = false;
+                    let substitution = substitution?;
+                    // A template name's substitution may be followed by its
+                    // arguments, which makes a new candidate; otherwise it's
+                    // not one.
+                    if self.peek() != b'I' {
+                        return Some(substitution);
+                    }
+                    Rc::new(Node::Template(substitution, self.template_args()?))
+                } else {
+                    let name = self.name()?;
+                    if let Node::Standard(_) = *name {
+                        return Some(name);
+                    }
+                    name
+                }
+            }
+            b'D' => {
+                self.advance(1);
+                match self.next()? {
+                    b'T' | b't' => {
+                        let expression = self.expression()?;
+                        self.expect(b'E')?;
+                        Rc::new(Node::Decltype(expression))
+                    }
+                    b'p' => Rc::new(Node::PackExpansion(self.ty()?)),
+                    b'v' => {
+                        let dimension = if self.eat(b'_') {
+                            self.expression()?
+                        } else {
+                            Rc::new(Node::Name(self.number()?.to_string()))
+                        };
+                        self.expect(b'_')?;
+                        Rc::new(Node::Vector(self.ty()?, dimension))
+                    }
+                    code => return self.extended_builtin_type(code),
+                }
+            }
+            _ => return None,
+        };
+        self.add_substitution(&node);
+        Some(node)
+    }
+
+    /// The built-in types that start with `D`, after it, which aren't
+    /// substitution candidates.
+    fn extended_builtin_type(&mut self, code: u8) -> Option<Rc<Node>> {
+        let name = match code {
+            b'a' => return Some(Rc::new(Node::Name("auto".to_string()))),
+            b'c' => return Some(Rc::new(Node::Name("decltype(auto)".to_string()))),
+            b'f' => "decimal32",
+            b'd' => "decimal64",
+            b'e' => "decimal128",
+            b'h' => "half",
+            b'u' => "char8_t",
+            b's' => "char16_t",
+            b'i' => "char32_t",
+            b'n' => "decltype(nullptr)",
+            b'F' => {
+                // _Float<N>, _Float<N>x, and std::bfloat16_t.
+                let bits = self.number()?;
+                let name = if self.eat(b'x') {
+                    format!("_Float{bits}x")
+                } else if bits == 16 && self.eat(b'b') {
+                    "std::bfloat16_t".to_string()
+                } else {
+                    self.expect(b'_')?;
+                    format!("_Float{bits}")
+                };
+                return Some(Rc::new(Node::Name(name)));
+            }
+            _ => return None,
+        };
+        Some(Rc::new(Node::Builtin(name)))
+    }
+
+    /// Whether a type's cv-qualifiers (or a function type's exception
+    /// specification) come next.
+    fn at_qualifier(&self) -> bool {
+        match self.peek() {
+            b'r' | b'V' | b'K' => true,
+            b'D' => matches!(self.peek_next(), b'x' | b'o' | b'O' | b'w'),
+            _ => false,
+        }
+    }
+
+    // <CV-qualifiers> ::= [r] [V] [K], and for function types,
+    // [<exception-spec>] [Dx]
+    fn cv_qualifiers(&mut self) -> Option<FunctionQualifiers> {
+        let mut qualifiers = FunctionQualifiers::default();
+        while self.at_qualifier() {
+            match self.next()? {
+                b'r' => qualifiers.cv.is_restrict = true,
+                b'V' => qualifiers.cv.is_volatile = true,
+                b'K' => qualifiers.cv.is_const = true,
+                _ => match self.next()? {
+                    b'x' => qualifiers.transaction_safe = true,
+                    b'o' => qualifiers.exceptions = Some(Exceptions::Noexcept(None)),
+                    b'O' => {
+                        let condition = self.expression()?;
+                        self.expect(b'E')?;
+                        qualifiers.exceptions = Some(Exceptions::Noexcept(Some(condition)));
+                    }
+                    _ => {
+                        let types = self.parameters()?;
+                        self.expect(b'E')?;
+                        qualifiers.exceptions = Some(Exceptions::Throw(types));
+                    }
+                },
+            }
+        }
+        Some(qualifiers)
+    }
+
+    /// The type of a reference.
+    fn referent(&mut self) -> Option<Rc<Node>> {
+        self.in_reference = true;
+        let ty = self.ty();
+        self.in_reference = false;
+        if let Some(ty) = &ty {
+            let known = self.reference_scopes.iter().any(|(p, _)| Rc::ptr_eq(p, ty));
+            if matches!(**ty, Node::Parameter(..)) && !known {
+                self.reference_scopes.push((ty.clone(), ty.clone()));
+            }
+        }
+        ty
+    }
+
+    // <function-type> ::= F [Y] <bare-function-type> [<ref-qualifier>] E
+    fn function_type(&mut self) -> Option<Rc<Node>> {
+        self.expect(b'F')?;
+        // extern "C", which isn't shown.
+        self.eat(b'Y');
+        let function = self.bare_function_type(true)?;
+        let ref_qualifier = self.ref_qualifier();
+        self.expect(b'E')?;
+        let Node::Function { ret, params, .. } = &*function else {
+            unreachable!();
+        };
+        Some(Rc::new(Node::Function {
+            ret: ret.clone(),
+            params: params.clone(),
+            qualifiers: FunctionQualifiers {
+                ref_qualifier,
+                ..FunctionQualifiers::default()
+            },
+        }))
+    }
+
+    // <bare-function-type> ::= [J] <signature type>+
+    fn bare_function_type(&mut self, mut has_return_type: bool) -> Option<Rc<Node>> {
+        if self.eat(b'J') {
+            has_return_type = true;
+        }
+        let ret = match has_return_type {
+            true => Some(self.ty()?),
+            false => None,
+        };
+        let params = self.parameters()?;
+        Some(Rc::new(Node::Function {
+            ret,
+            params,
+            qualifiers: FunctionQualifiers::default(),
+        }))
+    }
+
+    /// Parameter types, up to the end of the function type; there must be
+    /// at least one, which is `void` for none.
+    fn parameters(&mut self) -> Option<Vec<Rc<Node>>> {
+        let mut params = vec![];
+        loop {
+            match self.peek() {
+                0 | b'E' | b'.' => break,
+                b'R' | b'O' if self.peek_next() == b'E' => break,
+                _ => params.push(self.ty()?),
+            }
+        }
+        match params.as_slice() {
+            [] => None,
+            [only] if **only == Node::Builtin("void") => Some(vec![]),
+            _ => Some(params),
+        }
+    }
+
+    // <array-type> ::= A <positive dimension number> _ <element type>
+    //              ::= A [<dimension expression>] _ <element type>
+    fn array_type(&mut self) -> Option<Rc<Node>> {
+        self.expect(b'A')?;
+        let dimension = match self.peek() {
+            b'_' => None,
+            b'0'..=b'9' => {
+                let start = self.pos;
+                while self.peek().is_ascii_digit() {
+                    self.advance(1);
+                }
+                let digits = std::str::from_utf8(&self.input[start..self.pos]).ok()?;
+                Some(Rc::new(Node::Name(digits.to_string())))
+            }
+            _ => Some(self.expression()?),
+        };
+        self.expect(b'_')?;
+        Some(Rc::new(Node::Array(self.ty()?, dimension)))
+    }
+
+    // <operator-name>, as a name.
+    fn operator_name(&mut self) -> Option<Rc<Node>> {
+        let code = self.peek_at(0..2);
+        if code.len() < 2 {
+            return None;
+        }
+        self.advance(2);
+        if code[0] == b'v' && code[1].is_ascii_digit() {
+            return Some(Rc::new(Node::VendorOperator(self.source_name()?)));
+        }
+        if code == b"cv" {
+            let in_conversion = std::mem::replace(&mut self.in_conversion, !self.in_expression);
+            let ty = self.ty();
+            self.in_conversion = in_conversion;
+            return Some(Rc::new(Node::Conversion(ty?)));
+        }
+        let operator = find_operator(code)?;
+        if operator.code == "li" {
+            return Some(Rc::new(Node::LiteralOperator(self.source_name()?)));
+        }
+        Some(Rc::new(Node::Operator(operator)))
+    }
+
+    // <expression>
+    fn expression(&mut self) -> Option<Rc<Node>> {
+        self.nested(|parser| {
+            let in_expression = std::mem::replace(&mut parser.in_expression, true);
+            let expression = parser.expression_inner();
+            parser.in_expression = in_expression;
+            expression
+        })
+    }
+
+    fn expression_inner(&mut self) -> Option<Rc<Node>> {
+        let (peek, next) = (self.peek(), self.peek_next());
+        match (peek, next) {
+            (b'L', _) => return self.expr_primary(),
+            (b'T', _) => return self.template_param(),
+            (b's', b'r') => return self.unresolved_name(),
+            (b's', b'p') => {
+                self.advance(2);
+                return Some(Rc::new(Node::PackExpansion(self.expression()?)));
+            }
+            (b'f', b'p') => {
+                self.advance(2);
+                if self.eat(b'T') {
+                    return Some(Rc::new(Node::FunctionParameter(0)));
+                }
+                while matches!(self.peek(), b'r' | b'V' | b'K') {
+                    self.advance(1);
+                }
+                let index = self.compact_number()?;
+                return Some(Rc::new(Node::FunctionParameter(index + 1)));
+            }
+            (b'0'..=b'9', _) | (b'o', b'n') => {
+                // A dependent name, as in decltype(f(t)), or an operator's,
+                // as in operator+(t).
+                if peek == b'o' {
+                    self.advance(2);
+                }
+                let name = self.unqualified_name(None)?;
+                return match self.peek() {
+                    b'I' => Some(Rc::new(Node::Template(name, self.template_args()?))),
+                    _ => Some(name),
+                };
+            }
+            (b'i' | b't', b'l') => {
+                self.advance(2);
+                let ty = match peek {
+                    b't' => Some(self.ty()?),
+                    _ => None,
+                };
+                return Some(Rc::new(Node::InitializerList(ty, self.expressions()?)));
+            }
+            (b'u', _) => {
+                // A vendor's extended expression: its name, and arguments.
+                self.advance(1);
+                let name = self.source_name()?;
+                let mut args = vec![];
+                while !self.eat(b'E') {
+                    args.push(self.template_arg()?);
+                }
+                return Some(Rc::new(Node::Call(name, args)));
+            }
+            (b'c', b'v') => {
+                self.advance(2);
+                let in_conversion = std::mem::replace(&mut self.in_conversion, false);
+                let ty = self.ty();
+                self.in_conversion = in_conversion;
+                let ty = ty?;
+                let operand = match self.eat(b'_') {
+                    true => Rc::new(Node::ExpressionList(self.expressions()?)),
+                    false => self.expression()?,
+                };
+                return Some(Rc::new(Node::Cast(ty, operand)));
+            }
+            _ => {}
+        }
+
+        let operator = find_operator(self.peek_at(0..2))?;
+        self.advance(2);
+        let node = match (operator.arity, operator.code) {
+            (_, "st") => Node::Unary(operator, self.ty()?),
+            (0, _) => Node::Nullary(operator),
+            (1, "pp" | "mm") => {
+                // `_` marks the prefix form.
+                let postfix = !self.eat(b'_');
+                let operand = self.expression()?;
+                match postfix {
+                    true => Node::Postfix(operator, operand),
+                    false => Node::Unary(operator, operand),
+                }
+            }
+            (1, "sP") => {
+                let mut args = vec![];
+                while !self.eat(b'E') {
+                    args.push(self.template_arg()?);
+                }
+                Node::SizeofPack(Rc::new(Node::Pack(args)))
+            }
+            (1, "sZ") => Node::SizeofPack(self.expression()?),
+            (1, _) => Node::Unary(operator, self.expression()?),
+            (2, "dc" | "sc" | "cc" | "rc") => {
+                let ty = self.ty()?;
+                Node::NamedCast(operator, ty, self.expression()?)
+            }
+            (2, "fl" | "fr") => {
+                let fold = find_operator(self.peek_at(0..2))?;
+                self.advance(2);
+                Node::Fold(operator.code, fold, vec![self.expression()?])
+            }
+            (2, "cl") => {
+                let function = self.expression()?;
+                Node::Call(function, self.expressions()?)
+            }
+            (2, "di") => {
+                let member = self.unqualified_name(None)?;
+                Node::Binary(operator, member, self.expression()?)
+            }
+            (2, "dt" | "pt") => {
+                let object = self.expression()?;
+                let mut member = self.unqualified_name(None)?;
+                if self.peek() == b'I' {
+                    member = Rc::new(Node::Template(member, self.template_args()?));
+                }
+                Node::Binary(operator, object, member)
+            }
+            (2, _) => {
+                let left = self.expression()?;
+                Node::Binary(operator, left, self.expression()?)
+            }
+            (3, "qu") => {
+                let condition = self.expression()?;
+                let then = self.expression()?;
+                Node::Trinary(operator, condition, then, self.expression()?)
+            }
+            (3, "fL" | "fR") => {
+                let fold = find_operator(self.peek_at(0..2))?;
+                self.advance(2);
+                let first = self.expression()?;
+                Node::Fold(operator.code, fold, vec![first, self.expression()?])
+            }
+            (3, "nw" | "na") => {
+                let mut placement = vec![];
+                while !self.eat(b'_') {
+                    placement.push(self.expression()?);
+                }
+                let ty = self.ty()?;
+                let initializer = if self.eat(b'E') {
+                    None
+                } else if self.peek_at(0..2) == b"pi" {
+                    // The initializer's `E` ends the whole expression.
+                    self.advance(2);
+                    Some(Rc::new(Node::ExpressionList(self.expressions()?)))
+                } else if self.peek_at(0..2) == b"il" {
+                    Some(self.expression()?)
+                } else {
+                    return None;
+                };
+                Node::New {
+                    placement,
+                    ty,
+                    initializer,
+                }
+            }
+            _ => return None,
+        };
+        Some(Rc::new(node))
+    }
+
+    /// Expressions up to an `E`.
+    fn expressions(&mut self) -> Option<Vec<Rc<Node>>> {
+        let mut expressions = vec![];
+        while !self.eat(b'E') {
+            expressions.push(self.expression()?);
+        }
+        Some(expressions)
+    }
+
+    // <unresolved-name>, after `sr`: a scope then a name in it.
+    fn unresolved_name(&mut self) -> Option<Rc<Node>> {
+        self.advance(2);
+        let scope = if matches!(self.peek(), b'0'..=b'9' | b'a'..=b'z' | b'C' | b'U' | b'L') {
+            let scope = self.prefix(false)?;
+            self.eat(b'E');
+            scope
+        } else {
+            self.ty()?
+        };
+        let name = self.unqualified_name(Some(scope))?;
+        match self.peek() {
+            b'I' => Some(Rc::new(Node::Template(name, self.template_args()?))),
+            _ => Some(name),
+        }
+    }
+
+    // <expr-primary> ::= L <type> <value number> E | L <mangled-name> E
+    fn expr_primary(&mut self) -> Option<Rc<Node>> {
+        self.expect(b'L')?;
+        let node = if matches!(self.peek(), b'_' | b'Z') {
+            self.mangled_name(false)?
+        } else {
+            let ty = self.ty()?;
+            if *ty == Node::Builtin("decltype(nullptr)") && self.eat(b'E') {
+                return Some(ty);
+            }
+            let negative = self.eat(b'n');
+            let start = self.pos;
+            while self.peek() != b'E' {
+                self.next()?;
+            }
+            if self.pos == start {
+                return None;
+            }
+            let value = std::str::from_utf8(&self.input[start..self.pos]).ok()?;
+            Rc::new(Node::Literal(ty, value.to_string(), negative))
+        };
+        self.expect(b'E')?;
+        Some(node)
+    }
+}
+
+/// Whether a function's encoding has its return type: a template's does,
+/// unless it's a constructor, destructor or conversion operator.
+fn has_return_type(name: &Node) -> bool {
+    match name {
+        Node::Local(_, entity) => has_return_type(entity),
+        Node::Template(name, _) => !is_ctor_dtor_or_conversion(name),
+        Node::MemberQualified(name, _) => has_return_type(name),
+        _ => false,
+    }
+}
+
+fn is_ctor_dtor_or_conversion(name: &Node) -> bool {
+    match name {
+        Node::Qualified(_, name) | Node::Local(_, name) => is_ctor_dtor_or_conversion(name),
+        Node::Constructor(_) | Node::Destructor(_) | Node::Conversion(_) => true,
+        _ => false,
+    }
+}
+
+/// Takes the qualifiers of a member function off its name (or that of a
+/// local class's member function), for after its parameters.
+fn take_member_qualifiers(name: &Rc<Node>) -> (Rc<Node>, FunctionQualifiers) {
+    match &**name {
+        Node::MemberQualified(name, qualifiers) => (name.clone(), qualifiers.clone()),
+        Node::Local(function, entity) => match &**entity {
+            Node::MemberQualified(entity, qualifiers) => (
+                Rc::new(Node::Local(function.clone(), entity.clone())),
+                qualifiers.clone(),
+            ),
+            _ => (name.clone(), FunctionQualifiers::default()),
+        },
+        _ => (name.clone(), FunctionQualifiers::default()),
+    }
+}
+
+/// The name of a built-in type, by its code.
+fn builtin_type(code: u8) -> Option<&'static str> {
+    Some(match code {
+        b'a' => "signed char",
+        b'b' => "bool",
+        b'c' => "char",
+        b'd' => "double",
+        b'e' => "long double",
+        b'f' => "float",
+        b'g' => "__float128",
+        b'h' => "unsigned char",
+        b'i' => "int",
+        b'j' => "unsigned int",
+        b'l' => "long",
+        b'm' => "unsigned long",
+        b'n' => "__int128",
+        b'o' => "unsigned __int128",
+        b's' => "short",
+        b't' => "unsigned short",
+        b'v' => "void",
+        b'w' => "wchar_t",
+        b'x' => "long long",
+        b'y' => "unsigned long long",
+        b'z' => "...",
+        _ => return None,
+    })
+}
+
+const fn operator(code: &'static str, name: &'static str, arity: u8) -> Operator {
+    Operator { code, name, arity }
+}
+
+/// The operators, by their codes, in order.
+const OPERATORS: &[Operator] = &[
+    operator("aN", "&=", 2),
+    operator("aS", "=", 2),
+    operator("aa", "&&", 2),
+    operator("ad", "&", 1),
+    operator("an", "&", 2),
+    operator("at", "alignof ", 1),
+    operator("aw", "co_await ", 1),
+    operator("az", "alignof ", 1),
+    operator("cc", "const_cast", 2),
+    operator("cl", "()", 2),
+    operator("cm", ",", 2),
+    operator("co", "~", 1),
+    operator("dV", "/=", 2),
+    operator("dX", "[...]=", 3),
+    operator("da", "delete[] ", 1),
+    operator("dc", "dynamic_cast", 2),
+    operator("de", "*", 1),
+    operator("di", "=", 2),
+    operator("dl", "delete ", 1),
+    operator("ds", ".*", 2),
+    operator("dt", ".", 2),
+    operator("dv", "/", 2),
+    operator("dx", "]=", 2),
+    operator("eO", "^=", 2),
+    operator("eo", "^", 2),
+    operator("eq", "==", 2),
+    operator("fL", "...", 3),
+    operator("fR", "...", 3),
+    operator("fl", "...", 2),
+    operator("fr", "...", 2),
+    operator("ge", ">=", 2),
+    operator("gs", "::", 1),
+    operator("gt", ">", 2),
+    operator("ix", "[]", 2),
+    operator("lS", "<<=", 2),
+    operator("le", "<=", 2),
+    operator("li", "operator\"\" ", 1),
+    operator("ls", "<<", 2),
+    operator("lt", "<", 2),
+    operator("mI", "-=", 2),
+    operator("mL", "*=", 2),
+    operator("mi", "-", 2),
+    operator("ml", "*", 2),
+    operator("mm", "--", 1),
+    operator("na", "new[]", 3),
+    operator("ne", "!=", 2),
+    operator("ng", "-", 1),
+    operator("nt", "!", 1),
+    operator("nw", "new", 3),
+    operator("oR", "|=", 2),
+    operator("oo", "||", 2),
+    operator("or", "|", 2),
+    operator("pL", "+=", 2),
+    operator("pl", "+", 2),
+    operator("pm", "->*", 2),
+    operator("pp", "++", 1),
+    operator("ps", "+", 1),
+    operator("pt", "->", 2),
+    operator("qu", "?", 3),
+    operator("rM", "%=", 2),
+    operator("rS", ">>=", 2),
+    operator("rc", "reinterpret_cast", 2),
+    operator("rm", "%", 2),
+    operator("rs", ">>", 2),
+    operator("sP", "sizeof...", 1),
+    operator("sZ", "sizeof...", 1),
+    operator("sc", "static_cast", 2),
+    operator("ss", "<=>", 2),
+    operator("st", "sizeof ", 1),
+    operator("sz", "sizeof ", 1),
+    operator("tr", "throw", 0),
+    operator("tw", "throw ", 1),
+];
+
+fn find_operator(code: &[u8]) -> Option<Operator> {
+    OPERATORS
+        .binary_search_by(|operator| operator.code.as_bytes().cmp(code))
+        .ok()
+        .map(|index| OPERATORS[index])
+}
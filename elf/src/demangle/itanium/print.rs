@@ -0,0 +1,785 @@
+/// Writing out a parsed name as C++. A type is written in two parts, one
+/// on either side of what it's the type of: `int (*)[3]` is `int (*` and
+/// `)[3]`, and a function returning one is written between them. Spacing
+/// and parentheses follow the GNU demangler, so names come out as `nm -C`
+/// and `objdump -C` show them.
+///
+use super::{Exceptions, FunctionQualifiers, Node, Qualifiers, RefQualifier};
+use std::rc::Rc;
+
+/// How deep the tree is followed, and how long a name is allowed to get,
+/// before giving up on it. Packs and template arguments that refer back to
+/// each other can make a short mangling print as a very long name.
+const MAX_DEPTH: usize = 512;
+const MAX_LEN: usize = 256 * 1024;
+
+pub fn print(node: &Rc<Node>) -> Option<String> {
+    let mut printer = Printer {
+        out: String::new(),
+        last: None,
+        in_lambda: false,
+        pack_index: None,
+        depth: 0,
+        failed: false,
+    };
+    printer.node(node);
+    (!printer.failed).then_some(printer.out)
+}
+
+struct Printer {
+    out: String,
+    /// The last character written. Taking back the comma before an empty
+    /// pack leaves this as the comma's space, as it does in the GNU
+    /// demangler, which matters for whether `>>` gets a space.
+    last: Option<char>,
+    /// Whether a lambda's parameters are being written, where template
+    /// parameters are written as `auto:1` and so on.
+    in_lambda: bool,
+    /// Which element of the packs a pack expansion is being written for.
+    pack_index: Option<usize>,
+    depth: usize,
+    failed: bool,
+}
+
+impl Printer {
+    fn push(&mut self, s: &str) {
+        self.out.push_str(s);
+        self.last = s.chars().next_back().or(self.last);
+    }
+
+    fn last_char(&self) -> Option<char> {
+        self.last
+    }
+
+    /// Writes the whole of a node.
+    fn node(&mut self, node: &Rc<Node>) {
+        self.left(node);
+        self.right(node);
+    }
+
+    /// What a template parameter stands for: its argument, or if that's a
+    /// pack, the element that a pack expansion is being written for (or
+    /// the first, outside of one).
+    fn resolve(&mut self, node: &Rc<Node>) -> Rc<Node> {
+        let mut node = node.clone();
+        if self.in_lambda {
+            return node;
+        }
+        loop {
+            let arg = match &*node {
+                Node::Parameter(_, arg) => arg.clone(),
+                Node::Forward(cell, _) => match &*cell.borrow() {
+                    Some(arg) => arg.clone(),
+                    None => {
+                        self.failed = true;
+                        return node.clone();
+                    }
+                },
+                _ => return node,
+            };
+            node = match &*arg {
+                Node::Pack(args) => match args.get(self.pack_index.unwrap_or(0)) {
+                    Some(arg) => arg.clone(),
+                    None => {
+                        self.failed = true;
+                        return node;
+                    }
+                },
+                _ => arg,
+            };
+        }
+    }
+
+    fn left(&mut self, node: &Rc<Node>) {
+        if self.failed || self.depth >= MAX_DEPTH || self.out.len() > MAX_LEN {
+            self.failed = true;
+            return;
+        }
+        self.depth += 1;
+        self.left_inner(node);
+        self.depth -= 1;
+    }
+
+    fn right(&mut self, node: &Rc<Node>) {
+        if self.failed || self.depth >= MAX_DEPTH {
+            self.failed = true;
+            return;
+        }
+        self.depth += 1;
+        self.right_inner(node);
+        self.depth -= 1;
+    }
+
+    fn left_inner(&mut self, node: &Rc<Node>) {
+        match &**node {
+            Node::Parameter(index, _) | Node::Forward(_, index) if self.in_lambda => {
+                self.push(&format!("auto:{}", index + 1))
+            }
+            Node::Parameter(..) | Node::Forward(..) => {
+                let arg = self.resolve(node);
+                self.left(&arg);
+            }
+            Node::Name(name) => self.push(name),
+            Node::Standard(name) | Node::Builtin(name) => self.push(name),
+            Node::Qualified(scope, name) => {
+                self.node(scope);
+                self.push("::");
+                self.node(name);
+            }
+            Node::Template(name, args) => {
+                self.node(name);
+                if self.last_char() == Some('<') {
+                    self.push(" ");
+                }
+                self.push("<");
+                self.list(args);
+                if self.last_char() == Some('>') {
+                    self.push(" ");
+                }
+                self.push(">");
+            }
+            Node::AbiTag(name, tag) => {
+                self.node(name);
+                self.push("[abi:");
+                self.push(tag);
+                self.push("]");
+            }
+            Node::Operator(operator) => {
+                self.push("operator");
+                if operator.name.starts_with(|c: char| c.is_ascii_lowercase()) {
+                    self.push(" ");
+                }
+                self.push(operator.name.trim_end_matches(' '));
+            }
+            Node::LiteralOperator(name) => {
+                self.push("operator\"\" ");
+                self.node(name);
+            }
+            Node::VendorOperator(name) | Node::Conversion(name) => {
+                self.push("operator ");
+                self.node(name);
+            }
+            Node::Constructor(name) => self.node(name),
+            Node::Destructor(name) => {
+                self.push("~");
+                self.node(name);
+            }
+            Node::Lambda(params, index) => {
+                self.push("{lambda(");
+                let in_lambda = std::mem::replace(&mut self.in_lambda, true);
+                self.list(params);
+                self.in_lambda = in_lambda;
+                self.push(&format!(")#{}}}", index + 1));
+            }
+            Node::UnnamedType(index) => self.push(&format!("{{unnamed type#{}}}", index + 1)),
+            Node::StructuredBinding(names) => {
+                self.push("[");
+                self.list(names);
+                self.push("]");
+            }
+            Node::Local(function, entity) => {
+                self.node(function);
+                self.push("::");
+                self.node(entity);
+            }
+            Node::DefaultArgument(index, entity) => {
+                self.push(&format!("{{default arg#{}}}::", index + 1));
+                self.node(entity);
+            }
+            Node::MemberQualified(name, qualifiers) => {
+                self.node(name);
+                self.function_qualifiers(qualifiers);
+            }
+            Node::Encoding(name, function) => self.encoding(name, function),
+            Node::Clone(encoding, suffix) => {
+                self.node(encoding);
+                self.push(" [clone ");
+                self.push(suffix);
+                self.push("]");
+            }
+            Node::Special(prefix, name) => {
+                self.push(prefix);
+                self.node(name);
+            }
+            Node::ConstructionVtable(base, derived) => {
+                self.push("construction vtable for ");
+                self.node(base);
+                self.push("-in-");
+                self.node(derived);
+            }
+            Node::ReferenceTemporary(name, index) => {
+                self.push(&format!("reference temporary #{index} for "));
+                self.node(name);
+            }
+
+            Node::VendorQualified(ty, qualifier) => {
+                self.left(ty);
+                self.push(" ");
+                self.node(qualifier);
+            }
+            Node::CvQualified(ty, qualifiers) => {
+                // A qualifier on a parameter that has it already is only
+                // written once.
+                match &*self.resolve(ty) {
+                    Node::CvQualified(inner, inner_qualifiers)
+                        if matches!(**ty, Node::Parameter(..) | Node::Forward(..)) =>
+                    {
+                        self.left(inner);
+                        self.qualifiers(Qualifiers {
+                            is_const: inner_qualifiers.is_const && !qualifiers.is_const,
+                            is_volatile: inner_qualifiers.is_volatile && !qualifiers.is_volatile,
+                            is_restrict: inner_qualifiers.is_restrict && !qualifiers.is_restrict,
+                        });
+                    }
+                    _ => self.left(ty),
+                }
+                self.qualifiers(*qualifiers);
+            }
+            Node::Pointer(pointee) => self.pointer_left(pointee, "*"),
+            Node::LValueReference(referent) | Node::RValueReference(referent) => {
+                let (symbol, referent) = self.collapse(node, referent);
+                self.pointer_left(&referent, symbol);
+            }
+            Node::Complex(ty, kind) => {
+                self.left(ty);
+                self.push(kind);
+            }
+            Node::Function { ret, .. } => {
+                if let Some(ret) = ret {
+                    self.left(ret);
+                    if !self.has_right(ret) {
+                        self.push(" ");
+                    }
+                }
+            }
+            Node::Array(element, _) => self.left(element),
+            Node::Vector(element, dimension) => {
+                self.left(element);
+                self.push(" __vector(");
+                self.node(dimension);
+                self.push(")");
+            }
+            Node::PointerToMember(class, member) => {
+                self.left(member);
+                match &*self.resolve(member) {
+                    Node::Function { .. } => {
+                        if self.last_char() != Some(' ') {
+                            self.push(" ");
+                        }
+                        self.push("(");
+                    }
+                    Node::Array(..) => self.push(" ("),
+                    _ => {
+                        if self.last_char() != Some('(') {
+                            self.push(" ");
+                        }
+                    }
+                }
+                self.node(class);
+                self.push("::*");
+            }
+            Node::Decltype(expression) => {
+                self.push("decltype (");
+                self.node(expression);
+                self.push(")");
+            }
+            Node::Pack(args) => self.list(args),
+            Node::PackExpansion(pattern) => match self.find_pack(pattern) {
+                Some(len) => {
+                    let pack_index = self.pack_index;
+                    for index in 0..len {
+                        if index > 0 {
+                            self.push(", ");
+                        }
+                        self.pack_index = Some(index);
+                        self.node(pattern);
+                    }
+                    self.pack_index = pack_index;
+                }
+                None => {
+                    self.subexpression(pattern);
+                    self.push("...");
+                }
+            },
+
+            Node::Literal(ty, value, negative) => self.literal(ty, value, *negative),
+            Node::FunctionParameter(0) => self.push("this"),
+            Node::FunctionParameter(index) => self.push(&format!("{{parm#{index}}}")),
+            Node::Nullary(operator) => self.push(operator.name),
+            Node::Unary(operator, operand) => {
+                let mut operand = operand;
+                if let ("ad", Node::Encoding(name, _)) = (operator.code, &**operand) {
+                    if let Node::Qualified(..) = **name {
+                        operand = name;
+                    }
+                }
+                self.push(operator.name);
+                match operator.code {
+                    "gs" => self.node(operand),
+                    "st" => {
+                        self.push("(");
+                        self.node(operand);
+                        self.push(")");
+                    }
+                    _ => self.subexpression(operand),
+                }
+            }
+            Node::Postfix(operator, operand) => {
+                self.subexpression(operand);
+                self.push(operator.name);
+            }
+            Node::Binary(operator, left, right) => {
+                let greater = operator.name == ">";
+                if greater {
+                    self.push("(");
+                }
+                self.subexpression(left);
+                if operator.code == "ix" {
+                    self.push("[");
+                    self.node(right);
+                    self.push("]");
+                } else {
+                    self.push(operator.name);
+                    self.subexpression(right);
+                }
+                if greater {
+                    self.push(")");
+                }
+            }
+            Node::Trinary(_, condition, then, otherwise) => {
+                self.subexpression(condition);
+                self.push("?");
+                self.subexpression(then);
+                self.push(" : ");
+                self.subexpression(otherwise);
+            }
+            Node::Cast(ty, operand) => {
+                self.push("(");
+                self.node(ty);
+                self.push(")");
+                self.subexpression(operand);
+            }
+            Node::NamedCast(operator, ty, operand) => {
+                self.push(operator.name);
+                self.push("<");
+                self.node(ty);
+                self.push(">(");
+                self.node(operand);
+                self.push(")");
+            }
+            Node::Call(function, args) => {
+                let function = match &**function {
+                    Node::Encoding(name, _) => name,
+                    _ => function,
+                };
+                self.subexpression(function);
+                self.push("(");
+                self.list(args);
+                self.push(")");
+            }
+            Node::ExpressionList(expressions) => self.list(expressions),
+            Node::InitializerList(ty, expressions) => {
+                if let Some(ty) = ty {
+                    self.node(ty);
+                }
+                self.push("{");
+                self.list(expressions);
+                self.push("}");
+            }
+            Node::SizeofPack(pack) => {
+                let len = match &**pack {
+                    Node::Pack(args) => Some(args.len()),
+                    _ => self.find_pack(pack),
+                };
+                self.push(&len.unwrap_or(0).to_string());
+            }
+            Node::Fold(code, operator, operands) => {
+                let pack_index = self.pack_index.take();
+                self.push("(");
+                match (*code, &operands[..]) {
+                    ("fl", [pack]) => {
+                        self.push("...");
+                        self.push(operator.name);
+                        self.subexpression(pack);
+                    }
+                    ("fr", [pack]) => {
+                        self.subexpression(pack);
+                        self.push(operator.name);
+                        self.push("...");
+                    }
+                    (_, [first, second]) => {
+                        self.subexpression(first);
+                        self.push(operator.name);
+                        self.push("...");
+                        self.push(operator.name);
+                        self.subexpression(second);
+                    }
+                    _ => self.failed = true,
+                }
+                self.push(")");
+                self.pack_index = pack_index;
+            }
+            Node::New {
+                placement,
+                ty,
+                initializer,
+            } => {
+                self.push("new ");
+                if !placement.is_empty() {
+                    self.push("(");
+                    self.list(placement);
+                    self.push(") ");
+                }
+                self.node(ty);
+                if let Some(initializer) = initializer {
+                    self.subexpression(initializer);
+                }
+            }
+        }
+    }
+
+    fn right_inner(&mut self, node: &Rc<Node>) {
+        match &**node {
+            Node::Parameter(..) | Node::Forward(..) if self.in_lambda => {}
+            Node::Parameter(..) | Node::Forward(..) => {
+                let arg = self.resolve(node);
+                self.right(&arg);
+            }
+            Node::VendorQualified(ty, _)
+            | Node::CvQualified(ty, _)
+            | Node::Complex(ty, _)
+            | Node::Vector(ty, _) => self.right(ty),
+            Node::Pointer(pointee) => self.pointer_right(pointee),
+            Node::LValueReference(referent) | Node::RValueReference(referent) => {
+                let (_, referent) = self.collapse(node, referent);
+                self.pointer_right(&referent);
+            }
+            Node::Function {
+                ret,
+                params,
+                qualifiers,
+            } => {
+                self.push("(");
+                self.list(params);
+                self.push(")");
+                self.function_qualifiers(qualifiers);
+                if let Some(ret) = ret {
+                    self.right(ret);
+                }
+            }
+            Node::Array(element, dimension) => {
+                if self.last_char() != Some(']') {
+                    self.push(" ");
+                }
+                self.push("[");
+                if let Some(dimension) = dimension {
+                    self.node(dimension);
+                }
+                self.push("]");
+                self.right(element);
+            }
+            Node::PointerToMember(_, member) => {
+                if matches!(
+                    *self.resolve(member),
+                    Node::Function { .. } | Node::Array(..)
+                ) {
+                    self.push(")");
+                }
+                self.right(member);
+            }
+            _ => {}
+        }
+    }
+
+    /// Whether a type has a part that's written after what it's the type
+    /// of, like the parameters of a function.
+    fn has_right(&mut self, node: &Rc<Node>) -> bool {
+        let node = self.resolve(node);
+        match &*node {
+            Node::Function { .. } | Node::Array(..) => true,
+            Node::Pointer(ty)
+            | Node::LValueReference(ty)
+            | Node::RValueReference(ty)
+            | Node::CvQualified(ty, _)
+            | Node::VendorQualified(ty, _)
+            | Node::Complex(ty, _)
+            | Node::Vector(ty, _)
+            | Node::PointerToMember(_, ty) => self.has_right(ty),
+            _ => false,
+        }
+    }
+
+    /// What a type is under its cv-qualifiers, which is what decides
+    /// whether a pointer to it needs parentheses.
+    fn unqualified(&mut self, node: &Rc<Node>) -> Rc<Node> {
+        let mut node = self.resolve(node);
+        while let Node::CvQualified(ty, _) = &*node {
+            node = self.resolve(&ty.clone());
+        }
+        node
+    }
+
+    /// The left part of a pointer or a reference, which needs parentheses
+    /// if it's to an array or a function: `int (*)[3]`, `void (&)()`.
+    fn pointer_left(&mut self, pointee: &Rc<Node>, symbol: &str) {
+        self.left(pointee);
+        match &*self.unqualified(pointee) {
+            Node::Array(..) => self.push(" ("),
+            Node::Function { .. } => {
+                if !matches!(self.last_char(), Some('(' | '*' | ' ')) {
+                    self.push(" ");
+                }
+                self.push("(");
+            }
+            _ => {}
+        }
+        self.push(symbol);
+    }
+
+    fn pointer_right(&mut self, pointee: &Rc<Node>) {
+        if matches!(
+            *self.unqualified(pointee),
+            Node::Array(..) | Node::Function { .. }
+        ) {
+            self.push(")");
+        }
+        self.right(pointee);
+    }
+
+    /// A reference to a reference collapses to the one reference: `T&`
+    /// with `T = int&&` is `int&`.
+    fn collapse(&mut self, node: &Rc<Node>, referent: &Rc<Node>) -> (&'static str, Rc<Node>) {
+        let mut rvalue = matches!(**node, Node::RValueReference(_));
+        let mut referent = referent.clone();
+        loop {
+            let arg = self.resolve(&referent);
+            match &*arg {
+                Node::LValueReference(inner) => {
+                    rvalue = false;
+                    referent = inner.clone();
+                }
+                Node::RValueReference(inner) => referent = inner.clone(),
+                _ => break,
+            }
+        }
+        (if rvalue { "&&" } else { "&" }, referent)
+    }
+
+    fn qualifiers(&mut self, qualifiers: Qualifiers) {
+        if qualifiers.is_const {
+            self.push(" const");
+        }
+        if qualifiers.is_volatile {
+            self.push(" volatile");
+        }
+        if qualifiers.is_restrict {
+            self.push(" restrict");
+        }
+    }
+
+    fn function_qualifiers(&mut self, qualifiers: &FunctionQualifiers) {
+        if qualifiers.transaction_safe {
+            self.push(" transaction_safe");
+        }
+        match &qualifiers.exceptions {
+            Some(Exceptions::Noexcept(None)) => self.push(" noexcept"),
+            Some(Exceptions::Noexcept(Some(condition))) => {
+                self.push(" noexcept");
+                self.subexpression(condition);
+            }
+            Some(Exceptions::Throw(types)) => {
+                self.push(" throw(");
+                self.list(types);
+                self.push(")");
+            }
+            None => {}
+        }
+        self.qualifiers(qualifiers.cv);
+        match qualifiers.ref_qualifier {
+            Some(RefQualifier::LValue) => self.push(" &"),
+            Some(RefQualifier::RValue) => self.push(" &&"),
+            None => {}
+        }
+    }
+
+    fn encoding(&mut self, name: &Rc<Node>, function: &Rc<Node>) {
+        let Node::Function {
+            ret,
+            params,
+            qualifiers,
+        } = &**function
+        else {
+            self.failed = true;
+            return;
+        };
+        if let Some(ret) = ret {
+            self.left(ret);
+            if !self.has_right(ret) {
+                self.push(" ");
+            }
+        }
+        self.node(name);
+        self.push("(");
+        self.list(params);
+        self.push(")");
+        self.function_qualifiers(qualifiers);
+        if let Some(ret) = ret {
+            self.right(ret);
+        }
+    }
+
+    /// Writes a list with commas between, leaving off the commas after
+    /// the last element that writes anything, as empty packs don't.
+    fn list(&mut self, nodes: &[Rc<Node>]) {
+        let mut commas = None;
+        for (index, node) in nodes.iter().enumerate() {
+            if index > 0 {
+                commas.get_or_insert(self.out.len());
+                self.push(", ");
+            }
+            let len = self.out.len();
+            self.node(node);
+            if self.out.len() > len {
+                commas = None;
+            }
+        }
+        if let Some(len) = commas {
+            self.out.truncate(len);
+        }
+    }
+
+    /// An operand, in parentheses unless it's just a name.
+    fn subexpression(&mut self, node: &Rc<Node>) {
+        let simple = matches!(
+            **node,
+            Node::Name(_)
+                | Node::Qualified(..)
+                | Node::InitializerList(..)
+                | Node::FunctionParameter(_)
+        );
+        if !simple {
+            self.push("(");
+        }
+        self.node(node);
+        if !simple {
+            self.push(")");
+        }
+    }
+
+    fn literal(&mut self, ty: &Rc<Node>, value: &str, negative: bool) {
+        let minus = if negative { "-" } else { "" };
+        if let Node::Builtin(name) = **ty {
+            let suffix = match name {
+                "int" => Some(""),
+                "unsigned int" => Some("u"),
+                "long" => Some("l"),
+                "unsigned long" => Some("ul"),
+                "long long" => Some("ll"),
+                "unsigned long long" => Some("ull"),
+                _ => None,
+            };
+            if let Some(suffix) = suffix {
+                self.push(&format!("{minus}{value}{suffix}"));
+                return;
+            }
+            if name == "bool" && !negative {
+                match value {
+                    "0" => return self.push("false"),
+                    "1" => return self.push("true"),
+                    _ => {}
+                }
+            }
+        }
+        self.push("(");
+        self.node(ty);
+        self.push(")");
+        match **ty {
+            Node::Builtin("float" | "double" | "long double" | "__float128") => {
+                self.push(&format!("{minus}[{value}]"))
+            }
+            _ => self.push(&format!("{minus}{value}")),
+        }
+    }
+
+    /// The length of the first pack a pattern uses, if it uses one.
+    fn find_pack(&mut self, node: &Rc<Node>) -> Option<usize> {
+        let children: Vec<&Rc<Node>> = match &**node {
+            Node::Parameter(_, arg) => {
+                return match &**arg {
+                    Node::Pack(args) => Some(args.len()),
+                    _ => None,
+                };
+            }
+            Node::Forward(cell, _) => {
+                return match cell.borrow().as_deref() {
+                    Some(Node::Pack(args)) => Some(args.len()),
+                    _ => None,
+                };
+            }
+            Node::Lambda(..)
+            | Node::Name(_)
+            | Node::AbiTag(..)
+            | Node::Operator(_)
+            | Node::Builtin(_)
+            | Node::Standard(_)
+            | Node::FunctionParameter(_)
+            | Node::UnnamedType(_)
+            | Node::DefaultArgument(..)
+            | Node::Nullary(_) => return None,
+            Node::Qualified(a, b)
+            | Node::Local(a, b)
+            | Node::Encoding(a, b)
+            | Node::VendorQualified(a, b)
+            | Node::Vector(a, b)
+            | Node::PointerToMember(a, b)
+            | Node::Cast(a, b)
+            | Node::NamedCast(_, a, b)
+            | Node::Binary(_, a, b)
+            | Node::ConstructionVtable(a, b) => vec![a, b],
+            Node::LiteralOperator(a)
+            | Node::VendorOperator(a)
+            | Node::Conversion(a)
+            | Node::Constructor(a)
+            | Node::Destructor(a)
+            | Node::MemberQualified(a, _)
+            | Node::Clone(a, _)
+            | Node::Special(_, a)
+            | Node::ReferenceTemporary(a, _)
+            | Node::CvQualified(a, _)
+            | Node::Pointer(a)
+            | Node::LValueReference(a)
+            | Node::RValueReference(a)
+            | Node::Complex(a, _)
+            | Node::Decltype(a)
+            | Node::PackExpansion(a)
+            | Node::Literal(a, ..)
+            | Node::Unary(_, a)
+            | Node::Postfix(_, a)
+            | Node::SizeofPack(a) => vec![a],
+            Node::Template(a, list) | Node::Call(a, list) => {
+                std::iter::once(a).chain(list).collect()
+            }
+            Node::StructuredBinding(list)
+            | Node::Pack(list)
+            | Node::ExpressionList(list)
+            | Node::Fold(_, _, list) => list.iter().collect(),
+            Node::Trinary(_, a, b, c) => vec![a, b, c],
+            Node::Array(a, b) => std::iter::once(a).chain(b).collect(),
+            Node::InitializerList(a, list) => a.iter().chain(list).collect(),
+            Node::Function { ret, params, .. } => ret.iter().chain(params).collect(),
+            Node::New {
+                placement,
+                ty,
+                initializer,
+            } => placement.iter().chain([ty]).chain(initializer).collect(),
+        };
+        if self.depth >= MAX_DEPTH {
+            self.failed = true;
+            return None;
+        }
+        self.depth += 1;
+        let len = children.into_iter().find_map(|child| self.find_pack(child));
+        self.depth -= 1;
+        len
+    }
+}
@@ -0,0 +1,31 @@
+/// Demangling symbol names back into the names in the source: C++ names
+/// mangled by the Itanium C++ ABI (`_Z...`), and Rust's, in the legacy
+/// scheme (`_ZN...17h<hash>E`) and in v0 (`_R...`). The output is what
+/// `nm -C` and `objdump -C` print for the same names.
+///
+mod itanium;
+mod rust;
+
+use std::borrow::Cow;
+
+/// Demangles a name, if it's mangled in a scheme we know and well-formed.
+/// Rust's legacy names are valid C++ manglings too, so they're tried as
+/// Rust first.
+pub fn demangle(name: &str) -> Option<String> {
+    rust::demangle(name).or_else(|| itanium::demangle(name))
+}
+
+/// Demangles a symbol's name as it's listed, keeping a symbol version or
+/// an `@plt` after it as it is: `_ZdlPv@GLIBCXX_3.4` is
+/// `operator delete(void*)@GLIBCXX_3.4`. Names that aren't mangled are
+/// returned unchanged.
+pub fn demangle_symbol(name: &str) -> Cow<'_, str> {
+    let (base, suffix) = match name.find('@') {
+        Some(at) if at > 0 => name.split_at(at),
+        _ => (name, ""),
+    };
+    match demangle(base) {
+        Some(demangled) => Cow::Owned(demangled + suffix),
+        None => Cow::Borrowed(name),
+    }
+}
@@ -0,0 +1,149 @@
+/// Demangling Rust symbol names, in both of the schemes rustc has used:
+/// the legacy one, which is a C++-style `_ZN...E` whose last segment is a
+/// hash (`17h0123456789abcdefE`), and v0 (`_R...`), in `v0.rs`. As in the
+/// GNU demangler, the hash and crate disambiguators aren't shown.
+///
+mod v0;
+
+/// Demangles a Rust name, or returns `None` if it isn't one (or isn't
+/// well-formed), leaving legacy names to be tried as C++.
+pub fn demangle(mangled: &str) -> Option<String> {
+    if let Some(rest) = mangled.strip_prefix("_ZN") {
+        legacy(rest)
+    } else if let Some(rest) = mangled.strip_prefix("_R") {
+        // A `.llvm.1234` or the like on the end isn't part of the name.
+        let end = rest.find('.').unwrap_or(rest.len());
+        v0::demangle(&rest[..end])
+    } else {
+        None
+    }
+}
+
+/// A legacy name, after its `_ZN`: segments up to an `E`, of which the
+/// last is the hash.
+fn legacy(mangled: &str) -> Option<String> {
+    let allowed = |c: u8| c.is_ascii_alphanumeric() || matches!(c, b'_' | b'$' | b'.' | b':');
+    if !mangled.bytes().all(allowed) {
+        return None;
+    }
+    // Drop a `.suffix` after the `E`: the name ends at the last `E` that's
+    // either last or followed by a `.`.
+    let bytes = mangled.as_bytes();
+    let mut end = bytes.len();
+    while end > 0 && !(bytes[end - 1] == b'E' && (end == bytes.len() || bytes[end] == b'.')) {
+        end -= 1;
+    }
+    let mangled = mangled[..end].strip_suffix('E')?;
+    if mangled.len() <= 19 || !mangled[mangled.len() - 19..].starts_with("17h") {
+        return None;
+    }
+
+    let mut segments = vec![];
+    let mut rest = mangled;
+    while !rest.is_empty() {
+        let digits = rest.bytes().take_while(u8::is_ascii_digit).count();
+        if digits == 0 || (digits > 1 && rest.starts_with('0')) {
+            return None;
+        }
+        let len: usize = rest[..digits].parse().ok()?;
+        let segment = rest.get(digits..digits.checked_add(len)?)?;
+        if segment.is_empty() {
+            return None;
+        }
+        segments.push(segment);
+        rest = &rest[digits + len..];
+    }
+    if !is_hash(segments.pop()?) {
+        return None;
+    }
+
+    let mut out = String::new();
+    for (index, segment) in segments.into_iter().enumerate() {
+        if index > 0 {
+            out.push_str("::");
+        }
+        unescape(segment, &mut out);
+    }
+    Some(out)
+}
+
+/// Whether a segment is a hash: `h` and 16 hex digits, at least five of
+/// them different, as a name that happens to look like one rarely has.
+fn is_hash(segment: &str) -> bool {
+    let Some(hex) = segment.strip_prefix('h') else {
+        return false;
+    };
+    let mut seen = 0u16;
+    for c in hex.chars() {
+        match c.to_digit(16) {
+            Some(digit) if !c.is_ascii_uppercase() => seen |= 1 << digit,
+            _ => return false,
+        }
+    }
+    hex.len() == 16 && seen.count_ones() >= 5
+}
+
+/// Writes a legacy segment with its escapes undone: `$LT$` for `<`,
+/// `$u20$` for a space, `..` for `::`, and so on. Anything after an escape
+/// that isn't one is written as it is.
+fn unescape(segment: &str, out: &mut String) {
+    // An `_` is put before an escape at the start of a segment, to make
+    // it a valid identifier.
+    let mut rest = match segment.strip_prefix('_') {
+        Some(rest) if rest.starts_with('$') => rest,
+        _ => segment,
+    };
+    while !rest.is_empty() {
+        if rest.starts_with('$') {
+            match escape(rest) {
+                Some((c, len)) => {
+                    out.push(c);
+                    rest = &rest[len..];
+                }
+                None => {
+                    out.push_str(rest);
+                    return;
+                }
+            }
+        } else if let Some(after) = rest.strip_prefix("..") {
+            out.push_str("::");
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix('.') {
+            out.push('.');
+            rest = after;
+        } else {
+            let len = rest.find(['$', '.']).unwrap_or(rest.len());
+            out.push_str(&rest[..len]);
+            rest = &rest[len..];
+        }
+    }
+}
+
+/// The character a `$...$` escape at the start of `s` stands for, and the
+/// escape's length.
+fn escape(s: &str) -> Option<(char, usize)> {
+    let inner = &s[1..s[1..].find('$')? + 1];
+    let c = match inner {
+        "C" => ',',
+        "SP" => '@',
+        "BP" => '*',
+        "RF" => '&',
+        "LT" => '<',
+        "GT" => '>',
+        "LP" => '(',
+        "RP" => ')',
+        _ => {
+            let hex = inner.strip_prefix('u').filter(|hex| hex.len() == 2)?;
+            if hex.bytes().any(|c| c.is_ascii_uppercase()) {
+                return None;
+            }
+            // Only printable ASCII.
+            let c = u8::from_str_radix(hex, 16).ok()?;
+            if !(0x20..0x7f).contains(&c) {
+                return None;
+            }
+            c as char
+        }
+    };
+    Some((c, inner.len() + 2))
+}
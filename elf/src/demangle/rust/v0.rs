@@ -0,0 +1,640 @@
+/// Demangling Rust's v0 names (RFC 2603), which rustc uses with
+/// `-C symbol-mangling-version=v0`: a path made of crates, items, impls and
+/// generic arguments, which can refer back to parts already read (`B`).
+/// Identifiers outside ASCII are in Punycode. The output follows the GNU
+/// demangler's, without the crates' disambiguators.
+///
+use std::fmt::Write;
+
+/// How deeply paths, types and constants may nest.
+const MAX_DEPTH: u32 = 1024;
+/// The longest name to write; backreferences can make a short mangled name
+/// very long.
+const MAX_LEN: usize = 1 << 18;
+
+/// Demangles a v0 name, from after its `_R`.
+pub(super) fn demangle(mangled: &str) -> Option<String> {
+    if !mangled.starts_with(|c: char| c.is_ascii_uppercase())
+        || !mangled
+            .bytes()
+            .all(|c| c.is_ascii_alphanumeric() || c == b'_')
+    {
+        return None;
+    }
+    let mut demangler = Demangler {
+        sym: mangled.as_bytes(),
+        next: 0,
+        out: String::new(),
+        skipping: false,
+        bound_lifetimes: 0,
+        depth: 0,
+    };
+    demangler.path(true)?;
+    // The crate it was instantiated in, if it isn't the one it's from.
+    if demangler.next < demangler.sym.len() {
+        demangler.skipping = true;
+        demangler.path(false)?;
+    }
+    (demangler.next == demangler.sym.len()).then_some(demangler.out)
+}
+
+/// An identifier: its ASCII part, and the Punycode for the rest, if it
+/// has any.
+struct Ident<'a> {
+    ascii: &'a str,
+    punycode: Option<&'a str>,
+}
+
+struct Demangler<'a> {
+    sym: &'a [u8],
+    next: usize,
+    out: String,
+    /// Whether to read without writing, as for the path of an impl.
+    skipping: bool,
+    /// How many lifetimes the `for<...>`s around the type bind.
+    bound_lifetimes: u64,
+    depth: u32,
+}
+
+impl<'a> Demangler<'a> {
+    fn peek(&self) -> Option<u8> {
+        self.sym.get(self.next).copied()
+    }
+
+    fn next(&mut self) -> Option<u8> {
+        let c = self.peek()?;
+        self.next += 1;
+        Some(c)
+    }
+
+    fn eat(&mut self, c: u8) -> bool {
+        let found = self.peek() == Some(c);
+        self.next += found as usize;
+        found
+    }
+
+    fn print(&mut self, s: &str) -> Option<()> {
+        if !self.skipping {
+            self.out.push_str(s);
+            if self.out.len() > MAX_LEN {
+                return None;
+            }
+        }
+        Some(())
+    }
+
+    /// Runs `f` one level deeper, failing if that's too deep.
+    fn nested<T>(&mut self, f: impl FnOnce(&mut Self) -> Option<T>) -> Option<T> {
+        if self.depth >= MAX_DEPTH {
+            return None;
+        }
+        self.depth += 1;
+        let result = f(self);
+        self.depth -= 1;
+        result
+    }
+
+    /// Follows a backreference, whose `B` has been read: runs `f` at the
+    /// position it refers to, unless nothing's being written.
+    fn backref<T: Default>(&mut self, f: impl FnOnce(&mut Self) -> Option<T>) -> Option<T> {
+        let start = self.next - 1;
+        let target = usize::try_from(self.integer_62()?).ok()?;
+        // Only back, so there's no loop.
+        if target >= start {
+            return None;
+        }
+        if self.skipping {
+            return Some(T::default());
+        }
+        let next = std::mem::replace(&mut self.next, target);
+        let result = f(self);
+        self.next = next;
+        result
+    }
+
+    /// A base-62 number: `_` for 0, or the digits of one less and an `_`.
+    fn integer_62(&mut self) -> Option<u64> {
+        if self.eat(b'_') {
+            return Some(0);
+        }
+        let mut value = 0u64;
+        while !self.eat(b'_') {
+            let digit = match self.next()? {
+                c @ b'0'..=b'9' => c - b'0',
+                c @ b'a'..=b'z' => 10 + c - b'a',
+                c @ b'A'..=b'Z' => 36 + c - b'A',
+                _ => return None,
+            };
+            value = value.checked_mul(62)?.checked_add(digit.into())?;
+        }
+        value.checked_add(1)
+    }
+
+    /// A number after the tag, if it's there, or 0.
+    fn opt_integer_62(&mut self, tag: u8) -> Option<u64> {
+        if !self.eat(tag) {
+            return Some(0);
+        }
+        self.integer_62()?.checked_add(1)
+    }
+
+    fn disambiguator(&mut self) -> Option<u64> {
+        self.opt_integer_62(b's')
+    }
+
+    /// Hex digits up to an `_`: their value, if it fits in 64 bits, and
+    /// the digits.
+    fn hex_nibbles(&mut self) -> Option<(u64, &'a str)> {
+        let start = self.next;
+        let mut value = 0u64;
+        while !self.eat(b'_') {
+            let digit = match self.next()? {
+                c @ b'0'..=b'9' => c - b'0',
+                c @ b'a'..=b'f' => 10 + c - b'a',
+                _ => return None,
+            };
+            value = value << 4 | u64::from(digit);
+        }
+        let digits = std::str::from_utf8(&self.sym[start..self.next - 1]).ok()?;
+        Some((value, digits))
+    }
+
+    fn ident(&mut self) -> Option<Ident<'a>> {
+        let is_punycode = self.eat(b'u');
+        let first = self.next()?;
+        if !first.is_ascii_digit() {
+            return None;
+        }
+        let mut len = usize::from(first - b'0');
+        if first != b'0' {
+            while let Some(c @ b'0'..=b'9') = self.peek() {
+                self.next += 1;
+                len = len.checked_mul(10)?.checked_add(usize::from(c - b'0'))?;
+            }
+        }
+        self.eat(b'_');
+        let start = self.next;
+        self.next = start.checked_add(len)?;
+        let text = std::str::from_utf8(self.sym.get(start..self.next)?).ok()?;
+        if !is_punycode {
+            return Some(Ident {
+                ascii: text,
+                punycode: None,
+            });
+        }
+        // The Punycode is after the last `_`, or all of it if there's none.
+        let (ascii, punycode) = text.rsplit_once('_').unwrap_or(("", text));
+        if punycode.is_empty() {
+            return None;
+        }
+        Some(Ident {
+            ascii,
+            punycode: Some(punycode),
+        })
+    }
+
+    fn print_ident(&mut self, ident: &Ident) -> Option<()> {
+        if self.skipping {
+            return Some(());
+        }
+        match ident.punycode {
+            None => self.print(ident.ascii),
+            Some(punycode) => self.print(&decode_punycode(ident.ascii, punycode)?),
+        }
+    }
+
+    /// Writes a path. In a value (`in_value`), generic arguments are
+    /// written with a turbofish, as in `f::<T>`, rather than as in a type.
+    fn path(&mut self, in_value: bool) -> Option<()> {
+        self.nested(|d| d.path_inner(in_value))
+    }
+
+    fn path_inner(&mut self, in_value: bool) -> Option<()> {
+        match self.next()? {
+            // A crate root.
+            b'C' => {
+                self.disambiguator()?;
+                let name = self.ident()?;
+                self.print_ident(&name)?;
+            }
+            // An item in a namespace: an uppercase one, like the `C` of
+            // closures, is shown in braces, and a lowercase one only by the
+            // item's name.
+            b'N' => {
+                let namespace = self.next()?;
+                if !namespace.is_ascii_alphabetic() {
+                    return None;
+                }
+                self.path(in_value)?;
+                let disambiguator = self.disambiguator()?;
+                let name = self.ident()?;
+                let named = !name.ascii.is_empty() || name.punycode.is_some();
+                if namespace.is_ascii_uppercase() {
+                    self.print("::{")?;
+                    match namespace {
+                        b'C' => self.print("closure")?,
+                        b'S' => self.print("shim")?,
+                        _ => self.print(&char::from(namespace).to_string())?,
+                    }
+                    if named {
+                        self.print(":")?;
+                        self.print_ident(&name)?;
+                    }
+                    self.print(&format!("#{disambiguator}}}"))?;
+                } else if named {
+                    self.print("::")?;
+                    self.print_ident(&name)?;
+                }
+            }
+            // An inherent impl (`M`), or an impl of a trait (`X`), after
+            // the path of the impl itself, which isn't shown; or a type as
+            // a trait without an impl (`Y`).
+            tag @ (b'M' | b'X' | b'Y') => {
+                if tag != b'Y' {
+                    self.disambiguator()?;
+                    let skipping = std::mem::replace(&mut self.skipping, true);
+                    self.path(in_value)?;
+                    self.skipping = skipping;
+                }
+                self.print("<")?;
+                self.ty()?;
+                if tag != b'M' {
+                    self.print(" as ")?;
+                    self.path(false)?;
+                }
+                self.print(">")?;
+            }
+            // Generic arguments.
+            b'I' => {
+                self.path(in_value)?;
+                if in_value {
+                    self.print("::")?;
+                }
+                self.print("<")?;
+                self.generic_args()?;
+                self.print(">")?;
+            }
+            b'B' => self.backref(|d| d.path(in_value))?,
+            _ => return None,
+        }
+        Some(())
+    }
+
+    /// Generic arguments up to an `E`, separated by commas.
+    fn generic_args(&mut self) -> Option<()> {
+        let mut first = true;
+        while !self.eat(b'E') {
+            if !std::mem::take(&mut first) {
+                self.print(", ")?;
+            }
+            self.generic_arg()?;
+        }
+        Some(())
+    }
+
+    fn generic_arg(&mut self) -> Option<()> {
+        if self.eat(b'L') {
+            let lifetime = self.integer_62()?;
+            self.lifetime(lifetime)
+        } else if self.eat(b'K') {
+            self.constant()
+        } else {
+            self.ty()
+        }
+    }
+
+    /// Writes a lifetime by its De Bruijn index among the bound ones: 1 for
+    /// the one bound last, which is `'a` at the outermost binder. 0 is
+    /// `'_`.
+    fn lifetime(&mut self, index: u64) -> Option<()> {
+        if index == 0 {
+            return self.print("'_");
+        }
+        let depth = self.bound_lifetimes.wrapping_sub(index);
+        if depth < 26 {
+            self.print(&format!("'{}", char::from(b'a' + depth as u8)))
+        } else {
+            self.print(&format!("'_{depth}"))
+        }
+    }
+
+    /// Writes the `for<'a, ...> ` of a binder, if it binds any lifetimes,
+    /// and binds them.
+    fn binder(&mut self) -> Option<()> {
+        let count = self.opt_integer_62(b'G')?;
+        if count == 0 {
+            return Some(());
+        }
+        self.print("for<")?;
+        for i in 0..count {
+            if i > 0 {
+                self.print(", ")?;
+            }
+            self.bound_lifetimes = self.bound_lifetimes.checked_add(1)?;
+            self.lifetime(1)?;
+        }
+        self.print("> ")
+    }
+
+    fn ty(&mut self) -> Option<()> {
+        self.nested(Self::ty_inner)
+    }
+
+    fn ty_inner(&mut self) -> Option<()> {
+        let tag = self.next()?;
+        if let Some(basic) = basic_type(tag) {
+            return self.print(basic);
+        }
+        match tag {
+            b'R' | b'Q' => {
+                self.print("&")?;
+                if self.eat(b'L') {
+                    let lifetime = self.integer_62()?;
+                    if lifetime != 0 {
+                        self.lifetime(lifetime)?;
+                        self.print(" ")?;
+                    }
+                }
+                if tag == b'Q' {
+                    self.print("mut ")?;
+                }
+                self.ty()?;
+            }
+            b'P' => {
+                self.print("*const ")?;
+                self.ty()?;
+            }
+            b'O' => {
+                self.print("*mut ")?;
+                self.ty()?;
+            }
+            b'A' => {
+                self.print("[")?;
+                self.ty()?;
+                self.print("; ")?;
+                self.constant()?;
+                self.print("]")?;
+            }
+            b'S' => {
+                self.print("[")?;
+                self.ty()?;
+                self.print("]")?;
+            }
+            b'T' => {
+                self.print("(")?;
+                let count = self.types()?;
+                self.print(if count == 1 { ",)" } else { ")" })?;
+            }
+            b'F' => {
+                let bound = self.bound_lifetimes;
+                self.binder()?;
+                if self.eat(b'U') {
+                    self.print("unsafe ")?;
+                }
+                if self.eat(b'K') {
+                    let abi = if self.eat(b'C') {
+                        "C"
+                    } else {
+                        match self.ident()? {
+                            Ident {
+                                ascii,
+                                punycode: None,
+                            } if !ascii.is_empty() => ascii,
+                            _ => return None,
+                        }
+                    };
+                    // A `-` in the ABI's name is mangled as `_`.
+                    self.print(&format!("extern \"{}\" ", abi.replace('_', "-")))?;
+                }
+                self.print("fn(")?;
+                self.types()?;
+                self.print(")")?;
+                // `()` isn't written as the return type.
+                if !self.eat(b'u') {
+                    self.print(" -> ")?;
+                    self.ty()?;
+                }
+                self.bound_lifetimes = bound;
+            }
+            b'D' => {
+                self.print("dyn ")?;
+                let bound = self.bound_lifetimes;
+                self.binder()?;
+                let mut first = true;
+                while !self.eat(b'E') {
+                    if !std::mem::take(&mut first) {
+                        self.print(" + ")?;
+                    }
+                    self.dyn_trait()?;
+                }
+                self.bound_lifetimes = bound;
+                if !self.eat(b'L') {
+                    return None;
+                }
+                let lifetime = self.integer_62()?;
+                if lifetime != 0 {
+                    self.print(" + ")?;
+                    self.lifetime(lifetime)?;
+                }
+            }
+            b'B' => self.backref(Self::ty)?,
+            _ => {
+                // A path, whose tag this was.
+                self.next -= 1;
+                self.path(false)?;
+            }
+        }
+        Some(())
+    }
+
+    /// Types up to an `E`, separated by commas, returning how many there
+    /// were.
+    fn types(&mut self) -> Option<usize> {
+        let mut count = 0;
+        while !self.eat(b'E') {
+            if count > 0 {
+                self.print(", ")?;
+            }
+            self.ty()?;
+            count += 1;
+        }
+        Some(count)
+    }
+
+    /// A trait of a `dyn`, with the types bound to its associated types, as
+    /// in `Iterator<Item = u8>`.
+    fn dyn_trait(&mut self) -> Option<()> {
+        let mut open = self.path_maybe_open_generics()?;
+        while self.eat(b'p') {
+            self.print(if open { ", " } else { "<" })?;
+            open = true;
+            let name = self.ident()?;
+            self.print_ident(&name)?;
+            self.print(" = ")?;
+            self.ty()?;
+        }
+        if open {
+            self.print(">")?;
+        }
+        Some(())
+    }
+
+    /// Writes a path, leaving its generic arguments open for the bindings
+    /// of a `dyn` trait to be added to. Returns whether it did.
+    fn path_maybe_open_generics(&mut self) -> Option<bool> {
+        self.nested(|d| {
+            if d.eat(b'B') {
+                d.backref(Self::path_maybe_open_generics)
+            } else if d.eat(b'I') {
+                d.path(false)?;
+                d.print("<")?;
+                d.generic_args()?;
+                Some(true)
+            } else {
+                d.path(false)?;
+                Some(false)
+            }
+        })
+    }
+
+    /// Writes a constant generic argument: an integer, a `bool` or a
+    /// `char`, or `_` for one that's a placeholder.
+    fn constant(&mut self) -> Option<()> {
+        self.nested(|d| {
+            if d.eat(b'B') {
+                return d.backref(Self::constant);
+            }
+            match d.next()? {
+                b'p' => d.print("_"),
+                b'h' | b't' | b'm' | b'y' | b'o' | b'j' => d.const_uint(),
+                b'a' | b's' | b'l' | b'x' | b'n' | b'i' => {
+                    if d.eat(b'n') {
+                        d.print("-")?;
+                    }
+                    d.const_uint()
+                }
+                b'b' => match d.hex_nibbles()? {
+                    (0, "0") => d.print("false"),
+                    (1, "1") => d.print("true"),
+                    _ => None,
+                },
+                b'c' => d.const_char(),
+                _ => None,
+            }
+        })
+    }
+
+    fn const_uint(&mut self) -> Option<()> {
+        match self.hex_nibbles()? {
+            (_, "") => None,
+            // Too big for 64 bits, so it's written in hex, as it's mangled.
+            (_, digits) if digits.len() > 16 => self.print(&format!("0x{digits}")),
+            (value, _) => self.print(&value.to_string()),
+        }
+    }
+
+    /// Writes a `char`, escaped as Rust's `Debug` would, mostly.
+    fn const_char(&mut self) -> Option<()> {
+        let (value, digits) = self.hex_nibbles()?;
+        if digits.is_empty() || digits.len() > 8 {
+            return None;
+        }
+        let mut out = String::from("'");
+        match value {
+            0x09 => out.push_str("\\t"),
+            0x0d => out.push_str("\\r"),
+            0x0a => out.push_str("\\n"),
+            0x21..=0x7d => out.push(char::from(value as u8)),
+            _ => write!(out, "\\u{{{value:x}}}").ok()?,
+        }
+        out.push('\'');
+        self.print(&out)
+    }
+}
+
+/// The primitive type a tag stands for, if it's one.
+fn basic_type(tag: u8) -> Option<&'static str> {
+    Some(match tag {
+        b'b' => "bool",
+        b'c' => "char",
+        b'e' => "str",
+        b'u' => "()",
+        b'a' => "i8",
+        b's' => "i16",
+        b'l' => "i32",
+        b'x' => "i64",
+        b'n' => "i128",
+        b'i' => "isize",
+        b'h' => "u8",
+        b't' => "u16",
+        b'm' => "u32",
+        b'y' => "u64",
+        b'o' => "u128",
+        b'j' => "usize",
+        b'f' => "f32",
+        b'd' => "f64",
+        b'z' => "!",
+        b'p' => "_",
+        b'v' => "...",
+        _ => return None,
+    })
+}
+
+/// Decodes an identifier's Punycode (RFC 3492), inserting the characters it
+/// encodes among those of its ASCII part.
+fn decode_punycode(ascii: &str, punycode: &str) -> Option<String> {
+    const BASE: usize = 36;
+    const T_MIN: usize = 1;
+    const T_MAX: usize = 26;
+    const SKEW: usize = 38;
+
+    let mut out: Vec<char> = ascii.chars().collect();
+    let mut damp = 700;
+    let mut bias = 72;
+    let mut i = 0usize;
+    let mut n = 0x80usize;
+    let mut digits = punycode.bytes().peekable();
+    while digits.peek().is_some() {
+        // A variable-length delta, in which each digit below its threshold
+        // is the last.
+        let mut delta = 0usize;
+        let mut weight = 1usize;
+        let mut k = 0;
+        loop {
+            k += BASE;
+            let t = k.saturating_sub(bias).clamp(T_MIN, T_MAX);
+            let digit = match digits.next()? {
+                c @ b'a'..=b'z' => usize::from(c - b'a'),
+                c @ b'0'..=b'9' => 26 + usize::from(c - b'0'),
+                _ => return None,
+            };
+            delta = delta.checked_add(digit.checked_mul(weight)?)?;
+            if digit < t {
+                break;
+            }
+            weight = weight.checked_mul(BASE - t)?;
+        }
+
+        let len = out.len() + 1;
+        i = i.checked_add(delta)?;
+        n = n.checked_add(i / len)?;
+        i %= len;
+        out.insert(i, char::from_u32(u32::try_from(n).ok()?)?);
+        i += 1;
+        if out.len() > MAX_LEN {
+            return None;
+        }
+
+        // Adapt the bias to the deltas seen.
+        delta /= damp;
+        damp = 2;
+        delta += delta / len;
+        k = 0;
+        while delta > (BASE - T_MIN) * T_MAX / 2 {
+            delta /= BASE - T_MIN;
+            k += BASE;
+        }
+        bias = k + (BASE - T_MIN + 1) * delta / (delta + SKEW);
+    }
+    Some(out.into_iter().collect())
+}
@@ -0,0 +1,86 @@
+/// Problems found in a file that parsing got past: when parsing leniently,
+/// inconsistencies are recorded here instead of stopping at the first one.
+///
+use std::fmt;
+
+use crate::ElfError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// The headers describe something that doesn't make sense, or that
+    /// isn't in the file; reading it later may fail.
+    Warning,
+
+    /// Part of the headers couldn't be read, and was skipped or left empty.
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+
+    /// File offset of the header or table entry that the problem is in.
+    pub offset: u64,
+
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {:#x}: {}", self.severity, self.offset, self.message)
+    }
+}
+
+/// Collects diagnostics during parsing. When parsing strictly, errors
+/// are returned rather than recorded, and warnings are dropped.
+pub struct Recorder {
+    lenient: bool,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl Recorder {
+    pub fn new(lenient: bool) -> Recorder {
+        Recorder {
+            lenient,
+            diagnostics: vec![],
+        }
+    }
+
+    pub fn is_lenient(&self) -> bool {
+        self.lenient
+    }
+
+    /// Records `error`, found at `offset`, if parsing leniently; otherwise returns it.
+    pub fn error(&mut self, offset: u64, error: ElfError) -> Result<(), ElfError> {
+        if !self.lenient {
+            return Err(error);
+        }
+
+        self.diagnostics.push(Diagnostic {
+            severity: Severity::Error,
+            offset,
+            message: error.to_string(),
+        });
+
+        Ok(())
+    }
+
+    pub fn warning(&mut self, offset: u64, message: String) {
+        if self.lenient {
+            self.diagnostics.push(Diagnostic {
+                severity: Severity::Warning,
+                offset,
+                message,
+            });
+        }
+    }
+}
@@ -0,0 +1,794 @@
+/// Picks an instruction's class from its top-level encoding bits, and
+/// decodes the data processing and branch classes; loads and stores, SIMD
+/// and floating point, and system instructions have their own modules.
+///
+use super::*;
+
+/// An instruction before it's given its address and encoding.
+pub(super) struct Decoded {
+    pub mnemonic: String,
+    pub operands: Vec<Operand>,
+    pub comment: Option<String>,
+    pub flow: Flow,
+}
+
+/// Decodes the instruction at the start of `bytes`, which is at `address`.
+/// Words that aren't an instruction decode as "<unknown>", and so do the
+/// last bytes of a section too short to hold one.
+pub fn decode(bytes: &[u8], address: u64) -> Instruction {
+    let Some(&word) = bytes.first_chunk::<INSTRUCTION_LEN>() else {
+        return Instruction {
+            address,
+            len: bytes.len(),
+            word: 0,
+            mnemonic: "<unknown>".to_owned(),
+            operands: vec![],
+            comment: None,
+            flow: Flow::Stop,
+        };
+    };
+    let word = u32::from_le_bytes(word);
+
+    let decoded = match bits(word, 25, 4) {
+        0b0000 => reserved(word),
+        0b1000 | 0b1001 => data_immediate(word, address),
+        0b1010 | 0b1011 => branch_system(word, address),
+        0b0100 | 0b0110 | 0b1100 | 0b1110 => loads::load_store(word, address),
+        0b0101 | 0b1101 => data_register(word),
+        0b0111 | 0b1111 => simd::simd_fp(word),
+        _ => None,
+    };
+    let decoded = decoded.unwrap_or(Decoded {
+        mnemonic: "<unknown>".to_owned(),
+        operands: vec![],
+        comment: None,
+        flow: Flow::Stop,
+    });
+
+    Instruction {
+        address,
+        len: INSTRUCTION_LEN,
+        word,
+        mnemonic: decoded.mnemonic,
+        operands: decoded.operands,
+        comment: decoded.comment,
+        flow: decoded.flow,
+    }
+}
+
+/// `width` bits of `word`, from bit `low` up.
+pub(super) fn bits(word: u32, low: u32, width: u32) -> u32 {
+    (word >> low) & ((1 << width) - 1)
+}
+
+pub(super) fn bit(word: u32, n: u32) -> bool {
+    word >> n & 1 != 0
+}
+
+/// A `width`-bit field as a signed number.
+pub(super) fn signed(value: u32, width: u32) -> i64 {
+    ((value as i64) << (64 - width)) >> (64 - width)
+}
+
+/// An instruction that carries on to the next one.
+pub(super) fn op(mnemonic: &str, operands: Vec<Operand>) -> Option<Decoded> {
+    Some(Decoded {
+        mnemonic: mnemonic.to_owned(),
+        operands,
+        comment: None,
+        flow: Flow::Next,
+    })
+}
+
+/// A general-purpose register, x or w by `sf`, where 31 is the zero register.
+pub(super) fn gpr(sf: bool, number: u32) -> Operand {
+    Operand::Register(match sf {
+        true => Register::X(number as u8),
+        false => Register::W(number as u8),
+    })
+}
+
+/// A general-purpose register where 31 is the stack pointer.
+pub(super) fn gpr_sp(sf: bool, number: u32) -> Operand {
+    Operand::Register(match (sf, number) {
+        (true, 31) => Register::Sp,
+        (false, 31) => Register::Wsp,
+        (true, _) => Register::X(number as u8),
+        (false, _) => Register::W(number as u8),
+    })
+}
+
+pub(super) fn shift(kind: &'static str, amount: u32) -> Operand {
+    Operand::Shift(Shift {
+        kind,
+        amount: Some(amount as u8),
+    })
+}
+
+/// A SIMD and floating point register as a scalar of `size` bytes.
+pub(super) fn fp(number: u32, size: u8) -> Operand {
+    Operand::Register(Register::Fp {
+        number: number as u8,
+        size,
+    })
+}
+
+/// The arrangements of a vector, by the log2 of its elements' size in
+/// bytes and then Q (whether it's all 128 bits, or just the low 64).
+pub(super) const ARRANGEMENTS: [&str; 8] = ["8b", "16b", "4h", "8h", "2s", "4s", "1d", "2d"];
+
+const CONDITIONS: [&str; 16] = [
+    "eq", "ne", "hs", "lo", "mi", "pl", "vs", "vc", "hi", "ls", "ge", "lt", "gt", "le", "al", "nv",
+];
+
+pub(super) fn condition(number: u32) -> Operand {
+    Operand::Name(CONDITIONS[number as usize & 15].to_owned())
+}
+
+/// The udf instruction is all that's defined in the reserved space.
+fn reserved(word: u32) -> Option<Decoded> {
+    if word >> 16 != 0 {
+        return None;
+    }
+    Some(Decoded {
+        flow: Flow::Stop,
+        ..op("udf", vec![Operand::Immediate(word as i64)])?
+    })
+}
+
+// ---------------------------
+// Data processing, immediate.
+
+fn data_immediate(word: u32, address: u64) -> Option<Decoded> {
+    let sf = bit(word, 31);
+    let rd = bits(word, 0, 5);
+    let rn = bits(word, 5, 5);
+
+    match bits(word, 23, 3) {
+        0b000 | 0b001 => {
+            let offset = signed(bits(word, 5, 19) << 2 | bits(word, 29, 2), 21);
+            match bit(word, 31) {
+                // llvm-objdump writes adr's offset, and adrp's target.
+                false => op("adr", vec![gpr(true, rd), Operand::Immediate(offset)]),
+                true => {
+                    let target = (address & !0xfff).wrapping_add((offset << 12) as u64);
+                    op("adrp", vec![gpr(true, rd), Operand::Target(target)])
+                }
+            }
+        }
+        0b010 => add_immediate(word),
+        0b100 => logical_immediate(word),
+        0b101 => move_wide(word),
+        0b110 => bitfield(word),
+        0b111 => {
+            let imms = bits(word, 10, 6);
+            let rm = bits(word, 16, 5);
+            if bits(word, 29, 2) != 0 || bit(word, 21) || bit(word, 22) != sf || !sf && imms >= 32 {
+                return None;
+            }
+            let imms = Operand::Immediate(imms as i64);
+            match rn == rm {
+                true => op("ror", vec![gpr(sf, rd), gpr(sf, rn), imms]),
+                false => op("extr", vec![gpr(sf, rd), gpr(sf, rn), gpr(sf, rm), imms]),
+            }
+        }
+        _ => None,
+    }
+}
+
+fn add_immediate(word: u32) -> Option<Decoded> {
+    let sf = bit(word, 31);
+    let (sub, flags) = (bit(word, 30), bit(word, 29));
+    let rd = bits(word, 0, 5);
+    let rn = bits(word, 5, 5);
+    let imm = bits(word, 10, 12);
+    let shifted = bit(word, 22);
+
+    let mut operands = vec![Operand::Immediate(imm as i64)];
+    let mut comment = None;
+    if shifted {
+        operands.push(shift("lsl", 12));
+        comment = Some(format!("={}", imm << 12));
+    }
+    let op = |mnemonic, operands| {
+        Some(Decoded {
+            comment: comment.clone(),
+            ..op(mnemonic, operands)?
+        })
+    };
+
+    if !sub && !flags && !shifted && imm == 0 && (rd == 31 || rn == 31) {
+        return op("mov", vec![gpr_sp(sf, rd), gpr_sp(sf, rn)]);
+    }
+    if flags && rd == 31 {
+        operands.insert(0, gpr_sp(sf, rn));
+        return op(if sub { "cmp" } else { "cmn" }, operands);
+    }
+
+    let destination = match flags {
+        true => gpr(sf, rd),
+        false => gpr_sp(sf, rd),
+    };
+    operands.splice(0..0, [destination, gpr_sp(sf, rn)]);
+    let mnemonic = match (sub, flags) {
+        (false, false) => "add",
+        (false, true) => "adds",
+        (true, false) => "sub",
+        (true, true) => "subs",
+    };
+    op(mnemonic, operands)
+}
+
+/// The value of a logical instruction's bit pattern (N, immr and imms), if
+/// it's one that can be encoded.
+pub(super) fn decode_bit_mask(sf: bool, n: u32, immr: u32, imms: u32) -> Option<u64> {
+    if !sf && n != 0 {
+        return None;
+    }
+    let combined = n << 6 | (!imms & 0x3f);
+    if combined < 2 {
+        return None;
+    }
+    let len = 31 - combined.leading_zeros();
+    let size = 1u32 << len;
+    let levels = size - 1;
+    let (s, r) = (imms & levels, immr & levels);
+    if s == levels {
+        return None;
+    }
+
+    let ones = (1u64 << (s + 1)) - 1;
+    let element_mask = match size {
+        64 => u64::MAX,
+        _ => (1u64 << size) - 1,
+    };
+    let element = match r {
+        0 => ones,
+        _ => (ones >> r | ones << (size - r)) & element_mask,
+    };
+
+    let width = if sf { 64 } else { 32 };
+    let mut value = 0;
+    for i in (0..width).step_by(size as usize) {
+        value |= element << i;
+    }
+    Some(value)
+}
+
+/// Whether movz can write `value`: one 16-bit piece of it (or none) is set.
+fn is_movz_value(value: u64, width: u32) -> bool {
+    (0..width)
+        .step_by(16)
+        .any(|shift| value & !(0xffff << shift) == 0)
+}
+
+fn sign_extend(value: u64, width: u32) -> i64 {
+    match width {
+        32 => value as u32 as i32 as i64,
+        _ => value as i64,
+    }
+}
+
+fn logical_immediate(word: u32) -> Option<Decoded> {
+    let sf = bit(word, 31);
+    let width = if sf { 64 } else { 32 };
+    let rd = bits(word, 0, 5);
+    let rn = bits(word, 5, 5);
+    let value = decode_bit_mask(sf, bits(word, 22, 1), bits(word, 16, 6), bits(word, 10, 6))?;
+    let inverse = !value & if sf { u64::MAX } else { 0xffff_ffff };
+
+    match bits(word, 29, 2) {
+        0b00 => op(
+            "and",
+            vec![gpr_sp(sf, rd), gpr(sf, rn), Operand::Mask(value)],
+        ),
+        // A mov that movz or movn can't do (and that they do instead).
+        0b01 if rn == 31 && !is_movz_value(value, width) && !is_movz_value(inverse, width) => op(
+            "mov",
+            vec![
+                gpr_sp(sf, rd),
+                Operand::Immediate(sign_extend(value, width)),
+            ],
+        ),
+        0b01 => op(
+            "orr",
+            vec![gpr_sp(sf, rd), gpr(sf, rn), Operand::Mask(value)],
+        ),
+        0b10 => op(
+            "eor",
+            vec![gpr_sp(sf, rd), gpr(sf, rn), Operand::Mask(value)],
+        ),
+        _ if rd == 31 => op("tst", vec![gpr(sf, rn), Operand::Mask(value)]),
+        _ => op("ands", vec![gpr(sf, rd), gpr(sf, rn), Operand::Mask(value)]),
+    }
+}
+
+fn move_wide(word: u32) -> Option<Decoded> {
+    let sf = bit(word, 31);
+    let width = if sf { 64 } else { 32 };
+    let rd = bits(word, 0, 5);
+    let imm = bits(word, 5, 16) as u64;
+    let hw = bits(word, 21, 2);
+    if !sf && hw >= 2 {
+        return None;
+    }
+    let shift_amount = hw * 16;
+    let value = imm << shift_amount;
+    let mask = if sf { u64::MAX } else { 0xffff_ffff };
+
+    // As movz or movn, written as mov when it's the one that should be.
+    let mov = |value: u64| {
+        op(
+            "mov",
+            vec![gpr(sf, rd), Operand::Immediate(sign_extend(value, width))],
+        )
+    };
+    let mut operands = vec![gpr(sf, rd), Operand::Immediate(imm as i64)];
+    if hw != 0 {
+        operands.push(shift("lsl", shift_amount));
+    }
+
+    match bits(word, 29, 2) {
+        0b00 => {
+            let inverse = !value & mask;
+            match is_movz_value(inverse, width) || (imm == 0 && hw != 0) {
+                true => op("movn", operands),
+                false => mov(inverse),
+            }
+        }
+        0b10 => match imm == 0 && hw != 0 {
+            true => op("movz", operands),
+            false => mov(value),
+        },
+        0b11 => op("movk", operands),
+        _ => None,
+    }
+}
+
+fn bitfield(word: u32) -> Option<Decoded> {
+    let sf = bit(word, 31);
+    let size = if sf { 64 } else { 32 };
+    let rd = bits(word, 0, 5);
+    let rn = bits(word, 5, 5);
+    let imms = bits(word, 10, 6);
+    let immr = bits(word, 16, 6);
+    if bit(word, 22) != sf || !sf && (immr >= 32 || imms >= 32) {
+        return None;
+    }
+
+    let imm = |value: u32| Operand::Immediate(value as i64);
+    let (rd, rn_w, rn) = (gpr(sf, rd), gpr(false, rn), gpr(sf, rn));
+    // Inserting at lsb, or extracting from it, width bits.
+    let insert = |mnemonic| {
+        op(
+            mnemonic,
+            vec![
+                rd.clone(),
+                rn.clone(),
+                imm((size - immr) & (size - 1)),
+                imm(imms + 1),
+            ],
+        )
+    };
+    let extract = |mnemonic| {
+        op(
+            mnemonic,
+            vec![rd.clone(), rn.clone(), imm(immr), imm(imms - immr + 1)],
+        )
+    };
+
+    match bits(word, 29, 2) {
+        0b00 => match (immr, imms) {
+            (_, imms) if imms == size - 1 => op("asr", vec![rd, rn, imm(immr)]),
+            (0, 7) => op("sxtb", vec![rd, rn_w]),
+            (0, 15) => op("sxth", vec![rd, rn_w]),
+            (0, 31) => op("sxtw", vec![rd, rn_w]),
+            _ if imms < immr => insert("sbfiz"),
+            _ => extract("sbfx"),
+        },
+        0b01 => match imms < immr {
+            true => insert("bfi"),
+            false => extract("bfxil"),
+        },
+        0b10 => match (immr, imms) {
+            (_, imms) if imms != size - 1 && imms + 1 == immr => {
+                op("lsl", vec![rd, rn, imm(size - 1 - imms)])
+            }
+            (_, imms) if imms == size - 1 => op("lsr", vec![rd, rn, imm(immr)]),
+            (0, 7) if !sf => op("uxtb", vec![rd, rn_w]),
+            (0, 15) if !sf => op("uxth", vec![rd, rn_w]),
+            _ if imms < immr => insert("ubfiz"),
+            _ => extract("ubfx"),
+        },
+        _ => None,
+    }
+}
+
+// ----------------------------------
+// Branches, exceptions, and system.
+
+fn branch_system(word: u32, address: u64) -> Option<Decoded> {
+    let target = |offset: i64| Operand::Target(address.wrapping_add((offset << 2) as u64));
+    let rt = bits(word, 0, 5);
+
+    match bits(word, 29, 3) {
+        0b000 | 0b100 => {
+            let target = target(signed(bits(word, 0, 26), 26));
+            Some(match bit(word, 31) {
+                false => Decoded {
+                    flow: Flow::Jump(target_address(&target)),
+                    ..op("b", vec![target])?
+                },
+                true => Decoded {
+                    flow: Flow::Call(target_address(&target)),
+                    ..op("bl", vec![target])?
+                },
+            })
+        }
+        0b010 if bits(word, 24, 2) == 0 && !bit(word, 4) => {
+            let target = target(signed(bits(word, 5, 19), 19));
+            let mnemonic = format!("b.{}", CONDITIONS[bits(word, 0, 4) as usize]);
+            Some(Decoded {
+                flow: Flow::Branch(target_address(&target)),
+                ..op(&mnemonic, vec![target])?
+            })
+        }
+        0b001 | 0b101 => {
+            let (mnemonic, operands) = match bit(word, 25) {
+                false => {
+                    let sf = bit(word, 31);
+                    let target = target(signed(bits(word, 5, 19), 19));
+                    let mnemonic = if bit(word, 24) { "cbnz" } else { "cbz" };
+                    (mnemonic, vec![gpr(sf, rt), target])
+                }
+                true => {
+                    let number = bits(word, 31, 1) << 5 | bits(word, 19, 5);
+                    let target = target(signed(bits(word, 5, 14), 14));
+                    let mnemonic = if bit(word, 24) { "tbnz" } else { "tbz" };
+                    let operands = vec![
+                        gpr(number >= 32, rt),
+                        Operand::Immediate(number as i64),
+                        target,
+                    ];
+                    (mnemonic, operands)
+                }
+            };
+            let flow = Flow::Branch(target_address(operands.last()?));
+            Some(Decoded {
+                flow,
+                ..op(mnemonic, operands)?
+            })
+        }
+        0b110 => match bits(word, 24, 2) {
+            0b00 => system::exception(word),
+            0b01 if bits(word, 22, 2) == 0 => system::system(word),
+            0b10 | 0b11 => branch_register(word),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn target_address(operand: &Operand) -> u64 {
+    match operand {
+        Operand::Target(address) => *address,
+        _ => 0,
+    }
+}
+
+fn branch_register(word: u32) -> Option<Decoded> {
+    if bits(word, 16, 5) != 0b11111 || bits(word, 10, 6) != 0 || bits(word, 0, 5) != 0 {
+        return None;
+    }
+    let rn = bits(word, 5, 5);
+
+    let (mnemonic, operands, flow) = match bits(word, 21, 4) {
+        0b0000 => ("br", vec![gpr(true, rn)], Flow::IndirectJump),
+        0b0001 => ("blr", vec![gpr(true, rn)], Flow::IndirectCall),
+        0b0010 if rn == 30 => ("ret", vec![], Flow::Return),
+        0b0010 => ("ret", vec![gpr(true, rn)], Flow::Return),
+        0b0100 if rn == 31 => ("eret", vec![], Flow::Return),
+        0b0101 if rn == 31 => ("drps", vec![], Flow::Return),
+        _ => return None,
+    };
+    Some(Decoded {
+        flow,
+        ..op(mnemonic, operands)?
+    })
+}
+
+// --------------------------
+// Data processing, register.
+
+const SHIFTS: [&str; 4] = ["lsl", "lsr", "asr", "ror"];
+const EXTENDS: [&str; 8] = [
+    "uxtb", "uxth", "uxtw", "uxtx", "sxtb", "sxth", "sxtw", "sxtx",
+];
+
+fn data_register(word: u32) -> Option<Decoded> {
+    match (bit(word, 28), bits(word, 21, 4)) {
+        (false, op2) if op2 & 0b1000 == 0 => logical_register(word),
+        (false, op2) if op2 & 0b1001 == 0b1000 => add_shifted(word),
+        (false, _) => add_extended(word),
+        (true, 0b0000) => add_carry(word),
+        (true, 0b0010) => conditional_compare(word),
+        (true, 0b0100) => conditional_select(word),
+        (true, 0b0110) => match bit(word, 30) {
+            false => data_two_source(word),
+            true => data_one_source(word),
+        },
+        (true, op2) if op2 & 0b1000 != 0 => data_three_source(word),
+        _ => None,
+    }
+}
+
+/// A shifted register operand's shift, which isn't written when it's lsl #0.
+fn register_shift(kind: u32, amount: u32) -> Option<Operand> {
+    match (kind, amount) {
+        (0, 0) => None,
+        (kind, amount) => Some(shift(SHIFTS[kind as usize], amount)),
+    }
+}
+
+fn logical_register(word: u32) -> Option<Decoded> {
+    let sf = bit(word, 31);
+    let rd = bits(word, 0, 5);
+    let rn = bits(word, 5, 5);
+    let rm = bits(word, 16, 5);
+    let amount = bits(word, 10, 6);
+    if !sf && amount >= 32 {
+        return None;
+    }
+    let kind = bits(word, 22, 2);
+    let shift = register_shift(kind, amount);
+    let with_shift = |mut operands: Vec<Operand>| {
+        operands.extend(shift.clone());
+        operands
+    };
+
+    let opc = bits(word, 29, 2) << 1 | bits(word, 21, 1);
+    match opc {
+        0b010 if rn == 31 && shift.is_none() => op("mov", vec![gpr(sf, rd), gpr(sf, rm)]),
+        0b011 if rn == 31 => op("mvn", with_shift(vec![gpr(sf, rd), gpr(sf, rm)])),
+        0b110 if rd == 31 => op("tst", with_shift(vec![gpr(sf, rn), gpr(sf, rm)])),
+        _ => {
+            let mnemonic = ["and", "bic", "orr", "orn", "eor", "eon", "ands", "bics"][opc as usize];
+            op(
+                mnemonic,
+                with_shift(vec![gpr(sf, rd), gpr(sf, rn), gpr(sf, rm)]),
+            )
+        }
+    }
+}
+
+fn add_shifted(word: u32) -> Option<Decoded> {
+    let sf = bit(word, 31);
+    let (sub, flags) = (bit(word, 30), bit(word, 29));
+    let rd = bits(word, 0, 5);
+    let rn = bits(word, 5, 5);
+    let rm = bits(word, 16, 5);
+    let amount = bits(word, 10, 6);
+    let kind = bits(word, 22, 2);
+    if kind == 3 || !sf && amount >= 32 {
+        return None;
+    }
+    let shift = register_shift(kind, amount);
+    let with_shift = |mut operands: Vec<Operand>| {
+        operands.extend(shift.clone());
+        operands
+    };
+
+    match (sub, flags) {
+        (_, true) if rd == 31 => op(
+            if sub { "cmp" } else { "cmn" },
+            with_shift(vec![gpr(sf, rn), gpr(sf, rm)]),
+        ),
+        (true, _) if rn == 31 => op(
+            if flags { "negs" } else { "neg" },
+            with_shift(vec![gpr(sf, rd), gpr(sf, rm)]),
+        ),
+        _ => {
+            let mnemonic = ["add", "adds", "sub", "subs"][(sub as usize) << 1 | flags as usize];
+            op(
+                mnemonic,
+                with_shift(vec![gpr(sf, rd), gpr(sf, rn), gpr(sf, rm)]),
+            )
+        }
+    }
+}
+
+fn add_extended(word: u32) -> Option<Decoded> {
+    let sf = bit(word, 31);
+    let (sub, flags) = (bit(word, 30), bit(word, 29));
+    let rd = bits(word, 0, 5);
+    let rn = bits(word, 5, 5);
+    let rm = bits(word, 16, 5);
+    let amount = bits(word, 10, 3);
+    let option = bits(word, 13, 3);
+    if bits(word, 22, 2) != 0 || amount > 4 {
+        return None;
+    }
+
+    // With the stack pointer, the extension that does nothing is lsl.
+    let uses_sp = rn == 31 || rd == 31 && !flags;
+    let extend = match (option, amount) {
+        (0b011, 0) | (0b010, 0) if uses_sp && (option == 0b011) == sf => None,
+        (0b011, _) | (0b010, _) if uses_sp && (option == 0b011) == sf => Some(shift("lsl", amount)),
+        (_, 0) => Some(Operand::Shift(Shift {
+            kind: EXTENDS[option as usize],
+            amount: None,
+        })),
+        _ => Some(shift(EXTENDS[option as usize], amount)),
+    };
+    let rm = gpr(sf && option & 3 == 3, rm);
+
+    let mut operands = match (flags, rd) {
+        (true, 31) => vec![gpr_sp(sf, rn), rm],
+        (true, _) => vec![gpr(sf, rd), gpr_sp(sf, rn), rm],
+        (false, _) => vec![gpr_sp(sf, rd), gpr_sp(sf, rn), rm],
+    };
+    operands.extend(extend);
+
+    let mnemonic = match (sub, flags, rd) {
+        (false, true, 31) => "cmn",
+        (true, true, 31) => "cmp",
+        _ => ["add", "adds", "sub", "subs"][(sub as usize) << 1 | flags as usize],
+    };
+    op(mnemonic, operands)
+}
+
+fn add_carry(word: u32) -> Option<Decoded> {
+    if bits(word, 10, 6) != 0 {
+        return None;
+    }
+    let sf = bit(word, 31);
+    let (sub, flags) = (bit(word, 30), bit(word, 29));
+    let rd = bits(word, 0, 5);
+    let rn = bits(word, 5, 5);
+    let rm = bits(word, 16, 5);
+
+    match (sub, rn) {
+        (true, 31) => op(
+            if flags { "ngcs" } else { "ngc" },
+            vec![gpr(sf, rd), gpr(sf, rm)],
+        ),
+        _ => op(
+            ["adc", "adcs", "sbc", "sbcs"][(sub as usize) << 1 | flags as usize],
+            vec![gpr(sf, rd), gpr(sf, rn), gpr(sf, rm)],
+        ),
+    }
+}
+
+fn conditional_compare(word: u32) -> Option<Decoded> {
+    if !bit(word, 29) || bit(word, 10) || bit(word, 4) {
+        return None;
+    }
+    let sf = bit(word, 31);
+    let rn = bits(word, 5, 5);
+    let value = bits(word, 16, 5);
+    let second = match bit(word, 11) {
+        true => Operand::Immediate(value as i64),
+        false => gpr(sf, value),
+    };
+
+    op(
+        if bit(word, 30) { "ccmp" } else { "ccmn" },
+        vec![
+            gpr(sf, rn),
+            second,
+            Operand::Immediate(bits(word, 0, 4) as i64),
+            condition(bits(word, 12, 4)),
+        ],
+    )
+}
+
+fn conditional_select(word: u32) -> Option<Decoded> {
+    if bit(word, 29) || bit(word, 11) {
+        return None;
+    }
+    let sf = bit(word, 31);
+    let rd = bits(word, 0, 5);
+    let rn = bits(word, 5, 5);
+    let rm = bits(word, 16, 5);
+    let cond = bits(word, 12, 4);
+    // The aliases test the inverse condition, which can't be al or nv.
+    let inverse = condition(cond ^ 1);
+    let aliased = rn == rm && cond >> 1 != 0b111;
+
+    let kind = bits(word, 30, 1) << 1 | bits(word, 10, 1);
+    match kind {
+        0b01 if aliased && rn == 31 => op("cset", vec![gpr(sf, rd), inverse]),
+        0b10 if aliased && rn == 31 => op("csetm", vec![gpr(sf, rd), inverse]),
+        0b01..=0b11 if aliased => op(
+            ["", "cinc", "cinv", "cneg"][kind as usize],
+            vec![gpr(sf, rd), gpr(sf, rn), inverse],
+        ),
+        _ => op(
+            ["csel", "csinc", "csinv", "csneg"][kind as usize],
+            vec![gpr(sf, rd), gpr(sf, rn), gpr(sf, rm), condition(cond)],
+        ),
+    }
+}
+
+fn data_two_source(word: u32) -> Option<Decoded> {
+    if bit(word, 29) {
+        return None;
+    }
+    let sf = bit(word, 31);
+    let mnemonic = match bits(word, 10, 6) {
+        0b000010 => "udiv",
+        0b000011 => "sdiv",
+        0b001000 => "lsl",
+        0b001001 => "lsr",
+        0b001010 => "asr",
+        0b001011 => "ror",
+        _ => return None,
+    };
+    op(
+        mnemonic,
+        vec![
+            gpr(sf, bits(word, 0, 5)),
+            gpr(sf, bits(word, 5, 5)),
+            gpr(sf, bits(word, 16, 5)),
+        ],
+    )
+}
+
+fn data_one_source(word: u32) -> Option<Decoded> {
+    if bit(word, 29) || bits(word, 16, 5) != 0 {
+        return None;
+    }
+    let sf = bit(word, 31);
+    let mnemonic = match (bits(word, 10, 6), sf) {
+        (0b000000, _) => "rbit",
+        (0b000001, _) => "rev16",
+        (0b000010, false) => "rev",
+        (0b000010, true) => "rev32",
+        (0b000011, true) => "rev",
+        (0b000100, _) => "clz",
+        (0b000101, _) => "cls",
+        _ => return None,
+    };
+    op(
+        mnemonic,
+        vec![gpr(sf, bits(word, 0, 5)), gpr(sf, bits(word, 5, 5))],
+    )
+}
+
+fn data_three_source(word: u32) -> Option<Decoded> {
+    let sf = bit(word, 31);
+    let rd = bits(word, 0, 5);
+    let rn = bits(word, 5, 5);
+    let ra = bits(word, 10, 5);
+    let rm = bits(word, 16, 5);
+    let subtract = bit(word, 15);
+    if bits(word, 29, 2) != 0 {
+        return None;
+    }
+
+    let (mnemonic, alias, long) = match (bits(word, 21, 3), subtract) {
+        (0b000, false) => ("madd", "mul", false),
+        (0b000, true) => ("msub", "mneg", false),
+        (0b001, false) if sf => ("smaddl", "smull", true),
+        (0b001, true) if sf => ("smsubl", "smnegl", true),
+        (0b101, false) if sf => ("umaddl", "umull", true),
+        (0b101, true) if sf => ("umsubl", "umnegl", true),
+        (0b010, false) if sf => {
+            return op("smulh", vec![gpr(true, rd), gpr(true, rn), gpr(true, rm)]);
+        }
+        (0b110, false) if sf => {
+            return op("umulh", vec![gpr(true, rd), gpr(true, rn), gpr(true, rm)]);
+        }
+        _ => return None,
+    };
+
+    let sources = !long && sf;
+    let mut operands = vec![gpr(sf, rd), gpr(sources, rn), gpr(sources, rm)];
+    match ra {
+        31 => op(alias, operands),
+        _ => {
+            operands.push(gpr(sf, ra));
+            op(mnemonic, operands)
+        }
+    }
+}
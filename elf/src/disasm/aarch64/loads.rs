@@ -0,0 +1,372 @@
+/// Loads and stores: of single registers (with an immediate offset, an
+/// index register, or pre- or post-indexing), of pairs, exclusive and
+/// ordered ones, loads of literals, and the SIMD loads and stores of
+/// structures.
+///
+use super::decode::*;
+use super::*;
+
+pub(super) fn load_store(word: u32, address: u64) -> Option<Decoded> {
+    match (bits(word, 27, 3), bits(word, 24, 2)) {
+        (0b001, _) if !bit(word, 26) && bits(word, 24, 2) == 0 => exclusive(word),
+        (0b001, _) if bit(word, 26) && !bit(word, 31) && !bit(word, 29) => structures(word),
+        (0b011, 0b00) => literal(word, address),
+        (0b101, _) => pair(word),
+        (0b111, 0b00) if !bit(word, 21) => register_immediate(word),
+        (0b111, 0b00) if bits(word, 10, 2) == 0b10 => register_offset(word),
+        (0b111, 0b01) => register_unsigned(word),
+        _ => None,
+    }
+}
+
+/// The base register of an address, where 31 is the stack pointer.
+fn base(number: u32) -> Register {
+    match number {
+        31 => Register::Sp,
+        _ => Register::X(number as u8),
+    }
+}
+
+fn memory(rn: u32, offset: i64, writeback: bool) -> Operand {
+    Operand::Memory {
+        base: base(rn),
+        index: None,
+        offset,
+        writeback,
+    }
+}
+
+fn exclusive(word: u32) -> Option<Decoded> {
+    let size = bits(word, 30, 2);
+    let (o2, load, o1, o0) = (bit(word, 23), bit(word, 22), bit(word, 21), bit(word, 15));
+    let rs = bits(word, 16, 5);
+    let rt2 = bits(word, 10, 5);
+    let rn = bits(word, 5, 5);
+    let rt = bits(word, 0, 5);
+    let sf = size == 3;
+    let suffix = ["b", "h", "", ""][size as usize];
+    let address = memory(rn, 0, false);
+
+    let (name, operands) = match (o2, load, o1) {
+        (false, false, false) => (
+            if o0 { "stlxr" } else { "stxr" },
+            vec![gpr(false, rs), gpr(sf, rt), address],
+        ),
+        (false, true, false) => (
+            if o0 { "ldaxr" } else { "ldxr" },
+            vec![gpr(sf, rt), address],
+        ),
+        (false, false, true) if size >= 2 => (
+            if o0 { "stlxp" } else { "stxp" },
+            vec![gpr(false, rs), gpr(sf, rt), gpr(sf, rt2), address],
+        ),
+        (false, true, true) if size >= 2 => (
+            if o0 { "ldaxp" } else { "ldxp" },
+            vec![gpr(sf, rt), gpr(sf, rt2), address],
+        ),
+        (true, false, false) if o0 => ("stlr", vec![gpr(sf, rt), address]),
+        (true, true, false) if o0 => ("ldar", vec![gpr(sf, rt), address]),
+        _ => return None,
+    };
+    let suffix = if o1 { "" } else { suffix };
+    op(&format!("{name}{suffix}"), operands)
+}
+
+/// A prefetch operation, by name if it has one, as in `pldl1keep`.
+fn prefetch(rt: u32) -> Operand {
+    let kind = ["pld", "pli", "pst", ""][bits(rt, 3, 2) as usize];
+    let target = bits(rt, 1, 2);
+    match (kind, target) {
+        ("", _) | (_, 3) => Operand::Immediate(rt as i64),
+        _ => {
+            let policy = if bit(rt, 0) { "strm" } else { "keep" };
+            Operand::Name(format!("{kind}l{}{policy}", target + 1))
+        }
+    }
+}
+
+fn literal(word: u32, address: u64) -> Option<Decoded> {
+    let rt = bits(word, 0, 5);
+    let target = Operand::Target(address.wrapping_add((signed(bits(word, 5, 19), 19) << 2) as u64));
+
+    let (mnemonic, register) = match (bit(word, 26), bits(word, 30, 2)) {
+        (false, 0b00) => ("ldr", gpr(false, rt)),
+        (false, 0b01) => ("ldr", gpr(true, rt)),
+        (false, 0b10) => ("ldrsw", gpr(true, rt)),
+        (false, _) => ("prfm", prefetch(rt)),
+        (true, 0b00) => ("ldr", fp(rt, 4)),
+        (true, 0b01) => ("ldr", fp(rt, 8)),
+        (true, 0b10) => ("ldr", fp(rt, 16)),
+        (true, _) => return None,
+    };
+    op(mnemonic, vec![register, target])
+}
+
+fn pair(word: u32) -> Option<Decoded> {
+    let opc = bits(word, 30, 2);
+    let simd = bit(word, 26);
+    let load = bit(word, 22);
+    let kind = bits(word, 23, 2);
+    let rt2 = bits(word, 10, 5);
+    let rn = bits(word, 5, 5);
+    let rt = bits(word, 0, 5);
+
+    let (name, size, register): (&str, u8, fn(u32) -> Operand) = match (simd, opc) {
+        (false, 0b00) => ("p", 4, |number| gpr(false, number)),
+        (false, 0b10) => ("p", 8, |number| gpr(true, number)),
+        (false, 0b01) if load && kind != 0 => ("psw", 4, |number| gpr(true, number)),
+        (true, 0b00) => ("p", 4, |number| fp(number, 4)),
+        (true, 0b01) => ("p", 8, |number| fp(number, 8)),
+        (true, 0b10) => ("p", 16, |number| fp(number, 16)),
+        _ => return None,
+    };
+    let mnemonic = match (kind, load) {
+        (0b00, false) => "stn".to_owned(),
+        (0b00, true) => "ldn".to_owned(),
+        (_, false) => "st".to_owned(),
+        (_, true) => "ld".to_owned(),
+    } + name;
+
+    let offset = signed(bits(word, 15, 7), 7) * size as i64;
+    let mut operands = vec![register(rt), register(rt2)];
+    match kind {
+        0b01 => operands.extend([memory(rn, 0, false), Operand::Immediate(offset)]),
+        0b11 => operands.push(memory(rn, offset, true)),
+        _ => operands.push(memory(rn, offset, false)),
+    }
+    op(&mnemonic, operands)
+}
+
+/// What a load or store of one register is, by its size, whether it's a
+/// SIMD and floating point register, and opc: its mnemonic (without the
+/// `ld` or `st`, or `prfm`), whether it loads, the register (or prefetch
+/// operation), and the size it scales offsets by.
+fn single(word: u32) -> Option<(&'static str, bool, Operand, u8)> {
+    let rt = bits(word, 0, 5);
+    let size = bits(word, 30, 2);
+    let opc = bits(word, 22, 2);
+
+    Some(match (bit(word, 26), size, opc) {
+        (false, 0b00, 0b00) => ("rb", false, gpr(false, rt), 1),
+        (false, 0b00, 0b01) => ("rb", true, gpr(false, rt), 1),
+        (false, 0b00, 0b10) => ("rsb", true, gpr(true, rt), 1),
+        (false, 0b00, 0b11) => ("rsb", true, gpr(false, rt), 1),
+        (false, 0b01, 0b00) => ("rh", false, gpr(false, rt), 2),
+        (false, 0b01, 0b01) => ("rh", true, gpr(false, rt), 2),
+        (false, 0b01, 0b10) => ("rsh", true, gpr(true, rt), 2),
+        (false, 0b01, 0b11) => ("rsh", true, gpr(false, rt), 2),
+        (false, 0b10, 0b00) => ("r", false, gpr(false, rt), 4),
+        (false, 0b10, 0b01) => ("r", true, gpr(false, rt), 4),
+        (false, 0b10, 0b10) => ("rsw", true, gpr(true, rt), 4),
+        (false, 0b11, 0b00) => ("r", false, gpr(true, rt), 8),
+        (false, 0b11, 0b01) => ("r", true, gpr(true, rt), 8),
+        (false, 0b11, 0b10) => ("prfm", false, prefetch(rt), 8),
+        (true, 0b00, 0b10) => ("r", false, fp(rt, 16), 16),
+        (true, 0b00, 0b11) => ("r", true, fp(rt, 16), 16),
+        (true, size, 0b00 | 0b01) => {
+            let bytes = 1 << size;
+            ("r", opc == 0b01, fp(rt, bytes), bytes)
+        }
+        _ => return None,
+    })
+}
+
+/// The mnemonic for `single`'s kind of load or store, with `infix` (`u`
+/// for unscaled offsets, `t` for unprivileged) after the `ld` or `st`.
+fn mnemonic(kind: &str, load: bool, infix: &str) -> String {
+    match kind {
+        "prfm" if infix == "u" => "prfum".to_owned(),
+        "prfm" => kind.to_owned(),
+        _ => {
+            let (r, rest) = kind.split_at(1);
+            format!("{}{infix}{r}{rest}", if load { "ld" } else { "st" })
+        }
+    }
+}
+
+fn register_immediate(word: u32) -> Option<Decoded> {
+    let (kind, load, register, _) = single(word)?;
+    let rn = bits(word, 5, 5);
+    let offset = signed(bits(word, 12, 9), 9);
+    let simd = bit(word, 26);
+
+    match bits(word, 10, 2) {
+        0b00 => op(
+            &mnemonic(kind, load, "u"),
+            vec![register, memory(rn, offset, false)],
+        ),
+        0b01 if kind != "prfm" => op(
+            &mnemonic(kind, load, ""),
+            vec![register, memory(rn, 0, false), Operand::Immediate(offset)],
+        ),
+        0b10 if kind != "prfm" && !simd => op(
+            &mnemonic(kind, load, "t"),
+            vec![register, memory(rn, offset, false)],
+        ),
+        0b11 if kind != "prfm" => op(
+            &mnemonic(kind, load, ""),
+            vec![register, memory(rn, offset, true)],
+        ),
+        _ => None,
+    }
+}
+
+fn register_offset(word: u32) -> Option<Decoded> {
+    let (kind, load, register, size) = single(word)?;
+    let rn = bits(word, 5, 5);
+    let rm = bits(word, 16, 5);
+    let option = bits(word, 13, 3);
+    let scaled = bit(word, 12);
+    if option & 0b010 == 0 {
+        return None;
+    }
+
+    let amount = scaled.then(|| size.trailing_zeros() as u8);
+    let index = match option {
+        0b011 => Register::X(rm as u8),
+        _ if option & 1 != 0 => Register::X(rm as u8),
+        _ => Register::W(rm as u8),
+    };
+    let shift = match (option, amount) {
+        (0b011, None) => None,
+        (0b011, amount) => Some(Shift {
+            kind: "lsl",
+            amount,
+        }),
+        (option, amount) => Some(Shift {
+            kind: ["", "", "uxtw", "", "", "", "sxtw", "sxtx"][option as usize],
+            amount,
+        }),
+    };
+    op(
+        &mnemonic(kind, load, ""),
+        vec![
+            register,
+            Operand::Memory {
+                base: base(rn),
+                index: Some((index, shift)),
+                offset: 0,
+                writeback: false,
+            },
+        ],
+    )
+}
+
+fn register_unsigned(word: u32) -> Option<Decoded> {
+    let (kind, load, register, size) = single(word)?;
+    let rn = bits(word, 5, 5);
+    let offset = bits(word, 10, 12) as i64 * size as i64;
+    op(
+        &mnemonic(kind, load, ""),
+        vec![register, memory(rn, offset, false)],
+    )
+}
+
+/// `count` consecutive registers, from `first` (wrapping around after v31).
+fn list(first: u32, count: u32, register: impl Fn(u8) -> Register) -> Vec<Register> {
+    (0..count)
+        .map(|i| register(((first + i) % 32) as u8))
+        .collect()
+}
+
+/// The SIMD loads and stores of multiple structures, of single ones (to
+/// and from one element of each register), and the loads that replicate
+/// one to all the elements.
+fn structures(word: u32) -> Option<Decoded> {
+    let q = bits(word, 30, 1);
+    let load = bit(word, 22);
+    let post = bit(word, 23);
+    let rm = bits(word, 16, 5);
+    let opcode = bits(word, 12, 4);
+    let size = bits(word, 10, 2);
+    let rn = bits(word, 5, 5);
+    let rt = bits(word, 0, 5);
+    let prefix = if load { "ld" } else { "st" };
+
+    if !post && rm != 0 {
+        return None;
+    }
+
+    let (mnemonic, registers, index, bytes) = match bit(word, 24) {
+        false => {
+            if bit(word, 21) {
+                return None;
+            }
+            let (structure, count) = match opcode {
+                0b0000 => (4, 4),
+                0b0010 => (1, 4),
+                0b0100 => (3, 3),
+                0b0110 => (1, 3),
+                0b0111 => (1, 1),
+                0b1000 => (2, 2),
+                0b1010 => (1, 2),
+                _ => return None,
+            };
+            if structure > 1 && size == 3 && q == 0 {
+                return None;
+            }
+            let arrangement = ARRANGEMENTS[(size << 1 | q) as usize];
+            let registers = list(rt, count, |number| Register::Vector {
+                number,
+                arrangement,
+            });
+            let mnemonic = format!("{prefix}{structure}");
+            (mnemonic, registers, None, (count * 8) << q)
+        }
+        true => {
+            let r = bits(word, 21, 1);
+            let s = bits(word, 12, 1);
+            let opcode = bits(word, 13, 3);
+            let count = (opcode & 1) << 1 | r;
+            let count = count + 1;
+
+            let (element, index) = match opcode >> 1 {
+                0b00 => (1, q << 3 | s << 2 | size),
+                0b01 if size & 1 == 0 => (2, q << 2 | s << 1 | size >> 1),
+                0b10 if size == 0 => (4, q << 1 | s),
+                0b10 if size == 1 && s == 0 => (8, q),
+                0b11 if load && s == 0 => {
+                    let arrangement = ARRANGEMENTS[(size << 1 | q) as usize];
+                    let registers = list(rt, count, |number| Register::Vector {
+                        number,
+                        arrangement,
+                    });
+                    let mnemonic = format!("ld{count}r");
+                    let bytes = count << size;
+                    return structure_operands(mnemonic, registers, None, bytes, rn, rm, post);
+                }
+                _ => return None,
+            };
+            let registers = list(rt, count, |number| Register::Elements {
+                number,
+                size: element as u8,
+            });
+            let mnemonic = format!("{prefix}{count}");
+            (mnemonic, registers, Some(index as u8), count * element)
+        }
+    };
+
+    structure_operands(mnemonic, registers, index, bytes, rn, rm, post)
+}
+
+/// The operands of a SIMD structure load or store: the registers, the
+/// base, and after it what post-indexing adds, the size of what's loaded
+/// (`bytes`) or a register.
+fn structure_operands(
+    mnemonic: String,
+    registers: Vec<Register>,
+    index: Option<u8>,
+    bytes: u32,
+    rn: u32,
+    rm: u32,
+    post: bool,
+) -> Option<Decoded> {
+    let mut operands = vec![Operand::List { registers, index }, memory(rn, 0, false)];
+    if post {
+        operands.push(match rm {
+            31 => Operand::Immediate(bytes as i64),
+            _ => gpr(true, rm),
+        });
+    }
+    op(&mnemonic, operands)
+}
@@ -0,0 +1,230 @@
+/// A decoder for AArch64 (A64) instructions of the base ARMv8.0
+/// architecture: the integer data processing, load and store, branch,
+/// system, floating point and Advanced SIMD classes. Instructions are
+/// written as llvm-objdump writes them, with the aliases it prefers (`mov`,
+/// `cmp`, `lsl` and so on), so that the two can be compared line for line.
+///
+mod decode;
+mod loads;
+mod simd;
+mod system;
+mod system_registers;
+
+pub use decode::decode;
+
+use crate::disasm::Flow;
+
+/// Every instruction is this long.
+pub const INSTRUCTION_LEN: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Register {
+    /// x0 to x30, and xzr as 31.
+    X(u8),
+    /// w0 to w30, and wzr as 31.
+    W(u8),
+    Sp,
+    Wsp,
+    /// A SIMD and floating point register as a scalar: b, h, s, d or q,
+    /// by size in bytes.
+    Fp {
+        number: u8,
+        size: u8,
+    },
+    /// A SIMD register as a vector, with its arrangement, as in `v0.4s`.
+    Vector {
+        number: u8,
+        arrangement: &'static str,
+    },
+    /// A SIMD register's elements of a size, with no count: in lists of
+    /// registers that an index follows, as in `{ v0.s, v1.s }[1]`.
+    Elements {
+        number: u8,
+        size: u8,
+    },
+    /// One element of a vector, as in `v0.s[1]`.
+    Element {
+        number: u8,
+        size: u8,
+        index: u8,
+    },
+}
+
+/// The letter for an element or scalar of `size` bytes.
+fn size_letter(size: u8) -> char {
+    match size {
+        1 => 'b',
+        2 => 'h',
+        4 => 's',
+        8 => 'd',
+        _ => 'q',
+    }
+}
+
+impl Register {
+    pub fn name(&self) -> String {
+        match *self {
+            Register::X(31) => "xzr".to_owned(),
+            Register::X(number) => format!("x{number}"),
+            Register::W(31) => "wzr".to_owned(),
+            Register::W(number) => format!("w{number}"),
+            Register::Sp => "sp".to_owned(),
+            Register::Wsp => "wsp".to_owned(),
+            Register::Fp { number, size } => format!("{}{number}", size_letter(size)),
+            Register::Vector {
+                number,
+                arrangement,
+            } => format!("v{number}.{arrangement}"),
+            Register::Elements { number, size } => format!("v{number}.{}", size_letter(size)),
+            Register::Element {
+                number,
+                size,
+                index,
+            } => format!("v{number}.{}[{index}]", size_letter(size)),
+        }
+    }
+}
+
+/// A shift or extension of a register operand, as in `lsl #12` or `sxtw`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Shift {
+    pub kind: &'static str,
+    pub amount: Option<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Operand {
+    Register(Register),
+    /// An immediate, written in decimal.
+    Immediate(i64),
+    /// A bit pattern (for the logical instructions), or a number written
+    /// in hex (for supervisor calls and breakpoints).
+    Mask(u64),
+    /// A floating point immediate.
+    Float(f64),
+    /// A shift or extension of the register before it.
+    Shift(Shift),
+    /// A base register, with an index register or an offset; the
+    /// offset is written for `writeback` (pre-indexing) even when it's 0.
+    /// The offset of post-indexing is an operand of its own, after this.
+    Memory {
+        base: Register,
+        index: Option<(Register, Option<Shift>)>,
+        offset: i64,
+        writeback: bool,
+    },
+    /// The address that a branch, or a load of a literal, refers to.
+    Target(u64),
+    /// A list of consecutive SIMD registers, and the element of each
+    /// they load or store.
+    List {
+        registers: Vec<Register>,
+        index: Option<u8>,
+    },
+    /// Anything else, written as it is: conditions, system registers and
+    /// operations, barrier options and prefetch operations.
+    Name(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Instruction {
+    pub address: u64,
+    /// `INSTRUCTION_LEN`, or less for the bytes left at the end of a
+    /// section too short to hold an instruction.
+    pub len: usize,
+    /// The instruction as the number it is, little endian.
+    pub word: u32,
+    pub mnemonic: String,
+    pub operands: Vec<Operand>,
+    /// What llvm-objdump notes after some instructions, such as the value
+    /// of a shifted immediate.
+    pub comment: Option<String>,
+    pub flow: Flow,
+}
+
+/// The column llvm-objdump lines comments up at, counting from the tab
+/// before the mnemonic.
+const COMMENT_COLUMN: usize = 40;
+
+impl Instruction {
+    /// Whether this is an instruction that couldn't be decoded.
+    pub fn is_bad(&self) -> bool {
+        self.mnemonic == "<unknown>"
+    }
+
+    /// Writes the instruction as llvm-objdump does. `symbolize` names an
+    /// address, as in `main+0x1c`, for branch targets.
+    pub fn format(&self, symbolize: &dyn Fn(u64) -> Option<String>) -> String {
+        if self.operands.is_empty() {
+            return self.mnemonic.clone();
+        }
+        let operands: Vec<String> = self
+            .operands
+            .iter()
+            .map(|operand| format_operand(operand, symbolize))
+            .collect();
+
+        let mut text = format!("{}\t{}", self.mnemonic, operands.join(", "));
+
+        if let Some(comment) = &self.comment {
+            let column =
+                (8 + self.mnemonic.len()) / 8 * 8 + 8 + text.len() - self.mnemonic.len() - 1;
+            let padding = COMMENT_COLUMN.saturating_sub(column).max(1);
+            text.push_str(&format!("{:padding$}// {comment}", ""));
+        }
+        text
+    }
+}
+
+fn format_shift(shift: &Shift) -> String {
+    match shift.amount {
+        Some(amount) => format!("{} #{amount}", shift.kind),
+        None => shift.kind.to_owned(),
+    }
+}
+
+fn format_operand(operand: &Operand, symbolize: &dyn Fn(u64) -> Option<String>) -> String {
+    match operand {
+        Operand::Register(register) => register.name(),
+        Operand::Immediate(value) => format!("#{value}"),
+        Operand::Mask(0) => "#0".to_owned(),
+        Operand::Mask(value) => format!("#{value:#x}"),
+        Operand::Float(value) => format!("#{value:.8}"),
+        Operand::Shift(shift) => format_shift(shift),
+        Operand::Memory {
+            base,
+            index,
+            offset,
+            writeback,
+        } => {
+            let mut text = format!("[{}", base.name());
+            if let Some((register, shift)) = index {
+                text.push_str(&format!(", {}", register.name()));
+                if let Some(shift) = shift {
+                    text.push_str(&format!(", {}", format_shift(shift)));
+                }
+            }
+            if *offset != 0 || *writeback {
+                text.push_str(&format!(", #{offset}"));
+            }
+            text.push(']');
+            if *writeback {
+                text.push('!');
+            }
+            text
+        }
+        Operand::Target(address) => match symbolize(*address) {
+            Some(name) => format!("{address:#x} <{name}>"),
+            None => format!("{address:#x}"),
+        },
+        Operand::List { registers, index } => {
+            let names: Vec<String> = registers.iter().map(Register::name).collect();
+            let mut text = format!("{{ {} }}", names.join(", "));
+            if let Some(index) = index {
+                text.push_str(&format!("[{index}]"));
+            }
+            text
+        }
+        Operand::Name(name) => name.clone(),
+    }
+}
@@ -0,0 +1,1231 @@
+/// Floating point and Advanced SIMD: scalar floating point arithmetic,
+/// comparisons and conversions, and the vector instructions and their
+/// scalar forms. Half precision arithmetic (an ARMv8.2 extension) isn't
+/// decoded, only conversions to and from it.
+///
+use super::decode::*;
+use super::*;
+
+pub(super) fn simd_fp(word: u32) -> Option<Decoded> {
+    match (bit(word, 31), bit(word, 30), bit(word, 28)) {
+        (_, false, true) => floating_point(word),
+        (false, _, false) => advanced_simd(word),
+        (false, true, true) => advanced_simd_scalar(word),
+        _ => None,
+    }
+}
+
+/// A vector register, with elements of 2^`size` bytes filling the low 64
+/// bits or (with `q`) all 128.
+fn vector(number: u32, size: u32, q: u32) -> Operand {
+    Operand::Register(Register::Vector {
+        number: number as u8,
+        arrangement: ARRANGEMENTS[(size << 1 | q) as usize],
+    })
+}
+
+/// A scalar of 2^`size` bytes.
+fn scalar(number: u32, size: u32) -> Operand {
+    fp(number, 1 << size)
+}
+
+fn element(number: u32, size: u32, index: u32) -> Operand {
+    Operand::Register(Register::Element {
+        number: number as u8,
+        size: 1 << size,
+        index: index as u8,
+    })
+}
+
+/// Adds the `2` that the instructions working on the upper half of a
+/// vector (`q`) have, as in `xtn2`.
+fn upper(mnemonic: &str, q: u32) -> String {
+    match q {
+        1 => format!("{mnemonic}2"),
+        _ => mnemonic.to_owned(),
+    }
+}
+
+/// The 8-bit floating point immediate of fmov: a sign, a 3-bit exponent
+/// and a 4-bit fraction.
+fn expand_float(imm8: u32) -> f64 {
+    let fraction = 1.0 + (imm8 & 0xf) as f64 / 16.0;
+    let exponent = match bit(imm8, 6) {
+        true => bits(imm8, 4, 2) as i32 - 3,
+        false => bits(imm8, 4, 2) as i32 + 1,
+    };
+    let value = fraction * 2f64.powi(exponent);
+    if bit(imm8, 7) {
+        -value
+    } else {
+        value
+    }
+}
+
+/// The 64-bit immediate of movi, each bit of which is a byte of ones or
+/// zeros, written with a fixed width as llvm-objdump does (with no `0x`
+/// for 0).
+fn byte_mask(imm8: u32) -> Operand {
+    let value = (0..8)
+        .filter(|&byte| bit(imm8, byte))
+        .fold(0u64, |value, byte| value | 0xff << (8 * byte));
+    Operand::Name(match value {
+        0 => format!("#{:016}", 0),
+        _ => format!("#{value:#016x}"),
+    })
+}
+
+/// The comparisons with zero write it as an operand.
+fn zero(float: bool) -> Operand {
+    Operand::Name(if float { "#0.0" } else { "#0" }.to_owned())
+}
+
+// ---------------------
+// Scalar floating point.
+
+fn floating_point(word: u32) -> Option<Decoded> {
+    let sf = bit(word, 31);
+    let kind = bits(word, 22, 2);
+    let rm = bits(word, 16, 5);
+    let rn = bits(word, 5, 5);
+    let rd = bits(word, 0, 5);
+    if bit(word, 29) {
+        return None;
+    }
+    // Single, double or half precision; 0b10 is only a vector's upper
+    // half, for fmov.
+    let size = match kind {
+        0b00 => 2,
+        0b01 => 3,
+        0b11 => 1,
+        _ if !bit(word, 24) && bit(word, 21) && bits(word, 10, 6) == 0 => {
+            return integer_conversion(word, None);
+        }
+        _ => return None,
+    };
+
+    if bit(word, 24) {
+        if sf || size == 1 {
+            return None;
+        }
+        let mnemonic = ["fmadd", "fmsub", "fnmadd", "fnmsub"]
+            [(bits(word, 21, 1) << 1 | bits(word, 15, 1)) as usize];
+        let ra = bits(word, 10, 5);
+        return op(
+            mnemonic,
+            vec![
+                scalar(rd, size),
+                scalar(rn, size),
+                scalar(rm, size),
+                scalar(ra, size),
+            ],
+        );
+    }
+    if !bit(word, 21) {
+        return fixed_conversion(word, size);
+    }
+    if bits(word, 10, 6) == 0 {
+        return integer_conversion(word, Some(size));
+    }
+    if sf {
+        return None;
+    }
+    if bits(word, 10, 5) == 0b10000 {
+        return one_source(word, size);
+    }
+    // Only conversions take half precision operands.
+    if size == 1 {
+        return None;
+    }
+
+    if bits(word, 10, 4) == 0b1000 {
+        // Compares.
+        if bits(word, 14, 2) != 0 || bits(word, 0, 3) != 0 {
+            return None;
+        }
+        let mnemonic = if bit(word, 4) { "fcmpe" } else { "fcmp" };
+        let other = match bit(word, 3) {
+            true => zero(true),
+            false => scalar(rm, size),
+        };
+        return op(mnemonic, vec![scalar(rn, size), other]);
+    }
+    if bits(word, 10, 3) == 0b100 {
+        if bits(word, 5, 5) != 0 {
+            return None;
+        }
+        let value = expand_float(bits(word, 13, 8));
+        return op("fmov", vec![scalar(rd, size), Operand::Float(value)]);
+    }
+
+    match bits(word, 10, 2) {
+        0b01 => op(
+            if bit(word, 4) { "fccmpe" } else { "fccmp" },
+            vec![
+                scalar(rn, size),
+                scalar(rm, size),
+                Operand::Immediate(bits(word, 0, 4) as i64),
+                condition(bits(word, 12, 4)),
+            ],
+        ),
+        0b10 => {
+            let mnemonic = [
+                "fmul", "fdiv", "fadd", "fsub", "fmax", "fmin", "fmaxnm", "fminnm", "fnmul",
+            ]
+            .get(bits(word, 12, 4) as usize)?;
+            op(
+                mnemonic,
+                vec![scalar(rd, size), scalar(rn, size), scalar(rm, size)],
+            )
+        }
+        0b11 => op(
+            "fcsel",
+            vec![
+                scalar(rd, size),
+                scalar(rn, size),
+                scalar(rm, size),
+                condition(bits(word, 12, 4)),
+            ],
+        ),
+        _ => None,
+    }
+}
+
+fn one_source(word: u32, size: u32) -> Option<Decoded> {
+    let rn = bits(word, 5, 5);
+    let rd = bits(word, 0, 5);
+    let opcode = bits(word, 15, 6);
+
+    if let 0b000100 | 0b000101 | 0b000111 = opcode {
+        let to = [2, 3, 0, 1][(opcode & 3) as usize];
+        if to == size {
+            return None;
+        }
+        return op("fcvt", vec![scalar(rd, to), scalar(rn, size)]);
+    }
+    if size == 1 {
+        return None;
+    }
+    let mnemonic = match opcode {
+        0b000000 => "fmov",
+        0b000001 => "fabs",
+        0b000010 => "fneg",
+        0b000011 => "fsqrt",
+        0b001000 => "frintn",
+        0b001001 => "frintp",
+        0b001010 => "frintm",
+        0b001011 => "frintz",
+        0b001100 => "frinta",
+        0b001110 => "frintx",
+        0b001111 => "frinti",
+        _ => return None,
+    };
+    op(mnemonic, vec![scalar(rd, size), scalar(rn, size)])
+}
+
+/// Conversions between floating point and integers, and fmov between the
+/// two kinds of register. `size` is None for the upper half of a vector.
+fn integer_conversion(word: u32, size: Option<u32>) -> Option<Decoded> {
+    let sf = bit(word, 31);
+    let rmode = bits(word, 19, 2);
+    let opcode = bits(word, 16, 3);
+    let rn = bits(word, 5, 5);
+    let rd = bits(word, 0, 5);
+
+    let Some(size) = size else {
+        return match (sf, rmode, opcode) {
+            (true, 0b01, 0b110) => op("fmov", vec![gpr(true, rd), element(rn, 3, 1)]),
+            (true, 0b01, 0b111) => op("fmov", vec![element(rd, 3, 1), gpr(true, rn)]),
+            _ => None,
+        };
+    };
+    if size == 1 {
+        return None;
+    }
+
+    match (rmode, opcode) {
+        (_, 0b000 | 0b001) => {
+            let mnemonic = ["fcvtn", "fcvtp", "fcvtm", "fcvtz"][rmode as usize];
+            let signed = if opcode == 0 { "s" } else { "u" };
+            op(
+                &format!("{mnemonic}{signed}"),
+                vec![gpr(sf, rd), scalar(rn, size)],
+            )
+        }
+        (0b00, 0b010 | 0b011) => op(
+            if opcode == 0b010 { "scvtf" } else { "ucvtf" },
+            vec![scalar(rd, size), gpr(sf, rn)],
+        ),
+        (0b00, 0b100 | 0b101) => op(
+            if opcode == 0b100 { "fcvtas" } else { "fcvtau" },
+            vec![gpr(sf, rd), scalar(rn, size)],
+        ),
+        // fmov only moves between registers of the same size.
+        (0b00, 0b110 | 0b111) if sf == (size == 3) => match opcode {
+            0b110 => op("fmov", vec![gpr(sf, rd), scalar(rn, size)]),
+            _ => op("fmov", vec![scalar(rd, size), gpr(sf, rn)]),
+        },
+        _ => None,
+    }
+}
+
+/// Conversions between floating point and fixed point numbers, with a
+/// number of fraction bits.
+fn fixed_conversion(word: u32, size: u32) -> Option<Decoded> {
+    let sf = bit(word, 31);
+    let scale = bits(word, 10, 6);
+    let rn = bits(word, 5, 5);
+    let rd = bits(word, 0, 5);
+    if size == 1 || (!sf && scale < 32) {
+        return None;
+    }
+    let fraction = Operand::Immediate(64 - scale as i64);
+
+    match (bits(word, 19, 2), bits(word, 16, 3)) {
+        (0b00, 0b010) => op("scvtf", vec![scalar(rd, size), gpr(sf, rn), fraction]),
+        (0b00, 0b011) => op("ucvtf", vec![scalar(rd, size), gpr(sf, rn), fraction]),
+        (0b11, 0b000) => op("fcvtzs", vec![gpr(sf, rd), scalar(rn, size), fraction]),
+        (0b11, 0b001) => op("fcvtzu", vec![gpr(sf, rd), scalar(rn, size), fraction]),
+        _ => None,
+    }
+}
+
+// --------------
+// Advanced SIMD.
+
+fn advanced_simd(word: u32) -> Option<Decoded> {
+    match (bit(word, 24), bit(word, 21), bits(word, 10, 2)) {
+        (false, true, 0b01 | 0b11) => three_same(word),
+        (false, true, 0b00) => three_different(word),
+        (false, true, _) => match bits(word, 17, 4) {
+            0b0000 => two_misc(word),
+            0b1000 => across_lanes(word),
+            _ => None,
+        },
+        (false, false, _) if bit(word, 15) => None,
+        (false, false, 0b01 | 0b11) if bits(word, 21, 3) == 0 => copy(word),
+        (false, false, 0b00 | 0b10) if bit(word, 29) => extract(word),
+        (false, false, 0b00) => table(word),
+        (false, false, 0b10) => permute(word),
+        (true, _, 0b01 | 0b11) if !bit(word, 23) => match bits(word, 19, 4) {
+            0 => modified_immediate(word),
+            _ => shift_immediate(word),
+        },
+        (true, _, 0b00 | 0b10) => indexed(word),
+        _ => None,
+    }
+}
+
+/// Which sizes of element an instruction takes.
+#[derive(Clone, Copy, PartialEq)]
+enum Sizes {
+    /// Any, though 64-bit elements need a whole vector.
+    All,
+    /// Any but 64 bits.
+    NotD,
+    /// 16 and 32 bits.
+    HS,
+    /// 8 and 16 bits.
+    BH,
+    /// Just bytes.
+    B,
+    /// Just 64 bits, for scalars.
+    D,
+}
+
+impl Sizes {
+    fn allow(self, size: u32, q: u32) -> bool {
+        match self {
+            Sizes::All => size < 3 || q == 1,
+            Sizes::NotD => size < 3,
+            Sizes::HS => size == 1 || size == 2,
+            Sizes::BH => size < 2,
+            Sizes::B => size == 0,
+            Sizes::D => size == 3,
+        }
+    }
+}
+
+/// The mnemonics of the vector instructions with three registers of the
+/// same arrangement, by U and opcode, with the sizes they take.
+const THREE_SAME: [[(&str, Sizes); 24]; 2] = [
+    [
+        ("shadd", Sizes::NotD),
+        ("sqadd", Sizes::All),
+        ("srhadd", Sizes::NotD),
+        ("", Sizes::All),
+        ("shsub", Sizes::NotD),
+        ("sqsub", Sizes::All),
+        ("cmgt", Sizes::All),
+        ("cmge", Sizes::All),
+        ("sshl", Sizes::All),
+        ("sqshl", Sizes::All),
+        ("srshl", Sizes::All),
+        ("sqrshl", Sizes::All),
+        ("smax", Sizes::NotD),
+        ("smin", Sizes::NotD),
+        ("sabd", Sizes::NotD),
+        ("saba", Sizes::NotD),
+        ("add", Sizes::All),
+        ("cmtst", Sizes::All),
+        ("mla", Sizes::NotD),
+        ("mul", Sizes::NotD),
+        ("smaxp", Sizes::NotD),
+        ("sminp", Sizes::NotD),
+        ("sqdmulh", Sizes::HS),
+        ("addp", Sizes::All),
+    ],
+    [
+        ("uhadd", Sizes::NotD),
+        ("uqadd", Sizes::All),
+        ("urhadd", Sizes::NotD),
+        ("", Sizes::All),
+        ("uhsub", Sizes::NotD),
+        ("uqsub", Sizes::All),
+        ("cmhi", Sizes::All),
+        ("cmhs", Sizes::All),
+        ("ushl", Sizes::All),
+        ("uqshl", Sizes::All),
+        ("urshl", Sizes::All),
+        ("uqrshl", Sizes::All),
+        ("umax", Sizes::NotD),
+        ("umin", Sizes::NotD),
+        ("uabd", Sizes::NotD),
+        ("uaba", Sizes::NotD),
+        ("sub", Sizes::All),
+        ("cmeq", Sizes::All),
+        ("mls", Sizes::NotD),
+        ("pmul", Sizes::B),
+        ("umaxp", Sizes::NotD),
+        ("uminp", Sizes::NotD),
+        ("sqrdmulh", Sizes::HS),
+        ("", Sizes::All),
+    ],
+];
+
+/// The floating point instructions with three registers of the same
+/// arrangement, by U, then bit 23, then the low bits of the opcode.
+const THREE_SAME_FLOAT: [[[&str; 8]; 2]; 2] = [
+    [
+        [
+            "fmaxnm", "fmla", "fadd", "fmulx", "fcmeq", "", "fmax", "frecps",
+        ],
+        ["fminnm", "fmls", "fsub", "", "", "", "fmin", "frsqrts"],
+    ],
+    [
+        [
+            "fmaxnmp", "", "faddp", "fmul", "fcmge", "facge", "fmaxp", "fdiv",
+        ],
+        ["fminnmp", "", "fabd", "", "fcmgt", "facgt", "fminp", ""],
+    ],
+];
+
+fn three_same(word: u32) -> Option<Decoded> {
+    let q = bits(word, 30, 1);
+    let u = bits(word, 29, 1);
+    let size = bits(word, 22, 2);
+    let rm = bits(word, 16, 5);
+    let opcode = bits(word, 11, 5);
+    let rn = bits(word, 5, 5);
+    let rd = bits(word, 0, 5);
+
+    if opcode >= 0b11000 {
+        let mnemonic =
+            THREE_SAME_FLOAT[u as usize][bits(word, 23, 1) as usize][(opcode & 7) as usize];
+        let size = 2 + bits(word, 22, 1);
+        if mnemonic.is_empty() || (size == 3 && q == 0) {
+            return None;
+        }
+        return op(
+            mnemonic,
+            vec![
+                vector(rd, size, q),
+                vector(rn, size, q),
+                vector(rm, size, q),
+            ],
+        );
+    }
+    if opcode == 0b00011 {
+        let mnemonic =
+            [["and", "bic", "orr", "orn"], ["eor", "bsl", "bit", "bif"]][u as usize][size as usize];
+        if mnemonic == "orr" && rm == rn {
+            return op("mov", vec![vector(rd, 0, q), vector(rn, 0, q)]);
+        }
+        return op(
+            mnemonic,
+            vec![vector(rd, 0, q), vector(rn, 0, q), vector(rm, 0, q)],
+        );
+    }
+
+    let (mnemonic, sizes) = THREE_SAME[u as usize][opcode as usize];
+    if mnemonic.is_empty() || !sizes.allow(size, q) {
+        return None;
+    }
+    op(
+        mnemonic,
+        vec![
+            vector(rd, size, q),
+            vector(rn, size, q),
+            vector(rm, size, q),
+        ],
+    )
+}
+
+const THREE_DIFFERENT: [[&str; 16]; 2] = [
+    [
+        "saddl", "saddw", "ssubl", "ssubw", "addhn", "sabal", "subhn", "sabdl", "smlal", "sqdmlal",
+        "smlsl", "sqdmlsl", "smull", "sqdmull", "pmull", "",
+    ],
+    [
+        "uaddl", "uaddw", "usubl", "usubw", "raddhn", "uabal", "rsubhn", "uabdl", "umlal", "",
+        "umlsl", "", "umull", "", "", "",
+    ],
+];
+
+/// The instructions whose operands are of two sizes of element: long ones
+/// (with results twice the size), wide ones (taking one of each), and
+/// narrow ones (with results half the size).
+fn three_different(word: u32) -> Option<Decoded> {
+    let q = bits(word, 30, 1);
+    let u = bits(word, 29, 1);
+    let size = bits(word, 22, 2);
+    let rm = bits(word, 16, 5);
+    let opcode = bits(word, 12, 4);
+    let rn = bits(word, 5, 5);
+    let rd = bits(word, 0, 5);
+
+    let mnemonic = THREE_DIFFERENT[u as usize][opcode as usize];
+    let allowed = match mnemonic {
+        "" => false,
+        "pmull" => size == 0,
+        _ if mnemonic.starts_with("sqdm") => size == 1 || size == 2,
+        _ => size < 3,
+    };
+    if !allowed {
+        return None;
+    }
+    let (wide, narrow) = (vector(rd, size + 1, 1), vector(rd, size, q));
+    let operands = match opcode {
+        0b0001 | 0b0011 => vec![wide, vector(rn, size + 1, 1), vector(rm, size, q)],
+        0b0100 | 0b0110 => vec![narrow, vector(rn, size + 1, 1), vector(rm, size + 1, 1)],
+        _ => vec![wide, vector(rn, size, q), vector(rm, size, q)],
+    };
+    op(&upper(mnemonic, q), operands)
+}
+
+/// How the operands of an instruction with two registers relate.
+enum Form {
+    /// Both have the same arrangement.
+    Same(Sizes),
+    /// The same, and a comparison with zero.
+    Zero,
+    /// Adding pairs of elements into ones twice the size.
+    Pairwise,
+    /// Narrowing each element to half its size.
+    Narrow,
+    /// Floating point, in single or double precision.
+    Float,
+    FloatZero,
+}
+
+fn two_misc(word: u32) -> Option<Decoded> {
+    let q = bits(word, 30, 1);
+    let u = bits(word, 29, 1);
+    let size = bits(word, 22, 2);
+    let opcode = bits(word, 12, 5);
+    let rn = bits(word, 5, 5);
+    let rd = bits(word, 0, 5);
+    let float = bits(word, 22, 1);
+
+    // Those whose operands don't follow any of the forms.
+    match (u, opcode) {
+        (0, 0b10110) if size < 2 => {
+            let operands = vec![vector(rd, 1 + float, q), vector(rn, 2 + float, 1)];
+            return op(&upper("fcvtn", q), operands);
+        }
+        (0, 0b10111) if size < 2 => {
+            let operands = vec![vector(rd, 2 + float, 1), vector(rn, 1 + float, q)];
+            return op(&upper("fcvtl", q), operands);
+        }
+        (1, 0b10110) if size == 1 => {
+            return op(
+                &upper("fcvtxn", q),
+                vec![vector(rd, 2, q), vector(rn, 3, 1)],
+            );
+        }
+        (1, 0b10011) if size < 3 => {
+            let operands = vec![
+                vector(rd, size + 1, 1),
+                vector(rn, size, q),
+                Operand::Immediate(8 << size),
+            ];
+            return op(&upper("shll", q), operands);
+        }
+        (1, 0b00101) if size < 2 => {
+            let mnemonic = if size == 0 { "mvn" } else { "rbit" };
+            return op(mnemonic, vec![vector(rd, 0, q), vector(rn, 0, q)]);
+        }
+        (_, 0b11100) if size == 2 => {
+            let mnemonic = if u == 0 { "urecpe" } else { "ursqrte" };
+            return op(mnemonic, vec![vector(rd, 2, q), vector(rn, 2, q)]);
+        }
+        _ => {}
+    }
+
+    let high = bit(word, 23);
+    let (mnemonic, form) = match (u, opcode) {
+        (0, 0b00000) => ("rev64", Form::Same(Sizes::NotD)),
+        (0, 0b00001) => ("rev16", Form::Same(Sizes::B)),
+        (0, 0b00010) => ("saddlp", Form::Pairwise),
+        (0, 0b00011) => ("suqadd", Form::Same(Sizes::All)),
+        (0, 0b00100) => ("cls", Form::Same(Sizes::NotD)),
+        (0, 0b00101) => ("cnt", Form::Same(Sizes::B)),
+        (0, 0b00110) => ("sadalp", Form::Pairwise),
+        (0, 0b00111) => ("sqabs", Form::Same(Sizes::All)),
+        (0, 0b01000) => ("cmgt", Form::Zero),
+        (0, 0b01001) => ("cmeq", Form::Zero),
+        (0, 0b01010) => ("cmlt", Form::Zero),
+        (0, 0b01011) => ("abs", Form::Same(Sizes::All)),
+        (0, 0b10010) => ("xtn", Form::Narrow),
+        (0, 0b10100) => ("sqxtn", Form::Narrow),
+        (1, 0b00000) => ("rev32", Form::Same(Sizes::BH)),
+        (1, 0b00010) => ("uaddlp", Form::Pairwise),
+        (1, 0b00011) => ("usqadd", Form::Same(Sizes::All)),
+        (1, 0b00100) => ("clz", Form::Same(Sizes::NotD)),
+        (1, 0b00110) => ("uadalp", Form::Pairwise),
+        (1, 0b00111) => ("sqneg", Form::Same(Sizes::All)),
+        (1, 0b01000) => ("cmge", Form::Zero),
+        (1, 0b01001) => ("cmle", Form::Zero),
+        (1, 0b01011) => ("neg", Form::Same(Sizes::All)),
+        (1, 0b10010) => ("sqxtun", Form::Narrow),
+        (1, 0b10100) => ("uqxtn", Form::Narrow),
+        _ => match (u, high, opcode) {
+            (0, true, 0b01100) => ("fcmgt", Form::FloatZero),
+            (0, true, 0b01101) => ("fcmeq", Form::FloatZero),
+            (0, true, 0b01110) => ("fcmlt", Form::FloatZero),
+            (0, true, 0b01111) => ("fabs", Form::Float),
+            (0, false, 0b11000) => ("frintn", Form::Float),
+            (0, false, 0b11001) => ("frintm", Form::Float),
+            (0, false, 0b11010) => ("fcvtns", Form::Float),
+            (0, false, 0b11011) => ("fcvtms", Form::Float),
+            (0, false, 0b11100) => ("fcvtas", Form::Float),
+            (0, false, 0b11101) => ("scvtf", Form::Float),
+            (0, true, 0b11000) => ("frintp", Form::Float),
+            (0, true, 0b11001) => ("frintz", Form::Float),
+            (0, true, 0b11010) => ("fcvtps", Form::Float),
+            (0, true, 0b11011) => ("fcvtzs", Form::Float),
+            (0, true, 0b11101) => ("frecpe", Form::Float),
+            (1, true, 0b01100) => ("fcmge", Form::FloatZero),
+            (1, true, 0b01101) => ("fcmle", Form::FloatZero),
+            (1, true, 0b01111) => ("fneg", Form::Float),
+            (1, false, 0b11000) => ("frinta", Form::Float),
+            (1, false, 0b11001) => ("frintx", Form::Float),
+            (1, false, 0b11010) => ("fcvtnu", Form::Float),
+            (1, false, 0b11011) => ("fcvtmu", Form::Float),
+            (1, false, 0b11100) => ("fcvtau", Form::Float),
+            (1, false, 0b11101) => ("ucvtf", Form::Float),
+            (1, true, 0b11001) => ("frinti", Form::Float),
+            (1, true, 0b11010) => ("fcvtpu", Form::Float),
+            (1, true, 0b11011) => ("fcvtzu", Form::Float),
+            (1, true, 0b11101) => ("frsqrte", Form::Float),
+            (1, true, 0b11111) => ("fsqrt", Form::Float),
+            _ => return None,
+        },
+    };
+
+    match form {
+        Form::Same(sizes) if sizes.allow(size, q) => {
+            op(mnemonic, vec![vector(rd, size, q), vector(rn, size, q)])
+        }
+        Form::Zero if Sizes::All.allow(size, q) => op(
+            mnemonic,
+            vec![vector(rd, size, q), vector(rn, size, q), zero(false)],
+        ),
+        Form::Pairwise if size < 3 => {
+            op(mnemonic, vec![vector(rd, size + 1, q), vector(rn, size, q)])
+        }
+        Form::Narrow if size < 3 => op(
+            &upper(mnemonic, q),
+            vec![vector(rd, size, q), vector(rn, size + 1, 1)],
+        ),
+        Form::Float | Form::FloatZero if float == 0 || q == 1 => {
+            let size = 2 + float;
+            let mut operands = vec![vector(rd, size, q), vector(rn, size, q)];
+            if let Form::FloatZero = form {
+                operands.push(zero(true));
+            }
+            op(mnemonic, operands)
+        }
+        _ => None,
+    }
+}
+
+fn across_lanes(word: u32) -> Option<Decoded> {
+    let q = bits(word, 30, 1);
+    let u = bits(word, 29, 1);
+    let size = bits(word, 22, 2);
+    let rn = bits(word, 5, 5);
+    let rd = bits(word, 0, 5);
+
+    let (mnemonic, long) = match (u, bits(word, 12, 5)) {
+        (0, 0b00011) => ("saddlv", true),
+        (0, 0b01010) => ("smaxv", false),
+        (0, 0b11010) => ("sminv", false),
+        (0, 0b11011) => ("addv", false),
+        (1, 0b00011) => ("uaddlv", true),
+        (1, 0b01010) => ("umaxv", false),
+        (1, 0b11010) => ("uminv", false),
+        (1, opcode @ (0b01100 | 0b01111)) => {
+            if q == 0 || size & 1 != 0 {
+                return None;
+            }
+            let mnemonic = match (opcode, size) {
+                (0b01100, 0) => "fmaxnmv",
+                (0b01100, _) => "fminnmv",
+                (_, 0) => "fmaxv",
+                _ => "fminv",
+            };
+            return op(mnemonic, vec![scalar(rd, 2), vector(rn, 2, 1)]);
+        }
+        _ => return None,
+    };
+    if size == 3 || (size == 2 && q == 0) {
+        return None;
+    }
+    let result = scalar(rd, if long { size + 1 } else { size });
+    op(mnemonic, vec![result, vector(rn, size, q)])
+}
+
+/// The size of element that an imm5 field picks (by its lowest set bit),
+/// and the index of one, from the bits above that.
+fn element_index(imm5: u32) -> Option<(u32, u32)> {
+    let size = imm5.trailing_zeros();
+    match size {
+        0..=3 => Some((size, imm5 >> (size + 1))),
+        _ => None,
+    }
+}
+
+fn copy(word: u32) -> Option<Decoded> {
+    let q = bits(word, 30, 1);
+    let imm4 = bits(word, 11, 4);
+    let rn = bits(word, 5, 5);
+    let rd = bits(word, 0, 5);
+    let (size, index) = element_index(bits(word, 16, 5))?;
+
+    if bit(word, 29) {
+        if q == 0 {
+            return None;
+        }
+        let from = element(rn, size, imm4 >> size);
+        return op("mov", vec![element(rd, size, index), from]);
+    }
+    match imm4 {
+        0b0000 if Sizes::All.allow(size, q) => {
+            op("dup", vec![vector(rd, size, q), element(rn, size, index)])
+        }
+        0b0001 if Sizes::All.allow(size, q) => {
+            op("dup", vec![vector(rd, size, q), gpr(size == 3, rn)])
+        }
+        0b0011 if q == 1 => op("mov", vec![element(rd, size, index), gpr(size == 3, rn)]),
+        0b0101 if size < 2 + q => op("smov", vec![gpr(q == 1, rd), element(rn, size, index)]),
+        0b0111 => match (q, size) {
+            (0, 0 | 1) => op("umov", vec![gpr(false, rd), element(rn, size, index)]),
+            (0, 2) | (1, 3) => op("mov", vec![gpr(q == 1, rd), element(rn, size, index)]),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn table(word: u32) -> Option<Decoded> {
+    let q = bits(word, 30, 1);
+    let rm = bits(word, 16, 5);
+    let rn = bits(word, 5, 5);
+    let rd = bits(word, 0, 5);
+    if bits(word, 22, 2) != 0 {
+        return None;
+    }
+    let registers = (0..=bits(word, 13, 2))
+        .map(|i| Register::Vector {
+            number: ((rn + i) % 32) as u8,
+            arrangement: "16b",
+        })
+        .collect();
+    let mnemonic = if bit(word, 12) { "tbx" } else { "tbl" };
+    op(
+        mnemonic,
+        vec![
+            vector(rd, 0, q),
+            Operand::List {
+                registers,
+                index: None,
+            },
+            vector(rm, 0, q),
+        ],
+    )
+}
+
+fn permute(word: u32) -> Option<Decoded> {
+    let q = bits(word, 30, 1);
+    let size = bits(word, 22, 2);
+    let rm = bits(word, 16, 5);
+    let rn = bits(word, 5, 5);
+    let rd = bits(word, 0, 5);
+    let mnemonic =
+        ["", "uzp1", "trn1", "zip1", "", "uzp2", "trn2", "zip2"][bits(word, 12, 3) as usize];
+    if mnemonic.is_empty() || !Sizes::All.allow(size, q) {
+        return None;
+    }
+    op(
+        mnemonic,
+        vec![
+            vector(rd, size, q),
+            vector(rn, size, q),
+            vector(rm, size, q),
+        ],
+    )
+}
+
+fn extract(word: u32) -> Option<Decoded> {
+    let q = bits(word, 30, 1);
+    let imm4 = bits(word, 11, 4);
+    if bits(word, 22, 2) != 0 || (q == 0 && imm4 >= 8) {
+        return None;
+    }
+    let (rm, rn, rd) = (bits(word, 16, 5), bits(word, 5, 5), bits(word, 0, 5));
+    op(
+        "ext",
+        vec![
+            vector(rd, 0, q),
+            vector(rn, 0, q),
+            vector(rm, 0, q),
+            Operand::Immediate(imm4 as i64),
+        ],
+    )
+}
+
+fn modified_immediate(word: u32) -> Option<Decoded> {
+    let q = bits(word, 30, 1);
+    let negated = bit(word, 29);
+    let cmode = bits(word, 12, 4);
+    let rd = bits(word, 0, 5);
+    let imm8 = bits(word, 16, 3) << 5 | bits(word, 5, 5);
+    if bit(word, 11) {
+        return None;
+    }
+    let immediate = Operand::Immediate(imm8 as i64);
+    let (mov, logical) = match negated {
+        false => ("movi", "orr"),
+        true => ("mvni", "bic"),
+    };
+
+    // 32-bit elements, then 16-bit ones, shifted by bytes.
+    let (size, amount) = match cmode {
+        0b0000..=0b0111 => (2, 8 * bits(cmode, 1, 2)),
+        0b1000..=0b1011 => (1, 8 * bits(cmode, 1, 1)),
+        _ => (0, 0),
+    };
+    let mnemonic = if cmode & 1 == 0 { mov } else { logical };
+    match cmode {
+        0b0000..=0b1011 => {
+            let mut operands = vec![vector(rd, size, q), immediate];
+            if amount != 0 {
+                operands.push(shift("lsl", amount));
+            }
+            op(mnemonic, operands)
+        }
+        0b1100 | 0b1101 => op(
+            mov,
+            vec![vector(rd, 2, q), immediate, shift("msl", 8 << (cmode & 1))],
+        ),
+        0b1110 if !negated => op("movi", vec![vector(rd, 0, q), immediate]),
+        0b1110 if q == 0 => op("movi", vec![scalar(rd, 3), byte_mask(imm8)]),
+        0b1110 => op("movi", vec![vector(rd, 3, 1), byte_mask(imm8)]),
+        _ if !negated => op(
+            "fmov",
+            vec![vector(rd, 2, q), Operand::Float(expand_float(imm8))],
+        ),
+        _ if q == 1 => op(
+            "fmov",
+            vec![vector(rd, 3, 1), Operand::Float(expand_float(imm8))],
+        ),
+        _ => None,
+    }
+}
+
+/// How a shift by an immediate treats its operands.
+enum Shifted {
+    Right,
+    Left,
+    /// Shifting right, into elements half the size.
+    Narrow,
+    /// Shifting left, into elements twice the size.
+    Long,
+    /// Converting to or from fixed point with a number of fraction bits.
+    Fixed,
+}
+
+/// The instructions that shift by an immediate, by U and opcode, with
+/// which of them are only for 64-bit elements as scalars.
+fn shift_kind(u: u32, opcode: u32) -> Option<(&'static str, Shifted, bool)> {
+    Some(match (u, opcode) {
+        (0, 0b00000) => ("sshr", Shifted::Right, true),
+        (0, 0b00010) => ("ssra", Shifted::Right, true),
+        (0, 0b00100) => ("srshr", Shifted::Right, true),
+        (0, 0b00110) => ("srsra", Shifted::Right, true),
+        (0, 0b01010) => ("shl", Shifted::Left, true),
+        (0, 0b01110) => ("sqshl", Shifted::Left, false),
+        (0, 0b10000) => ("shrn", Shifted::Narrow, true),
+        (0, 0b10001) => ("rshrn", Shifted::Narrow, true),
+        (0, 0b10010) => ("sqshrn", Shifted::Narrow, false),
+        (0, 0b10011) => ("sqrshrn", Shifted::Narrow, false),
+        (0, 0b10100) => ("sshll", Shifted::Long, true),
+        (0, 0b11100) => ("scvtf", Shifted::Fixed, false),
+        (0, 0b11111) => ("fcvtzs", Shifted::Fixed, false),
+        (1, 0b00000) => ("ushr", Shifted::Right, true),
+        (1, 0b00010) => ("usra", Shifted::Right, true),
+        (1, 0b00100) => ("urshr", Shifted::Right, true),
+        (1, 0b00110) => ("ursra", Shifted::Right, true),
+        (1, 0b01000) => ("sri", Shifted::Right, true),
+        (1, 0b01010) => ("sli", Shifted::Left, true),
+        (1, 0b01100) => ("sqshlu", Shifted::Left, false),
+        (1, 0b01110) => ("uqshl", Shifted::Left, false),
+        (1, 0b10000) => ("sqshrun", Shifted::Narrow, false),
+        (1, 0b10001) => ("sqrshrun", Shifted::Narrow, false),
+        (1, 0b10010) => ("uqshrn", Shifted::Narrow, false),
+        (1, 0b10011) => ("uqrshrn", Shifted::Narrow, false),
+        (1, 0b10100) => ("ushll", Shifted::Long, true),
+        (1, 0b11100) => ("ucvtf", Shifted::Fixed, false),
+        (1, 0b11111) => ("fcvtzu", Shifted::Fixed, false),
+        _ => return None,
+    })
+}
+
+/// The element size that immh picks (by its highest set bit), and the
+/// shift that immh and immb encode: left by what they are over the
+/// element size, or right by what they are under twice it.
+fn shift_amount(word: u32, kind: &Shifted) -> (u32, Operand) {
+    let immh = bits(word, 19, 4);
+    let size = 31 - immh.leading_zeros();
+    let encoded = bits(word, 16, 7);
+    let amount = match kind {
+        Shifted::Left | Shifted::Long => encoded - (8 << size),
+        _ => (16 << size) - encoded,
+    };
+    (size, Operand::Immediate(amount as i64))
+}
+
+fn shift_immediate(word: u32) -> Option<Decoded> {
+    let q = bits(word, 30, 1);
+    let rn = bits(word, 5, 5);
+    let rd = bits(word, 0, 5);
+    let (mnemonic, kind, _) = shift_kind(bits(word, 29, 1), bits(word, 11, 5))?;
+    let (size, amount) = shift_amount(word, &kind);
+
+    match kind {
+        Shifted::Right | Shifted::Left if Sizes::All.allow(size, q) => op(
+            mnemonic,
+            vec![vector(rd, size, q), vector(rn, size, q), amount],
+        ),
+        Shifted::Narrow if size < 3 => op(
+            &upper(mnemonic, q),
+            vec![vector(rd, size, q), vector(rn, size + 1, 1), amount],
+        ),
+        Shifted::Long if size < 3 => op(
+            &upper(mnemonic, q),
+            vec![vector(rd, size + 1, 1), vector(rn, size, q), amount],
+        ),
+        Shifted::Fixed if size == 2 || (size == 3 && q == 1) => op(
+            mnemonic,
+            vec![vector(rd, size, q), vector(rn, size, q), amount],
+        ),
+        _ => None,
+    }
+}
+
+/// How the operands of an instruction by an element relate.
+enum ByElement {
+    Same,
+    Long,
+    Float,
+}
+
+/// The instructions that take an element of a register as their last
+/// operand, by U and opcode, and whether they're only scalar too.
+fn by_element(u: u32, opcode: u32) -> Option<(&'static str, ByElement, bool)> {
+    Some(match (u, opcode) {
+        (0, 0b0001) => ("fmla", ByElement::Float, true),
+        (0, 0b0010) => ("smlal", ByElement::Long, false),
+        (0, 0b0011) => ("sqdmlal", ByElement::Long, true),
+        (0, 0b0101) => ("fmls", ByElement::Float, true),
+        (0, 0b0110) => ("smlsl", ByElement::Long, false),
+        (0, 0b0111) => ("sqdmlsl", ByElement::Long, true),
+        (0, 0b1000) => ("mul", ByElement::Same, false),
+        (0, 0b1001) => ("fmul", ByElement::Float, true),
+        (0, 0b1010) => ("smull", ByElement::Long, false),
+        (0, 0b1011) => ("sqdmull", ByElement::Long, true),
+        (0, 0b1100) => ("sqdmulh", ByElement::Same, true),
+        (0, 0b1101) => ("sqrdmulh", ByElement::Same, true),
+        (1, 0b0000) => ("mla", ByElement::Same, false),
+        (1, 0b0010) => ("umlal", ByElement::Long, false),
+        (1, 0b0100) => ("mls", ByElement::Same, false),
+        (1, 0b0110) => ("umlsl", ByElement::Long, false),
+        (1, 0b1001) => ("fmulx", ByElement::Float, true),
+        (1, 0b1010) => ("umull", ByElement::Long, false),
+        _ => return None,
+    })
+}
+
+/// The element an instruction by an element takes: its size, register and
+/// index. Smaller elements have more bits of index, and fewer registers.
+fn indexed_element(word: u32, float: bool) -> Option<(u32, Operand)> {
+    let size = bits(word, 22, 2);
+    let (h, l, m) = (bits(word, 11, 1), bits(word, 21, 1), bits(word, 20, 1));
+    let rm = bits(word, 16, 4);
+    let (register, index) = match (float, size) {
+        (false, 1) => (rm, h << 2 | l << 1 | m),
+        (false, 2) | (true, 2) => (m << 4 | rm, h << 1 | l),
+        (true, 3) if l == 0 => (m << 4 | rm, h),
+        _ => return None,
+    };
+    Some((size, element(register, size, index)))
+}
+
+fn indexed(word: u32) -> Option<Decoded> {
+    let q = bits(word, 30, 1);
+    let rn = bits(word, 5, 5);
+    let rd = bits(word, 0, 5);
+    let (mnemonic, kind, _) = by_element(bits(word, 29, 1), bits(word, 12, 4))?;
+    let (size, element) = indexed_element(word, matches!(kind, ByElement::Float))?;
+
+    match kind {
+        ByElement::Long => op(
+            &upper(mnemonic, q),
+            vec![vector(rd, size + 1, 1), vector(rn, size, q), element],
+        ),
+        _ if size == 3 && q == 0 => None,
+        _ => op(
+            mnemonic,
+            vec![vector(rd, size, q), vector(rn, size, q), element],
+        ),
+    }
+}
+
+// ----------------------------
+// Advanced SIMD, scalar forms.
+
+fn advanced_simd_scalar(word: u32) -> Option<Decoded> {
+    match (bit(word, 24), bit(word, 21), bits(word, 10, 2)) {
+        (false, true, 0b01 | 0b11) => scalar_three_same(word),
+        (false, true, 0b00) => scalar_three_different(word),
+        (false, true, _) => match bits(word, 17, 4) {
+            0b0000 => scalar_two_misc(word),
+            0b1000 => scalar_pairwise(word),
+            _ => None,
+        },
+        (false, false, 0b01 | 0b11) if bits(word, 21, 3) == 0 && !bit(word, 15) => {
+            scalar_copy(word)
+        }
+        (true, _, 0b01 | 0b11) if !bit(word, 23) && bits(word, 19, 4) != 0 => scalar_shift(word),
+        (true, _, 0b00 | 0b10) => scalar_indexed(word),
+        _ => None,
+    }
+}
+
+fn scalar_three_same(word: u32) -> Option<Decoded> {
+    let u = bits(word, 29, 1);
+    let size = bits(word, 22, 2);
+    let rm = bits(word, 16, 5);
+    let opcode = bits(word, 11, 5);
+    let rn = bits(word, 5, 5);
+    let rd = bits(word, 0, 5);
+
+    if opcode >= 0b11000 {
+        let high = bits(word, 23, 1);
+        let mnemonic = match (u, high, opcode & 7) {
+            (0, 0, 3) | (0, 0, 4) | (0, _, 7) | (1, 0, 4 | 5) | (1, 1, 2 | 4 | 5) => {
+                THREE_SAME_FLOAT[u as usize][high as usize][(opcode & 7) as usize]
+            }
+            _ => return None,
+        };
+        let size = 2 + bits(word, 22, 1);
+        return op(
+            mnemonic,
+            vec![scalar(rd, size), scalar(rn, size), scalar(rm, size)],
+        );
+    }
+
+    let (mnemonic, _) = THREE_SAME[u as usize][opcode as usize];
+    let allowed = match opcode {
+        0b00001 | 0b00101 | 0b01001 | 0b01011 => true,
+        0b00110 | 0b00111 | 0b01000 | 0b01010 | 0b10000 | 0b10001 => size == 3,
+        0b10110 => size == 1 || size == 2,
+        _ => false,
+    };
+    if !allowed {
+        return None;
+    }
+    op(
+        mnemonic,
+        vec![scalar(rd, size), scalar(rn, size), scalar(rm, size)],
+    )
+}
+
+fn scalar_three_different(word: u32) -> Option<Decoded> {
+    let size = bits(word, 22, 2);
+    let rm = bits(word, 16, 5);
+    let rn = bits(word, 5, 5);
+    let rd = bits(word, 0, 5);
+    let mnemonic = match (bits(word, 29, 1), bits(word, 12, 4)) {
+        (0, 0b1001) => "sqdmlal",
+        (0, 0b1011) => "sqdmlsl",
+        (0, 0b1101) => "sqdmull",
+        _ => return None,
+    };
+    if size != 1 && size != 2 {
+        return None;
+    }
+    op(
+        mnemonic,
+        vec![scalar(rd, size + 1), scalar(rn, size), scalar(rm, size)],
+    )
+}
+
+fn scalar_two_misc(word: u32) -> Option<Decoded> {
+    let u = bits(word, 29, 1);
+    let size = bits(word, 22, 2);
+    let opcode = bits(word, 12, 5);
+    let rn = bits(word, 5, 5);
+    let rd = bits(word, 0, 5);
+    let float = bits(word, 22, 1);
+
+    let (mnemonic, form) = match (u, opcode) {
+        (0, 0b00011) => ("suqadd", Form::Same(Sizes::All)),
+        (0, 0b00111) => ("sqabs", Form::Same(Sizes::All)),
+        (0, 0b01000) => ("cmgt", Form::Zero),
+        (0, 0b01001) => ("cmeq", Form::Zero),
+        (0, 0b01010) => ("cmlt", Form::Zero),
+        (0, 0b01011) => ("abs", Form::Same(Sizes::D)),
+        (0, 0b10100) => ("sqxtn", Form::Narrow),
+        (1, 0b00011) => ("usqadd", Form::Same(Sizes::All)),
+        (1, 0b00111) => ("sqneg", Form::Same(Sizes::All)),
+        (1, 0b01000) => ("cmge", Form::Zero),
+        (1, 0b01001) => ("cmle", Form::Zero),
+        (1, 0b01011) => ("neg", Form::Same(Sizes::D)),
+        (1, 0b10010) => ("sqxtun", Form::Narrow),
+        (1, 0b10100) => ("uqxtn", Form::Narrow),
+        (1, 0b10110) if size == 1 => {
+            return op("fcvtxn", vec![scalar(rd, 2), scalar(rn, 3)]);
+        }
+        _ => match (u, bit(word, 23), opcode) {
+            (0, true, 0b01100) => ("fcmgt", Form::FloatZero),
+            (0, true, 0b01101) => ("fcmeq", Form::FloatZero),
+            (0, true, 0b01110) => ("fcmlt", Form::FloatZero),
+            (0, false, 0b11010) => ("fcvtns", Form::Float),
+            (0, false, 0b11011) => ("fcvtms", Form::Float),
+            (0, false, 0b11100) => ("fcvtas", Form::Float),
+            (0, false, 0b11101) => ("scvtf", Form::Float),
+            (0, true, 0b11010) => ("fcvtps", Form::Float),
+            (0, true, 0b11011) => ("fcvtzs", Form::Float),
+            (0, true, 0b11101) => ("frecpe", Form::Float),
+            (0, true, 0b11111) => ("frecpx", Form::Float),
+            (1, true, 0b01100) => ("fcmge", Form::FloatZero),
+            (1, true, 0b01101) => ("fcmle", Form::FloatZero),
+            (1, false, 0b11010) => ("fcvtnu", Form::Float),
+            (1, false, 0b11011) => ("fcvtmu", Form::Float),
+            (1, false, 0b11100) => ("fcvtau", Form::Float),
+            (1, false, 0b11101) => ("ucvtf", Form::Float),
+            (1, true, 0b11010) => ("fcvtpu", Form::Float),
+            (1, true, 0b11011) => ("fcvtzu", Form::Float),
+            (1, true, 0b11101) => ("frsqrte", Form::Float),
+            _ => return None,
+        },
+    };
+
+    match form {
+        Form::Same(sizes) if sizes.allow(size, 1) => {
+            op(mnemonic, vec![scalar(rd, size), scalar(rn, size)])
+        }
+        Form::Zero if size == 3 => op(
+            mnemonic,
+            vec![scalar(rd, size), scalar(rn, size), zero(false)],
+        ),
+        Form::Narrow if size < 3 => op(mnemonic, vec![scalar(rd, size), scalar(rn, size + 1)]),
+        Form::Float => op(mnemonic, vec![scalar(rd, 2 + float), scalar(rn, 2 + float)]),
+        Form::FloatZero => op(
+            mnemonic,
+            vec![scalar(rd, 2 + float), scalar(rn, 2 + float), zero(true)],
+        ),
+        _ => None,
+    }
+}
+
+fn scalar_pairwise(word: u32) -> Option<Decoded> {
+    let size = bits(word, 22, 2);
+    let rn = bits(word, 5, 5);
+    let rd = bits(word, 0, 5);
+    let float = bits(word, 22, 1);
+
+    let mnemonic = match (bits(word, 29, 1), bit(word, 23), bits(word, 12, 5)) {
+        (0, _, 0b11011) if size == 3 => return op("addp", vec![scalar(rd, 3), vector(rn, 3, 1)]),
+        (1, false, 0b01100) => "fmaxnmp",
+        (1, false, 0b01101) => "faddp",
+        (1, false, 0b01111) => "fmaxp",
+        (1, true, 0b01100) => "fminnmp",
+        (1, true, 0b01111) => "fminp",
+        _ => return None,
+    };
+    op(
+        mnemonic,
+        vec![scalar(rd, 2 + float), vector(rn, 2 + float, float)],
+    )
+}
+
+fn scalar_copy(word: u32) -> Option<Decoded> {
+    if bit(word, 29) || bits(word, 11, 4) != 0 {
+        return None;
+    }
+    let (size, index) = element_index(bits(word, 16, 5))?;
+    let (rn, rd) = (bits(word, 5, 5), bits(word, 0, 5));
+    op("mov", vec![scalar(rd, size), element(rn, size, index)])
+}
+
+fn scalar_shift(word: u32) -> Option<Decoded> {
+    let rn = bits(word, 5, 5);
+    let rd = bits(word, 0, 5);
+    let (mnemonic, kind, only_d) = shift_kind(bits(word, 29, 1), bits(word, 11, 5))?;
+    let (size, amount) = shift_amount(word, &kind);
+
+    match kind {
+        Shifted::Right | Shifted::Left if size == 3 || !only_d => {
+            op(mnemonic, vec![scalar(rd, size), scalar(rn, size), amount])
+        }
+        Shifted::Narrow if size < 3 && !only_d => op(
+            mnemonic,
+            vec![scalar(rd, size), scalar(rn, size + 1), amount],
+        ),
+        Shifted::Fixed if size >= 2 => {
+            op(mnemonic, vec![scalar(rd, size), scalar(rn, size), amount])
+        }
+        _ => None,
+    }
+}
+
+fn scalar_indexed(word: u32) -> Option<Decoded> {
+    let rn = bits(word, 5, 5);
+    let rd = bits(word, 0, 5);
+    let (mnemonic, kind, scalar_too) = by_element(bits(word, 29, 1), bits(word, 12, 4))?;
+    if !scalar_too {
+        return None;
+    }
+    let (size, element) = indexed_element(word, matches!(kind, ByElement::Float))?;
+
+    match kind {
+        ByElement::Long => op(
+            mnemonic,
+            vec![scalar(rd, size + 1), scalar(rn, size), element],
+        ),
+        _ => op(mnemonic, vec![scalar(rd, size), scalar(rn, size), element]),
+    }
+}
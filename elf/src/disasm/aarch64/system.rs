@@ -0,0 +1,215 @@
+/// The exception-generating and system instructions: supervisor calls and
+/// breakpoints, hints, barriers, the cache and TLB maintenance that sys
+/// does, and reading and writing system registers.
+///
+use super::decode::*;
+use super::system_registers::*;
+use super::*;
+
+pub(super) fn exception(word: u32) -> Option<Decoded> {
+    let imm = bits(word, 5, 16) as u64;
+    let (mnemonic, flow) = match (bits(word, 21, 3), bits(word, 2, 3), bits(word, 0, 2)) {
+        (0b000, 0, 0b01) => ("svc", Flow::Next),
+        (0b000, 0, 0b10) => ("hvc", Flow::Next),
+        (0b000, 0, 0b11) => ("smc", Flow::Next),
+        (0b001, 0, 0b00) => ("brk", Flow::Stop),
+        (0b010, 0, 0b00) => ("hlt", Flow::Stop),
+        (0b101, 0, 0b01) => ("dcps1", Flow::Next),
+        (0b101, 0, 0b10) => ("dcps2", Flow::Next),
+        (0b101, 0, 0b11) => ("dcps3", Flow::Next),
+        _ => return None,
+    };
+    // The debug state changes leave out an immediate of 0.
+    let operands = match mnemonic.starts_with("dcps") && imm == 0 {
+        true => vec![],
+        false => vec![Operand::Mask(imm)],
+    };
+    Some(Decoded {
+        flow,
+        ..op(mnemonic, operands)?
+    })
+}
+
+/// The hints with names; the others are written by number.
+const HINTS: [(u32, &str); 8] = [
+    (0, "nop"),
+    (1, "yield"),
+    (2, "wfe"),
+    (3, "wfi"),
+    (4, "sev"),
+    (5, "sevl"),
+    (6, "dgh"),
+    (20, "csdb"),
+];
+
+const BARRIER_OPTIONS: [&str; 16] = [
+    "", "oshld", "oshst", "osh", "", "nshld", "nshst", "nsh", "", "ishld", "ishst", "ish", "",
+    "ld", "st", "sy",
+];
+
+/// The operations that sys does (and that it's written as), by op1, CRn,
+/// CRm and op2, and whether they take a register.
+const SYS_OPERATIONS: [(u32, &str, &str, bool); 55] = [
+    (0x0388, "ic", "ialluis", false),
+    (0x03a8, "ic", "iallu", false),
+    (0x03b1, "dc", "ivac", true),
+    (0x03b2, "dc", "isw", true),
+    (0x03c0, "at", "s1e1r", true),
+    (0x03c1, "at", "s1e1w", true),
+    (0x03c2, "at", "s1e0r", true),
+    (0x03c3, "at", "s1e0w", true),
+    (0x03d2, "dc", "csw", true),
+    (0x03f2, "dc", "cisw", true),
+    (0x0418, "tlbi", "vmalle1is", false),
+    (0x0419, "tlbi", "vae1is", true),
+    (0x041a, "tlbi", "aside1is", true),
+    (0x041b, "tlbi", "vaae1is", true),
+    (0x041d, "tlbi", "vale1is", true),
+    (0x041f, "tlbi", "vaale1is", true),
+    (0x0438, "tlbi", "vmalle1", false),
+    (0x0439, "tlbi", "vae1", true),
+    (0x043a, "tlbi", "aside1", true),
+    (0x043b, "tlbi", "vaae1", true),
+    (0x043d, "tlbi", "vale1", true),
+    (0x043f, "tlbi", "vaale1", true),
+    (0x1ba1, "dc", "zva", true),
+    (0x1ba9, "ic", "ivau", true),
+    (0x1bd1, "dc", "cvac", true),
+    (0x1bd9, "dc", "cvau", true),
+    (0x1bf1, "dc", "civac", true),
+    (0x23c0, "at", "s1e2r", true),
+    (0x23c1, "at", "s1e2w", true),
+    (0x23c4, "at", "s12e1r", true),
+    (0x23c5, "at", "s12e1w", true),
+    (0x23c6, "at", "s12e0r", true),
+    (0x23c7, "at", "s12e0w", true),
+    (0x2401, "tlbi", "ipas2e1is", true),
+    (0x2405, "tlbi", "ipas2le1is", true),
+    (0x2418, "tlbi", "alle2is", false),
+    (0x2419, "tlbi", "vae2is", true),
+    (0x241c, "tlbi", "alle1is", false),
+    (0x241d, "tlbi", "vale2is", true),
+    (0x241e, "tlbi", "vmalls12e1is", false),
+    (0x2421, "tlbi", "ipas2e1", true),
+    (0x2425, "tlbi", "ipas2le1", true),
+    (0x2438, "tlbi", "alle2", false),
+    (0x2439, "tlbi", "vae2", true),
+    (0x243c, "tlbi", "alle1", false),
+    (0x243d, "tlbi", "vale2", true),
+    (0x243e, "tlbi", "vmalls12e1", false),
+    (0x33c0, "at", "s1e3r", true),
+    (0x33c1, "at", "s1e3w", true),
+    (0x3418, "tlbi", "alle3is", false),
+    (0x3419, "tlbi", "vae3is", true),
+    (0x341d, "tlbi", "vale3is", true),
+    (0x3438, "tlbi", "alle3", false),
+    (0x3439, "tlbi", "vae3", true),
+    (0x343d, "tlbi", "vale3", true),
+];
+
+/// A system register's name, or its encoding as in `S3_0_C15_C2_0` if it
+/// hasn't one (for reading, or writing: `access`).
+fn system_register(encoding: u32, access: u8) -> Operand {
+    let name = SYSTEM_REGISTERS
+        .iter()
+        .find(|(number, _, how)| *number as u32 == encoding && how & access != 0)
+        .map(|(_, name, _)| name.to_string());
+    Operand::Name(name.unwrap_or_else(|| {
+        format!(
+            "S{}_{}_C{}_C{}_{}",
+            encoding >> 14,
+            bits(encoding, 11, 3),
+            bits(encoding, 7, 4),
+            bits(encoding, 3, 4),
+            bits(encoding, 0, 3)
+        )
+    }))
+}
+
+pub(super) fn system(word: u32) -> Option<Decoded> {
+    let read = bit(word, 21);
+    let op0 = bits(word, 19, 2);
+    let op1 = bits(word, 16, 3);
+    let crn = bits(word, 12, 4);
+    let crm = bits(word, 8, 4);
+    let op2 = bits(word, 5, 3);
+    let rt = bits(word, 0, 5);
+    let encoding = bits(word, 5, 16);
+    let immediate = |value: u32| Operand::Immediate(value as i64);
+
+    match (read, op0) {
+        (false, 0b00) if rt == 31 => match (op1, crn, op2) {
+            (0b000, 0b0100, 0b101) => op(
+                "msr",
+                vec![Operand::Name("SPSel".to_owned()), immediate(crm)],
+            ),
+            (0b011, 0b0100, 0b110) => op(
+                "msr",
+                vec![Operand::Name("DAIFSet".to_owned()), immediate(crm)],
+            ),
+            (0b011, 0b0100, 0b111) => op(
+                "msr",
+                vec![Operand::Name("DAIFClr".to_owned()), immediate(crm)],
+            ),
+            (0b011, 0b0010, _) => {
+                let number = crm << 3 | op2;
+                match HINTS.iter().find(|(hint, _)| *hint == number) {
+                    Some((_, name)) => op(name, vec![]),
+                    None => op("hint", vec![immediate(number)]),
+                }
+            }
+            (0b011, 0b0011, 0b010) if crm == 15 => op("clrex", vec![]),
+            (0b011, 0b0011, 0b010) => op("clrex", vec![immediate(crm)]),
+            (0b011, 0b0011, 0b100) if crm == 0 => op("ssbb", vec![]),
+            (0b011, 0b0011, 0b100) if crm == 4 => op("pssbb", vec![]),
+            (0b011, 0b0011, 0b100 | 0b101) => {
+                let mnemonic = if op2 == 0b100 { "dsb" } else { "dmb" };
+                match BARRIER_OPTIONS[crm as usize] {
+                    "" => op(mnemonic, vec![immediate(crm)]),
+                    option => op(mnemonic, vec![Operand::Name(option.to_owned())]),
+                }
+            }
+            (0b011, 0b0011, 0b110) if crm == 15 => op("isb", vec![]),
+            (0b011, 0b0011, 0b110) => op("isb", vec![immediate(crm)]),
+            _ => op("msr", vec![system_register(encoding, WRITE), gpr(true, rt)]),
+        },
+        (false, 0b01) => {
+            let operation = SYS_OPERATIONS
+                .iter()
+                .find(|(number, ..)| *number == bits(word, 5, 14));
+            match operation {
+                Some((_, mnemonic, name, true)) => op(
+                    mnemonic,
+                    vec![Operand::Name(name.to_string()), gpr(true, rt)],
+                ),
+                Some((_, mnemonic, name, false)) => {
+                    op(mnemonic, vec![Operand::Name(name.to_string())])
+                }
+                None => {
+                    let mut operands = vec![
+                        immediate(op1),
+                        Operand::Name(format!("c{crn}")),
+                        Operand::Name(format!("c{crm}")),
+                        immediate(op2),
+                    ];
+                    if rt != 31 {
+                        operands.push(gpr(true, rt));
+                    }
+                    op("sys", operands)
+                }
+            }
+        }
+        (true, 0b01) => op(
+            "sysl",
+            vec![
+                gpr(true, rt),
+                immediate(op1),
+                Operand::Name(format!("c{crn}")),
+                Operand::Name(format!("c{crm}")),
+                immediate(op2),
+            ],
+        ),
+        (false, _) => op("msr", vec![system_register(encoding, WRITE), gpr(true, rt)]),
+        (true, _) => op("mrs", vec![gpr(true, rt), system_register(encoding, READ)]),
+    }
+}
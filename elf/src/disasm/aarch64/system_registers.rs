@@ -0,0 +1,607 @@
+/// The names of the system registers that mrs and msr read and write, by
+/// their encoding (op0, op1, CRn, CRm and op2, from bit 14 down), as
+/// llvm-objdump names them for ARMv8.0. Some can only be read, or only
+/// written, and are written by their encoding in the other instruction;
+/// one encoding is two registers, one for each.
+///
+pub(super) const READ: u8 = 1;
+pub(super) const WRITE: u8 = 2;
+const BOTH: u8 = READ | WRITE;
+
+pub(super) const SYSTEM_REGISTERS: &[(u16, &str, u8)] = &[
+    (0x8002, "OSDTRRX_EL1", BOTH),
+    (0x8004, "DBGBVR0_EL1", BOTH),
+    (0x8005, "DBGBCR0_EL1", BOTH),
+    (0x8006, "DBGWVR0_EL1", BOTH),
+    (0x8007, "DBGWCR0_EL1", BOTH),
+    (0x800c, "DBGBVR1_EL1", BOTH),
+    (0x800d, "DBGBCR1_EL1", BOTH),
+    (0x800e, "DBGWVR1_EL1", BOTH),
+    (0x800f, "DBGWCR1_EL1", BOTH),
+    (0x8010, "MDCCINT_EL1", BOTH),
+    (0x8012, "MDSCR_EL1", BOTH),
+    (0x8014, "DBGBVR2_EL1", BOTH),
+    (0x8015, "DBGBCR2_EL1", BOTH),
+    (0x8016, "DBGWVR2_EL1", BOTH),
+    (0x8017, "DBGWCR2_EL1", BOTH),
+    (0x801a, "OSDTRTX_EL1", BOTH),
+    (0x801c, "DBGBVR3_EL1", BOTH),
+    (0x801d, "DBGBCR3_EL1", BOTH),
+    (0x801e, "DBGWVR3_EL1", BOTH),
+    (0x801f, "DBGWCR3_EL1", BOTH),
+    (0x8024, "DBGBVR4_EL1", BOTH),
+    (0x8025, "DBGBCR4_EL1", BOTH),
+    (0x8026, "DBGWVR4_EL1", BOTH),
+    (0x8027, "DBGWCR4_EL1", BOTH),
+    (0x802c, "DBGBVR5_EL1", BOTH),
+    (0x802d, "DBGBCR5_EL1", BOTH),
+    (0x802e, "DBGWVR5_EL1", BOTH),
+    (0x802f, "DBGWCR5_EL1", BOTH),
+    (0x8032, "OSECCR_EL1", BOTH),
+    (0x8034, "DBGBVR6_EL1", BOTH),
+    (0x8035, "DBGBCR6_EL1", BOTH),
+    (0x8036, "DBGWVR6_EL1", BOTH),
+    (0x8037, "DBGWCR6_EL1", BOTH),
+    (0x803c, "DBGBVR7_EL1", BOTH),
+    (0x803d, "DBGBCR7_EL1", BOTH),
+    (0x803e, "DBGWVR7_EL1", BOTH),
+    (0x803f, "DBGWCR7_EL1", BOTH),
+    (0x8044, "DBGBVR8_EL1", BOTH),
+    (0x8045, "DBGBCR8_EL1", BOTH),
+    (0x8046, "DBGWVR8_EL1", BOTH),
+    (0x8047, "DBGWCR8_EL1", BOTH),
+    (0x804c, "DBGBVR9_EL1", BOTH),
+    (0x804d, "DBGBCR9_EL1", BOTH),
+    (0x804e, "DBGWVR9_EL1", BOTH),
+    (0x804f, "DBGWCR9_EL1", BOTH),
+    (0x8054, "DBGBVR10_EL1", BOTH),
+    (0x8055, "DBGBCR10_EL1", BOTH),
+    (0x8056, "DBGWVR10_EL1", BOTH),
+    (0x8057, "DBGWCR10_EL1", BOTH),
+    (0x805c, "DBGBVR11_EL1", BOTH),
+    (0x805d, "DBGBCR11_EL1", BOTH),
+    (0x805e, "DBGWVR11_EL1", BOTH),
+    (0x805f, "DBGWCR11_EL1", BOTH),
+    (0x8064, "DBGBVR12_EL1", BOTH),
+    (0x8065, "DBGBCR12_EL1", BOTH),
+    (0x8066, "DBGWVR12_EL1", BOTH),
+    (0x8067, "DBGWCR12_EL1", BOTH),
+    (0x806c, "DBGBVR13_EL1", BOTH),
+    (0x806d, "DBGBCR13_EL1", BOTH),
+    (0x806e, "DBGWVR13_EL1", BOTH),
+    (0x806f, "DBGWCR13_EL1", BOTH),
+    (0x8074, "DBGBVR14_EL1", BOTH),
+    (0x8075, "DBGBCR14_EL1", BOTH),
+    (0x8076, "DBGWVR14_EL1", BOTH),
+    (0x8077, "DBGWCR14_EL1", BOTH),
+    (0x807c, "DBGBVR15_EL1", BOTH),
+    (0x807d, "DBGBCR15_EL1", BOTH),
+    (0x807e, "DBGWVR15_EL1", BOTH),
+    (0x807f, "DBGWCR15_EL1", BOTH),
+    (0x8080, "MDRAR_EL1", READ),
+    (0x8084, "OSLAR_EL1", WRITE),
+    (0x808c, "OSLSR_EL1", READ),
+    (0x809c, "OSDLR_EL1", BOTH),
+    (0x80a4, "DBGPRCR_EL1", BOTH),
+    (0x83c6, "DBGCLAIMSET_EL1", BOTH),
+    (0x83ce, "DBGCLAIMCLR_EL1", BOTH),
+    (0x83f6, "DBGAUTHSTATUS_EL1", READ),
+    (0x8801, "TRCTRACEIDR", BOTH),
+    (0x8802, "TRCVICTLR", BOTH),
+    (0x8804, "TRCSEQEVR0", BOTH),
+    (0x8805, "TRCCNTRLDVR0", BOTH),
+    (0x8806, "TRCIDR8", READ),
+    (0x8807, "TRCIMSPEC0", BOTH),
+    (0x8808, "TRCPRGCTLR", BOTH),
+    (0x8809, "TRCQCTLR", BOTH),
+    (0x880a, "TRCVIIECTLR", BOTH),
+    (0x880c, "TRCSEQEVR1", BOTH),
+    (0x880d, "TRCCNTRLDVR1", BOTH),
+    (0x880e, "TRCIDR9", READ),
+    (0x880f, "TRCIMSPEC1", BOTH),
+    (0x8810, "TRCPROCSELR", BOTH),
+    (0x8812, "TRCVISSCTLR", BOTH),
+    (0x8814, "TRCSEQEVR2", BOTH),
+    (0x8815, "TRCCNTRLDVR2", BOTH),
+    (0x8816, "TRCIDR10", READ),
+    (0x8817, "TRCIMSPEC2", BOTH),
+    (0x8818, "TRCSTATR", READ),
+    (0x881a, "TRCVIPCSSCTLR", BOTH),
+    (0x881d, "TRCCNTRLDVR3", BOTH),
+    (0x881e, "TRCIDR11", READ),
+    (0x881f, "TRCIMSPEC3", BOTH),
+    (0x8820, "TRCCONFIGR", BOTH),
+    (0x8825, "TRCCNTCTLR0", BOTH),
+    (0x8826, "TRCIDR12", READ),
+    (0x8827, "TRCIMSPEC4", BOTH),
+    (0x882d, "TRCCNTCTLR1", BOTH),
+    (0x882e, "TRCIDR13", READ),
+    (0x882f, "TRCIMSPEC5", BOTH),
+    (0x8830, "TRCAUXCTLR", BOTH),
+    (0x8834, "TRCSEQRSTEVR", BOTH),
+    (0x8835, "TRCCNTCTLR2", BOTH),
+    (0x8837, "TRCIMSPEC6", BOTH),
+    (0x883c, "TRCSEQSTR", BOTH),
+    (0x883d, "TRCCNTCTLR3", BOTH),
+    (0x883f, "TRCIMSPEC7", BOTH),
+    (0x8840, "TRCEVENTCTL0R", BOTH),
+    (0x8842, "TRCVDCTLR", BOTH),
+    (0x8844, "TRCEXTINSELR", BOTH),
+    (0x8845, "TRCCNTVR0", BOTH),
+    (0x8847, "TRCIDR0", READ),
+    (0x8848, "TRCEVENTCTL1R", BOTH),
+    (0x884a, "TRCVDSACCTLR", BOTH),
+    (0x884c, "TRCEXTINSELR1", BOTH),
+    (0x884d, "TRCCNTVR1", BOTH),
+    (0x884f, "TRCIDR1", READ),
+    (0x8850, "TRCRSR", BOTH),
+    (0x8852, "TRCVDARCCTLR", BOTH),
+    (0x8854, "TRCEXTINSELR2", BOTH),
+    (0x8855, "TRCCNTVR2", BOTH),
+    (0x8857, "TRCIDR2", READ),
+    (0x8858, "TRCSTALLCTLR", BOTH),
+    (0x885c, "TRCEXTINSELR3", BOTH),
+    (0x885d, "TRCCNTVR3", BOTH),
+    (0x885f, "TRCIDR3", READ),
+    (0x8860, "TRCTSCTLR", BOTH),
+    (0x8867, "TRCIDR4", READ),
+    (0x8868, "TRCSYNCPR", BOTH),
+    (0x886f, "TRCIDR5", READ),
+    (0x8870, "TRCCCCTLR", BOTH),
+    (0x8877, "TRCIDR6", READ),
+    (0x8878, "TRCBBCTLR", BOTH),
+    (0x887f, "TRCIDR7", READ),
+    (0x8881, "TRCRSCTLR16", BOTH),
+    (0x8882, "TRCSSCCR0", BOTH),
+    (0x8883, "TRCSSPCICR0", BOTH),
+    (0x8884, "TRCOSLAR", WRITE),
+    (0x8889, "TRCRSCTLR17", BOTH),
+    (0x888a, "TRCSSCCR1", BOTH),
+    (0x888b, "TRCSSPCICR1", BOTH),
+    (0x888c, "TRCOSLSR", READ),
+    (0x8890, "TRCRSCTLR2", BOTH),
+    (0x8891, "TRCRSCTLR18", BOTH),
+    (0x8892, "TRCSSCCR2", BOTH),
+    (0x8893, "TRCSSPCICR2", BOTH),
+    (0x8898, "TRCRSCTLR3", BOTH),
+    (0x8899, "TRCRSCTLR19", BOTH),
+    (0x889a, "TRCSSCCR3", BOTH),
+    (0x889b, "TRCSSPCICR3", BOTH),
+    (0x88a0, "TRCRSCTLR4", BOTH),
+    (0x88a1, "TRCRSCTLR20", BOTH),
+    (0x88a2, "TRCSSCCR4", BOTH),
+    (0x88a3, "TRCSSPCICR4", BOTH),
+    (0x88a4, "TRCPDCR", BOTH),
+    (0x88a8, "TRCRSCTLR5", BOTH),
+    (0x88a9, "TRCRSCTLR21", BOTH),
+    (0x88aa, "TRCSSCCR5", BOTH),
+    (0x88ab, "TRCSSPCICR5", BOTH),
+    (0x88ac, "TRCPDSR", READ),
+    (0x88b0, "TRCRSCTLR6", BOTH),
+    (0x88b1, "TRCRSCTLR22", BOTH),
+    (0x88b2, "TRCSSCCR6", BOTH),
+    (0x88b3, "TRCSSPCICR6", BOTH),
+    (0x88b8, "TRCRSCTLR7", BOTH),
+    (0x88b9, "TRCRSCTLR23", BOTH),
+    (0x88ba, "TRCSSCCR7", BOTH),
+    (0x88bb, "TRCSSPCICR7", BOTH),
+    (0x88c0, "TRCRSCTLR8", BOTH),
+    (0x88c1, "TRCRSCTLR24", BOTH),
+    (0x88c2, "TRCSSCSR0", BOTH),
+    (0x88c8, "TRCRSCTLR9", BOTH),
+    (0x88c9, "TRCRSCTLR25", BOTH),
+    (0x88ca, "TRCSSCSR1", BOTH),
+    (0x88d0, "TRCRSCTLR10", BOTH),
+    (0x88d1, "TRCRSCTLR26", BOTH),
+    (0x88d2, "TRCSSCSR2", BOTH),
+    (0x88d8, "TRCRSCTLR11", BOTH),
+    (0x88d9, "TRCRSCTLR27", BOTH),
+    (0x88da, "TRCSSCSR3", BOTH),
+    (0x88e0, "TRCRSCTLR12", BOTH),
+    (0x88e1, "TRCRSCTLR28", BOTH),
+    (0x88e2, "TRCSSCSR4", BOTH),
+    (0x88e8, "TRCRSCTLR13", BOTH),
+    (0x88e9, "TRCRSCTLR29", BOTH),
+    (0x88ea, "TRCSSCSR5", BOTH),
+    (0x88f0, "TRCRSCTLR14", BOTH),
+    (0x88f1, "TRCRSCTLR30", BOTH),
+    (0x88f2, "TRCSSCSR6", BOTH),
+    (0x88f8, "TRCRSCTLR15", BOTH),
+    (0x88f9, "TRCRSCTLR31", BOTH),
+    (0x88fa, "TRCSSCSR7", BOTH),
+    (0x8900, "TRCACVR0", BOTH),
+    (0x8901, "TRCACVR8", BOTH),
+    (0x8902, "TRCACATR0", BOTH),
+    (0x8903, "TRCACATR8", BOTH),
+    (0x8904, "TRCDVCVR0", BOTH),
+    (0x8905, "TRCDVCVR4", BOTH),
+    (0x8906, "TRCDVCMR0", BOTH),
+    (0x8907, "TRCDVCMR4", BOTH),
+    (0x8910, "TRCACVR1", BOTH),
+    (0x8911, "TRCACVR9", BOTH),
+    (0x8912, "TRCACATR1", BOTH),
+    (0x8913, "TRCACATR9", BOTH),
+    (0x8920, "TRCACVR2", BOTH),
+    (0x8921, "TRCACVR10", BOTH),
+    (0x8922, "TRCACATR2", BOTH),
+    (0x8923, "TRCACATR10", BOTH),
+    (0x8924, "TRCDVCVR1", BOTH),
+    (0x8925, "TRCDVCVR5", BOTH),
+    (0x8926, "TRCDVCMR1", BOTH),
+    (0x8927, "TRCDVCMR5", BOTH),
+    (0x8930, "TRCACVR3", BOTH),
+    (0x8931, "TRCACVR11", BOTH),
+    (0x8932, "TRCACATR3", BOTH),
+    (0x8933, "TRCACATR11", BOTH),
+    (0x8940, "TRCACVR4", BOTH),
+    (0x8941, "TRCACVR12", BOTH),
+    (0x8942, "TRCACATR4", BOTH),
+    (0x8943, "TRCACATR12", BOTH),
+    (0x8944, "TRCDVCVR2", BOTH),
+    (0x8945, "TRCDVCVR6", BOTH),
+    (0x8946, "TRCDVCMR2", BOTH),
+    (0x8947, "TRCDVCMR6", BOTH),
+    (0x8950, "TRCACVR5", BOTH),
+    (0x8951, "TRCACVR13", BOTH),
+    (0x8952, "TRCACATR5", BOTH),
+    (0x8953, "TRCACATR13", BOTH),
+    (0x8960, "TRCACVR6", BOTH),
+    (0x8961, "TRCACVR14", BOTH),
+    (0x8962, "TRCACATR6", BOTH),
+    (0x8963, "TRCACATR14", BOTH),
+    (0x8964, "TRCDVCVR3", BOTH),
+    (0x8965, "TRCDVCVR7", BOTH),
+    (0x8966, "TRCDVCMR3", BOTH),
+    (0x8967, "TRCDVCMR7", BOTH),
+    (0x8970, "TRCACVR7", BOTH),
+    (0x8971, "TRCACVR15", BOTH),
+    (0x8972, "TRCACATR7", BOTH),
+    (0x8973, "TRCACATR15", BOTH),
+    (0x8980, "TRCCIDCVR0", BOTH),
+    (0x8981, "TRCVMIDCVR0", BOTH),
+    (0x8982, "TRCCIDCCTLR0", BOTH),
+    (0x898a, "TRCCIDCCTLR1", BOTH),
+    (0x8990, "TRCCIDCVR1", BOTH),
+    (0x8991, "TRCVMIDCVR1", BOTH),
+    (0x8992, "TRCVMIDCCTLR0", BOTH),
+    (0x899a, "TRCVMIDCCTLR1", BOTH),
+    (0x89a0, "TRCCIDCVR2", BOTH),
+    (0x89a1, "TRCVMIDCVR2", BOTH),
+    (0x89b0, "TRCCIDCVR3", BOTH),
+    (0x89b1, "TRCVMIDCVR3", BOTH),
+    (0x89c0, "TRCCIDCVR4", BOTH),
+    (0x89c1, "TRCVMIDCVR4", BOTH),
+    (0x89d0, "TRCCIDCVR5", BOTH),
+    (0x89d1, "TRCVMIDCVR5", BOTH),
+    (0x89e0, "TRCCIDCVR6", BOTH),
+    (0x89e1, "TRCVMIDCVR6", BOTH),
+    (0x89f0, "TRCCIDCVR7", BOTH),
+    (0x89f1, "TRCVMIDCVR7", BOTH),
+    (0x8b84, "TRCITCTRL", BOTH),
+    (0x8b97, "TRCDEVID", READ),
+    (0x8b9f, "TRCDEVTYPE", READ),
+    (0x8ba7, "TRCPIDR4", READ),
+    (0x8baf, "TRCPIDR5", READ),
+    (0x8bb7, "TRCPIDR6", READ),
+    (0x8bbf, "TRCPIDR7", READ),
+    (0x8bc6, "TRCCLAIMSET", BOTH),
+    (0x8bc7, "TRCPIDR0", READ),
+    (0x8bce, "TRCCLAIMCLR", BOTH),
+    (0x8bcf, "TRCPIDR1", READ),
+    (0x8bd6, "TRCDEVAFF0", READ),
+    (0x8bd7, "TRCPIDR2", READ),
+    (0x8bde, "TRCDEVAFF1", READ),
+    (0x8bdf, "TRCPIDR3", READ),
+    (0x8be6, "TRCLAR", WRITE),
+    (0x8be7, "TRCCIDR0", READ),
+    (0x8bee, "TRCLSR", READ),
+    (0x8bef, "TRCCIDR1", READ),
+    (0x8bf6, "TRCAUTHSTATUS", READ),
+    (0x8bf7, "TRCCIDR2", READ),
+    (0x8bfe, "TRCDEVARCH", READ),
+    (0x8bff, "TRCCIDR3", READ),
+    (0x9000, "TEECR32_EL1", BOTH),
+    (0x9080, "TEEHBR32_EL1", BOTH),
+    (0x9808, "MDCCSR_EL0", READ),
+    (0x9820, "DBGDTR_EL0", BOTH),
+    (0x9828, "DBGDTRRX_EL0", READ),
+    (0x9828, "DBGDTRTX_EL0", WRITE),
+    (0xa038, "DBGVCR32_EL2", BOTH),
+    (0xc000, "MIDR_EL1", READ),
+    (0xc005, "MPIDR_EL1", READ),
+    (0xc006, "REVIDR_EL1", READ),
+    (0xc008, "ID_PFR0_EL1", READ),
+    (0xc009, "ID_PFR1_EL1", READ),
+    (0xc00a, "ID_DFR0_EL1", READ),
+    (0xc00b, "ID_AFR0_EL1", READ),
+    (0xc00c, "ID_MMFR0_EL1", READ),
+    (0xc00d, "ID_MMFR1_EL1", READ),
+    (0xc00e, "ID_MMFR2_EL1", READ),
+    (0xc00f, "ID_MMFR3_EL1", READ),
+    (0xc010, "ID_ISAR0_EL1", READ),
+    (0xc011, "ID_ISAR1_EL1", READ),
+    (0xc012, "ID_ISAR2_EL1", READ),
+    (0xc013, "ID_ISAR3_EL1", READ),
+    (0xc014, "ID_ISAR4_EL1", READ),
+    (0xc015, "ID_ISAR5_EL1", READ),
+    (0xc016, "ID_MMFR4_EL1", READ),
+    (0xc018, "MVFR0_EL1", READ),
+    (0xc019, "MVFR1_EL1", READ),
+    (0xc01a, "MVFR2_EL1", READ),
+    (0xc01e, "ID_MMFR5_EL1", READ),
+    (0xc020, "ID_AA64PFR0_EL1", READ),
+    (0xc021, "ID_AA64PFR1_EL1", READ),
+    (0xc028, "ID_AA64DFR0_EL1", READ),
+    (0xc029, "ID_AA64DFR1_EL1", READ),
+    (0xc02c, "ID_AA64AFR0_EL1", READ),
+    (0xc02d, "ID_AA64AFR1_EL1", READ),
+    (0xc030, "ID_AA64ISAR0_EL1", READ),
+    (0xc031, "ID_AA64ISAR1_EL1", READ),
+    (0xc032, "ID_AA64ISAR2_EL1", READ),
+    (0xc038, "ID_AA64MMFR0_EL1", READ),
+    (0xc039, "ID_AA64MMFR1_EL1", READ),
+    (0xc03a, "ID_AA64MMFR2_EL1", READ),
+    (0xc080, "SCTLR_EL1", BOTH),
+    (0xc081, "ACTLR_EL1", BOTH),
+    (0xc082, "CPACR_EL1", BOTH),
+    (0xc100, "TTBR0_EL1", BOTH),
+    (0xc101, "TTBR1_EL1", BOTH),
+    (0xc102, "TCR_EL1", BOTH),
+    (0xc200, "SPSR_EL1", BOTH),
+    (0xc201, "ELR_EL1", BOTH),
+    (0xc208, "SP_EL0", BOTH),
+    (0xc210, "SPSel", BOTH),
+    (0xc212, "CurrentEL", READ),
+    (0xc230, "ICC_PMR_EL1", BOTH),
+    (0xc288, "AFSR0_EL1", BOTH),
+    (0xc289, "AFSR1_EL1", BOTH),
+    (0xc290, "ESR_EL1", BOTH),
+    (0xc2a4, "ERXPFGF_EL1", READ),
+    (0xc2a5, "ERXPFGCTL_EL1", BOTH),
+    (0xc2a6, "ERXPFGCDN_EL1", BOTH),
+    (0xc2aa, "ERXMISC2_EL1", BOTH),
+    (0xc2ab, "ERXMISC3_EL1", BOTH),
+    (0xc300, "FAR_EL1", BOTH),
+    (0xc3a0, "PAR_EL1", BOTH),
+    (0xc4d8, "TRBLIMITR_EL1", BOTH),
+    (0xc4d9, "TRBPTR_EL1", BOTH),
+    (0xc4da, "TRBBASER_EL1", BOTH),
+    (0xc4db, "TRBSR_EL1", BOTH),
+    (0xc4dc, "TRBMAR_EL1", BOTH),
+    (0xc4de, "TRBTRG_EL1", BOTH),
+    (0xc4df, "TRBIDR_EL1", READ),
+    (0xc4f1, "PMINTENSET_EL1", BOTH),
+    (0xc4f2, "PMINTENCLR_EL1", BOTH),
+    (0xc4f6, "PMMIR_EL1", BOTH),
+    (0xc510, "MAIR_EL1", BOTH),
+    (0xc518, "AMAIR_EL1", BOTH),
+    (0xc600, "VBAR_EL1", BOTH),
+    (0xc601, "RVBAR_EL1", READ),
+    (0xc602, "RMR_EL1", BOTH),
+    (0xc608, "ISR_EL1", READ),
+    (0xc640, "ICC_IAR0_EL1", READ),
+    (0xc641, "ICC_EOIR0_EL1", WRITE),
+    (0xc642, "ICC_HPPIR0_EL1", READ),
+    (0xc643, "ICC_BPR0_EL1", BOTH),
+    (0xc644, "ICC_AP0R0_EL1", BOTH),
+    (0xc645, "ICC_AP0R1_EL1", BOTH),
+    (0xc646, "ICC_AP0R2_EL1", BOTH),
+    (0xc647, "ICC_AP0R3_EL1", BOTH),
+    (0xc648, "ICC_AP1R0_EL1", BOTH),
+    (0xc649, "ICC_AP1R1_EL1", BOTH),
+    (0xc64a, "ICC_AP1R2_EL1", BOTH),
+    (0xc64b, "ICC_AP1R3_EL1", BOTH),
+    (0xc659, "ICC_DIR_EL1", WRITE),
+    (0xc65b, "ICC_RPR_EL1", READ),
+    (0xc65d, "ICC_SGI1R_EL1", WRITE),
+    (0xc65e, "ICC_ASGI1R_EL1", WRITE),
+    (0xc65f, "ICC_SGI0R_EL1", WRITE),
+    (0xc660, "ICC_IAR1_EL1", READ),
+    (0xc661, "ICC_EOIR1_EL1", WRITE),
+    (0xc662, "ICC_HPPIR1_EL1", READ),
+    (0xc663, "ICC_BPR1_EL1", BOTH),
+    (0xc664, "ICC_CTLR_EL1", BOTH),
+    (0xc665, "ICC_SRE_EL1", BOTH),
+    (0xc666, "ICC_IGRPEN0_EL1", BOTH),
+    (0xc667, "ICC_IGRPEN1_EL1", BOTH),
+    (0xc681, "CONTEXTIDR_EL1", BOTH),
+    (0xc684, "TPIDR_EL1", BOTH),
+    (0xc708, "CNTKCTL_EL1", BOTH),
+    (0xc800, "CCSIDR_EL1", READ),
+    (0xc801, "CLIDR_EL1", READ),
+    (0xc807, "AIDR_EL1", READ),
+    (0xd000, "CSSELR_EL1", BOTH),
+    (0xd801, "CTR_EL0", READ),
+    (0xd807, "DCZID_EL0", READ),
+    (0xda10, "NZCV", BOTH),
+    (0xda11, "DAIF", BOTH),
+    (0xda20, "FPCR", BOTH),
+    (0xda21, "FPSR", BOTH),
+    (0xda28, "DSPSR_EL0", BOTH),
+    (0xda29, "DLR_EL0", BOTH),
+    (0xdce0, "PMCR_EL0", BOTH),
+    (0xdce1, "PMCNTENSET_EL0", BOTH),
+    (0xdce2, "PMCNTENCLR_EL0", BOTH),
+    (0xdce3, "PMOVSCLR_EL0", BOTH),
+    (0xdce4, "PMSWINC_EL0", WRITE),
+    (0xdce5, "PMSELR_EL0", BOTH),
+    (0xdce6, "PMCEID0_EL0", READ),
+    (0xdce7, "PMCEID1_EL0", READ),
+    (0xdce8, "PMCCNTR_EL0", BOTH),
+    (0xdce9, "PMXEVTYPER_EL0", BOTH),
+    (0xdcea, "PMXEVCNTR_EL0", BOTH),
+    (0xdcf0, "PMUSERENR_EL0", BOTH),
+    (0xdcf3, "PMOVSSET_EL0", BOTH),
+    (0xde82, "TPIDR_EL0", BOTH),
+    (0xde83, "TPIDRRO_EL0", BOTH),
+    (0xdf00, "CNTFRQ_EL0", BOTH),
+    (0xdf01, "CNTPCT_EL0", READ),
+    (0xdf02, "CNTVCT_EL0", READ),
+    (0xdf10, "CNTP_TVAL_EL0", BOTH),
+    (0xdf11, "CNTP_CTL_EL0", BOTH),
+    (0xdf12, "CNTP_CVAL_EL0", BOTH),
+    (0xdf18, "CNTV_TVAL_EL0", BOTH),
+    (0xdf19, "CNTV_CTL_EL0", BOTH),
+    (0xdf1a, "CNTV_CVAL_EL0", BOTH),
+    (0xdf40, "PMEVCNTR0_EL0", BOTH),
+    (0xdf41, "PMEVCNTR1_EL0", BOTH),
+    (0xdf42, "PMEVCNTR2_EL0", BOTH),
+    (0xdf43, "PMEVCNTR3_EL0", BOTH),
+    (0xdf44, "PMEVCNTR4_EL0", BOTH),
+    (0xdf45, "PMEVCNTR5_EL0", BOTH),
+    (0xdf46, "PMEVCNTR6_EL0", BOTH),
+    (0xdf47, "PMEVCNTR7_EL0", BOTH),
+    (0xdf48, "PMEVCNTR8_EL0", BOTH),
+    (0xdf49, "PMEVCNTR9_EL0", BOTH),
+    (0xdf4a, "PMEVCNTR10_EL0", BOTH),
+    (0xdf4b, "PMEVCNTR11_EL0", BOTH),
+    (0xdf4c, "PMEVCNTR12_EL0", BOTH),
+    (0xdf4d, "PMEVCNTR13_EL0", BOTH),
+    (0xdf4e, "PMEVCNTR14_EL0", BOTH),
+    (0xdf4f, "PMEVCNTR15_EL0", BOTH),
+    (0xdf50, "PMEVCNTR16_EL0", BOTH),
+    (0xdf51, "PMEVCNTR17_EL0", BOTH),
+    (0xdf52, "PMEVCNTR18_EL0", BOTH),
+    (0xdf53, "PMEVCNTR19_EL0", BOTH),
+    (0xdf54, "PMEVCNTR20_EL0", BOTH),
+    (0xdf55, "PMEVCNTR21_EL0", BOTH),
+    (0xdf56, "PMEVCNTR22_EL0", BOTH),
+    (0xdf57, "PMEVCNTR23_EL0", BOTH),
+    (0xdf58, "PMEVCNTR24_EL0", BOTH),
+    (0xdf59, "PMEVCNTR25_EL0", BOTH),
+    (0xdf5a, "PMEVCNTR26_EL0", BOTH),
+    (0xdf5b, "PMEVCNTR27_EL0", BOTH),
+    (0xdf5c, "PMEVCNTR28_EL0", BOTH),
+    (0xdf5d, "PMEVCNTR29_EL0", BOTH),
+    (0xdf5e, "PMEVCNTR30_EL0", BOTH),
+    (0xdf60, "PMEVTYPER0_EL0", BOTH),
+    (0xdf61, "PMEVTYPER1_EL0", BOTH),
+    (0xdf62, "PMEVTYPER2_EL0", BOTH),
+    (0xdf63, "PMEVTYPER3_EL0", BOTH),
+    (0xdf64, "PMEVTYPER4_EL0", BOTH),
+    (0xdf65, "PMEVTYPER5_EL0", BOTH),
+    (0xdf66, "PMEVTYPER6_EL0", BOTH),
+    (0xdf67, "PMEVTYPER7_EL0", BOTH),
+    (0xdf68, "PMEVTYPER8_EL0", BOTH),
+    (0xdf69, "PMEVTYPER9_EL0", BOTH),
+    (0xdf6a, "PMEVTYPER10_EL0", BOTH),
+    (0xdf6b, "PMEVTYPER11_EL0", BOTH),
+    (0xdf6c, "PMEVTYPER12_EL0", BOTH),
+    (0xdf6d, "PMEVTYPER13_EL0", BOTH),
+    (0xdf6e, "PMEVTYPER14_EL0", BOTH),
+    (0xdf6f, "PMEVTYPER15_EL0", BOTH),
+    (0xdf70, "PMEVTYPER16_EL0", BOTH),
+    (0xdf71, "PMEVTYPER17_EL0", BOTH),
+    (0xdf72, "PMEVTYPER18_EL0", BOTH),
+    (0xdf73, "PMEVTYPER19_EL0", BOTH),
+    (0xdf74, "PMEVTYPER20_EL0", BOTH),
+    (0xdf75, "PMEVTYPER21_EL0", BOTH),
+    (0xdf76, "PMEVTYPER22_EL0", BOTH),
+    (0xdf77, "PMEVTYPER23_EL0", BOTH),
+    (0xdf78, "PMEVTYPER24_EL0", BOTH),
+    (0xdf79, "PMEVTYPER25_EL0", BOTH),
+    (0xdf7a, "PMEVTYPER26_EL0", BOTH),
+    (0xdf7b, "PMEVTYPER27_EL0", BOTH),
+    (0xdf7c, "PMEVTYPER28_EL0", BOTH),
+    (0xdf7d, "PMEVTYPER29_EL0", BOTH),
+    (0xdf7e, "PMEVTYPER30_EL0", BOTH),
+    (0xdf7f, "PMCCFILTR_EL0", BOTH),
+    (0xe000, "VPIDR_EL2", BOTH),
+    (0xe005, "VMPIDR_EL2", BOTH),
+    (0xe080, "SCTLR_EL2", BOTH),
+    (0xe081, "ACTLR_EL2", BOTH),
+    (0xe088, "HCR_EL2", BOTH),
+    (0xe089, "MDCR_EL2", BOTH),
+    (0xe08a, "CPTR_EL2", BOTH),
+    (0xe08b, "HSTR_EL2", BOTH),
+    (0xe08f, "HACR_EL2", BOTH),
+    (0xe100, "TTBR0_EL2", BOTH),
+    (0xe102, "TCR_EL2", BOTH),
+    (0xe108, "VTTBR_EL2", BOTH),
+    (0xe10a, "VTCR_EL2", BOTH),
+    (0xe130, "VSTTBR_EL2", BOTH),
+    (0xe180, "DACR32_EL2", BOTH),
+    (0xe200, "SPSR_EL2", BOTH),
+    (0xe201, "ELR_EL2", BOTH),
+    (0xe208, "SP_EL1", BOTH),
+    (0xe218, "SPSR_irq", BOTH),
+    (0xe219, "SPSR_abt", BOTH),
+    (0xe21a, "SPSR_und", BOTH),
+    (0xe21b, "SPSR_fiq", BOTH),
+    (0xe281, "IFSR32_EL2", BOTH),
+    (0xe288, "AFSR0_EL2", BOTH),
+    (0xe289, "AFSR1_EL2", BOTH),
+    (0xe290, "ESR_EL2", BOTH),
+    (0xe298, "FPEXC32_EL2", BOTH),
+    (0xe300, "FAR_EL2", BOTH),
+    (0xe304, "HPFAR_EL2", BOTH),
+    (0xe510, "MAIR_EL2", BOTH),
+    (0xe518, "AMAIR_EL2", BOTH),
+    (0xe600, "VBAR_EL2", BOTH),
+    (0xe601, "RVBAR_EL2", READ),
+    (0xe602, "RMR_EL2", BOTH),
+    (0xe640, "ICH_AP0R0_EL2", BOTH),
+    (0xe641, "ICH_AP0R1_EL2", BOTH),
+    (0xe642, "ICH_AP0R2_EL2", BOTH),
+    (0xe643, "ICH_AP0R3_EL2", BOTH),
+    (0xe648, "ICH_AP1R0_EL2", BOTH),
+    (0xe649, "ICH_AP1R1_EL2", BOTH),
+    (0xe64a, "ICH_AP1R2_EL2", BOTH),
+    (0xe64b, "ICH_AP1R3_EL2", BOTH),
+    (0xe64d, "ICC_SRE_EL2", BOTH),
+    (0xe658, "ICH_HCR_EL2", BOTH),
+    (0xe659, "ICH_VTR_EL2", READ),
+    (0xe65a, "ICH_MISR_EL2", READ),
+    (0xe65b, "ICH_EISR_EL2", READ),
+    (0xe65d, "ICH_ELRSR_EL2", READ),
+    (0xe65f, "ICH_VMCR_EL2", BOTH),
+    (0xe660, "ICH_LR0_EL2", BOTH),
+    (0xe661, "ICH_LR1_EL2", BOTH),
+    (0xe662, "ICH_LR2_EL2", BOTH),
+    (0xe663, "ICH_LR3_EL2", BOTH),
+    (0xe664, "ICH_LR4_EL2", BOTH),
+    (0xe665, "ICH_LR5_EL2", BOTH),
+    (0xe666, "ICH_LR6_EL2", BOTH),
+    (0xe667, "ICH_LR7_EL2", BOTH),
+    (0xe668, "ICH_LR8_EL2", BOTH),
+    (0xe669, "ICH_LR9_EL2", BOTH),
+    (0xe66a, "ICH_LR10_EL2", BOTH),
+    (0xe66b, "ICH_LR11_EL2", BOTH),
+    (0xe66c, "ICH_LR12_EL2", BOTH),
+    (0xe66d, "ICH_LR13_EL2", BOTH),
+    (0xe66e, "ICH_LR14_EL2", BOTH),
+    (0xe66f, "ICH_LR15_EL2", BOTH),
+    (0xe682, "TPIDR_EL2", BOTH),
+    (0xe703, "CNTVOFF_EL2", BOTH),
+    (0xe708, "CNTHCTL_EL2", BOTH),
+    (0xe710, "CNTHP_TVAL_EL2", BOTH),
+    (0xe711, "CNTHP_CTL_EL2", BOTH),
+    (0xe712, "CNTHP_CVAL_EL2", BOTH),
+    (0xf080, "SCTLR_EL3", BOTH),
+    (0xf081, "ACTLR_EL3", BOTH),
+    (0xf088, "SCR_EL3", BOTH),
+    (0xf089, "SDER32_EL3", BOTH),
+    (0xf08a, "CPTR_EL3", BOTH),
+    (0xf099, "MDCR_EL3", BOTH),
+    (0xf100, "TTBR0_EL3", BOTH),
+    (0xf102, "TCR_EL3", BOTH),
+    (0xf200, "SPSR_EL3", BOTH),
+    (0xf201, "ELR_EL3", BOTH),
+    (0xf208, "SP_EL2", BOTH),
+    (0xf288, "AFSR0_EL3", BOTH),
+    (0xf289, "AFSR1_EL3", BOTH),
+    (0xf290, "ESR_EL3", BOTH),
+    (0xf300, "FAR_EL3", BOTH),
+    (0xf510, "MAIR_EL3", BOTH),
+    (0xf518, "AMAIR_EL3", BOTH),
+    (0xf600, "VBAR_EL3", BOTH),
+    (0xf601, "RVBAR_EL3", READ),
+    (0xf602, "RMR_EL3", BOTH),
+    (0xf664, "ICC_CTLR_EL3", BOTH),
+    (0xf665, "ICC_SRE_EL3", BOTH),
+    (0xf667, "ICC_IGRPEN1_EL3", BOTH),
+    (0xf682, "TPIDR_EL3", BOTH),
+    (0xff10, "CNTPS_TVAL_EL1", BOTH),
+    (0xff11, "CNTPS_CTL_EL1", BOTH),
+    (0xff12, "CNTPS_CVAL_EL1", BOTH),
+];
@@ -0,0 +1,420 @@
+/// Recursive-descent disassembly: following the code from the entry point
+/// and the function symbols, through jumps, branches and calls, rather than
+/// sweeping through whole sections. Bytes that control never reaches (jump
+/// tables, literal pools, padding) aren't decoded as instructions. What's
+/// reached is split into basic blocks, each function's into a control flow
+/// graph, which can be written in Graphviz's DOT language.
+///
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::disasm::{Disassembler, Flow, Instruction};
+use crate::parse::symbols::*;
+use crate::parse::types::*;
+use crate::parse::{SHF_ALLOC, SHF_EXECINSTR};
+use crate::{ElfError, ElfFile};
+
+/// How control gets from one block to the next.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EdgeKind {
+    /// A jump, always taken.
+    Jump,
+    /// A conditional branch, taken.
+    Taken,
+    /// A conditional branch, not taken.
+    NotTaken,
+    /// On to the next instruction, which another block starts with (or
+    /// after a call).
+    Fallthrough,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Edge {
+    pub target: u64,
+    pub kind: EdgeKind,
+}
+
+/// A run of instructions that control enters only at the first and leaves
+/// only after the last.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BasicBlock {
+    pub start: u64,
+    pub instructions: Vec<Instruction>,
+    /// The blocks of the same function that control goes to next. Jumps
+    /// out of the function, and returns, have none.
+    pub successors: Vec<Edge>,
+}
+
+impl BasicBlock {
+    /// The address just after the last instruction.
+    pub fn end(&self) -> u64 {
+        self.instructions
+            .last()
+            .map_or(self.start, |last| last.address() + last.size() as u64)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Function {
+    /// The function's symbol, or `sub_` and its address if it has none.
+    pub name: String,
+    pub address: u64,
+    /// In order of address, starting with the one at `address`.
+    pub blocks: Vec<BasicBlock>,
+    /// The functions it calls, or jumps to as a tail call, in order of
+    /// address.
+    pub calls: Vec<u64>,
+}
+
+impl Function {
+    /// The block that starts at `address`.
+    pub fn block(&self, address: u64) -> Option<&BasicBlock> {
+        let index = self
+            .blocks
+            .binary_search_by_key(&address, |block| block.start)
+            .ok()?;
+        Some(&self.blocks[index])
+    }
+
+    /// Writes the function's control flow graph as a Graphviz digraph, with
+    /// a node for each block, listing its instructions. Taken branches are
+    /// green and branches not taken red. `symbolize` names addresses in
+    /// the instructions, as in `main+0x1c`.
+    pub fn to_dot(&self, symbolize: &dyn Fn(u64) -> Option<String>) -> String {
+        let mut dot = format!("digraph \"{}\" {{\n", escape(&self.name));
+        dot.push_str("    node [shape=box, fontname=\"monospace\"];\n");
+
+        for block in &self.blocks {
+            let mut label = String::new();
+            for instruction in &block.instructions {
+                let text = instruction.format(symbolize).replace('\t', " ");
+                label += &format!("{:x}: {}\\l", instruction.address(), escape(&text));
+            }
+            dot += &format!("    \"{:#x}\" [label=\"{label}\"];\n", block.start);
+        }
+        for block in &self.blocks {
+            for edge in &block.successors {
+                let style = match edge.kind {
+                    EdgeKind::Taken => " [color=green]",
+                    EdgeKind::NotTaken => " [color=red]",
+                    EdgeKind::Jump | EdgeKind::Fallthrough => "",
+                };
+                dot += &format!(
+                    "    \"{:#x}\" -> \"{:#x}\"{style};\n",
+                    block.start, edge.target
+                );
+            }
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// Escapes a string for a quoted DOT string.
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// The functions of a file's code, each with its control flow graph.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ControlFlow {
+    /// In order of address.
+    pub functions: Vec<Function>,
+}
+
+/// The bytes of an executable section, where they'll be in memory.
+struct Code {
+    address: u64,
+    bytes: Vec<u8>,
+}
+
+impl ControlFlow {
+    /// Disassembles a file's code by following it, from the entry point
+    /// and the function symbols (of the symbol table, or the dynamic one if
+    /// the file is stripped), and then from what they call. Each of them is
+    /// a function, whose code is what it reaches without going through
+    /// another's start.
+    pub fn for_file(
+        elf_file: &mut ElfFile,
+        disassembler: Disassembler,
+    ) -> Result<ControlFlow, ElfError> {
+        let mut code = vec![];
+        for index in 0..elf_file.section_headers.len() {
+            let header = &elf_file.section_headers[index].header_data;
+            let executable = SHF_ALLOC | SHF_EXECINSTR;
+            if header.flags & executable != executable || header.type_id() == SHT_NOBITS {
+                continue;
+            }
+            let address = header.addr;
+            let bytes = elf_file.section_bytes(index)?.to_vec();
+            code.push(Code { address, bytes });
+        }
+
+        let symbols = Names::read(elf_file, &code)?;
+        let mut names = symbols.functions.clone();
+        let entry = elf_file.header.entry_point;
+        if bytes_at(&code, entry).is_some() {
+            names
+                .entry(entry)
+                .or_insert_with(|| symbols.for_unnamed(entry));
+        }
+
+        // First, everything that can be reached, for what's called.
+        let mut walk = Walk::new(&code, disassembler);
+        for &address in names.keys() {
+            walk.follow(address, &BTreeSet::new(), true);
+        }
+        for address in walk.calls {
+            if bytes_at(&code, address).is_some() {
+                names
+                    .entry(address)
+                    .or_insert_with(|| symbols.for_unnamed(address));
+            }
+        }
+
+        // Then each function, up to the others' starts.
+        let entries: BTreeSet<u64> = names.keys().copied().collect();
+        let functions = names
+            .into_iter()
+            .map(|(address, name)| {
+                let mut walk = Walk::new(&code, disassembler);
+                walk.follow(address, &entries, false);
+                Function {
+                    name,
+                    address,
+                    blocks: walk.blocks(),
+                    calls: walk.calls.into_iter().collect(),
+                }
+            })
+            .collect();
+
+        Ok(ControlFlow { functions })
+    }
+
+    /// The function with this name.
+    pub fn function(&self, name: &str) -> Option<&Function> {
+        self.functions.iter().find(|function| function.name == name)
+    }
+}
+
+/// The bytes of code from `address` to the end of its section.
+fn bytes_at(code: &[Code], address: u64) -> Option<&[u8]> {
+    code.iter().find_map(|section| {
+        let offset = address.checked_sub(section.address)?;
+        section
+            .bytes
+            .get(offset as usize..)
+            .filter(|bytes| !bytes.is_empty())
+    })
+}
+
+/// The names of the symbols in the code, by address: of functions, and of
+/// anything else (labels with no type, like `_start` often is, and PLT
+/// stubs, as in `printf@plt`), for code that's reached but has no function
+/// symbol. Where several share an address, a global symbol is picked over
+/// a weak one over a local one.
+struct Names {
+    functions: BTreeMap<u64, String>,
+    others: BTreeMap<u64, String>,
+}
+
+impl Names {
+    fn read(elf_file: &mut ElfFile, code: &[Code]) -> Result<Names, ElfError> {
+        let mut functions = BTreeMap::new();
+        let mut others = BTreeMap::new();
+
+        let table = match elf_file.section_indices_by_type(SHT_SYMTAB).first() {
+            Some(&index) => Some(index),
+            None => elf_file
+                .section_indices_by_type(SHT_DYNSYM)
+                .first()
+                .copied(),
+        };
+        let symbols = match table {
+            Some(table) => elf_file.symbols(table)?,
+            None => vec![],
+        };
+
+        for symbol in symbols {
+            let entry = &symbol.symbol_data;
+            if symbol.name.is_empty()
+                || entry.section_index == 0
+                || bytes_at(code, entry.value).is_none()
+            {
+                continue;
+            }
+            let names = match entry.symbol_type() {
+                STT_FUNC | STT_GNU_IFUNC => &mut functions,
+                STT_NOTYPE => &mut others,
+                _ => continue,
+            };
+            let rank = match entry.bind() {
+                STB_GLOBAL => 0,
+                STB_WEAK => 1,
+                _ => 2,
+            };
+            let candidate = (rank, symbol.name);
+            match names.get(&entry.value) {
+                Some(best) if *best <= candidate => {}
+                _ => {
+                    names.insert(entry.value, candidate);
+                }
+            }
+        }
+
+        for import in elf_file.imports()?.imports {
+            if let Some(plt) = import.plt {
+                others
+                    .entry(plt)
+                    .or_insert_with(|| (3, format!("{}@plt", import.name)));
+            }
+        }
+
+        let names = |ranked: BTreeMap<u64, (u8, String)>| {
+            ranked
+                .into_iter()
+                .map(|(address, (_, name))| (address, name))
+                .collect()
+        };
+        Ok(Names {
+            functions: names(functions),
+            others: names(others),
+        })
+    }
+
+    /// The name for a function found at `address` that has no symbol of
+    /// its own: another symbol there, or `sub_` and its address.
+    fn for_unnamed(&self, address: u64) -> String {
+        match self.others.get(&address) {
+            Some(name) => name.clone(),
+            None => format!("sub_{address:x}"),
+        }
+    }
+}
+
+/// Following code from where it starts, decoding each instruction once.
+struct Walk<'a> {
+    code: &'a [Code],
+    disassembler: Disassembler,
+    instructions: BTreeMap<u64, Instruction>,
+    /// Where blocks must start: where control comes from somewhere other
+    /// than the instruction before.
+    leaders: BTreeSet<u64>,
+    calls: BTreeSet<u64>,
+}
+
+impl<'a> Walk<'a> {
+    fn new(code: &'a [Code], disassembler: Disassembler) -> Walk<'a> {
+        Walk {
+            code,
+            disassembler,
+            instructions: BTreeMap::new(),
+            leaders: BTreeSet::new(),
+            calls: BTreeSet::new(),
+        }
+    }
+
+    /// Decodes all that control can reach from `start`, stopping at the
+    /// starts of other functions in `entries` (which are where their calls
+    /// and tail calls go) unless `into_calls`, when calls are followed too.
+    fn follow(&mut self, start: u64, entries: &BTreeSet<u64>, into_calls: bool) {
+        let mut pending = vec![start];
+        self.leaders.insert(start);
+
+        while let Some(mut address) = pending.pop() {
+            loop {
+                if self.instructions.contains_key(&address)
+                    || (address != start && entries.contains(&address))
+                {
+                    break;
+                }
+                let Some(bytes) = bytes_at(self.code, address) else {
+                    break;
+                };
+                let instruction = self.disassembler.decode(bytes, address);
+                let flow = instruction.flow();
+                let next = address + instruction.size() as u64;
+                let bad = instruction.is_bad();
+                self.instructions.insert(address, instruction);
+                if bad {
+                    break;
+                }
+
+                match flow {
+                    Flow::Next | Flow::IndirectCall => {}
+                    Flow::Call(target) => {
+                        self.calls.insert(target);
+                        if into_calls {
+                            pending.push(target);
+                        }
+                    }
+                    Flow::Jump(target) | Flow::Branch(target) => {
+                        if target != start && entries.contains(&target) {
+                            self.calls.insert(target);
+                        } else if bytes_at(self.code, target).is_some() {
+                            self.leaders.insert(target);
+                            pending.push(target);
+                        }
+                        if let Flow::Jump(_) = flow {
+                            break;
+                        }
+                        self.leaders.insert(next);
+                    }
+                    Flow::IndirectJump | Flow::Return | Flow::Stop => break,
+                }
+                address = next;
+            }
+        }
+    }
+
+    /// Splits what was decoded into basic blocks: one starts at each
+    /// leader, after each instruction that doesn't simply go on to the
+    /// next, and wherever there's a gap between instructions.
+    fn blocks(&self) -> Vec<BasicBlock> {
+        let mut blocks: Vec<BasicBlock> = vec![];
+        for (&address, instruction) in &self.instructions {
+            let continues = blocks.last().is_some_and(|block| {
+                let last = block.instructions.last().unwrap();
+                block.end() == address
+                    && !self.leaders.contains(&address)
+                    && !last.is_bad()
+                    && matches!(last.flow(), Flow::Next | Flow::Call(_) | Flow::IndirectCall)
+            });
+            match blocks.last_mut() {
+                Some(block) if continues => block.instructions.push(instruction.clone()),
+                _ => blocks.push(BasicBlock {
+                    start: address,
+                    instructions: vec![instruction.clone()],
+                    successors: vec![],
+                }),
+            }
+        }
+
+        let starts: BTreeSet<u64> = blocks.iter().map(|block| block.start).collect();
+        for block in &mut blocks {
+            let last = block.instructions.last().unwrap();
+            let next = block.end();
+            let mut successors = vec![];
+            if !last.is_bad() {
+                match last.flow() {
+                    Flow::Next | Flow::Call(_) | Flow::IndirectCall => {
+                        successors.push((next, EdgeKind::Fallthrough));
+                    }
+                    Flow::Jump(target) => successors.push((target, EdgeKind::Jump)),
+                    Flow::Branch(target) => {
+                        successors.push((target, EdgeKind::Taken));
+                        successors.push((next, EdgeKind::NotTaken));
+                    }
+                    Flow::IndirectJump | Flow::Return | Flow::Stop => {}
+                }
+            }
+            block.successors = successors
+                .into_iter()
+                .filter(|(target, _)| starts.contains(target))
+                .map(|(target, kind)| Edge { target, kind })
+                .collect();
+        }
+
+        blocks
+    }
+}
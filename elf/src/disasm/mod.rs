@@ -0,0 +1,130 @@
+/// Instruction decoders, for disassembling the code in executable sections.
+/// Each architecture has its own module, with its own instruction type,
+/// and they share how instructions pass control on, which is what following
+/// the code (rather than just sweeping through it) needs.
+///
+pub mod aarch64;
+pub mod cfg;
+pub mod riscv;
+pub mod x86;
+
+use crate::parse::header::{EM_AARCH64, EM_RISCV, EM_X86_64};
+use crate::{ElfError, ElfFile};
+
+/// Which assembler syntax to write x86 instructions in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Syntax {
+    /// AT&T syntax, as GNU as and objdump use by default: sources first,
+    /// `%` before registers and `$` before immediates.
+    Att,
+    Intel,
+}
+
+/// Where control goes after an instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Flow {
+    /// On to the next instruction.
+    Next,
+    /// To a known address, always.
+    Jump(u64),
+    /// To a known address, or on to the next instruction.
+    Branch(u64),
+    /// To a function at a known address, which returns to the next instruction.
+    Call(u64),
+    /// Somewhere only known when the code runs (through a register, a
+    /// table, or a pointer in memory).
+    IndirectJump,
+    IndirectCall,
+    Return,
+    /// Nowhere: the instruction traps or halts.
+    Stop,
+}
+
+/// The decoder for a file's machine, with how to write what it decodes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Disassembler {
+    X86_64(Syntax),
+    Aarch64,
+    Riscv(riscv::Isa),
+}
+
+/// An instruction of any of the machines, for code (like the `disasm`
+/// command) that doesn't need to know which.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Instruction {
+    X86_64(x86::Instruction, Syntax),
+    Aarch64(aarch64::Instruction),
+    Riscv(riscv::Instruction),
+}
+
+impl Disassembler {
+    /// The decoder for the file's machine (`e_machine`). `syntax` is for
+    /// x86-64, the only one with a choice. For RISC-V, which extensions
+    /// the code uses comes from the file too.
+    pub fn for_file(elf_file: &mut ElfFile, syntax: Syntax) -> Result<Disassembler, ElfError> {
+        match elf_file.header.machine_id() {
+            EM_X86_64 => Ok(Disassembler::X86_64(syntax)),
+            EM_AARCH64 => Ok(Disassembler::Aarch64),
+            EM_RISCV => Ok(Disassembler::Riscv(riscv::Isa::for_file(elf_file)?)),
+            machine => Err(ElfError::UnsupportedMachine { machine }),
+        }
+    }
+
+    /// Decodes the instruction at the start of `bytes`, which is at `address`.
+    pub fn decode(&self, bytes: &[u8], address: u64) -> Instruction {
+        match *self {
+            Disassembler::X86_64(syntax) => {
+                Instruction::X86_64(x86::decode(bytes, address), syntax)
+            }
+            Disassembler::Aarch64 => Instruction::Aarch64(aarch64::decode(bytes, address)),
+            Disassembler::Riscv(isa) => Instruction::Riscv(riscv::decode(bytes, address, &isa)),
+        }
+    }
+}
+
+impl Instruction {
+    pub fn address(&self) -> u64 {
+        match self {
+            Instruction::X86_64(instruction, _) => instruction.address,
+            Instruction::Aarch64(instruction) => instruction.address,
+            Instruction::Riscv(instruction) => instruction.address,
+        }
+    }
+
+    /// How many bytes it takes. An instruction that couldn't be decoded
+    /// takes at least one, so that decoding can go on after it.
+    pub fn size(&self) -> usize {
+        match self {
+            Instruction::X86_64(instruction, _) => instruction.len,
+            Instruction::Aarch64(instruction) => instruction.len,
+            Instruction::Riscv(instruction) => instruction.len,
+        }
+    }
+
+    pub fn flow(&self) -> Flow {
+        match self {
+            Instruction::X86_64(instruction, _) => instruction.flow,
+            Instruction::Aarch64(instruction) => instruction.flow,
+            Instruction::Riscv(instruction) => instruction.flow,
+        }
+    }
+
+    pub fn is_bad(&self) -> bool {
+        match self {
+            Instruction::X86_64(instruction, _) => instruction.is_bad(),
+            Instruction::Aarch64(instruction) => instruction.is_bad(),
+            Instruction::Riscv(instruction) => instruction.is_bad(),
+        }
+    }
+
+    /// Writes the instruction as the disassemblers we follow do: objdump
+    /// for x86-64, and llvm-objdump for AArch64 and RISC-V. `symbolize` names an
+    /// address, as in `main+0x1c`.
+    pub fn format(&self, symbolize: &dyn Fn(u64) -> Option<String>) -> String {
+        match self {
+            Instruction::X86_64(instruction, syntax) => instruction.format(*syntax, symbolize),
+            Instruction::Aarch64(instruction) => instruction.format(symbolize),
+            Instruction::Riscv(instruction) => instruction.format(symbolize),
+        }
+    }
+}
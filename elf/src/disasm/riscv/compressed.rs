@@ -0,0 +1,225 @@
+/// Decodes compressed, 16-bit instructions (the C extension) into the
+/// 32-bit instructions they stand for, which are written as those are.
+/// Hints, the encodings that would do nothing (as in `c.li zero, 1`),
+/// aren't expanded, and keep their `c.` names.
+///
+use super::decode::*;
+use super::*;
+
+/// One of the eight registers, x8 to x15, that three-bit fields name.
+fn prime(field: u32) -> u32 {
+    field + 8
+}
+
+/// A hint, which llvm-objdump writes in its compressed form.
+fn hint(mnemonic: &str, operands: Vec<Operand>) -> Option<Decoded> {
+    op(&format!("c.{mnemonic}"), operands)
+}
+
+pub(super) fn instruction(half: u16, address: u64, isa: &Isa) -> Option<Decoded> {
+    let half = half as u32;
+    match bits(half, 0, 2) {
+        0b00 => quadrant_0(half, isa),
+        0b01 => quadrant_1(half, address, isa),
+        _ => quadrant_2(half, isa),
+    }
+}
+
+/// The sizes of loads and stores: (offset scaled by) word, double word.
+fn word_offset(half: u32) -> i64 {
+    ((bits(half, 10, 3) << 3) | (bits(half, 6, 1) << 2) | (bits(half, 5, 1) << 6)) as i64
+}
+
+fn double_offset(half: u32) -> i64 {
+    ((bits(half, 10, 3) << 3) | (bits(half, 5, 2) << 6)) as i64
+}
+
+/// Stack pointer adjustments, and loads and stores relative to x8-x15.
+fn quadrant_0(half: u32, isa: &Isa) -> Option<Decoded> {
+    let rv64 = isa.xlen == 64;
+    let (rd, rs1) = (prime(bits(half, 2, 3)), prime(bits(half, 7, 3)));
+    match bits(half, 13, 3) {
+        0b000 if half == 0 => Some(Decoded {
+            flow: Flow::Stop,
+            ..op("unimp", vec![])?
+        }),
+        0b000 => {
+            let offset = (bits(half, 11, 2) << 4)
+                | (bits(half, 7, 4) << 6)
+                | (bits(half, 6, 1) << 2)
+                | (bits(half, 5, 1) << 3);
+            match offset {
+                0 => None,
+                _ => Some(addi(rd, 2, offset as i64)),
+            }
+        }
+        0b001 if isa.d => op("fld", vec![f(rd), memory(rs1, double_offset(half))]),
+        0b010 => op("lw", vec![x(rd), memory(rs1, word_offset(half))]),
+        0b011 if rv64 => op("ld", vec![x(rd), memory(rs1, double_offset(half))]),
+        0b011 if isa.f => op("flw", vec![f(rd), memory(rs1, word_offset(half))]),
+        0b101 if isa.d => op("fsd", vec![f(rd), memory(rs1, double_offset(half))]),
+        0b110 => op("sw", vec![x(rd), memory(rs1, word_offset(half))]),
+        0b111 if rv64 => op("sd", vec![x(rd), memory(rs1, double_offset(half))]),
+        0b111 if isa.f => op("fsw", vec![f(rd), memory(rs1, word_offset(half))]),
+        _ => None,
+    }
+}
+
+/// The six-bit signed immediate of the CI format.
+fn ci_immediate(half: u32) -> i64 {
+    signed((bits(half, 12, 1) << 5) | bits(half, 2, 5), 6)
+}
+
+/// The six-bit shift amount of c.slli, c.srli and c.srai.
+fn shift_amount(half: u32) -> i64 {
+    ((bits(half, 12, 1) << 5) | bits(half, 2, 5)) as i64
+}
+
+/// A shift by `shamt`, which on RV32 must be less than 32. llvm-objdump
+/// still decodes it if it's not, but with no expansion to write it as.
+fn shift(name: &str, rd: u32, shamt: i64, rv64: bool) -> Option<Decoded> {
+    match rv64 || shamt < 32 {
+        true => op(name, vec![x(rd), x(rd), imm(shamt)]),
+        false => hint(name, vec![x(rd), imm(shamt)]),
+    }
+}
+
+/// Integer operations on immediates and registers, jumps and branches.
+fn quadrant_1(half: u32, address: u64, isa: &Isa) -> Option<Decoded> {
+    let rv64 = isa.xlen == 64;
+    let rd = bits(half, 7, 5);
+    let immediate = ci_immediate(half);
+    match bits(half, 13, 3) {
+        0b000 => match (rd, immediate) {
+            (0, 0) => op("nop", vec![]),
+            (0, _) => hint("nop", vec![imm(immediate)]),
+            _ => Some(addi(rd, rd, immediate)),
+        },
+        0b001 if rv64 => match (rd, immediate) {
+            (0, _) => None,
+            (_, 0) => op("sext.w", vec![x(rd), x(rd)]),
+            _ => op("addiw", vec![x(rd), x(rd), imm(immediate)]),
+        },
+        0b001 => Some(jal(1, target(address, jump_offset(half), isa))),
+        0b010 if rd == 0 => hint("li", vec![x(0), imm(immediate)]),
+        0b010 => Some(addi(rd, 0, immediate)),
+        0b011 if rd == 2 => {
+            let offset = (bits(half, 12, 1) << 9)
+                | (bits(half, 6, 1) << 4)
+                | (bits(half, 5, 1) << 6)
+                | (bits(half, 3, 2) << 7)
+                | (bits(half, 2, 1) << 5);
+            match offset {
+                0 => None,
+                _ => Some(addi(2, 2, signed(offset, 10))),
+            }
+        }
+        // The immediate is the upper one's low six bits, sign extended to
+        // all twenty.
+        0b011 if rd == 0 => hint("lui", vec![x(0), imm(immediate)]),
+        0b011 => op("lui", vec![x(rd), imm(immediate & 0xfffff)]),
+        0b100 => arithmetic(half, rv64),
+        0b101 => Some(jal(0, target(address, jump_offset(half), isa))),
+        funct3 => {
+            let offset = (bits(half, 12, 1) << 8)
+                | (bits(half, 10, 2) << 3)
+                | (bits(half, 5, 2) << 6)
+                | (bits(half, 3, 2) << 1)
+                | (bits(half, 2, 1) << 5);
+            let target = target(address, signed(offset, 9), isa);
+            let mnemonic = if funct3 == 0b110 { "beq" } else { "bne" };
+            Some(branch_to(mnemonic, prime(bits(half, 7, 3)), 0, target))
+        }
+    }
+}
+
+/// The offset of c.j and c.jal.
+fn jump_offset(half: u32) -> i64 {
+    let offset = (bits(half, 12, 1) << 11)
+        | (bits(half, 11, 1) << 4)
+        | (bits(half, 9, 2) << 8)
+        | (bits(half, 8, 1) << 10)
+        | (bits(half, 7, 1) << 6)
+        | (bits(half, 6, 1) << 7)
+        | (bits(half, 3, 3) << 1)
+        | (bits(half, 2, 1) << 5);
+    signed(offset, 12)
+}
+
+/// The operations on x8-x15: shifts, and with an immediate, and those
+/// between two registers.
+fn arithmetic(half: u32, rv64: bool) -> Option<Decoded> {
+    let rd = prime(bits(half, 7, 3));
+    let rs2 = prime(bits(half, 2, 3));
+    match bits(half, 10, 2) {
+        0b00 | 0b01 => {
+            let name = match bits(half, 10, 2) {
+                0b00 => "srli",
+                _ => "srai",
+            };
+            match shift_amount(half) {
+                0 => hint(&format!("{name}64"), vec![x(rd)]),
+                shamt => shift(name, rd, shamt, rv64),
+            }
+        }
+        0b10 => op("andi", vec![x(rd), x(rd), imm(ci_immediate(half))]),
+        _ => {
+            let mnemonic = match (bits(half, 12, 1), bits(half, 5, 2)) {
+                (0, 0b00) => "sub",
+                (0, 0b01) => "xor",
+                (0, 0b10) => "or",
+                (0, _) => "and",
+                (_, 0b00) if rv64 => "subw",
+                (_, 0b01) if rv64 => "addw",
+                _ => return None,
+            };
+            op(mnemonic, vec![x(rd), x(rd), x(rs2)])
+        }
+    }
+}
+
+/// Shifts, loads and stores relative to the stack pointer, moves, and
+/// jumps through registers.
+fn quadrant_2(half: u32, isa: &Isa) -> Option<Decoded> {
+    let rv64 = isa.xlen == 64;
+    let (rd, rs2) = (bits(half, 7, 5), bits(half, 2, 5));
+    let word_offset = (bits(half, 12, 1) << 5) | (bits(half, 4, 3) << 2) | (bits(half, 2, 2) << 6);
+    let double_offset =
+        (bits(half, 12, 1) << 5) | (bits(half, 5, 2) << 3) | (bits(half, 2, 3) << 6);
+    let word_store = (bits(half, 9, 4) << 2) | (bits(half, 7, 2) << 6);
+    let double_store = (bits(half, 10, 3) << 3) | (bits(half, 7, 3) << 6);
+    let sp = |offset: u32| memory(2, offset as i64);
+
+    match bits(half, 13, 3) {
+        0b000 => {
+            let shamt = shift_amount(half);
+            match (rd, shamt) {
+                (_, 0) => hint("slli64", vec![x(rd)]),
+                (0, _) => hint("slli", vec![x(0), imm(shamt)]),
+                _ => shift("slli", rd, shamt, rv64),
+            }
+        }
+        0b001 if isa.d => op("fld", vec![f(rd), sp(double_offset)]),
+        0b010 if rd != 0 => op("lw", vec![x(rd), sp(word_offset)]),
+        0b011 if rv64 && rd != 0 => op("ld", vec![x(rd), sp(double_offset)]),
+        0b011 if !rv64 && isa.f => op("flw", vec![f(rd), sp(word_offset)]),
+        0b100 => match (bits(half, 12, 1), rd, rs2) {
+            (0, 0, 0) => None,
+            (0, _, 0) => Some(jalr(0, rd, 0)),
+            (0, 0, _) => hint("mv", vec![x(0), x(rs2)]),
+            (0, _, _) => Some(addi(rd, rs2, 0)),
+            (_, 0, 0) => Some(Decoded {
+                flow: Flow::Stop,
+                ..op("ebreak", vec![])?
+            }),
+            (_, _, 0) => Some(jalr(1, rd, 0)),
+            (_, 0, _) => hint("add", vec![x(0), x(rs2)]),
+            _ => op("add", vec![x(rd), x(rd), x(rs2)]),
+        },
+        0b101 if isa.d => op("fsd", vec![f(rs2), sp(double_store)]),
+        0b110 => op("sw", vec![x(rs2), sp(word_store)]),
+        0b111 if rv64 => op("sd", vec![x(rs2), sp(double_store)]),
+        0b111 if isa.f => op("fsw", vec![f(rs2), sp(word_store)]),
+        _ => None,
+    }
+}
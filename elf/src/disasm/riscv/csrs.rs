@@ -0,0 +1,388 @@
+/// The names of the control and status registers, by number, as
+/// llvm-objdump names them. The upper halves of 64-bit registers (`cycleh`,
+/// `mstatush` and so on) are only there on RV32.
+///
+pub(super) const CSRS: &[(u16, &str, bool)] = &[
+    (0x000, "ustatus", false),
+    (0x001, "fflags", false),
+    (0x002, "frm", false),
+    (0x003, "fcsr", false),
+    (0x004, "uie", false),
+    (0x005, "utvec", false),
+    (0x008, "vstart", false),
+    (0x009, "vxsat", false),
+    (0x00a, "vxrm", false),
+    (0x00f, "vcsr", false),
+    (0x015, "seed", false),
+    (0x040, "uscratch", false),
+    (0x041, "uepc", false),
+    (0x042, "ucause", false),
+    (0x043, "utval", false),
+    (0x044, "uip", false),
+    (0x100, "sstatus", false),
+    (0x102, "sedeleg", false),
+    (0x103, "sideleg", false),
+    (0x104, "sie", false),
+    (0x105, "stvec", false),
+    (0x106, "scounteren", false),
+    (0x10a, "senvcfg", false),
+    (0x10c, "sstateen0", false),
+    (0x10d, "sstateen1", false),
+    (0x10e, "sstateen2", false),
+    (0x10f, "sstateen3", false),
+    (0x140, "sscratch", false),
+    (0x141, "sepc", false),
+    (0x142, "scause", false),
+    (0x143, "stval", false),
+    (0x144, "sip", false),
+    (0x14d, "stimecmp", false),
+    (0x15d, "stimecmph", true),
+    (0x180, "satp", false),
+    (0x200, "vsstatus", false),
+    (0x204, "vsie", false),
+    (0x205, "vstvec", false),
+    (0x240, "vsscratch", false),
+    (0x241, "vsepc", false),
+    (0x242, "vscause", false),
+    (0x243, "vstval", false),
+    (0x244, "vsip", false),
+    (0x24d, "vstimecmp", false),
+    (0x25d, "vstimecmph", true),
+    (0x280, "vsatp", false),
+    (0x300, "mstatus", false),
+    (0x301, "misa", false),
+    (0x302, "medeleg", false),
+    (0x303, "mideleg", false),
+    (0x304, "mie", false),
+    (0x305, "mtvec", false),
+    (0x306, "mcounteren", false),
+    (0x30a, "menvcfg", false),
+    (0x30c, "mstateen0", false),
+    (0x30d, "mstateen1", false),
+    (0x30e, "mstateen2", false),
+    (0x30f, "mstateen3", false),
+    (0x310, "mstatush", true),
+    (0x31a, "menvcfgh", true),
+    (0x31c, "mstateen0h", true),
+    (0x31d, "mstateen1h", true),
+    (0x31e, "mstateen2h", true),
+    (0x31f, "mstateen3h", true),
+    (0x320, "mcountinhibit", false),
+    (0x323, "mhpmevent3", false),
+    (0x324, "mhpmevent4", false),
+    (0x325, "mhpmevent5", false),
+    (0x326, "mhpmevent6", false),
+    (0x327, "mhpmevent7", false),
+    (0x328, "mhpmevent8", false),
+    (0x329, "mhpmevent9", false),
+    (0x32a, "mhpmevent10", false),
+    (0x32b, "mhpmevent11", false),
+    (0x32c, "mhpmevent12", false),
+    (0x32d, "mhpmevent13", false),
+    (0x32e, "mhpmevent14", false),
+    (0x32f, "mhpmevent15", false),
+    (0x330, "mhpmevent16", false),
+    (0x331, "mhpmevent17", false),
+    (0x332, "mhpmevent18", false),
+    (0x333, "mhpmevent19", false),
+    (0x334, "mhpmevent20", false),
+    (0x335, "mhpmevent21", false),
+    (0x336, "mhpmevent22", false),
+    (0x337, "mhpmevent23", false),
+    (0x338, "mhpmevent24", false),
+    (0x339, "mhpmevent25", false),
+    (0x33a, "mhpmevent26", false),
+    (0x33b, "mhpmevent27", false),
+    (0x33c, "mhpmevent28", false),
+    (0x33d, "mhpmevent29", false),
+    (0x33e, "mhpmevent30", false),
+    (0x33f, "mhpmevent31", false),
+    (0x340, "mscratch", false),
+    (0x341, "mepc", false),
+    (0x342, "mcause", false),
+    (0x343, "mtval", false),
+    (0x344, "mip", false),
+    (0x34a, "mtinst", false),
+    (0x34b, "mtval2", false),
+    (0x3a0, "pmpcfg0", false),
+    (0x3a1, "pmpcfg1", true),
+    (0x3a2, "pmpcfg2", false),
+    (0x3a3, "pmpcfg3", true),
+    (0x3a4, "pmpcfg4", false),
+    (0x3a5, "pmpcfg5", true),
+    (0x3a6, "pmpcfg6", false),
+    (0x3a7, "pmpcfg7", true),
+    (0x3a8, "pmpcfg8", false),
+    (0x3a9, "pmpcfg9", true),
+    (0x3aa, "pmpcfg10", false),
+    (0x3ab, "pmpcfg11", true),
+    (0x3ac, "pmpcfg12", false),
+    (0x3ad, "pmpcfg13", true),
+    (0x3ae, "pmpcfg14", false),
+    (0x3af, "pmpcfg15", true),
+    (0x3b0, "pmpaddr0", false),
+    (0x3b1, "pmpaddr1", false),
+    (0x3b2, "pmpaddr2", false),
+    (0x3b3, "pmpaddr3", false),
+    (0x3b4, "pmpaddr4", false),
+    (0x3b5, "pmpaddr5", false),
+    (0x3b6, "pmpaddr6", false),
+    (0x3b7, "pmpaddr7", false),
+    (0x3b8, "pmpaddr8", false),
+    (0x3b9, "pmpaddr9", false),
+    (0x3ba, "pmpaddr10", false),
+    (0x3bb, "pmpaddr11", false),
+    (0x3bc, "pmpaddr12", false),
+    (0x3bd, "pmpaddr13", false),
+    (0x3be, "pmpaddr14", false),
+    (0x3bf, "pmpaddr15", false),
+    (0x3c0, "pmpaddr16", false),
+    (0x3c1, "pmpaddr17", false),
+    (0x3c2, "pmpaddr18", false),
+    (0x3c3, "pmpaddr19", false),
+    (0x3c4, "pmpaddr20", false),
+    (0x3c5, "pmpaddr21", false),
+    (0x3c6, "pmpaddr22", false),
+    (0x3c7, "pmpaddr23", false),
+    (0x3c8, "pmpaddr24", false),
+    (0x3c9, "pmpaddr25", false),
+    (0x3ca, "pmpaddr26", false),
+    (0x3cb, "pmpaddr27", false),
+    (0x3cc, "pmpaddr28", false),
+    (0x3cd, "pmpaddr29", false),
+    (0x3ce, "pmpaddr30", false),
+    (0x3cf, "pmpaddr31", false),
+    (0x3d0, "pmpaddr32", false),
+    (0x3d1, "pmpaddr33", false),
+    (0x3d2, "pmpaddr34", false),
+    (0x3d3, "pmpaddr35", false),
+    (0x3d4, "pmpaddr36", false),
+    (0x3d5, "pmpaddr37", false),
+    (0x3d6, "pmpaddr38", false),
+    (0x3d7, "pmpaddr39", false),
+    (0x3d8, "pmpaddr40", false),
+    (0x3d9, "pmpaddr41", false),
+    (0x3da, "pmpaddr42", false),
+    (0x3db, "pmpaddr43", false),
+    (0x3dc, "pmpaddr44", false),
+    (0x3dd, "pmpaddr45", false),
+    (0x3de, "pmpaddr46", false),
+    (0x3df, "pmpaddr47", false),
+    (0x3e0, "pmpaddr48", false),
+    (0x3e1, "pmpaddr49", false),
+    (0x3e2, "pmpaddr50", false),
+    (0x3e3, "pmpaddr51", false),
+    (0x3e4, "pmpaddr52", false),
+    (0x3e5, "pmpaddr53", false),
+    (0x3e6, "pmpaddr54", false),
+    (0x3e7, "pmpaddr55", false),
+    (0x3e8, "pmpaddr56", false),
+    (0x3e9, "pmpaddr57", false),
+    (0x3ea, "pmpaddr58", false),
+    (0x3eb, "pmpaddr59", false),
+    (0x3ec, "pmpaddr60", false),
+    (0x3ed, "pmpaddr61", false),
+    (0x3ee, "pmpaddr62", false),
+    (0x3ef, "pmpaddr63", false),
+    (0x5a8, "scontext", false),
+    (0x600, "hstatus", false),
+    (0x602, "hedeleg", false),
+    (0x603, "hideleg", false),
+    (0x604, "hie", false),
+    (0x605, "htimedelta", false),
+    (0x606, "hcounteren", false),
+    (0x607, "hgeie", false),
+    (0x60a, "henvcfg", false),
+    (0x60c, "hstateen0", false),
+    (0x60d, "hstateen1", false),
+    (0x60e, "hstateen2", false),
+    (0x60f, "hstateen3", false),
+    (0x615, "htimedeltah", true),
+    (0x61a, "henvcfgh", true),
+    (0x61c, "hstateen0h", true),
+    (0x61d, "hstateen1h", true),
+    (0x61e, "hstateen2h", true),
+    (0x61f, "hstateen3h", true),
+    (0x643, "htval", false),
+    (0x644, "hip", false),
+    (0x645, "hvip", false),
+    (0x64a, "htinst", false),
+    (0x680, "hgatp", false),
+    (0x6a8, "hcontext", false),
+    (0x723, "mhpmevent3h", true),
+    (0x724, "mhpmevent4h", true),
+    (0x725, "mhpmevent5h", true),
+    (0x726, "mhpmevent6h", true),
+    (0x727, "mhpmevent7h", true),
+    (0x728, "mhpmevent8h", true),
+    (0x729, "mhpmevent9h", true),
+    (0x72a, "mhpmevent10h", true),
+    (0x72b, "mhpmevent11h", true),
+    (0x72c, "mhpmevent12h", true),
+    (0x72d, "mhpmevent13h", true),
+    (0x72e, "mhpmevent14h", true),
+    (0x72f, "mhpmevent15h", true),
+    (0x730, "mhpmevent16h", true),
+    (0x731, "mhpmevent17h", true),
+    (0x732, "mhpmevent18h", true),
+    (0x733, "mhpmevent19h", true),
+    (0x734, "mhpmevent20h", true),
+    (0x735, "mhpmevent21h", true),
+    (0x736, "mhpmevent22h", true),
+    (0x737, "mhpmevent23h", true),
+    (0x738, "mhpmevent24h", true),
+    (0x739, "mhpmevent25h", true),
+    (0x73a, "mhpmevent26h", true),
+    (0x73b, "mhpmevent27h", true),
+    (0x73c, "mhpmevent28h", true),
+    (0x73d, "mhpmevent29h", true),
+    (0x73e, "mhpmevent30h", true),
+    (0x73f, "mhpmevent31h", true),
+    (0x747, "mseccfg", false),
+    (0x757, "mseccfgh", true),
+    (0x7a0, "tselect", false),
+    (0x7a1, "tdata1", false),
+    (0x7a2, "tdata2", false),
+    (0x7a3, "tdata3", false),
+    (0x7a8, "mcontext", false),
+    (0x7b0, "dcsr", false),
+    (0x7b1, "dpc", false),
+    (0x7b2, "dscratch0", false),
+    (0x7b3, "dscratch1", false),
+    (0xb00, "mcycle", false),
+    (0xb02, "minstret", false),
+    (0xb03, "mhpmcounter3", false),
+    (0xb04, "mhpmcounter4", false),
+    (0xb05, "mhpmcounter5", false),
+    (0xb06, "mhpmcounter6", false),
+    (0xb07, "mhpmcounter7", false),
+    (0xb08, "mhpmcounter8", false),
+    (0xb09, "mhpmcounter9", false),
+    (0xb0a, "mhpmcounter10", false),
+    (0xb0b, "mhpmcounter11", false),
+    (0xb0c, "mhpmcounter12", false),
+    (0xb0d, "mhpmcounter13", false),
+    (0xb0e, "mhpmcounter14", false),
+    (0xb0f, "mhpmcounter15", false),
+    (0xb10, "mhpmcounter16", false),
+    (0xb11, "mhpmcounter17", false),
+    (0xb12, "mhpmcounter18", false),
+    (0xb13, "mhpmcounter19", false),
+    (0xb14, "mhpmcounter20", false),
+    (0xb15, "mhpmcounter21", false),
+    (0xb16, "mhpmcounter22", false),
+    (0xb17, "mhpmcounter23", false),
+    (0xb18, "mhpmcounter24", false),
+    (0xb19, "mhpmcounter25", false),
+    (0xb1a, "mhpmcounter26", false),
+    (0xb1b, "mhpmcounter27", false),
+    (0xb1c, "mhpmcounter28", false),
+    (0xb1d, "mhpmcounter29", false),
+    (0xb1e, "mhpmcounter30", false),
+    (0xb1f, "mhpmcounter31", false),
+    (0xb80, "mcycleh", true),
+    (0xb82, "minstreth", true),
+    (0xb83, "mhpmcounter3h", true),
+    (0xb84, "mhpmcounter4h", true),
+    (0xb85, "mhpmcounter5h", true),
+    (0xb86, "mhpmcounter6h", true),
+    (0xb87, "mhpmcounter7h", true),
+    (0xb88, "mhpmcounter8h", true),
+    (0xb89, "mhpmcounter9h", true),
+    (0xb8a, "mhpmcounter10h", true),
+    (0xb8b, "mhpmcounter11h", true),
+    (0xb8c, "mhpmcounter12h", true),
+    (0xb8d, "mhpmcounter13h", true),
+    (0xb8e, "mhpmcounter14h", true),
+    (0xb8f, "mhpmcounter15h", true),
+    (0xb90, "mhpmcounter16h", true),
+    (0xb91, "mhpmcounter17h", true),
+    (0xb92, "mhpmcounter18h", true),
+    (0xb93, "mhpmcounter19h", true),
+    (0xb94, "mhpmcounter20h", true),
+    (0xb95, "mhpmcounter21h", true),
+    (0xb96, "mhpmcounter22h", true),
+    (0xb97, "mhpmcounter23h", true),
+    (0xb98, "mhpmcounter24h", true),
+    (0xb99, "mhpmcounter25h", true),
+    (0xb9a, "mhpmcounter26h", true),
+    (0xb9b, "mhpmcounter27h", true),
+    (0xb9c, "mhpmcounter28h", true),
+    (0xb9d, "mhpmcounter29h", true),
+    (0xb9e, "mhpmcounter30h", true),
+    (0xb9f, "mhpmcounter31h", true),
+    (0xc00, "cycle", false),
+    (0xc01, "time", false),
+    (0xc02, "instret", false),
+    (0xc03, "hpmcounter3", false),
+    (0xc04, "hpmcounter4", false),
+    (0xc05, "hpmcounter5", false),
+    (0xc06, "hpmcounter6", false),
+    (0xc07, "hpmcounter7", false),
+    (0xc08, "hpmcounter8", false),
+    (0xc09, "hpmcounter9", false),
+    (0xc0a, "hpmcounter10", false),
+    (0xc0b, "hpmcounter11", false),
+    (0xc0c, "hpmcounter12", false),
+    (0xc0d, "hpmcounter13", false),
+    (0xc0e, "hpmcounter14", false),
+    (0xc0f, "hpmcounter15", false),
+    (0xc10, "hpmcounter16", false),
+    (0xc11, "hpmcounter17", false),
+    (0xc12, "hpmcounter18", false),
+    (0xc13, "hpmcounter19", false),
+    (0xc14, "hpmcounter20", false),
+    (0xc15, "hpmcounter21", false),
+    (0xc16, "hpmcounter22", false),
+    (0xc17, "hpmcounter23", false),
+    (0xc18, "hpmcounter24", false),
+    (0xc19, "hpmcounter25", false),
+    (0xc1a, "hpmcounter26", false),
+    (0xc1b, "hpmcounter27", false),
+    (0xc1c, "hpmcounter28", false),
+    (0xc1d, "hpmcounter29", false),
+    (0xc1e, "hpmcounter30", false),
+    (0xc1f, "hpmcounter31", false),
+    (0xc20, "vl", false),
+    (0xc21, "vtype", false),
+    (0xc22, "vlenb", false),
+    (0xc80, "cycleh", true),
+    (0xc81, "timeh", true),
+    (0xc82, "instreth", true),
+    (0xc83, "hpmcounter3h", true),
+    (0xc84, "hpmcounter4h", true),
+    (0xc85, "hpmcounter5h", true),
+    (0xc86, "hpmcounter6h", true),
+    (0xc87, "hpmcounter7h", true),
+    (0xc88, "hpmcounter8h", true),
+    (0xc89, "hpmcounter9h", true),
+    (0xc8a, "hpmcounter10h", true),
+    (0xc8b, "hpmcounter11h", true),
+    (0xc8c, "hpmcounter12h", true),
+    (0xc8d, "hpmcounter13h", true),
+    (0xc8e, "hpmcounter14h", true),
+    (0xc8f, "hpmcounter15h", true),
+    (0xc90, "hpmcounter16h", true),
+    (0xc91, "hpmcounter17h", true),
+    (0xc92, "hpmcounter18h", true),
+    (0xc93, "hpmcounter19h", true),
+    (0xc94, "hpmcounter20h", true),
+    (0xc95, "hpmcounter21h", true),
+    (0xc96, "hpmcounter22h", true),
+    (0xc97, "hpmcounter23h", true),
+    (0xc98, "hpmcounter24h", true),
+    (0xc99, "hpmcounter25h", true),
+    (0xc9a, "hpmcounter26h", true),
+    (0xc9b, "hpmcounter27h", true),
+    (0xc9c, "hpmcounter28h", true),
+    (0xc9d, "hpmcounter29h", true),
+    (0xc9e, "hpmcounter30h", true),
+    (0xc9f, "hpmcounter31h", true),
+    (0xda0, "scountovf", false),
+    (0xe12, "hgeip", false),
+    (0xf11, "mvendorid", false),
+    (0xf12, "marchid", false),
+    (0xf13, "mimpid", false),
+    (0xf14, "mhartid", false),
+    (0xf15, "mconfigptr", false),
+];
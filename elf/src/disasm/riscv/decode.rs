@@ -0,0 +1,727 @@
+/// Decodes 32-bit instructions by their major opcode, and picks the alias
+/// llvm-objdump writes where there is one. Compressed instructions have
+/// their own module, and come back here to be written as the instructions
+/// they expand to.
+///
+use super::csrs::CSRS;
+use super::*;
+
+/// An instruction before it's given its address and encoding.
+pub(super) struct Decoded {
+    pub mnemonic: String,
+    pub operands: Vec<Operand>,
+    pub flow: Flow,
+}
+
+/// Decodes the instruction at the start of `bytes`, which is at `address`,
+/// for code of the instruction set `isa`. Words that aren't an instruction
+/// of it decode as "<unknown>", and so do the last bytes of a section too
+/// short to hold one.
+pub fn decode(bytes: &[u8], address: u64, isa: &Isa) -> Instruction {
+    let unknown = |len, word| Instruction {
+        address,
+        len,
+        word,
+        mnemonic: "<unknown>".to_owned(),
+        operands: vec![],
+        flow: Flow::Stop,
+    };
+
+    // The low two bits are 11 for 32-bit instructions, and anything else
+    // for 16-bit ones.
+    let Some(&half) = bytes.first_chunk::<2>() else {
+        return unknown(bytes.len(), 0);
+    };
+    let (len, word, decoded) = if half[0] & 0b11 == 0b11 {
+        let Some(&word) = bytes.first_chunk::<4>() else {
+            return unknown(bytes.len(), 0);
+        };
+        let word = u32::from_le_bytes(word);
+        (4, word, instruction(word, address, isa))
+    } else {
+        let half = u16::from_le_bytes(half);
+        let decoded = match isa.c {
+            true => compressed::instruction(half, address, isa),
+            false => None,
+        };
+        (2, half as u32, decoded)
+    };
+
+    match decoded {
+        Some(decoded) => Instruction {
+            address,
+            len,
+            word,
+            mnemonic: decoded.mnemonic,
+            operands: decoded.operands,
+            flow: decoded.flow,
+        },
+        None => unknown(len, word),
+    }
+}
+
+/// `width` bits of `word`, from bit `low` up.
+pub(super) fn bits(word: u32, low: u32, width: u32) -> u32 {
+    (word >> low) & ((1 << width) - 1)
+}
+
+/// A `width`-bit field as a signed number.
+pub(super) fn signed(value: u32, width: u32) -> i64 {
+    ((value as i64) << (64 - width)) >> (64 - width)
+}
+
+/// An instruction that carries on to the next one.
+pub(super) fn op(mnemonic: &str, operands: Vec<Operand>) -> Option<Decoded> {
+    Some(Decoded {
+        mnemonic: mnemonic.to_owned(),
+        operands,
+        flow: Flow::Next,
+    })
+}
+
+pub(super) fn x(number: u32) -> Operand {
+    Operand::Register(Register::X(number as u8))
+}
+
+pub(super) fn f(number: u32) -> Operand {
+    Operand::Register(Register::F(number as u8))
+}
+
+pub(super) fn imm(value: i64) -> Operand {
+    Operand::Immediate(value)
+}
+
+pub(super) fn memory(base: u32, offset: i64) -> Operand {
+    Operand::Memory {
+        base: Register::X(base as u8),
+        offset: Some(offset),
+    }
+}
+
+/// The fields of the R, I, S and B formats, which are where they are
+/// in all of them.
+fn rd(word: u32) -> u32 {
+    bits(word, 7, 5)
+}
+
+fn rs1(word: u32) -> u32 {
+    bits(word, 15, 5)
+}
+
+fn rs2(word: u32) -> u32 {
+    bits(word, 20, 5)
+}
+
+fn funct3(word: u32) -> u32 {
+    bits(word, 12, 3)
+}
+
+fn funct7(word: u32) -> u32 {
+    bits(word, 25, 7)
+}
+
+/// The immediate of the I format.
+fn i_immediate(word: u32) -> i64 {
+    signed(bits(word, 20, 12), 12)
+}
+
+fn instruction(word: u32, address: u64, isa: &Isa) -> Option<Decoded> {
+    let rv64 = isa.xlen == 64;
+    match bits(word, 0, 7) {
+        0b0110111 => op("lui", vec![x(rd(word)), imm((word >> 12) as i64)]),
+        0b0010111 => op("auipc", vec![x(rd(word)), imm((word >> 12) as i64)]),
+        0b1101111 => {
+            let offset = (bits(word, 31, 1) << 20)
+                | (bits(word, 12, 8) << 12)
+                | (bits(word, 20, 1) << 11)
+                | (bits(word, 21, 10) << 1);
+            Some(jal(rd(word), target(address, signed(offset, 21), isa)))
+        }
+        0b1100111 if funct3(word) == 0 => Some(jalr(rd(word), rs1(word), i_immediate(word))),
+        0b1100011 => branch(word, address, isa),
+        0b0000011 => {
+            let mnemonic = match funct3(word) {
+                0b000 => "lb",
+                0b001 => "lh",
+                0b010 => "lw",
+                0b011 if rv64 => "ld",
+                0b100 => "lbu",
+                0b101 => "lhu",
+                0b110 if rv64 => "lwu",
+                _ => return None,
+            };
+            op(
+                mnemonic,
+                vec![x(rd(word)), memory(rs1(word), i_immediate(word))],
+            )
+        }
+        0b0100011 => {
+            let mnemonic = match funct3(word) {
+                0b000 => "sb",
+                0b001 => "sh",
+                0b010 => "sw",
+                0b011 if rv64 => "sd",
+                _ => return None,
+            };
+            op(
+                mnemonic,
+                vec![x(rs2(word)), memory(rs1(word), s_immediate(word))],
+            )
+        }
+        0b0010011 => op_immediate(word),
+        0b0011011 if rv64 => op_immediate_32(word),
+        0b0110011 => op_register(word, isa),
+        0b0111011 if rv64 => op_register_32(word, isa),
+        0b0001111 => misc_mem(word, isa),
+        0b1110011 => system(word, isa),
+        0b0101111 if isa.a => atomic(word, isa),
+        0b0000111 | 0b0100111 => load_store_fp(word, isa),
+        0b1000011 | 0b1000111 | 0b1001011 | 0b1001111 => fused_multiply_add(word, isa),
+        0b1010011 => op_fp(word, isa),
+        _ => None,
+    }
+}
+
+/// The immediate of the S format, split around rd's place.
+fn s_immediate(word: u32) -> i64 {
+    signed((bits(word, 25, 7) << 5) | bits(word, 7, 5), 12)
+}
+
+/// Where a jump or branch `offset` bytes from `address` goes, which wraps
+/// around at 32 bits on RV32.
+pub(super) fn target(address: u64, offset: i64, isa: &Isa) -> u64 {
+    let target = address.wrapping_add(offset as u64);
+    match isa.xlen {
+        32 => target & 0xffff_ffff,
+        _ => target,
+    }
+}
+
+/// jal, and c.j and c.jal.
+pub(super) fn jal(rd: u32, target: u64) -> Decoded {
+    let (mnemonic, operands, flow) = match rd {
+        0 => ("j", vec![Operand::Target(target)], Flow::Jump(target)),
+        1 => ("jal", vec![Operand::Target(target)], Flow::Call(target)),
+        _ => (
+            "jal",
+            vec![x(rd), Operand::Target(target)],
+            Flow::Call(target),
+        ),
+    };
+    Decoded {
+        mnemonic: mnemonic.to_owned(),
+        operands,
+        flow,
+    }
+}
+
+/// jalr, and c.jr and c.jalr.
+pub(super) fn jalr(rd: u32, rs1: u32, offset: i64) -> Decoded {
+    let (mnemonic, operands) = match (rd, rs1, offset) {
+        (0, 1, 0) => ("ret", vec![]),
+        (0, _, 0) => ("jr", vec![x(rs1)]),
+        (0, _, _) => ("jr", vec![memory(rs1, offset)]),
+        (1, _, 0) => ("jalr", vec![x(rs1)]),
+        (1, _, _) => ("jalr", vec![memory(rs1, offset)]),
+        (_, _, 0) => ("jalr", vec![x(rd), x(rs1)]),
+        _ => ("jalr", vec![x(rd), memory(rs1, offset)]),
+    };
+    let flow = match (rd, rs1, offset) {
+        (0, 1, 0) => Flow::Return,
+        (0, _, _) => Flow::IndirectJump,
+        _ => Flow::IndirectCall,
+    };
+    Decoded {
+        mnemonic: mnemonic.to_owned(),
+        operands,
+        flow,
+    }
+}
+
+fn branch(word: u32, address: u64, isa: &Isa) -> Option<Decoded> {
+    let offset = (bits(word, 31, 1) << 12)
+        | (bits(word, 7, 1) << 11)
+        | (bits(word, 25, 6) << 5)
+        | (bits(word, 8, 4) << 1);
+    let target = target(address, signed(offset, 13), isa);
+    let (rs1, rs2) = (rs1(word), rs2(word));
+
+    let mnemonic = match funct3(word) {
+        0b000 => "beq",
+        0b001 => "bne",
+        0b100 => "blt",
+        0b101 => "bge",
+        0b110 => "bltu",
+        0b111 => "bgeu",
+        _ => return None,
+    };
+    Some(branch_to(mnemonic, rs1, rs2, target))
+}
+
+/// A conditional branch, written with the alias for comparing with zero
+/// when it has one: c.beqz and c.bnez come here too.
+pub(super) fn branch_to(mnemonic: &str, rs1: u32, rs2: u32, target: u64) -> Decoded {
+    let target_operand = Operand::Target(target);
+    let (mnemonic, operands) = match (mnemonic, rs1, rs2) {
+        ("beq", _, 0) => ("beqz", vec![x(rs1), target_operand]),
+        ("bne", _, 0) => ("bnez", vec![x(rs1), target_operand]),
+        ("blt", _, 0) => ("bltz", vec![x(rs1), target_operand]),
+        ("blt", 0, _) => ("bgtz", vec![x(rs2), target_operand]),
+        ("bge", 0, _) => ("blez", vec![x(rs2), target_operand]),
+        ("bge", _, 0) => ("bgez", vec![x(rs1), target_operand]),
+        _ => (mnemonic, vec![x(rs1), x(rs2), target_operand]),
+    };
+    Decoded {
+        mnemonic: mnemonic.to_owned(),
+        operands,
+        flow: Flow::Branch(target),
+    }
+}
+
+/// Integer operations on a register and an immediate.
+fn op_immediate(word: u32) -> Option<Decoded> {
+    let (rd, rs1, immediate) = (rd(word), rs1(word), i_immediate(word));
+    // Shifts take six bits of amount, and the bits above say which shift
+    // it is. On RV32 the top bit of the amount should be clear, but
+    // llvm-objdump doesn't check, and neither do we.
+    let shamt = bits(word, 20, 6) as i64;
+    let shift = bits(word, 26, 6);
+
+    match funct3(word) {
+        0b000 => Some(addi(rd, rs1, immediate)),
+        0b010 => op("slti", vec![x(rd), x(rs1), imm(immediate)]),
+        0b011 if immediate == 1 => op("seqz", vec![x(rd), x(rs1)]),
+        0b011 => op("sltiu", vec![x(rd), x(rs1), imm(immediate)]),
+        0b100 if immediate == -1 => op("not", vec![x(rd), x(rs1)]),
+        0b100 => op("xori", vec![x(rd), x(rs1), imm(immediate)]),
+        0b110 => op("ori", vec![x(rd), x(rs1), imm(immediate)]),
+        0b111 => op("andi", vec![x(rd), x(rs1), imm(immediate)]),
+        0b001 if shift == 0 => op("slli", vec![x(rd), x(rs1), imm(shamt)]),
+        0b101 if shift == 0 => op("srli", vec![x(rd), x(rs1), imm(shamt)]),
+        0b101 if shift == 0b010000 => op("srai", vec![x(rd), x(rs1), imm(shamt)]),
+        _ => None,
+    }
+}
+
+/// addi, with its aliases: c.addi, c.li, c.mv and the stack pointer ones
+/// come here too.
+pub(super) fn addi(rd: u32, rs1: u32, immediate: i64) -> Decoded {
+    let decoded = match (rd, rs1, immediate) {
+        (0, 0, 0) => op("nop", vec![]),
+        (_, 0, _) => op("li", vec![x(rd), imm(immediate)]),
+        (_, _, 0) => op("mv", vec![x(rd), x(rs1)]),
+        _ => op("addi", vec![x(rd), x(rs1), imm(immediate)]),
+    };
+    decoded.unwrap()
+}
+
+/// The RV64 operations on the low 32 bits of a register and an immediate.
+fn op_immediate_32(word: u32) -> Option<Decoded> {
+    let (rd, rs1, immediate) = (rd(word), rs1(word), i_immediate(word));
+    let shamt = bits(word, 20, 5) as i64;
+    match (funct3(word), funct7(word)) {
+        (0b000, _) if immediate == 0 => op("sext.w", vec![x(rd), x(rs1)]),
+        (0b000, _) => op("addiw", vec![x(rd), x(rs1), imm(immediate)]),
+        (0b001, 0) => op("slliw", vec![x(rd), x(rs1), imm(shamt)]),
+        (0b101, 0) => op("srliw", vec![x(rd), x(rs1), imm(shamt)]),
+        (0b101, 0b0100000) => op("sraiw", vec![x(rd), x(rs1), imm(shamt)]),
+        _ => None,
+    }
+}
+
+/// Integer operations on two registers, and multiplication and division.
+fn op_register(word: u32, isa: &Isa) -> Option<Decoded> {
+    let (rd, rs1, rs2) = (rd(word), rs1(word), rs2(word));
+    let mnemonic = match (funct7(word), funct3(word)) {
+        (0, 0b000) => "add",
+        (0b0100000, 0b000) if rs1 == 0 => return op("neg", vec![x(rd), x(rs2)]),
+        (0b0100000, 0b000) => "sub",
+        (0, 0b001) => "sll",
+        (0, 0b010) if rs2 == 0 => return op("sltz", vec![x(rd), x(rs1)]),
+        (0, 0b010) if rs1 == 0 => return op("sgtz", vec![x(rd), x(rs2)]),
+        (0, 0b010) => "slt",
+        (0, 0b011) if rs1 == 0 => return op("snez", vec![x(rd), x(rs2)]),
+        (0, 0b011) => "sltu",
+        (0, 0b100) => "xor",
+        (0, 0b101) => "srl",
+        (0b0100000, 0b101) => "sra",
+        (0, 0b110) => "or",
+        (0, 0b111) => "and",
+        (1, funct3) if isa.m => [
+            "mul", "mulh", "mulhsu", "mulhu", "div", "divu", "rem", "remu",
+        ][funct3 as usize],
+        _ => return None,
+    };
+    op(mnemonic, vec![x(rd), x(rs1), x(rs2)])
+}
+
+/// The RV64 operations on the low 32 bits of two registers.
+fn op_register_32(word: u32, isa: &Isa) -> Option<Decoded> {
+    let (rd, rs1, rs2) = (rd(word), rs1(word), rs2(word));
+    let mnemonic = match (funct7(word), funct3(word)) {
+        (0, 0b000) => "addw",
+        (0b0100000, 0b000) if rs1 == 0 => return op("negw", vec![x(rd), x(rs2)]),
+        (0b0100000, 0b000) => "subw",
+        (0, 0b001) => "sllw",
+        (0, 0b101) => "srlw",
+        (0b0100000, 0b101) => "sraw",
+        (1, 0b000) if isa.m => "mulw",
+        (1, 0b100) if isa.m => "divw",
+        (1, 0b101) if isa.m => "divuw",
+        (1, 0b110) if isa.m => "remw",
+        (1, 0b111) if isa.m => "remuw",
+        _ => return None,
+    };
+    op(mnemonic, vec![x(rd), x(rs1), x(rs2)])
+}
+
+/// fence, fence.tso and fence.i. The fields they don't use must be zero.
+fn misc_mem(word: u32, isa: &Isa) -> Option<Decoded> {
+    if rd(word) != 0 || rs1(word) != 0 {
+        return None;
+    }
+    let (fm, pred, succ) = (bits(word, 28, 4), bits(word, 24, 4), bits(word, 20, 4));
+    match funct3(word) {
+        0b000 if fm == 0 && pred == 0b1111 && succ == 0b1111 => op("fence", vec![]),
+        0b000 if fm == 0 => op("fence", vec![fence_set(pred), fence_set(succ)]),
+        0b000 if fm == 0b1000 && pred == 0b0011 && succ == 0b0011 => op("fence.tso", vec![]),
+        0b001 if isa.zifencei && word >> 20 == 0 => op("fence.i", vec![]),
+        _ => None,
+    }
+}
+
+/// What a fence orders before or after it: device input and output, and
+/// memory reads and writes.
+fn fence_set(set: u32) -> Operand {
+    let name: String = "iorw"
+        .chars()
+        .enumerate()
+        .filter(|&(i, _)| set & (0b1000 >> i) != 0)
+        .map(|(_, c)| c)
+        .collect();
+    match name.is_empty() {
+        true => Operand::Name("unknown".to_owned()),
+        false => Operand::Name(name),
+    }
+}
+
+/// Environment calls, returns from traps, and the instructions that read
+/// and write control and status registers.
+fn system(word: u32, isa: &Isa) -> Option<Decoded> {
+    let (rd, rs1, csr) = (rd(word), rs1(word), word >> 20);
+    if funct3(word) == 0 {
+        if rd != 0 {
+            return None;
+        }
+        let (mnemonic, flow) = match word {
+            0x00000073 => ("ecall", Flow::Next),
+            0x00100073 => ("ebreak", Flow::Stop),
+            0x00200073 => ("uret", Flow::Return),
+            0x10200073 => ("sret", Flow::Return),
+            0x30200073 => ("mret", Flow::Return),
+            0x7b200073 => ("dret", Flow::Return),
+            0x10500073 => ("wfi", Flow::Next),
+            _ if funct7(word) == 0b0001001 => {
+                let operands = match (rs1, rs2(word)) {
+                    (0, 0) => vec![],
+                    (_, 0) => vec![x(rs1)],
+                    (_, rs2) => vec![x(rs1), x(rs2)],
+                };
+                return op("sfence.vma", operands);
+            }
+            _ => return None,
+        };
+        return Some(Decoded {
+            flow,
+            ..op(mnemonic, vec![])?
+        });
+    }
+    if !isa.zicsr {
+        return None;
+    }
+
+    // `unimp` is a write of zero to the read-only cycle counter, which
+    // traps.
+    if word == 0xc0001073 {
+        return Some(Decoded {
+            flow: Flow::Stop,
+            ..op("unimp", vec![])?
+        });
+    }
+
+    // The floating point registers' aliases, then the counters', then
+    // those for ignoring what was read, or writing nothing.
+    if let Some(decoded) = float_csr_alias(word, isa) {
+        return Some(decoded);
+    }
+    let counter = match (csr, isa.xlen) {
+        (0xc00, _) => Some("rdcycle"),
+        (0xc01, _) => Some("rdtime"),
+        (0xc02, _) => Some("rdinstret"),
+        (0xc80, 32) => Some("rdcycleh"),
+        (0xc81, 32) => Some("rdtimeh"),
+        (0xc82, 32) => Some("rdinstreth"),
+        _ => None,
+    };
+    if let (Some(mnemonic), 0b010, 0) = (counter, funct3(word), rs1) {
+        return op(mnemonic, vec![x(rd)]);
+    }
+
+    let csr = csr_name(csr, isa);
+    let source = match funct3(word) {
+        0b001..=0b011 => x(rs1),
+        _ => imm(rs1 as i64),
+    };
+    let (mnemonic, operands) = match (funct3(word), rd, rs1) {
+        (0b010, _, 0) => ("csrr", vec![x(rd), csr]),
+        (0b001, 0, _) => ("csrw", vec![csr, source]),
+        (0b010, 0, _) => ("csrs", vec![csr, source]),
+        (0b011, 0, _) => ("csrc", vec![csr, source]),
+        (0b101, 0, _) => ("csrwi", vec![csr, source]),
+        (0b110, 0, _) => ("csrsi", vec![csr, source]),
+        (0b111, 0, _) => ("csrci", vec![csr, source]),
+        (0b001, _, _) => ("csrrw", vec![x(rd), csr, source]),
+        (0b010, _, _) => ("csrrs", vec![x(rd), csr, source]),
+        (0b011, _, _) => ("csrrc", vec![x(rd), csr, source]),
+        (0b101, _, _) => ("csrrwi", vec![x(rd), csr, source]),
+        (0b110, _, _) => ("csrrsi", vec![x(rd), csr, source]),
+        (0b111, _, _) => ("csrrci", vec![x(rd), csr, source]),
+        _ => return None,
+    };
+    op(mnemonic, operands)
+}
+
+/// The aliases for reading and writing the floating point control and
+/// status registers: `frflags`, `fsrm`, `fsrmi` and so on.
+fn float_csr_alias(word: u32, isa: &Isa) -> Option<Decoded> {
+    let name = match (isa.f, word >> 20) {
+        (true, 0x001) => "flags",
+        (true, 0x002) => "rm",
+        (true, 0x003) => "csr",
+        _ => return None,
+    };
+    let (rd, rs1) = (rd(word), rs1(word));
+    let (suffix, operands) = match (funct3(word), rd, rs1) {
+        (0b010, _, 0) => return op(&format!("fr{name}"), vec![x(rd)]),
+        (0b001, 0, _) => ("", vec![x(rs1)]),
+        (0b001, _, _) => ("", vec![x(rd), x(rs1)]),
+        (0b101, 0, _) if name != "csr" => ("i", vec![imm(rs1 as i64)]),
+        (0b101, _, _) if name != "csr" => ("i", vec![x(rd), imm(rs1 as i64)]),
+        _ => return None,
+    };
+    op(&format!("fs{name}{suffix}"), operands)
+}
+
+/// A control and status register by name, or by number if it hasn't one.
+fn csr_name(number: u32, isa: &Isa) -> Operand {
+    let name = CSRS
+        .iter()
+        .find(|&&(csr, _, rv32_only)| csr as u32 == number && (isa.xlen == 32 || !rv32_only));
+    match name {
+        Some((_, name, _)) => Operand::Name((*name).to_owned()),
+        None => imm(number as i64),
+    }
+}
+
+/// Load-reserved, store-conditional, and the atomic memory operations.
+fn atomic(word: u32, isa: &Isa) -> Option<Decoded> {
+    let size = match funct3(word) {
+        0b010 => "w",
+        0b011 if isa.xlen == 64 => "d",
+        _ => return None,
+    };
+    let ordering = match bits(word, 25, 2) {
+        0b00 => "",
+        0b01 => ".rl",
+        0b10 => ".aq",
+        _ => ".aqrl",
+    };
+    let (rd, rs1, rs2) = (rd(word), rs1(word), rs2(word));
+    let address = Operand::Memory {
+        base: Register::X(rs1 as u8),
+        offset: None,
+    };
+
+    let name = match bits(word, 27, 5) {
+        0b00010 if rs2 == 0 => {
+            return op(&format!("lr.{size}{ordering}"), vec![x(rd), address]);
+        }
+        0b00011 => "sc",
+        0b00001 => "amoswap",
+        0b00000 => "amoadd",
+        0b00100 => "amoxor",
+        0b01100 => "amoand",
+        0b01000 => "amoor",
+        0b10000 => "amomin",
+        0b10100 => "amomax",
+        0b11000 => "amominu",
+        0b11100 => "amomaxu",
+        _ => return None,
+    };
+    op(
+        &format!("{name}.{size}{ordering}"),
+        vec![x(rd), x(rs2), address],
+    )
+}
+
+/// The floating point format of an instruction, `.s` or `.d`, from its
+/// two-bit fmt field, if the extension for it is there.
+fn format_suffix(fmt: u32, isa: &Isa) -> Option<&'static str> {
+    match fmt {
+        0b00 if isa.f => Some("s"),
+        0b01 if isa.d => Some("d"),
+        _ => None,
+    }
+}
+
+fn load_store_fp(word: u32, isa: &Isa) -> Option<Decoded> {
+    let size = match funct3(word) {
+        0b010 if isa.f => "w",
+        0b011 if isa.d => "d",
+        _ => return None,
+    };
+    match bits(word, 0, 7) {
+        0b0000111 => op(
+            &format!("fl{size}"),
+            vec![f(rd(word)), memory(rs1(word), i_immediate(word))],
+        ),
+        _ => op(
+            &format!("fs{size}"),
+            vec![f(rs2(word)), memory(rs1(word), s_immediate(word))],
+        ),
+    }
+}
+
+/// The rounding mode, written only when it isn't the dynamic one (from
+/// frm), which is what's assumed. 5 and 6 aren't rounding modes.
+fn rounding_mode(word: u32) -> Option<Option<Operand>> {
+    let mode = match funct3(word) {
+        0b000 => "rne",
+        0b001 => "rtz",
+        0b010 => "rdn",
+        0b011 => "rup",
+        0b100 => "rmm",
+        0b111 => return Some(None),
+        _ => return None,
+    };
+    Some(Some(Operand::Name(mode.to_owned())))
+}
+
+fn fused_multiply_add(word: u32, isa: &Isa) -> Option<Decoded> {
+    let suffix = format_suffix(bits(word, 25, 2), isa)?;
+    let name = match bits(word, 0, 7) {
+        0b1000011 => "fmadd",
+        0b1000111 => "fmsub",
+        0b1001011 => "fnmsub",
+        _ => "fnmadd",
+    };
+    let mut operands = vec![
+        f(rd(word)),
+        f(rs1(word)),
+        f(rs2(word)),
+        f(bits(word, 27, 5)),
+    ];
+    operands.extend(rounding_mode(word)?);
+    op(&format!("{name}.{suffix}"), operands)
+}
+
+/// The other floating point instructions: arithmetic, sign injection,
+/// comparisons, conversions and moves.
+fn op_fp(word: u32, isa: &Isa) -> Option<Decoded> {
+    let rv64 = isa.xlen == 64;
+    let suffix = format_suffix(bits(word, 25, 2), isa)?;
+    let (rd, rs1, rs2) = (rd(word), rs1(word), rs2(word));
+    let with_rounding = |mnemonic: &str, mut operands: Vec<Operand>| {
+        operands.extend(rounding_mode(word)?);
+        op(mnemonic, operands)
+    };
+
+    match bits(word, 27, 5) {
+        0b00000 => with_rounding(&format!("fadd.{suffix}"), vec![f(rd), f(rs1), f(rs2)]),
+        0b00001 => with_rounding(&format!("fsub.{suffix}"), vec![f(rd), f(rs1), f(rs2)]),
+        0b00010 => with_rounding(&format!("fmul.{suffix}"), vec![f(rd), f(rs1), f(rs2)]),
+        0b00011 => with_rounding(&format!("fdiv.{suffix}"), vec![f(rd), f(rs1), f(rs2)]),
+        0b01011 if rs2 == 0 => with_rounding(&format!("fsqrt.{suffix}"), vec![f(rd), f(rs1)]),
+        0b00100 => {
+            let (name, alias) = match funct3(word) {
+                0b000 => ("fsgnj", "fmv"),
+                0b001 => ("fsgnjn", "fneg"),
+                0b010 => ("fsgnjx", "fabs"),
+                _ => return None,
+            };
+            match rs1 == rs2 {
+                true => op(&format!("{alias}.{suffix}"), vec![f(rd), f(rs1)]),
+                false => op(&format!("{name}.{suffix}"), vec![f(rd), f(rs1), f(rs2)]),
+            }
+        }
+        0b00101 => {
+            let name = match funct3(word) {
+                0b000 => "fmin",
+                0b001 => "fmax",
+                _ => return None,
+            };
+            op(&format!("{name}.{suffix}"), vec![f(rd), f(rs1), f(rs2)])
+        }
+        // Between the two formats, which needs both extensions. Widening
+        // is exact, so takes no rounding mode.
+        0b01000 if isa.d => match (suffix, rs2) {
+            ("s", 0b00001) => with_rounding("fcvt.s.d", vec![f(rd), f(rs1)]),
+            ("d", 0b00000) if funct3(word) == 0 => op("fcvt.d.s", vec![f(rd), f(rs1)]),
+            _ => None,
+        },
+        0b10100 => {
+            let name = match funct3(word) {
+                0b010 => "feq",
+                0b001 => "flt",
+                0b000 => "fle",
+                _ => return None,
+            };
+            op(&format!("{name}.{suffix}"), vec![x(rd), f(rs1), f(rs2)])
+        }
+        0b11000 => {
+            let integer = match rs2 {
+                0b00000 => "w",
+                0b00001 => "wu",
+                0b00010 if rv64 => "l",
+                0b00011 if rv64 => "lu",
+                _ => return None,
+            };
+            with_rounding(&format!("fcvt.{integer}.{suffix}"), vec![x(rd), f(rs1)])
+        }
+        0b11010 => {
+            let integer = match rs2 {
+                0b00000 => "w",
+                0b00001 => "wu",
+                0b00010 if rv64 => "l",
+                0b00011 if rv64 => "lu",
+                _ => return None,
+            };
+            let mnemonic = format!("fcvt.{suffix}.{integer}");
+            // A 32-bit integer fits in a double exactly.
+            if suffix == "d" && !rv64_integer(integer) {
+                return match funct3(word) {
+                    0 => op(&mnemonic, vec![f(rd), x(rs1)]),
+                    _ => None,
+                };
+            }
+            with_rounding(&mnemonic, vec![f(rd), x(rs1)])
+        }
+        0b11100 if rs2 == 0 => match (funct3(word), suffix) {
+            (0b000, "s") => op("fmv.x.w", vec![x(rd), f(rs1)]),
+            (0b000, _) if rv64 => op("fmv.x.d", vec![x(rd), f(rs1)]),
+            (0b001, _) => op(&format!("fclass.{suffix}"), vec![x(rd), f(rs1)]),
+            _ => None,
+        },
+        0b11110 if rs2 == 0 && funct3(word) == 0 => match suffix {
+            "s" => op("fmv.w.x", vec![f(rd), x(rs1)]),
+            _ if rv64 => op("fmv.d.x", vec![f(rd), x(rs1)]),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Whether a conversion's integer is a 64-bit one.
+fn rv64_integer(integer: &str) -> bool {
+    integer.starts_with('l')
+}
@@ -0,0 +1,242 @@
+/// A decoder for RISC-V instructions, of RV32 and RV64: the base integer
+/// set and the M, A, F, D and C extensions, with Zicsr and Zifencei.
+/// Which of them a file uses comes from its `.riscv.attributes` section
+/// (and its `e_flags`, for C), and instructions of any others don't decode.
+/// Instructions are written as llvm-objdump writes them, with the aliases
+/// it prefers (`li`, `mv`, `ret`, `beqz` and so on), and compressed ones as
+/// the instructions they stand for.
+///
+mod compressed;
+mod csrs;
+mod decode;
+
+pub use decode::decode;
+
+use crate::disasm::Flow;
+use crate::parse::attributes::{AttributeValue, TAG_RISCV_ARCH};
+use crate::parse::header::EF_RISCV_RVC;
+use crate::{ElfError, ElfFile};
+
+/// The base instruction set and the extensions that code may use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Isa {
+    /// 32 or 64.
+    pub xlen: u32,
+    /// Integer multiplication and division.
+    pub m: bool,
+    /// Atomics.
+    pub a: bool,
+    /// Single precision floating point.
+    pub f: bool,
+    /// Double precision floating point.
+    pub d: bool,
+    /// Compressed, 16-bit instructions.
+    pub c: bool,
+    /// The instructions that read and write control and status registers.
+    pub zicsr: bool,
+    /// fence.i.
+    pub zifencei: bool,
+}
+
+impl Isa {
+    /// RV64GC: everything this decoder knows.
+    pub const RV64GC: Isa = Isa {
+        xlen: 64,
+        m: true,
+        a: true,
+        f: true,
+        d: true,
+        c: true,
+        zicsr: true,
+        zifencei: true,
+    };
+
+    /// Reads an ISA string, as in `rv64imac` or (from an attributes
+    /// section, with versions) `rv64i2p1_m2p0_a2p1_c2p0_zicsr2p0`.
+    /// Extensions this decoder doesn't know are ignored. Before version 2.1
+    /// of the base set, Zicsr and Zifencei were part of it.
+    pub fn parse(isa: &str) -> Option<Isa> {
+        let isa = isa.to_ascii_lowercase();
+        let (xlen, rest) = match isa.get(..4)? {
+            "rv32" => (32, &isa[4..]),
+            "rv64" => (64, &isa[4..]),
+            _ => return None,
+        };
+        let mut parsed = Isa {
+            xlen,
+            m: false,
+            a: false,
+            f: false,
+            d: false,
+            c: false,
+            zicsr: false,
+            zifencei: false,
+        };
+
+        for (i, part) in rest.split('_').enumerate() {
+            if i > 0 && part.starts_with(['z', 's', 'x']) {
+                let name = part.trim_end_matches(|c: char| c.is_ascii_digit() || c == 'p');
+                match name {
+                    "zicsr" => parsed.zicsr = true,
+                    "zifencei" => parsed.zifencei = true,
+                    _ => {}
+                }
+                continue;
+            }
+
+            // Single letters, each maybe with a version, as in `i2p1`.
+            let mut chars = part.chars().peekable();
+            while let Some(letter) = chars.next() {
+                let mut version = String::new();
+                while let Some(&c) = chars.peek() {
+                    if !(c.is_ascii_digit() || (c == 'p' && !version.is_empty())) {
+                        break;
+                    }
+                    version.push(c);
+                    chars.next();
+                }
+                match letter {
+                    'i' | 'e' => {
+                        let (major, minor) = version.split_once('p').unwrap_or((&version, "0"));
+                        let version = (major.parse().unwrap_or(2), minor.parse().unwrap_or(0));
+                        if version < (2, 1) {
+                            parsed.zicsr = true;
+                            parsed.zifencei = true;
+                        }
+                    }
+                    'g' => {
+                        (parsed.m, parsed.a, parsed.f, parsed.d) = (true, true, true, true);
+                        (parsed.zicsr, parsed.zifencei) = (true, true);
+                    }
+                    'm' => parsed.m = true,
+                    'a' => parsed.a = true,
+                    'f' => parsed.f = true,
+                    'd' => parsed.d = true,
+                    'c' => parsed.c = true,
+                    _ => {}
+                }
+            }
+        }
+        Some(parsed)
+    }
+
+    /// The instruction set of a file's code: from the `arch` attribute in
+    /// its `.riscv.attributes` section if it has one, and otherwise RV64G
+    /// (as the ELF header is 64-bit). Either way, C if `e_flags` says it's
+    /// used.
+    pub fn for_file(elf_file: &mut ElfFile) -> Result<Isa, ElfError> {
+        let arch = elf_file
+            .build_attributes()?
+            .into_iter()
+            .filter(|subsection| subsection.vendor == "riscv")
+            .find_map(|subsection| match subsection.get(TAG_RISCV_ARCH) {
+                Some(AttributeValue::String(arch)) => Isa::parse(arch),
+                _ => None,
+            });
+        let isa = arch.unwrap_or(Isa {
+            c: false,
+            ..Isa::RV64GC
+        });
+        Ok(Isa {
+            c: isa.c || elf_file.header.flags & EF_RISCV_RVC != 0,
+            ..isa
+        })
+    }
+}
+
+/// The ABI names of the integer registers.
+const X_NAMES: [&str; 32] = [
+    "zero", "ra", "sp", "gp", "tp", "t0", "t1", "t2", "s0", "s1", "a0", "a1", "a2", "a3", "a4",
+    "a5", "a6", "a7", "s2", "s3", "s4", "s5", "s6", "s7", "s8", "s9", "s10", "s11", "t3", "t4",
+    "t5", "t6",
+];
+
+/// The ABI names of the floating point registers.
+const F_NAMES: [&str; 32] = [
+    "ft0", "ft1", "ft2", "ft3", "ft4", "ft5", "ft6", "ft7", "fs0", "fs1", "fa0", "fa1", "fa2",
+    "fa3", "fa4", "fa5", "fa6", "fa7", "fs2", "fs3", "fs4", "fs5", "fs6", "fs7", "fs8", "fs9",
+    "fs10", "fs11", "ft8", "ft9", "ft10", "ft11",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Register {
+    /// An integer register, x0 (zero) to x31.
+    X(u8),
+    /// A floating point register, f0 to f31.
+    F(u8),
+}
+
+impl Register {
+    /// The register's ABI name, as in `a0` or `fs1`.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Register::X(number) => X_NAMES[number as usize & 31],
+            Register::F(number) => F_NAMES[number as usize & 31],
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Operand {
+    Register(Register),
+    /// An immediate, written in decimal.
+    Immediate(i64),
+    /// A base register with an offset, as in `8(sp)`; atomics have no
+    /// offset, as in `(a0)`.
+    Memory {
+        base: Register,
+        offset: Option<i64>,
+    },
+    /// The address that a jump or branch goes to.
+    Target(u64),
+    /// Anything else, written as it is: control and status registers,
+    /// rounding modes, and what a fence orders.
+    Name(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Instruction {
+    pub address: u64,
+    /// 4 bytes, or 2 for a compressed instruction (and for the bytes left
+    /// at the end of a section too short to hold an instruction, what's
+    /// left).
+    pub len: usize,
+    /// The instruction as the number it is, little endian.
+    pub word: u32,
+    pub mnemonic: String,
+    pub operands: Vec<Operand>,
+    pub flow: Flow,
+}
+
+impl Instruction {
+    /// Whether this is an instruction that couldn't be decoded.
+    pub fn is_bad(&self) -> bool {
+        self.mnemonic == "<unknown>"
+    }
+
+    /// Writes the instruction as llvm-objdump does. `symbolize` names an
+    /// address, as in `main+0x1c`, for jump and branch targets.
+    pub fn format(&self, symbolize: &dyn Fn(u64) -> Option<String>) -> String {
+        if self.operands.is_empty() {
+            return self.mnemonic.clone();
+        }
+        let operands: Vec<String> = self
+            .operands
+            .iter()
+            .map(|operand| match operand {
+                Operand::Register(register) => register.name().to_owned(),
+                Operand::Immediate(value) => value.to_string(),
+                Operand::Memory { base, offset } => match offset {
+                    Some(offset) => format!("{offset}({})", base.name()),
+                    None => format!("({})", base.name()),
+                },
+                Operand::Target(address) => match symbolize(*address) {
+                    Some(name) => format!("{address:#x} <{name}>"),
+                    None => format!("{address:#x}"),
+                },
+                Operand::Name(name) => name.clone(),
+            })
+            .collect();
+        format!("{}\t{}", self.mnemonic, operands.join(", "))
+    }
+}
@@ -0,0 +1,1457 @@
+/// Reads an instruction's prefixes, opcode, ModRM and SIB bytes,
+/// displacement and immediate, and makes its operands from the
+/// specifications in the opcode tables.
+///
+use super::tables::{self, Entry, Map, Opcode};
+use super::*;
+
+/// Why the bytes at an address aren't an instruction.
+enum Error {
+    /// They run out partway through one.
+    Truncated,
+    /// The opcode (or its prefixes or ModRM byte) isn't defined.
+    Invalid,
+}
+
+type Result<T> = std::result::Result<T, Error>;
+
+// REX bits.
+const REX_B: u8 = 0x1;
+const REX_X: u8 = 0x2;
+const REX_R: u8 = 0x4;
+const REX_W: u8 = 0x8;
+const REX: u8 = 0x40;
+
+/// The fields of a VEX or EVEX prefix, with the register numbers inverted
+/// back.
+#[derive(Debug, Clone, Copy, Default)]
+struct Vex {
+    evex: bool,
+    r: bool,
+    x: bool,
+    b: bool,
+    /// EVEX's second extension bits for registers: R' and V'.
+    r2: bool,
+    v2: bool,
+    w: bool,
+    vvvv: u8,
+    /// The vector length: 0 for 128 bits, 1 for 256 and 2 for 512.
+    l: u8,
+    pp: u8,
+    map: u8,
+    aaa: u8,
+    z: bool,
+    /// EVEX.b: broadcast for memory operands, and rounding for registers.
+    b_bit: bool,
+}
+
+/// Decodes the instruction at the start of `bytes`, which is at `address`.
+/// Bytes that aren't an instruction decode as "(bad)", with the length
+/// that objdump skips for them.
+pub fn decode(bytes: &[u8], address: u64) -> Instruction {
+    let bytes = &bytes[..bytes.len().min(MAX_INSTRUCTION_LEN)];
+    let mut decoder = Decoder::new(bytes, address);
+
+    match decoder.instruction() {
+        Ok(instruction) => instruction,
+        Err(error) => decoder.bad(error),
+    }
+}
+
+struct Decoder<'a> {
+    bytes: &'a [u8],
+    address: u64,
+    pos: usize,
+
+    /// The legacy and REX prefixes, in order, and whether each is still to
+    /// be shown (because the instruction hasn't used it).
+    prefixes: Vec<(u8, bool)>,
+
+    // The index of the last prefix of each kind.
+    operand_size_prefix: Option<usize>,
+    address_size_prefix: Option<usize>,
+    rep_prefix: Option<usize>,
+    segment_prefix: Option<usize>,
+    fwait_prefix: Option<usize>,
+    rex_prefix: Option<usize>,
+
+    rex: u8,
+    rex_used: u8,
+    vex: Option<Vex>,
+
+    opcode: Option<Opcode>,
+    entry: Entry,
+    modrm: Option<u8>,
+    /// Where the opcode ends (and the ModRM byte, if any, starts).
+    opcode_end: usize,
+
+    /// The size of the operands whose size follows the prefixes, once read.
+    operand_size: u8,
+
+    /// The size of the ModRM operand when it's in memory, for AT&T suffixes.
+    memory_size: Option<u8>,
+
+    /// Whether any operand is a register, or a memory operand is
+    /// broadcast.
+    has_register: bool,
+    broadcast: bool,
+}
+
+impl<'a> Decoder<'a> {
+    fn new(bytes: &'a [u8], address: u64) -> Self {
+        Decoder {
+            bytes,
+            address,
+            pos: 0,
+            prefixes: vec![],
+            operand_size_prefix: None,
+            address_size_prefix: None,
+            rep_prefix: None,
+            segment_prefix: None,
+            fwait_prefix: None,
+            rex_prefix: None,
+            rex: 0,
+            rex_used: 0,
+            vex: None,
+            opcode: None,
+            entry: Entry::BAD,
+            modrm: None,
+            opcode_end: 0,
+            operand_size: 4,
+            memory_size: None,
+            has_register: false,
+            broadcast: false,
+        }
+    }
+
+    fn peek(&self) -> Result<u8> {
+        self.bytes.get(self.pos).copied().ok_or(Error::Truncated)
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        let byte = self.peek()?;
+        self.pos += 1;
+        Ok(byte)
+    }
+
+    fn uint(&mut self, size: usize) -> Result<u64> {
+        let bytes = self
+            .bytes
+            .get(self.pos..self.pos + size)
+            .ok_or(Error::Truncated)?;
+        self.pos += size;
+
+        Ok(bytes
+            .iter()
+            .rev()
+            .fold(0, |value, byte| value << 8 | *byte as u64))
+    }
+
+    /// Reads `size` bytes, sign-extended.
+    fn int(&mut self, size: usize) -> Result<i64> {
+        let value = self.uint(size)?;
+        let shift = 64 - 8 * size as u32;
+        Ok((value << shift) as i64 >> shift)
+    }
+
+    /// Reads the legacy and REX prefixes.
+    fn read_prefixes(&mut self) -> Result<()> {
+        loop {
+            let byte = self.peek()?;
+            let index = self.prefixes.len();
+
+            match byte {
+                0x26 | 0x2e | 0x36 | 0x3e | 0x64 | 0x65 => self.segment_prefix = Some(index),
+                0x66 => self.operand_size_prefix = Some(index),
+                0x67 => self.address_size_prefix = Some(index),
+                0xf2 | 0xf3 => self.rep_prefix = Some(index),
+                0xf0 => {}
+
+                // fwait is an instruction of its own, unless it's before
+                // an x87 instruction, whose "fn" form it makes the plain one.
+                0x9b => match self.bytes.get(self.pos + 1) {
+                    Some(0xd8..=0xdf) => self.fwait_prefix = Some(index),
+                    _ => return Ok(()),
+                },
+
+                // REX is only a prefix right before the opcode: if another
+                // prefix follows it, it's an instruction (that does nothing).
+                0x40..=0x4f => {
+                    self.pos += 1;
+                    self.prefixes.push((byte, true));
+                    self.rex_prefix = Some(index);
+                    self.rex = byte;
+
+                    return match self.peek()? {
+                        0x26
+                        | 0x2e
+                        | 0x36
+                        | 0x3e
+                        | 0x40..=0x4f
+                        | 0x64..=0x67
+                        | 0x9b
+                        | 0xf0
+                        | 0xf2
+                        | 0xf3 => Err(Error::Invalid),
+                        _ => Ok(()),
+                    };
+                }
+                _ => return Ok(()),
+            }
+
+            self.pos += 1;
+            self.prefixes.push((byte, true));
+        }
+    }
+
+    fn use_prefix(&mut self, index: Option<usize>) {
+        if let Some(index) = index {
+            self.prefixes[index].1 = false;
+        }
+    }
+
+    fn prefix(&self, index: Option<usize>) -> Option<u8> {
+        index.map(|index| self.prefixes[index].0)
+    }
+
+    /// Marks a REX bit as used, if it's set (with 0, marks REX itself as used).
+    fn use_rex(&mut self, bit: u8) {
+        if bit == 0 {
+            self.rex_used |= REX;
+        } else if self.rex & bit != 0 {
+            self.rex_used |= bit | REX;
+        }
+    }
+
+    /// Whether a REX (or VEX) bit is set, marking it used.
+    fn rex_bit(&mut self, bit: u8) -> bool {
+        if let Some(vex) = self.vex {
+            return match bit {
+                REX_B => vex.b,
+                REX_X => vex.x,
+                REX_R => vex.r,
+                _ => vex.w,
+            };
+        }
+        self.use_rex(bit);
+        self.rex & bit != 0
+    }
+
+    fn w(&mut self) -> bool {
+        self.rex_bit(REX_W)
+    }
+
+    fn modrm(&mut self) -> Result<u8> {
+        if let Some(modrm) = self.modrm {
+            return Ok(modrm);
+        }
+        let modrm = self.u8()?;
+        self.modrm = Some(modrm);
+        Ok(modrm)
+    }
+
+    /// The ModRM byte, without reading it.
+    fn peek_modrm(&self) -> u8 {
+        self.modrm
+            .or_else(|| self.bytes.get(self.pos).copied())
+            .unwrap_or(0)
+    }
+
+    fn instruction(&mut self) -> Result<Instruction> {
+        self.read_prefixes()?;
+
+        let opcode = self.opcode()?;
+        self.opcode = Some(opcode);
+        self.entry = tables::lookup(&opcode).ok_or(Error::Invalid)?;
+        if opcode.has_modrm() {
+            self.modrm()?;
+        }
+
+        if self.entry.flags & tables::PREFIXED != 0 {
+            self.use_mandatory_prefix(&opcode);
+        }
+        // fwait before an x87 instruction belongs to it.
+        if opcode.map == Map::OneByte && (0xd8..=0xdf).contains(&opcode.byte) {
+            self.use_prefix(self.fwait_prefix);
+        }
+        if self.entry.flags & tables::USES_W != 0 {
+            self.w();
+        }
+        if self.entry.flags & tables::SIZED != 0 {
+            self.operand_size();
+        }
+        if self.entry.flags & tables::ADDRESS != 0 {
+            self.use_prefix(self.address_size_prefix);
+        }
+        if self.entry.flags & tables::DEF64 != 0 {
+            self.operand_size = match self.prefix(self.operand_size_prefix) {
+                Some(_) if self.rex & REX_W == 0 => {
+                    self.use_prefix(self.operand_size_prefix);
+                    2
+                }
+                _ => 8,
+            };
+        }
+
+        // Without VEX, there's no VEX.vvvv or fourth register.
+        let legacy = self.vex.is_none();
+        let mut operands = vec![];
+        for spec in self.entry.operands.split(',') {
+            if spec.is_empty() || legacy && matches!(&spec[..1], "H" | "B" | "L") {
+                continue;
+            }
+            operands.push(self.operand(spec)?);
+        }
+        let predicate = self.predicate(&mut operands);
+
+        // EVEX rounding goes after the operands (in Intel order, before the
+        // immediate, if there's one).
+        if let Some(rounding) = self.rounding(&opcode) {
+            let at = match operands.last() {
+                Some(Operand::Immediate(_)) => operands.len() - 1,
+                _ => operands.len(),
+            };
+            operands.insert(at, rounding);
+        }
+
+        let (mnemonic, intel_mnemonic) = self.mnemonics(&operands, predicate);
+        let (flow, indirect) = self.flow(&operands);
+        let hint = self.hint();
+        self.name_prefixes();
+
+        // The REX prefix is shown unless all of its bits were used.
+        if self.rex != 0 && (self.rex ^ self.rex_used) == 0 {
+            self.use_prefix(self.rex_prefix);
+        }
+
+        let (mask, zeroing) = match self.vex {
+            Some(vex) if vex.evex => ((vex.aaa != 0).then_some(vex.aaa), vex.z),
+            _ => (None, false),
+        };
+
+        Ok(Instruction {
+            address: self.address,
+            len: self.pos,
+            prefixes: self.shown_prefixes(),
+            mnemonic,
+            intel_mnemonic,
+            operands,
+            hint,
+            indirect,
+            mask,
+            zeroing,
+            flow,
+        })
+    }
+
+    /// An operand that must be in memory but is a register: objdump then
+    /// skips only the prefixes and the opcode.
+    fn bad_operand(&mut self) -> Error {
+        self.pos = self.opcode_end;
+        Error::Invalid
+    }
+
+    /// What's left after an error. objdump skips the bytes read so far if
+    /// the opcode is invalid; if the instruction is cut short, it skips
+    /// just the first byte, written as the prefix it is or as `.byte`.
+    fn bad(&mut self, error: Error) -> Instruction {
+        if let Error::Truncated = error {
+            let name = match (self.prefixes.first(), self.bytes.first()) {
+                (Some(&(prefix, _)), _) => prefix_name(prefix),
+                (None, Some(byte)) => format!(".byte {byte:#x}"),
+                (None, None) => ".byte".to_owned(),
+            };
+            return Instruction {
+                mnemonic: name.clone(),
+                intel_mnemonic: name,
+                ..self.bad_instruction(self.bytes.len().min(1))
+            };
+        }
+
+        // A REX prefix before another prefix is an instruction of its own,
+        // after any prefixes before it.
+        if self.rex_prefix.is_some_and(|index| index + 1 == self.pos) && self.vex.is_none() {
+            let mut instruction = Instruction {
+                mnemonic: rex_name(self.rex),
+                intel_mnemonic: rex_name(self.rex),
+                ..self.bad_instruction(self.pos)
+            };
+            self.prefixes.pop();
+            instruction.prefixes = self.shown_prefixes();
+            return instruction;
+        }
+
+        // All the prefixes are shown, except one that picked an SSE
+        // instruction (which the tables then didn't have).
+        for prefix in &mut self.prefixes {
+            prefix.1 = true;
+        }
+        if let Some(opcode) = self.opcode.filter(|_| self.entry.is_bad()) {
+            let without = Opcode {
+                prefix: 0,
+                ..opcode
+            };
+            if !opcode.vex && opcode.prefix != 0 && tables::lookup(&without).is_some() {
+                self.use_mandatory_prefix(&opcode);
+            }
+        }
+
+        let mut instruction = self.bad_instruction(self.pos.max(1));
+        instruction.prefixes = self.shown_prefixes();
+        instruction
+    }
+
+    fn bad_instruction(&self, len: usize) -> Instruction {
+        Instruction {
+            address: self.address,
+            len,
+            prefixes: vec![],
+            mnemonic: "(bad)".to_owned(),
+            intel_mnemonic: "(bad)".to_owned(),
+            operands: vec![],
+            hint: None,
+            indirect: false,
+            mask: None,
+            zeroing: false,
+            flow: Flow::Stop,
+        }
+    }
+
+    fn shown_prefixes(&self) -> Vec<String> {
+        self.prefixes
+            .iter()
+            .filter(|(_, shown)| *shown)
+            .map(|(byte, _)| prefix_name(*byte))
+            .collect()
+    }
+
+    /// Reads the opcode, after a VEX or EVEX prefix if there's one.
+    fn opcode(&mut self) -> Result<Opcode> {
+        let byte = self.u8()?;
+
+        let (map, byte) = match byte {
+            // VEX and EVEX take the place of instructions that are invalid
+            // in 64-bit mode (les, lds and bound).
+            0xc4 | 0xc5 | 0x62 => {
+                self.read_vex(byte)?;
+                let vex = self.vex.unwrap();
+                let map = match vex.map {
+                    1 => Map::TwoByte,
+                    2 => Map::ThreeByte38,
+                    3 => Map::ThreeByte3a,
+                    _ => return Err(Error::Invalid),
+                };
+                (map, self.u8()?)
+            }
+            0x0f => match self.u8()? {
+                0x38 => (Map::ThreeByte38, self.u8()?),
+                0x3a => (Map::ThreeByte3a, self.u8()?),
+                // 3DNow!, which objdump doesn't decode.
+                0x0f => {
+                    self.pos -= 1;
+                    return Err(Error::Invalid);
+                }
+                byte => (Map::TwoByte, byte),
+            },
+            byte => (Map::OneByte, byte),
+        };
+
+        let prefix = match self.vex {
+            Some(vex) => [0, 0x66, 0xf3, 0xf2][vex.pp as usize],
+            None => match self.prefix(self.rep_prefix) {
+                Some(prefix) => prefix,
+                None => self.prefix(self.operand_size_prefix).unwrap_or(0),
+            },
+        };
+
+        self.opcode_end = self.pos;
+        let vex = self.vex.unwrap_or_default();
+        Ok(Opcode {
+            map,
+            byte,
+            prefix,
+            modrm: self.peek_modrm(),
+            rex: self.rex,
+            w: match self.vex {
+                Some(vex) => vex.w,
+                None => self.rex & REX_W != 0,
+            },
+            data16: self.operand_size_prefix.is_some(),
+            addr32: self.address_size_prefix.is_some(),
+            l: vex.l,
+            vex: self.vex.is_some(),
+            evex: vex.evex,
+        })
+    }
+
+    fn read_vex(&mut self, byte: u8) -> Result<()> {
+        let first = self.u8()?;
+        let mut vex = Vex {
+            r: first & 0x80 == 0,
+            ..Vex::default()
+        };
+
+        match byte {
+            0xc5 => {
+                vex.map = 1;
+                vex.vvvv = (!first >> 3) & 0xf;
+                vex.l = (first >> 2) & 1;
+                vex.pp = first & 3;
+            }
+            0xc4 => {
+                if !(1..=3).contains(&(first & 0x1f)) {
+                    self.pos -= 1;
+                    return Err(Error::Invalid);
+                }
+                let second = self.u8()?;
+                vex.x = first & 0x40 == 0;
+                vex.b = first & 0x20 == 0;
+                vex.map = first & 0x1f;
+                vex.w = second & 0x80 != 0;
+                vex.vvvv = (!second >> 3) & 0xf;
+                vex.l = (second >> 2) & 1;
+                vex.pp = second & 3;
+            }
+            // objdump stops at the first byte that isn't valid.
+            _ => {
+                // Maps 5 and 6 (for half-precision floats) aren't decoded here.
+                if !matches!(first & 0xf, 1..=3 | 5 | 6) {
+                    self.pos -= 1;
+                    return Err(Error::Invalid);
+                }
+                let second = self.u8()?;
+                if second & 0x04 == 0 {
+                    self.pos -= 1;
+                    return Err(Error::Invalid);
+                }
+                let third = self.u8()?;
+                vex.evex = true;
+                vex.x = first & 0x40 == 0;
+                vex.b = first & 0x20 == 0;
+                vex.r2 = first & 0x10 == 0;
+                vex.map = first & 0x3;
+                vex.w = second & 0x80 != 0;
+                vex.vvvv = (!second >> 3) & 0xf;
+                vex.pp = second & 3;
+                vex.z = third & 0x80 != 0;
+                vex.l = (third >> 5) & 3;
+                vex.b_bit = third & 0x10 != 0;
+                vex.v2 = third & 0x08 == 0;
+                vex.aaa = third & 7;
+            }
+        }
+
+        // Prefixes before it that it replaces are left unused, and shown.
+        self.vex = Some(vex);
+        Ok(())
+    }
+
+    /// Marks the prefix that selected an instruction (as 66 does packed
+    /// double-precision forms of SSE instructions) as used.
+    fn use_mandatory_prefix(&mut self, opcode: &Opcode) {
+        if self.vex.is_some() {
+            return;
+        }
+        match opcode.prefix {
+            0xf2 | 0xf3 => self.use_prefix(self.rep_prefix),
+            0x66 => self.use_prefix(self.operand_size_prefix),
+            _ => {}
+        }
+    }
+
+    fn vector_length(&self) -> u8 {
+        match self.vex {
+            Some(vex) => 16 << vex.l.min(2),
+            None => 16,
+        }
+    }
+
+    /// The size of operands sized by the prefixes: REX.W, then 66.
+    fn operand_size(&mut self) -> u8 {
+        if self.entry.flags & tables::DEF64 != 0 {
+            return self.operand_size;
+        }
+        self.operand_size = if self.w() {
+            8
+        } else if self.prefix(self.operand_size_prefix).is_some() && self.vex.is_none() {
+            self.use_prefix(self.operand_size_prefix);
+            2
+        } else {
+            4
+        };
+        self.operand_size
+    }
+
+    /// The size of an operand, from the lowercase part of its specification.
+    fn size(&mut self, size: &str) -> u8 {
+        match size {
+            "b" => 1,
+            "w" => 2,
+            "d" => 4,
+            "q" => 8,
+            "t" => 10,
+            "o" => 16,
+            "v" => self.operand_size(),
+            "y" => match self.w() {
+                true => 8,
+                false => 4,
+            },
+            // 32 bits (without marking REX.W used), or 16 with 66.
+            "z" if self.rex & REX_W != 0 && self.vex.is_none() => 4,
+            "z" => self.operand_size().min(4),
+            // Vectors: the whole vector, half, a quarter or an eighth.
+            "x" => self.vector_length(),
+            "h" => self.vector_length() / 2,
+            "qr" => self.vector_length() / 4,
+            "e" => self.vector_length() / 8,
+            // A far pointer: a 2-byte selector and a 4-byte offset (or a
+            // 2-byte one with the operand size prefix).
+            "p" => match self.operand_size_prefix {
+                Some(_) => 4,
+                None => 6,
+            },
+            _ => 0,
+        }
+    }
+
+    fn gpr(&mut self, number: u8, size: u8) -> Register {
+        self.has_register = true;
+        match size {
+            1 if self.rex == 0 && self.vex.is_none() && (4..8).contains(&number) => {
+                Register::HighByte(number - 4)
+            }
+            // spl to dil need REX, which is otherwise shown as unused.
+            1 if (4..8).contains(&number) => {
+                self.use_rex(0);
+                Register::Gpr { number, size }
+            }
+            _ => Register::Gpr { number, size },
+        }
+    }
+
+    /// The register in ModRM.reg, extended by REX.R (and EVEX.R').
+    fn reg(&mut self) -> Result<u8> {
+        let modrm = self.modrm()?;
+        let r = self.rex_bit(REX_R) as u8;
+        Ok((modrm >> 3) & 7 | r << 3)
+    }
+
+    fn vector_reg(&mut self) -> Result<u8> {
+        let reg = self.reg()?;
+        let r2 = self.vex.is_some_and(|vex| vex.r2) as u8;
+        Ok(reg | r2 << 4)
+    }
+
+    /// The register in ModRM.rm, extended by REX.B (and EVEX.X).
+    fn rm(&mut self) -> Result<u8> {
+        let modrm = self.modrm()?;
+        let b = self.rex_bit(REX_B) as u8;
+        Ok(modrm & 7 | b << 3)
+    }
+
+    fn vector_rm(&mut self) -> Result<u8> {
+        let rm = self.rm()?;
+        let x = self.vex.is_some_and(|vex| vex.evex && vex.x) as u8;
+        Ok(rm | x << 4)
+    }
+
+    /// The register in VEX.vvvv (extended by EVEX.V').
+    fn vvvv(&self) -> u8 {
+        match self.vex {
+            Some(vex) => vex.vvvv | ((vex.evex && vex.v2) as u8) << 4,
+            None => 0,
+        }
+    }
+
+    fn is_memory(&mut self) -> Result<bool> {
+        Ok(self.modrm()? >> 6 != 3)
+    }
+
+    fn vector(&mut self, number: u8, size: u8) -> Register {
+        self.has_register = true;
+        Register::Vector {
+            number,
+            size: size.max(16),
+        }
+    }
+
+    /// Makes an operand from its specification: an addressing method (in
+    /// capitals, as in the Intel manuals) and a size.
+    fn operand(&mut self, spec: &str) -> Result<Operand> {
+        if let Some(register) = spec.strip_prefix('%') {
+            return self.fixed_register(register);
+        }
+        match spec {
+            "1" => return Ok(Operand::One),
+            "STi" => {
+                self.has_register = true;
+                return Ok(Operand::Register(Register::StI(self.modrm()? & 7)));
+            }
+            _ => {}
+        }
+
+        let split = spec
+            .find(|c: char| c.is_ascii_lowercase() || c.is_ascii_digit())
+            .unwrap_or(spec.len());
+        let (method, size) = spec.split_at(split);
+
+        Ok(match method {
+            // ModRM.rm: a general-purpose register or memory.
+            "E" => {
+                let size = self.size(size);
+                match self.is_memory()? {
+                    true => Operand::Memory(self.memory(size)?),
+                    false => {
+                        let rm = self.rm()?;
+                        Operand::Register(self.gpr(rm, size))
+                    }
+                }
+            }
+            // ModRM.rm: only memory.
+            "M" => {
+                if !self.is_memory()? {
+                    return Err(self.bad_operand());
+                }
+                let size = self.size(size);
+                Operand::Memory(self.memory(size)?)
+            }
+            // ModRM.rm: only a general-purpose register.
+            "R" => {
+                let size = self.size(size);
+                let rm = self.rm()?;
+                Operand::Register(self.gpr(rm, size))
+            }
+            // ModRM.reg: a general-purpose register.
+            "G" => {
+                let size = self.size(size);
+                let reg = self.reg()?;
+                Operand::Register(self.gpr(reg, size))
+            }
+            // VEX.vvvv: a general-purpose register.
+            "B" => {
+                let size = self.size(size);
+                let vvvv = self.vvvv();
+                Operand::Register(self.gpr(vvvv, size))
+            }
+            // The low bits of the opcode, extended by REX.B.
+            "Z" => {
+                let size = self.size(size);
+                let number = self.bytes[self.pos - 1] & 7 | (self.rex_bit(REX_B) as u8) << 3;
+                Operand::Register(self.gpr(number, size))
+            }
+            "S" => {
+                self.has_register = true;
+                Operand::Register(Register::Segment((self.modrm()? >> 3) & 7))
+            }
+            "C" => {
+                self.has_register = true;
+                Operand::Register(Register::Control(self.reg()?))
+            }
+            "D" => {
+                self.has_register = true;
+                Operand::Register(Register::Debug(self.reg()?))
+            }
+
+            "I" => self.immediate(size)?,
+            // A branch target. objdump takes an operand size prefix to make
+            // the offset (and the target) 16 bits, as AMD processors do.
+            "J" => {
+                let short = size == "z"
+                    && self.rex & REX_W == 0
+                    && self.prefix(self.operand_size_prefix).is_some();
+                let offset = match (size, short) {
+                    ("b", _) => self.int(1)?,
+                    (_, true) => {
+                        self.use_prefix(self.operand_size_prefix);
+                        self.int(2)?
+                    }
+                    _ => self.int(4)?,
+                };
+                let next = self.address.wrapping_add(self.pos as u64);
+                let target = next.wrapping_add(offset as u64);
+                Operand::Target(if short { target & 0xffff } else { target })
+            }
+
+            // A memory offset, as wide as an address.
+            "O" => {
+                let size = self.size(size);
+                let address_size = match self.address_size_prefix {
+                    Some(_) => 4,
+                    None => 8,
+                };
+                let offset = self.uint(address_size)?;
+                self.memory_size = Some(size);
+                Operand::Memory(Memory {
+                    segment: self.segment(),
+                    base: None,
+                    index: None,
+                    scale: 1,
+                    displacement: offset as i64,
+                    has_displacement: true,
+                    size: 0,
+                    broadcast: None,
+                })
+            }
+
+            // String operands: ds:rsi (or another segment) and es:rdi.
+            "X" | "Y" => {
+                let size = self.size(size);
+                self.string_operand(method == "Y", size, 6 + (method == "Y") as u8)
+            }
+            // xlat's table, at ds:rbx.
+            "XL" => self.string_operand(false, 1, 3),
+
+            // The top of the x87 stack (and `STi` above, one relative to it).
+            "ST" => {
+                self.has_register = true;
+                Operand::Register(Register::St)
+            }
+
+            // MMX registers: in ModRM.reg, or ModRM.rm (or memory).
+            "P" => {
+                self.has_register = true;
+                Operand::Register(Register::Mmx((self.modrm()? >> 3) & 7))
+            }
+            "N" => {
+                self.has_register = true;
+                Operand::Register(Register::Mmx(self.modrm()? & 7))
+            }
+            "Q" => match self.is_memory()? {
+                true => {
+                    let size = self.size(size);
+                    Operand::Memory(self.memory(size)?)
+                }
+                false => {
+                    self.has_register = true;
+                    Operand::Register(Register::Mmx(self.modrm()? & 7))
+                }
+            },
+
+            // Vector registers: in ModRM.reg, ModRM.rm (or memory), VEX.vvvv
+            // and the top of an immediate.
+            "V" => {
+                let size = self.register_size(size);
+                let reg = self.vector_reg()?;
+                Operand::Register(self.vector(reg, size))
+            }
+            "W" | "U" => match self.is_memory()? {
+                true if method == "W" => {
+                    let size = self.size(size);
+                    Operand::Memory(self.memory(size)?)
+                }
+                true => return Err(Error::Invalid),
+                false => {
+                    let size = self.register_size(size);
+                    let rm = self.vector_rm()?;
+                    Operand::Register(self.vector(rm, size))
+                }
+            },
+            "H" => {
+                let size = self.register_size(size);
+                let vvvv = self.vvvv();
+                Operand::Register(self.vector(vvvv, size))
+            }
+            "L" => {
+                let size = self.register_size(size);
+                let number = self.u8()? >> 4;
+                Operand::Register(self.vector(number, size))
+            }
+
+            // Opmask registers: in ModRM.reg, ModRM.rm (or memory) and VEX.vvvv.
+            "KG" => {
+                self.has_register = true;
+                Operand::Register(Register::Mask((self.modrm()? >> 3) & 7))
+            }
+            "KE" => match self.is_memory()? {
+                true => {
+                    let size = self.size(size);
+                    Operand::Memory(self.memory(size)?)
+                }
+                false => {
+                    self.has_register = true;
+                    Operand::Register(Register::Mask(self.modrm()? & 7))
+                }
+            },
+            "KH" => {
+                self.has_register = true;
+                Operand::Register(Register::Mask(self.vvvv() & 7))
+            }
+            _ => unreachable!("x86 operand specification {spec}"),
+        })
+    }
+
+    /// The size of a vector register, which for operands sized in
+    /// fractions of the vector is never less than an xmm register.
+    fn register_size(&mut self, size: &str) -> u8 {
+        match size {
+            "x" | "h" | "qr" | "e" => self.size(size).max(16),
+            "qq" => 32,
+            _ => 16,
+        }
+    }
+
+    fn fixed_register(&mut self, name: &str) -> Result<Operand> {
+        self.has_register = true;
+
+        let register = match name {
+            "al" => Register::Gpr { number: 0, size: 1 },
+            "cl" => Register::Gpr { number: 1, size: 1 },
+            "ax" => Register::Gpr { number: 0, size: 2 },
+            // The accumulator, sized by the prefixes (but not beyond 32 bits for eAX).
+            "rax" => {
+                let size = self.operand_size();
+                Register::Gpr { number: 0, size }
+            }
+            "eax" => {
+                let size = self.size("z");
+                Register::Gpr { number: 0, size }
+            }
+            "ecx" => Register::Gpr { number: 1, size: 4 },
+            "edx" => Register::Gpr { number: 2, size: 4 },
+            "dx" => return Ok(Operand::Port),
+            "xmm0" => Register::Vector {
+                number: 0,
+                size: 16,
+            },
+            "fs" => Register::Segment(4),
+            "gs" => Register::Segment(5),
+            _ => unreachable!("x86 register {name}"),
+        };
+        Ok(Operand::Register(register))
+    }
+
+    fn immediate(&mut self, size: &str) -> Result<Operand> {
+        let (value, size) = match size {
+            "b" => (self.uint(1)?, 1),
+            "w" => (self.uint(2)?, 2),
+            "d" => (self.uint(4)?, 4),
+            // Sign-extended to the operand size.
+            "bs" => {
+                let size = self.operand_size();
+                (self.int(1)? as u64, size)
+            }
+            "z" => {
+                let size = self.operand_size();
+                (self.int(size.min(4) as usize)? as u64, size)
+            }
+            "v" => {
+                let size = self.operand_size();
+                (self.uint(size as usize)?, size)
+            }
+            _ => unreachable!("x86 immediate size {size}"),
+        };
+
+        let mask = match size {
+            8 => u64::MAX,
+            _ => (1 << (8 * size)) - 1,
+        };
+        Ok(Operand::Immediate(value & mask))
+    }
+
+    /// The active segment prefix: fs or gs, since 64-bit mode ignores the others.
+    fn segment(&mut self) -> Option<Register> {
+        match self.prefix(self.segment_prefix)? {
+            0x64 => {
+                self.use_prefix(self.segment_prefix);
+                Some(Register::Segment(4))
+            }
+            0x65 => {
+                self.use_prefix(self.segment_prefix);
+                Some(Register::Segment(5))
+            }
+            _ => None,
+        }
+    }
+
+    /// A string instruction's source or destination, which objdump writes
+    /// with their segments.
+    fn string_operand(&mut self, destination: bool, size: u8, number: u8) -> Operand {
+        let segment = match destination {
+            true => Register::Segment(0),
+            false => {
+                let segment = self.segment();
+                self.use_prefix(self.segment_prefix);
+                segment.unwrap_or(Register::Segment(3))
+            }
+        };
+        self.use_prefix(self.address_size_prefix);
+        let base_size = match self.address_size_prefix {
+            Some(_) => 4,
+            None => 8,
+        };
+        self.memory_size = Some(size);
+
+        Operand::Memory(Memory {
+            segment: Some(segment),
+            base: Some(Register::Gpr {
+                number,
+                size: base_size,
+            }),
+            index: None,
+            scale: 1,
+            displacement: 0,
+            has_displacement: false,
+            size,
+            broadcast: None,
+        })
+    }
+
+    /// Reads a memory operand from ModRM, SIB and the displacement.
+    fn memory(&mut self, size: u8) -> Result<Memory> {
+        let modrm = self.modrm()?;
+        let (md, rm) = (modrm >> 6, modrm & 7);
+
+        self.use_prefix(self.address_size_prefix);
+        let address_size = match self.address_size_prefix {
+            Some(_) => 4,
+            None => 8,
+        };
+        let segment = self.segment();
+
+        let sib = match rm {
+            4 => Some(self.u8()?),
+            _ => None,
+        };
+        let b = self.rex_bit(REX_B) as u8;
+
+        let mut base = match sib {
+            Some(sib) => sib & 7,
+            None => rm,
+        };
+        let mut has_base = true;
+        let mut rip_relative = false;
+
+        let mut displacement = 0;
+        let has_displacement = md != 0 || base == 5;
+        match md {
+            0 if base == 5 => {
+                has_base = false;
+                rip_relative = sib.is_none();
+                displacement = self.int(4)?;
+            }
+            1 => displacement = self.int(1)? * self.disp8_scale(size),
+            2 => displacement = self.int(4)?,
+            _ => {}
+        }
+        base |= b << 3;
+
+        // An index of 4 (without REX.X) means there's none; objdump shows
+        // it as riz when the SIB byte is needed anyway.
+        let mut index = None;
+        let mut scale = 1;
+        if let Some(sib) = sib {
+            let x = self.rex_bit(REX_X) as u8;
+            let number = (sib >> 3) & 7 | x << 3;
+            scale = 1 << (sib >> 6);
+
+            let vsib = self.entry.flags & tables::VSIB != 0;
+            if vsib {
+                let v2 = self.vex.is_some_and(|vex| vex.evex && vex.v2) as u8;
+                let size = self.vector_length();
+                index = Some(self.vector(number | v2 << 4, size));
+            } else if number != 4 {
+                index = Some(Register::Gpr {
+                    number,
+                    size: address_size,
+                });
+            } else if scale != 1 || (has_base && base & 7 != 4) || address_size == 4 {
+                index = Some(match address_size {
+                    4 => Register::Eiz,
+                    _ => Register::Riz,
+                });
+            }
+        }
+
+        let base = match (has_base, rip_relative, address_size) {
+            (true, _, _) => Some(Register::Gpr {
+                number: base,
+                size: address_size,
+            }),
+            (false, true, 4) => Some(Register::Eip),
+            (false, true, _) => Some(Register::Rip),
+            _ => None,
+        };
+
+        let broadcast = match self.vex {
+            Some(vex) if vex.evex && vex.b_bit && self.entry.flags & tables::BROADCAST != 0 => {
+                self.broadcast = true;
+                Some(self.vector_length() / self.element_size())
+            }
+            _ => None,
+        };
+
+        let size = match broadcast {
+            Some(_) => self.element_size(),
+            None => size,
+        };
+        self.memory_size = Some(size);
+
+        Ok(Memory {
+            segment,
+            base,
+            index,
+            scale,
+            displacement,
+            has_displacement,
+            size,
+            broadcast,
+        })
+    }
+
+    /// The size of the elements of EVEX vectors, which is what's broadcast.
+    fn element_size(&self) -> u8 {
+        match self.vex {
+            Some(vex) if vex.w => 8,
+            _ => 4,
+        }
+    }
+
+    /// How much EVEX scales 8-bit displacements by: the size of the memory
+    /// operand (or of one element, if it's broadcast).
+    fn disp8_scale(&self, size: u8) -> i64 {
+        match self.vex {
+            Some(vex) if vex.evex => match vex.b_bit && self.entry.flags & tables::BROADCAST != 0 {
+                true => self.element_size() as i64,
+                false => size.max(1) as i64,
+            },
+            _ => 1,
+        }
+    }
+
+    /// EVEX rounding control, or suppressing all exceptions, which use the
+    /// b bit with register operands.
+    fn rounding(&mut self, opcode: &Opcode) -> Option<Operand> {
+        let vex = self.vex?;
+        if !vex.evex || !vex.b_bit || opcode.modrm >> 6 != 3 {
+            return None;
+        }
+        if self.entry.flags & tables::ROUNDING != 0 {
+            Some(Operand::Rounding(
+                ["rn-sae", "rd-sae", "ru-sae", "rz-sae"][vex.l as usize],
+            ))
+        } else if self.entry.flags & tables::SAE != 0 {
+            Some(Operand::Rounding("sae"))
+        } else {
+            None
+        }
+    }
+
+    /// Takes the predicate of a comparison out of its immediate, if it has
+    /// a name, for the mnemonic (as in cmpltps).
+    fn predicate(&mut self, operands: &mut Vec<Operand>) -> Option<&'static str> {
+        const CMP: [&str; 32] = [
+            "eq", "lt", "le", "unord", "neq", "nlt", "nle", "ord", "eq_uq", "nge", "ngt", "false",
+            "neq_oq", "ge", "gt", "true", "eq_os", "lt_oq", "le_oq", "unord_s", "neq_us", "nlt_uq",
+            "nle_uq", "ord_s", "eq_us", "nge_uq", "ngt_uq", "false_os", "neq_os", "ge_oq", "gt_oq",
+            "true_us",
+        ];
+        const VPCMP: [&str; 8] = ["eq", "lt", "le", "", "neq", "nlt", "nle", ""];
+
+        if self.entry.flags & tables::PREDICATE == 0 {
+            return None;
+        }
+        let Some(Operand::Immediate(value)) = operands.last() else {
+            return None;
+        };
+
+        let name = match self.entry.mnemonic {
+            name if name.starts_with("pclmul") => match value {
+                0x00 => "lqlq",
+                0x01 => "hqlq",
+                0x10 => "lqhq",
+                0x11 => "hqhq",
+                _ => return None,
+            },
+            name if name.starts_with("pcmp") => VPCMP.get(*value as usize).copied()?,
+            _ => {
+                let count = match self.vex {
+                    Some(_) => 32,
+                    None => 8,
+                };
+                CMP[..count].get(*value as usize).copied()?
+            }
+        };
+        if name.is_empty() {
+            return None;
+        }
+        operands.pop();
+        Some(name)
+    }
+
+    /// Expands the mnemonic template for AT&T and Intel syntax.
+    fn mnemonics(
+        &mut self,
+        operands: &[Operand],
+        predicate: Option<&'static str>,
+    ) -> (String, String) {
+        let template = self.entry.mnemonic;
+        let mut att = String::new();
+        let mut intel = String::new();
+
+        if self.vex.is_some() && self.entry.flags & tables::NO_V == 0 {
+            att.push('v');
+            intel.push('v');
+        }
+
+        let memory = operands
+            .iter()
+            .any(|operand| matches!(operand, Operand::Memory(_)));
+        let suffix = |size| match size {
+            1 => "b",
+            2 => "w",
+            4 => "l",
+            8 => "q",
+            _ => "",
+        };
+
+        // Groups whose names are shared add their suffixes by a flag.
+        let suffixed;
+        let template = match self.entry.flags & tables::S {
+            0 => template,
+            _ => {
+                suffixed = format!("{template}S");
+                &suffixed
+            }
+        };
+
+        // Inside `{AT&T|Intel}`, what's written only goes to one of them.
+        let (mut to_att, mut to_intel) = (true, true);
+        for c in template.chars() {
+            let mut both = String::new();
+            let mut only_att = "";
+
+            match c {
+                '{' => to_intel = false,
+                '|' => (to_att, to_intel) = (false, true),
+                '}' => (to_att, to_intel) = (true, true),
+
+                // The size, in AT&T syntax, of a memory operand (which a
+                // register operand would otherwise give).
+                'S' => {
+                    if memory && !self.broadcast {
+                        only_att = suffix(self.memory_size.unwrap_or(0));
+                    }
+                }
+                // Likewise, but for sizes other than the default.
+                'T' => {
+                    if memory && self.operand_size == 2 {
+                        only_att = "w";
+                    }
+                }
+                // The size of the first operand (or, for crc32, the source),
+                // always, in AT&T syntax.
+                'R' => {
+                    let sized = |operand: &Operand| match operand {
+                        Operand::Register(Register::Gpr { size, .. }) => Some(*size),
+                        Operand::Memory(memory) => Some(memory.size),
+                        _ => None,
+                    };
+                    let size = match self.entry.flags & tables::CRC32 {
+                        0 => operands.iter().find_map(sized),
+                        _ => operands.iter().rev().find_map(sized),
+                    };
+                    only_att = suffix(size.unwrap_or(0));
+                }
+                // A comparison's predicate.
+                '*' => both.push_str(predicate.unwrap_or("")),
+                // 'w' with an operand size prefix, in both syntaxes.
+                'P' => {
+                    if self.prefix(self.operand_size_prefix).is_some() && self.rex & REX_W == 0 {
+                        self.use_prefix(self.operand_size_prefix);
+                        both.push('w');
+                    }
+                }
+                // 'q' with REX.W, or 'w' with an operand size prefix.
+                'Q' => {
+                    if self.w() {
+                        both.push('q');
+                    } else if self.prefix(self.operand_size_prefix).is_some() {
+                        self.use_prefix(self.operand_size_prefix);
+                        both.push('w');
+                    }
+                }
+                // The vector length of VEX conversions from memory, which
+                // their operands don't show in AT&T syntax.
+                'X' => {
+                    if memory && self.vex.is_some() && !self.broadcast {
+                        only_att = match self.vector_length() {
+                            16 => "x",
+                            32 => "y",
+                            _ => "z",
+                        };
+                    }
+                }
+                // x87 instructions that don't wait, unless fwait is before them.
+                'N' => match self.fwait_prefix {
+                    Some(_) => self.use_prefix(self.fwait_prefix),
+                    None => both.push('n'),
+                },
+                // 'l' with an address size prefix (for loop), in AT&T syntax.
+                'A' => {
+                    if self.address_size_prefix.is_some() {
+                        self.use_prefix(self.address_size_prefix);
+                        only_att = "l";
+                    }
+                }
+                c => both.push(c),
+            }
+
+            if to_att {
+                att.push_str(&both);
+                att.push_str(only_att);
+            }
+            if to_intel {
+                intel.push_str(&both);
+            }
+        }
+
+        (att, intel)
+    }
+
+    fn flow(&self, operands: &[Operand]) -> (Flow, bool) {
+        let flags = self.entry.flags;
+        let target = operands.iter().find_map(|operand| match operand {
+            Operand::Target(target) => Some(*target),
+            _ => None,
+        });
+        let indirect = flags & tables::INDIRECT != 0;
+
+        let flow = if flags & tables::CALL != 0 {
+            match target {
+                Some(target) => Flow::Call(target),
+                None => Flow::IndirectCall,
+            }
+        } else if flags & tables::JUMP != 0 {
+            match target {
+                Some(target) => Flow::Jump(target),
+                None => Flow::IndirectJump,
+            }
+        } else if flags & tables::BRANCH != 0 {
+            Flow::Branch(target.unwrap_or(0))
+        } else if flags & tables::RETURN != 0 {
+            Flow::Return
+        } else if flags & tables::STOP != 0 {
+            Flow::Stop
+        } else {
+            Flow::Next
+        };
+        (flow, indirect)
+    }
+
+    /// The branch hint given by the segment prefix, if it's cs or ds.
+    fn hint(&mut self) -> Option<Hint> {
+        if self.entry.flags & tables::HINT == 0 {
+            return None;
+        }
+        match self.prefixes[self.segment_prefix?].0 {
+            0x2e => Some(Hint::NotTaken),
+            0x3e => Some(Hint::Taken),
+            _ => None,
+        }
+    }
+
+    /// Renames the prefixes that some instructions give other meanings.
+    /// Whether F2 or F3 (`byte`) is a hint for hardware lock elision,
+    /// xacquire or xrelease: on an instruction on memory that lock is
+    /// before (or xchg, which locks anyway), or F3 on a store.
+    fn lock_elision(&self, byte: u8) -> bool {
+        let (Some(opcode), Some(modrm)) = (self.opcode, self.modrm) else {
+            return false;
+        };
+        if self.vex.is_some() || modrm >> 6 == 3 {
+            return false;
+        }
+        let reg = (modrm >> 3) & 7;
+
+        let lockable = match opcode.map {
+            Map::OneByte => match opcode.byte {
+                0x00..=0x31 => opcode.byte & 7 < 2,
+                0x80..=0x83 => reg != 7,
+                0xf6 | 0xf7 => reg == 2 || reg == 3,
+                0xfe | 0xff => reg < 2,
+                _ => false,
+            },
+            Map::TwoByte => match opcode.byte {
+                0xab | 0xb0 | 0xb1 | 0xb3 | 0xbb | 0xc0 | 0xc1 => true,
+                0xba => reg >= 5,
+                0xc7 => reg == 1,
+                _ => false,
+            },
+            _ => false,
+        };
+        let locked = self.prefixes.iter().any(|(byte, _)| *byte == 0xf0);
+        let xchg = opcode.map == Map::OneByte && matches!(opcode.byte, 0x86 | 0x87);
+        let store = opcode.map == Map::OneByte && matches!(opcode.byte, 0x88 | 0x89 | 0xc6 | 0xc7);
+
+        lockable && locked || xchg || store && byte == 0xf3
+    }
+
+    fn name_prefixes(&mut self) {
+        let flags = self.entry.flags;
+        if let Some(index) = self.rep_prefix {
+            let (byte, shown) = self.prefixes[index];
+            if shown {
+                match byte {
+                    0xf3 if flags & tables::REP != 0 => self.prefixes[index].0 = REP,
+                    0xf2 if flags & tables::BND != 0 => self.prefixes[index].0 = BND,
+                    0xf2 | 0xf3 if self.lock_elision(byte) => {
+                        self.prefixes[index].0 = if byte == 0xf2 { XACQUIRE } else { XRELEASE };
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        if flags & tables::NOTRACK != 0 && self.operand_size_prefix.is_none() {
+            if let Some(index) = self.segment_prefix {
+                if self.prefixes.iter().any(|(byte, _)| *byte == 0x3e) {
+                    self.prefixes[index] = (NOTRACK, true);
+                }
+            }
+        }
+    }
+}
+
+// Prefixes renamed by the instructions they're on, in place of their bytes.
+const REP: u8 = 0x01;
+const BND: u8 = 0x02;
+const NOTRACK: u8 = 0x03;
+const XACQUIRE: u8 = 0x04;
+const XRELEASE: u8 = 0x05;
+
+fn rex_name(rex: u8) -> String {
+    let mut name = "rex".to_owned();
+    if rex & 0xf != 0 {
+        name.push('.');
+        for (bit, letter) in [(REX_W, 'W'), (REX_R, 'R'), (REX_X, 'X'), (REX_B, 'B')] {
+            if rex & bit != 0 {
+                name.push(letter);
+            }
+        }
+    }
+    name
+}
+
+fn prefix_name(byte: u8) -> String {
+    match byte {
+        0x26 => "es",
+        0x2e => "cs",
+        0x36 => "ss",
+        0x3e => "ds",
+        0x64 => "fs",
+        0x65 => "gs",
+        0x66 => "data16",
+        0x67 => "addr32",
+        0xf0 => "lock",
+        0xf2 => "repnz",
+        0xf3 => "repz",
+        0x9b => "fwait",
+        REP => "rep",
+        BND => "bnd",
+        NOTRACK => "notrack",
+        XACQUIRE => "xacquire",
+        XRELEASE => "xrelease",
+        _ => return rex_name(byte),
+    }
+    .to_owned()
+}
@@ -0,0 +1,456 @@
+/// A decoder for x86-64 instructions: the legacy, REX, VEX and EVEX
+/// prefixes, the one-, two- and three-byte opcode maps, and ModRM and SIB
+/// addressing. Instructions are written in AT&T or Intel syntax as GNU
+/// objdump writes them, down to the prefixes it shows and its spacing, so
+/// that the two can be compared line for line.
+///
+mod decode;
+mod tables;
+
+pub use decode::decode;
+
+use crate::disasm::{Flow, Syntax};
+
+/// The longest an instruction can be.
+pub const MAX_INSTRUCTION_LEN: usize = 15;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Register {
+    /// A general-purpose register, by number (0 to 15) and size in bytes.
+    Gpr {
+        number: u8,
+        size: u8,
+    },
+    /// ah, ch, dh or bh, which take the place of spl to dil without REX.
+    HighByte(u8),
+    Rip,
+    Eip,
+    /// The "no index" index, which objdump shows when a SIB byte has a
+    /// scale but no index register.
+    Riz,
+    Eiz,
+    /// es, cs, ss, ds, fs, gs.
+    Segment(u8),
+    Control(u8),
+    Debug(u8),
+    Mmx(u8),
+    /// xmm, ymm or zmm, by size in bytes.
+    Vector {
+        number: u8,
+        size: u8,
+    },
+    /// An AVX-512 opmask register, k0 to k7.
+    Mask(u8),
+    /// The top of the x87 stack, and a register relative to it.
+    St,
+    StI(u8),
+}
+
+const GPR64: [&str; 16] = [
+    "rax", "rcx", "rdx", "rbx", "rsp", "rbp", "rsi", "rdi", "r8", "r9", "r10", "r11", "r12", "r13",
+    "r14", "r15",
+];
+const GPR32: [&str; 16] = [
+    "eax", "ecx", "edx", "ebx", "esp", "ebp", "esi", "edi", "r8d", "r9d", "r10d", "r11d", "r12d",
+    "r13d", "r14d", "r15d",
+];
+const GPR16: [&str; 16] = [
+    "ax", "cx", "dx", "bx", "sp", "bp", "si", "di", "r8w", "r9w", "r10w", "r11w", "r12w", "r13w",
+    "r14w", "r15w",
+];
+const GPR8: [&str; 16] = [
+    "al", "cl", "dl", "bl", "spl", "bpl", "sil", "dil", "r8b", "r9b", "r10b", "r11b", "r12b",
+    "r13b", "r14b", "r15b",
+];
+const HIGH_BYTES: [&str; 4] = ["ah", "ch", "dh", "bh"];
+const SEGMENTS: [&str; 8] = ["es", "cs", "ss", "ds", "fs", "gs", "?", "?"];
+
+impl Register {
+    pub fn name(&self) -> String {
+        match *self {
+            Register::Gpr { number, size } => match size {
+                1 => GPR8[number as usize & 15],
+                2 => GPR16[number as usize & 15],
+                4 => GPR32[number as usize & 15],
+                _ => GPR64[number as usize & 15],
+            }
+            .to_owned(),
+            Register::HighByte(number) => HIGH_BYTES[number as usize & 3].to_owned(),
+            Register::Rip => "rip".to_owned(),
+            Register::Eip => "eip".to_owned(),
+            Register::Riz => "riz".to_owned(),
+            Register::Eiz => "eiz".to_owned(),
+            Register::Segment(number) => SEGMENTS[number as usize & 7].to_owned(),
+            Register::Control(number) => format!("cr{number}"),
+            Register::Debug(number) => format!("db{number}"),
+            Register::Mmx(number) => format!("mm{number}"),
+            Register::Vector { number, size } => match size {
+                64 => format!("zmm{number}"),
+                32 => format!("ymm{number}"),
+                _ => format!("xmm{number}"),
+            },
+            Register::Mask(number) => format!("k{number}"),
+            Register::St => "st".to_owned(),
+            Register::StI(number) => format!("st({number})"),
+        }
+    }
+}
+
+/// A memory operand: `segment:[base + index * scale + displacement]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Memory {
+    pub segment: Option<Register>,
+    /// The base register, which is `Rip` for addresses relative to the
+    /// next instruction.
+    pub base: Option<Register>,
+    pub index: Option<Register>,
+    pub scale: u8,
+    pub displacement: i64,
+
+    /// Whether the encoding has a displacement, which is shown even if it's 0.
+    pub has_displacement: bool,
+
+    /// How many bytes are accessed, for Intel syntax's `DWORD PTR`, or 0
+    /// where objdump doesn't show it (as for lea).
+    pub size: u8,
+
+    /// For EVEX instructions that broadcast one element of memory: into how many.
+    pub broadcast: Option<u8>,
+}
+
+impl Memory {
+    fn is_rip_relative(&self) -> bool {
+        matches!(self.base, Some(Register::Rip | Register::Eip))
+    }
+
+    /// Whether it's written with brackets (or parentheses): objdump writes
+    /// a displacement alone as an absolute address.
+    fn is_bracketed(&self) -> bool {
+        self.base.is_some() && !self.is_rip_relative() || self.index.is_some()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Operand {
+    Register(Register),
+    /// An immediate, already truncated to (or sign-extended to) its operand size.
+    Immediate(u64),
+    Memory(Memory),
+    /// The destination of a relative jump or call.
+    Target(u64),
+    /// The count of a shift by one, which AT&T syntax leaves out.
+    One,
+    /// The I/O port in dx, which AT&T syntax writes as `(%dx)`.
+    Port,
+    /// EVEX rounding control, or suppressing all exceptions: `{rn-sae}`, `{sae}`.
+    Rounding(&'static str),
+}
+
+/// A branch hint, given by a cs (not taken) or ds (taken) prefix on a
+/// conditional jump.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hint {
+    Taken,
+    NotTaken,
+}
+
+impl Hint {
+    /// The name of the prefix that gives the hint.
+    fn prefix(self) -> &'static str {
+        match self {
+            Hint::Taken => "ds",
+            Hint::NotTaken => "cs",
+        }
+    }
+
+    /// What AT&T syntax writes after the mnemonic, in place of the prefix.
+    fn suffix(self) -> &'static str {
+        match self {
+            Hint::Taken => ",pt",
+            Hint::NotTaken => ",pn",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Instruction {
+    pub address: u64,
+    pub len: usize,
+
+    /// The prefixes written before the mnemonic, such as "lock", "rep",
+    /// or "data16" for an operand size prefix the instruction doesn't use.
+    pub prefixes: Vec<String>,
+
+    /// The mnemonic in AT&T syntax, with its size suffix, and in Intel syntax.
+    pub mnemonic: String,
+    pub intel_mnemonic: String,
+
+    /// In Intel order: the destination first.
+    pub operands: Vec<Operand>,
+
+    /// A branch hint, written ",pt" or ",pn" after the mnemonic in AT&T
+    /// syntax, and as its prefix in Intel syntax.
+    pub hint: Option<Hint>,
+
+    /// Whether the target is in an operand (a register or memory), which
+    /// AT&T syntax writes with a `*`.
+    pub indirect: bool,
+
+    /// The EVEX opmask register applied to the destination, and whether
+    /// masked elements are zeroed rather than merged.
+    pub mask: Option<u8>,
+    pub zeroing: bool,
+
+    pub flow: Flow,
+}
+
+impl Instruction {
+    /// Whether this is an instruction that couldn't be decoded.
+    pub fn is_bad(&self) -> bool {
+        self.mnemonic == "(bad)"
+    }
+
+    /// The address that a RIP-relative memory operand refers to.
+    pub fn rip_target(&self) -> Option<u64> {
+        self.operands.iter().find_map(|operand| match operand {
+            Operand::Memory(memory) if memory.is_rip_relative() => {
+                Some((self.address + self.len as u64).wrapping_add(memory.displacement as u64))
+            }
+            _ => None,
+        })
+    }
+
+    /// Writes the instruction as objdump does. `symbolize` names an
+    /// address, as in `main+0x1c`, for branch targets and the comment after
+    /// RIP-relative operands.
+    pub fn format(&self, syntax: Syntax, symbolize: &dyn Fn(u64) -> Option<String>) -> String {
+        let mut text = String::new();
+
+        // In AT&T syntax, the hint's prefix (the last segment prefix) is
+        // written as a suffix instead.
+        let hint_prefix = match syntax {
+            Syntax::Att => self
+                .hint
+                .and_then(|hint| self.prefixes.iter().rposition(|p| p == hint.prefix())),
+            Syntax::Intel => None,
+        };
+
+        for (i, prefix) in self.prefixes.iter().enumerate() {
+            if Some(i) == hint_prefix {
+                continue;
+            }
+            text.push_str(prefix);
+            text.push(' ');
+        }
+
+        match syntax {
+            Syntax::Att => {
+                text.push_str(&self.mnemonic);
+                if let Some(hint) = self.hint {
+                    text.push_str(hint.suffix());
+                }
+            }
+            Syntax::Intel => text.push_str(&self.intel_mnemonic),
+        }
+
+        if self.operands.is_empty() {
+            return text;
+        }
+
+        // The mnemonic (with its prefixes) is padded to 6 characters.
+        while text.len() < 6 {
+            text.push(' ');
+        }
+        text.push(' ');
+
+        let mut operands: Vec<String> = self
+            .operands
+            .iter()
+            .map(|operand| format_operand(operand, syntax, symbolize))
+            .filter(|operand| !operand.is_empty())
+            .collect();
+
+        if let Some(mask) = self.mask {
+            let register = match syntax {
+                Syntax::Att => format!("{{%k{mask}}}"),
+                Syntax::Intel => format!("{{k{mask}}}"),
+            };
+            operands[0].push_str(&register);
+        }
+        if self.zeroing {
+            operands[0].push_str("{z}");
+        }
+
+        // objdump keeps enter's two immediates in Intel's order.
+        if syntax == Syntax::Att && !self.intel_mnemonic.starts_with("enter") {
+            operands.reverse();
+        }
+        if syntax == Syntax::Att && self.indirect {
+            operands[0].insert(0, '*');
+        }
+        text.push_str(&operands.join(","));
+
+        if let Some(target) = self.rip_target() {
+            text.push_str("        # ");
+            text.push_str(&format_address(target, symbolize));
+        }
+
+        text
+    }
+}
+
+/// Writes an address as objdump does: `1040 <puts>` if it has a name, and
+/// otherwise `0x1040`.
+pub fn format_address(address: u64, symbolize: &dyn Fn(u64) -> Option<String>) -> String {
+    match symbolize(address) {
+        Some(name) => format!("{address:x} <{name}>"),
+        None => format!("{address:#x}"),
+    }
+}
+
+/// A displacement, signed.
+fn displacement(value: i64) -> String {
+    match value < 0 {
+        true => format!("-{:#x}", value.unsigned_abs()),
+        false => format!("{value:#x}"),
+    }
+}
+
+fn register(register: Register, syntax: Syntax) -> String {
+    match syntax {
+        Syntax::Att => format!("%{}", register.name()),
+        Syntax::Intel => register.name(),
+    }
+}
+
+fn format_operand(
+    operand: &Operand,
+    syntax: Syntax,
+    symbolize: &dyn Fn(u64) -> Option<String>,
+) -> String {
+    match (operand, syntax) {
+        (Operand::Register(r), _) => register(*r, syntax),
+
+        (Operand::Immediate(value), Syntax::Att) => format!("${value:#x}"),
+        (Operand::Immediate(value), Syntax::Intel) => format!("{value:#x}"),
+
+        (Operand::Memory(memory), Syntax::Att) => format_att_memory(memory),
+        (Operand::Memory(memory), Syntax::Intel) => format_intel_memory(memory),
+
+        (Operand::Target(address), _) => format_address(*address, symbolize),
+
+        (Operand::One, Syntax::Att) => String::new(),
+        (Operand::One, Syntax::Intel) => "1".to_owned(),
+
+        (Operand::Port, Syntax::Att) => "(%dx)".to_owned(),
+        (Operand::Port, Syntax::Intel) => "dx".to_owned(),
+
+        (Operand::Rounding(rounding), _) => format!("{{{rounding}}}"),
+    }
+}
+
+fn format_att_memory(memory: &Memory) -> String {
+    let mut text = String::new();
+
+    if let Some(segment) = memory.segment {
+        text.push_str(&register(segment, Syntax::Att));
+        text.push(':');
+    }
+
+    let rip_relative = memory.is_rip_relative();
+    if memory.has_displacement {
+        match memory.is_bracketed() || rip_relative {
+            true => text.push_str(&displacement(memory.displacement)),
+            false => text.push_str(&format!("{:#x}", memory.displacement as u64)),
+        }
+    }
+
+    if rip_relative || memory.is_bracketed() {
+        text.push('(');
+        if let Some(base) = memory.base {
+            text.push_str(&register(base, Syntax::Att));
+        }
+        if let Some(index) = memory.index {
+            text.push(',');
+            text.push_str(&register(index, Syntax::Att));
+            text.push_str(&format!(",{}", memory.scale));
+        }
+        text.push(')');
+    }
+
+    if let Some(count) = memory.broadcast {
+        text.push_str(&format!("{{1to{count}}}"));
+    }
+
+    text
+}
+
+/// The name Intel syntax gives to the size of a memory operand.
+fn size_name(size: u8) -> Option<&'static str> {
+    Some(match size {
+        1 => "BYTE",
+        2 => "WORD",
+        4 => "DWORD",
+        6 => "FWORD",
+        8 => "QWORD",
+        10 => "TBYTE",
+        16 => "XMMWORD",
+        32 => "YMMWORD",
+        64 => "ZMMWORD",
+        _ => return None,
+    })
+}
+
+fn format_intel_memory(memory: &Memory) -> String {
+    let mut text = String::new();
+
+    // A broadcast element is sized by itself.
+    if let Some(name) = size_name(memory.size) {
+        text.push_str(name);
+        text.push_str(" PTR ");
+    }
+
+    if let Some(segment) = memory.segment {
+        text.push_str(&segment.name());
+        text.push(':');
+    }
+
+    let rip_relative = memory.is_rip_relative();
+    let bracketed = memory.is_bracketed();
+
+    if bracketed || rip_relative {
+        text.push('[');
+        if let Some(base) = memory.base {
+            text.push_str(&base.name());
+        }
+        if let Some(index) = memory.index {
+            if memory.base.is_some() && !rip_relative {
+                text.push('+');
+            }
+            text.push_str(&index.name());
+            text.push_str(&format!("*{}", memory.scale));
+        }
+        if memory.has_displacement {
+            if !bracketed {
+                text.push_str(&format!("+{:#x}", memory.displacement as u64));
+            } else if memory.displacement >= 0 {
+                text.push('+');
+                text.push_str(&displacement(memory.displacement));
+            } else {
+                text.push_str(&displacement(memory.displacement));
+            }
+        }
+        text.push(']');
+    } else if memory.has_displacement {
+        if memory.segment.is_none() {
+            text.push_str("ds:");
+        }
+        text.push_str(&format!("{:#x}", memory.displacement as u64));
+    }
+
+    if let Some(count) = memory.broadcast {
+        text.push_str(&format!("{{1to{count}}}"));
+    }
+
+    text
+}
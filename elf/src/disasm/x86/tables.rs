@@ -0,0 +1,1538 @@
+/// The opcode maps: what each opcode is called, and how to read its
+/// operands.
+///
+/// Operands are given in Intel order, as in the Intel manuals: an
+/// addressing method in capitals and a size in lowercase, such as `Ev` for
+/// a register or memory operand of the operand size, or `Ib` for an 8-bit
+/// immediate. `%` names a fixed register. SSE instructions are given in
+/// their VEX form, with `H` for the extra source in VEX.vvvv, which the
+/// legacy form doesn't have, and without the "v" that VEX adds to their
+/// names.
+///
+/// Mnemonics can have `{AT&T|Intel}` alternatives, and capitals that the
+/// decoder expands from the operands and prefixes (see `Decoder::mnemonics`).
+///
+#[derive(Debug, Clone, Copy)]
+pub(super) struct Entry {
+    pub mnemonic: &'static str,
+    pub operands: &'static str,
+    pub flags: u32,
+}
+
+impl Entry {
+    pub const BAD: Entry = Entry {
+        mnemonic: "",
+        operands: "",
+        flags: 0,
+    };
+
+    pub fn is_bad(&self) -> bool {
+        self.mnemonic.is_empty()
+    }
+
+    const fn with(self, flags: u32) -> Entry {
+        Entry {
+            flags: self.flags | flags,
+            ..self
+        }
+    }
+}
+
+const fn e(mnemonic: &'static str, operands: &'static str) -> Entry {
+    Entry {
+        mnemonic,
+        operands,
+        flags: 0,
+    }
+}
+
+const fn f(mnemonic: &'static str, operands: &'static str, flags: u32) -> Entry {
+    Entry {
+        mnemonic,
+        operands,
+        flags,
+    }
+}
+
+const BAD: Entry = Entry::BAD;
+
+// Entry flags.
+
+/// The operand size is 64 bits unless there's an operand size prefix.
+pub const DEF64: u32 = 1 << 0;
+/// The instruction is selected by its 66, F2 or F3 prefix.
+pub const PREFIXED: u32 = 1 << 1;
+/// Its name depends on REX.W (or VEX.W).
+pub const USES_W: u32 = 1 << 2;
+/// Its name depends on the operand size.
+pub const SIZED: u32 = 1 << 3;
+/// F3 is written "rep" (rather than "repz").
+pub const REP: u32 = 1 << 4;
+/// F2 is written "bnd", for MPX.
+pub const BND: u32 = 1 << 5;
+/// 3E is written "notrack", for CET.
+pub const NOTRACK: u32 = 1 << 6;
+/// 2E and 3E are branch hints.
+pub const HINT: u32 = 1 << 7;
+/// AT&T syntax writes `*` before the operand.
+pub const INDIRECT: u32 = 1 << 8;
+pub const JUMP: u32 = 1 << 9;
+pub const BRANCH: u32 = 1 << 10;
+pub const CALL: u32 = 1 << 11;
+pub const RETURN: u32 = 1 << 12;
+pub const STOP: u32 = 1 << 13;
+/// A VEX instruction whose name doesn't start with "v".
+pub const NO_V: u32 = 1 << 14;
+/// Only with VEX (or EVEX), or only without.
+pub const VEX_ONLY: u32 = 1 << 15;
+pub const LEGACY: u32 = 1 << 16;
+/// Its index is a vector of registers (VSIB), for gathers and scatters.
+pub const VSIB: u32 = 1 << 17;
+/// With EVEX, a memory operand can be broadcast, and register operands
+/// can have rounding control, or suppress all exceptions.
+pub const BROADCAST: u32 = 1 << 18;
+pub const ROUNDING: u32 = 1 << 19;
+pub const SAE: u32 = 1 << 20;
+/// A predicate in the immediate makes the name: `*` in the mnemonic.
+pub const PREDICATE: u32 = 1 << 21;
+/// crc32 with a 16-bit operand, where 66 isn't the mandatory prefix.
+pub const CRC32: u32 = 1 << 29;
+/// The instruction uses the address size prefix.
+pub const ADDRESS: u32 = 1 << 30;
+/// Adds the AT&T size suffix of a memory operand, as `S` in the mnemonic
+/// does, for groups whose names are shared.
+pub const S: u32 = 1 << 31;
+/// MMX, which VEX can't encode.
+const MMX: u32 = LEGACY;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Map {
+    OneByte,
+    TwoByte,
+    ThreeByte38,
+    ThreeByte3a,
+}
+
+/// What the tables need to know to find an instruction.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct Opcode {
+    pub map: Map,
+    pub byte: u8,
+    /// The prefix that can select SSE instructions: 0, 0x66, 0xf3 or 0xf2.
+    pub prefix: u8,
+    /// The ModRM byte after the opcode, if the instruction has one.
+    pub modrm: u8,
+    pub rex: u8,
+    pub w: bool,
+    pub data16: bool,
+    pub addr32: bool,
+    pub l: u8,
+    pub vex: bool,
+    pub evex: bool,
+}
+
+impl Opcode {
+    /// Whether a ModRM byte follows the opcode, even for instructions
+    /// (such as `endbr64` and `fxch`) that it only selects.
+    pub fn has_modrm(&self) -> bool {
+        match self.map {
+            Map::OneByte => {
+                matches!(
+                    self.byte,
+                    0x00..=0x3f if self.byte & 7 < 4
+                ) || matches!(
+                    self.byte,
+                    0x62 | 0x63 | 0x69 | 0x6b | 0x80..=0x8f | 0xc0 | 0xc1 | 0xc4..=0xc7
+                        | 0xd0..=0xd3 | 0xd8..=0xdf | 0xf6 | 0xf7 | 0xfe | 0xff
+                )
+            }
+            Map::TwoByte => !matches!(
+                self.byte,
+                0x04..=0x0b | 0x0e | 0x30..=0x37 | 0x77 | 0x80..=0x8f | 0xa0..=0xa2
+                    | 0xa8..=0xaa | 0xc8..=0xcf
+            ),
+            Map::ThreeByte38 | Map::ThreeByte3a => true,
+        }
+    }
+
+    fn reg(&self) -> usize {
+        ((self.modrm >> 3) & 7) as usize
+    }
+
+    fn rm(&self) -> usize {
+        (self.modrm & 7) as usize
+    }
+
+    fn is_register(&self) -> bool {
+        self.modrm >> 6 == 3
+    }
+
+    /// Picks the instruction for the prefix, from those for none, 66, F3
+    /// and F2.
+    fn pick(&self, entries: [Entry; 4]) -> Entry {
+        let (index, flags) = match self.prefix {
+            0x66 => (1, PREFIXED),
+            0xf3 => (2, PREFIXED),
+            0xf2 => (3, PREFIXED),
+            _ => (0, 0),
+        };
+        match entries[index].is_bad() {
+            true => BAD,
+            false => entries[index].with(flags),
+        }
+    }
+}
+
+/// Finds the instruction for an opcode, if it's defined.
+pub(super) fn lookup(opcode: &Opcode) -> Option<Entry> {
+    let entry = match opcode.map {
+        Map::OneByte => one_byte(opcode),
+        Map::TwoByte => two_byte(opcode),
+        Map::ThreeByte38 => three_byte_38(opcode),
+        Map::ThreeByte3a => three_byte_3a(opcode),
+    };
+    let entry = match opcode.evex {
+        true => evex(opcode, entry),
+        false => entry,
+    };
+
+    if entry.is_bad()
+        || opcode.vex && entry.flags & LEGACY != 0
+        || !opcode.vex && entry.flags & VEX_ONLY != 0
+    {
+        return None;
+    }
+    Some(entry)
+}
+
+const JCC: [&str; 16] = [
+    "jo", "jno", "jb", "jae", "je", "jne", "jbe", "ja", "js", "jns", "jp", "jnp", "jl", "jge",
+    "jle", "jg",
+];
+const CMOVCC: [&str; 16] = [
+    "cmovo", "cmovno", "cmovb", "cmovae", "cmove", "cmovne", "cmovbe", "cmova", "cmovs", "cmovns",
+    "cmovp", "cmovnp", "cmovl", "cmovge", "cmovle", "cmovg",
+];
+const SETCC: [&str; 16] = [
+    "seto", "setno", "setb", "setae", "sete", "setne", "setbe", "seta", "sets", "setns", "setp",
+    "setnp", "setl", "setge", "setle", "setg",
+];
+
+const ARITHMETIC: [&str; 8] = ["add", "or", "adc", "sbb", "and", "sub", "xor", "cmp"];
+const SHIFTS: [&str; 8] = ["rol", "ror", "rcl", "rcr", "shl", "shr", "shl", "sar"];
+
+fn one_byte(op: &Opcode) -> Entry {
+    let reg = op.reg();
+
+    match op.byte {
+        0x00..=0x3f if op.byte & 7 < 6 => {
+            let name = ARITHMETIC[(op.byte >> 3) as usize];
+            let operands = ["Eb,Gb", "Ev,Gv", "Gb,Eb", "Gv,Ev", "%al,Ib", "%rax,Iz"];
+            e(name, operands[(op.byte & 7) as usize])
+        }
+
+        0x50..=0x57 => f("push", "Zv", DEF64),
+        0x58..=0x5f => f("pop", "Zv", DEF64),
+        0x63 if op.w => e("{movslq|movsxd}", "Gv,Ed"),
+        0x63 => e("movsxd", "Gv,Ed"),
+        0x68 => f("pushP", "Iz", DEF64),
+        0x69 => e("imul", "Gv,Ev,Iz"),
+        0x6a => f("pushP", "Ibs", DEF64),
+        0x6b => e("imul", "Gv,Ev,Ibs"),
+        0x6c => f("ins{R|}", "Yb,%dx", REP),
+        0x6d => f("ins{R|}", "Yz,%dx", REP),
+        0x6e => f("outs{R|}", "%dx,Xb", REP),
+        0x6f => f("outs{R|}", "%dx,Xz", REP),
+        0x70..=0x7f => f(JCC[(op.byte & 0xf) as usize], "Jb", BRANCH | BND | HINT),
+
+        0x80 => e(ARITHMETIC[reg], "Eb,Ib").with(S),
+        0x81 => e(ARITHMETIC[reg], "Ev,Iz").with(S),
+        0x83 => e(ARITHMETIC[reg], "Ev,Ibs").with(S),
+        0x84 => e("test", "Eb,Gb"),
+        0x85 => e("test", "Ev,Gv"),
+        0x86 => e("xchg", "Eb,Gb"),
+        0x87 => e("xchg", "Ev,Gv"),
+        0x88 => e("mov", "Eb,Gb"),
+        0x89 => e("mov", "Ev,Gv"),
+        0x8a => e("mov", "Gb,Eb"),
+        0x8b => e("mov", "Gv,Ev"),
+        0x8c if op.is_register() => e("mov", "Ev,Sw"),
+        0x8c => e("mov", "Mw,Sw"),
+        0x8d => e("lea", "Gv,M"),
+        0x8e if op.is_register() => e("mov", "Sw,Ev"),
+        0x8e => e("mov", "Sw,Mw"),
+        0x8f if reg == 0 => f("popT", "Ev", DEF64),
+
+        // nop is xchg with eax, unless it's with r8, or 16 bits.
+        0x90 if op.rex & 1 != 0 || op.data16 && op.prefix != 0xf3 => e("xchg", "Zv,%rax"),
+        0x90 if op.prefix == 0xf3 => f("pause", "", PREFIXED),
+        0x90 => e("nop", ""),
+        0x91..=0x97 => e("xchg", "Zv,%rax"),
+        0x98 => f(
+            match (op.w, op.data16) {
+                (true, _) => "{cltq|cdqe}",
+                (false, true) => "{cbtw|cbw}",
+                (false, false) => "{cwtl|cwde}",
+            },
+            "",
+            SIZED,
+        ),
+        0x99 => f(
+            match (op.w, op.data16) {
+                (true, _) => "{cqto|cqo}",
+                (false, true) => "{cwtd|cwd}",
+                (false, false) => "{cltd|cdq}",
+            },
+            "",
+            SIZED,
+        ),
+        0x9b => e("fwait", ""),
+        0x9c => f("pushfP", "", DEF64),
+        0x9d => f("popfP", "", DEF64),
+        0x9e => e("sahf", ""),
+        0x9f => e("lahf", ""),
+
+        // With an address size prefix, the offset is 32 bits (but objdump
+        // still shows the prefix).
+        0xa0..=0xa3 if op.addr32 => {
+            let operands = ["%al,Ob", "%rax,Ov", "Ob,%al", "Ov,%rax"];
+            e("mov", operands[(op.byte - 0xa0) as usize])
+        }
+        0xa0 => e("movabs", "%al,Ob"),
+        0xa1 => e("movabs", "%rax,Ov"),
+        0xa2 => e("movabs", "Ob,%al"),
+        0xa3 => e("movabs", "Ov,%rax"),
+        0xa4 => f("movs{R|}", "Yb,Xb", REP),
+        0xa5 => f("movs{R|}", "Yv,Xv", REP),
+        0xa6 => e("cmps{R|}", "Xb,Yb"),
+        0xa7 => e("cmps{R|}", "Xv,Yv"),
+        0xa8 => e("test", "%al,Ib"),
+        0xa9 => e("test", "%rax,Iz"),
+        0xaa => f("stos", "Yb,%al", REP),
+        0xab => f("stos", "Yv,%rax", REP),
+        0xac => f("lods", "%al,Xb", REP),
+        0xad => f("lods", "%rax,Xv", REP),
+        0xae => e("scas", "%al,Yb"),
+        0xaf => e("scas", "%rax,Yv"),
+
+        0xb0..=0xb7 => e("mov", "Zb,Ib"),
+        0xb8..=0xbf if op.w => e("movabs", "Zv,Iv"),
+        0xb8..=0xbf => e("mov", "Zv,Iv"),
+
+        0xc0 => e(SHIFTS[reg], "Eb,Ib").with(S),
+        0xc1 => e(SHIFTS[reg], "Ev,Ib").with(S),
+        0xc2 => f("retP", "Iw", RETURN | BND),
+        0xc3 => f("retP", "", RETURN | BND),
+        0xc6 if op.modrm == 0xf8 => e("xabort", "Ib"),
+        0xc6 if reg == 0 => e("movS", "Eb,Ib"),
+        0xc7 if op.modrm == 0xf8 => f("xbegin", "Jz", BRANCH),
+        0xc7 if reg == 0 => e("movS", "Ev,Iz"),
+        0xc8 => f("enterP", "Iw,Ib", DEF64),
+        0xc9 => f("leaveP", "", DEF64),
+        0xca => f("{lret|retf}Q", "Iw", RETURN),
+        0xcb => f("{lret|retf}Q", "", RETURN),
+        0xcc => f("int3", "", STOP),
+        0xcd => e("int", "Ib"),
+        0xcf => f("iretQ", "", RETURN),
+
+        0xd0 => e(SHIFTS[reg], "Eb,1").with(S),
+        0xd1 => e(SHIFTS[reg], "Ev,1").with(S),
+        0xd2 => e(SHIFTS[reg], "Eb,%cl").with(S),
+        0xd3 => e(SHIFTS[reg], "Ev,%cl").with(S),
+        0xd7 => e("xlat", "XL"),
+        0xd8..=0xdf => x87(op),
+
+        0xe0 => f("loopneA", "Jb", BRANCH | HINT),
+        0xe1 => f("loopeA", "Jb", BRANCH | HINT),
+        0xe2 => f("loopA", "Jb", BRANCH | HINT),
+        0xe3 if op.addr32 => f("jecxz", "Jb", BRANCH | HINT | ADDRESS),
+        0xe3 => f("jrcxz", "Jb", BRANCH | HINT),
+        0xe4 => e("in", "%al,Ib"),
+        0xe5 => e("in", "%eax,Ib"),
+        0xe6 => e("out", "Ib,%al"),
+        0xe7 => e("out", "Ib,%eax"),
+        0xe8 => f("callP", "Jz", CALL | BND),
+        0xe9 => f("jmpP", "Jz", JUMP | BND),
+        0xeb => f("jmp", "Jb", JUMP | BND),
+        0xec => e("in", "%al,%dx"),
+        0xed => e("in", "%eax,%dx"),
+        0xee => e("out", "%dx,%al"),
+        0xef => e("out", "%dx,%eax"),
+
+        0xf1 => e("int1", ""),
+        0xf4 => f("hlt", "", STOP),
+        0xf5 => e("cmc", ""),
+        0xf6 => e(
+            ["test", "test", "not", "neg", "mul", "imul", "div", "idiv"][reg],
+            ["Eb,Ib", "Eb,Ib", "Eb", "Eb", "Eb", "Eb", "Eb", "Eb"][reg],
+        )
+        .with(S),
+        0xf7 => e(
+            ["test", "test", "not", "neg", "mul", "imul", "div", "idiv"][reg],
+            ["Ev,Iz", "Ev,Iz", "Ev", "Ev", "Ev", "Ev", "Ev", "Ev"][reg],
+        )
+        .with(S),
+        0xf8 => e("clc", ""),
+        0xf9 => e("stc", ""),
+        0xfa => e("cli", ""),
+        0xfb => e("sti", ""),
+        0xfc => e("cld", ""),
+        0xfd => e("std", ""),
+        0xfe if reg < 2 => e(["inc", "dec"][reg], "Eb").with(S),
+        0xff => match reg {
+            0 => e("inc", "Ev").with(S),
+            1 => e("dec", "Ev").with(S),
+            2 => f("callT", "Ev", DEF64 | CALL | INDIRECT | BND | NOTRACK),
+            3 => f("{lcall|call}", "Mp", CALL | INDIRECT),
+            4 => f("jmpT", "Ev", DEF64 | JUMP | INDIRECT | BND | NOTRACK),
+            5 => f("{ljmp|jmp}", "Mp", JUMP | INDIRECT),
+            6 => f("pushT", "Ev", DEF64),
+            _ => BAD,
+        },
+
+        _ => BAD,
+    }
+}
+
+/// The x87 instructions, D8 to DF, by ModRM.reg: for memory operands,
+/// with their sizes, and for registers.
+fn x87(op: &Opcode) -> Entry {
+    const MEMORY: [[(&str, &str); 8]; 8] = [
+        [
+            ("fadd{s|}", "Md"),
+            ("fmul{s|}", "Md"),
+            ("fcom{s|}", "Md"),
+            ("fcomp{s|}", "Md"),
+            ("fsub{s|}", "Md"),
+            ("fsubr{s|}", "Md"),
+            ("fdiv{s|}", "Md"),
+            ("fdivr{s|}", "Md"),
+        ],
+        [
+            ("fld{s|}", "Md"),
+            ("(bad)", "M"),
+            ("fst{s|}", "Md"),
+            ("fstp{s|}", "Md"),
+            ("fldenv", "M"),
+            ("fldcw", "Mw"),
+            ("fNstenv", "M"),
+            ("fNstcw", "Mw"),
+        ],
+        [
+            ("fiadd{l|}", "Md"),
+            ("fimul{l|}", "Md"),
+            ("ficom{l|}", "Md"),
+            ("ficomp{l|}", "Md"),
+            ("fisub{l|}", "Md"),
+            ("fisubr{l|}", "Md"),
+            ("fidiv{l|}", "Md"),
+            ("fidivr{l|}", "Md"),
+        ],
+        [
+            ("fild{l|}", "Md"),
+            ("fisttp{l|}", "Md"),
+            ("fist{l|}", "Md"),
+            ("fistp{l|}", "Md"),
+            ("(bad)", "M"),
+            ("fld{t|}", "Mt"),
+            ("(bad)", "M"),
+            ("fstp{t|}", "Mt"),
+        ],
+        [
+            ("fadd{l|}", "Mq"),
+            ("fmul{l|}", "Mq"),
+            ("fcom{l|}", "Mq"),
+            ("fcomp{l|}", "Mq"),
+            ("fsub{l|}", "Mq"),
+            ("fsubr{l|}", "Mq"),
+            ("fdiv{l|}", "Mq"),
+            ("fdivr{l|}", "Mq"),
+        ],
+        [
+            ("fld{l|}", "Mq"),
+            ("fisttp{ll|}", "Mq"),
+            ("fst{l|}", "Mq"),
+            ("fstp{l|}", "Mq"),
+            ("frstor", "M"),
+            ("(bad)", "M"),
+            ("fNsave", "M"),
+            ("fNstsw", "Mw"),
+        ],
+        [
+            ("fiadd{s|}", "Mw"),
+            ("fimul{s|}", "Mw"),
+            ("ficom{s|}", "Mw"),
+            ("ficomp{s|}", "Mw"),
+            ("fisub{s|}", "Mw"),
+            ("fisubr{s|}", "Mw"),
+            ("fidiv{s|}", "Mw"),
+            ("fidivr{s|}", "Mw"),
+        ],
+        [
+            ("fild{s|}", "Mw"),
+            ("fisttp{s|}", "Mw"),
+            ("fist{s|}", "Mw"),
+            ("fistp{s|}", "Mw"),
+            ("fbld", "Mt"),
+            ("fild{ll|}", "Mq"),
+            ("fbstp", "Mt"),
+            ("fistp{ll|}", "Mq"),
+        ],
+    ];
+
+    let index = (op.byte - 0xd8) as usize;
+    let (reg, rm) = (op.reg(), op.rm());
+    if !op.is_register() {
+        let (mnemonic, operands) = MEMORY[index][reg];
+        return e(mnemonic, operands);
+    }
+
+    let bad = e("(bad)", "");
+    let by_rm = |names: [&'static str; 8]| match names[rm] {
+        "" => bad,
+        name => e(name, ""),
+    };
+
+    match (index, reg) {
+        (0, _) => e(
+            [
+                "fadd", "fmul", "fcom", "fcomp", "fsub", "fsubr", "fdiv", "fdivr",
+            ][reg],
+            [
+                "ST,STi", "ST,STi", "STi", "STi", "ST,STi", "ST,STi", "ST,STi", "ST,STi",
+            ][reg],
+        ),
+        (1, 0) => e("fld", "STi"),
+        (1, 1) => e("fxch", "STi"),
+        (1, 2) if rm == 0 => e("fnop", ""),
+        (1, 4) => by_rm(["fchs", "fabs", "", "", "ftst", "fxam", "", ""]),
+        (1, 5) => by_rm([
+            "fld1", "fldl2t", "fldl2e", "fldpi", "fldlg2", "fldln2", "fldz", "",
+        ]),
+        (1, 6) => by_rm([
+            "f2xm1", "fyl2x", "fptan", "fpatan", "fxtract", "fprem1", "fdecstp", "fincstp",
+        ]),
+        (1, 7) => by_rm([
+            "fprem", "fyl2xp1", "fsqrt", "fsincos", "frndint", "fscale", "fsin", "fcos",
+        ]),
+        (2, 0..=3) => e(["fcmovb", "fcmove", "fcmovbe", "fcmovu"][reg], "ST,STi"),
+        (2, 5) if rm == 1 => e("fucompp", ""),
+        (3, 0..=3) => e(["fcmovnb", "fcmovne", "fcmovnbe", "fcmovnu"][reg], "ST,STi"),
+        (3, 4) => by_rm([
+            "fNeni(8087 only)",
+            "fNdisi(8087 only)",
+            "fNclex",
+            "fNinit",
+            "fNsetpm(287 only)",
+            "frstpm(287 only)",
+            "",
+            "",
+        ]),
+        (3, 5) => e("fucomi", "ST,STi"),
+        (3, 6) => e("fcomi", "ST,STi"),
+        // AT&T syntax swaps the names of the subtractions and divisions
+        // whose destination isn't st (a long-standing bug in UnixWare's
+        // assembler, which GNU as kept).
+        (4, 0 | 1 | 4..=7) => e(
+            [
+                "fadd",
+                "fmul",
+                "",
+                "",
+                "{fsub|fsubr}",
+                "{fsubr|fsub}",
+                "{fdiv|fdivr}",
+                "{fdivr|fdiv}",
+            ][reg],
+            "STi,ST",
+        ),
+        (5, 0) => e("ffree", "STi"),
+        (5, 2..=5) => e(["fst", "fstp", "fucom", "fucomp"][reg - 2], "STi"),
+        (6, 0 | 1 | 4..=7) => e(
+            [
+                "faddp",
+                "fmulp",
+                "",
+                "",
+                "{fsubp|fsubrp}",
+                "{fsubrp|fsubp}",
+                "{fdivp|fdivrp}",
+                "{fdivrp|fdivp}",
+            ][reg],
+            "STi,ST",
+        ),
+        (6, 3) if rm == 1 => e("fcompp", ""),
+        (7, 0) => e("ffreep", "STi"),
+        (7, 4) if rm == 0 => e("fNstsw", "%ax"),
+        (7, 5) => e("fucomip", "ST,STi"),
+        (7, 6) => e("fcomip", "ST,STi"),
+        _ => bad,
+    }
+}
+
+/// The 0F map: system and general-purpose instructions, and SSE.
+fn two_byte(op: &Opcode) -> Entry {
+    let reg = op.reg();
+    let register = op.is_register();
+
+    if op.vex && !op.evex && matches!(op.byte, 0x41..=0x4b | 0x90..=0x93 | 0x98 | 0x99) {
+        return mask(op);
+    }
+
+    let entry = match op.byte {
+        0x00 => match reg {
+            0 | 1 if register => e(["sldt", "str"][reg], "Ev"),
+            0 | 1 => e(["sldt", "str"][reg], "Mw"),
+            2..=5 => e(["lldt", "ltr", "verr", "verw"][reg - 2], "Ew"),
+            _ => BAD,
+        },
+        0x01 if !register => match reg {
+            0..=3 => e(["sgdt", "sidt", "lgdt", "lidt"][reg], "M"),
+            4 => e("smsw", "Mw"),
+            6 => e("lmsw", "Mw"),
+            7 => e("invlpg", "Mb"),
+            _ => BAD,
+        },
+        0x01 => match (reg, op.modrm) {
+            (4, _) => e("smsw", "Rv"),
+            (6, _) => e("lmsw", "Ew"),
+            (_, 0xc1) => e("vmcall", ""),
+            (_, 0xc2) => e("vmlaunch", ""),
+            (_, 0xc3) => e("vmresume", ""),
+            (_, 0xc4) => e("vmxoff", ""),
+            (_, 0xc8) => e("{monitor %rax,%ecx,%edx|monitor}", ""),
+            (_, 0xc9) => e("{mwait  %eax,%ecx|mwait}", ""),
+            (_, 0xca) => e("clac", ""),
+            (_, 0xcb) => e("stac", ""),
+            (_, 0xcf) => e("encls", ""),
+            (_, 0xd0) => e("xgetbv", ""),
+            (_, 0xd1) => e("xsetbv", ""),
+            (_, 0xd4) => e("vmfunc", ""),
+            (_, 0xd5) => e("xend", ""),
+            (_, 0xd6) => e("xtest", ""),
+            (_, 0xd7) => e("enclu", ""),
+            (_, 0xe8) if op.prefix == 0 => e("serialize", ""),
+            (_, 0xee) => e("rdpkru", ""),
+            (_, 0xef) => e("wrpkru", ""),
+            (_, 0xf8) => e("swapgs", ""),
+            (_, 0xf9) => e("rdtscp", ""),
+            _ => BAD,
+        },
+        0x02 => e("lar", "Gv,Ew"),
+        0x03 => e("lsl", "Gv,Ew"),
+        0x05 => e("syscall", ""),
+        0x06 => e("clts", ""),
+        0x07 if op.w => f("sysretq", "", USES_W | STOP),
+        0x07 => f("sysret{l|d}", "", STOP),
+        0x08 => e("invd", ""),
+        0x09 => e("wbinvd", ""),
+        0x0b => f("ud2", "", STOP),
+        0x0e => e("femms", ""),
+        0x0d if !register => e(
+            ["prefetch", "prefetchw", "prefetchwt1", "prefetch"][reg.min(3)],
+            "Mb",
+        ),
+        0x18 if !register && reg < 4 => e(
+            ["prefetchnta", "prefetcht0", "prefetcht1", "prefetcht2"][reg],
+            "Mb",
+        ),
+        0x1e if op.prefix == 0xf3 && op.modrm == 0xfa => f("endbr64", "", PREFIXED),
+        0x1e if op.prefix == 0xf3 && op.modrm == 0xfb => f("endbr32", "", PREFIXED),
+        0x1e if op.prefix == 0xf3 && register && reg == 1 => {
+            f(if op.w { "rdsspq" } else { "rdsspd" }, "Ry", PREFIXED)
+        }
+        0x18..=0x1f => e("nopS", "Ev"),
+        0x20 => e("mov", "Rq,Cq"),
+        0x21 => e("mov", "Rq,Dq"),
+        0x22 => e("mov", "Cq,Rq"),
+        0x23 => e("mov", "Dq,Rq"),
+        0x30 => e("wrmsr", ""),
+        0x31 => e("rdtsc", ""),
+        0x32 => e("rdmsr", ""),
+        0x33 => e("rdpmc", ""),
+        0x34 => e("sysenter", ""),
+        0x35 => f("sysexit", "", STOP),
+        0x37 => e("getsec", ""),
+        0x40..=0x4f => e(CMOVCC[(op.byte & 0xf) as usize], "Gv,Ev"),
+        0x80..=0x8f => f(JCC[(op.byte & 0xf) as usize], "Jz", BRANCH | BND | HINT),
+        0x90..=0x9f => e(SETCC[(op.byte & 0xf) as usize], "Eb"),
+        0xa0 => f("pushP", "%fs", DEF64),
+        0xa1 => f("popP", "%fs", DEF64),
+        0xa2 => e("cpuid", ""),
+        0xa3 => e("bt", "Ev,Gv"),
+        0xa4 => e("shld", "Ev,Gv,Ib"),
+        0xa5 => e("shld", "Ev,Gv,%cl"),
+        0xa8 => f("pushP", "%gs", DEF64),
+        0xa9 => f("popP", "%gs", DEF64),
+        0xaa => e("rsm", ""),
+        0xab => e("bts", "Ev,Gv"),
+        0xac => e("shrd", "Ev,Gv,Ib"),
+        0xad => e("shrd", "Ev,Gv,%cl"),
+        0xae => group15(op),
+        0xaf => e("imul", "Gv,Ev"),
+        0xb0 => e("cmpxchg", "Eb,Gb"),
+        0xb1 => e("cmpxchg", "Ev,Gv"),
+        0xb2 => e("lss", "Gv,Mp"),
+        0xb3 => e("btr", "Ev,Gv"),
+        0xb4 => e("lfs", "Gv,Mp"),
+        0xb5 => e("lgs", "Gv,Mp"),
+        0xb6 => e("movz{b|x}R", "Gv,Eb"),
+        0xb7 => e("movz{w|x}R", "Gv,Ew"),
+        0xb8 if op.prefix == 0xf3 => f("popcnt", "Gv,Ev", PREFIXED),
+        0xb9 => f("ud1", "Gv,Ev", STOP),
+        0xba if reg >= 4 => e(["bt", "bts", "btr", "btc"][reg - 4], "Ev,Ib").with(S),
+        0xbb => e("btc", "Ev,Gv"),
+        0xbc if op.prefix == 0xf3 => f("tzcnt", "Gv,Ev", PREFIXED),
+        0xbc => e("bsf", "Gv,Ev"),
+        0xbd if op.prefix == 0xf3 => f("lzcnt", "Gv,Ev", PREFIXED),
+        0xbd => e("bsr", "Gv,Ev"),
+        0xbe => e("movs{b|x}R", "Gv,Eb"),
+        0xbf => e("movs{w|x}R", "Gv,Ew"),
+        0xc0 => e("xadd", "Eb,Gb"),
+        0xc1 => e("xadd", "Ev,Gv"),
+        0xc3 if op.prefix == 0 => e("movnti", "My,Gy"),
+        0xc7 => group9(op),
+        0xc8..=0xcf => e("bswap", "Zy"),
+        0xff => f("ud0", "Gv,Ev", STOP),
+        _ => return sse(op),
+    };
+
+    // VEX only encodes SSE instructions.
+    match op.vex {
+        true => BAD,
+        false => entry,
+    }
+}
+
+/// The AVX-512 instructions on opmask registers, which VEX encodes. Their
+/// names end in the size: without a prefix, `w` (or `q` with VEX.W), and with
+/// 66, `b` (or `d`).
+fn mask(op: &Opcode) -> Entry {
+    let size = match (op.prefix, op.w) {
+        (0, false) => 0,
+        (0, true) => 1,
+        (0x66, false) => 2,
+        (0x66, true) => 3,
+        _ => 4,
+    };
+    let sized = |names: [&'static str; 4], operands| match size {
+        4 => BAD,
+        _ => f(names[size], operands, NO_V | USES_W),
+    };
+    let register = op.is_register();
+
+    match op.byte {
+        // Those with two sources take VEX.L=1, and the others VEX.L=0.
+        0x44 | 0x90..=0x99 if op.l != 0 => BAD,
+        0x41..=0x4b if op.byte != 0x44 && (op.l != 1 || !register) => BAD,
+
+        0x41 => sized(["kandw", "kandq", "kandb", "kandd"], "KG,KH,KE"),
+        0x42 => sized(["kandnw", "kandnq", "kandnb", "kandnd"], "KG,KH,KE"),
+        0x45 => sized(["korw", "korq", "korb", "kord"], "KG,KH,KE"),
+        0x46 => sized(["kxnorw", "kxnorq", "kxnorb", "kxnord"], "KG,KH,KE"),
+        0x47 => sized(["kxorw", "kxorq", "kxorb", "kxord"], "KG,KH,KE"),
+        0x4a => sized(["kaddw", "kaddq", "kaddb", "kaddd"], "KG,KH,KE"),
+        0x4b => sized(["kunpckwd", "kunpckdq", "kunpckbw", ""], "KG,KH,KE"),
+
+        0x44 if register => sized(["knotw", "knotq", "knotb", "knotd"], "KG,KE"),
+        0x90 => match size {
+            0 | 2 => sized(["kmovw", "", "kmovb", ""], "KG,KEw"),
+            _ => sized(["", "kmovq", "", "kmovd"], "KG,KEq"),
+        },
+        0x91 if !register => match size {
+            0 | 2 => sized(["kmovw", "", "kmovb", ""], "Mw,KG"),
+            _ => sized(["", "kmovq", "", "kmovd"], "Mq,KG"),
+        },
+        // To and from general-purpose registers, F2 is for d and q.
+        0x92 | 0x93 if register => {
+            let name = match (op.prefix, op.w) {
+                (0, false) => "kmovw",
+                (0x66, false) => "kmovb",
+                (0xf2, false) => "kmovd",
+                (0xf2, true) => "kmovq",
+                _ => return BAD,
+            };
+            match op.byte {
+                0x92 => f(name, "KG,Ry", NO_V | USES_W),
+                _ => f(name, "Gy,KE", NO_V | USES_W),
+            }
+        }
+        0x98 if register => sized(["kortestw", "kortestq", "kortestb", "kortestd"], "KG,KE"),
+        0x99 if register => sized(["ktestw", "ktestq", "ktestb", "ktestd"], "KG,KE"),
+        _ => BAD,
+    }
+}
+
+/// 0F AE: fences, saving and restoring state, and (with F3) the fs and gs bases.
+fn group15(op: &Opcode) -> Entry {
+    let reg = op.reg();
+    let w = |name: &'static str, name64: &'static str| match op.w {
+        true => f(name64, "M", USES_W),
+        false => e(name, "M"),
+    };
+
+    match (op.is_register(), op.prefix) {
+        (false, 0) => match reg {
+            0 => w("fxsave", "fxsave64"),
+            1 => w("fxrstor", "fxrstor64"),
+            2 => e("ldmxcsr", "Md"),
+            3 => e("stmxcsr", "Md"),
+            4 => w("xsave", "xsave64"),
+            5 => w("xrstor", "xrstor64"),
+            6 => w("xsaveopt", "xsaveopt64"),
+            _ => e("clflush", "Mb"),
+        },
+        (false, 0x66) if reg >= 6 => f(["clwb", "clflushopt"][reg - 6], "Mb", PREFIXED),
+        (true, 0) if reg >= 5 => e(["lfence", "mfence", "sfence"][reg - 5], ""),
+        (true, 0xf3) if reg < 4 => f(
+            ["rdfsbase", "rdgsbase", "wrfsbase", "wrgsbase"][reg],
+            "Ry",
+            PREFIXED,
+        ),
+        _ => BAD,
+    }
+}
+
+/// 0F C7: compare and exchange, VMX pointers, and random numbers.
+fn group9(op: &Opcode) -> Entry {
+    let reg = op.reg();
+    let w = |name: &'static str, name64: &'static str| match op.w {
+        true => f(name64, "M", USES_W),
+        false => e(name, "M"),
+    };
+
+    match (op.is_register(), reg) {
+        (false, 1) if op.w => f("cmpxchg16b", "Mo", USES_W),
+        (false, 1) => e("cmpxchg8b", "Mq"),
+        (false, 3) => w("xrstors", "xrstors64"),
+        (false, 4) => w("xsavec", "xsavec64"),
+        (false, 5) => w("xsaves", "xsaves64"),
+        (false, 6) => op.pick([
+            e("vmptrld", "Mq"),
+            e("vmclear", "Mq"),
+            e("vmxon", "Mq"),
+            BAD,
+        ]),
+        (false, 7) if op.prefix == 0 => e("vmptrst", "Mq"),
+        (true, 6) => e("rdrand", "Rv"),
+        (true, 7) if op.prefix == 0xf3 => f("rdpid", "Rq", PREFIXED),
+        (true, 7) => e("rdseed", "Rv"),
+        _ => BAD,
+    }
+}
+
+/// The names of the integer SSE instructions of the 0F map, from 60 to 6B
+/// and D0 to FF, which have MMX forms without the 66 prefix.
+fn integer_sse(byte: u8) -> Option<&'static str> {
+    Some(match byte {
+        0x60 => "punpcklbw",
+        0x61 => "punpcklwd",
+        0x62 => "punpckldq",
+        0x63 => "packsswb",
+        0x64 => "pcmpgtb",
+        0x65 => "pcmpgtw",
+        0x66 => "pcmpgtd",
+        0x67 => "packuswb",
+        0x68 => "punpckhbw",
+        0x69 => "punpckhwd",
+        0x6a => "punpckhdq",
+        0x6b => "packssdw",
+        0x74 => "pcmpeqb",
+        0x75 => "pcmpeqw",
+        0x76 => "pcmpeqd",
+        0xd4 => "paddq",
+        0xd5 => "pmullw",
+        0xd8 => "psubusb",
+        0xd9 => "psubusw",
+        0xda => "pminub",
+        0xdb => "pand",
+        0xdc => "paddusb",
+        0xdd => "paddusw",
+        0xde => "pmaxub",
+        0xdf => "pandn",
+        0xe0 => "pavgb",
+        0xe3 => "pavgw",
+        0xe4 => "pmulhuw",
+        0xe5 => "pmulhw",
+        0xe8 => "psubsb",
+        0xe9 => "psubsw",
+        0xea => "pminsw",
+        0xeb => "por",
+        0xec => "paddsb",
+        0xed => "paddsw",
+        0xee => "pmaxsw",
+        0xef => "pxor",
+        0xf4 => "pmuludq",
+        0xf5 => "pmaddwd",
+        0xf6 => "psadbw",
+        0xf8 => "psubb",
+        0xf9 => "psubw",
+        0xfa => "psubd",
+        0xfb => "psubq",
+        0xfc => "paddb",
+        0xfd => "paddw",
+        0xfe => "paddd",
+        _ => return None,
+    })
+}
+
+/// Shifts by the count in a register: D1 to D3, E1, E2 and F1 to F3.
+fn shift_sse(byte: u8) -> Option<&'static str> {
+    Some(match byte {
+        0xd1 => "psrlw",
+        0xd2 => "psrld",
+        0xd3 => "psrlq",
+        0xe1 => "psraw",
+        0xe2 => "psrad",
+        0xf1 => "psllw",
+        0xf2 => "pslld",
+        0xf3 => "psllq",
+        _ => return None,
+    })
+}
+
+/// Arithmetic on packed and scalar floating point, 51 and 58 to 5F: the
+/// names for no prefix, 66, F3 and F2.
+fn float_sse(byte: u8) -> Option<[&'static str; 4]> {
+    Some(match byte {
+        0x51 => ["sqrtps", "sqrtpd", "sqrtss", "sqrtsd"],
+        0x58 => ["addps", "addpd", "addss", "addsd"],
+        0x59 => ["mulps", "mulpd", "mulss", "mulsd"],
+        0x5c => ["subps", "subpd", "subss", "subsd"],
+        0x5d => ["minps", "minpd", "minss", "minsd"],
+        0x5e => ["divps", "divpd", "divss", "divsd"],
+        0x5f => ["maxps", "maxpd", "maxss", "maxsd"],
+        _ => return None,
+    })
+}
+
+/// The SSE (and MMX) instructions of the 0F map.
+fn sse(op: &Opcode) -> Entry {
+    let register = op.is_register();
+
+    if let Some(name) = integer_sse(op.byte) {
+        return op.pick([
+            f(name, "Pq,Qq", MMX),
+            f(name, "Vx,Hx,Wx", BROADCAST),
+            BAD,
+            BAD,
+        ]);
+    }
+    if let Some(name) = shift_sse(op.byte) {
+        return op.pick([f(name, "Pq,Qq", MMX), e(name, "Vx,Hx,Wo"), BAD, BAD]);
+    }
+    if let Some(names) = float_sse(op.byte) {
+        let packed = match op.byte {
+            0x51 => "Vx,Wx",
+            _ => "Vx,Hx,Wx",
+        };
+        let flags = BROADCAST
+            | match op.byte {
+                0x5d | 0x5f => SAE,
+                _ => ROUNDING,
+            };
+        return op.pick([
+            f(names[0], packed, flags),
+            f(names[1], packed, flags),
+            f(names[2], "Vo,Ho,Wd", flags),
+            f(names[3], "Vo,Ho,Wq", flags),
+        ]);
+    }
+
+    match op.byte {
+        0x10 => op.pick([
+            e("movups", "Vx,Wx"),
+            e("movupd", "Vx,Wx"),
+            e("movss", if register { "Vo,Ho,Uo" } else { "Vo,Md" }),
+            e("movsd", if register { "Vo,Ho,Uo" } else { "Vo,Mq" }),
+        ]),
+        0x11 => op.pick([
+            e("movups", "Wx,Vx"),
+            e("movupd", "Wx,Vx"),
+            e("movss", if register { "Uo,Ho,Vo" } else { "Md,Vo" }),
+            e("movsd", if register { "Uo,Ho,Vo" } else { "Mq,Vo" }),
+        ]),
+        0x12 => op.pick([
+            match register {
+                true => e("movhlps", "Vo,Ho,Uo"),
+                false => e("movlps", "Vo,Ho,Mq"),
+            },
+            e("movlpd", "Vo,Ho,Mq"),
+            e("movsldup", "Vx,Wx"),
+            e("movddup", if op.l == 0 { "Vo,Wq" } else { "Vx,Wx" }),
+        ]),
+        0x13 => op.pick([e("movlps", "Mq,Vo"), e("movlpd", "Mq,Vo"), BAD, BAD]),
+        0x14 => op.pick([
+            e("unpcklps", "Vx,Hx,Wx"),
+            e("unpcklpd", "Vx,Hx,Wx"),
+            BAD,
+            BAD,
+        ]),
+        0x15 => op.pick([
+            e("unpckhps", "Vx,Hx,Wx"),
+            e("unpckhpd", "Vx,Hx,Wx"),
+            BAD,
+            BAD,
+        ]),
+        0x16 => op.pick([
+            match register {
+                true => e("movlhps", "Vo,Ho,Uo"),
+                false => e("movhps", "Vo,Ho,Mq"),
+            },
+            e("movhpd", "Vo,Ho,Mq"),
+            e("movshdup", "Vx,Wx"),
+            BAD,
+        ]),
+        0x17 => op.pick([e("movhps", "Mq,Vo"), e("movhpd", "Mq,Vo"), BAD, BAD]),
+        0x28 => op.pick([e("movaps", "Vx,Wx"), e("movapd", "Vx,Wx"), BAD, BAD]),
+        0x29 => op.pick([e("movaps", "Wx,Vx"), e("movapd", "Wx,Vx"), BAD, BAD]),
+        0x2a => op.pick([
+            f("cvtpi2ps", "Vo,Qq", MMX),
+            f("cvtpi2pd", "Vo,Qq", MMX),
+            e("cvtsi2ssS", "Vo,Ho,Ey"),
+            e("cvtsi2sdS", "Vo,Ho,Ey"),
+        ]),
+        0x2b => op.pick([e("movntps", "Mx,Vx"), e("movntpd", "Mx,Vx"), BAD, BAD]),
+        0x2c => op.pick([
+            f("cvttps2pi", "Pq,Wq", MMX),
+            f("cvttpd2pi", "Pq,Wo", MMX),
+            e("cvttss2si", "Gy,Wd"),
+            e("cvttsd2si", "Gy,Wq"),
+        ]),
+        0x2d => op.pick([
+            f("cvtps2pi", "Pq,Wq", MMX),
+            f("cvtpd2pi", "Pq,Wo", MMX),
+            e("cvtss2si", "Gy,Wd"),
+            e("cvtsd2si", "Gy,Wq"),
+        ]),
+        0x2e => op.pick([e("ucomiss", "Vo,Wd"), e("ucomisd", "Vo,Wq"), BAD, BAD]),
+        0x2f => op.pick([e("comiss", "Vo,Wd"), e("comisd", "Vo,Wq"), BAD, BAD]),
+        0x50 => op.pick([e("movmskps", "Gd,Ux"), e("movmskpd", "Gd,Ux"), BAD, BAD]),
+        0x52 => op.pick([e("rsqrtps", "Vx,Wx"), BAD, e("rsqrtss", "Vo,Ho,Wd"), BAD]),
+        0x53 => op.pick([e("rcpps", "Vx,Wx"), BAD, e("rcpss", "Vo,Ho,Wd"), BAD]),
+        0x54 => op.pick([e("andps", "Vx,Hx,Wx"), e("andpd", "Vx,Hx,Wx"), BAD, BAD]),
+        0x55 => op.pick([e("andnps", "Vx,Hx,Wx"), e("andnpd", "Vx,Hx,Wx"), BAD, BAD]),
+        0x56 => op.pick([e("orps", "Vx,Hx,Wx"), e("orpd", "Vx,Hx,Wx"), BAD, BAD]),
+        0x57 => op.pick([e("xorps", "Vx,Hx,Wx"), e("xorpd", "Vx,Hx,Wx"), BAD, BAD]),
+        0x5a => op.pick([
+            e("cvtps2pd", "Vx,Wh"),
+            e("cvtpd2psX", "Vo,Wx"),
+            e("cvtss2sd", "Vo,Ho,Wd"),
+            e("cvtsd2ss", "Vo,Ho,Wq"),
+        ]),
+        0x5b => op.pick([
+            e("cvtdq2ps", "Vx,Wx"),
+            e("cvtps2dq", "Vx,Wx"),
+            e("cvttps2dq", "Vx,Wx"),
+            BAD,
+        ]),
+        0x6c => op.pick([BAD, e("punpcklqdq", "Vx,Hx,Wx"), BAD, BAD]),
+        0x6d => op.pick([BAD, e("punpckhqdq", "Vx,Hx,Wx"), BAD, BAD]),
+        0x6e => op.pick([
+            f(if op.w { "movq" } else { "movd" }, "Pq,Ey", MMX),
+            e(if op.w { "movq" } else { "movd" }, "Vo,Ey"),
+            BAD,
+            BAD,
+        ]),
+        0x6f => op.pick([
+            f("movq", "Pq,Qq", MMX),
+            e("movdqa", "Vx,Wx"),
+            e("movdqu", "Vx,Wx"),
+            BAD,
+        ]),
+        0x70 => op.pick([
+            f("pshufw", "Pq,Qq,Ib", MMX),
+            e("pshufd", "Vx,Wx,Ib"),
+            e("pshufhw", "Vx,Wx,Ib"),
+            e("pshuflw", "Vx,Wx,Ib"),
+        ]),
+        0x71..=0x73 => {
+            let names: [&str; 8] = match op.byte {
+                0x71 => ["", "", "psrlw", "", "psraw", "", "psllw", ""],
+                0x72 => ["", "", "psrld", "", "psrad", "", "pslld", ""],
+                _ => ["", "", "psrlq", "psrldq", "", "", "psllq", "pslldq"],
+            };
+            let name = names[op.reg()];
+            if name.is_empty() || !register {
+                return BAD;
+            }
+            let mmx = match name {
+                "psrldq" | "pslldq" => BAD,
+                _ => f(name, "Nq,Ib", MMX),
+            };
+            op.pick([mmx, e(name, "Hx,Ux,Ib"), BAD, BAD])
+        }
+        0x77 if op.vex && op.prefix == 0 => match op.l {
+            0 => e("zeroupper", ""),
+            _ => e("zeroall", ""),
+        },
+        0x77 if op.prefix == 0 => e("emms", ""),
+        0x78 if op.prefix == 0 => f("vmread", "Eq,Gq", LEGACY),
+        0x79 if op.prefix == 0 => f("vmwrite", "Gq,Eq", LEGACY),
+        0x7c => op.pick([BAD, e("haddpd", "Vx,Hx,Wx"), BAD, e("haddps", "Vx,Hx,Wx")]),
+        0x7d => op.pick([BAD, e("hsubpd", "Vx,Hx,Wx"), BAD, e("hsubps", "Vx,Hx,Wx")]),
+        0x7e => op.pick([
+            f(if op.w { "movq" } else { "movd" }, "Ey,Pq", MMX),
+            e(if op.w { "movq" } else { "movd" }, "Ey,Vo"),
+            e("movq", "Vo,Wq"),
+            BAD,
+        ]),
+        0x7f => op.pick([
+            f("movq", "Qq,Pq", MMX),
+            e("movdqa", "Wx,Vx"),
+            e("movdqu", "Wx,Vx"),
+            BAD,
+        ]),
+        0xc2 => op.pick([
+            f("cmp*ps", "Vx,Hx,Wx,Ib", PREDICATE),
+            f("cmp*pd", "Vx,Hx,Wx,Ib", PREDICATE),
+            f("cmp*ss", "Vo,Ho,Wd,Ib", PREDICATE),
+            f("cmp*sd", "Vo,Ho,Wq,Ib", PREDICATE),
+        ]),
+        0xc4 => {
+            let source = if register { "Rd" } else { "Mw" };
+            op.pick([
+                f(
+                    "pinsrw",
+                    if register { "Pq,Rd,Ib" } else { "Pq,Mw,Ib" },
+                    MMX,
+                ),
+                e(
+                    "pinsrw",
+                    if source == "Rd" {
+                        "Vo,Ho,Rd,Ib"
+                    } else {
+                        "Vo,Ho,Mw,Ib"
+                    },
+                ),
+                BAD,
+                BAD,
+            ])
+        }
+        0xc5 if register => op.pick([
+            f("pextrw", "Gd,Nq,Ib", MMX),
+            e("pextrw", "Gd,Uo,Ib"),
+            BAD,
+            BAD,
+        ]),
+        0xc6 => op.pick([
+            e("shufps", "Vx,Hx,Wx,Ib"),
+            e("shufpd", "Vx,Hx,Wx,Ib"),
+            BAD,
+            BAD,
+        ]),
+        0xd0 => op.pick([
+            BAD,
+            e("addsubpd", "Vx,Hx,Wx"),
+            BAD,
+            e("addsubps", "Vx,Hx,Wx"),
+        ]),
+        0xd6 => op.pick([
+            BAD,
+            e("movq", "Wq,Vo"),
+            f("movq2dq", "Vo,Nq", LEGACY),
+            f("movdq2q", "Pq,Uo", LEGACY),
+        ]),
+        0xd7 if register => op.pick([
+            f("pmovmskb", "Gd,Nq", MMX),
+            e("pmovmskb", "Gd,Ux"),
+            BAD,
+            BAD,
+        ]),
+        0xe6 => op.pick([
+            BAD,
+            e("cvttpd2dqX", "Vo,Wx"),
+            e("cvtdq2pd", "Vx,Wh"),
+            e("cvtpd2dqX", "Vo,Wx"),
+        ]),
+        0xe7 => op.pick([f("movntq", "Mq,Pq", MMX), e("movntdq", "Mx,Vx"), BAD, BAD]),
+        0xf0 => op.pick([BAD, BAD, BAD, e("lddqu", "Vx,Mx")]),
+        0xf7 if register => op.pick([
+            f("maskmovq", "Pq,Nq", MMX),
+            e("maskmovdqu", "Vo,Uo"),
+            BAD,
+            BAD,
+        ]),
+        _ => BAD,
+    }
+}
+
+/// The 0F 38 map: SSSE3, SSE4, AES and SHA, AVX2, FMA and BMI.
+fn three_byte_38(op: &Opcode) -> Entry {
+    let ssse3 = [
+        "pshufb",
+        "phaddw",
+        "phaddd",
+        "phaddsw",
+        "pmaddubsw",
+        "phsubw",
+        "phsubd",
+        "phsubsw",
+        "psignb",
+        "psignw",
+        "psignd",
+        "pmulhrsw",
+    ];
+
+    let vex = |name, operands| f(name, operands, VEX_ONLY);
+    let w = |name_w0, name_w1| match op.w {
+        true => name_w1,
+        false => name_w0,
+    };
+
+    match op.byte {
+        0x00..=0x0b => {
+            let name = ssse3[op.byte as usize];
+            op.pick([f(name, "Pq,Qq", MMX), e(name, "Vx,Hx,Wx"), BAD, BAD])
+        }
+        0x0c => op.pick([BAD, vex("permilps", "Vx,Hx,Wx"), BAD, BAD]),
+        0x0d => op.pick([BAD, vex("permilpd", "Vx,Hx,Wx"), BAD, BAD]),
+        0x0e => op.pick([BAD, vex("testps", "Vx,Wx"), BAD, BAD]),
+        0x0f => op.pick([BAD, vex("testpd", "Vx,Wx"), BAD, BAD]),
+        0x10 => op.pick([BAD, f("pblendvb", "Vo,Wo,%xmm0", LEGACY), BAD, BAD]),
+        0x13 => op.pick([BAD, vex("cvtph2ps", "Vx,Wh"), BAD, BAD]),
+        0x14 => op.pick([BAD, f("blendvps", "Vo,Wo,%xmm0", LEGACY), BAD, BAD]),
+        0x15 => op.pick([BAD, f("blendvpd", "Vo,Wo,%xmm0", LEGACY), BAD, BAD]),
+        0x16 => op.pick([BAD, vex("permps", "Vx,Hx,Wx"), BAD, BAD]),
+        0x17 => op.pick([BAD, e("ptest", "Vx,Wx"), BAD, BAD]),
+        0x18 => op.pick([BAD, vex("broadcastss", "Vx,Wd"), BAD, BAD]),
+        0x19 => op.pick([BAD, vex("broadcastsd", "Vx,Wq"), BAD, BAD]),
+        0x1a => op.pick([BAD, vex("broadcastf128", "Vx,Mo"), BAD, BAD]),
+        0x1c..=0x1e => {
+            let name = ["pabsb", "pabsw", "pabsd"][(op.byte - 0x1c) as usize];
+            op.pick([f(name, "Pq,Qq", MMX), e(name, "Vx,Wx"), BAD, BAD])
+        }
+        0x20..=0x25 | 0x30..=0x35 => {
+            let (name, operands) = [
+                ("pmovsxbw", "Vx,Wh"),
+                ("pmovsxbd", "Vx,Wqr"),
+                ("pmovsxbq", "Vx,We"),
+                ("pmovsxwd", "Vx,Wh"),
+                ("pmovsxwq", "Vx,Wqr"),
+                ("pmovsxdq", "Vx,Wh"),
+                ("pmovzxbw", "Vx,Wh"),
+                ("pmovzxbd", "Vx,Wqr"),
+                ("pmovzxbq", "Vx,We"),
+                ("pmovzxwd", "Vx,Wh"),
+                ("pmovzxwq", "Vx,Wqr"),
+                ("pmovzxdq", "Vx,Wh"),
+            ][(op.byte >> 4 & 1) as usize * 6 + (op.byte & 0xf) as usize];
+            op.pick([BAD, e(name, operands), BAD, BAD])
+        }
+        0x28 => op.pick([BAD, e("pmuldq", "Vx,Hx,Wx"), BAD, BAD]),
+        0x29 => op.pick([BAD, e("pcmpeqq", "Vx,Hx,Wx"), BAD, BAD]),
+        0x2a => op.pick([BAD, e("movntdqa", "Vx,Mx"), BAD, BAD]),
+        0x2b => op.pick([BAD, e("packusdw", "Vx,Hx,Wx"), BAD, BAD]),
+        0x2c => op.pick([BAD, vex("maskmovps", "Vx,Hx,Mx"), BAD, BAD]),
+        0x2d => op.pick([BAD, vex("maskmovpd", "Vx,Hx,Mx"), BAD, BAD]),
+        0x2e => op.pick([BAD, vex("maskmovps", "Mx,Hx,Vx"), BAD, BAD]),
+        0x2f => op.pick([BAD, vex("maskmovpd", "Mx,Hx,Vx"), BAD, BAD]),
+        0x36 => op.pick([BAD, vex("permd", "Vx,Hx,Wx"), BAD, BAD]),
+        0x37 => op.pick([BAD, e("pcmpgtq", "Vx,Hx,Wx"), BAD, BAD]),
+        0x38..=0x40 => {
+            let name = [
+                "pminsb", "pminsd", "pminuw", "pminud", "pmaxsb", "pmaxsd", "pmaxuw", "pmaxud",
+                "pmulld",
+            ][(op.byte - 0x38) as usize];
+            op.pick([BAD, e(name, "Vx,Hx,Wx"), BAD, BAD])
+        }
+        0x41 => op.pick([BAD, e("phminposuw", "Vo,Wo"), BAD, BAD]),
+        0x45 => op.pick([BAD, vex(w("psrlvd", "psrlvq"), "Vx,Hx,Wx"), BAD, BAD]),
+        0x46 => op.pick([BAD, vex("psravd", "Vx,Hx,Wx"), BAD, BAD]),
+        0x47 => op.pick([BAD, vex(w("psllvd", "psllvq"), "Vx,Hx,Wx"), BAD, BAD]),
+        0x58 => op.pick([BAD, vex("pbroadcastd", "Vx,Wd"), BAD, BAD]),
+        0x59 => op.pick([BAD, vex("pbroadcastq", "Vx,Wq"), BAD, BAD]),
+        0x5a => op.pick([BAD, vex("broadcasti128", "Vx,Mo"), BAD, BAD]),
+        0x78 => op.pick([BAD, vex("pbroadcastb", "Vx,Wb"), BAD, BAD]),
+        0x79 => op.pick([BAD, vex("pbroadcastw", "Vx,Ww"), BAD, BAD]),
+        0x8c => op.pick([BAD, vex(w("pmaskmovd", "pmaskmovq"), "Vx,Hx,Mx"), BAD, BAD]),
+        0x8e => op.pick([BAD, vex(w("pmaskmovd", "pmaskmovq"), "Mx,Hx,Vx"), BAD, BAD]),
+        0x90..=0x93 if !op.is_register() && !op.evex => {
+            let name = match (op.byte, op.w) {
+                (0x90, false) => "pgatherdd",
+                (0x90, true) => "pgatherdq",
+                (0x91, false) => "pgatherqd",
+                (0x91, true) => "pgatherqq",
+                (0x92, false) => "gatherdps",
+                (0x92, true) => "gatherdpd",
+                (0x93, false) => "gatherqps",
+                _ => "gatherqpd",
+            };
+            // The elements are as wide as the wider of the indices and the
+            // data, so 32-bit data with 64-bit indices only fills half a vector.
+            let operands = match (op.byte & 1, op.w) {
+                (1, false) => "Vo,M,Ho",
+                _ => "Vx,M,Hx",
+            };
+            op.pick([BAD, f(name, operands, VEX_ONLY | VSIB), BAD, BAD])
+        }
+        0x96..=0x9f | 0xa6..=0xaf | 0xb6..=0xbf => fma(op),
+        0xc8..=0xcd if op.prefix == 0 => {
+            let (name, operands) = [
+                ("sha1nexte", "Vo,Wo"),
+                ("sha1msg1", "Vo,Wo"),
+                ("sha1msg2", "Vo,Wo"),
+                ("sha256rnds2", "Vo,Wo,%xmm0"),
+                ("sha256msg1", "Vo,Wo"),
+                ("sha256msg2", "Vo,Wo"),
+            ][(op.byte - 0xc8) as usize];
+            f(name, operands, LEGACY)
+        }
+        0xcf => op.pick([BAD, e("gf2p8mulb", "Vx,Hx,Wx"), BAD, BAD]),
+        0xdb => op.pick([BAD, e("aesimc", "Vo,Wo"), BAD, BAD]),
+        0xdc..=0xdf => {
+            let name = ["aesenc", "aesenclast", "aesdec", "aesdeclast"][(op.byte - 0xdc) as usize];
+            op.pick([BAD, e(name, "Vx,Hx,Wx"), BAD, BAD])
+        }
+        0xf0 | 0xf1 if op.vex => BAD,
+        0xf0 if op.prefix == 0xf2 => f("crc32{b|}", "Gy,Eb", PREFIXED),
+        0xf1 if op.prefix == 0xf2 => f("crc32R", "Gy,Ev", PREFIXED | CRC32),
+        0xf0 if op.prefix != 0xf3 => e("movbe", "Gv,Mv"),
+        0xf1 if op.prefix != 0xf3 => e("movbe", "Mv,Gv"),
+        0xf2 if op.prefix == 0 => vex("andn", "Gy,By,Ey").with(NO_V),
+        0xf3 if op.prefix == 0 && op.vex => match op.reg() {
+            1 => vex("blsr", "By,Ey").with(NO_V),
+            2 => vex("blsmsk", "By,Ey").with(NO_V),
+            3 => vex("blsi", "By,Ey").with(NO_V),
+            _ => BAD,
+        },
+        0xf5 => op
+            .pick([
+                vex("bzhi", "Gy,Ey,By"),
+                BAD,
+                vex("pext", "Gy,By,Ey"),
+                vex("pdep", "Gy,By,Ey"),
+            ])
+            .with(NO_V),
+        0xf6 if op.vex => op.pick([BAD, BAD, BAD, vex("mulx", "Gy,By,Ey")]).with(NO_V),
+        0xf6 => op.pick([BAD, e("adcx", "Gy,Ey"), e("adox", "Gy,Ey"), BAD]),
+        0xf7 => op
+            .pick([
+                vex("bextr", "Gy,Ey,By"),
+                vex("shlx", "Gy,Ey,By"),
+                vex("sarx", "Gy,Ey,By"),
+                vex("shrx", "Gy,Ey,By"),
+            ])
+            .with(NO_V),
+        _ => BAD,
+    }
+}
+
+/// The fused multiply-adds: 96 to 9F, A6 to AF and B6 to BF of the 0F 38
+/// map, whose W bit picks single or double precision.
+fn fma(op: &Opcode) -> Entry {
+    let names = match op.byte {
+        0x96 => ["fmaddsub132ps", "fmaddsub132pd"],
+        0x97 => ["fmsubadd132ps", "fmsubadd132pd"],
+        0x98 => ["fmadd132ps", "fmadd132pd"],
+        0x99 => ["fmadd132ss", "fmadd132sd"],
+        0x9a => ["fmsub132ps", "fmsub132pd"],
+        0x9b => ["fmsub132ss", "fmsub132sd"],
+        0x9c => ["fnmadd132ps", "fnmadd132pd"],
+        0x9d => ["fnmadd132ss", "fnmadd132sd"],
+        0x9e => ["fnmsub132ps", "fnmsub132pd"],
+        0x9f => ["fnmsub132ss", "fnmsub132sd"],
+        0xa6 => ["fmaddsub213ps", "fmaddsub213pd"],
+        0xa7 => ["fmsubadd213ps", "fmsubadd213pd"],
+        0xa8 => ["fmadd213ps", "fmadd213pd"],
+        0xa9 => ["fmadd213ss", "fmadd213sd"],
+        0xaa => ["fmsub213ps", "fmsub213pd"],
+        0xab => ["fmsub213ss", "fmsub213sd"],
+        0xac => ["fnmadd213ps", "fnmadd213pd"],
+        0xad => ["fnmadd213ss", "fnmadd213sd"],
+        0xae => ["fnmsub213ps", "fnmsub213pd"],
+        0xaf => ["fnmsub213ss", "fnmsub213sd"],
+        0xb6 => ["fmaddsub231ps", "fmaddsub231pd"],
+        0xb7 => ["fmsubadd231ps", "fmsubadd231pd"],
+        0xb8 => ["fmadd231ps", "fmadd231pd"],
+        0xb9 => ["fmadd231ss", "fmadd231sd"],
+        0xba => ["fmsub231ps", "fmsub231pd"],
+        0xbb => ["fmsub231ss", "fmsub231sd"],
+        0xbc => ["fnmadd231ps", "fnmadd231pd"],
+        0xbd => ["fnmadd231ss", "fnmadd231sd"],
+        0xbe => ["fnmsub231ps", "fnmsub231pd"],
+        0xbf => ["fnmsub231ss", "fnmsub231sd"],
+        _ => return BAD,
+    };
+    let scalar = op.byte & 0xf >= 8 && op.byte & 1 == 1;
+    let operands = match (scalar, op.w) {
+        (false, _) => "Vx,Hx,Wx",
+        (true, false) => "Vo,Ho,Wd",
+        (true, true) => "Vo,Ho,Wq",
+    };
+    op.pick([
+        BAD,
+        f(
+            names[op.w as usize],
+            operands,
+            VEX_ONLY | BROADCAST | ROUNDING,
+        ),
+        BAD,
+        BAD,
+    ])
+}
+
+/// The 0F 3A map: instructions with an 8-bit immediate.
+fn three_byte_3a(op: &Opcode) -> Entry {
+    let register = op.is_register();
+    let vex = |name, operands| f(name, operands, VEX_ONLY);
+
+    let entry = match op.byte {
+        // Shifting opmask registers.
+        0x30..=0x33 if op.vex && !op.evex && op.l == 0 && register => {
+            let names = [
+                ["kshiftrb", "kshiftrw"],
+                ["kshiftrd", "kshiftrq"],
+                ["kshiftlb", "kshiftlw"],
+                ["kshiftld", "kshiftlq"],
+            ];
+            let name = names[(op.byte - 0x30) as usize][op.w as usize];
+            f(name, "KG,KE,Ib", NO_V | USES_W)
+        }
+        0x00 if op.w => vex("permq", "Vx,Wx,Ib"),
+        0x01 if op.w => vex("permpd", "Vx,Wx,Ib"),
+        0x02 => vex("pblendd", "Vx,Hx,Wx,Ib"),
+        0x04 => vex("permilps", "Vx,Wx,Ib"),
+        0x05 => vex("permilpd", "Vx,Wx,Ib"),
+        0x06 => vex("perm2f128", "Vx,Hx,Wx,Ib"),
+        0x08 => e("roundps", "Vx,Wx,Ib"),
+        0x09 => e("roundpd", "Vx,Wx,Ib"),
+        0x0a => e("roundss", "Vo,Ho,Wd,Ib"),
+        0x0b => e("roundsd", "Vo,Ho,Wq,Ib"),
+        0x0c => e("blendps", "Vx,Hx,Wx,Ib"),
+        0x0d => e("blendpd", "Vx,Hx,Wx,Ib"),
+        0x0e => e("pblendw", "Vx,Hx,Wx,Ib"),
+        0x0f if op.prefix == 0 => return f("palignr", "Pq,Qq,Ib", MMX),
+        0x0f => e("palignr", "Vx,Hx,Wx,Ib"),
+        0x14 => e("pextrb", if register { "Rd,Vo,Ib" } else { "Mb,Vo,Ib" }),
+        0x15 => e("pextrw", if register { "Rd,Vo,Ib" } else { "Mw,Vo,Ib" }),
+        0x16 => f(if op.w { "pextrq" } else { "pextrd" }, "Ey,Vo,Ib", USES_W),
+        0x17 => e("extractps", "Ed,Vo,Ib"),
+        0x18 => vex("insertf128", "Vx,Hx,Wo,Ib"),
+        0x19 => vex("extractf128", "Wo,Vx,Ib"),
+        0x1d => vex("cvtps2ph", "Wh,Vx,Ib"),
+        0x20 => e(
+            "pinsrb",
+            if register {
+                "Vo,Ho,Rd,Ib"
+            } else {
+                "Vo,Ho,Mb,Ib"
+            },
+        ),
+        0x21 => e("insertps", "Vo,Ho,Wd,Ib"),
+        0x22 => f(
+            if op.w { "pinsrq" } else { "pinsrd" },
+            "Vo,Ho,Ey,Ib",
+            USES_W,
+        ),
+        0x38 => vex("inserti128", "Vx,Hx,Wo,Ib"),
+        0x39 => vex("extracti128", "Wo,Vx,Ib"),
+        0x40 => e("dpps", "Vx,Hx,Wx,Ib"),
+        0x41 => e("dppd", "Vo,Ho,Wo,Ib"),
+        0x42 => e("mpsadbw", "Vx,Hx,Wx,Ib"),
+        0x44 => f("pclmul*dq", "Vx,Hx,Wx,Ib", PREDICATE),
+        0x46 => vex("perm2i128", "Vx,Hx,Wx,Ib"),
+        0x4a => vex("blendvps", "Vx,Hx,Wx,Lx"),
+        0x4b => vex("blendvpd", "Vx,Hx,Wx,Lx"),
+        0x4c => vex("pblendvb", "Vx,Hx,Wx,Lx"),
+        0x60 => e("pcmpestrm", "Vo,Wo,Ib"),
+        0x61 => e("pcmpestri", "Vo,Wo,Ib"),
+        0x62 => e("pcmpistrm", "Vo,Wo,Ib"),
+        0x63 => e("pcmpistri", "Vo,Wo,Ib"),
+        0xcc if op.prefix == 0 => return f("sha1rnds4", "Vo,Wo,Ib", LEGACY),
+        0xce => e("gf2p8affineqb", "Vx,Hx,Wx,Ib"),
+        0xcf => e("gf2p8affineinvqb", "Vx,Hx,Wx,Ib"),
+        0xdf => e("aeskeygenassist", "Vo,Wo,Ib"),
+        0xf0 if op.prefix == 0xf2 && op.vex => return f("rorx", "Gy,Ey,Ib", PREFIXED | NO_V),
+        _ => BAD,
+    };
+
+    op.pick([BAD, entry, BAD, BAD])
+}
+
+/// Instructions that EVEX encodes differently from VEX, or only EVEX
+/// encodes: those of AVX-512 that compilers and string functions use most.
+fn evex(op: &Opcode, entry: Entry) -> Entry {
+    let w = |name_w0, name_w1| match op.w {
+        true => name_w1,
+        false => name_w0,
+    };
+    let evex = |name, operands| f(name, operands, PREFIXED | BROADCAST);
+
+    match (op.map, op.prefix, op.byte) {
+        (Map::TwoByte, 0x66, 0x6f) => evex(w("movdqa32", "movdqa64"), "Vx,Wx"),
+        (Map::TwoByte, 0x66, 0x7f) => evex(w("movdqa32", "movdqa64"), "Wx,Vx"),
+        (Map::TwoByte, 0xf3, 0x6f) => evex(w("movdqu32", "movdqu64"), "Vx,Wx"),
+        (Map::TwoByte, 0xf3, 0x7f) => evex(w("movdqu32", "movdqu64"), "Wx,Vx"),
+        (Map::TwoByte, 0xf2, 0x6f) => evex(w("movdqu8", "movdqu16"), "Vx,Wx"),
+        (Map::TwoByte, 0xf2, 0x7f) => evex(w("movdqu8", "movdqu16"), "Wx,Vx"),
+        (Map::TwoByte, 0x66, 0xdb) => evex(w("pandd", "pandq"), "Vx,Hx,Wx"),
+        (Map::TwoByte, 0x66, 0xdf) => evex(w("pandnd", "pandnq"), "Vx,Hx,Wx"),
+        (Map::TwoByte, 0x66, 0xeb) => evex(w("pord", "porq"), "Vx,Hx,Wx"),
+        (Map::TwoByte, 0x66, 0xef) => evex(w("pxord", "pxorq"), "Vx,Hx,Wx"),
+        (Map::TwoByte, 0x66, 0x74) => evex("pcmpeqb", "KG,Hx,Wx"),
+        (Map::TwoByte, 0x66, 0x75) => evex("pcmpeqw", "KG,Hx,Wx"),
+        (Map::TwoByte, 0x66, 0x76) => evex("pcmpeqd", "KG,Hx,Wx"),
+        (Map::TwoByte, 0x66, 0x64) => evex("pcmpgtb", "KG,Hx,Wx"),
+        (Map::TwoByte, 0x66, 0x65) => evex("pcmpgtw", "KG,Hx,Wx"),
+        (Map::TwoByte, 0x66, 0x66) => evex("pcmpgtd", "KG,Hx,Wx"),
+        (Map::ThreeByte38, 0x66, 0x29) => evex("pcmpeqq", "KG,Hx,Wx"),
+        (Map::ThreeByte38, 0x66, 0x37) => evex("pcmpgtq", "KG,Hx,Wx"),
+        (Map::ThreeByte38, 0x66, 0x26) => evex(w("ptestmb", "ptestmw"), "KG,Hx,Wx"),
+        (Map::ThreeByte38, 0x66, 0x27) => evex(w("ptestmd", "ptestmq"), "KG,Hx,Wx"),
+        (Map::ThreeByte38, 0xf3, 0x26) => evex(w("ptestnmb", "ptestnmw"), "KG,Hx,Wx"),
+        (Map::ThreeByte38, 0xf3, 0x27) => evex(w("ptestnmd", "ptestnmq"), "KG,Hx,Wx"),
+        (Map::ThreeByte38, 0x66, 0x7a) => evex("pbroadcastb", "Vx,Rd"),
+        (Map::ThreeByte38, 0x66, 0x7b) => evex("pbroadcastw", "Vx,Rd"),
+        (Map::ThreeByte38, 0x66, 0x7c) => evex(w("pbroadcastd", "pbroadcastq"), "Vx,Ry"),
+        (Map::ThreeByte38, 0x66, 0x1a) => {
+            f(w("broadcastf32x4", "broadcastf64x2"), "Vx,Mo", PREFIXED)
+        }
+        (Map::ThreeByte38, 0x66, 0x1b) => {
+            f(w("broadcastf32x8", "broadcastf64x4"), "Vx,Mh", PREFIXED)
+        }
+        (Map::ThreeByte38, 0x66, 0x5a) => {
+            f(w("broadcasti32x4", "broadcasti64x2"), "Vx,Mo", PREFIXED)
+        }
+        (Map::ThreeByte38, 0x66, 0x5b) => {
+            f(w("broadcasti32x8", "broadcasti64x4"), "Vx,Mh", PREFIXED)
+        }
+        (Map::ThreeByte38, 0x66, 0x64) => evex(w("pblendmd", "pblendmq"), "Vx,Hx,Wx"),
+        (Map::ThreeByte38, 0x66, 0x66) => evex(w("pblendmb", "pblendmw"), "Vx,Hx,Wx"),
+        (Map::ThreeByte38, 0x66, 0x3b) => evex(w("pminud", "pminuq"), "Vx,Hx,Wx"),
+        (Map::ThreeByte38, 0x66, 0x3f) => evex(w("pmaxud", "pmaxuq"), "Vx,Hx,Wx"),
+        (Map::ThreeByte38, 0x66, 0xb4) if op.w => evex("pmadd52luq", "Vx,Hx,Wx"),
+        (Map::ThreeByte38, 0x66, 0xb5) if op.w => evex("pmadd52huq", "Vx,Hx,Wx"),
+        // Rotates join the shifts by an immediate.
+        (Map::TwoByte, 0x66, 0x72) if op.reg() < 2 => {
+            let name = [w("prord", "prorq"), w("prold", "prolq")][op.reg()];
+            evex(name, "Hx,Wx,Ib")
+        }
+        (Map::TwoByte, 0x66, 0x72) if op.reg() == 4 && op.w => evex("psraq", "Hx,Wx,Ib"),
+        (Map::ThreeByte3a, 0x66, 0x25) => evex(w("pternlogd", "pternlogq"), "Vx,Hx,Wx,Ib"),
+        (Map::ThreeByte3a, 0x66, 0x18) => evex(w("insertf32x4", "insertf64x2"), "Vx,Hx,Wo,Ib"),
+        (Map::ThreeByte3a, 0x66, 0x19) => evex(w("extractf32x4", "extractf64x2"), "Wo,Vx,Ib"),
+        (Map::ThreeByte3a, 0x66, 0x1a) => evex(w("insertf32x8", "insertf64x4"), "Vx,Hx,Wh,Ib"),
+        (Map::ThreeByte3a, 0x66, 0x1b) => evex(w("extractf32x8", "extractf64x4"), "Wh,Vx,Ib"),
+        (Map::ThreeByte3a, 0x66, 0x38) => evex(w("inserti32x4", "inserti64x2"), "Vx,Hx,Wo,Ib"),
+        (Map::ThreeByte3a, 0x66, 0x39) => evex(w("extracti32x4", "extracti64x2"), "Wo,Vx,Ib"),
+        (Map::ThreeByte3a, 0x66, 0x3a) => evex(w("inserti32x8", "inserti64x4"), "Vx,Hx,Wh,Ib"),
+        (Map::ThreeByte3a, 0x66, 0x3b) => evex(w("extracti32x8", "extracti64x4"), "Wh,Vx,Ib"),
+        (Map::ThreeByte3a, 0x66, 0x03) => evex(w("alignd", "alignq"), "Vx,Hx,Wx,Ib"),
+        (Map::ThreeByte3a, 0x66, 0x23) => evex(w("shuff32x4", "shuff64x2"), "Vx,Hx,Wx,Ib"),
+        (Map::ThreeByte3a, 0x66, 0x43) => evex(w("shufi32x4", "shufi64x2"), "Vx,Hx,Wx,Ib"),
+        (Map::ThreeByte3a, 0x66, 0x3e) => {
+            evex(w("pcmp*ub", "pcmp*uw"), "KG,Hx,Wx,Ib").with(PREDICATE)
+        }
+        (Map::ThreeByte3a, 0x66, 0x3f) => {
+            evex(w("pcmp*b", "pcmp*w"), "KG,Hx,Wx,Ib").with(PREDICATE)
+        }
+        (Map::ThreeByte3a, 0x66, 0x1e) => {
+            evex(w("pcmp*ud", "pcmp*uq"), "KG,Hx,Wx,Ib").with(PREDICATE)
+        }
+        (Map::ThreeByte3a, 0x66, 0x1f) => {
+            evex(w("pcmp*d", "pcmp*q"), "KG,Hx,Wx,Ib").with(PREDICATE)
+        }
+        _ => entry,
+    }
+}
@@ -0,0 +1,98 @@
+/// Abbreviations (.debug_abbrev). Each entry in .debug_info starts with the
+/// code of an abbreviation, which gives its tag, whether it has children,
+/// and the name and form of each of its attributes, so that the entry
+/// itself only holds the values.
+///
+use std::collections::HashMap;
+
+use crate::dwarf::reader::Reader;
+use crate::dwarf::*;
+use crate::ElfError;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AttributeSpec {
+    pub name: u64,
+    pub form: u64,
+
+    /// The value of a DW_FORM_implicit_const attribute, which is
+    /// kept here rather than in the entries.
+    pub implicit_const: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Abbreviation {
+    pub code: u64,
+    pub tag: u64,
+    pub has_children: bool,
+    pub attributes: Vec<AttributeSpec>,
+}
+
+/// The abbreviations of a unit (or of several units that share them).
+#[derive(Debug, Default)]
+pub struct Abbreviations {
+    by_code: HashMap<u64, Abbreviation>,
+}
+
+impl Abbreviations {
+    pub fn get(&self, code: u64) -> Option<&Abbreviation> {
+        self.by_code.get(&code)
+    }
+}
+
+/// Reads the abbreviations starting at `offset` in .debug_abbrev, up to
+/// the 0 code that ends them.
+pub fn read_abbreviations(debug_abbrev: &[u8], offset: u64) -> Result<Abbreviations, ElfError> {
+    let mut reader = Reader::new(debug_abbrev, "Abbreviation");
+    if offset > debug_abbrev.len() as u64 {
+        return Err(ElfError::Truncated {
+            what: "Abbreviation",
+            offset,
+            needed: 1,
+        });
+    }
+    reader.seek(offset as usize);
+
+    let mut by_code = HashMap::new();
+
+    loop {
+        let code = reader.uleb128()?;
+        if code == 0 {
+            break;
+        }
+
+        let tag = reader.uleb128()?;
+        let has_children = reader.u8()? != 0;
+        let mut attributes = vec![];
+
+        loop {
+            let name = reader.uleb128()?;
+            let form = reader.uleb128()?;
+            if name == 0 && form == 0 {
+                break;
+            }
+
+            let implicit_const = match form {
+                DW_FORM_IMPLICIT_CONST => reader.sleb128()?,
+                _ => 0,
+            };
+
+            attributes.push(AttributeSpec {
+                name,
+                form,
+                implicit_const,
+            });
+        }
+
+        by_code.insert(
+            code,
+            Abbreviation {
+                code,
+                tag,
+                has_children,
+                attributes,
+            },
+        );
+    }
+
+    Ok(Abbreviations { by_code })
+}
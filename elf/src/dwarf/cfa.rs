@@ -0,0 +1,387 @@
+/// The call frame instructions of CIEs and FDEs, run to give a table of
+/// rules: for each address, how to find the canonical frame address (CFA,
+/// the stack pointer before the call) and the caller's registers. With
+/// those, a stack can be unwound offline, from a core dump.
+///
+use std::collections::{BTreeMap, BTreeSet};
+use std::ops::Range;
+
+use crate::dwarf::expression::*;
+use crate::dwarf::frame::Cie;
+use crate::dwarf::reader::Reader;
+use crate::ElfError;
+
+// Call frame instructions (DW_CFA_*). The first three have their
+// operand in the low six bits of the opcode.
+pub const DW_CFA_ADVANCE_LOC: u8 = 0x40;
+pub const DW_CFA_OFFSET: u8 = 0x80;
+pub const DW_CFA_RESTORE: u8 = 0xc0;
+pub const DW_CFA_NOP: u8 = 0x00;
+pub const DW_CFA_SET_LOC: u8 = 0x01;
+pub const DW_CFA_ADVANCE_LOC1: u8 = 0x02;
+pub const DW_CFA_ADVANCE_LOC2: u8 = 0x03;
+pub const DW_CFA_ADVANCE_LOC4: u8 = 0x04;
+pub const DW_CFA_OFFSET_EXTENDED: u8 = 0x05;
+pub const DW_CFA_RESTORE_EXTENDED: u8 = 0x06;
+pub const DW_CFA_UNDEFINED: u8 = 0x07;
+pub const DW_CFA_SAME_VALUE: u8 = 0x08;
+pub const DW_CFA_REGISTER: u8 = 0x09;
+pub const DW_CFA_REMEMBER_STATE: u8 = 0x0a;
+pub const DW_CFA_RESTORE_STATE: u8 = 0x0b;
+pub const DW_CFA_DEF_CFA: u8 = 0x0c;
+pub const DW_CFA_DEF_CFA_REGISTER: u8 = 0x0d;
+pub const DW_CFA_DEF_CFA_OFFSET: u8 = 0x0e;
+pub const DW_CFA_DEF_CFA_EXPRESSION: u8 = 0x0f;
+pub const DW_CFA_EXPRESSION: u8 = 0x10;
+pub const DW_CFA_OFFSET_EXTENDED_SF: u8 = 0x11;
+pub const DW_CFA_DEF_CFA_SF: u8 = 0x12;
+pub const DW_CFA_DEF_CFA_OFFSET_SF: u8 = 0x13;
+pub const DW_CFA_VAL_OFFSET: u8 = 0x14;
+pub const DW_CFA_VAL_OFFSET_SF: u8 = 0x15;
+pub const DW_CFA_VAL_EXPRESSION: u8 = 0x16;
+pub const DW_CFA_MIPS_ADVANCE_LOC8: u8 = 0x1d;
+pub const DW_CFA_GNU_WINDOW_SAVE: u8 = 0x2d;
+pub const DW_CFA_GNU_ARGS_SIZE: u8 = 0x2e;
+pub const DW_CFA_GNU_NEGATIVE_OFFSET_EXTENDED: u8 = 0x2f;
+
+/// How to compute the CFA.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CfaRule {
+    RegisterOffset { register: u16, offset: i64 },
+    Expression(Vec<u8>),
+}
+
+/// How to find the value a register had in the caller.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegisterRule {
+    /// It can't be recovered.
+    Undefined,
+    /// It hasn't changed.
+    SameValue,
+    /// It's saved at this offset from the CFA.
+    Offset(i64),
+    /// It's the CFA plus this offset.
+    ValOffset(i64),
+    /// It's in another register.
+    Register(u16),
+    /// It's saved at the address the expression computes, from the CFA.
+    Expression(Vec<u8>),
+    /// It's the value the expression computes, from the CFA.
+    ValExpression(Vec<u8>),
+}
+
+/// The rules for the code at the addresses in `range`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnwindRow {
+    pub range: Range<u64>,
+    pub cfa: CfaRule,
+
+    /// Rules for the registers that have them; those without one are
+    /// taken to have the same value in the caller.
+    pub registers: BTreeMap<u16, RegisterRule>,
+
+    /// The column of the return address, whose value in the caller is
+    /// where it continues.
+    pub return_address_register: u16,
+}
+
+/// The rows of rules for an FDE's code (or for a CIE, its initial rules).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnwindTable {
+    /// A row for each address that the instructions advance to, in order.
+    /// Rows may be empty, when an instruction advances by nothing.
+    pub rows: Vec<UnwindRow>,
+
+    /// Every register that the instructions give rules for.
+    pub columns: BTreeSet<u16>,
+}
+
+impl UnwindTable {
+    /// The rules for the code at `address`.
+    pub fn row_at(&self, address: u64) -> Option<&UnwindRow> {
+        self.rows.iter().find(|row| row.range.contains(&address))
+    }
+}
+
+/// The rules as the instructions change them.
+#[derive(Debug, Clone)]
+struct State {
+    cfa: CfaRule,
+    registers: BTreeMap<u16, RegisterRule>,
+
+    /// The offset the CFA was last given, which DW_CFA_def_cfa_register
+    /// goes back to after an expression replaced it (as gcc's unwinder does).
+    cfa_offset: i64,
+}
+
+/// Runs the instructions in `reader`, starting at the start of `range`
+/// with the `initial` rules (the CIE's, for an FDE). `read_address` reads
+/// the operand of DW_CFA_set_loc, in the FDE's pointer encoding.
+pub(crate) fn execute_instructions(
+    mut reader: Reader,
+    cie: &Cie,
+    initial: Option<&UnwindTable>,
+    range: Range<u64>,
+    read_address: impl Fn(&mut Reader) -> Result<u64, ElfError>,
+) -> Result<UnwindTable, ElfError> {
+    let initial_row = initial.and_then(|table| table.rows.last());
+
+    let mut state = match initial_row {
+        Some(row) => State {
+            cfa: row.cfa.clone(),
+            registers: row.registers.clone(),
+            cfa_offset: match row.cfa {
+                CfaRule::RegisterOffset { offset, .. } => offset,
+                CfaRule::Expression(_) => 0,
+            },
+        },
+        None => State {
+            cfa: CfaRule::RegisterOffset {
+                register: 0,
+                offset: 0,
+            },
+            registers: BTreeMap::new(),
+            cfa_offset: 0,
+        },
+    };
+
+    let mut table = UnwindTable {
+        rows: vec![],
+        columns: initial
+            .map(|table| table.columns.clone())
+            .unwrap_or_default(),
+    };
+    let mut remembered: Vec<State> = vec![];
+    let mut location = range.start;
+
+    let code_factor = cie.code_alignment_factor;
+    let data_factor = cie.data_alignment_factor;
+
+    while !reader.is_empty() {
+        let offset = reader.offset();
+        let opcode = reader.u8()?;
+
+        // The instructions that advance end a row, and start the next.
+        let delta = match (opcode & 0xc0, opcode) {
+            (DW_CFA_ADVANCE_LOC, _) => Some((opcode & 0x3f) as u64),
+            (_, DW_CFA_ADVANCE_LOC1) => Some(reader.u8()? as u64),
+            (_, DW_CFA_ADVANCE_LOC2) => Some(reader.u16()? as u64),
+            (_, DW_CFA_ADVANCE_LOC4) => Some(reader.u32()? as u64),
+            (_, DW_CFA_MIPS_ADVANCE_LOC8) => Some(reader.u64()?),
+            _ => None,
+        };
+        let advance = match opcode {
+            DW_CFA_SET_LOC => Some(read_address(&mut reader)?),
+            _ => delta.map(|delta| location.wrapping_add(delta.wrapping_mul(code_factor))),
+        };
+
+        if let Some(next) = advance {
+            table.rows.push(UnwindRow {
+                range: location..next,
+                cfa: state.cfa.clone(),
+                registers: state.registers.clone(),
+                return_address_register: cie.return_address_register,
+            });
+            location = next;
+            continue;
+        }
+
+        // The register whose rule the instruction sets, and the rule.
+        let (register, rule) = match (opcode & 0xc0, opcode) {
+            (DW_CFA_OFFSET, _) => {
+                let offset = (reader.uleb128()? as i64).wrapping_mul(data_factor);
+                ((opcode & 0x3f) as u16, Some(RegisterRule::Offset(offset)))
+            }
+            (DW_CFA_RESTORE, _) => ((opcode & 0x3f) as u16, None),
+
+            (_, DW_CFA_OFFSET_EXTENDED) => {
+                let register = reader.uleb128()? as u16;
+                let offset = (reader.uleb128()? as i64).wrapping_mul(data_factor);
+                (register, Some(RegisterRule::Offset(offset)))
+            }
+            (_, DW_CFA_OFFSET_EXTENDED_SF) => {
+                let register = reader.uleb128()? as u16;
+                let offset = reader.sleb128()?.wrapping_mul(data_factor);
+                (register, Some(RegisterRule::Offset(offset)))
+            }
+            (_, DW_CFA_GNU_NEGATIVE_OFFSET_EXTENDED) => {
+                let register = reader.uleb128()? as u16;
+                let offset = (reader.uleb128()? as i64)
+                    .wrapping_neg()
+                    .wrapping_mul(data_factor);
+                (register, Some(RegisterRule::Offset(offset)))
+            }
+            (_, DW_CFA_VAL_OFFSET) => {
+                let register = reader.uleb128()? as u16;
+                let offset = (reader.uleb128()? as i64).wrapping_mul(data_factor);
+                (register, Some(RegisterRule::ValOffset(offset)))
+            }
+            (_, DW_CFA_VAL_OFFSET_SF) => {
+                let register = reader.uleb128()? as u16;
+                let offset = reader.sleb128()?.wrapping_mul(data_factor);
+                (register, Some(RegisterRule::ValOffset(offset)))
+            }
+            (_, DW_CFA_RESTORE_EXTENDED) => (reader.uleb128()? as u16, None),
+            (_, DW_CFA_UNDEFINED) => (reader.uleb128()? as u16, Some(RegisterRule::Undefined)),
+            (_, DW_CFA_SAME_VALUE) => (reader.uleb128()? as u16, Some(RegisterRule::SameValue)),
+            (_, DW_CFA_REGISTER) => {
+                let register = reader.uleb128()? as u16;
+                let other = reader.uleb128()? as u16;
+                (register, Some(RegisterRule::Register(other)))
+            }
+            (_, DW_CFA_EXPRESSION | DW_CFA_VAL_EXPRESSION) => {
+                let register = reader.uleb128()? as u16;
+                let length = reader.uleb128()?;
+                let expression = reader.bytes(length as usize)?.to_vec();
+
+                let rule = match opcode {
+                    DW_CFA_EXPRESSION => RegisterRule::Expression(expression),
+                    _ => RegisterRule::ValExpression(expression),
+                };
+                (register, Some(rule))
+            }
+
+            // The rest change the CFA, or the state as a whole.
+            _ => {
+                match opcode {
+                    DW_CFA_DEF_CFA => {
+                        let register = reader.uleb128()? as u16;
+                        let offset = reader.uleb128()? as i64;
+                        state.cfa = CfaRule::RegisterOffset { register, offset };
+                        state.cfa_offset = offset;
+                    }
+                    DW_CFA_DEF_CFA_SF => {
+                        let register = reader.uleb128()? as u16;
+                        let offset = reader.sleb128()?.wrapping_mul(data_factor);
+                        state.cfa = CfaRule::RegisterOffset { register, offset };
+                        state.cfa_offset = offset;
+                    }
+                    DW_CFA_DEF_CFA_REGISTER => {
+                        let register = reader.uleb128()? as u16;
+                        let offset = state.cfa_offset;
+                        state.cfa = CfaRule::RegisterOffset { register, offset };
+                    }
+                    DW_CFA_DEF_CFA_OFFSET | DW_CFA_DEF_CFA_OFFSET_SF => {
+                        let new_offset = match opcode {
+                            DW_CFA_DEF_CFA_OFFSET => reader.uleb128()? as i64,
+                            _ => reader.sleb128()?.wrapping_mul(data_factor),
+                        };
+                        if let CfaRule::RegisterOffset { offset, .. } = &mut state.cfa {
+                            *offset = new_offset;
+                        }
+                        state.cfa_offset = new_offset;
+                    }
+                    DW_CFA_DEF_CFA_EXPRESSION => {
+                        let length = reader.uleb128()?;
+                        let expression = reader.bytes(length as usize)?.to_vec();
+                        state.cfa = CfaRule::Expression(expression);
+                    }
+
+                    DW_CFA_REMEMBER_STATE => remembered.push(state.clone()),
+                    DW_CFA_RESTORE_STATE => {
+                        state = remembered.pop().ok_or_else(|| {
+                            reader.invalid(offset, "no state was remembered to restore".to_owned())
+                        })?;
+                    }
+
+                    DW_CFA_GNU_ARGS_SIZE => {
+                        reader.uleb128()?;
+                    }
+
+                    // AArch64 uses this to toggle whether the return address
+                    // is signed (DW_CFA_AARCH64_negate_ra_state), which only
+                    // matters for stripping the signature from it.
+                    DW_CFA_NOP | DW_CFA_GNU_WINDOW_SAVE => {}
+
+                    _ => {
+                        return Err(reader.invalid(
+                            offset,
+                            format!("the call frame instruction {opcode:#04x} isn't supported"),
+                        ))
+                    }
+                }
+                continue;
+            }
+        };
+
+        table.columns.insert(register);
+
+        // Restoring goes back to the initial rule.
+        let rule =
+            rule.or_else(|| initial_row.and_then(|row| row.registers.get(&register).cloned()));
+        match rule {
+            Some(rule) => state.registers.insert(register, rule),
+            None => state.registers.remove(&register),
+        };
+    }
+
+    table.rows.push(UnwindRow {
+        range: location..range.end.max(location),
+        cfa: state.cfa,
+        registers: state.registers,
+        return_address_register: cie.return_address_register,
+    });
+
+    Ok(table)
+}
+
+impl UnwindRow {
+    /// Computes the CFA of a frame whose registers are `registers`.
+    pub fn cfa(&self, registers: &Registers, memory: Memory) -> Result<u64, ElfError> {
+        match &self.cfa {
+            CfaRule::RegisterOffset { register, offset } => registers
+                .get(register)
+                .map(|value| value.wrapping_add(*offset as u64))
+                .ok_or_else(|| ElfError::Unavailable {
+                    what: format!("The value of register {register}"),
+                }),
+
+            CfaRule::Expression(expression) => evaluate(expression, 8, &[], registers, memory),
+        }
+    }
+
+    /// Unwinds a frame: from the values of its registers, computes those of
+    /// the caller's, reading what was saved on the stack from `memory`. The
+    /// caller's `stack_pointer` register is set to the CFA, and its return
+    /// address column holds the address it continues at.
+    pub fn unwind(
+        &self,
+        registers: &Registers,
+        stack_pointer: u16,
+        memory: Memory,
+    ) -> Result<Registers, ElfError> {
+        let cfa = self.cfa(registers, memory)?;
+
+        let read = |address: u64| {
+            memory(address, 8).ok_or_else(|| ElfError::Unavailable {
+                what: format!("Memory at {address:#x}"),
+            })
+        };
+
+        let mut caller = registers.clone();
+        caller.insert(stack_pointer, cfa);
+
+        for (&register, rule) in &self.registers {
+            let value = match rule {
+                RegisterRule::Undefined => None,
+                RegisterRule::SameValue => registers.get(&register).copied(),
+                RegisterRule::Offset(offset) => Some(read(cfa.wrapping_add(*offset as u64))?),
+                RegisterRule::ValOffset(offset) => Some(cfa.wrapping_add(*offset as u64)),
+                RegisterRule::Register(other) => registers.get(other).copied(),
+                RegisterRule::Expression(expression) => {
+                    Some(read(evaluate(expression, 8, &[cfa], registers, memory)?)?)
+                }
+                RegisterRule::ValExpression(expression) => {
+                    Some(evaluate(expression, 8, &[cfa], registers, memory)?)
+                }
+            };
+
+            match value {
+                Some(value) => caller.insert(register, value),
+                None => caller.remove(&register),
+            };
+        }
+
+        Ok(caller)
+    }
+}
@@ -0,0 +1,205 @@
+/// An evaluator for DWARF expressions, the stack machine programs that call
+/// frame information uses for rules that offsets can't express (such as the
+/// CFA in a PLT entry). Only the operations that compute values are
+/// supported, not those describing where a variable is.
+///
+use std::collections::BTreeMap;
+
+use crate::dwarf::reader::Reader;
+use crate::dwarf::*;
+use crate::ElfError;
+
+/// How many operations an expression may run, in case its branches loop.
+const MAX_OPERATIONS: usize = 10_000;
+
+/// The values of a frame's registers, by their DWARF numbers.
+pub type Registers = BTreeMap<u16, u64>;
+
+/// Reads `size` bytes (up to 8) of the memory of the process being
+/// examined, as a little-endian number, or None if it isn't available.
+pub type Memory<'a> = &'a dyn Fn(u64, usize) -> Option<u64>;
+
+/// Evaluates `expression`, starting with `stack` on the stack, and
+/// returns the value on top of the stack when it ends.
+pub fn evaluate(
+    expression: &[u8],
+    address_size: u8,
+    stack: &[u64],
+    registers: &Registers,
+    memory: Memory,
+) -> Result<u64, ElfError> {
+    let mut reader = Reader::new(expression, "DWARF expression");
+    let mut stack = stack.to_vec();
+    let address_size = address_size as usize;
+
+    let register = |number: u16| {
+        registers
+            .get(&number)
+            .copied()
+            .ok_or_else(|| ElfError::Unavailable {
+                what: format!("The value of register {number}"),
+            })
+    };
+    let invalid = |offset: usize, reason: &str| ElfError::InvalidData {
+        what: "DWARF expression",
+        offset: offset as u64,
+        reason: reason.to_owned(),
+    };
+    let read = |address: u64, size: usize| {
+        memory(address, size).ok_or_else(|| ElfError::Unavailable {
+            what: format!("Memory at {address:#x}"),
+        })
+    };
+
+    for _ in 0..MAX_OPERATIONS {
+        let offset = reader.offset();
+        if reader.is_empty() {
+            return stack
+                .pop()
+                .ok_or_else(|| invalid(offset, "it leaves nothing on the stack"));
+        }
+
+        let underflow = || invalid(offset, "the stack is empty");
+
+        let opcode = reader.u8()?;
+        match opcode {
+            DW_OP_ADDR => stack.push(reader.uint(address_size)?),
+            DW_OP_CONST1U => stack.push(reader.u8()? as u64),
+            DW_OP_CONST1S => stack.push(reader.i8()? as u64),
+            DW_OP_CONST2U => stack.push(reader.u16()? as u64),
+            DW_OP_CONST2S => stack.push(reader.u16()? as i16 as u64),
+            DW_OP_CONST4U => stack.push(reader.u32()? as u64),
+            DW_OP_CONST4S => stack.push(reader.u32()? as i32 as u64),
+            DW_OP_CONST8U | DW_OP_CONST8S => stack.push(reader.u64()?),
+            DW_OP_CONSTU => stack.push(reader.uleb128()?),
+            DW_OP_CONSTS => stack.push(reader.sleb128()? as u64),
+            DW_OP_LIT0..=DW_OP_LIT31 => stack.push((opcode - DW_OP_LIT0) as u64),
+
+            DW_OP_BREG0..=DW_OP_BREG31 => {
+                let value = register((opcode - DW_OP_BREG0) as u16)?;
+                stack.push(value.wrapping_add(reader.sleb128()? as u64));
+            }
+            DW_OP_BREGX => {
+                let value = register(reader.uleb128()? as u16)?;
+                stack.push(value.wrapping_add(reader.sleb128()? as u64));
+            }
+
+            DW_OP_DEREF => {
+                let address = stack.pop().ok_or_else(underflow)?;
+                stack.push(read(address, address_size)?);
+            }
+            DW_OP_DEREF_SIZE => {
+                let size = reader.u8()? as usize;
+                let address = stack.pop().ok_or_else(underflow)?;
+                stack.push(read(address, size.min(8))?);
+            }
+
+            DW_OP_DUP => stack.push(*stack.last().ok_or_else(underflow)?),
+            DW_OP_DROP => {
+                stack.pop().ok_or_else(underflow)?;
+            }
+            DW_OP_OVER | DW_OP_PICK => {
+                let index = match opcode {
+                    DW_OP_OVER => 1,
+                    _ => reader.u8()? as usize,
+                };
+                let value = stack
+                    .len()
+                    .checked_sub(index + 1)
+                    .map(|position| stack[position])
+                    .ok_or_else(underflow)?;
+                stack.push(value);
+            }
+            DW_OP_SWAP | DW_OP_ROT => {
+                let count = if opcode == DW_OP_SWAP { 2 } else { 3 };
+                let start = stack.len().checked_sub(count).ok_or_else(underflow)?;
+                stack[start..].rotate_right(1);
+            }
+
+            DW_OP_ABS | DW_OP_NEG | DW_OP_NOT => {
+                let value = stack.pop().ok_or_else(underflow)?;
+                stack.push(match opcode {
+                    DW_OP_ABS => (value as i64).unsigned_abs(),
+                    DW_OP_NEG => value.wrapping_neg(),
+                    _ => !value,
+                });
+            }
+            DW_OP_PLUS_UCONST => {
+                let value = stack.pop().ok_or_else(underflow)?;
+                stack.push(value.wrapping_add(reader.uleb128()?));
+            }
+
+            DW_OP_AND..=DW_OP_XOR | DW_OP_EQ..=DW_OP_NE => {
+                let b = stack.pop().ok_or_else(underflow)?;
+                let a = stack.pop().ok_or_else(underflow)?;
+                stack.push(
+                    binary(opcode, a, b).ok_or_else(|| invalid(offset, "it divides by zero"))?,
+                );
+            }
+
+            DW_OP_SKIP | DW_OP_BRA => {
+                let distance = reader.u16()? as i16;
+                let taken = opcode == DW_OP_SKIP || stack.pop().ok_or_else(underflow)? != 0;
+
+                if taken {
+                    let target = reader.offset() as i64 + distance as i64;
+                    if target < 0 || target as usize > expression.len() {
+                        return Err(invalid(offset, "it branches out of it"));
+                    }
+                    reader.seek(target as usize);
+                }
+            }
+
+            DW_OP_NOP => {}
+
+            _ => {
+                return Err(invalid(
+                    offset,
+                    &format!("operation {opcode:#04x} isn't supported"),
+                ))
+            }
+        }
+    }
+
+    Err(invalid(0, "it runs for too long"))
+}
+
+/// Applies an operation on the two values on top of the stack, `a`
+/// below `b`. None for division by zero.
+fn binary(opcode: u8, a: u64, b: u64) -> Option<u64> {
+    let (signed_a, signed_b) = (a as i64, b as i64);
+
+    Some(match opcode {
+        DW_OP_AND => a & b,
+        DW_OP_OR => a | b,
+        DW_OP_XOR => a ^ b,
+        DW_OP_PLUS => a.wrapping_add(b),
+        DW_OP_MINUS => a.wrapping_sub(b),
+        DW_OP_MUL => a.wrapping_mul(b),
+        DW_OP_DIV => signed_a.checked_div(signed_b)? as u64,
+        DW_OP_MOD => a.checked_rem(b)?,
+        DW_OP_SHL => {
+            if b < 64 {
+                a << b
+            } else {
+                0
+            }
+        }
+        DW_OP_SHR => {
+            if b < 64 {
+                a >> b
+            } else {
+                0
+            }
+        }
+        DW_OP_SHRA => (signed_a >> b.min(63)) as u64,
+        // Comparisons are of signed values.
+        DW_OP_EQ => (signed_a == signed_b) as u64,
+        DW_OP_GE => (signed_a >= signed_b) as u64,
+        DW_OP_GT => (signed_a > signed_b) as u64,
+        DW_OP_LE => (signed_a <= signed_b) as u64,
+        DW_OP_LT => (signed_a < signed_b) as u64,
+        DW_OP_NE => (signed_a != signed_b) as u64,
+        _ => return None,
+    })
+}
@@ -0,0 +1,555 @@
+/// Call frame information: the CIEs and FDEs of .eh_frame and .debug_frame,
+/// which describe how to find each function's caller, and the binary search
+/// table in .eh_frame_hdr that finds the FDE for an address.
+///
+use std::ops::Range;
+
+use crate::dwarf::cfa::*;
+use crate::dwarf::reader::Reader;
+use crate::ElfError;
+
+// Pointer encodings (DW_EH_PE_*): the low four bits give the format,
+// and the next three what the value is relative to.
+pub const DW_EH_PE_ABSPTR: u8 = 0x00;
+pub const DW_EH_PE_ULEB128: u8 = 0x01;
+pub const DW_EH_PE_UDATA2: u8 = 0x02;
+pub const DW_EH_PE_UDATA4: u8 = 0x03;
+pub const DW_EH_PE_UDATA8: u8 = 0x04;
+pub const DW_EH_PE_SLEB128: u8 = 0x09;
+pub const DW_EH_PE_SDATA2: u8 = 0x0a;
+pub const DW_EH_PE_SDATA4: u8 = 0x0b;
+pub const DW_EH_PE_SDATA8: u8 = 0x0c;
+pub const DW_EH_PE_PCREL: u8 = 0x10;
+pub const DW_EH_PE_TEXTREL: u8 = 0x20;
+pub const DW_EH_PE_DATAREL: u8 = 0x30;
+pub const DW_EH_PE_FUNCREL: u8 = 0x40;
+pub const DW_EH_PE_ALIGNED: u8 = 0x50;
+pub const DW_EH_PE_INDIRECT: u8 = 0x80;
+pub const DW_EH_PE_OMIT: u8 = 0xff;
+
+/// The sections that call frame information is in. Their entries are laid
+/// out alike, but differ in how CIEs are told apart from FDEs, and in how
+/// addresses are encoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameSection {
+    /// .eh_frame, which is loaded for exception handling.
+    EhFrame,
+    /// .debug_frame, which only debuggers read.
+    DebugFrame,
+}
+
+impl FrameSection {
+    pub fn name(&self) -> &'static str {
+        match self {
+            FrameSection::EhFrame => ".eh_frame",
+            FrameSection::DebugFrame => ".debug_frame",
+        }
+    }
+}
+
+/// A Common Information Entry: what the FDEs that point to it share.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cie {
+    /// Where the entry is, in its section.
+    pub offset: u64,
+    pub length: u64,
+
+    /// The ID that marks the entry as a CIE, as it's stored.
+    pub id: u64,
+    pub offset_size: usize,
+
+    pub version: u8,
+    pub augmentation: String,
+    pub address_size: u8,
+
+    /// What the operands of the instructions are multiplied by.
+    pub code_alignment_factor: u64,
+    pub data_alignment_factor: i64,
+
+    /// The column of the rules for the return address.
+    pub return_address_register: u16,
+
+    /// How the addresses in the FDEs are encoded (the 'R' augmentation),
+    /// and their language-specific data (the 'L' augmentation).
+    pub fde_encoding: u8,
+    pub lsda_encoding: u8,
+
+    /// The personality routine for exception handling. With an indirect
+    /// encoding, this is where the routine's address is.
+    pub personality: Option<u64>,
+
+    /// True for the frames of signal handlers (the 'S' augmentation), whose
+    /// return address is the instruction to run next, and not after a call.
+    pub is_signal_frame: bool,
+
+    /// Where the initial instructions are, in the section.
+    pub instructions: Range<usize>,
+}
+
+/// A Frame Description Entry, which describes the frames of the code at
+/// the addresses in `range`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fde {
+    pub offset: u64,
+    pub length: u64,
+
+    /// The pointer to the CIE as it's stored: in .eh_frame, it's the
+    /// distance back to it from the pointer itself.
+    pub cie_pointer: u64,
+    pub cie_offset: u64,
+
+    pub range: Range<u64>,
+
+    /// The language-specific data area, for exception handling.
+    pub lsda: Option<u64>,
+
+    pub instructions: Range<usize>,
+}
+
+/// The entries of a section of call frame information, in order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FrameEntry {
+    Cie(Cie),
+    Fde(Fde),
+    /// An entry with a length of zero, which ends .eh_frame, at `offset`.
+    Terminator(u64),
+}
+
+/// What encoded pointers can be relative to.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct PointerBases {
+    /// The address of the start of the data.
+    pub section: u64,
+    pub data: Option<u64>,
+}
+
+/// Reads a pointer in the `encoding` that .eh_frame uses, where the value
+/// can be relative to where it is. Indirect pointers aren't followed.
+pub(crate) fn read_pointer(
+    reader: &mut Reader,
+    encoding: u8,
+    address_size: u8,
+    bases: PointerBases,
+) -> Result<u64, ElfError> {
+    let mut offset = reader.offset();
+
+    if encoding & 0x70 == DW_EH_PE_ALIGNED {
+        offset = offset.next_multiple_of(address_size.max(1) as usize);
+        reader.seek(offset);
+    }
+
+    let value = match encoding & 0x0f {
+        DW_EH_PE_ABSPTR => reader.uint(address_size as usize)?,
+        DW_EH_PE_ULEB128 => reader.uleb128()?,
+        DW_EH_PE_UDATA2 => reader.u16()? as u64,
+        DW_EH_PE_UDATA4 => reader.u32()? as u64,
+        DW_EH_PE_UDATA8 => reader.u64()?,
+        DW_EH_PE_SLEB128 => reader.sleb128()? as u64,
+        DW_EH_PE_SDATA2 => reader.u16()? as i16 as u64,
+        DW_EH_PE_SDATA4 => reader.u32()? as i32 as u64,
+        DW_EH_PE_SDATA8 => reader.u64()?,
+        _ => {
+            return Err(reader.invalid(
+                offset,
+                format!("the pointer encoding {encoding:#04x} isn't valid"),
+            ))
+        }
+    };
+
+    let base = match (encoding & 0x70, bases.data) {
+        (DW_EH_PE_ABSPTR | DW_EH_PE_ALIGNED, _) => 0,
+        (DW_EH_PE_PCREL, _) => bases.section.wrapping_add(offset as u64),
+        (DW_EH_PE_DATAREL, Some(data)) => data,
+
+        _ => {
+            return Err(reader.invalid(
+                offset,
+                format!("pointers encoded as {encoding:#04x} aren't supported"),
+            ))
+        }
+    };
+
+    Ok(base.wrapping_add(value))
+}
+
+/// The contents of .eh_frame or .debug_frame, with the address it's loaded
+/// at (which pointers in .eh_frame are relative to). Entries are decoded
+/// when they're asked for.
+#[derive(Debug, Clone)]
+pub struct CallFrameInfo {
+    section: FrameSection,
+    address: u64,
+    data: Vec<u8>,
+}
+
+impl CallFrameInfo {
+    pub fn new(section: FrameSection, data: Vec<u8>, address: u64) -> CallFrameInfo {
+        CallFrameInfo {
+            section,
+            address,
+            data,
+        }
+    }
+
+    pub fn section(&self) -> FrameSection {
+        self.section
+    }
+
+    pub fn address(&self) -> u64 {
+        self.address
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    fn bases(&self) -> PointerBases {
+        PointerBases {
+            section: self.address,
+            data: None,
+        }
+    }
+
+    /// Decodes every entry, in order.
+    pub fn entries(&self) -> Result<Vec<FrameEntry>, ElfError> {
+        let mut entries = vec![];
+        let mut offset = 0;
+
+        while offset < self.data.len() {
+            let (entry, next) = self.read_entry(offset as u64)?;
+
+            // Padding after a terminator is skipped, as readelf does.
+            offset = next;
+            if let FrameEntry::Terminator(_) = entry {
+                while self.data.get(offset) == Some(&0) {
+                    offset += 1;
+                }
+            }
+
+            entries.push(entry);
+        }
+
+        Ok(entries)
+    }
+
+    /// Every FDE, in the order they're in.
+    pub fn fdes(&self) -> Result<Vec<Fde>, ElfError> {
+        Ok(self
+            .entries()?
+            .into_iter()
+            .filter_map(|entry| match entry {
+                FrameEntry::Fde(fde) => Some(fde),
+                _ => None,
+            })
+            .collect())
+    }
+
+    /// Finds the FDE for the code at `address` by going through all of them;
+    /// `EhFrameHdr::search` finds FDEs in .eh_frame more quickly.
+    pub fn find_fde(&self, address: u64) -> Result<Option<Fde>, ElfError> {
+        Ok(self
+            .fdes()?
+            .into_iter()
+            .find(|fde| fde.range.contains(&address)))
+    }
+
+    /// Reads the CIE at `offset` in the section.
+    pub fn cie_at(&self, offset: u64) -> Result<Cie, ElfError> {
+        match self.read_entry(offset)?.0 {
+            FrameEntry::Cie(cie) => Ok(cie),
+            _ => Err(self.invalid(offset, "it isn't a CIE")),
+        }
+    }
+
+    /// Reads the FDE at `offset` in the section.
+    pub fn fde_at(&self, offset: u64) -> Result<Fde, ElfError> {
+        match self.read_entry(offset)?.0 {
+            FrameEntry::Fde(fde) => Ok(fde),
+            _ => Err(self.invalid(offset, "it isn't an FDE")),
+        }
+    }
+
+    fn invalid(&self, offset: u64, reason: &str) -> ElfError {
+        ElfError::InvalidData {
+            what: self.section.name(),
+            offset,
+            reason: reason.to_owned(),
+        }
+    }
+
+    /// Reads the entry at `offset`, and returns it with the offset of the next.
+    fn read_entry(&self, offset: u64) -> Result<(FrameEntry, usize), ElfError> {
+        let mut reader = Reader::new(&self.data, self.section.name());
+        reader.seek(usize::try_from(offset).unwrap_or(usize::MAX));
+
+        let (length, offset_size) = reader.unit_length()?;
+        if length == 0 {
+            return Ok((FrameEntry::Terminator(offset), reader.offset()));
+        }
+
+        let mut entry = reader.split(length)?;
+        let next = reader.offset();
+
+        let id_offset = entry.offset() as u64;
+        let id = entry.uint(offset_size)?;
+
+        let is_cie = match self.section {
+            FrameSection::EhFrame => id == 0,
+            FrameSection::DebugFrame => id == u64::MAX >> (64 - 8 * offset_size),
+        };
+
+        let entry = match is_cie {
+            true => FrameEntry::Cie(self.read_cie(entry, offset, length, id, offset_size)?),
+            false => {
+                // In .eh_frame, the pointer counts back from where it is.
+                let cie_offset = match self.section {
+                    FrameSection::EhFrame => id_offset
+                        .checked_sub(id)
+                        .ok_or_else(|| self.invalid(offset, "its CIE pointer is out of range"))?,
+                    FrameSection::DebugFrame => id,
+                };
+                let cie = self.cie_at(cie_offset)?;
+
+                FrameEntry::Fde(self.read_fde(entry, offset, length, id, &cie)?)
+            }
+        };
+
+        Ok((entry, next))
+    }
+
+    fn read_cie(
+        &self,
+        mut reader: Reader,
+        offset: u64,
+        length: u64,
+        id: u64,
+        offset_size: usize,
+    ) -> Result<Cie, ElfError> {
+        let version = reader.u8()?;
+        if !matches!(version, 1 | 3 | 4) {
+            return Err(ElfError::UnsupportedVersion {
+                what: "CIE",
+                version: version as u16,
+            });
+        }
+
+        let augmentation = String::from_utf8_lossy(reader.cstr()?).into_owned();
+
+        // Version 4 gives the size of addresses, and of segment selectors
+        // (which no ELF platform uses).
+        let mut address_size = 8;
+        if version >= 4 {
+            address_size = reader.u8()?;
+            reader.u8()?;
+        }
+
+        let code_alignment_factor = reader.uleb128()?;
+        let data_alignment_factor = reader.sleb128()?;
+        let return_address_register = match version {
+            1 => reader.u8()? as u16,
+            _ => reader.uleb128()? as u16,
+        };
+
+        let mut cie = Cie {
+            offset,
+            length,
+            id,
+            offset_size,
+            version,
+            augmentation,
+            address_size,
+            code_alignment_factor,
+            data_alignment_factor,
+            return_address_register,
+            fde_encoding: DW_EH_PE_ABSPTR,
+            lsda_encoding: DW_EH_PE_OMIT,
+            personality: None,
+            is_signal_frame: false,
+            instructions: 0..0,
+        };
+
+        // With 'z', the augmentation data has a length, so that letters
+        // we don't know can be skipped.
+        if let Some(letters) = cie.augmentation.strip_prefix('z') {
+            let length = reader.uleb128()?;
+            let mut data = reader.split(length)?;
+
+            for letter in letters.chars() {
+                match letter {
+                    'R' => cie.fde_encoding = data.u8()?,
+                    'L' => cie.lsda_encoding = data.u8()?,
+                    'P' => {
+                        let encoding = data.u8()?;
+                        let pointer =
+                            read_pointer(&mut data, encoding, address_size, self.bases())?;
+                        cie.personality = Some(pointer);
+                    }
+                    'S' => cie.is_signal_frame = true,
+                    _ => break,
+                }
+            }
+        } else if cie.augmentation == "eh" {
+            // Old versions of gcc put the address of exception tables here.
+            reader.uint(address_size as usize)?;
+        } else if !cie.augmentation.is_empty() {
+            return Err(self.invalid(
+                offset,
+                &format!("the augmentation {:?} isn't supported", cie.augmentation),
+            ));
+        }
+
+        cie.instructions = reader.offset()..reader.end();
+        Ok(cie)
+    }
+
+    fn read_fde(
+        &self,
+        mut reader: Reader,
+        offset: u64,
+        length: u64,
+        cie_pointer: u64,
+        cie: &Cie,
+    ) -> Result<Fde, ElfError> {
+        let start = read_pointer(
+            &mut reader,
+            cie.fde_encoding,
+            cie.address_size,
+            self.bases(),
+        )?;
+
+        // The size is in the same format, but never relative.
+        let size = read_pointer(
+            &mut reader,
+            cie.fde_encoding & 0x0f,
+            cie.address_size,
+            self.bases(),
+        )?;
+
+        let mut lsda = None;
+        if cie.augmentation.starts_with('z') {
+            let length = reader.uleb128()?;
+            let mut data = reader.split(length)?;
+
+            if cie.lsda_encoding != DW_EH_PE_OMIT && length > 0 {
+                lsda = Some(read_pointer(
+                    &mut data,
+                    cie.lsda_encoding,
+                    cie.address_size,
+                    self.bases(),
+                )?);
+            }
+        }
+
+        Ok(Fde {
+            offset,
+            length,
+            cie_pointer,
+            cie_offset: cie.offset,
+            range: start..start.wrapping_add(size),
+            lsda,
+            instructions: reader.offset()..reader.end(),
+        })
+    }
+
+    /// The bytes of a CIE's or FDE's instructions.
+    pub fn instructions(&self, range: &Range<usize>) -> &[u8] {
+        &self.data[range.clone()]
+    }
+
+    /// Runs a CIE's initial instructions, for the rules that its FDEs start
+    /// from. The table has one row, from address 0.
+    pub fn initial_rules(&self, cie: &Cie) -> Result<UnwindTable, ElfError> {
+        self.execute(cie, &cie.instructions, None, 0..0)
+    }
+
+    /// Runs an FDE's instructions, for the rules at each of its addresses.
+    pub fn unwind_table(&self, fde: &Fde) -> Result<UnwindTable, ElfError> {
+        let cie = self.cie_at(fde.cie_offset)?;
+        let initial = self.initial_rules(&cie)?;
+
+        self.execute(&cie, &fde.instructions, Some(&initial), fde.range.clone())
+    }
+
+    fn execute(
+        &self,
+        cie: &Cie,
+        instructions: &Range<usize>,
+        initial: Option<&UnwindTable>,
+        range: Range<u64>,
+    ) -> Result<UnwindTable, ElfError> {
+        let mut reader = Reader::new(&self.data, self.section.name());
+        let reader = {
+            reader.seek(instructions.start);
+            reader.split((instructions.end - instructions.start) as u64)?
+        };
+
+        let bases = self.bases();
+
+        execute_instructions(reader, cie, initial, range, |reader| {
+            read_pointer(reader, cie.fde_encoding, cie.address_size, bases)
+        })
+    }
+}
+
+/// The contents of .eh_frame_hdr: where .eh_frame is, and a table of the
+/// start address of each FDE's code, sorted, for a binary search.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EhFrameHdr {
+    pub version: u8,
+    pub eh_frame_address: u64,
+
+    /// The start address of each FDE's code, with the address of the FDE.
+    pub table: Vec<(u64, u64)>,
+}
+
+impl EhFrameHdr {
+    /// Finds the address of the FDE whose code starts closest before
+    /// `address`. Its range still has to be checked, as there can be gaps.
+    pub fn search(&self, address: u64) -> Option<u64> {
+        let after = self.table.partition_point(|&(start, _)| start <= address);
+
+        Some(self.table.get(after.checked_sub(1)?)?.1)
+    }
+}
+
+/// Reads .eh_frame_hdr, which is loaded at `address`.
+pub fn read_eh_frame_hdr(bytes: &[u8], address: u64) -> Result<EhFrameHdr, ElfError> {
+    let mut reader = Reader::new(bytes, ".eh_frame_hdr");
+
+    let version = reader.u8()?;
+    if version != 1 {
+        return Err(ElfError::UnsupportedVersion {
+            what: ".eh_frame_hdr",
+            version: version as u16,
+        });
+    }
+
+    let eh_frame_encoding = reader.u8()?;
+    let count_encoding = reader.u8()?;
+    let table_encoding = reader.u8()?;
+
+    // Values in it may be relative to its start.
+    let bases = PointerBases {
+        section: address,
+        data: Some(address),
+    };
+
+    let eh_frame_address = read_pointer(&mut reader, eh_frame_encoding, 8, bases)?;
+
+    let mut table = vec![];
+    if count_encoding != DW_EH_PE_OMIT && table_encoding != DW_EH_PE_OMIT {
+        let count = read_pointer(&mut reader, count_encoding, 8, bases)?;
+
+        for _ in 0..count {
+            let start = read_pointer(&mut reader, table_encoding, 8, bases)?;
+            let fde = read_pointer(&mut reader, table_encoding, 8, bases)?;
+            table.push((start, fde));
+        }
+    }
+
+    Ok(EhFrameHdr {
+        version,
+        eh_frame_address,
+        table,
+    })
+}
@@ -0,0 +1,229 @@
+/// The debugging information of a whole file: every unit in .debug_info,
+/// with the sections that its attributes point into, so that references
+/// between entries can be followed and address ranges read.
+///
+use std::ops::Range;
+
+use crate::dwarf::ranges::*;
+use crate::dwarf::unit::*;
+use crate::dwarf::*;
+use crate::ElfError;
+
+/// The contents of the DWARF sections that .debug_info needs. Missing
+/// sections are empty.
+#[derive(Debug, Default, Clone)]
+pub struct DwarfSections {
+    pub info: Vec<u8>,
+    pub abbrev: Vec<u8>,
+    pub str: Vec<u8>,
+    pub line_str: Vec<u8>,
+    pub str_offsets: Vec<u8>,
+    pub addr: Vec<u8>,
+    pub rnglists: Vec<u8>,
+    pub loclists: Vec<u8>,
+
+    /// .debug_ranges, which .debug_rnglists replaced in version 5.
+    pub ranges: Vec<u8>,
+}
+
+/// A function with code, from a DW_TAG_subprogram entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Function {
+    /// Names may be on the declaration the entry points to, rather than
+    /// on the entry itself, as for C++ methods and inlined functions.
+    pub name: Option<String>,
+    pub linkage_name: Option<String>,
+
+    /// Where the code is, in order; usually there is only one range.
+    pub ranges: Vec<Range<u64>>,
+
+    pub decl_line: Option<u64>,
+    pub external: bool,
+
+    /// Where the entry is, in .debug_info.
+    pub offset: u64,
+}
+
+#[derive(Debug)]
+pub struct DebugInfo {
+    sections: DwarfSections,
+    units: Vec<Unit>,
+}
+
+/// How many declarations to follow when looking for an attribute, in
+/// case references loop.
+const MAX_REFERENCE_DEPTH: usize = 8;
+
+impl DebugInfo {
+    /// Reads every unit in .debug_info.
+    pub fn new(sections: DwarfSections) -> Result<DebugInfo, ElfError> {
+        let units = read_unit_headers(&sections.info)?
+            .iter()
+            .map(|header| read_unit(&sections, header))
+            .collect::<Result<_, _>>()?;
+
+        Ok(DebugInfo { sections, units })
+    }
+
+    pub fn sections(&self) -> &DwarfSections {
+        &self.sections
+    }
+
+    pub fn units(&self) -> &[Unit] {
+        &self.units
+    }
+
+    /// Finds the entry at `offset` in .debug_info, with its unit.
+    pub fn entry_at(&self, offset: u64) -> Option<(&Unit, &Entry)> {
+        let after = self
+            .units
+            .partition_point(|unit| unit.header.offset <= offset);
+        let unit = self.units.get(after.checked_sub(1)?)?;
+
+        Some((unit, unit.entry_at(offset)?))
+    }
+
+    /// Follows a reference attribute (such as DW_AT_type) of an entry.
+    pub fn referenced(&self, entry: &Entry, name: u64) -> Option<(&Unit, &Entry)> {
+        match entry.attribute(name)? {
+            AttributeValue::Reference(offset) => self.entry_at(*offset),
+            _ => None,
+        }
+    }
+
+    /// Looks for an attribute on an entry, and then on the declaration or the
+    /// abstract instance that it completes (through DW_AT_specification or
+    /// DW_AT_abstract_origin), where names and types usually are.
+    pub fn find_attribute<'a>(
+        &'a self,
+        mut entry: &'a Entry,
+        name: u64,
+    ) -> Option<&'a AttributeValue> {
+        for _ in 0..MAX_REFERENCE_DEPTH {
+            if let Some(value) = entry.attribute(name) {
+                return Some(value);
+            }
+
+            (_, entry) = self
+                .referenced(entry, DW_AT_SPECIFICATION)
+                .or_else(|| self.referenced(entry, DW_AT_ABSTRACT_ORIGIN))?;
+        }
+
+        None
+    }
+
+    pub fn name<'a>(&'a self, entry: &'a Entry) -> Option<&'a str> {
+        self.find_attribute(entry, DW_AT_NAME)?.as_str()
+    }
+
+    /// The mangled name of an entry, which only languages with overloading
+    /// (like C++ and Rust) give.
+    pub fn linkage_name<'a>(&'a self, entry: &'a Entry) -> Option<&'a str> {
+        self.find_attribute(entry, DW_AT_LINKAGE_NAME)
+            .or_else(|| self.find_attribute(entry, DW_AT_MIPS_LINKAGE_NAME))?
+            .as_str()
+    }
+
+    /// The address ranges of an entry's code: from DW_AT_low_pc and
+    /// DW_AT_high_pc, or from a range list. Empty when it has no code.
+    pub fn ranges(&self, unit: &Unit, entry: &Entry) -> Result<Vec<Range<u64>>, ElfError> {
+        let header = &unit.header;
+
+        if let Some(&AttributeValue::Address(low)) = entry.attribute(DW_AT_LOW_PC) {
+            // From version 4, high_pc may be the size instead of the end.
+            let high = match entry.attribute(DW_AT_HIGH_PC) {
+                Some(&AttributeValue::Address(high)) => high,
+                Some(value) => match value.as_u64() {
+                    Some(size) => low.wrapping_add(size),
+                    None => return Ok(vec![]),
+                },
+                None => return Ok(vec![]),
+            };
+
+            let mut ranges = vec![];
+            if low < high {
+                ranges.push(low..high);
+            }
+            return Ok(ranges);
+        }
+
+        let Some(offset) = entry
+            .attribute(DW_AT_RANGES)
+            .and_then(|value| value.as_u64())
+        else {
+            return Ok(vec![]);
+        };
+
+        // The lists are from the unit's base address, its low_pc.
+        let base_address = match unit.root().and_then(|root| root.attribute(DW_AT_LOW_PC)) {
+            Some(&AttributeValue::Address(base)) => base,
+            _ => 0,
+        };
+
+        let ranges = if header.version >= 5 {
+            read_range_list(
+                &self.sections.rnglists,
+                offset,
+                header.address_size,
+                base_address,
+                |index| self.address(unit, index),
+            )?
+        } else {
+            read_ranges(
+                &self.sections.ranges,
+                offset,
+                header.address_size,
+                base_address,
+            )?
+        };
+
+        Ok(ranges
+            .into_iter()
+            .filter(|range| range.start < range.end)
+            .collect())
+    }
+
+    /// Looks up an entry of the unit's table in .debug_addr.
+    fn address(&self, unit: &Unit, index: u64) -> Result<u64, ElfError> {
+        let base = TableBases::new(unit.root(), &unit.header).addr;
+        let size = unit.header.address_size as usize;
+
+        table_entry(&self.sections.addr, "Address table", base, index, size)
+    }
+
+    /// Every function with code, in order of address. Functions whose code a
+    /// linker discarded (left at address 0) aren't included.
+    pub fn functions(&self) -> Result<Vec<Function>, ElfError> {
+        let mut functions = vec![];
+
+        for unit in &self.units {
+            for entry in &unit.entries {
+                if entry.tag != DW_TAG_SUBPROGRAM {
+                    continue;
+                }
+
+                let mut ranges = self.ranges(unit, entry)?;
+                ranges.retain(|range| range.start != 0);
+                if ranges.is_empty() {
+                    continue;
+                }
+                ranges.sort_by_key(|range| range.start);
+
+                functions.push(Function {
+                    name: self.name(entry).map(str::to_owned),
+                    linkage_name: self.linkage_name(entry).map(str::to_owned),
+                    ranges,
+                    decl_line: self
+                        .find_attribute(entry, DW_AT_DECL_LINE)
+                        .and_then(|value| value.as_u64()),
+                    external: self.find_attribute(entry, DW_AT_EXTERNAL)
+                        == Some(&AttributeValue::Flag(true)),
+                    offset: entry.offset,
+                });
+            }
+        }
+
+        functions.sort_by_key(|function| function.ranges[0].start);
+        Ok(functions)
+    }
+}
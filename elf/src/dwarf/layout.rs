@@ -0,0 +1,423 @@
+/// Types, written as C declarations, and the memory layout of structures,
+/// classes, and unions: where each member is, and the holes and padding
+/// between them, as `pahole` shows.
+///
+use crate::dwarf::info::DebugInfo;
+use crate::dwarf::reader::Reader;
+use crate::dwarf::unit::*;
+use crate::dwarf::*;
+
+/// How deep a type can be (pointers to arrays of pointers...), in
+/// case references loop.
+const MAX_TYPE_DEPTH: usize = 32;
+
+/// A member of a structure, class, or union, or a base class.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Member {
+    /// The type, as it's written before the name: "int", "char *", or
+    /// "void" for a pointer to a function returning void.
+    pub type_name: String,
+
+    /// The name, with the rest of the declaration around it: "x",
+    /// "buffer[16]", or "(*callback)(int)". Empty for a base class or an
+    /// anonymous structure or union.
+    pub name: String,
+
+    /// Where the member is, and its size, in bits.
+    pub bit_offset: u64,
+    pub bit_size: u64,
+
+    /// The size of the member's type, which for a bit field
+    /// is the size of the unit that it's stored in.
+    pub byte_size: u64,
+
+    pub is_bit_field: bool,
+    pub is_base_class: bool,
+}
+
+impl Member {
+    pub fn byte_offset(&self) -> u64 {
+        self.bit_offset / 8
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Layout {
+    /// "struct", "class", or "union".
+    pub kind: &'static str,
+    pub name: Option<String>,
+    pub size: u64,
+    pub members: Vec<Member>,
+
+    /// Where the type's entry is, in .debug_info.
+    pub offset: u64,
+}
+
+/// Unused space before a member.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Hole {
+    /// The index of the member after the hole.
+    pub before: usize,
+    pub bits: u64,
+}
+
+impl Layout {
+    /// The holes between members. A union's members all start
+    /// at the beginning, so it has none.
+    pub fn holes(&self) -> Vec<Hole> {
+        let mut holes = vec![];
+        if self.kind == "union" {
+            return holes;
+        }
+
+        let mut end = 0;
+        for (index, member) in self.members.iter().enumerate() {
+            if member.bit_offset > end {
+                holes.push(Hole {
+                    before: index,
+                    bits: member.bit_offset - end,
+                });
+            }
+            end = end.max(member.bit_offset.saturating_add(member.bit_size));
+        }
+
+        holes
+    }
+
+    /// The unused bits at the end, after the last member.
+    pub fn padding_bits(&self) -> u64 {
+        let end = self
+            .members
+            .iter()
+            .map(|member| member.bit_offset.saturating_add(member.bit_size))
+            .max()
+            .unwrap_or(0);
+
+        self.size.saturating_mul(8).saturating_sub(end)
+    }
+}
+
+/// Joins a type name to the declaration that follows it.
+fn join(type_name: &str, declarator: &str) -> String {
+    if declarator.is_empty() {
+        type_name.to_owned()
+    } else {
+        format!("{type_name} {declarator}")
+    }
+}
+
+/// Reads the offset of a member from its DW_AT_data_member_location, which
+/// is a constant, or (in DWARF 2) a `DW_OP_plus_uconst` expression.
+fn member_location(member: &Entry) -> Option<u64> {
+    match member.attribute(DW_AT_DATA_MEMBER_LOCATION)? {
+        AttributeValue::Block(bytes) | AttributeValue::Expression(bytes) => {
+            let mut reader = Reader::new(bytes, "Member location");
+            if reader.u8().ok()? != DW_OP_PLUS_UCONST {
+                return None;
+            }
+            reader.uleb128().ok()
+        }
+        value => value.as_u64(),
+    }
+}
+
+impl DebugInfo {
+    fn type_of(&self, entry: &Entry) -> Option<(&Unit, &Entry)> {
+        self.referenced(entry, DW_AT_TYPE)
+    }
+
+    /// Writes the type `ty` (or void, for None) as a C declaration of
+    /// `declarator`: `int *`, `char name[16]`, `void (*done)(void)`...
+    pub fn declaration(&self, ty: Option<(&Unit, &Entry)>, declarator: &str) -> String {
+        self.declare(ty, declarator.to_owned(), 0)
+    }
+
+    fn declare(&self, ty: Option<(&Unit, &Entry)>, declarator: String, depth: usize) -> String {
+        let Some((unit, entry)) = ty else {
+            return join("void", &declarator);
+        };
+        if depth > MAX_TYPE_DEPTH {
+            return join("?", &declarator);
+        }
+
+        let target = self.type_of(entry);
+        let target_tag = target.map(|(_, target)| target.tag);
+
+        match entry.tag {
+            DW_TAG_POINTER_TYPE
+            | DW_TAG_PTR_TO_MEMBER_TYPE
+            | DW_TAG_REFERENCE_TYPE
+            | DW_TAG_RVALUE_REFERENCE_TYPE => {
+                let sigil = match entry.tag {
+                    DW_TAG_REFERENCE_TYPE => "&",
+                    DW_TAG_RVALUE_REFERENCE_TYPE => "&&",
+                    _ => "*",
+                };
+
+                // A pointer to an array or a function needs parentheses.
+                let declarator = match target_tag {
+                    Some(DW_TAG_ARRAY_TYPE | DW_TAG_SUBROUTINE_TYPE) => {
+                        format!("({sigil}{declarator})")
+                    }
+                    _ => format!("{sigil}{declarator}"),
+                };
+                self.declare(target, declarator, depth + 1)
+            }
+
+            DW_TAG_CONST_TYPE | DW_TAG_VOLATILE_TYPE | DW_TAG_RESTRICT_TYPE
+            | DW_TAG_ATOMIC_TYPE => {
+                let qualifier = match entry.tag {
+                    DW_TAG_CONST_TYPE => "const",
+                    DW_TAG_VOLATILE_TYPE => "volatile",
+                    DW_TAG_RESTRICT_TYPE => "restrict",
+                    _ => "_Atomic",
+                };
+
+                // A qualified pointer has the qualifier after the `*`.
+                match target_tag {
+                    Some(DW_TAG_POINTER_TYPE | DW_TAG_REFERENCE_TYPE) => {
+                        self.declare(target, join(qualifier, &declarator), depth + 1)
+                    }
+                    _ => format!(
+                        "{qualifier} {}",
+                        self.declare(target, declarator, depth + 1)
+                    ),
+                }
+            }
+
+            DW_TAG_ARRAY_TYPE => {
+                let mut declarator = declarator;
+                for subrange in unit.children(entry) {
+                    if subrange.tag != DW_TAG_SUBRANGE_TYPE {
+                        continue;
+                    }
+                    match array_count(subrange) {
+                        Some(count) => declarator.push_str(&format!("[{count}]")),
+                        None => declarator.push_str("[]"),
+                    }
+                }
+                self.declare(target, declarator, depth + 1)
+            }
+
+            DW_TAG_SUBROUTINE_TYPE => {
+                let mut parameters = vec![];
+                for parameter in unit.children(entry) {
+                    match parameter.tag {
+                        DW_TAG_FORMAL_PARAMETER => parameters.push(self.declare(
+                            self.type_of(parameter),
+                            String::new(),
+                            depth + 1,
+                        )),
+                        DW_TAG_UNSPECIFIED_PARAMETERS => parameters.push("...".to_owned()),
+                        _ => {}
+                    }
+                }
+                if parameters.is_empty() && entry.has_flag(DW_AT_PROTOTYPED) {
+                    parameters.push("void".to_owned());
+                }
+
+                let declarator = format!("{declarator}({})", parameters.join(", "));
+                self.declare(target, declarator, depth + 1)
+            }
+
+            DW_TAG_STRUCTURE_TYPE
+            | DW_TAG_CLASS_TYPE
+            | DW_TAG_UNION_TYPE
+            | DW_TAG_ENUMERATION_TYPE => {
+                let keyword = match entry.tag {
+                    DW_TAG_STRUCTURE_TYPE => "struct",
+                    DW_TAG_CLASS_TYPE => "class",
+                    DW_TAG_UNION_TYPE => "union",
+                    _ => "enum",
+                };
+                let name = entry.name().unwrap_or("{...}");
+
+                join(&format!("{keyword} {name}"), &declarator)
+            }
+
+            // Base types, typedefs, and anything else with a name.
+            _ => join(entry.name().unwrap_or("?"), &declarator),
+        }
+    }
+
+    /// The size of a type in bytes, if it's known.
+    pub fn type_size(&self, unit: &Unit, entry: &Entry) -> Option<u64> {
+        self.size_of(unit, entry, 0)
+    }
+
+    fn size_of(&self, unit: &Unit, entry: &Entry, depth: usize) -> Option<u64> {
+        if let Some(size) = entry
+            .attribute(DW_AT_BYTE_SIZE)
+            .and_then(|size| size.as_u64())
+        {
+            return Some(size);
+        }
+        if depth > MAX_TYPE_DEPTH {
+            return None;
+        }
+
+        match entry.tag {
+            DW_TAG_POINTER_TYPE
+            | DW_TAG_PTR_TO_MEMBER_TYPE
+            | DW_TAG_REFERENCE_TYPE
+            | DW_TAG_RVALUE_REFERENCE_TYPE => Some(unit.header.address_size as u64),
+
+            DW_TAG_ARRAY_TYPE => {
+                let (target_unit, target) = self.type_of(entry)?;
+                let mut size = self.size_of(target_unit, target, depth + 1)?;
+
+                for subrange in unit.children(entry) {
+                    if subrange.tag == DW_TAG_SUBRANGE_TYPE {
+                        size = size.checked_mul(array_count(subrange)?)?;
+                    }
+                }
+                Some(size)
+            }
+
+            DW_TAG_TYPEDEF | DW_TAG_CONST_TYPE | DW_TAG_VOLATILE_TYPE | DW_TAG_RESTRICT_TYPE
+            | DW_TAG_ATOMIC_TYPE => {
+                let (target_unit, target) = self.type_of(entry)?;
+                self.size_of(target_unit, target, depth + 1)
+            }
+
+            _ => None,
+        }
+    }
+
+    /// Works out the layout of a structure, class, or union type. There's
+    /// none for other entries, or for declarations of types defined elsewhere.
+    pub fn layout(&self, unit: &Unit, entry: &Entry) -> Option<Layout> {
+        let kind = match entry.tag {
+            DW_TAG_STRUCTURE_TYPE => "struct",
+            DW_TAG_CLASS_TYPE => "class",
+            DW_TAG_UNION_TYPE => "union",
+            _ => return None,
+        };
+        if entry.has_flag(DW_AT_DECLARATION) {
+            return None;
+        }
+
+        let mut members = vec![];
+
+        for child in unit.children(entry) {
+            if !matches!(child.tag, DW_TAG_MEMBER | DW_TAG_INHERITANCE) {
+                continue;
+            }
+
+            // Static members (before DWARF 5, when they became variables)
+            // are declarations without a location.
+            let location = member_location(child);
+            if location.is_none()
+                && (child.has_flag(DW_AT_DECLARATION) || child.has_flag(DW_AT_EXTERNAL))
+            {
+                continue;
+            }
+            let location = location.unwrap_or(0);
+
+            let ty = self.type_of(child);
+            let byte_size = child
+                .attribute(DW_AT_BYTE_SIZE)
+                .and_then(|size| size.as_u64())
+                .or_else(|| ty.and_then(|(unit, ty)| self.type_size(unit, ty)))
+                .unwrap_or(0);
+
+            let bit_size = child
+                .attribute(DW_AT_BIT_SIZE)
+                .and_then(|size| size.as_u64());
+            let bit_offset = match (bit_size, child.attribute(DW_AT_DATA_BIT_OFFSET)) {
+                (Some(_), Some(offset)) => offset.as_u64().unwrap_or(0),
+
+                // Before DWARF 4, a bit field's offset was from the most
+                // significant bit of its storage unit, which on a little
+                // endian machine is at its end.
+                (Some(bits), None) => {
+                    let from_top = child
+                        .attribute(DW_AT_BIT_OFFSET)
+                        .and_then(|offset| offset.as_u64())
+                        .unwrap_or(0);
+                    let end = location.saturating_add(byte_size).saturating_mul(8);
+                    end.saturating_sub(from_top.saturating_add(bits))
+                }
+
+                (None, _) => location.saturating_mul(8),
+            };
+
+            let declaration = self.declaration(ty, "\0");
+            let (type_name, rest) = declaration.split_once('\0').unwrap_or((&declaration, ""));
+
+            // Keep `(*` of a function pointer with the name, as in `void (*done)(void)`.
+            let (type_name, prefix) = match type_name.rfind(" (") {
+                Some(at) => (&type_name[..at], &type_name[at + 1..]),
+                None => (type_name, ""),
+            };
+
+            let name = match child.tag {
+                DW_TAG_INHERITANCE => String::new(),
+                _ => format!("{prefix}{}{rest}", child.name().unwrap_or("")),
+            };
+
+            members.push(Member {
+                type_name: type_name.trim_end().to_owned(),
+                name,
+                bit_offset,
+                bit_size: bit_size.unwrap_or(byte_size.saturating_mul(8)),
+                byte_size,
+                is_bit_field: bit_size.is_some(),
+                is_base_class: child.tag == DW_TAG_INHERITANCE,
+            });
+        }
+
+        // Rust (among others) reorders fields, but lists them as declared.
+        if kind != "union" {
+            members.sort_by_key(|member| member.bit_offset);
+        }
+
+        Some(Layout {
+            kind,
+            name: entry.name().map(str::to_owned),
+            size: entry.attribute(DW_AT_BYTE_SIZE)?.as_u64()?,
+            members,
+            offset: entry.offset,
+        })
+    }
+
+    /// The layouts of every named structure, class, and union. A type that's
+    /// defined in more than one unit (from a header) is only listed once.
+    pub fn layouts(&self) -> Vec<Layout> {
+        let mut layouts: Vec<Layout> = vec![];
+
+        for unit in self.units() {
+            for entry in &unit.entries {
+                if entry.name().is_none() {
+                    continue;
+                }
+                let Some(layout) = self.layout(unit, entry) else {
+                    continue;
+                };
+
+                let seen = layouts.iter().any(|other| {
+                    (other.kind, &other.name, other.size)
+                        == (layout.kind, &layout.name, layout.size)
+                });
+                if !seen {
+                    layouts.push(layout);
+                }
+            }
+        }
+
+        layouts
+    }
+}
+
+/// The number of elements in an array dimension, from
+/// DW_AT_count or DW_AT_upper_bound (which counts from 0).
+fn array_count(subrange: &Entry) -> Option<u64> {
+    if let Some(count) = subrange.attribute(DW_AT_COUNT) {
+        return count.as_u64();
+    }
+
+    subrange
+        .attribute(DW_AT_UPPER_BOUND)?
+        .as_u64()
+        .and_then(|bound| bound.checked_add(1))
+}
@@ -0,0 +1,658 @@
+/// DWARF line-number programs (.debug_line), which map machine code back to
+/// source lines. Each compilation unit has a program for a state machine that
+/// produces a table of rows, each giving the file, line, and column of the code
+/// from its address up to the next row's. Versions 2 to 5 are read, in both
+/// the 32-bit and the 64-bit DWARF formats.
+///
+use std::ops::Range;
+
+use crate::dwarf::reader::Reader;
+use crate::dwarf::*;
+use crate::parse::strings::StringTable;
+use crate::ElfError;
+
+// Standard opcodes.
+pub const DW_LNS_COPY: u8 = 1;
+pub const DW_LNS_ADVANCE_PC: u8 = 2;
+pub const DW_LNS_ADVANCE_LINE: u8 = 3;
+pub const DW_LNS_SET_FILE: u8 = 4;
+pub const DW_LNS_SET_COLUMN: u8 = 5;
+pub const DW_LNS_NEGATE_STMT: u8 = 6;
+pub const DW_LNS_SET_BASIC_BLOCK: u8 = 7;
+pub const DW_LNS_CONST_ADD_PC: u8 = 8;
+pub const DW_LNS_FIXED_ADVANCE_PC: u8 = 9;
+pub const DW_LNS_SET_PROLOGUE_END: u8 = 10;
+pub const DW_LNS_SET_EPILOGUE_BEGIN: u8 = 11;
+pub const DW_LNS_SET_ISA: u8 = 12;
+
+// Extended opcodes (after a 0 byte and a length).
+pub const DW_LNE_END_SEQUENCE: u8 = 1;
+pub const DW_LNE_SET_ADDRESS: u8 = 2;
+pub const DW_LNE_DEFINE_FILE: u8 = 3;
+pub const DW_LNE_SET_DISCRIMINATOR: u8 = 4;
+
+// Content types of directory and file entries, in version 5.
+pub const DW_LNCT_PATH: u64 = 1;
+pub const DW_LNCT_DIRECTORY_INDEX: u64 = 2;
+pub const DW_LNCT_TIMESTAMP: u64 = 3;
+pub const DW_LNCT_SIZE: u64 = 4;
+pub const DW_LNCT_MD5: u64 = 5;
+
+#[derive(Debug, Clone)]
+pub struct LineProgramHeader {
+    /// Where the program's unit starts, in .debug_line.
+    pub offset: u64,
+
+    pub version: u16,
+
+    /// 4, or 8 in the 64-bit DWARF format.
+    pub offset_size: usize,
+
+    /// Only recorded from version 5; 0 before that.
+    pub address_size: u8,
+
+    pub minimum_instruction_length: u8,
+    pub maximum_operations_per_instruction: u8,
+    pub default_is_stmt: bool,
+    pub line_base: i8,
+    pub line_range: u8,
+    pub opcode_base: u8,
+
+    /// The number of operands of each standard opcode, from 1.
+    pub standard_opcode_lengths: Vec<u8>,
+
+    /// In version 5 the first directory is the compilation directory. Before
+    /// that, it's directory 0, but isn't listed, so this starts at directory 1.
+    pub include_directories: Vec<String>,
+
+    /// In version 5 these are numbered from 0, and before that from 1.
+    pub file_names: Vec<FileEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileEntry {
+    pub path: String,
+    pub directory_index: u64,
+
+    /// Zero when unknown, as they usually are.
+    pub timestamp: u64,
+    pub size: u64,
+
+    pub md5: Option<[u8; 16]>,
+}
+
+/// A row of the line table: the registers of the state machine when it was added.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineRow {
+    pub address: u64,
+
+    /// The operation within a VLIW instruction; always 0 elsewhere.
+    pub op_index: u64,
+
+    pub file: u64,
+    pub line: u64,
+
+    /// From 1; 0 means the whole line.
+    pub column: u64,
+
+    pub is_stmt: bool,
+    pub basic_block: bool,
+
+    /// The first address after a sequence of instructions; this row
+    /// only marks the end, and doesn't give a line for that address.
+    pub end_sequence: bool,
+
+    pub prologue_end: bool,
+    pub epilogue_begin: bool,
+    pub isa: u64,
+    pub discriminator: u64,
+}
+
+impl LineRow {
+    fn new(default_is_stmt: bool) -> LineRow {
+        LineRow {
+            address: 0,
+            op_index: 0,
+            file: 1,
+            line: 1,
+            column: 0,
+            is_stmt: default_is_stmt,
+            basic_block: false,
+            end_sequence: false,
+            prologue_end: false,
+            epilogue_begin: false,
+            isa: 0,
+            discriminator: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LineProgram {
+    pub header: LineProgramHeader,
+    pub rows: Vec<LineRow>,
+
+    /// The directory of the unit, from its DW_AT_comp_dir in .debug_info,
+    /// which version 2 to 4 programs don't record themselves.
+    pub compilation_directory: Option<String>,
+}
+
+/// The string sections that version 5 headers refer to.
+#[derive(Clone, Copy, Default)]
+pub struct LineStrings<'a> {
+    /// .debug_line_str, for DW_FORM_line_strp.
+    pub line_str: StringTable<'a>,
+
+    /// .debug_str, for DW_FORM_strp.
+    pub str: StringTable<'a>,
+}
+
+// ------------------
+// Program headers.
+
+/// Joins a file name to its directory, unless the name is already absolute.
+fn join_path(directory: &str, path: &str) -> String {
+    if path.starts_with('/') || directory.is_empty() {
+        path.to_owned()
+    } else {
+        format!("{}/{path}", directory.trim_end_matches('/'))
+    }
+}
+
+/// A value in a version 5 directory or file entry.
+enum EntryValue<'a> {
+    String(String),
+    Number(u64),
+    Block(&'a [u8]),
+}
+
+fn read_entry_value<'a>(
+    reader: &mut Reader<'a>,
+    form: u64,
+    offset_size: usize,
+    strings: &LineStrings,
+) -> Result<EntryValue<'a>, ElfError> {
+    let offset = reader.offset();
+
+    let string = |table: &StringTable, string_offset: u64| {
+        table
+            .get_lossy(string_offset as usize)
+            .map(|string| EntryValue::String(string.into_owned()))
+            .map_err(|e| e.reading("Line program file name"))
+    };
+
+    match form {
+        DW_FORM_STRING => Ok(EntryValue::String(
+            String::from_utf8_lossy(reader.cstr()?).into_owned(),
+        )),
+        DW_FORM_LINE_STRP => string(&strings.line_str, reader.uint(offset_size)?),
+        DW_FORM_STRP => string(&strings.str, reader.uint(offset_size)?),
+
+        DW_FORM_UDATA => Ok(EntryValue::Number(reader.uleb128()?)),
+        DW_FORM_DATA1 => Ok(EntryValue::Number(reader.uint(1)?)),
+        DW_FORM_DATA2 => Ok(EntryValue::Number(reader.uint(2)?)),
+        DW_FORM_DATA4 => Ok(EntryValue::Number(reader.uint(4)?)),
+        DW_FORM_DATA8 => Ok(EntryValue::Number(reader.uint(8)?)),
+
+        DW_FORM_DATA16 => Ok(EntryValue::Block(reader.bytes(16)?)),
+        DW_FORM_BLOCK => {
+            let len = reader.uleb128()?;
+            Ok(EntryValue::Block(
+                reader.bytes(usize::try_from(len).unwrap_or(usize::MAX))?,
+            ))
+        }
+
+        _ => Err(reader.invalid(
+            offset,
+            format!("form {form:#x} isn't supported in a line program header"),
+        )),
+    }
+}
+
+/// Reads a version 5 list of directory or file entries: first the
+/// format of each entry, then the entries.
+fn read_entries(
+    reader: &mut Reader,
+    offset_size: usize,
+    strings: &LineStrings,
+) -> Result<Vec<FileEntry>, ElfError> {
+    let format_count = reader.u8()?;
+    let mut format = vec![];
+
+    for _ in 0..format_count {
+        format.push((reader.uleb128()?, reader.uleb128()?));
+    }
+
+    let count = reader.uleb128()?;
+    let mut entries = vec![];
+
+    for _ in 0..count {
+        let mut entry = FileEntry {
+            path: String::new(),
+            directory_index: 0,
+            timestamp: 0,
+            size: 0,
+            md5: None,
+        };
+
+        for &(content_type, form) in &format {
+            let value = read_entry_value(reader, form, offset_size, strings)?;
+
+            match (content_type, value) {
+                (DW_LNCT_PATH, EntryValue::String(path)) => entry.path = path,
+                (DW_LNCT_DIRECTORY_INDEX, EntryValue::Number(index)) => {
+                    entry.directory_index = index
+                }
+                (DW_LNCT_TIMESTAMP, EntryValue::Number(time)) => entry.timestamp = time,
+                (DW_LNCT_SIZE, EntryValue::Number(size)) => entry.size = size,
+                (DW_LNCT_MD5, EntryValue::Block(md5)) => entry.md5 = md5.try_into().ok(),
+
+                // Vendor-defined content, or content in an unexpected form.
+                _ => {}
+            }
+        }
+
+        entries.push(entry);
+    }
+
+    Ok(entries)
+}
+
+/// Reads a version 2 to 4 file entry, after its name: the
+/// directory index, modification time, and size.
+fn read_file_entry(reader: &mut Reader, path: &[u8]) -> Result<FileEntry, ElfError> {
+    Ok(FileEntry {
+        path: String::from_utf8_lossy(path).into_owned(),
+        directory_index: reader.uleb128()?,
+        timestamp: reader.uleb128()?,
+        size: reader.uleb128()?,
+        md5: None,
+    })
+}
+
+/// Reads the header of the line program whose unit `reader` holds (after
+/// its length). Leaves `reader` at the start of the program.
+fn read_header(
+    reader: &mut Reader,
+    offset: usize,
+    offset_size: usize,
+    strings: &LineStrings,
+) -> Result<LineProgramHeader, ElfError> {
+    let version = reader.u16()?;
+    if !(2..=5).contains(&version) {
+        return Err(ElfError::UnsupportedVersion {
+            what: "Line program",
+            version,
+        });
+    }
+
+    let mut address_size = 0;
+    if version >= 5 {
+        address_size = reader.u8()?;
+        let _segment_selector_size = reader.u8()?;
+    }
+
+    let header_length = reader.uint(offset_size)?;
+    let program_start = reader.offset() as u64 + header_length;
+
+    let minimum_instruction_length = reader.u8()?;
+    let maximum_operations_per_instruction = if version >= 4 { reader.u8()? } else { 1 };
+    let default_is_stmt = reader.u8()? != 0;
+    let line_base = reader.i8()?;
+    let line_range = reader.u8()?;
+    let opcode_base = reader.u8()?;
+
+    if line_range == 0 {
+        return Err(reader.invalid(offset, "the line range is 0".to_owned()));
+    }
+
+    let standard_opcode_lengths = reader
+        .bytes(opcode_base.saturating_sub(1) as usize)?
+        .to_vec();
+
+    let mut include_directories = vec![];
+    let mut file_names = vec![];
+
+    if version >= 5 {
+        include_directories = read_entries(reader, offset_size, strings)?
+            .into_iter()
+            .map(|entry| entry.path)
+            .collect();
+        file_names = read_entries(reader, offset_size, strings)?;
+    } else {
+        loop {
+            let directory = reader.cstr()?;
+            if directory.is_empty() {
+                break;
+            }
+            include_directories.push(String::from_utf8_lossy(directory).into_owned());
+        }
+
+        loop {
+            let path = reader.cstr()?;
+            if path.is_empty() {
+                break;
+            }
+            file_names.push(read_file_entry(reader, path)?);
+        }
+    }
+
+    // There may be fields we don't know about before the program.
+    reader.seek(usize::try_from(program_start).unwrap_or(usize::MAX));
+
+    Ok(LineProgramHeader {
+        offset: offset as u64,
+        version,
+        offset_size,
+        address_size,
+        minimum_instruction_length,
+        maximum_operations_per_instruction,
+        default_is_stmt,
+        line_base,
+        line_range,
+        opcode_base,
+        standard_opcode_lengths,
+        include_directories,
+        file_names,
+    })
+}
+
+// ------------------
+// The state machine.
+
+/// Runs the line program in `reader`, returning the rows it produces.
+/// `file_names` gets any files that DW_LNE_define_file adds.
+fn run_program(
+    reader: &mut Reader,
+    header: &LineProgramHeader,
+    file_names: &mut Vec<FileEntry>,
+) -> Result<Vec<LineRow>, ElfError> {
+    let mut rows = vec![];
+    let mut row = LineRow::new(header.default_is_stmt);
+
+    let max_ops = header.maximum_operations_per_instruction.max(1) as u64;
+    let min_length = header.minimum_instruction_length as u64;
+
+    // Advances the address and op_index by `advance` operations.
+    let advance_pc = |row: &mut LineRow, advance: u64| {
+        if max_ops == 1 {
+            row.address = row.address.wrapping_add(min_length.wrapping_mul(advance));
+        } else {
+            let ops = row.op_index.wrapping_add(advance);
+            row.address = row
+                .address
+                .wrapping_add(min_length.wrapping_mul(ops / max_ops));
+            row.op_index = ops % max_ops;
+        }
+    };
+
+    // Adds a row, and resets the registers that only apply to one row.
+    let emit = |rows: &mut Vec<LineRow>, row: &mut LineRow| {
+        rows.push(row.clone());
+
+        row.basic_block = false;
+        row.prologue_end = false;
+        row.epilogue_begin = false;
+        row.discriminator = 0;
+    };
+
+    while !reader.is_empty() {
+        let offset = reader.offset();
+        let opcode = reader.u8()?;
+
+        if opcode >= header.opcode_base {
+            let adjusted = (opcode - header.opcode_base) as u64;
+            let line_range = header.line_range as u64;
+
+            advance_pc(&mut row, adjusted / line_range);
+            row.line = row
+                .line
+                .wrapping_add_signed(header.line_base as i64 + (adjusted % line_range) as i64);
+            emit(&mut rows, &mut row);
+
+            continue;
+        }
+
+        match opcode {
+            0 => {
+                let len = reader.uleb128()?;
+                let mut extended = reader.split(len)?;
+                if len == 0 {
+                    continue;
+                }
+
+                match extended.u8()? {
+                    DW_LNE_END_SEQUENCE => {
+                        row.end_sequence = true;
+                        emit(&mut rows, &mut row);
+                        row = LineRow::new(header.default_is_stmt);
+                    }
+                    DW_LNE_SET_ADDRESS => {
+                        row.address = extended.uint(len as usize - 1)?;
+                        row.op_index = 0;
+                    }
+                    DW_LNE_DEFINE_FILE => {
+                        let path = extended.cstr()?;
+                        file_names.push(read_file_entry(&mut extended, path)?);
+                    }
+                    DW_LNE_SET_DISCRIMINATOR => row.discriminator = extended.uleb128()?,
+
+                    // Other extended opcodes are skipped, with their operands.
+                    _ => {}
+                }
+            }
+
+            DW_LNS_COPY => emit(&mut rows, &mut row),
+            DW_LNS_ADVANCE_PC => {
+                let advance = reader.uleb128()?;
+                advance_pc(&mut row, advance);
+            }
+            DW_LNS_ADVANCE_LINE => row.line = row.line.wrapping_add_signed(reader.sleb128()?),
+            DW_LNS_SET_FILE => row.file = reader.uleb128()?,
+            DW_LNS_SET_COLUMN => row.column = reader.uleb128()?,
+            DW_LNS_NEGATE_STMT => row.is_stmt = !row.is_stmt,
+            DW_LNS_SET_BASIC_BLOCK => row.basic_block = true,
+            DW_LNS_CONST_ADD_PC => {
+                let adjusted = (255 - header.opcode_base) as u64;
+                advance_pc(&mut row, adjusted / header.line_range as u64);
+            }
+            DW_LNS_FIXED_ADVANCE_PC => {
+                row.address = row.address.wrapping_add(reader.u16()? as u64);
+                row.op_index = 0;
+            }
+            DW_LNS_SET_PROLOGUE_END => row.prologue_end = true,
+            DW_LNS_SET_EPILOGUE_BEGIN => row.epilogue_begin = true,
+            DW_LNS_SET_ISA => row.isa = reader.uleb128()?,
+
+            // An opcode from a later version, or a vendor's: the header
+            // says how many operands to skip.
+            _ => {
+                let operands = header
+                    .standard_opcode_lengths
+                    .get(opcode as usize - 1)
+                    .ok_or_else(|| reader.invalid(offset, format!("unknown opcode {opcode}")))?;
+
+                for _ in 0..*operands {
+                    reader.uleb128()?;
+                }
+            }
+        }
+    }
+
+    Ok(rows)
+}
+
+/// Reads every line program in a .debug_line section, with the contents of
+/// the string sections that version 5 headers refer to (which may be empty).
+pub fn read_line_programs(
+    debug_line: &[u8],
+    strings: &LineStrings,
+) -> Result<Vec<LineProgram>, ElfError> {
+    let mut reader = Reader::new(debug_line, "Line program");
+    let mut programs = vec![];
+
+    while !reader.is_empty() {
+        let offset = reader.offset();
+        let (length, offset_size) = reader.unit_length()?;
+        let mut unit = reader.split(length)?;
+
+        let mut header = read_header(&mut unit, offset, offset_size, strings)?;
+        let mut file_names = header.file_names.clone();
+
+        let rows = run_program(&mut unit, &header, &mut file_names)?;
+        header.file_names = file_names;
+
+        programs.push(LineProgram {
+            header,
+            rows,
+            compilation_directory: None,
+        });
+    }
+
+    Ok(programs)
+}
+
+impl LineProgram {
+    /// The path of the file with the given number in the rows, joined to its
+    /// directory. Version 2 to 4 programs don't record the compilation
+    /// directory, so without `compilation_directory` set, paths relative
+    /// to it stay relative.
+    pub fn file_path(&self, file: u64) -> Option<String> {
+        let header = &self.header;
+
+        if header.version >= 5 {
+            let entry = header.file_names.get(file as usize)?;
+            let directory = header
+                .include_directories
+                .get(entry.directory_index as usize)
+                .map_or("", |directory| directory.as_str());
+
+            // A directory other than the first may be relative to the first.
+            let compilation_directory = header.include_directories.first();
+            let directory = match compilation_directory {
+                Some(base) if entry.directory_index != 0 => join_path(base, directory),
+                _ => directory.to_owned(),
+            };
+
+            return Some(join_path(&directory, &entry.path));
+        }
+
+        let entry = header.file_names.get((file as usize).checked_sub(1)?)?;
+        let compilation_directory = self.compilation_directory.as_deref().unwrap_or("");
+        let directory = match entry.directory_index {
+            0 => compilation_directory,
+            index => header
+                .include_directories
+                .get(index as usize - 1)
+                .map_or("", |directory| directory.as_str()),
+        };
+
+        let directory = join_path(compilation_directory, directory);
+        Some(join_path(&directory, &entry.path))
+    }
+}
+
+// --------------------
+// Looking up addresses.
+
+/// Where an address is in the source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceLocation {
+    pub file: String,
+    pub line: u64,
+
+    /// From 1, or 0 when the compiler didn't say.
+    pub column: u64,
+
+    /// Tells apart blocks of code on the same line (such as the parts
+    /// of a `for`), or 0 for the only one.
+    pub discriminator: u64,
+}
+
+/// The rows of a sequence: instructions at consecutive addresses,
+/// from `start` up to (but not including) `end`.
+#[derive(Debug)]
+struct Sequence {
+    start: u64,
+    end: u64,
+    program: usize,
+    rows: Range<usize>,
+}
+
+/// The line programs of a file, indexed by address for lookups.
+#[derive(Debug)]
+pub struct LineTable {
+    programs: Vec<LineProgram>,
+
+    /// Sorted by start address.
+    sequences: Vec<Sequence>,
+}
+
+impl LineTable {
+    pub fn new(programs: Vec<LineProgram>) -> LineTable {
+        let mut sequences = vec![];
+
+        for (index, program) in programs.iter().enumerate() {
+            let mut first = 0;
+
+            for (i, row) in program.rows.iter().enumerate() {
+                if !row.end_sequence {
+                    continue;
+                }
+
+                // Linkers leave the sequences of discarded code at address 0.
+                let start = program.rows[first].address;
+                if start < row.address && start != 0 {
+                    sequences.push(Sequence {
+                        start,
+                        end: row.address,
+                        program: index,
+                        rows: first..i,
+                    });
+                }
+                first = i + 1;
+            }
+        }
+
+        sequences.sort_by_key(|sequence| sequence.start);
+
+        LineTable {
+            programs,
+            sequences,
+        }
+    }
+
+    pub fn programs(&self) -> &[LineProgram] {
+        &self.programs
+    }
+
+    /// Finds the file, line, and column of the code at `address`.
+    pub fn source_location(&self, address: u64) -> Option<SourceLocation> {
+        let after = self
+            .sequences
+            .partition_point(|sequence| sequence.start <= address);
+        let start = self.sequences[after.checked_sub(1)?].start;
+
+        // Code can be described by more than one unit (when a linker keeps the
+        // debug info of duplicate functions), and the first unit wins.
+        let first = self
+            .sequences
+            .partition_point(|sequence| sequence.start < start);
+        let sequence = &self.sequences[first];
+
+        if address >= sequence.end {
+            return None;
+        }
+
+        let program = &self.programs[sequence.program];
+        let rows = &program.rows[sequence.rows.clone()];
+        let row = &rows[rows.partition_point(|row| row.address <= address) - 1];
+
+        Some(SourceLocation {
+            file: program.file_path(row.file)?,
+            line: row.line,
+            column: row.column,
+            discriminator: row.discriminator,
+        })
+    }
+}
@@ -0,0 +1,194 @@
+/// Parsing for DWARF debugging information, from the `.debug_*` sections
+/// (which `ElfFile::debug_section_data` reads, decompressing them if needed).
+///
+pub mod abbrev;
+pub mod cfa;
+pub mod expression;
+pub mod frame;
+pub mod info;
+pub mod layout;
+pub mod line;
+pub mod ranges;
+pub(crate) mod reader;
+pub mod registers;
+pub mod unit;
+
+// Attribute forms (DW_FORM_*): how each value is encoded.
+pub const DW_FORM_ADDR: u64 = 0x01;
+pub const DW_FORM_BLOCK2: u64 = 0x03;
+pub const DW_FORM_BLOCK4: u64 = 0x04;
+pub const DW_FORM_DATA2: u64 = 0x05;
+pub const DW_FORM_DATA4: u64 = 0x06;
+pub const DW_FORM_DATA8: u64 = 0x07;
+pub const DW_FORM_STRING: u64 = 0x08;
+pub const DW_FORM_BLOCK: u64 = 0x09;
+pub const DW_FORM_BLOCK1: u64 = 0x0a;
+pub const DW_FORM_DATA1: u64 = 0x0b;
+pub const DW_FORM_FLAG: u64 = 0x0c;
+pub const DW_FORM_SDATA: u64 = 0x0d;
+pub const DW_FORM_STRP: u64 = 0x0e;
+pub const DW_FORM_UDATA: u64 = 0x0f;
+pub const DW_FORM_REF_ADDR: u64 = 0x10;
+pub const DW_FORM_REF1: u64 = 0x11;
+pub const DW_FORM_REF2: u64 = 0x12;
+pub const DW_FORM_REF4: u64 = 0x13;
+pub const DW_FORM_REF8: u64 = 0x14;
+pub const DW_FORM_REF_UDATA: u64 = 0x15;
+pub const DW_FORM_INDIRECT: u64 = 0x16;
+pub const DW_FORM_SEC_OFFSET: u64 = 0x17;
+pub const DW_FORM_EXPRLOC: u64 = 0x18;
+pub const DW_FORM_FLAG_PRESENT: u64 = 0x19;
+pub const DW_FORM_STRX: u64 = 0x1a;
+pub const DW_FORM_ADDRX: u64 = 0x1b;
+pub const DW_FORM_REF_SUP4: u64 = 0x1c;
+pub const DW_FORM_STRP_SUP: u64 = 0x1d;
+pub const DW_FORM_DATA16: u64 = 0x1e;
+pub const DW_FORM_LINE_STRP: u64 = 0x1f;
+pub const DW_FORM_REF_SIG8: u64 = 0x20;
+pub const DW_FORM_IMPLICIT_CONST: u64 = 0x21;
+pub const DW_FORM_LOCLISTX: u64 = 0x22;
+pub const DW_FORM_RNGLISTX: u64 = 0x23;
+pub const DW_FORM_REF_SUP8: u64 = 0x24;
+pub const DW_FORM_STRX1: u64 = 0x25;
+pub const DW_FORM_STRX2: u64 = 0x26;
+pub const DW_FORM_STRX3: u64 = 0x27;
+pub const DW_FORM_STRX4: u64 = 0x28;
+pub const DW_FORM_ADDRX1: u64 = 0x29;
+pub const DW_FORM_ADDRX2: u64 = 0x2a;
+pub const DW_FORM_ADDRX3: u64 = 0x2b;
+pub const DW_FORM_ADDRX4: u64 = 0x2c;
+
+// GNU extensions: indices for split DWARF, and references to
+// the supplementary file made by dwz.
+pub const DW_FORM_GNU_ADDR_INDEX: u64 = 0x1f01;
+pub const DW_FORM_GNU_STR_INDEX: u64 = 0x1f02;
+pub const DW_FORM_GNU_REF_ALT: u64 = 0x1f20;
+pub const DW_FORM_GNU_STRP_ALT: u64 = 0x1f21;
+
+// Unit types (DW_UT_*), from version 5.
+pub const DW_UT_COMPILE: u8 = 0x01;
+pub const DW_UT_TYPE: u8 = 0x02;
+pub const DW_UT_PARTIAL: u8 = 0x03;
+pub const DW_UT_SKELETON: u8 = 0x04;
+pub const DW_UT_SPLIT_COMPILE: u8 = 0x05;
+pub const DW_UT_SPLIT_TYPE: u8 = 0x06;
+
+// Tags (DW_TAG_*): what kind of thing an entry describes. Like
+// the attributes, only the common ones are listed.
+pub const DW_TAG_ARRAY_TYPE: u64 = 0x01;
+pub const DW_TAG_CLASS_TYPE: u64 = 0x02;
+pub const DW_TAG_ENUMERATION_TYPE: u64 = 0x04;
+pub const DW_TAG_FORMAL_PARAMETER: u64 = 0x05;
+pub const DW_TAG_LEXICAL_BLOCK: u64 = 0x0b;
+pub const DW_TAG_MEMBER: u64 = 0x0d;
+pub const DW_TAG_POINTER_TYPE: u64 = 0x0f;
+pub const DW_TAG_REFERENCE_TYPE: u64 = 0x10;
+pub const DW_TAG_COMPILE_UNIT: u64 = 0x11;
+pub const DW_TAG_STRUCTURE_TYPE: u64 = 0x13;
+pub const DW_TAG_SUBROUTINE_TYPE: u64 = 0x15;
+pub const DW_TAG_TYPEDEF: u64 = 0x16;
+pub const DW_TAG_UNION_TYPE: u64 = 0x17;
+pub const DW_TAG_UNSPECIFIED_PARAMETERS: u64 = 0x18;
+pub const DW_TAG_INHERITANCE: u64 = 0x1c;
+pub const DW_TAG_INLINED_SUBROUTINE: u64 = 0x1d;
+pub const DW_TAG_PTR_TO_MEMBER_TYPE: u64 = 0x1f;
+pub const DW_TAG_SUBRANGE_TYPE: u64 = 0x21;
+pub const DW_TAG_BASE_TYPE: u64 = 0x24;
+pub const DW_TAG_CONST_TYPE: u64 = 0x26;
+pub const DW_TAG_ENUMERATOR: u64 = 0x28;
+pub const DW_TAG_SUBPROGRAM: u64 = 0x2e;
+pub const DW_TAG_VARIABLE: u64 = 0x34;
+pub const DW_TAG_VOLATILE_TYPE: u64 = 0x35;
+pub const DW_TAG_RESTRICT_TYPE: u64 = 0x37;
+pub const DW_TAG_NAMESPACE: u64 = 0x39;
+pub const DW_TAG_UNSPECIFIED_TYPE: u64 = 0x3b;
+pub const DW_TAG_PARTIAL_UNIT: u64 = 0x3c;
+pub const DW_TAG_RVALUE_REFERENCE_TYPE: u64 = 0x42;
+pub const DW_TAG_ATOMIC_TYPE: u64 = 0x47;
+pub const DW_TAG_SKELETON_UNIT: u64 = 0x4a;
+
+// Attributes (DW_AT_*) of units, functions, and types.
+pub const DW_AT_LOCATION: u64 = 0x02;
+pub const DW_AT_NAME: u64 = 0x03;
+pub const DW_AT_BYTE_SIZE: u64 = 0x0b;
+pub const DW_AT_BIT_OFFSET: u64 = 0x0c;
+pub const DW_AT_BIT_SIZE: u64 = 0x0d;
+pub const DW_AT_STMT_LIST: u64 = 0x10;
+pub const DW_AT_LOW_PC: u64 = 0x11;
+pub const DW_AT_HIGH_PC: u64 = 0x12;
+pub const DW_AT_LANGUAGE: u64 = 0x13;
+pub const DW_AT_COMP_DIR: u64 = 0x1b;
+pub const DW_AT_INLINE: u64 = 0x20;
+pub const DW_AT_PRODUCER: u64 = 0x25;
+pub const DW_AT_PROTOTYPED: u64 = 0x27;
+pub const DW_AT_COUNT: u64 = 0x37;
+pub const DW_AT_UPPER_BOUND: u64 = 0x2f;
+pub const DW_AT_ABSTRACT_ORIGIN: u64 = 0x31;
+pub const DW_AT_DATA_MEMBER_LOCATION: u64 = 0x38;
+pub const DW_AT_DECL_FILE: u64 = 0x3a;
+pub const DW_AT_DECL_LINE: u64 = 0x3b;
+pub const DW_AT_DECLARATION: u64 = 0x3c;
+pub const DW_AT_EXTERNAL: u64 = 0x3f;
+pub const DW_AT_SPECIFICATION: u64 = 0x47;
+pub const DW_AT_TYPE: u64 = 0x49;
+pub const DW_AT_RANGES: u64 = 0x55;
+pub const DW_AT_DATA_BIT_OFFSET: u64 = 0x6b;
+pub const DW_AT_LINKAGE_NAME: u64 = 0x6e;
+pub const DW_AT_STR_OFFSETS_BASE: u64 = 0x72;
+pub const DW_AT_ADDR_BASE: u64 = 0x73;
+pub const DW_AT_RNGLISTS_BASE: u64 = 0x74;
+pub const DW_AT_LOCLISTS_BASE: u64 = 0x8c;
+pub const DW_AT_MIPS_LINKAGE_NAME: u64 = 0x2007;
+pub const DW_AT_GNU_ADDR_BASE: u64 = 0x2133;
+
+// Operations (DW_OP_*) of DWARF expressions, which compute values on a
+// stack. These are the ones that computing addresses needs; DWARF 2 also
+// gives member offsets as `DW_OP_plus_uconst <offset>`.
+pub const DW_OP_ADDR: u8 = 0x03;
+pub const DW_OP_DEREF: u8 = 0x06;
+pub const DW_OP_CONST1U: u8 = 0x08;
+pub const DW_OP_CONST1S: u8 = 0x09;
+pub const DW_OP_CONST2U: u8 = 0x0a;
+pub const DW_OP_CONST2S: u8 = 0x0b;
+pub const DW_OP_CONST4U: u8 = 0x0c;
+pub const DW_OP_CONST4S: u8 = 0x0d;
+pub const DW_OP_CONST8U: u8 = 0x0e;
+pub const DW_OP_CONST8S: u8 = 0x0f;
+pub const DW_OP_CONSTU: u8 = 0x10;
+pub const DW_OP_CONSTS: u8 = 0x11;
+pub const DW_OP_DUP: u8 = 0x12;
+pub const DW_OP_DROP: u8 = 0x13;
+pub const DW_OP_OVER: u8 = 0x14;
+pub const DW_OP_PICK: u8 = 0x15;
+pub const DW_OP_SWAP: u8 = 0x16;
+pub const DW_OP_ROT: u8 = 0x17;
+pub const DW_OP_ABS: u8 = 0x19;
+pub const DW_OP_AND: u8 = 0x1a;
+pub const DW_OP_DIV: u8 = 0x1b;
+pub const DW_OP_MINUS: u8 = 0x1c;
+pub const DW_OP_MOD: u8 = 0x1d;
+pub const DW_OP_MUL: u8 = 0x1e;
+pub const DW_OP_NEG: u8 = 0x1f;
+pub const DW_OP_NOT: u8 = 0x20;
+pub const DW_OP_OR: u8 = 0x21;
+pub const DW_OP_PLUS: u8 = 0x22;
+pub const DW_OP_PLUS_UCONST: u8 = 0x23;
+pub const DW_OP_SHL: u8 = 0x24;
+pub const DW_OP_SHR: u8 = 0x25;
+pub const DW_OP_SHRA: u8 = 0x26;
+pub const DW_OP_XOR: u8 = 0x27;
+pub const DW_OP_BRA: u8 = 0x28;
+pub const DW_OP_EQ: u8 = 0x29;
+pub const DW_OP_GE: u8 = 0x2a;
+pub const DW_OP_GT: u8 = 0x2b;
+pub const DW_OP_LE: u8 = 0x2c;
+pub const DW_OP_LT: u8 = 0x2d;
+pub const DW_OP_NE: u8 = 0x2e;
+pub const DW_OP_SKIP: u8 = 0x2f;
+pub const DW_OP_LIT0: u8 = 0x30;
+pub const DW_OP_LIT31: u8 = 0x4f;
+pub const DW_OP_BREG0: u8 = 0x70;
+pub const DW_OP_BREG31: u8 = 0x8f;
+pub const DW_OP_BREGX: u8 = 0x92;
+pub const DW_OP_DEREF_SIZE: u8 = 0x94;
+pub const DW_OP_NOP: u8 = 0x96;
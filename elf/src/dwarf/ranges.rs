@@ -0,0 +1,110 @@
+/// Address range lists, for code that isn't in one piece (a function split
+/// into hot and cold parts, or a unit whose functions are in different
+/// sections): .debug_rnglists from version 5, and .debug_ranges before it.
+///
+use std::ops::Range;
+
+use crate::dwarf::reader::Reader;
+use crate::ElfError;
+
+// Range list entry kinds (DW_RLE_*).
+pub const DW_RLE_END_OF_LIST: u8 = 0x00;
+pub const DW_RLE_BASE_ADDRESSX: u8 = 0x01;
+pub const DW_RLE_STARTX_ENDX: u8 = 0x02;
+pub const DW_RLE_STARTX_LENGTH: u8 = 0x03;
+pub const DW_RLE_OFFSET_PAIR: u8 = 0x04;
+pub const DW_RLE_BASE_ADDRESS: u8 = 0x05;
+pub const DW_RLE_START_END: u8 = 0x06;
+pub const DW_RLE_START_LENGTH: u8 = 0x07;
+
+/// Reads the version 5 range list at `offset` in .debug_rnglists. Offset
+/// pairs are from `base_address` (the unit's low_pc) until a base address
+/// entry changes it, and `address` looks up entries in .debug_addr.
+pub fn read_range_list(
+    debug_rnglists: &[u8],
+    offset: u64,
+    address_size: u8,
+    mut base_address: u64,
+    address: impl Fn(u64) -> Result<u64, ElfError>,
+) -> Result<Vec<Range<u64>>, ElfError> {
+    let mut reader = Reader::new(debug_rnglists, "Range list");
+    reader.seek(usize::try_from(offset).unwrap_or(usize::MAX));
+
+    let address_size = address_size as usize;
+    let mut ranges = vec![];
+
+    loop {
+        let entry_offset = reader.offset();
+
+        let range = match reader.u8()? {
+            DW_RLE_END_OF_LIST => break,
+
+            DW_RLE_BASE_ADDRESSX => {
+                base_address = address(reader.uleb128()?)?;
+                continue;
+            }
+            DW_RLE_BASE_ADDRESS => {
+                base_address = reader.uint(address_size)?;
+                continue;
+            }
+
+            DW_RLE_STARTX_ENDX => address(reader.uleb128()?)?..address(reader.uleb128()?)?,
+            DW_RLE_STARTX_LENGTH => {
+                let start = address(reader.uleb128()?)?;
+                start..start.wrapping_add(reader.uleb128()?)
+            }
+            DW_RLE_OFFSET_PAIR => {
+                let (start, end) = (reader.uleb128()?, reader.uleb128()?);
+                base_address.wrapping_add(start)..base_address.wrapping_add(end)
+            }
+            DW_RLE_START_END => reader.uint(address_size)?..reader.uint(address_size)?,
+            DW_RLE_START_LENGTH => {
+                let start = reader.uint(address_size)?;
+                start..start.wrapping_add(reader.uleb128()?)
+            }
+
+            kind => {
+                return Err(reader.invalid(
+                    entry_offset,
+                    format!("range list entry kind {kind:#x} is unknown"),
+                ))
+            }
+        };
+
+        ranges.push(range);
+    }
+
+    Ok(ranges)
+}
+
+/// Reads the list at `offset` in .debug_ranges, from before version 5. Each
+/// entry is a pair of addresses from `base_address`, except that a first
+/// address of all ones sets the base address instead, and a pair of zeros
+/// ends the list.
+pub fn read_ranges(
+    debug_ranges: &[u8],
+    offset: u64,
+    address_size: u8,
+    mut base_address: u64,
+) -> Result<Vec<Range<u64>>, ElfError> {
+    let mut reader = Reader::new(debug_ranges, "Range list");
+    reader.seek(usize::try_from(offset).unwrap_or(usize::MAX));
+
+    let address_size = address_size as usize;
+    let base_selection = u64::MAX >> (64 - 8 * address_size.clamp(1, 8));
+    let mut ranges = vec![];
+
+    loop {
+        let (start, end) = (reader.uint(address_size)?, reader.uint(address_size)?);
+
+        match (start, end) {
+            (0, 0) => break,
+            (start, end) if start == base_selection => base_address = end,
+            (start, end) => {
+                ranges.push(base_address.wrapping_add(start)..base_address.wrapping_add(end))
+            }
+        }
+    }
+
+    Ok(ranges)
+}
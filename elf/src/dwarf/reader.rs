@@ -0,0 +1,189 @@
+/// A cursor over DWARF data, which is made of little-endian integers,
+/// LEB128 numbers, and null-terminated strings.
+///
+use crate::ElfError;
+
+#[derive(Clone, Debug)]
+pub struct Reader<'a> {
+    /// The whole section, so that offsets (in errors, too) are from its start.
+    bytes: &'a [u8],
+    offset: usize,
+    end: usize,
+
+    /// What is being read, for errors.
+    what: &'static str,
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(bytes: &'a [u8], what: &'static str) -> Reader<'a> {
+        Reader {
+            bytes,
+            offset: 0,
+            end: bytes.len(),
+            what,
+        }
+    }
+
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Where this reader stops, from the start of the section.
+    pub fn end(&self) -> usize {
+        self.end
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.offset >= self.end
+    }
+
+    /// Moves to `offset` from the start of the section (and not past the end of this reader).
+    pub fn seek(&mut self, offset: usize) {
+        self.offset = offset.min(self.end);
+    }
+
+    /// Returns a reader for the next `len` bytes, which this one then skips.
+    pub fn split(&mut self, len: u64) -> Result<Reader<'a>, ElfError> {
+        let start = self.offset;
+        self.bytes(usize::try_from(len).unwrap_or(usize::MAX))?;
+
+        Ok(Reader {
+            bytes: self.bytes,
+            offset: start,
+            end: self.offset,
+            what: self.what,
+        })
+    }
+
+    pub fn bytes(&mut self, len: usize) -> Result<&'a [u8], ElfError> {
+        let start = self.offset;
+        let end = start
+            .checked_add(len)
+            .filter(|&end| end <= self.end)
+            .ok_or(ElfError::Truncated {
+                what: self.what,
+                offset: start as u64,
+                needed: len as u64,
+            })?;
+
+        self.offset = end;
+        Ok(&self.bytes[start..end])
+    }
+
+    pub fn u8(&mut self) -> Result<u8, ElfError> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    pub fn i8(&mut self) -> Result<i8, ElfError> {
+        Ok(self.u8()? as i8)
+    }
+
+    pub fn u16(&mut self) -> Result<u16, ElfError> {
+        Ok(self.uint(2)? as u16)
+    }
+
+    pub fn u32(&mut self) -> Result<u32, ElfError> {
+        Ok(self.uint(4)? as u32)
+    }
+
+    pub fn u64(&mut self) -> Result<u64, ElfError> {
+        self.uint(8)
+    }
+
+    /// Reads an unsigned integer of `size` bytes (up to 8), such as an address.
+    pub fn uint(&mut self, size: usize) -> Result<u64, ElfError> {
+        let bytes = self.bytes(size)?;
+
+        Ok(bytes
+            .iter()
+            .take(8)
+            .rev()
+            .fold(0, |value, &byte| value << 8 | byte as u64))
+    }
+
+    pub fn uleb128(&mut self) -> Result<u64, ElfError> {
+        let mut value = 0;
+        let mut shift = 0;
+
+        loop {
+            let byte = self.u8()?;
+
+            if shift < 64 {
+                value |= ((byte & 0x7f) as u64) << shift;
+            }
+            shift += 7;
+
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+    }
+
+    pub fn sleb128(&mut self) -> Result<i64, ElfError> {
+        let mut value = 0i64;
+        let mut shift = 0;
+
+        loop {
+            let byte = self.u8()?;
+
+            if shift < 64 {
+                value |= ((byte & 0x7f) as i64) << shift;
+            }
+            shift += 7;
+
+            if byte & 0x80 == 0 {
+                // Extend the sign from the last byte's top bit.
+                if shift < 64 && byte & 0x40 != 0 {
+                    value |= -1 << shift;
+                }
+                return Ok(value);
+            }
+        }
+    }
+
+    /// Reads a null-terminated string, without the null.
+    pub fn cstr(&mut self) -> Result<&'a [u8], ElfError> {
+        let rest = &self.bytes[self.offset..self.end];
+
+        let len = rest
+            .iter()
+            .position(|&byte| byte == 0)
+            .ok_or(ElfError::Truncated {
+                what: self.what,
+                offset: self.offset as u64,
+                needed: rest.len() as u64 + 1,
+            })?;
+
+        let string = &rest[..len];
+        self.offset += len + 1;
+
+        Ok(string)
+    }
+
+    /// Reads the length at the start of a unit, and returns it with the size
+    /// of the offsets in the unit: 4 bytes, or 8 in the 64-bit DWARF format.
+    pub fn unit_length(&mut self) -> Result<(u64, usize), ElfError> {
+        let offset = self.offset;
+
+        match self.u32()? {
+            0xffff_ffff => Ok((self.u64()?, 8)),
+
+            reserved @ 0xffff_fff0.. => Err(ElfError::InvalidData {
+                what: self.what,
+                offset: offset as u64,
+                reason: format!("the length {reserved:#x} is reserved"),
+            }),
+
+            length => Ok((length as u64, 4)),
+        }
+    }
+
+    /// An error for the data just read, at `offset`.
+    pub fn invalid(&self, offset: usize, reason: String) -> ElfError {
+        ElfError::InvalidData {
+            what: self.what,
+            offset: offset as u64,
+            reason,
+        }
+    }
+}
@@ -0,0 +1,59 @@
+/// The names of the registers that DWARF numbers (in call frame information
+/// and expressions), which each architecture's ABI assigns.
+///
+use crate::parse::header::{EM_AARCH64, EM_RISCV, EM_X86_64};
+
+/// In the order of their DWARF numbers, from the x86-64 psABI.
+const X86_64: [&str; 17] = [
+    "rax", "rdx", "rcx", "rbx", "rsi", "rdi", "rbp", "rsp", "r8", "r9", "r10", "r11", "r12", "r13",
+    "r14", "r15", "rip",
+];
+
+/// The integer registers by their ABI names, which readelf and objdump use.
+const RISCV: [&str; 32] = [
+    "zero", "ra", "sp", "gp", "tp", "t0", "t1", "t2", "s0", "s1", "a0", "a1", "a2", "a3", "a4",
+    "a5", "a6", "a7", "s2", "s3", "s4", "s5", "s6", "s7", "s8", "s9", "s10", "s11", "t3", "t4",
+    "t5", "t6",
+];
+
+/// The name of a register on `machine` (an `EM_*` value), if it has one.
+pub fn register_name(machine: u16, register: u16) -> Option<String> {
+    let register = register as usize;
+
+    match machine {
+        EM_X86_64 => match register {
+            0..=16 => Some(X86_64[register].to_owned()),
+            17..=32 => Some(format!("xmm{}", register - 17)),
+            33..=40 => Some(format!("st{}", register - 33)),
+            41..=48 => Some(format!("mm{}", register - 41)),
+            49 => Some("rflags".to_owned()),
+            _ => None,
+        },
+
+        EM_AARCH64 => match register {
+            0..=30 => Some(format!("x{register}")),
+            31 => Some("sp".to_owned()),
+            64..=95 => Some(format!("v{}", register - 64)),
+            _ => None,
+        },
+
+        EM_RISCV => match register {
+            0..=31 => Some(RISCV[register].to_owned()),
+            32..=63 => Some(format!("f{}", register - 32)),
+            _ => None,
+        },
+
+        _ => None,
+    }
+}
+
+/// The register that holds the stack pointer on `machine`, which
+/// unwinding sets to the canonical frame address.
+pub fn stack_pointer(machine: u16) -> Option<u16> {
+    match machine {
+        EM_X86_64 => Some(7),
+        EM_AARCH64 => Some(31),
+        EM_RISCV => Some(2),
+        _ => None,
+    }
+}
@@ -0,0 +1,533 @@
+/// Units in .debug_info, and the tree of debugging information entries
+/// (DIEs) in each. An entry has a tag saying what it describes (a function,
+/// a type, a variable...), attributes, and children: a compilation unit's
+/// root entry has the unit's functions and types as children, a structure
+/// type has its members, and so on.
+///
+use crate::dwarf::abbrev::*;
+use crate::dwarf::info::DwarfSections;
+use crate::dwarf::reader::Reader;
+use crate::dwarf::*;
+use crate::parse::strings::StringTable;
+use crate::ElfError;
+
+// -------------
+// Unit headers.
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnitHeader {
+    /// Where the unit starts, in .debug_info.
+    pub offset: u64,
+
+    /// Where the next unit starts.
+    pub end: u64,
+
+    pub version: u16,
+
+    /// DW_UT_*; units before version 5 are all DW_UT_compile.
+    pub unit_type: u8,
+
+    /// 4, or 8 in the 64-bit DWARF format.
+    pub offset_size: usize,
+    pub address_size: u8,
+    pub abbrev_offset: u64,
+
+    /// The signature of a type unit, or the ID of the split DWARF
+    /// file of a skeleton unit.
+    pub signature: Option<u64>,
+
+    /// Where the first entry starts, after the header.
+    pub entries_offset: u64,
+}
+
+/// Reads the header of each unit in .debug_info.
+pub fn read_unit_headers(debug_info: &[u8]) -> Result<Vec<UnitHeader>, ElfError> {
+    let mut reader = Reader::new(debug_info, "Unit header");
+    let mut headers = vec![];
+
+    while !reader.is_empty() {
+        let offset = reader.offset();
+        let (length, offset_size) = reader.unit_length()?;
+        let mut unit = reader.split(length)?;
+
+        let version = unit.u16()?;
+        if !(2..=5).contains(&version) {
+            return Err(ElfError::UnsupportedVersion {
+                what: "Unit",
+                version,
+            });
+        }
+
+        let (unit_type, address_size, abbrev_offset);
+        if version >= 5 {
+            unit_type = unit.u8()?;
+            address_size = unit.u8()?;
+            abbrev_offset = unit.uint(offset_size)?;
+        } else {
+            unit_type = DW_UT_COMPILE;
+            abbrev_offset = unit.uint(offset_size)?;
+            address_size = unit.u8()?;
+        }
+
+        let signature = match unit_type {
+            DW_UT_SKELETON | DW_UT_SPLIT_COMPILE => Some(unit.u64()?),
+            DW_UT_TYPE | DW_UT_SPLIT_TYPE => {
+                let signature = unit.u64()?;
+                let _type_offset = unit.uint(offset_size)?;
+                Some(signature)
+            }
+            _ => None,
+        };
+
+        headers.push(UnitHeader {
+            offset: offset as u64,
+            end: reader.offset() as u64,
+            version,
+            unit_type,
+            offset_size,
+            address_size,
+            abbrev_offset,
+            signature,
+            entries_offset: unit.offset() as u64,
+        });
+    }
+
+    Ok(headers)
+}
+
+// -----------------
+// Attribute values.
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AttributeValue {
+    Address(u64),
+    Unsigned(u64),
+    Signed(i64),
+    Flag(bool),
+    String(String),
+
+    /// Another entry, by its offset in .debug_info.
+    Reference(u64),
+
+    /// The type unit with this signature.
+    TypeSignature(u64),
+
+    /// An offset in another section: a line program, range list, or location list.
+    SectionOffset(u64),
+
+    Block(Vec<u8>),
+
+    /// A DWARF expression, such as a location.
+    Expression(Vec<u8>),
+
+    /// An entry or a string in the supplementary file that dwz makes, by
+    /// its offset in that file's .debug_info or .debug_str.
+    SupplementaryReference(u64),
+    SupplementaryString(u64),
+
+    // Indices into the tables of the unit (in .debug_addr, .debug_str_offsets,
+    // .debug_rnglists, and .debug_loclists). These are replaced with what they
+    // point to when the unit is read, unless the table's section is missing.
+    AddressIndex(u64),
+    StringIndex(u64),
+    RangeListIndex(u64),
+    LocationListIndex(u64),
+}
+
+impl AttributeValue {
+    /// The value of a constant or an offset, which DWARF 2 and 3 give as data.
+    pub fn as_u64(&self) -> Option<u64> {
+        match *self {
+            AttributeValue::Unsigned(value) | AttributeValue::SectionOffset(value) => Some(value),
+            AttributeValue::Signed(value) => u64::try_from(value).ok(),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            AttributeValue::String(string) => Some(string),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attribute {
+    pub name: u64,
+    pub form: u64,
+    pub value: AttributeValue,
+}
+
+/// Reads a string from a string section, or an error naming what it was for.
+fn string_at(bytes: &[u8], offset: u64) -> Result<String, ElfError> {
+    StringTable::new(bytes)
+        .get_lossy(usize::try_from(offset).unwrap_or(usize::MAX))
+        .map(|string| string.into_owned())
+        .map_err(|e| e.reading("Attribute string"))
+}
+
+fn read_value(
+    reader: &mut Reader,
+    form: u64,
+    spec: &AttributeSpec,
+    header: &UnitHeader,
+    sections: &DwarfSections,
+) -> Result<AttributeValue, ElfError> {
+    use AttributeValue::*;
+
+    let offset_size = header.offset_size;
+    let block = |reader: &mut Reader, len: u64| -> Result<Vec<u8>, ElfError> {
+        Ok(reader
+            .bytes(usize::try_from(len).unwrap_or(usize::MAX))?
+            .to_vec())
+    };
+
+    Ok(match form {
+        DW_FORM_ADDR => Address(reader.uint(header.address_size as usize)?),
+
+        DW_FORM_DATA1 => Unsigned(reader.uint(1)?),
+        DW_FORM_DATA2 => Unsigned(reader.uint(2)?),
+        DW_FORM_DATA4 => Unsigned(reader.uint(4)?),
+        DW_FORM_DATA8 => Unsigned(reader.uint(8)?),
+        DW_FORM_UDATA => Unsigned(reader.uleb128()?),
+        DW_FORM_SDATA => Signed(reader.sleb128()?),
+        DW_FORM_IMPLICIT_CONST => Signed(spec.implicit_const),
+        DW_FORM_DATA16 => Block(block(reader, 16)?),
+
+        DW_FORM_FLAG => Flag(reader.u8()? != 0),
+        DW_FORM_FLAG_PRESENT => Flag(true),
+
+        DW_FORM_STRING => String(std::string::String::from_utf8_lossy(reader.cstr()?).into_owned()),
+        DW_FORM_STRP => String(string_at(&sections.str, reader.uint(offset_size)?)?),
+        DW_FORM_LINE_STRP => String(string_at(&sections.line_str, reader.uint(offset_size)?)?),
+
+        DW_FORM_BLOCK1 => {
+            let len = reader.uint(1)?;
+            Block(block(reader, len)?)
+        }
+        DW_FORM_BLOCK2 => {
+            let len = reader.uint(2)?;
+            Block(block(reader, len)?)
+        }
+        DW_FORM_BLOCK4 => {
+            let len = reader.uint(4)?;
+            Block(block(reader, len)?)
+        }
+        DW_FORM_BLOCK => {
+            let len = reader.uleb128()?;
+            Block(block(reader, len)?)
+        }
+        DW_FORM_EXPRLOC => {
+            let len = reader.uleb128()?;
+            Expression(block(reader, len)?)
+        }
+
+        // References within the unit are from its start.
+        DW_FORM_REF1 => Reference(header.offset.wrapping_add(reader.uint(1)?)),
+        DW_FORM_REF2 => Reference(header.offset.wrapping_add(reader.uint(2)?)),
+        DW_FORM_REF4 => Reference(header.offset.wrapping_add(reader.uint(4)?)),
+        DW_FORM_REF8 => Reference(header.offset.wrapping_add(reader.uint(8)?)),
+        DW_FORM_REF_UDATA => Reference(header.offset.wrapping_add(reader.uleb128()?)),
+
+        // In version 2 these were the size of an address.
+        DW_FORM_REF_ADDR => match header.version {
+            2 => Reference(reader.uint(header.address_size as usize)?),
+            _ => Reference(reader.uint(offset_size)?),
+        },
+        DW_FORM_REF_SIG8 => TypeSignature(reader.u64()?),
+
+        DW_FORM_SEC_OFFSET => SectionOffset(reader.uint(offset_size)?),
+
+        DW_FORM_REF_SUP4 => SupplementaryReference(reader.uint(4)?),
+        DW_FORM_REF_SUP8 => SupplementaryReference(reader.uint(8)?),
+        DW_FORM_GNU_REF_ALT => SupplementaryReference(reader.uint(offset_size)?),
+        DW_FORM_STRP_SUP | DW_FORM_GNU_STRP_ALT => SupplementaryString(reader.uint(offset_size)?),
+
+        DW_FORM_STRX | DW_FORM_GNU_STR_INDEX => StringIndex(reader.uleb128()?),
+        DW_FORM_STRX1 => StringIndex(reader.uint(1)?),
+        DW_FORM_STRX2 => StringIndex(reader.uint(2)?),
+        DW_FORM_STRX3 => StringIndex(reader.uint(3)?),
+        DW_FORM_STRX4 => StringIndex(reader.uint(4)?),
+
+        DW_FORM_ADDRX | DW_FORM_GNU_ADDR_INDEX => AddressIndex(reader.uleb128()?),
+        DW_FORM_ADDRX1 => AddressIndex(reader.uint(1)?),
+        DW_FORM_ADDRX2 => AddressIndex(reader.uint(2)?),
+        DW_FORM_ADDRX3 => AddressIndex(reader.uint(3)?),
+        DW_FORM_ADDRX4 => AddressIndex(reader.uint(4)?),
+
+        DW_FORM_RNGLISTX => RangeListIndex(reader.uleb128()?),
+        DW_FORM_LOCLISTX => LocationListIndex(reader.uleb128()?),
+
+        // The form comes first, in the entry.
+        DW_FORM_INDIRECT => {
+            let offset = reader.offset();
+            let form = reader.uleb128()?;
+            if matches!(form, DW_FORM_INDIRECT | DW_FORM_IMPLICIT_CONST) {
+                return Err(reader.invalid(offset, format!("form {form:#x} can't be indirect")));
+            }
+            read_value(reader, form, spec, header, sections)?
+        }
+
+        _ => {
+            return Err(reader.invalid(
+                reader.offset(),
+                format!("attribute form {form:#x} isn't supported"),
+            ))
+        }
+    })
+}
+
+// --------
+// Entries.
+
+/// A debugging information entry. Its parent and children are
+/// indices into the entries of its unit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    /// Where the entry starts, in .debug_info.
+    pub offset: u64,
+
+    pub tag: u64,
+    pub attributes: Vec<Attribute>,
+
+    pub parent: Option<usize>,
+    pub children: Vec<usize>,
+}
+
+impl Entry {
+    pub fn attribute(&self, name: u64) -> Option<&AttributeValue> {
+        self.attributes
+            .iter()
+            .find(|attribute| attribute.name == name)
+            .map(|attribute| &attribute.value)
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.attribute(DW_AT_NAME)?.as_str()
+    }
+
+    pub fn has_flag(&self, name: u64) -> bool {
+        matches!(self.attribute(name), Some(AttributeValue::Flag(true)))
+    }
+}
+
+/// A unit with its entries, in the order they're in the file,
+/// so the root entry is first.
+#[derive(Debug, Clone)]
+pub struct Unit {
+    pub header: UnitHeader,
+    pub entries: Vec<Entry>,
+}
+
+impl Unit {
+    pub fn root(&self) -> Option<&Entry> {
+        self.entries.first()
+    }
+
+    /// Finds the entry at `offset` in .debug_info, if it's in this unit.
+    pub fn entry_at(&self, offset: u64) -> Option<&Entry> {
+        self.entries
+            .binary_search_by_key(&offset, |entry| entry.offset)
+            .ok()
+            .map(|index| &self.entries[index])
+    }
+
+    pub fn children<'a>(&'a self, entry: &'a Entry) -> impl Iterator<Item = &'a Entry> + 'a {
+        entry.children.iter().map(|&index| &self.entries[index])
+    }
+
+    pub fn parent(&self, entry: &Entry) -> Option<&Entry> {
+        entry.parent.map(|index| &self.entries[index])
+    }
+}
+
+/// Reads the entries of the unit with the given header: all of them,
+/// or only the root entry.
+fn read_entries(
+    sections: &DwarfSections,
+    header: &UnitHeader,
+    root_only: bool,
+) -> Result<Vec<Entry>, ElfError> {
+    let abbreviations = read_abbreviations(&sections.abbrev, header.abbrev_offset)?;
+
+    let mut reader = Reader::new(&sections.info, "Debugging information entry");
+    reader.seek(header.entries_offset as usize);
+    let mut reader = reader.split(header.end.saturating_sub(header.entries_offset))?;
+
+    let mut entries: Vec<Entry> = vec![];
+    let mut parents: Vec<usize> = vec![];
+
+    while !reader.is_empty() {
+        let offset = reader.offset();
+
+        // A 0 code ends a list of children.
+        let code = reader.uleb128()?;
+        if code == 0 {
+            parents.pop();
+            continue;
+        }
+
+        let abbreviation = abbreviations
+            .get(code)
+            .ok_or_else(|| reader.invalid(offset, format!("abbreviation {code} isn't defined")))?;
+
+        let mut attributes = vec![];
+        for spec in &abbreviation.attributes {
+            attributes.push(Attribute {
+                name: spec.name,
+                form: spec.form,
+                value: read_value(&mut reader, spec.form, spec, header, sections)?,
+            });
+        }
+
+        let index = entries.len();
+        let parent = parents.last().copied();
+        if let Some(parent) = parent {
+            entries[parent].children.push(index);
+        }
+
+        entries.push(Entry {
+            offset: offset as u64,
+            tag: abbreviation.tag,
+            attributes,
+            parent,
+            children: vec![],
+        });
+
+        if root_only {
+            break;
+        }
+        if abbreviation.has_children {
+            parents.push(index);
+        }
+    }
+
+    resolve_indices(&mut entries, header, sections)?;
+    Ok(entries)
+}
+
+/// The bases of a unit's tables, from its root entry.
+pub(crate) struct TableBases {
+    pub str_offsets: u64,
+    pub addr: u64,
+    pub rnglists: u64,
+    pub loclists: u64,
+}
+
+impl TableBases {
+    /// Without an attribute giving it, a table starts just after its
+    /// section's header (as in split DWARF units, which don't have them).
+    pub fn new(root: Option<&Entry>, header: &UnitHeader) -> TableBases {
+        let header_size = if header.offset_size == 8 { 16 } else { 8 };
+        let lists_header_size = header_size + 4;
+
+        let base = |names: &[u64], default| {
+            names
+                .iter()
+                .find_map(|&name| root?.attribute(name)?.as_u64())
+                .unwrap_or(default)
+        };
+
+        TableBases {
+            str_offsets: base(&[DW_AT_STR_OFFSETS_BASE], header_size),
+            addr: base(&[DW_AT_ADDR_BASE, DW_AT_GNU_ADDR_BASE], header_size),
+            rnglists: base(&[DW_AT_RNGLISTS_BASE], lists_header_size),
+            loclists: base(&[DW_AT_LOCLISTS_BASE], lists_header_size),
+        }
+    }
+}
+
+/// Reads entry `index` of the table of `size`-byte values at `base` in `bytes`.
+pub(crate) fn table_entry(
+    bytes: &[u8],
+    what: &'static str,
+    base: u64,
+    index: u64,
+    size: usize,
+) -> Result<u64, ElfError> {
+    let offset = index
+        .checked_mul(size as u64)
+        .and_then(|offset| offset.checked_add(base))
+        .and_then(|offset| usize::try_from(offset).ok())
+        .unwrap_or(usize::MAX);
+
+    // Past the end, this reads nothing and reports the table as truncated.
+    let mut reader = Reader::new(bytes, what);
+    reader.seek(offset);
+    reader.uint(size)
+}
+
+/// Replaces index values with the addresses, strings, and
+/// list offsets that they point to in the unit's tables.
+fn resolve_indices(
+    entries: &mut [Entry],
+    header: &UnitHeader,
+    sections: &DwarfSections,
+) -> Result<(), ElfError> {
+    let bases = TableBases::new(entries.first(), header);
+    let offset_size = header.offset_size;
+
+    for attribute in entries.iter_mut().flat_map(|entry| &mut entry.attributes) {
+        attribute.value = match attribute.value {
+            AttributeValue::StringIndex(index) if !sections.str_offsets.is_empty() => {
+                let what = "String offsets table";
+                let offset = table_entry(
+                    &sections.str_offsets,
+                    what,
+                    bases.str_offsets,
+                    index,
+                    offset_size,
+                )?;
+                AttributeValue::String(string_at(&sections.str, offset)?)
+            }
+
+            AttributeValue::AddressIndex(index) if !sections.addr.is_empty() => {
+                let size = header.address_size as usize;
+                AttributeValue::Address(table_entry(
+                    &sections.addr,
+                    "Address table",
+                    bases.addr,
+                    index,
+                    size,
+                )?)
+            }
+
+            // List offsets are from the base.
+            AttributeValue::RangeListIndex(index) if !sections.rnglists.is_empty() => {
+                let what = "Range list offsets";
+                let offset =
+                    table_entry(&sections.rnglists, what, bases.rnglists, index, offset_size)?;
+                AttributeValue::SectionOffset(bases.rnglists + offset)
+            }
+
+            AttributeValue::LocationListIndex(index) if !sections.loclists.is_empty() => {
+                let what = "Location list offsets";
+                let offset =
+                    table_entry(&sections.loclists, what, bases.loclists, index, offset_size)?;
+                AttributeValue::SectionOffset(bases.loclists + offset)
+            }
+
+            _ => continue,
+        };
+    }
+
+    Ok(())
+}
+
+/// Reads a unit's entries.
+pub fn read_unit(sections: &DwarfSections, header: &UnitHeader) -> Result<Unit, ElfError> {
+    Ok(Unit {
+        header: header.clone(),
+        entries: read_entries(sections, header, false)?,
+    })
+}
+
+/// Reads only a unit's root entry (for its name, directory, and so on),
+/// which is much quicker than reading the whole unit.
+pub fn read_root_entry(
+    sections: &DwarfSections,
+    header: &UnitHeader,
+) -> Result<Option<Entry>, ElfError> {
+    Ok(read_entries(sections, header, true)?.pop())
+}
@@ -0,0 +1,1013 @@
+/// An ELF file opened for parsing, with its main, program, and section headers
+/// read up front, and accessors that read other parts of the file on demand.
+/// The bytes come from any `ByteSource`: by default the file is mapped.
+///
+use std::borrow::Cow;
+use std::fs::File;
+use std::path::Path;
+
+use crate::dwarf::cfa::*;
+use crate::dwarf::frame::*;
+use crate::dwarf::info::*;
+use crate::dwarf::line::*;
+use crate::dwarf::unit::*;
+use crate::dwarf::{DW_AT_COMP_DIR, DW_AT_STMT_LIST};
+use crate::file_read::*;
+use crate::parse::attributes::*;
+use crate::parse::compression::*;
+use crate::parse::dynamic::*;
+use crate::parse::hash::*;
+use crate::parse::header::{EM_AARCH64, EM_ARM, EM_RISCV, EM_X86_64};
+use crate::parse::layout::*;
+use crate::parse::notes::*;
+use crate::parse::plt::*;
+use crate::parse::relocs::*;
+use crate::parse::sframe::*;
+use crate::parse::strings::StringTable;
+use crate::parse::symbol_map::*;
+use crate::parse::symbols::*;
+use crate::parse::types::*;
+use crate::parse::versions::*;
+use crate::parse::*;
+use crate::{Diagnostic, ElfError, Recorder, Severity};
+
+/// The most zeros that a section or segment's contents are filled out
+/// with, where they aren't in the file. The sizes come from the headers,
+/// so they aren't trusted beyond this.
+const MAX_ZERO_FILL: u64 = 1 << 30;
+
+pub struct ElfFile<'a> {
+    source: Box<dyn ByteSource + 'a>,
+
+    pub header: Elf64Header,
+    pub program_headers: Vec<Elf64ProgramHeaderInfo>,
+    pub section_headers: Vec<Elf64SectionHeaderInfo>,
+
+    /// Problems found in the headers when parsing leniently; otherwise empty.
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl ElfFile<'static> {
+    /// Opens the file at `path`, mapping it into memory.
+    pub fn open(path: &Path) -> Result<ElfFile<'static>, ElfError> {
+        let file = File::open(path)?;
+
+        ElfFile::from_file(file)
+    }
+
+    /// Opens the file at `path` leniently; see `from_source_lenient`.
+    pub fn open_lenient(path: &Path) -> Result<ElfFile<'static>, ElfError> {
+        let file = File::open(path)?;
+
+        ElfFile::from_source_lenient(Box::new(MmapSource::new(&file)?))
+    }
+
+    /// Parses an open file, mapping it into memory.
+    pub fn from_file(file: File) -> Result<ElfFile<'static>, ElfError> {
+        ElfFile::from_source(Box::new(MmapSource::new(&file)?))
+    }
+}
+
+impl<'a> ElfFile<'a> {
+    /// Parses a file that's already in memory.
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<ElfFile<'a>, ElfError> {
+        ElfFile::from_source(Box::new(SliceSource::new(bytes)))
+    }
+
+    /// Parses a file that's already in memory leniently; see `from_source_lenient`.
+    pub fn from_bytes_lenient(bytes: &'a [u8]) -> Result<ElfFile<'a>, ElfError> {
+        ElfFile::from_source_lenient(Box::new(SliceSource::new(bytes)))
+    }
+
+    /// Parses a file, failing at the first problem in its headers.
+    pub fn from_source(source: Box<dyn ByteSource + 'a>) -> Result<ElfFile<'a>, ElfError> {
+        ElfFile::parse(source, Recorder::new(false))
+    }
+
+    /// Parses a file on a best-effort basis, for damaged or deliberately
+    /// malformed files. Problems in the program and section headers are
+    /// recorded in `diagnostics`, and parsing goes on with whatever could be
+    /// read; only a file whose main header can't be read fails to parse.
+    pub fn from_source_lenient(source: Box<dyn ByteSource + 'a>) -> Result<ElfFile<'a>, ElfError> {
+        ElfFile::parse(source, Recorder::new(true))
+    }
+
+    fn parse(
+        mut source: Box<dyn ByteSource + 'a>,
+        mut recorder: Recorder,
+    ) -> Result<ElfFile<'a>, ElfError> {
+        // A file too short for the magic bytes isn't an ELF file; one
+        // that has them but is too short for the header is truncated.
+        let magic = source.bytes(0, 4).map_err(|_| ElfError::BadMagic)?;
+
+        // -------------------
+        // Verify magic bytes.
+
+        if magic != b"\x7F\x45\x4c\x46" {
+            return Err(ElfError::BadMagic);
+        }
+
+        let ident = source
+            .bytes(0, ELF64_HEADER_LEN as u64)
+            .map_err(|e| e.reading("ELF header"))?;
+
+        // ----------------
+        // Read ELF header.
+
+        // Ensure that file is 64-bit ELF; we currently only support that combo.
+        let header = if ident[4] == b'\x02' && ident[5] == b'\x01' {
+            Elf64Header::parse_from_bytes(ident)
+        } else {
+            return Err(ElfError::UnsupportedClass {
+                class: ident[4],
+                endianness: ident[5],
+            });
+        };
+
+        // ---------------------
+        // Read program headers.
+
+        let table = read_table(
+            source.as_mut(),
+            header.program_header_offset,
+            header.program_header_entry_count,
+            header.program_header_entry_size,
+            ELF64_PROGRAM_HEADER_LEN,
+            "Program header table",
+            &mut recorder,
+        )?;
+        let program_headers = read_program_headers_64(table, &header);
+
+        // ---------------------
+        // Read section headers.
+
+        let table = read_table(
+            source.as_mut(),
+            header.section_header_offset,
+            header.section_header_entry_count,
+            header.section_header_entry_size,
+            ELF64_SECTION_HEADER_LEN,
+            "Section header table",
+            &mut recorder,
+        )?;
+        let entries = read_section_header_entries_64(table, &header);
+
+        // Section names come from the table that the main header points to.
+        let names_index = header.section_header_names_index as usize;
+
+        let names = match entries.get(names_index) {
+            Some(names) if names.type_id() != SHT_NOBITS => {
+                let (offset, size) = (names.offset, names.size);
+
+                if offset
+                    .checked_add(size)
+                    .is_some_and(|end| end <= source.len())
+                {
+                    source.bytes(offset, size)?
+                } else {
+                    let entry_offset = header.section_header_offset
+                        + names_index as u64 * header.section_header_entry_size as u64;
+
+                    let error = ElfError::Truncated {
+                        what: "Section name string table",
+                        offset,
+                        needed: size,
+                    };
+                    recorder.error(entry_offset, error)?;
+
+                    &[]
+                }
+            }
+
+            None if names_index != SHN_UNDEF as usize && !entries.is_empty() => {
+                // The offset of `e_shstrndx` in the main header.
+                recorder.warning(
+                    0x3e,
+                    format!("The section name string table index {names_index} is out of range."),
+                );
+
+                &[]
+            }
+
+            _ => &[],
+        };
+        let section_headers =
+            read_section_headers_64(entries, &StringTable::new(names), &header, &mut recorder)?;
+
+        if recorder.is_lenient() {
+            check_layout(
+                &header,
+                &program_headers,
+                &section_headers,
+                source.len(),
+                &mut recorder,
+            );
+        }
+
+        Ok(ElfFile {
+            source,
+            header,
+            program_headers,
+            section_headers,
+            diagnostics: recorder.diagnostics,
+        })
+    }
+
+    /// Returns `size` bytes of the file starting at `offset`.
+    pub fn bytes(&mut self, offset: u64, size: u64) -> Result<&[u8], ElfError> {
+        self.source.bytes(offset, size)
+    }
+
+    /// Returns the section header with the given index.
+    pub fn section(&self, index: usize) -> Result<&Elf64SectionHeaderEntry, ElfError> {
+        self.section_headers
+            .get(index)
+            .map(|section| &section.header_data)
+            .ok_or(ElfError::InvalidIndex {
+                what: "section",
+                index,
+            })
+    }
+
+    /// Returns the file contents of the section with the given index.
+    pub fn section_bytes(&mut self, index: usize) -> Result<&[u8], ElfError> {
+        let section = self.section(index)?;
+
+        if section.type_id() == SHT_NOBITS {
+            return Ok(&[]);
+        }
+
+        let (offset, size) = (section.offset, section.size);
+        self.bytes(offset, size)
+            .map_err(|e| e.reading("Section contents"))
+    }
+
+    /// Returns the contents of the section with the given index as they
+    /// are in memory: for a NOBITS section (such as .bss), that's zeros.
+    /// Compressed sections, including `.zdebug_*` ones, are decompressed.
+    pub fn section_data(&mut self, index: usize) -> Result<Cow<'_, [u8]>, ElfError> {
+        let section = self.section(index)?;
+
+        if section.type_id() == SHT_NOBITS {
+            let header_offset = self.header.section_header_offset
+                + index as u64 * self.header.section_header_entry_size as u64;
+            let size = zero_fill_size("Section header", header_offset, section.size)?;
+
+            return Ok(Cow::Owned(vec![0; size]));
+        }
+
+        let (offset, size) = (section.offset, section.size);
+        let bytes = self
+            .source
+            .bytes(offset, size)
+            .map_err(|e| e.reading("Section contents"))?;
+
+        match section_compression(&self.section_headers[index], bytes)? {
+            Some(compression) => Ok(Cow::Owned(decompress(&compression, bytes)?)),
+            None => Ok(Cow::Borrowed(bytes)),
+        }
+    }
+
+    /// Returns how the section with the given index is compressed, with
+    /// its compressed and uncompressed sizes, or None if it isn't.
+    pub fn section_compression(&mut self, index: usize) -> Result<Option<Compression>, ElfError> {
+        let section = self.section(index)?;
+
+        if section.type_id() == SHT_NOBITS {
+            return Ok(None);
+        }
+
+        // Only the compression header is needed, and both kinds fit in this.
+        let (offset, size) = (section.offset, section.size);
+        let bytes = self
+            .source
+            .bytes(offset, size.min(ELF64_COMPRESSION_HEADER_LEN as u64))
+            .map_err(|e| e.reading("Section contents"))?;
+
+        section_compression(&self.section_headers[index], bytes)
+    }
+
+    /// Returns the decompressed contents of the debug section with the given
+    /// name (such as ".debug_line"), or of its older `.zdebug` equivalent.
+    pub fn debug_section_data(&mut self, name: &str) -> Result<Option<Cow<'_, [u8]>>, ElfError> {
+        let gnu_name = name.replacen(".debug", ".zdebug", 1);
+
+        let index = self
+            .section_index_by_name(name)
+            .or_else(|| self.section_index_by_name(&gnu_name));
+
+        match index {
+            Some(index) => Ok(Some(self.section_data(index)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Reads the contents of the sections that DWARF debugging information
+    /// is in (decompressed), for `DebugInfo`. Missing sections are empty.
+    pub fn dwarf_sections(&mut self) -> Result<DwarfSections, ElfError> {
+        let mut read = |name| -> Result<Vec<u8>, ElfError> {
+            Ok(self
+                .debug_section_data(name)?
+                .map(Cow::into_owned)
+                .unwrap_or_default())
+        };
+
+        Ok(DwarfSections {
+            info: read(".debug_info")?,
+            abbrev: read(".debug_abbrev")?,
+            str: read(".debug_str")?,
+            line_str: read(".debug_line_str")?,
+            str_offsets: read(".debug_str_offsets")?,
+            addr: read(".debug_addr")?,
+            rnglists: read(".debug_rnglists")?,
+            loclists: read(".debug_loclists")?,
+            ranges: read(".debug_ranges")?,
+        })
+    }
+
+    /// Reads every unit in .debug_info, with its tree of entries.
+    pub fn debug_info(&mut self) -> Result<DebugInfo, ElfError> {
+        DebugInfo::new(self.dwarf_sections()?)
+    }
+
+    /// Decodes the DWARF line programs in .debug_line, indexed for looking up
+    /// addresses. A file without .debug_line gives an empty table.
+    pub fn line_table(&mut self) -> Result<LineTable, ElfError> {
+        let Some(debug_line) = self.debug_section_data(".debug_line")? else {
+            return Ok(LineTable::new(vec![]));
+        };
+        let debug_line = debug_line.into_owned();
+
+        let sections = self.dwarf_sections()?;
+        let strings = LineStrings {
+            line_str: StringTable::new(&sections.line_str),
+            str: StringTable::new(&sections.str),
+        };
+
+        let mut programs = read_line_programs(&debug_line, &strings)?;
+
+        // Before version 5, the compilation directory that relative paths
+        // are from is only in the unit's root entry.
+        if programs.iter().any(|program| program.header.version < 5) {
+            for header in read_unit_headers(&sections.info)? {
+                let Some(root) = read_root_entry(&sections, &header)? else {
+                    continue;
+                };
+                let (Some(offset), Some(directory)) = (
+                    root.attribute(DW_AT_STMT_LIST)
+                        .and_then(|value| value.as_u64()),
+                    root.attribute(DW_AT_COMP_DIR)
+                        .and_then(|value| value.as_str()),
+                ) else {
+                    continue;
+                };
+
+                for program in &mut programs {
+                    if program.header.offset == offset {
+                        program.compilation_directory = Some(directory.to_owned());
+                    }
+                }
+            }
+        }
+
+        Ok(LineTable::new(programs))
+    }
+
+    /// Finds the source file, line, and column of the code at `address`, from
+    /// the DWARF line programs. To look up many addresses, use `line_table`
+    /// once and look them up in that, instead of decoding them for each one.
+    pub fn source_location(&mut self, address: u64) -> Result<Option<SourceLocation>, ElfError> {
+        Ok(self.line_table()?.source_location(address))
+    }
+
+    /// Indexes the symbols of the symbol table and the dynamic one, by the
+    /// addresses they cover, and the DWARF functions, for the code they
+    /// don't.
+    pub fn symbol_map(&mut self) -> Result<SymbolMap, ElfError> {
+        let mut symbols = vec![];
+        for table in [SHT_SYMTAB, SHT_DYNSYM] {
+            for index in self.section_indices_by_type(table) {
+                symbols.extend(self.symbols(index)?.into_iter().filter(|symbol| {
+                    let entry = &symbol.symbol_data;
+                    !symbol.name.is_empty()
+                        && !is_mapping_symbol(&symbol.name)
+                        && !matches!(entry.symbol_type(), STT_SECTION | STT_FILE | STT_TLS)
+                        && entry.section_index != SHN_UNDEF
+                        && entry.section_index < SHN_LORESERVE
+                }));
+            }
+        }
+
+        // Symbols with no size cover what's up to the next one in their
+        // section, or up to the section's end.
+        let mut starts: Vec<(u16, u64)> = symbols
+            .iter()
+            .map(|symbol| (symbol.symbol_data.section_index, symbol.symbol_data.value))
+            .collect();
+        starts.sort();
+        starts.dedup();
+
+        let mut map_symbols = vec![];
+        for symbol in symbols {
+            let entry = &symbol.symbol_data;
+            let (section, start) = (entry.section_index, entry.value);
+            let end = match entry.size {
+                0 => {
+                    let next = starts.partition_point(|&other| other <= (section, start));
+                    match starts.get(next) {
+                        Some(&(other, end)) if other == section => end,
+                        _ => match self.section_headers.get(section as usize) {
+                            Some(section) => {
+                                let header = &section.header_data;
+                                header.addr.saturating_add(header.size)
+                            }
+                            None => start,
+                        },
+                    }
+                }
+                size => start.saturating_add(size),
+            };
+            let type_rank = match entry.symbol_type() {
+                STT_FUNC | STT_GNU_IFUNC => 0,
+                STT_OBJECT => 1,
+                _ => 2,
+            };
+            let bind_rank = match entry.bind() {
+                STB_GLOBAL => 0,
+                STB_WEAK => 1,
+                _ => 2,
+            };
+            map_symbols.push(MapSymbol {
+                name: symbol.name,
+                start,
+                end,
+                rank: type_rank * 3 + bind_rank,
+            });
+        }
+
+        let sections = self.dwarf_sections()?;
+        let functions = match sections.info.is_empty() {
+            true => vec![],
+            false => DebugInfo::new(sections)?.functions()?,
+        };
+
+        Ok(SymbolMap::new(map_symbols, &functions))
+    }
+
+    /// Names the address after the symbol it's in, as in `main+0x1c`, or
+    /// if no symbol covers it, the DWARF function. To look up many
+    /// addresses, use `symbol_map` once and look them up in that.
+    pub fn symbolize(&mut self, address: u64) -> Result<Option<Symbolized>, ElfError> {
+        Ok(self.symbol_map()?.lookup(address))
+    }
+
+    /// Reads the call frame information in .eh_frame or .debug_frame, if
+    /// the file has that section.
+    pub fn call_frame_info(
+        &mut self,
+        section: FrameSection,
+    ) -> Result<Option<CallFrameInfo>, ElfError> {
+        match section {
+            FrameSection::EhFrame => {
+                let Some(index) = self.section_index_by_name(".eh_frame") else {
+                    return Ok(None);
+                };
+                let address = self.section(index)?.addr;
+                let data = self.section_data(index)?.into_owned();
+
+                Ok(Some(CallFrameInfo::new(section, data, address)))
+            }
+
+            FrameSection::DebugFrame => Ok(self
+                .debug_section_data(".debug_frame")?
+                .map(|data| CallFrameInfo::new(section, data.into_owned(), 0))),
+        }
+    }
+
+    /// Reads the binary search table for .eh_frame. It's found through the
+    /// PT_GNU_EH_FRAME segment, as the unwinder at run time finds it, and
+    /// otherwise through the .eh_frame_hdr section.
+    pub fn eh_frame_hdr(&mut self) -> Result<Option<EhFrameHdr>, ElfError> {
+        let segment = self
+            .program_headers
+            .iter()
+            .map(|segment| &segment.header_data)
+            .find(|segment| segment.type_id() == PT_GNU_EH_FRAME);
+
+        let (offset, size, address) = match segment {
+            Some(segment) => (segment.offset, segment.file_size, segment.virtual_address),
+
+            None => match self.section_index_by_name(".eh_frame_hdr") {
+                Some(index) => {
+                    let section = self.section(index)?;
+                    (section.offset, section.size, section.addr)
+                }
+                None => return Ok(None),
+            },
+        };
+
+        let bytes = self
+            .bytes(offset, size)
+            .map_err(|e| e.reading(".eh_frame_hdr"))?;
+
+        Ok(Some(read_eh_frame_hdr(bytes, address)?))
+    }
+
+    /// Reads the SFrame stack trace information. Like .eh_frame_hdr, it's
+    /// found through its segment (PT_GNU_SFRAME) if there is one, and
+    /// otherwise through the .sframe section.
+    pub fn sframe(&mut self) -> Result<Option<SframeSection>, ElfError> {
+        let segment = self
+            .program_headers
+            .iter()
+            .map(|segment| &segment.header_data)
+            .find(|segment| segment.type_id() == PT_GNU_SFRAME);
+
+        let (offset, size, address) = match segment {
+            Some(segment) => (segment.offset, segment.file_size, segment.virtual_address),
+
+            None => match self.section_index_by_name(".sframe") {
+                Some(index) => {
+                    let section = self.section(index)?;
+                    (section.offset, section.size, section.addr)
+                }
+                None => return Ok(None),
+            },
+        };
+
+        let data = self
+            .bytes(offset, size)
+            .map_err(|e| e.reading(".sframe"))?
+            .to_vec();
+
+        Ok(Some(read_sframe(data, address)?))
+    }
+
+    /// Finds the rules for unwinding a frame of the code at `address`: from
+    /// .eh_frame, with .eh_frame_hdr to find its FDE if there is one, and
+    /// otherwise from .debug_frame.
+    pub fn unwind_row(&mut self, address: u64) -> Result<Option<UnwindRow>, ElfError> {
+        if let Some(eh_frame) = self.call_frame_info(FrameSection::EhFrame)? {
+            let fde = match self.eh_frame_hdr()? {
+                Some(hdr) => match hdr.search(address) {
+                    Some(fde_address) => {
+                        Some(eh_frame.fde_at(fde_address.wrapping_sub(eh_frame.address()))?)
+                    }
+                    None => None,
+                },
+                None => eh_frame.find_fde(address)?,
+            };
+
+            if let Some(fde) = fde.filter(|fde| fde.range.contains(&address)) {
+                return Ok(eh_frame.unwind_table(&fde)?.row_at(address).cloned());
+            }
+        }
+
+        if let Some(debug_frame) = self.call_frame_info(FrameSection::DebugFrame)? {
+            if let Some(fde) = debug_frame.find_fde(address)? {
+                return Ok(debug_frame.unwind_table(&fde)?.row_at(address).cloned());
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Returns the program header with the given index.
+    pub fn segment(&self, index: usize) -> Result<&Elf64ProgramHeaderEntry, ElfError> {
+        self.program_headers
+            .get(index)
+            .map(|segment| &segment.header_data)
+            .ok_or(ElfError::InvalidIndex {
+                what: "segment",
+                index,
+            })
+    }
+
+    /// Returns the contents of the segment with the given index as they are
+    /// loaded: the bytes from the file, then zeros for the rest of its size
+    /// in memory (where .bss usually is).
+    pub fn segment_data(&mut self, index: usize) -> Result<Cow<'_, [u8]>, ElfError> {
+        let segment = self.segment(index)?;
+        let (offset, file_size, mem_size) = (segment.offset, segment.file_size, segment.mem_size);
+
+        if mem_size > file_size {
+            let header_offset = self.header.program_header_offset
+                + index as u64 * self.header.program_header_entry_size as u64;
+            zero_fill_size("Program header", header_offset, mem_size)?;
+        }
+
+        let bytes = self
+            .bytes(offset, file_size)
+            .map_err(|e| e.reading("Segment contents"))?;
+
+        if mem_size <= file_size {
+            return Ok(Cow::Borrowed(bytes));
+        }
+
+        let mut data = bytes.to_vec();
+        data.resize(mem_size as usize, 0);
+
+        Ok(Cow::Owned(data))
+    }
+
+    pub fn section_index_by_name(&self, name: &str) -> Option<usize> {
+        self.section_headers.iter().position(|s| s.name == name)
+    }
+
+    /// Indices of the sections of the given type.
+    pub fn section_indices_by_type(&self, section_type: u32) -> Vec<usize> {
+        (0..self.section_headers.len())
+            .filter(|&i| self.section_headers[i].header_data.type_id() == section_type)
+            .collect()
+    }
+
+    /// Returns the string table section with the given index. This
+    /// borrows the file, so other sections can't be read meanwhile.
+    pub fn string_table(&mut self, index: usize) -> Result<StringTable<'_>, ElfError> {
+        Ok(StringTable::new(self.section_bytes(index)?))
+    }
+
+    /// Reads the symbols in the symbol table section with the given index.
+    pub fn symbols(&mut self, index: usize) -> Result<Vec<Elf64SymbolInfo>, ElfError> {
+        let link = self.section(index)?.link as usize;
+
+        let string_table = self.section_bytes(link)?.to_vec();
+        let table = self.section_bytes(index)?;
+
+        Ok(read_symbols_64(table, &StringTable::new(&string_table)))
+    }
+
+    /// Reads the symbol at `index` in the symbol table section with
+    /// the given index, without reading the rest of the table.
+    pub fn symbol(&mut self, table: usize, index: usize) -> Result<Elf64SymbolInfo, ElfError> {
+        let section = self.section(table)?;
+        let (offset, size, link) = (section.offset, section.size, section.link as usize);
+
+        let len = ELF64_SYMBOL_LEN as u64;
+        let entry_offset = (index as u64)
+            .checked_mul(len)
+            .filter(|entry_offset| entry_offset.checked_add(len).is_some_and(|end| end <= size))
+            .ok_or(ElfError::InvalidIndex {
+                what: "symbol",
+                index,
+            })?;
+
+        // A table at a bogus offset can run past the end of the address space.
+        let start = offset
+            .checked_add(entry_offset)
+            .ok_or(ElfError::Truncated {
+                what: "Symbol",
+                offset,
+                needed: entry_offset + len,
+            })?;
+        let bytes = self.bytes(start, len).map_err(|e| e.reading("Symbol"))?;
+        let symbol_data = Elf64SymbolEntry::parse_from_bytes(bytes);
+
+        let name = self
+            .string_table(link)?
+            .get_lossy(symbol_data.name_offset as usize)
+            .unwrap_or_default()
+            .into_owned();
+
+        Ok(Elf64SymbolInfo { symbol_data, name })
+    }
+
+    /// Finds the dynamic symbol that this file defines with the given name,
+    /// along with its index. This goes through the .gnu.hash or .hash table,
+    /// as the dynamic linker does, so only the symbols with the same hash
+    /// are read; without either table, it searches the whole of .dynsym.
+    pub fn lookup_dynamic_symbol(
+        &mut self,
+        name: &str,
+    ) -> Result<Option<(usize, Elf64SymbolInfo)>, ElfError> {
+        let Some(&dynsym) = self.section_indices_by_type(SHT_DYNSYM).first() else {
+            return Ok(None);
+        };
+
+        let is_match = |elf_file: &mut ElfFile, index: usize| -> Result<bool, ElfError> {
+            let symbol = elf_file.symbol(dynsym, index)?;
+
+            Ok(symbol.name == name && symbol.symbol_data.section_index != SHN_UNDEF)
+        };
+
+        let found = if let Some(index) = self.hash_table_for(SHT_GNU_HASH, dynsym) {
+            let table = read_gnu_hash_64(self.section_bytes(index)?)?;
+            table.lookup(name.as_bytes(), |i| is_match(self, i))?
+        } else if let Some(index) = self.hash_table_for(SHT_HASH, dynsym) {
+            let table = read_sysv_hash_64(self.section_bytes(index)?)?;
+            table.lookup(name.as_bytes(), |i| is_match(self, i))?
+        } else {
+            let symbols = self.symbols(dynsym)?;
+
+            return Ok(symbols.into_iter().enumerate().find(|(_, symbol)| {
+                symbol.name == name && symbol.symbol_data.section_index != SHN_UNDEF
+            }));
+        };
+
+        match found {
+            Some(index) => Ok(Some((index, self.symbol(dynsym, index)?))),
+            None => Ok(None),
+        }
+    }
+
+    /// The hash table section of the given type that indexes the symbol table `symbols`.
+    fn hash_table_for(&self, section_type: u32, symbols: usize) -> Option<usize> {
+        self.section_indices_by_type(section_type)
+            .into_iter()
+            .find(|&index| self.section_headers[index].header_data.link as usize == symbols)
+    }
+
+    /// Checks each .gnu.hash and .hash table against the symbol table it
+    /// indexes. A table that disagrees with the symbols (as after patching
+    /// a binary by hand) means the dynamic linker can't find some of them.
+    /// Inconsistencies are warnings, and a table that can't be read is an error.
+    pub fn check_hash_tables(&mut self) -> Vec<Diagnostic> {
+        let mut diagnostics = vec![];
+
+        let mut tables = self.section_indices_by_type(SHT_GNU_HASH);
+        tables.extend(self.section_indices_by_type(SHT_HASH));
+
+        for index in tables {
+            let section = &self.section_headers[index];
+            let (offset, link) = (
+                section.header_data.offset,
+                section.header_data.link as usize,
+            );
+            let is_gnu = section.header_data.type_id() == SHT_GNU_HASH;
+
+            let name = section.name.clone();
+            let diagnostic = |severity, message| Diagnostic {
+                severity,
+                offset,
+                message: format!("{name}: {message}"),
+            };
+
+            let linked_type = self
+                .section_headers
+                .get(link)
+                .map(|s| s.header_data.type_id());
+            if !matches!(linked_type, Some(SHT_DYNSYM | SHT_SYMTAB)) {
+                diagnostics.push(diagnostic(
+                    Severity::Warning,
+                    format!("Links to section {link}, which isn't a symbol table."),
+                ));
+                continue;
+            }
+
+            let problems = self.symbols(link).and_then(|symbols| {
+                let bytes = self.section_bytes(index)?;
+
+                if is_gnu {
+                    Ok(check_gnu_hash(&read_gnu_hash_64(bytes)?, &symbols))
+                } else {
+                    Ok(check_sysv_hash(&read_sysv_hash_64(bytes)?, &symbols))
+                }
+            });
+
+            match problems {
+                Ok(problems) => diagnostics.extend(
+                    problems
+                        .into_iter()
+                        .map(|problem| diagnostic(Severity::Warning, problem)),
+                ),
+                Err(error) => diagnostics.push(diagnostic(Severity::Error, error.to_string())),
+            }
+        }
+
+        diagnostics
+    }
+
+    /// Reads the dynamic section, if there is one, along with the
+    /// contents of the string table it refers to.
+    pub fn dynamic(&mut self) -> Result<Option<DynamicSection>, ElfError> {
+        let Some(&index) = self.section_indices_by_type(SHT_DYNAMIC).first() else {
+            return Ok(None);
+        };
+
+        let link = self.section(index)?.link as usize;
+
+        let string_table = self.section_bytes(link)?.to_vec();
+        let entries = read_dynamic_64(self.section_bytes(index)?);
+
+        Ok(Some(DynamicSection {
+            entries,
+            string_table,
+        }))
+    }
+
+    /// True if this is a position-independent executable, rather than a shared library.
+    pub fn is_pie(&mut self) -> Result<bool, ElfError> {
+        Ok(self
+            .dynamic()?
+            .is_some_and(|dynamic| is_pie(&dynamic.entries)))
+    }
+
+    /// Reads the symbol versioning sections, if there are any.
+    pub fn symbol_versions(&mut self) -> Result<Option<SymbolVersions>, ElfError> {
+        let Some(&versym_index) = self.section_indices_by_type(SHT_GNU_VERSYM).first() else {
+            return Ok(None);
+        };
+
+        let mut versions = SymbolVersions {
+            versym: read_versym_64(self.section_bytes(versym_index)?),
+            ..Default::default()
+        };
+
+        if let Some(&index) = self.section_indices_by_type(SHT_GNU_VERNEED).first() {
+            let section = self.section(index)?;
+            let (link, count) = (section.link as usize, section.info as usize);
+
+            let string_table = self.section_bytes(link)?.to_vec();
+            versions.needs = read_verneed_64(
+                self.section_bytes(index)?,
+                count,
+                &StringTable::new(&string_table),
+            )?;
+        }
+
+        if let Some(&index) = self.section_indices_by_type(SHT_GNU_VERDEF).first() {
+            let section = self.section(index)?;
+            let (link, count) = (section.link as usize, section.info as usize);
+
+            let string_table = self.section_bytes(link)?.to_vec();
+            versions.defs = read_verdef_64(
+                self.section_bytes(index)?,
+                count,
+                &StringTable::new(&string_table),
+            )?;
+        }
+
+        Ok(Some(versions))
+    }
+
+    /// Reads the relocations in the SHT_REL or SHT_RELA section with the given index.
+    pub fn relocations(&mut self, index: usize) -> Result<Vec<Elf64Relocation>, ElfError> {
+        let is_rela = self.section(index)?.type_id() == SHT_RELA;
+
+        Ok(read_relocations_64(self.section_bytes(index)?, is_rela))
+    }
+
+    /// Reads the addresses relocated by the SHT_RELR section with the given index.
+    pub fn relr_offsets(&mut self, index: usize) -> Result<Vec<u64>, ElfError> {
+        Ok(read_relr_64(self.section_bytes(index)?))
+    }
+
+    /// Finds the functions and objects imported through the GOT, with the
+    /// PLT stubs that jump to them, from the dynamic relocations that fill
+    /// in the GOT slots (R_*_JUMP_SLOT, R_*_GLOB_DAT and R_*_IRELATIVE,
+    /// for x86-64 and AArch64). For other machines, there are none.
+    pub fn imports(&mut self) -> Result<Imports, ElfError> {
+        let machine = self.header.machine_id();
+        let (jump_slot, glob_dat, irelative) = match machine {
+            EM_X86_64 => (R_X86_64_JUMP_SLOT, R_X86_64_GLOB_DAT, R_X86_64_IRELATIVE),
+            EM_AARCH64 => (R_AARCH64_JUMP_SLOT, R_AARCH64_GLOB_DAT, R_AARCH64_IRELATIVE),
+            _ => return Ok(Imports::default()),
+        };
+
+        let mut imports = vec![];
+        for index in self.section_indices_by_type(SHT_RELA) {
+            let table = self.section(index)?.link as usize;
+            if self.section(table)?.type_id() != SHT_DYNSYM {
+                continue;
+            }
+            let symbols = self.symbols(table)?;
+
+            for relocation in self.relocations(index)? {
+                let symbol_index = relocation.symbol_index() as usize;
+                let name = match relocation.reloc_type() {
+                    t if t == irelative => {
+                        format!("*ABS*+{:#x}", relocation.addend.unwrap_or(0))
+                    }
+                    t if t == jump_slot || t == glob_dat => match symbols.get(symbol_index) {
+                        Some(symbol) if symbol_index != 0 => symbol.name.clone(),
+                        _ => continue,
+                    },
+                    _ => continue,
+                };
+                imports.push(Import {
+                    name,
+                    symbol_index,
+                    got: relocation.offset,
+                    plt: None,
+                });
+            }
+        }
+
+        let mut stubs = vec![];
+        for index in 0..self.section_headers.len() {
+            let section = &self.section_headers[index];
+            let header = &section.header_data;
+            if !section.name.starts_with(".plt")
+                || header.flags & SHF_EXECINSTR == 0
+                || header.type_id() != SHT_PROGBITS
+            {
+                continue;
+            }
+            // Entries are 16 bytes, but for .plt.got without IBT, 8.
+            let entry_size = match header.entry_size {
+                0 => 16,
+                size => size as usize,
+            };
+            let address = header.addr;
+            let bytes = self.section_bytes(index)?;
+            stubs.extend(match machine {
+                EM_X86_64 => x86_64_stubs(bytes, address, entry_size),
+                _ => aarch64_stubs(bytes, address),
+            });
+        }
+
+        Ok(Imports::new(imports, &stubs))
+    }
+
+    /// Reads the processor-specific build attributes (`.riscv.attributes`,
+    /// or `.ARM.attributes`), if the file has them.
+    pub fn build_attributes(&mut self) -> Result<Vec<AttributesSubsection>, ElfError> {
+        let section_type = match self.header.machine_id() {
+            EM_ARM => SHT_ARM_ATTRIBUTES,
+            EM_AARCH64 => SHT_AARCH64_ATTRIBUTES,
+            EM_RISCV => SHT_RISCV_ATTRIBUTES,
+            _ => return Ok(vec![]),
+        };
+        match self.section_indices_by_type(section_type).first() {
+            Some(&index) => read_attributes(self.section_bytes(index)?),
+            None => Ok(vec![]),
+        }
+    }
+
+    /// Returns the GNU build ID, if the file has one.
+    pub fn build_id(&mut self) -> Result<Option<Vec<u8>>, ElfError> {
+        for index in self.section_indices_by_type(SHT_NOTE) {
+            let align = self.section(index)?.addr_align;
+
+            if let Some(id) = find_build_id(read_notes(self.section_bytes(index)?, align)) {
+                return Ok(Some(id));
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+/// Reads one of the header tables: `count` entries of `entry_size` bytes at
+/// `offset`. When parsing leniently, a table that the end of the file cuts
+/// short is read as far as it goes, and one whose entries are too small to
+/// hold an entry (`min_entry_size`) is skipped.
+fn read_table<'s>(
+    source: &'s mut dyn ByteSource,
+    offset: u64,
+    count: u16,
+    entry_size: u16,
+    min_entry_size: usize,
+    what: &'static str,
+    recorder: &mut Recorder,
+) -> Result<&'s [u8], ElfError> {
+    if count == 0 {
+        return Ok(&[]);
+    }
+
+    if (entry_size as usize) < min_entry_size {
+        let error = ElfError::InvalidEntrySize {
+            what,
+            size: entry_size as u64,
+        };
+        recorder.error(offset, error)?;
+
+        return Ok(&[]);
+    }
+
+    let needed = count as u64 * entry_size as u64;
+    let mut size = needed;
+
+    let available = source.len().saturating_sub(offset);
+    if needed > available {
+        recorder.error(
+            offset,
+            ElfError::Truncated {
+                what,
+                offset,
+                needed,
+            },
+        )?;
+
+        size = available - available % entry_size as u64;
+        if size == 0 {
+            return Ok(&[]);
+        }
+    }
+
+    source.bytes(offset, size).map_err(|e| e.reading(what))
+}
+
+/// Checks that `size` bytes can be filled with zeros, for the section or
+/// segment whose header is at `offset`.
+fn zero_fill_size(what: &'static str, offset: u64, size: u64) -> Result<usize, ElfError> {
+    if size > MAX_ZERO_FILL {
+        return Err(ElfError::InvalidData {
+            what,
+            offset,
+            reason: format!("its size in memory, {size:#x} bytes, is too large"),
+        });
+    }
+
+    Ok(size as usize)
+}
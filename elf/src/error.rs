@@ -0,0 +1,175 @@
+/// The errors that opening and parsing a file can produce. They carry enough
+/// detail (what was being read, and where) to be matched on, or reported.
+///
+use std::fmt;
+use std::io;
+
+use crate::parse::header::Machine;
+
+#[derive(Debug)]
+pub enum ElfError {
+    /// Reading the file failed.
+    Io(io::Error),
+
+    /// `what` starts at `offset` and needs `needed` bytes, but the
+    /// file, or the section or segment it's in, ends before that.
+    Truncated {
+        what: &'static str,
+        offset: u64,
+        needed: u64,
+    },
+
+    /// The file doesn't start with the ELF magic bytes.
+    BadMagic,
+
+    /// The file isn't 64-bit little endian, the only kind we support.
+    UnsupportedClass { class: u8, endianness: u8 },
+
+    /// An index (into the section header table, for example) that is out of range.
+    InvalidIndex { what: &'static str, index: usize },
+
+    /// A compressed section in a format (`ch_type`) that we can't decompress.
+    UnsupportedCompression { compression_type: u32 },
+
+    /// A compressed section whose data is corrupt, or isn't the size its header says.
+    Decompression { message: String },
+
+    /// A section looked up by a name that none of the sections have.
+    UnknownSection { name: String },
+
+    /// A structure, class, or union looked up by a name that the
+    /// debugging information doesn't define.
+    UnknownType { name: String },
+
+    /// A function looked up by a name that none of those found in the
+    /// code have.
+    UnknownFunction { name: String },
+
+    /// A table whose entries (of `size` bytes) are too small to hold one.
+    InvalidEntrySize { what: &'static str, size: u64 },
+
+    /// A string table offset that is out of range, or whose string isn't
+    /// terminated (or, when a `&str` was asked for, isn't UTF-8).
+    InvalidString { what: &'static str, offset: u64 },
+
+    /// A structure (a DWARF unit, for example) in a version we can't read.
+    UnsupportedVersion { what: &'static str, version: u16 },
+
+    /// A file for a machine (`e_machine`) whose instructions we can't decode.
+    UnsupportedMachine { machine: u16 },
+
+    /// A structure at `offset` (in its section) whose contents don't make sense.
+    InvalidData {
+        what: &'static str,
+        offset: u64,
+        reason: String,
+    },
+
+    /// A value that unwinding a stack frame needs (a register, or memory)
+    /// that the caller doesn't have.
+    Unavailable { what: String },
+}
+
+impl fmt::Display for ElfError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ElfError::Io(error) => write!(f, "I/O error: {error}."),
+
+            ElfError::Truncated {
+                what,
+                offset,
+                needed,
+            } => write!(
+                f,
+                "{what} at offset {offset:#x} needs {needed} bytes, but the data ends before that."
+            ),
+
+            ElfError::BadMagic => write!(f, "Parse failed: Bad file format."),
+
+            ElfError::UnsupportedClass { class, endianness } => write!(
+                f,
+                "This reader currently only supports 64-bit little endian ELF files \
+                 (class {class}, data encoding {endianness})."
+            ),
+
+            ElfError::InvalidIndex { what, index } => write!(f, "No {what} with index {index}."),
+
+            ElfError::UnsupportedCompression { compression_type } => write!(
+                f,
+                "Sections compressed with type {compression_type} are not supported."
+            ),
+
+            ElfError::Decompression { message } => {
+                write!(f, "Could not decompress section: {message}.")
+            }
+
+            ElfError::UnknownSection { name } => write!(f, "No section named {name}."),
+
+            ElfError::UnknownType { name } => {
+                write!(f, "No structure, class, or union named {name}.")
+            }
+
+            ElfError::UnknownFunction { name } => write!(f, "No function named {name}."),
+
+            ElfError::InvalidEntrySize { what, size } => write!(
+                f,
+                "{what} entries are {size} bytes, which is too small to hold one."
+            ),
+
+            ElfError::InvalidString { what, offset } => write!(
+                f,
+                "{what} at string table offset {offset:#x} is out of range, not terminated, or not UTF-8."
+            ),
+
+            ElfError::UnsupportedVersion { what, version } => {
+                write!(f, "{what} version {version} is not supported.")
+            }
+
+            ElfError::UnsupportedMachine { machine } => write!(
+                f,
+                "Disassembling code for {} is not supported.",
+                Machine::from(*machine).description()
+            ),
+
+            ElfError::InvalidData {
+                what,
+                offset,
+                reason,
+            } => write!(f, "{what} at offset {offset:#x} is invalid: {reason}."),
+
+            ElfError::Unavailable { what } => write!(f, "{what} isn't available."),
+        }
+    }
+}
+
+impl std::error::Error for ElfError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ElfError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ElfError {
+    fn from(error: io::Error) -> ElfError {
+        ElfError::Io(error)
+    }
+}
+
+impl ElfError {
+    /// Names what was being read in a `Truncated` or `InvalidString` error,
+    /// which byte sources and string tables don't know about; other errors
+    /// are returned as they are.
+    pub fn reading(self, what: &'static str) -> ElfError {
+        match self {
+            ElfError::Truncated { offset, needed, .. } => ElfError::Truncated {
+                what,
+                offset,
+                needed,
+            },
+            ElfError::InvalidString { offset, .. } => ElfError::InvalidString { what, offset },
+            other => other,
+        }
+    }
+}
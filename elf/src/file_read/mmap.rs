@@ -0,0 +1,36 @@
+/// A file mapped into memory, so that the operating system
+/// pages in only the parts that are actually read.
+///
+use std::fs::File;
+
+use memmap2::Mmap;
+
+use super::{checked_range, ByteSource};
+use crate::ElfError;
+
+pub struct MmapSource {
+    map: Mmap,
+}
+
+impl MmapSource {
+    pub fn new(file: &File) -> Result<MmapSource, ElfError> {
+        // SAFETY: The mapping is only read. If another process truncates or
+        // rewrites the file while it's mapped, reads may see the new contents
+        // or fault; as with other tools that map files, we accept that.
+        let map = unsafe { Mmap::map(file) }?;
+
+        Ok(MmapSource { map })
+    }
+}
+
+impl ByteSource for MmapSource {
+    fn len(&self) -> u64 {
+        self.map.len() as u64
+    }
+
+    fn bytes(&mut self, offset: u64, size: u64) -> Result<&[u8], ElfError> {
+        let (start, end) = checked_range(offset, size, self.len())?;
+
+        Ok(&self.map[start..end])
+    }
+}
@@ -1,46 +1,39 @@
-/// Utility to read bytes from a file for parsing.
+/// Sources of bytes for parsing. Parsing asks for the exact ranges it needs
+/// (a header, a table, a section's contents), so that a large file never has
+/// to be read into memory as a whole.
 ///
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+mod mmap;
+mod seek;
+mod slice;
 
-// In case we're reading a large file, we don't read it into
-// memory all at once. Instead we read chunks of size BUFFER_SIZE.
-const BUFFER_SIZE: usize = 64;
+pub use mmap::MmapSource;
+pub use seek::SeekSource;
+pub use slice::SliceSource;
 
-pub struct FileReader {
-    reader: BufReader<File>,
-    current_buffer: Vec<u8>,
-}
-
-impl FileReader {
-    pub fn new(file: File) -> FileReader {
-        FileReader {
-            reader: BufReader::with_capacity(BUFFER_SIZE, file),
-            current_buffer: vec![],
-        }
-    }
-
-    /// Ensures our buffer contains at least `length` bytes.
-    pub fn ensure_length(&mut self, length: usize) -> Result<(), String> {
-        while self.current_buffer.len() < length {
-            let buffer = self.reader.fill_buf().unwrap();
-            self.current_buffer.extend_from_slice(buffer);
+use crate::ElfError;
 
-            let bytes_read = buffer.len();
+pub trait ByteSource {
+    /// Total number of bytes available.
+    fn len(&self) -> u64;
 
-            if bytes_read == 0 {
-                return Err(String::from(
-                    "Requested file contains less than requested number of bytes.",
-                ));
-            }
-
-            self.reader.consume(bytes_read);
-        }
-
-        Ok(())
+    fn is_empty(&self) -> bool {
+        self.len() == 0
     }
 
-    pub fn buffer(&self) -> &[u8] {
-        &self.current_buffer[0..]
+    /// Returns the `size` bytes starting at `offset`, or a
+    /// `Truncated` error if any of them are past the end of the source.
+    fn bytes(&mut self, offset: u64, size: u64) -> Result<&[u8], ElfError>;
+}
+
+/// Checks that `size` bytes at `offset` lie within a source of length
+/// `len`, and returns the range as indices.
+fn checked_range(offset: u64, size: u64, len: u64) -> Result<(usize, usize), ElfError> {
+    match offset.checked_add(size) {
+        Some(end) if end <= len => Ok((offset as usize, end as usize)),
+        _ => Err(ElfError::Truncated {
+            what: "Read",
+            offset,
+            needed: size,
+        }),
     }
 }
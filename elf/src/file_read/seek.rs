@@ -0,0 +1,128 @@
+/// A file read on demand with positioned reads, for when mapping it isn't
+/// possible or wanted. Recently read pages are kept in a small cache, since
+/// parsing tends to come back to the same tables (string tables especially).
+///
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+
+use super::{checked_range, ByteSource};
+use crate::ElfError;
+
+const PAGE_SIZE: u64 = 4096;
+
+// With 4 KB pages, the cache holds up to 1 MB by default.
+const DEFAULT_CACHE_PAGES: usize = 256;
+
+struct Page {
+    index: u64,
+    data: Vec<u8>,
+}
+
+pub struct SeekSource {
+    file: File,
+    len: u64,
+
+    /// Cached pages, with the most recently used last.
+    cache: Vec<Page>,
+    cache_pages: usize,
+
+    /// Holds the bytes of a range that spans more than one page.
+    scratch: Vec<u8>,
+}
+
+impl SeekSource {
+    pub fn new(file: File) -> Result<SeekSource, ElfError> {
+        SeekSource::with_cache_pages(file, DEFAULT_CACHE_PAGES)
+    }
+
+    /// Creates a source that caches at most `cache_pages` pages (at least one).
+    pub fn with_cache_pages(file: File, cache_pages: usize) -> Result<SeekSource, ElfError> {
+        let len = file.metadata()?.len();
+
+        Ok(SeekSource {
+            file,
+            len,
+            cache: vec![],
+            cache_pages: cache_pages.max(1),
+            scratch: vec![],
+        })
+    }
+
+    /// Reads `buffer.len()` bytes at `offset` straight from the file.
+    fn read_at(&mut self, offset: u64, buffer: &mut [u8]) -> Result<(), ElfError> {
+        self.file.seek(SeekFrom::Start(offset))?;
+        self.file.read_exact(buffer)?;
+
+        Ok(())
+    }
+
+    /// Makes the page with the given index the most recently used
+    /// one in the cache, reading it in if it isn't there already.
+    fn load_page(&mut self, index: u64) -> Result<&Page, ElfError> {
+        if let Some(position) = self.cache.iter().position(|page| page.index == index) {
+            let page = self.cache.remove(position);
+            self.cache.push(page);
+        } else {
+            let start = index * PAGE_SIZE;
+            let mut data = vec![0; PAGE_SIZE.min(self.len - start) as usize];
+            self.read_at(start, &mut data)?;
+
+            if self.cache.len() >= self.cache_pages {
+                self.cache.remove(0);
+            }
+            self.cache.push(Page { index, data });
+        }
+
+        Ok(self.cache.last().unwrap())
+    }
+}
+
+impl ByteSource for SeekSource {
+    fn len(&self) -> u64 {
+        self.len
+    }
+
+    fn bytes(&mut self, offset: u64, size: u64) -> Result<&[u8], ElfError> {
+        let (start, end) = checked_range(offset, size, self.len)?;
+        if start == end {
+            return Ok(&[]);
+        }
+
+        let first_page = offset / PAGE_SIZE;
+        let last_page = (end as u64 - 1) / PAGE_SIZE;
+
+        if first_page == last_page {
+            let page_start = (offset % PAGE_SIZE) as usize;
+            let page = self.load_page(first_page)?;
+
+            return Ok(&page.data[page_start..page_start + (end - start)]);
+        }
+
+        // A range that spans pages is copied out. A large one is read directly,
+        // so that it doesn't push everything else out of the cache.
+        let mut scratch = std::mem::take(&mut self.scratch);
+        scratch.resize(end - start, 0);
+
+        if (last_page - first_page) as usize >= self.cache_pages / 4 {
+            self.read_at(offset, &mut scratch)?;
+        } else {
+            let mut position = offset;
+            let mut filled = 0;
+
+            for index in first_page..=last_page {
+                let page_start = (position % PAGE_SIZE) as usize;
+                let page = self.load_page(index)?;
+
+                let count = (page.data.len() - page_start).min(scratch.len() - filled);
+                scratch[filled..filled + count]
+                    .copy_from_slice(&page.data[page_start..page_start + count]);
+
+                filled += count;
+                position += count as u64;
+            }
+        }
+
+        self.scratch = scratch;
+        Ok(&self.scratch)
+    }
+}
@@ -0,0 +1,27 @@
+/// Bytes that are already in memory, such as a file embedded in
+/// the program or one that was read or decompressed elsewhere.
+///
+use super::{checked_range, ByteSource};
+use crate::ElfError;
+
+pub struct SliceSource<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> SliceSource<'a> {
+    pub fn new(bytes: &'a [u8]) -> SliceSource<'a> {
+        SliceSource { bytes }
+    }
+}
+
+impl ByteSource for SliceSource<'_> {
+    fn len(&self) -> u64 {
+        self.bytes.len() as u64
+    }
+
+    fn bytes(&mut self, offset: u64, size: u64) -> Result<&[u8], ElfError> {
+        let (start, end) = checked_range(offset, size, self.len())?;
+
+        Ok(&self.bytes[start..end])
+    }
+}
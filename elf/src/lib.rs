@@ -0,0 +1,16 @@
+/// Library for parsing ELF binaries.
+///
+/// The `elf` binary is a thin command-line front end for this.
+///
+pub mod demangle;
+mod diagnostic;
+pub mod disasm;
+pub mod dwarf;
+mod elf_file;
+mod error;
+pub mod file_read;
+pub mod parse;
+
+pub use diagnostic::{Diagnostic, Recorder, Severity};
+pub use elf_file::ElfFile;
+pub use error::ElfError;
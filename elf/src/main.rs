@@ -2,117 +2,73 @@
 ///
 /// Created by sean on 1/1/25.
 ///
-mod file_read;
-mod parse;
+mod cli;
 
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-use std::str;
-
-use crate::file_read::FileReader;
-use crate::parse::*;
+use std::io;
+use std::process::ExitCode;
 
+use clap::Parser;
 use colored::Colorize;
 
-#[macro_use]
-extern crate from_bytes_macro;
-
-// ------------
-// Some config.
+use elf::ElfError;
 
-// TODO: Path hardcoded for testing.
-const FILE: &str = "/home/sean/Code/A-K/binary_parsing/elf/test/AudioPlayer";
+use crate::cli::{Args, Command};
 
 // -------------------
 // Program entrypoint.
 
-fn main() -> Result<(), String> {
-    println!("Parsing binary file: {}", FILE);
-
-    // -----------------------------
-    // Create reader for file bytes.
-
-    let file = File::open(FILE).unwrap();
-
-    // In case we're reading a large file, we don't read it into memory all at once.
-    let mut reader = FileReader::new(file);
-
-    let mut bytes_needed = ELF64_HEADER_LEN;
-    reader.ensure_length(bytes_needed)?;
-
-    let buffer = reader.buffer();
-
-    // -------------------
-    // Verify magic bytes.
-
-    if &buffer[..4] == b"\x7F\x45\x4c\x46" {
-        println!("Found ELF magic bytes; will continue parsing file as ELF.");
-    } else {
-        println!("Did not find ELF magic bytes; aborting file parse.");
+fn main() -> ExitCode {
+    let args = Args::parse();
+    cli::configure_color(args.color);
+
+    // Keep going after a failure, so that every file gets reported on.
+    let mut failed = false;
+
+    let files = match &args.command {
+        Some(Command::Lint { files }) => files,
+        Some(Command::Dump(dump)) => std::slice::from_ref(&dump.file),
+        Some(Command::Addr2line(addr2line)) => std::slice::from_ref(&addr2line.file),
+        Some(Command::Symbolize(symbolize)) => std::slice::from_ref(&symbolize.file),
+        Some(Command::Functions { file }) => std::slice::from_ref(file),
+        Some(Command::Layout(layout)) => std::slice::from_ref(&layout.file),
+        Some(Command::Unwind(unwind)) => std::slice::from_ref(&unwind.file),
+        Some(Command::Disasm(disasm)) => std::slice::from_ref(&disasm.file),
+        Some(Command::Cfg(cfg)) => std::slice::from_ref(&cfg.file),
+        None => &args.files,
+    };
 
-        return Err("Parse failed: Bad file format.".to_string());
+    for path in files {
+        // Linting succeeds on a file with errors in it, but still fails overall.
+        let result = match &args.command {
+            Some(Command::Lint { .. }) => cli::lint(path),
+            Some(Command::Dump(dump)) => cli::dump(path, dump).map(|()| true),
+            Some(Command::Addr2line(addr2line)) => cli::addr2line(path, addr2line).map(|()| true),
+            Some(Command::Symbolize(symbolize)) => cli::symbolize(path, symbolize).map(|()| true),
+            Some(Command::Functions { .. }) => cli::functions(path).map(|()| true),
+            Some(Command::Layout(layout)) => cli::layout(path, layout).map(|()| true),
+            Some(Command::Unwind(unwind)) => cli::unwind(path, unwind).map(|()| true),
+            Some(Command::Disasm(disasm)) => cli::disasm(path, disasm).map(|()| true),
+            Some(Command::Cfg(cfg)) => cli::cfg(path, cfg).map(|()| true),
+            None => cli::run(path, &args).map(|()| true),
+        };
+
+        match result {
+            Ok(passed) => failed |= !passed,
+
+            // Whatever reads the output has stopped reading it, as `head`
+            // does, so there's no one left to write to.
+            Err(ElfError::Io(error)) if error.kind() == io::ErrorKind::BrokenPipe => break,
+
+            Err(message) => {
+                eprintln!("{}: {}: {message}", "error".red().bold(), path.display());
+                failed = true;
+            }
+        }
     }
 
-    // ----------------
-    // Read ELF header.
-
-    // Ensure that file is 64-bit ELF; we currently only support that combo.
-    let elf_header: Elf64Header = if buffer[4] == b'\x02' && buffer[5] == b'\x01' {
-        Elf64Header::parse_from_bytes(buffer)
+    if failed {
+        ExitCode::FAILURE
     } else {
-        return Err(
-            "This reader currently only supports 64-bit little endian ELF files.".to_string(),
-        );
-    };
-
-    println!("\n>> {} <<", "ELF main header.".red());
-    println!("\n{}: {:#04x?}", "Header data".green().bold(), elf_header);
-
-    // ---------------------
-    // Read program headers.
-
-    let program_header_size = elf_header.program_header_entry_count as usize
-        * elf_header.program_header_entry_size as usize;
-
-    bytes_needed = elf_header.program_header_offset as usize + program_header_size;
-    reader.ensure_length(bytes_needed)?;
-
-    let program_headers = read_program_headers_64(reader.buffer(), &elf_header);
-    println!("\n>> {} <<", "Program headers.".red());
-
-    program_headers.iter().for_each(|program_header| {
-        println!(
-            "\n{} type: {}",
-            "Program header".blue().bold(),
-            program_header.type_string
-        );
-        println!("Data: {:#04x?}", program_header.header_data);
-    });
-
-    // ---------------------
-    // Read section headers.
-
-    let section_header_size = elf_header.section_header_entry_size as usize
-        * elf_header.section_header_entry_count as usize;
-
-    bytes_needed = elf_header.section_header_offset as usize + section_header_size;
-    reader.ensure_length(bytes_needed)?;
-
-    let section_headers = read_section_headers_64(&mut reader, &elf_header);
-    println!("\n>> {} <<", "Section headers.".red());
-
-    section_headers.iter().for_each(|section_header| {
-        println!(
-            "\n{} type: {}",
-            "Section header".yellow().bold(),
-            section_header.type_string
-        );
-        println!("Section header name: {}", section_header.name);
-        println!("Data: {:#04x?}", section_header.header_data);
-    });
-
-    // --------
-    // Success!
-
-    Ok(())
+        ExitCode::SUCCESS
+    }
 }
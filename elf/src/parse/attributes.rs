@@ -0,0 +1,111 @@
+/// Parsing for build attributes sections, such as `.riscv.attributes`.
+///
+/// The section is a format version ('A'), then subsections, each a length, a
+/// vendor name (as "riscv" or "gnu") and sub-subsections of attributes for
+/// the whole file, or some of its sections or symbols. Each attribute is a
+/// ULEB128 tag, then a value: a ULEB128 number for even tags, and a
+/// null-terminated string for odd ones.
+///
+use crate::dwarf::reader::Reader;
+use crate::ElfError;
+
+/// Where attributes apply: the whole file, or (followed by their indices)
+/// some sections or symbols.
+pub const TAG_FILE: u64 = 1;
+pub const TAG_SECTION: u64 = 2;
+pub const TAG_SYMBOL: u64 = 3;
+
+// Tags of the "riscv" vendor's attributes.
+pub const TAG_RISCV_STACK_ALIGN: u64 = 4;
+pub const TAG_RISCV_ARCH: u64 = 5;
+pub const TAG_RISCV_UNALIGNED_ACCESS: u64 = 6;
+pub const TAG_RISCV_PRIV_SPEC: u64 = 8;
+pub const TAG_RISCV_PRIV_SPEC_MINOR: u64 = 10;
+pub const TAG_RISCV_PRIV_SPEC_REVISION: u64 = 12;
+
+const FORMAT_VERSION: u8 = b'A';
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AttributeValue {
+    Number(u64),
+    String(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attribute {
+    pub tag: u64,
+    pub value: AttributeValue,
+}
+
+/// One vendor's attributes for the whole file. (Those for sections and
+/// symbols are skipped.)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AttributesSubsection {
+    pub vendor: String,
+    pub attributes: Vec<Attribute>,
+}
+
+impl AttributesSubsection {
+    /// The value of the attribute with `tag`, if there is one.
+    pub fn get(&self, tag: u64) -> Option<&AttributeValue> {
+        self.attributes
+            .iter()
+            .find(|attribute| attribute.tag == tag)
+            .map(|attribute| &attribute.value)
+    }
+}
+
+/// Reads a build attributes section.
+pub fn read_attributes(bytes: &[u8]) -> Result<Vec<AttributesSubsection>, ElfError> {
+    let mut reader = Reader::new(bytes, "build attributes");
+
+    let version = reader.u8()?;
+    if version != FORMAT_VERSION {
+        return Err(ElfError::UnsupportedVersion {
+            what: "Build attributes",
+            version: version as u16,
+        });
+    }
+
+    let mut subsections = vec![];
+    while !reader.is_empty() {
+        // The length counts itself.
+        let start = reader.offset();
+        let len = reader.u32()? as u64;
+        if len < 4 {
+            return Err(reader.invalid(start, format!("subsection length {len}")));
+        }
+        let mut subsection = reader.split(len - 4)?;
+        let vendor = String::from_utf8_lossy(subsection.cstr()?).into_owned();
+
+        let mut attributes = vec![];
+        while !subsection.is_empty() {
+            let start = subsection.offset();
+            let tag = subsection.uleb128()?;
+            let len = subsection.u32()? as u64;
+            // As does this one, and the tag before it.
+            let header = (subsection.offset() - start) as u64;
+            if len < header {
+                return Err(subsection.invalid(start, format!("sub-subsection length {len}")));
+            }
+            let mut contents = subsection.split(len - header)?;
+            if tag != TAG_FILE {
+                continue;
+            }
+            while !contents.is_empty() {
+                let tag = contents.uleb128()?;
+                let value = match tag % 2 {
+                    0 => AttributeValue::Number(contents.uleb128()?),
+                    _ => AttributeValue::String(
+                        String::from_utf8_lossy(contents.cstr()?).into_owned(),
+                    ),
+                };
+                attributes.push(Attribute { tag, value });
+            }
+        }
+
+        subsections.push(AttributesSubsection { vendor, attributes });
+    }
+
+    Ok(subsections)
+}
@@ -0,0 +1,171 @@
+/// Compressed sections. There are two kinds: SHF_COMPRESSED sections, which
+/// start with an `Elf64_Chdr` header naming the format (zlib or zstd), and
+/// the older GNU `.zdebug_*` sections, which start with "ZLIB" and the size
+/// in big endian, and are always zlib.
+///
+use std::io::Read;
+
+use flate2::read::ZlibDecoder;
+use ruzstd::decoding::StreamingDecoder;
+
+use crate::parse::types::SHT_NOBITS;
+use crate::parse::*;
+use crate::ElfError;
+
+use from_bytes_macro::FromBytes;
+
+pub const ELF64_COMPRESSION_HEADER_LEN: usize = 24;
+
+#[derive(Debug, FromBytes)]
+pub struct Elf64CompressionHeader {
+    pub compression_type: u32,
+    pub reserved: u32,
+    pub size: u64,
+    pub addr_align: u64,
+}
+
+// Compression types (`ch_type`).
+pub const ELFCOMPRESS_ZLIB: u32 = 1;
+pub const ELFCOMPRESS_ZSTD: u32 = 2;
+
+const ZDEBUG_MAGIC: &[u8] = b"ZLIB";
+const ZDEBUG_HEADER_LEN: usize = 12;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompressionFormat {
+    Zlib,
+    Zstd,
+
+    /// A `.zdebug_*` section, which is also zlib.
+    GnuZlib,
+}
+
+/// How a section is compressed, and its sizes before and after.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Compression {
+    pub format: CompressionFormat,
+
+    /// Size of the section in the file, including the compression header.
+    pub compressed_size: u64,
+    pub uncompressed_size: u64,
+
+    /// Alignment of the uncompressed data. A `.zdebug_*` section
+    /// doesn't record it, so it's that of the section itself.
+    pub addr_align: u64,
+
+    /// Where the compressed stream starts, in the section.
+    pub data_offset: usize,
+}
+
+/// Works out how a section is compressed, if it is, from its header, its
+/// name, and the start of its contents (`bytes`).
+pub fn section_compression(
+    section: &Elf64SectionHeaderInfo,
+    bytes: &[u8],
+) -> Result<Option<Compression>, ElfError> {
+    let header = &section.header_data;
+
+    if header.type_id() == SHT_NOBITS {
+        return Ok(None);
+    }
+
+    if header.flags & SHF_COMPRESSED != 0 {
+        let header_bytes =
+            bytes
+                .get(..ELF64_COMPRESSION_HEADER_LEN)
+                .ok_or(ElfError::Truncated {
+                    what: "Compression header",
+                    offset: header.offset,
+                    needed: ELF64_COMPRESSION_HEADER_LEN as u64,
+                })?;
+        let chdr = Elf64CompressionHeader::parse_from_bytes(header_bytes);
+
+        let format = match chdr.compression_type {
+            ELFCOMPRESS_ZLIB => CompressionFormat::Zlib,
+            ELFCOMPRESS_ZSTD => CompressionFormat::Zstd,
+            other => {
+                return Err(ElfError::UnsupportedCompression {
+                    compression_type: other,
+                })
+            }
+        };
+
+        return Ok(Some(Compression {
+            format,
+            compressed_size: header.size,
+            uncompressed_size: chdr.size,
+            addr_align: chdr.addr_align,
+            data_offset: ELF64_COMPRESSION_HEADER_LEN,
+        }));
+    }
+
+    // A .zdebug section that doesn't start with the magic isn't compressed.
+    if section.name.starts_with(".zdebug") && bytes.starts_with(ZDEBUG_MAGIC) {
+        let size = bytes.get(4..ZDEBUG_HEADER_LEN).ok_or(ElfError::Truncated {
+            what: "Compression header",
+            offset: header.offset,
+            needed: ZDEBUG_HEADER_LEN as u64,
+        })?;
+
+        return Ok(Some(Compression {
+            format: CompressionFormat::GnuZlib,
+            compressed_size: header.size,
+            uncompressed_size: u64::from_be_bytes(size.try_into().unwrap()),
+            addr_align: header.addr_align,
+            data_offset: ZDEBUG_HEADER_LEN,
+        }));
+    }
+
+    Ok(None)
+}
+
+/// Decompresses the contents of a section (`bytes`, including the
+/// compression header) that is compressed as `compression` says.
+pub fn decompress(compression: &Compression, bytes: &[u8]) -> Result<Vec<u8>, ElfError> {
+    let stream = &bytes[compression.data_offset.min(bytes.len())..];
+    let size = compression.uncompressed_size;
+
+    // The size comes from the file, so don't trust it for more than a
+    // hint, and never read past it (in case the stream is a "bomb").
+    let mut data = Vec::with_capacity(size.min(stream.len() as u64 * 16) as usize);
+
+    let result = match compression.format {
+        CompressionFormat::Zlib | CompressionFormat::GnuZlib => ZlibDecoder::new(stream)
+            .take(size)
+            .read_to_end(&mut data)
+            .map(|_| ())
+            .map_err(|e| e.to_string()),
+
+        CompressionFormat::Zstd => decompress_zstd(stream, size, &mut data),
+    };
+
+    if let Err(message) = result {
+        return Err(ElfError::Decompression { message });
+    }
+
+    if data.len() as u64 != size {
+        return Err(ElfError::Decompression {
+            message: format!(
+                "Expected {size} bytes of uncompressed data, but got {}",
+                data.len()
+            ),
+        });
+    }
+
+    Ok(data)
+}
+
+/// Decompresses up to `size` bytes of zstd frames into `data`.
+fn decompress_zstd(mut stream: &[u8], size: u64, data: &mut Vec<u8>) -> Result<(), String> {
+    // Each frame is decompressed on its own, and a stream may have several.
+    while !stream.is_empty() && (data.len() as u64) < size {
+        let decoder = StreamingDecoder::new(&mut stream).map_err(|e| e.to_string())?;
+
+        decoder
+            .take(size - data.len() as u64)
+            .read_to_end(data)
+            .map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,328 @@
+/// Parsing for the dynamic section (SHT_DYNAMIC / PT_DYNAMIC).
+///
+use crate::parse::header::{Machine, EM_AARCH64, EM_RISCV};
+use crate::parse::strings::StringTable;
+
+use from_bytes_macro::FromBytes;
+
+pub const ELF64_DYNAMIC_LEN: usize = 16;
+
+#[derive(Debug, FromBytes)]
+pub struct Elf64DynamicEntry {
+    pub tag: i64,
+    pub value: u64,
+}
+
+// ---------------
+// Dynamic tags.
+
+pub const DT_NULL: i64 = 0;
+pub const DT_NEEDED: i64 = 1;
+pub const DT_PLTRELSZ: i64 = 2;
+pub const DT_PLTGOT: i64 = 3;
+pub const DT_HASH: i64 = 4;
+pub const DT_STRTAB: i64 = 5;
+pub const DT_SYMTAB: i64 = 6;
+pub const DT_RELA: i64 = 7;
+pub const DT_RELASZ: i64 = 8;
+pub const DT_RELAENT: i64 = 9;
+pub const DT_STRSZ: i64 = 10;
+pub const DT_SYMENT: i64 = 11;
+pub const DT_INIT: i64 = 12;
+pub const DT_FINI: i64 = 13;
+pub const DT_SONAME: i64 = 14;
+pub const DT_RPATH: i64 = 15;
+pub const DT_SYMBOLIC: i64 = 16;
+pub const DT_REL: i64 = 17;
+pub const DT_RELSZ: i64 = 18;
+pub const DT_RELENT: i64 = 19;
+pub const DT_PLTREL: i64 = 20;
+pub const DT_DEBUG: i64 = 21;
+pub const DT_TEXTREL: i64 = 22;
+pub const DT_JMPREL: i64 = 23;
+pub const DT_BIND_NOW: i64 = 24;
+pub const DT_INIT_ARRAY: i64 = 25;
+pub const DT_FINI_ARRAY: i64 = 26;
+pub const DT_INIT_ARRAYSZ: i64 = 27;
+pub const DT_FINI_ARRAYSZ: i64 = 28;
+pub const DT_RUNPATH: i64 = 29;
+pub const DT_FLAGS: i64 = 30;
+pub const DT_PREINIT_ARRAY: i64 = 32;
+pub const DT_PREINIT_ARRAYSZ: i64 = 33;
+pub const DT_SYMTAB_SHNDX: i64 = 34;
+pub const DT_RELRSZ: i64 = 35;
+pub const DT_RELR: i64 = 36;
+pub const DT_RELRENT: i64 = 37;
+
+pub const DT_GNU_PRELINKED: i64 = 0x6ffffdf5;
+pub const DT_GNU_CONFLICTSZ: i64 = 0x6ffffdf6;
+pub const DT_GNU_LIBLISTSZ: i64 = 0x6ffffdf7;
+pub const DT_CHECKSUM: i64 = 0x6ffffdf8;
+pub const DT_PLTPADSZ: i64 = 0x6ffffdf9;
+pub const DT_MOVEENT: i64 = 0x6ffffdfa;
+pub const DT_MOVESZ: i64 = 0x6ffffdfb;
+pub const DT_FEATURE: i64 = 0x6ffffdfc;
+pub const DT_POSFLAG_1: i64 = 0x6ffffdfd;
+pub const DT_SYMINSZ: i64 = 0x6ffffdfe;
+pub const DT_SYMINENT: i64 = 0x6ffffdff;
+pub const DT_GNU_HASH: i64 = 0x6ffffef5;
+pub const DT_TLSDESC_PLT: i64 = 0x6ffffef6;
+pub const DT_TLSDESC_GOT: i64 = 0x6ffffef7;
+pub const DT_GNU_CONFLICT: i64 = 0x6ffffef8;
+pub const DT_GNU_LIBLIST: i64 = 0x6ffffef9;
+pub const DT_CONFIG: i64 = 0x6ffffefa;
+pub const DT_DEPAUDIT: i64 = 0x6ffffefb;
+pub const DT_AUDIT: i64 = 0x6ffffefc;
+pub const DT_PLTPAD: i64 = 0x6ffffefd;
+pub const DT_MOVETAB: i64 = 0x6ffffefe;
+pub const DT_SYMINFO: i64 = 0x6ffffeff;
+pub const DT_VERSYM: i64 = 0x6ffffff0;
+pub const DT_RELACOUNT: i64 = 0x6ffffff9;
+pub const DT_RELCOUNT: i64 = 0x6ffffffa;
+pub const DT_FLAGS_1: i64 = 0x6ffffffb;
+pub const DT_VERDEF: i64 = 0x6ffffffc;
+pub const DT_VERDEFNUM: i64 = 0x6ffffffd;
+pub const DT_VERNEED: i64 = 0x6ffffffe;
+pub const DT_VERNEEDNUM: i64 = 0x6fffffff;
+pub const DT_AUXILIARY: i64 = 0x7ffffffd;
+pub const DT_FILTER: i64 = 0x7fffffff;
+
+pub const DT_AARCH64_BTI_PLT: i64 = 0x70000001;
+pub const DT_AARCH64_PAC_PLT: i64 = 0x70000003;
+pub const DT_AARCH64_VARIANT_PCS: i64 = 0x70000005;
+
+pub const DT_RISCV_VARIANT_CC: i64 = 0x70000001;
+
+// Bits of DT_FLAGS.
+pub const DF_ORIGIN: u64 = 0x1;
+pub const DF_SYMBOLIC: u64 = 0x2;
+pub const DF_TEXTREL: u64 = 0x4;
+pub const DF_BIND_NOW: u64 = 0x8;
+pub const DF_STATIC_TLS: u64 = 0x10;
+
+// Bits of DT_FLAGS_1.
+pub const DF_1_NOW: u64 = 0x1;
+pub const DF_1_PIE: u64 = 0x08000000;
+
+/// Names of the DT_FLAGS_1 bits, from the lowest.
+pub const DF_1_NAMES: [&str; 31] = [
+    "NOW",
+    "GLOBAL",
+    "GROUP",
+    "NODELETE",
+    "LOADFLTR",
+    "INITFIRST",
+    "NOOPEN",
+    "ORIGIN",
+    "DIRECT",
+    "TRANS",
+    "INTERPOSE",
+    "NODEFLIB",
+    "NODUMP",
+    "CONFALT",
+    "ENDFILTEE",
+    "DISPRELDNE",
+    "DISPRELPND",
+    "NODIRECT",
+    "IGNMULDEF",
+    "NOKSYMS",
+    "NOHDR",
+    "EDITED",
+    "NORELOC",
+    "SYMINTPOSE",
+    "GLOBAUDIT",
+    "SINGLETON",
+    "STUB",
+    "PIE",
+    "KMOD",
+    "WEAKFILTER",
+    "NOCOMMON",
+];
+
+// ---------------------
+// Reading the entries.
+
+/// The entries of a dynamic section, with the contents
+/// of the string table that their names refer to.
+pub struct DynamicSection {
+    pub entries: Vec<Elf64DynamicEntry>,
+    pub string_table: Vec<u8>,
+}
+
+impl DynamicSection {
+    pub fn strings(&self) -> StringTable<'_> {
+        StringTable::new(&self.string_table)
+    }
+}
+
+/// Reads the entries of a dynamic section, up to and including the DT_NULL
+/// entry that terminates it.
+pub fn read_dynamic_64(bytes: &[u8]) -> Vec<Elf64DynamicEntry> {
+    let mut entries = vec![];
+
+    for chunk in bytes.chunks_exact(ELF64_DYNAMIC_LEN) {
+        let entry = Elf64DynamicEntry::parse_from_bytes(chunk);
+        let is_null = entry.tag == DT_NULL;
+
+        entries.push(entry);
+
+        if is_null {
+            break;
+        }
+    }
+
+    entries
+}
+
+pub fn dynamic_tag_string(tag: i64, machine: Machine) -> String {
+    let str_val = match tag {
+        DT_NULL => "DT_NULL",
+        DT_NEEDED => "DT_NEEDED",
+        DT_PLTRELSZ => "DT_PLTRELSZ",
+        DT_PLTGOT => "DT_PLTGOT",
+        DT_HASH => "DT_HASH",
+        DT_STRTAB => "DT_STRTAB",
+        DT_SYMTAB => "DT_SYMTAB",
+        DT_RELA => "DT_RELA",
+        DT_RELASZ => "DT_RELASZ",
+        DT_RELAENT => "DT_RELAENT",
+        DT_STRSZ => "DT_STRSZ",
+        DT_SYMENT => "DT_SYMENT",
+        DT_INIT => "DT_INIT",
+        DT_FINI => "DT_FINI",
+        DT_SONAME => "DT_SONAME",
+        DT_RPATH => "DT_RPATH",
+        DT_SYMBOLIC => "DT_SYMBOLIC",
+        DT_REL => "DT_REL",
+        DT_RELSZ => "DT_RELSZ",
+        DT_RELENT => "DT_RELENT",
+        DT_PLTREL => "DT_PLTREL",
+        DT_DEBUG => "DT_DEBUG",
+        DT_TEXTREL => "DT_TEXTREL",
+        DT_JMPREL => "DT_JMPREL",
+        DT_BIND_NOW => "DT_BIND_NOW",
+        DT_INIT_ARRAY => "DT_INIT_ARRAY",
+        DT_FINI_ARRAY => "DT_FINI_ARRAY",
+        DT_INIT_ARRAYSZ => "DT_INIT_ARRAYSZ",
+        DT_FINI_ARRAYSZ => "DT_FINI_ARRAYSZ",
+        DT_RUNPATH => "DT_RUNPATH",
+        DT_FLAGS => "DT_FLAGS",
+        DT_PREINIT_ARRAY => "DT_PREINIT_ARRAY",
+        DT_PREINIT_ARRAYSZ => "DT_PREINIT_ARRAYSZ",
+        DT_SYMTAB_SHNDX => "DT_SYMTAB_SHNDX",
+        DT_RELRSZ => "DT_RELRSZ",
+        DT_RELR => "DT_RELR",
+        DT_RELRENT => "DT_RELRENT",
+
+        DT_GNU_PRELINKED => "DT_GNU_PRELINKED",
+        DT_GNU_CONFLICTSZ => "DT_GNU_CONFLICTSZ",
+        DT_GNU_LIBLISTSZ => "DT_GNU_LIBLISTSZ",
+        DT_CHECKSUM => "DT_CHECKSUM",
+        DT_PLTPADSZ => "DT_PLTPADSZ",
+        DT_MOVEENT => "DT_MOVEENT",
+        DT_MOVESZ => "DT_MOVESZ",
+        DT_FEATURE => "DT_FEATURE",
+        DT_POSFLAG_1 => "DT_POSFLAG_1",
+        DT_SYMINSZ => "DT_SYMINSZ",
+        DT_SYMINENT => "DT_SYMINENT",
+        DT_GNU_HASH => "DT_GNU_HASH",
+        DT_TLSDESC_PLT => "DT_TLSDESC_PLT",
+        DT_TLSDESC_GOT => "DT_TLSDESC_GOT",
+        DT_GNU_CONFLICT => "DT_GNU_CONFLICT",
+        DT_GNU_LIBLIST => "DT_GNU_LIBLIST",
+        DT_CONFIG => "DT_CONFIG",
+        DT_DEPAUDIT => "DT_DEPAUDIT",
+        DT_AUDIT => "DT_AUDIT",
+        DT_PLTPAD => "DT_PLTPAD",
+        DT_MOVETAB => "DT_MOVETAB",
+        DT_SYMINFO => "DT_SYMINFO",
+        DT_VERSYM => "DT_VERSYM",
+        DT_RELACOUNT => "DT_RELACOUNT",
+        DT_RELCOUNT => "DT_RELCOUNT",
+        DT_FLAGS_1 => "DT_FLAGS_1",
+        DT_VERDEF => "DT_VERDEF",
+        DT_VERDEFNUM => "DT_VERDEFNUM",
+        DT_VERNEED => "DT_VERNEED",
+        DT_VERNEEDNUM => "DT_VERNEEDNUM",
+        DT_AUXILIARY => "DT_AUXILIARY",
+        DT_FILTER => "DT_FILTER",
+
+        _ => match (machine.id(), tag) {
+            (EM_AARCH64, DT_AARCH64_BTI_PLT) => "DT_AARCH64_BTI_PLT",
+            (EM_AARCH64, DT_AARCH64_PAC_PLT) => "DT_AARCH64_PAC_PLT",
+            (EM_AARCH64, DT_AARCH64_VARIANT_PCS) => "DT_AARCH64_VARIANT_PCS",
+            (EM_RISCV, DT_RISCV_VARIANT_CC) => "DT_RISCV_VARIANT_CC",
+
+            _ => &format!("UNRECOGNIZED TAG: {tag:#x}"),
+        },
+    };
+
+    str_val.to_owned()
+}
+
+/// Describes the value of a dynamic entry in the way readelf does, using
+/// `string_table` (.dynstr) to look up names.
+pub fn dynamic_value_string(entry: &Elf64DynamicEntry, string_table: &StringTable) -> String {
+    let name = || {
+        string_table
+            .get_lossy(entry.value as usize)
+            .unwrap_or_default()
+    };
+
+    match entry.tag {
+        DT_NEEDED => format!("Shared library: [{}]", name()),
+        DT_SONAME => format!("Library soname: [{}]", name()),
+        DT_RPATH => format!("Library rpath: [{}]", name()),
+        DT_RUNPATH => format!("Library runpath: [{}]", name()),
+        DT_AUXILIARY => format!("Auxiliary library: [{}]", name()),
+        DT_FILTER => format!("Filter library: [{}]", name()),
+
+        DT_FLAGS => {
+            let names = [
+                (DF_ORIGIN, "ORIGIN"),
+                (DF_SYMBOLIC, "SYMBOLIC"),
+                (DF_TEXTREL, "TEXTREL"),
+                (DF_BIND_NOW, "BIND_NOW"),
+                (DF_STATIC_TLS, "STATIC_TLS"),
+            ];
+            let flags: Vec<&str> = names
+                .iter()
+                .filter(|(bit, _)| entry.value & bit != 0)
+                .map(|(_, name)| *name)
+                .collect();
+            flags.join(" ")
+        }
+
+        DT_FLAGS_1 => {
+            let flags: Vec<&str> = DF_1_NAMES
+                .iter()
+                .enumerate()
+                .filter(|(bit, _)| entry.value & (1 << bit) != 0)
+                .map(|(_, name)| *name)
+                .collect();
+            format!("Flags: {}", flags.join(" "))
+        }
+
+        DT_PLTREL => match entry.value as i64 {
+            DT_REL => "REL".to_owned(),
+            DT_RELA => "RELA".to_owned(),
+            other => format!("{other:#x}"),
+        },
+
+        DT_PLTRELSZ | DT_RELASZ | DT_STRSZ | DT_RELSZ | DT_RELAENT | DT_SYMENT | DT_RELENT
+        | DT_INIT_ARRAYSZ | DT_FINI_ARRAYSZ | DT_PREINIT_ARRAYSZ | DT_RELRSZ | DT_RELRENT
+        | DT_MOVEENT | DT_MOVESZ | DT_SYMINSZ | DT_SYMINENT | DT_GNU_CONFLICTSZ
+        | DT_GNU_LIBLISTSZ | DT_PLTPADSZ => format!("{} (bytes)", entry.value),
+
+        DT_VERDEFNUM | DT_VERNEEDNUM | DT_RELACOUNT | DT_RELCOUNT => entry.value.to_string(),
+
+        _ => format!("{:#x}", entry.value),
+    }
+}
+
+/// Whether the dynamic entries mark this as a position-independent executable.
+pub fn is_pie(entries: &[Elf64DynamicEntry]) -> bool {
+    entries
+        .iter()
+        .any(|entry| entry.tag == DT_FLAGS_1 && entry.value & DF_1_PIE != 0)
+}
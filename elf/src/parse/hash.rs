@@ -0,0 +1,326 @@
+/// Hash tables for finding dynamic symbols by name: the System V table
+/// (SHT_HASH), and the GNU table (SHT_GNU_HASH) that has replaced it, which
+/// adds a Bloom filter and keeps the symbols of each bucket together.
+///
+use std::convert::Infallible;
+
+use crate::parse::symbols::*;
+use crate::parse::utils::*;
+use crate::ElfError;
+
+const GNU_HASH_HEADER_LEN: usize = 16;
+
+/// The hash function of SHT_HASH tables, from the System V ABI.
+pub fn sysv_hash(name: &[u8]) -> u32 {
+    let mut hash: u32 = 0;
+
+    for &byte in name {
+        hash = (hash << 4).wrapping_add(byte as u32);
+
+        let high = hash & 0xf000_0000;
+        if high != 0 {
+            hash ^= high >> 24;
+        }
+        hash &= !high;
+    }
+
+    hash
+}
+
+/// The hash function of SHT_GNU_HASH tables (Bernstein's, with a multiplier of 33).
+pub fn gnu_hash(name: &[u8]) -> u32 {
+    name.iter().fold(5381u32, |hash, &byte| {
+        hash.wrapping_mul(33).wrapping_add(byte as u32)
+    })
+}
+
+/// Reads the `count` 32-bit words at `offset`, or an error naming `what`.
+fn read_words(
+    bytes: &[u8],
+    offset: usize,
+    count: usize,
+    what: &'static str,
+) -> Result<Vec<u32>, ElfError> {
+    let len = count.saturating_mul(4);
+    let words = offset
+        .checked_add(len)
+        .and_then(|end| bytes.get(offset..end))
+        .ok_or(ElfError::Truncated {
+            what,
+            offset: offset as u64,
+            needed: len as u64,
+        })?;
+
+    Ok(words
+        .chunks_exact(4)
+        .map(|word| from_le_bytes!(u32, word, 0))
+        .collect())
+}
+
+// -----------------
+// SysV hash tables.
+
+/// An SHT_HASH table. There's a chain entry for each symbol, linking it to the
+/// next symbol in its bucket, and 0 (the null symbol) ends a chain.
+#[derive(Debug)]
+pub struct SysvHashTable {
+    pub buckets: Vec<u32>,
+    pub chains: Vec<u32>,
+}
+
+pub fn read_sysv_hash_64(bytes: &[u8]) -> Result<SysvHashTable, ElfError> {
+    let counts = read_words(bytes, 0, 2, "Hash table header")?;
+    let (bucket_count, chain_count) = (counts[0] as usize, counts[1] as usize);
+
+    Ok(SysvHashTable {
+        buckets: read_words(bytes, 8, bucket_count, "Hash table buckets")?,
+        chains: read_words(
+            bytes,
+            8 + bucket_count * 4,
+            chain_count,
+            "Hash table chains",
+        )?,
+    })
+}
+
+impl SysvHashTable {
+    /// Finds the symbol named `name`. The table only gives candidates, so
+    /// `is_match` is asked whether the symbol at an index is the one wanted.
+    pub fn lookup<E>(
+        &self,
+        name: &[u8],
+        mut is_match: impl FnMut(usize) -> Result<bool, E>,
+    ) -> Result<Option<usize>, E> {
+        if self.buckets.is_empty() {
+            return Ok(None);
+        }
+
+        let bucket = sysv_hash(name) as usize % self.buckets.len();
+        let mut index = self.buckets[bucket] as usize;
+
+        // A damaged chain could loop, but can't be longer than the table.
+        for _ in 0..self.chains.len() {
+            if index == 0 || index >= self.chains.len() {
+                break;
+            }
+            if is_match(index)? {
+                return Ok(Some(index));
+            }
+            index = self.chains[index] as usize;
+        }
+
+        Ok(None)
+    }
+}
+
+/// Checks an SHT_HASH table against the symbol table it indexes,
+/// returning a description of each problem found.
+pub fn check_sysv_hash(table: &SysvHashTable, symbols: &[Elf64SymbolInfo]) -> Vec<String> {
+    let mut problems = vec![];
+
+    if table.chains.len() != symbols.len() {
+        problems.push(format!(
+            "The hash table has {} chain entries, but there are {} symbols.",
+            table.chains.len(),
+            symbols.len()
+        ));
+    }
+
+    let count = table.chains.len();
+    for (what, links) in [("bucket", &table.buckets), ("chain entry", &table.chains)] {
+        if let Some(i) = links.iter().position(|&link| link as usize >= count) {
+            problems.push(format!(
+                "Hash table {what} {i} links to symbol {}, past the end of the table.",
+                links[i]
+            ));
+        }
+    }
+
+    for (index, symbol) in symbols.iter().enumerate().skip(1) {
+        if symbol.name.is_empty() {
+            continue;
+        }
+
+        let found = table.lookup(symbol.name.as_bytes(), |i| {
+            Ok::<_, Infallible>(symbols.get(i).is_some_and(|s| s.name == symbol.name))
+        });
+
+        if found == Ok(None) {
+            problems.push(format!(
+                "Symbol {index} ({}) can't be found through the hash table.",
+                symbol.name
+            ));
+        }
+    }
+
+    problems
+}
+
+// ----------------
+// GNU hash tables.
+
+/// An SHT_GNU_HASH table. Only the symbols from `symbol_offset` on are in it,
+/// sorted by bucket. Each bucket holds the index of its first symbol, and the
+/// chain holds each symbol's hash, with the lowest bit set on the last symbol
+/// of a bucket. The Bloom filter rules out most names that aren't there.
+#[derive(Debug)]
+pub struct GnuHashTable {
+    pub symbol_offset: u32,
+    pub bloom_shift: u32,
+    pub bloom: Vec<u64>,
+    pub buckets: Vec<u32>,
+    pub chain: Vec<u32>,
+}
+
+pub fn read_gnu_hash_64(bytes: &[u8]) -> Result<GnuHashTable, ElfError> {
+    let header = read_words(bytes, 0, 4, "GNU hash table header")?;
+    let (bucket_count, symbol_offset) = (header[0] as usize, header[1]);
+    let (bloom_size, bloom_shift) = (header[2] as usize, header[3]);
+
+    let bloom = read_words(
+        bytes,
+        GNU_HASH_HEADER_LEN,
+        bloom_size.saturating_mul(2),
+        "GNU hash table Bloom filter",
+    )?;
+    let bloom = bloom
+        .chunks_exact(2)
+        .map(|words| words[0] as u64 | (words[1] as u64) << 32)
+        .collect();
+
+    let buckets_offset = GNU_HASH_HEADER_LEN + bloom_size * 8;
+    let buckets = read_words(
+        bytes,
+        buckets_offset,
+        bucket_count,
+        "GNU hash table buckets",
+    )?;
+
+    // The chain isn't counted; it runs to the end of the section.
+    let chain_offset = buckets_offset + bucket_count * 4;
+    let chain_count = bytes.len().saturating_sub(chain_offset) / 4;
+
+    Ok(GnuHashTable {
+        symbol_offset,
+        bloom_shift,
+        bloom,
+        buckets,
+        chain: read_words(bytes, chain_offset, chain_count, "GNU hash table chain")?,
+    })
+}
+
+impl GnuHashTable {
+    /// Finds the symbol named `name` the way the dynamic linker does. The table
+    /// only gives candidates (those with the same hash), so `is_match` is asked
+    /// whether the symbol at an index is the one wanted.
+    pub fn lookup<E>(
+        &self,
+        name: &[u8],
+        mut is_match: impl FnMut(usize) -> Result<bool, E>,
+    ) -> Result<Option<usize>, E> {
+        if self.buckets.is_empty() || self.bloom.is_empty() {
+            return Ok(None);
+        }
+
+        let hash = gnu_hash(name);
+
+        // The filter has two bits for each symbol. Like the dynamic linker,
+        // this assumes the number of words in it is a power of two.
+        let word = self.bloom[(hash / 64) as usize & (self.bloom.len() - 1)];
+        let second = hash.checked_shr(self.bloom_shift).unwrap_or(0);
+        let bits = 1u64 << (hash % 64) | 1u64 << (second % 64);
+        if word & bits != bits {
+            return Ok(None);
+        }
+
+        let symbol_offset = self.symbol_offset as usize;
+        let mut index = self.buckets[hash as usize % self.buckets.len()] as usize;
+
+        // An empty bucket holds 0, which is before the first symbol in the table.
+        if index < symbol_offset {
+            return Ok(None);
+        }
+
+        while let Some(&chain_hash) = self.chain.get(index - symbol_offset) {
+            if chain_hash | 1 == hash | 1 && is_match(index)? {
+                return Ok(Some(index));
+            }
+            if chain_hash & 1 != 0 {
+                break;
+            }
+            index += 1;
+        }
+
+        Ok(None)
+    }
+}
+
+/// Checks an SHT_GNU_HASH table against the symbol table it indexes,
+/// returning a description of each problem found.
+pub fn check_gnu_hash(table: &GnuHashTable, symbols: &[Elf64SymbolInfo]) -> Vec<String> {
+    let mut problems = vec![];
+    let symbol_offset = table.symbol_offset as usize;
+
+    if symbol_offset > symbols.len() {
+        problems.push(format!(
+            "The GNU hash table starts at symbol {symbol_offset}, but there are only {} symbols.",
+            symbols.len()
+        ));
+        return problems;
+    }
+
+    if !table.bloom.len().is_power_of_two() {
+        problems.push(format!(
+            "The GNU hash table's Bloom filter has {} words, which isn't a power of two.",
+            table.bloom.len()
+        ));
+    }
+
+    if table.chain.len() < symbols.len() - symbol_offset {
+        problems.push(format!(
+            "The GNU hash table's chain ends at symbol {}, before the last symbol.",
+            symbol_offset + table.chain.len()
+        ));
+    }
+
+    // Exported symbols before the start of the table can't be found.
+    for (index, symbol) in symbols.iter().enumerate().take(symbol_offset).skip(1) {
+        let data = &symbol.symbol_data;
+
+        if data.section_index != SHN_UNDEF && data.bind() != STB_LOCAL && !symbol.name.is_empty() {
+            problems.push(format!(
+                "Symbol {index} ({}) is defined, but comes before the symbols in the GNU hash table.",
+                symbol.name
+            ));
+        }
+    }
+
+    for (index, symbol) in symbols.iter().enumerate().skip(symbol_offset) {
+        let hash = gnu_hash(symbol.name.as_bytes());
+
+        match table.chain.get(index - symbol_offset) {
+            Some(&chain_hash) if chain_hash | 1 != hash | 1 => {
+                problems.push(format!(
+                    "Symbol {index} ({}) has the hash {chain_hash:#010x} in the GNU hash table, but its name hashes to {hash:#010x}.",
+                    symbol.name
+                ));
+                continue;
+            }
+            None => break,
+            _ => {}
+        }
+
+        let found = table.lookup(symbol.name.as_bytes(), |i| {
+            Ok::<_, Infallible>(symbols.get(i).is_some_and(|s| s.name == symbol.name))
+        });
+
+        if found == Ok(None) {
+            problems.push(format!(
+                "Symbol {index} ({}) can't be found through the GNU hash table.",
+                symbol.name
+            ));
+        }
+    }
+
+    problems
+}
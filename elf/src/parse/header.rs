@@ -0,0 +1,694 @@
+/// Decoding for the fields of the main ELF header, and a summary
+/// of it in the style of `readelf -h`.
+///
+use super::Elf64Header;
+
+use std::fmt;
+
+// -------------
+// Object types.
+
+pub const ET_NONE: u16 = 0;
+pub const ET_REL: u16 = 1;
+pub const ET_EXEC: u16 = 2;
+pub const ET_DYN: u16 = 3;
+pub const ET_CORE: u16 = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObjectType {
+    None,
+    Relocatable,
+    Executable,
+    Dynamic,
+    Core,
+    OsSpecific(u16),
+    ProcessorSpecific(u16),
+    Unknown(u16),
+}
+
+impl From<u16> for ObjectType {
+    fn from(value: u16) -> Self {
+        match value {
+            ET_NONE => ObjectType::None,
+            ET_REL => ObjectType::Relocatable,
+            ET_EXEC => ObjectType::Executable,
+            ET_DYN => ObjectType::Dynamic,
+            ET_CORE => ObjectType::Core,
+            0xfe00..=0xfeff => ObjectType::OsSpecific(value),
+            0xff00..=0xffff => ObjectType::ProcessorSpecific(value),
+            _ => ObjectType::Unknown(value),
+        }
+    }
+}
+
+impl fmt::Display for ObjectType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ObjectType::None => write!(f, "NONE (None)"),
+            ObjectType::Relocatable => write!(f, "REL (Relocatable file)"),
+            ObjectType::Executable => write!(f, "EXEC (Executable file)"),
+            ObjectType::Dynamic => write!(f, "DYN (Shared object file)"),
+            ObjectType::Core => write!(f, "CORE (Core file)"),
+            ObjectType::OsSpecific(value) => write!(f, "OS Specific: ({value:x})"),
+            ObjectType::ProcessorSpecific(value) => write!(f, "Processor Specific: ({value:x})"),
+            ObjectType::Unknown(value) => write!(f, "<unknown>: {value:x}"),
+        }
+    }
+}
+
+// --------
+// OS/ABIs.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OsAbi {
+    SystemV,
+    HpUx,
+    NetBsd,
+    Gnu,
+    Solaris,
+    Aix,
+    Irix,
+    FreeBsd,
+    Tru64,
+    Modesto,
+    OpenBsd,
+    OpenVms,
+    Nsk,
+    Aros,
+    FenixOs,
+    CloudAbi,
+    OpenVos,
+    Standalone,
+
+    /// Values from 64 up are processor-specific, and so
+    /// are kept as raw values along with the machine.
+    ProcessorSpecific(u8),
+    Unknown(u8),
+}
+
+impl From<u8> for OsAbi {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => OsAbi::SystemV,
+            1 => OsAbi::HpUx,
+            2 => OsAbi::NetBsd,
+            3 => OsAbi::Gnu,
+            6 => OsAbi::Solaris,
+            7 => OsAbi::Aix,
+            8 => OsAbi::Irix,
+            9 => OsAbi::FreeBsd,
+            10 => OsAbi::Tru64,
+            11 => OsAbi::Modesto,
+            12 => OsAbi::OpenBsd,
+            13 => OsAbi::OpenVms,
+            14 => OsAbi::Nsk,
+            15 => OsAbi::Aros,
+            16 => OsAbi::FenixOs,
+            17 => OsAbi::CloudAbi,
+            18 => OsAbi::OpenVos,
+            255 => OsAbi::Standalone,
+            64..=254 => OsAbi::ProcessorSpecific(value),
+            _ => OsAbi::Unknown(value),
+        }
+    }
+}
+
+impl OsAbi {
+    /// Description as given by readelf; processor-specific values are
+    /// only meaningful together with the machine type.
+    pub fn description(&self, machine: Machine) -> String {
+        let str_val = match self {
+            OsAbi::SystemV => "UNIX - System V",
+            OsAbi::HpUx => "UNIX - HP-UX",
+            OsAbi::NetBsd => "UNIX - NetBSD",
+            OsAbi::Gnu => "UNIX - GNU",
+            OsAbi::Solaris => "UNIX - Solaris",
+            OsAbi::Aix => "UNIX - AIX",
+            OsAbi::Irix => "UNIX - IRIX",
+            OsAbi::FreeBsd => "UNIX - FreeBSD",
+            OsAbi::Tru64 => "UNIX - TRU64",
+            OsAbi::Modesto => "Novell - Modesto",
+            OsAbi::OpenBsd => "UNIX - OpenBSD",
+            OsAbi::OpenVms => "VMS - OpenVMS",
+            OsAbi::Nsk => "HP - Non-Stop Kernel",
+            OsAbi::Aros => "AROS",
+            OsAbi::FenixOs => "FenixOS",
+            OsAbi::CloudAbi => "Nuxi CloudABI",
+            OsAbi::OpenVos => "Stratus Technologies OpenVOS",
+            OsAbi::Standalone => "Standalone App",
+
+            OsAbi::ProcessorSpecific(value) => match (machine, value) {
+                (Machine::Arm, 64) => "ARM EABI",
+                (Machine::Arm, 65) => "ARM FDPIC",
+                (Machine::Arm, 97) => "ARM",
+                (Machine::TiC6000, 64) => "Bare-metal C6000",
+                (Machine::TiC6000, 65) => "Linux C6000",
+                (Machine::AmdGpu, 64) => "AMD HSA",
+                (Machine::AmdGpu, 65) => "AMD PAL",
+                (Machine::AmdGpu, 66) => "AMD Mesa3D",
+                _ => &format!("<unknown: {value:x}>"),
+            },
+
+            OsAbi::Unknown(value) => &format!("<unknown: {value:x}>"),
+        };
+
+        str_val.to_owned()
+    }
+}
+
+// ---------
+// Machines.
+
+macro_rules! machines {
+    ( $( $constant:ident = $value:literal => $variant:ident, $description:literal; )* ) => {
+        $( pub const $constant: u16 = $value; )*
+
+        /// The `EM_*` machine types from `elf.h`.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub enum Machine {
+            $( $variant, )*
+            Unknown(u16),
+        }
+
+        impl From<u16> for Machine {
+            fn from(value: u16) -> Self {
+                match value {
+                    $( $value => Machine::$variant, )*
+                    _ => Machine::Unknown(value),
+                }
+            }
+        }
+
+        impl Machine {
+            pub fn id(&self) -> u16 {
+                match self {
+                    $( Machine::$variant => $value, )*
+                    Machine::Unknown(value) => *value,
+                }
+            }
+
+            pub fn description(&self) -> String {
+                match self {
+                    $( Machine::$variant => $description.to_owned(), )*
+                    Machine::Unknown(value) => format!("<unknown>: {value:#x}"),
+                }
+            }
+        }
+    };
+}
+
+machines! {
+    EM_NONE = 0 => None, "None";
+    EM_M32 = 1 => M32, "WE32100";
+    EM_SPARC = 2 => Sparc, "Sparc";
+    EM_386 = 3 => I386, "Intel 80386";
+    EM_68K = 4 => M68k, "MC68000";
+    EM_88K = 5 => M88k, "MC88000";
+    EM_IAMCU = 6 => Iamcu, "Intel MCU";
+    EM_860 = 7 => I860, "Intel 80860";
+    EM_MIPS = 8 => Mips, "MIPS R3000";
+    EM_S370 = 9 => S370, "IBM System/370";
+    EM_MIPS_RS3_LE = 10 => MipsRs3Le, "MIPS R4000 big-endian";
+    EM_PARISC = 15 => Parisc, "HPPA";
+    EM_VPP500 = 17 => Vpp500, "Fujitsu VPP500";
+    EM_SPARC32PLUS = 18 => Sparc32Plus, "Sparc v8+";
+    EM_960 = 19 => I960, "Intel 80960";
+    EM_PPC = 20 => Ppc, "PowerPC";
+    EM_PPC64 = 21 => Ppc64, "PowerPC64";
+    EM_S390 = 22 => S390, "IBM S/390";
+    EM_SPU = 23 => Spu, "SPU";
+    EM_V800 = 36 => V800, "NEC V800 series";
+    EM_FR20 = 37 => Fr20, "Fujitsu FR20";
+    EM_RH32 = 38 => Rh32, "TRW RH-32";
+    EM_RCE = 39 => Rce, "Motorola RCE";
+    EM_ARM = 40 => Arm, "ARM";
+    EM_FAKE_ALPHA = 41 => FakeAlpha, "Digital Alpha (old)";
+    EM_SH = 42 => Sh, "Renesas / SuperH SH";
+    EM_SPARCV9 = 43 => SparcV9, "Sparc v9";
+    EM_TRICORE = 44 => Tricore, "Siemens Tricore";
+    EM_ARC = 45 => Arc, "Argonaut RISC Core";
+    EM_H8_300 = 46 => H8_300, "Renesas H8/300";
+    EM_H8_300H = 47 => H8_300h, "Renesas H8/300H";
+    EM_H8S = 48 => H8s, "Renesas H8S";
+    EM_H8_500 = 49 => H8_500, "Renesas H8/500";
+    EM_IA_64 = 50 => Ia64, "Intel IA-64";
+    EM_MIPS_X = 51 => MipsX, "Stanford MIPS-X";
+    EM_COLDFIRE = 52 => Coldfire, "Motorola Coldfire";
+    EM_68HC12 = 53 => M68hc12, "Motorola MC68HC12 Microcontroller";
+    EM_MMA = 54 => Mma, "Fujitsu MMA Multimedia Accelerator";
+    EM_PCP = 55 => Pcp, "Siemens PCP";
+    EM_NCPU = 56 => Ncpu, "Sony nCPU embeeded RISC";
+    EM_NDR1 = 57 => Ndr1, "Denso NDR1 microprocessor";
+    EM_STARCORE = 58 => Starcore, "Motorola Start*Core processor";
+    EM_ME16 = 59 => Me16, "Toyota ME16 processor";
+    EM_ST100 = 60 => St100, "STMicroelectronic ST100 processor";
+    EM_TINYJ = 61 => Tinyj, "Advanced Logic Corp. Tinyj emb.fam";
+    EM_X86_64 = 62 => X86_64, "Advanced Micro Devices X86-64";
+    EM_PDSP = 63 => Pdsp, "Sony DSP Processor";
+    EM_PDP10 = 64 => Pdp10, "Digital PDP-10";
+    EM_PDP11 = 65 => Pdp11, "Digital PDP-11";
+    EM_FX66 = 66 => Fx66, "Siemens FX66 microcontroller";
+    EM_ST9PLUS = 67 => St9plus, "STMicroelectronics ST9+ 8/16 mc";
+    EM_ST7 = 68 => St7, "STmicroelectronics ST7 8 bit mc";
+    EM_68HC16 = 69 => M68hc16, "Motorola MC68HC16 Microcontroller";
+    EM_68HC11 = 70 => M68hc11, "Motorola MC68HC11 Microcontroller";
+    EM_68HC08 = 71 => M68hc08, "Motorola MC68HC08 Microcontroller";
+    EM_68HC05 = 72 => M68hc05, "Motorola MC68HC05 Microcontroller";
+    EM_SVX = 73 => Svx, "Silicon Graphics SVx";
+    EM_ST19 = 74 => St19, "STMicroelectronics ST19 8 bit mc";
+    EM_VAX = 75 => Vax, "Digital VAX";
+    EM_CRIS = 76 => Cris, "Axis Communications 32-bit emb.proc";
+    EM_JAVELIN = 77 => Javelin, "Infineon Technologies 32-bit emb.proc";
+    EM_FIREPATH = 78 => Firepath, "Element 14 64-bit DSP Processor";
+    EM_ZSP = 79 => Zsp, "LSI Logic 16-bit DSP Processor";
+    EM_MMIX = 80 => Mmix, "Donald Knuth's educational 64-bit proc";
+    EM_HUANY = 81 => Huany, "Harvard University machine-independent object files";
+    EM_PRISM = 82 => Prism, "SiTera Prism";
+    EM_AVR = 83 => Avr, "Atmel AVR 8-bit microcontroller";
+    EM_FR30 = 84 => Fr30, "Fujitsu FR30";
+    EM_D10V = 85 => D10v, "Mitsubishi D10V";
+    EM_D30V = 86 => D30v, "Mitsubishi D30V";
+    EM_V850 = 87 => V850, "NEC v850";
+    EM_M32R = 88 => M32r, "Mitsubishi M32R";
+    EM_MN10300 = 89 => Mn10300, "Matsushita MN10300";
+    EM_MN10200 = 90 => Mn10200, "Matsushita MN10200";
+    EM_PJ = 91 => Pj, "picoJava";
+    EM_OPENRISC = 92 => OpenRisc, "OpenRISC 1000";
+    EM_ARC_COMPACT = 93 => ArcCompact, "ARCompact";
+    EM_XTENSA = 94 => Xtensa, "Tensilica Xtensa Processor";
+    EM_VIDEOCORE = 95 => Videocore, "Alphamosaic VideoCore";
+    EM_TMM_GPP = 96 => TmmGpp, "Thompson Multimedia General Purpose Proc";
+    EM_NS32K = 97 => Ns32k, "National Semi. 32000";
+    EM_TPC = 98 => Tpc, "Tenor Network TPC";
+    EM_SNP1K = 99 => Snp1k, "Trebia SNP 1000";
+    EM_ST200 = 100 => St200, "STMicroelectronics ST200";
+    EM_IP2K = 101 => Ip2k, "Ubicom IP2xxx";
+    EM_MAX = 102 => Max, "MAX processor";
+    EM_CR = 103 => Cr, "National Semi. CompactRISC";
+    EM_F2MC16 = 104 => F2mc16, "Fujitsu F2MC16";
+    EM_MSP430 = 105 => Msp430, "Texas Instruments msp430 microcontroller";
+    EM_BLACKFIN = 106 => Blackfin, "Analog Devices Blackfin DSP";
+    EM_SE_C33 = 107 => SeC33, "Seiko Epson S1C33 family";
+    EM_SEP = 108 => Sep, "Sharp embedded microprocessor";
+    EM_ARCA = 109 => Arca, "Arca RISC";
+    EM_UNICORE = 110 => Unicore, "PKU-Unity & MPRC Peking Uni. mc series";
+    EM_EXCESS = 111 => Excess, "eXcess configurable cpu";
+    EM_DXP = 112 => Dxp, "Icera Semi. Deep Execution Processor";
+    EM_ALTERA_NIOS2 = 113 => AlteraNios2, "Altera Nios II";
+    EM_CRX = 114 => Crx, "National Semi. CompactRISC CRX";
+    EM_XGATE = 115 => Xgate, "Motorola XGATE";
+    EM_C166 = 116 => C166, "Infineon C16x/XC16x";
+    EM_M16C = 117 => M16c, "Renesas M16C";
+    EM_DSPIC30F = 118 => Dspic30f, "Microchip Technology dsPIC30F";
+    EM_CE = 119 => Ce, "Freescale Communication Engine RISC";
+    EM_M32C = 120 => M32c, "Renesas M32C";
+    EM_TSK3000 = 131 => Tsk3000, "Altium TSK3000";
+    EM_RS08 = 132 => Rs08, "Freescale RS08";
+    EM_SHARC = 133 => Sharc, "Analog Devices SHARC family";
+    EM_ECOG2 = 134 => Ecog2, "Cyan Technology eCOG2";
+    EM_SCORE7 = 135 => Score7, "Sunplus S+core7 RISC";
+    EM_DSP24 = 136 => Dsp24, "New Japan Radio (NJR) 24-bit DSP";
+    EM_VIDEOCORE3 = 137 => Videocore3, "Broadcom VideoCore III";
+    EM_LATTICEMICO32 = 138 => Latticemico32, "RISC for Lattice FPGA";
+    EM_SE_C17 = 139 => SeC17, "Seiko Epson C17";
+    EM_TI_C6000 = 140 => TiC6000, "Texas Instruments TMS320C6000 DSP";
+    EM_TI_C2000 = 141 => TiC2000, "Texas Instruments TMS320C2000 DSP";
+    EM_TI_C5500 = 142 => TiC5500, "Texas Instruments TMS320C55x DSP";
+    EM_TI_ARP32 = 143 => TiArp32, "Texas Instruments App. Specific RISC";
+    EM_TI_PRU = 144 => TiPru, "Texas Instruments Prog. Realtime Unit";
+    EM_MMDSP_PLUS = 160 => MmdspPlus, "STMicroelectronics 64bit VLIW DSP";
+    EM_CYPRESS_M8C = 161 => CypressM8c, "Cypress M8C";
+    EM_R32C = 162 => R32c, "Renesas R32C";
+    EM_TRIMEDIA = 163 => Trimedia, "NXP Semi. TriMedia";
+    EM_QDSP6 = 164 => Qdsp6, "QUALCOMM DSP6";
+    EM_8051 = 165 => I8051, "Intel 8051 and variants";
+    EM_STXP7X = 166 => Stxp7x, "STMicroelectronics STxP7x";
+    EM_NDS32 = 167 => Nds32, "Andes Tech. compact code emb. RISC";
+    EM_ECOG1X = 168 => Ecog1x, "Cyan Technology eCOG1X";
+    EM_MAXQ30 = 169 => Maxq30, "Dallas Semi. MAXQ30 mc";
+    EM_XIMO16 = 170 => Ximo16, "New Japan Radio (NJR) 16-bit DSP";
+    EM_MANIK = 171 => Manik, "M2000 Reconfigurable RISC";
+    EM_CRAYNV2 = 172 => Craynv2, "Cray NV2 vector architecture";
+    EM_RX = 173 => Rx, "Renesas RX";
+    EM_METAG = 174 => Metag, "Imagination Tech. META";
+    EM_MCST_ELBRUS = 175 => McstElbrus, "MCST Elbrus general purpose hardware architecture";
+    EM_ECOG16 = 176 => Ecog16, "Cyan Technology eCOG16";
+    EM_CR16 = 177 => Cr16, "National Semi. CompactRISC CR16";
+    EM_ETPU = 178 => Etpu, "Freescale Extended Time Processing Unit";
+    EM_SLE9X = 179 => Sle9x, "Infineon Tech. SLE9X";
+    EM_L10M = 180 => L10m, "Intel L10M";
+    EM_K10M = 181 => K10m, "Intel K10M";
+    EM_AARCH64 = 183 => AArch64, "AArch64";
+    EM_AVR32 = 185 => Avr32, "Amtel 32-bit microprocessor";
+    EM_STM8 = 186 => Stm8, "STMicroelectronics STM8";
+    EM_TILE64 = 187 => Tile64, "Tilera TILE64";
+    EM_TILEPRO = 188 => TilePro, "Tilera TILEPro";
+    EM_MICROBLAZE = 189 => MicroBlaze, "Xilinx MicroBlaze";
+    EM_CUDA = 190 => Cuda, "NVIDIA CUDA architecture";
+    EM_TILEGX = 191 => TileGx, "Tilera TILE-Gx";
+    EM_CLOUDSHIELD = 192 => Cloudshield, "CloudShield";
+    EM_COREA_1ST = 193 => Corea1st, "KIPO-KAIST Core-A 1st gen";
+    EM_COREA_2ND = 194 => Corea2nd, "KIPO-KAIST Core-A 2nd gen";
+    EM_ARCV2 = 195 => ArcV2, "ARCv2";
+    EM_OPEN8 = 196 => Open8, "Open8 RISC";
+    EM_RL78 = 197 => Rl78, "Renesas RL78";
+    EM_VIDEOCORE5 = 198 => Videocore5, "Broadcom VideoCore V";
+    EM_78KOR = 199 => Rl78kor, "Renesas 78K0R";
+    EM_56800EX = 200 => Fs56800ex, "Freescale 56800EX Digital Signal Controller (DSC)";
+    EM_BA1 = 201 => Ba1, "Beyond BA1";
+    EM_BA2 = 202 => Ba2, "Beyond BA2";
+    EM_XCORE = 203 => Xcore, "XMOS xCORE";
+    EM_MCHP_PIC = 204 => MchpPic, "Microchip 8-bit PIC(r)";
+    EM_INTELGT = 205 => Intelgt, "Intel Graphics Technology";
+    EM_KM32 = 210 => Km32, "KM211 KM32";
+    EM_KMX32 = 211 => Kmx32, "KM211 KMX32";
+    EM_EMX16 = 212 => Emx16, "KM211 KMX16";
+    EM_EMX8 = 213 => Emx8, "KM211 KMX8";
+    EM_KVARC = 214 => Kvarc, "KM211 KVARC";
+    EM_CDP = 215 => Cdp, "Paneve CDP";
+    EM_COGE = 216 => Coge, "Cognitive Smart Memory Processor";
+    EM_COOL = 217 => Cool, "Bluechip CoolEngine";
+    EM_NORC = 218 => Norc, "Nanoradio Optimized RISC";
+    EM_CSR_KALIMBA = 219 => CsrKalimba, "CSR Kalimba";
+    EM_Z80 = 220 => Z80, "Zilog Z80";
+    EM_VISIUM = 221 => Visium, "Controls and Data Services VISIUMcore";
+    EM_FT32 = 222 => Ft32, "FTDI Chip FT32";
+    EM_MOXIE = 223 => Moxie, "Moxie processor";
+    EM_AMDGPU = 224 => AmdGpu, "AMD GPU";
+    EM_RISCV = 243 => RiscV, "RISC-V";
+    EM_BPF = 247 => Bpf, "Linux BPF";
+    EM_CSKY = 252 => Csky, "CSKY";
+    EM_LOONGARCH = 258 => LoongArch, "LoongArch";
+    EM_ALPHA = 0x9026 => Alpha, "Alpha";
+}
+
+// ------------------------
+// Processor-specific flags.
+
+pub const EF_ARM_EABIMASK: u32 = 0xff000000;
+pub const EF_ARM_BE8: u32 = 0x00800000;
+pub const EF_ARM_LE8: u32 = 0x00400000;
+pub const EF_ARM_ABI_FLOAT_SOFT: u32 = 0x00000200;
+pub const EF_ARM_ABI_FLOAT_HARD: u32 = 0x00000400;
+pub const EF_ARM_INTERWORK: u32 = 0x00000004;
+pub const EF_ARM_APCS_26: u32 = 0x00000008;
+pub const EF_ARM_APCS_FLOAT: u32 = 0x00000010;
+pub const EF_ARM_PIC: u32 = 0x00000020;
+
+pub const EF_MIPS_NOREORDER: u32 = 0x00000001;
+pub const EF_MIPS_PIC: u32 = 0x00000002;
+pub const EF_MIPS_CPIC: u32 = 0x00000004;
+pub const EF_MIPS_ABI2: u32 = 0x00000020;
+pub const EF_MIPS_32BITMODE: u32 = 0x00000100;
+pub const EF_MIPS_FP64: u32 = 0x00000200;
+pub const EF_MIPS_NAN2008: u32 = 0x00000400;
+pub const EF_MIPS_ABI: u32 = 0x0000f000;
+pub const EF_MIPS_MACH: u32 = 0x00ff0000;
+pub const EF_MIPS_ARCH: u32 = 0xf0000000;
+
+pub const EF_RISCV_RVC: u32 = 0x0001;
+pub const EF_RISCV_FLOAT_ABI: u32 = 0x0006;
+pub const EF_RISCV_FLOAT_ABI_SOFT: u32 = 0x0000;
+pub const EF_RISCV_FLOAT_ABI_SINGLE: u32 = 0x0002;
+pub const EF_RISCV_FLOAT_ABI_DOUBLE: u32 = 0x0004;
+pub const EF_RISCV_FLOAT_ABI_QUAD: u32 = 0x0006;
+pub const EF_RISCV_RVE: u32 = 0x0008;
+pub const EF_RISCV_TSO: u32 = 0x0010;
+
+/// Describes the bits of `e_flags` that are defined for `machine`,
+/// following the wording that readelf uses.
+pub fn flags_descriptions(machine: Machine, flags: u32) -> Vec<String> {
+    match machine {
+        Machine::Arm => arm_flags(flags),
+        Machine::Mips | Machine::MipsRs3Le => mips_flags(flags),
+        Machine::RiscV => riscv_flags(flags),
+        _ => vec![],
+    }
+}
+
+fn arm_flags(flags: u32) -> Vec<String> {
+    let mut descriptions = vec![];
+    let eabi_version = (flags & EF_ARM_EABIMASK) >> 24;
+
+    match eabi_version {
+        0 => {
+            descriptions.push("GNU EABI".to_owned());
+
+            if flags & EF_ARM_INTERWORK != 0 {
+                descriptions.push("interworking enabled".to_owned());
+            }
+            if flags & EF_ARM_APCS_26 != 0 {
+                descriptions.push("uses APCS/26".to_owned());
+            } else {
+                descriptions.push("uses APCS/32".to_owned());
+            }
+            if flags & EF_ARM_APCS_FLOAT != 0 {
+                descriptions.push("uses APCS/float".to_owned());
+            }
+            if flags & EF_ARM_PIC != 0 {
+                descriptions.push("position independent".to_owned());
+            }
+        }
+
+        1..=5 => {
+            descriptions.push(format!("Version{eabi_version} EABI"));
+
+            if eabi_version == 5 {
+                if flags & EF_ARM_ABI_FLOAT_SOFT != 0 {
+                    descriptions.push("soft-float ABI".to_owned());
+                }
+                if flags & EF_ARM_ABI_FLOAT_HARD != 0 {
+                    descriptions.push("hard-float ABI".to_owned());
+                }
+            }
+            if flags & EF_ARM_BE8 != 0 {
+                descriptions.push("BE8".to_owned());
+            }
+            if flags & EF_ARM_LE8 != 0 {
+                descriptions.push("LE8".to_owned());
+            }
+        }
+
+        _ => descriptions.push("<unrecognized EABI>".to_owned()),
+    }
+
+    descriptions
+}
+
+fn mips_flags(flags: u32) -> Vec<String> {
+    let mut descriptions = vec![];
+
+    let bits = [
+        (EF_MIPS_NOREORDER, "noreorder"),
+        (EF_MIPS_PIC, "pic"),
+        (EF_MIPS_CPIC, "cpic"),
+        (EF_MIPS_ABI2, "abi2"),
+        (EF_MIPS_32BITMODE, "32bitmode"),
+        (EF_MIPS_FP64, "fp64"),
+        (EF_MIPS_NAN2008, "nan2008"),
+    ];
+    for (bit, name) in bits {
+        if flags & bit != 0 {
+            descriptions.push(name.to_owned());
+        }
+    }
+
+    let mach = match flags & EF_MIPS_MACH {
+        0 => None,
+        0x00810000 => Some("3900"),
+        0x00820000 => Some("4010"),
+        0x00830000 => Some("4100"),
+        0x00850000 => Some("4650"),
+        0x00870000 => Some("4120"),
+        0x00880000 => Some("4111"),
+        0x008a0000 => Some("sb1"),
+        0x008b0000 => Some("octeon"),
+        0x008c0000 => Some("xlr"),
+        0x008d0000 => Some("octeon2"),
+        0x008e0000 => Some("octeon3"),
+        0x00910000 => Some("5400"),
+        0x00920000 => Some("5900"),
+        0x00980000 => Some("5500"),
+        0x00990000 => Some("9000"),
+        0x00a00000 => Some("loongson-2e"),
+        0x00a10000 => Some("loongson-2f"),
+        0x00a20000 => Some("gs464"),
+        0x00a30000 => Some("gs464e"),
+        0x00a40000 => Some("gs264e"),
+        _ => Some("unknown CPU"),
+    };
+    descriptions.extend(mach.map(str::to_owned));
+
+    let abi = match flags & EF_MIPS_ABI {
+        0 => None,
+        0x1000 => Some("o32"),
+        0x2000 => Some("o64"),
+        0x3000 => Some("eabi32"),
+        0x4000 => Some("eabi64"),
+        _ => Some("unknown ABI"),
+    };
+    descriptions.extend(abi.map(str::to_owned));
+
+    let arch = match (flags & EF_MIPS_ARCH) >> 28 {
+        0x0 => "mips1",
+        0x1 => "mips2",
+        0x2 => "mips3",
+        0x3 => "mips4",
+        0x4 => "mips5",
+        0x5 => "mips32",
+        0x6 => "mips64",
+        0x7 => "mips32r2",
+        0x8 => "mips64r2",
+        0x9 => "mips32r6",
+        0xa => "mips64r6",
+        _ => "unknown ISA",
+    };
+    descriptions.push(arch.to_owned());
+
+    descriptions
+}
+
+fn riscv_flags(flags: u32) -> Vec<String> {
+    let mut descriptions = vec![];
+
+    if flags & EF_RISCV_RVC != 0 {
+        descriptions.push("RVC".to_owned());
+    }
+
+    let float_abi = match flags & EF_RISCV_FLOAT_ABI {
+        EF_RISCV_FLOAT_ABI_SOFT => "soft-float ABI",
+        EF_RISCV_FLOAT_ABI_SINGLE => "single-float ABI",
+        EF_RISCV_FLOAT_ABI_DOUBLE => "double-float ABI",
+        _ => "quad-float ABI",
+    };
+    descriptions.push(float_abi.to_owned());
+
+    if flags & EF_RISCV_RVE != 0 {
+        descriptions.push("RVE".to_owned());
+    }
+    if flags & EF_RISCV_TSO != 0 {
+        descriptions.push("TSO".to_owned());
+    }
+
+    descriptions
+}
+
+// ------------------------
+// Decoded header accessors.
+
+impl Elf64Header {
+    pub fn object_type(&self) -> ObjectType {
+        ObjectType::from(u16::from_le_bytes(self.object_type))
+    }
+
+    pub fn machine_type(&self) -> Machine {
+        Machine::from(self.machine_id())
+    }
+
+    pub fn os_abi(&self) -> OsAbi {
+        OsAbi::from(self.abi_os)
+    }
+
+    pub fn flags_descriptions(&self) -> Vec<String> {
+        flags_descriptions(self.machine_type(), self.flags)
+    }
+}
+
+/// The object type as readelf describes it. Whether a dynamic object
+/// is a PIE comes from its dynamic section, so the caller says.
+pub fn object_type_string(header: &Elf64Header, is_pie: bool) -> String {
+    match header.object_type() {
+        ObjectType::Dynamic if is_pie => "DYN (Position-Independent Executable file)".to_owned(),
+        other => other.to_string(),
+    }
+}
+
+/// Summarizes the main header in the format of `readelf -h`.
+pub fn header_summary(header: &Elf64Header, is_pie: bool) -> String {
+    let mut ident = header.magic_bytes.to_vec();
+    ident.extend([
+        header.bitness,
+        header.endianness,
+        header.elf_version,
+        header.abi_os,
+        header.abi_version,
+    ]);
+    ident.extend(header.abi_padding);
+
+    let magic: String = ident.iter().map(|b| format!("{b:02x} ")).collect();
+
+    let class = match header.bitness {
+        1 => "ELF32".to_owned(),
+        2 => "ELF64".to_owned(),
+        other => format!("<unknown: {other:x}>"),
+    };
+
+    let data = match header.endianness {
+        1 => "2's complement, little endian".to_owned(),
+        2 => "2's complement, big endian".to_owned(),
+        other => format!("<unknown: {other:x}>"),
+    };
+
+    let version = match header.elf_version {
+        1 => "1 (current)".to_owned(),
+        other => format!("{other} <unknown>"),
+    };
+
+    let mut flags = format!("{:#x}", header.flags);
+    for description in header.flags_descriptions() {
+        flags.push_str(", ");
+        flags.push_str(&description);
+    }
+
+    let rows = [
+        ("Class", class),
+        ("Data", data),
+        ("Version", version),
+        ("OS/ABI", header.os_abi().description(header.machine_type())),
+        ("ABI Version", header.abi_version.to_string()),
+        ("Type", object_type_string(header, is_pie)),
+        ("Machine", header.machine_type().description()),
+        ("Version", format!("{:#x}", header.version)),
+        ("Entry point address", format!("{:#x}", header.entry_point)),
+        (
+            "Start of program headers",
+            format!("{} (bytes into file)", header.program_header_offset),
+        ),
+        (
+            "Start of section headers",
+            format!("{} (bytes into file)", header.section_header_offset),
+        ),
+        ("Flags", flags),
+        (
+            "Size of this header",
+            format!("{} (bytes)", header.header_size),
+        ),
+        (
+            "Size of program headers",
+            format!("{} (bytes)", header.program_header_entry_size),
+        ),
+        (
+            "Number of program headers",
+            header.program_header_entry_count.to_string(),
+        ),
+        (
+            "Size of section headers",
+            format!("{} (bytes)", header.section_header_entry_size),
+        ),
+        (
+            "Number of section headers",
+            header.section_header_entry_count.to_string(),
+        ),
+        (
+            "Section header string table index",
+            header.section_header_names_index.to_string(),
+        ),
+    ];
+
+    let mut summary = format!("ELF Header:\n  Magic:   {magic}\n");
+    for (label, value) in rows {
+        summary.push_str(&format!("  {:<35}{value}\n", format!("{label}:")));
+    }
+
+    summary
+}
@@ -0,0 +1,193 @@
+/// Consistency checks on the program and section headers, for lenient
+/// parsing: whether what they describe fits in the file and is laid out
+/// sensibly. None of these stop parsing; the problems are only recorded.
+///
+use crate::parse::types::*;
+use crate::parse::*;
+use crate::Recorder;
+
+/// Section types whose `link` field is the index of another section.
+const LINKED_SECTION_TYPES: [u32; 10] = [
+    SHT_SYMTAB,
+    SHT_DYNSYM,
+    SHT_DYNAMIC,
+    SHT_REL,
+    SHT_RELA,
+    SHT_HASH,
+    SHT_GNU_HASH,
+    SHT_GNU_VERSYM,
+    SHT_GNU_VERNEED,
+    SHT_GNU_VERDEF,
+];
+
+/// Records problems with the layout described by the headers of a
+/// file that is `file_len` bytes long.
+pub fn check_layout(
+    elf_header: &Elf64Header,
+    program_headers: &[Elf64ProgramHeaderInfo],
+    section_headers: &[Elf64SectionHeaderInfo],
+    file_len: u64,
+    recorder: &mut Recorder,
+) {
+    // ---------------
+    // Header tables.
+
+    // Both tables hold 8-byte fields, so the ABI has them 8-byte aligned.
+    if !program_headers.is_empty() && !elf_header.program_header_offset.is_multiple_of(8) {
+        recorder.warning(
+            elf_header.program_header_offset,
+            "Program header table is not 8-byte aligned.".to_string(),
+        );
+    }
+    if !section_headers.is_empty() && !elf_header.section_header_offset.is_multiple_of(8) {
+        recorder.warning(
+            elf_header.section_header_offset,
+            "Section header table is not 8-byte aligned.".to_string(),
+        );
+    }
+
+    // ---------
+    // Segments.
+
+    let segment_offset = |index: usize| {
+        elf_header.program_header_offset
+            + index as u64 * elf_header.program_header_entry_size as u64
+    };
+
+    for (index, segment) in program_headers.iter().enumerate() {
+        let data = &segment.header_data;
+        let offset = segment_offset(index);
+
+        if !fits(data.offset, data.file_size, file_len) {
+            recorder.warning(
+                offset,
+                format!(
+                    "Segment {index} ({}) has contents at {:#x}..{:#x}, past the end of the file.",
+                    segment.type_string,
+                    data.offset,
+                    data.offset.saturating_add(data.file_size)
+                ),
+            );
+        }
+
+        if data.type_id() == PT_LOAD && data.file_size > data.mem_size {
+            recorder.warning(
+                offset,
+                format!("Segment {index} (PT_LOAD) is larger in the file than in memory."),
+            );
+        }
+
+        if data.align > 1 && !data.align.is_power_of_two() {
+            recorder.warning(
+                offset,
+                format!(
+                    "Segment {index} has an alignment of {}, which is not a power of two.",
+                    data.align
+                ),
+            );
+        }
+    }
+
+    // Loadable segments must not overlap in memory.
+    let loads: Vec<usize> = (0..program_headers.len())
+        .filter(|&i| program_headers[i].header_data.type_id() == PT_LOAD)
+        .collect();
+
+    for (n, &first) in loads.iter().enumerate() {
+        for &second in &loads[n + 1..] {
+            let a = &program_headers[first].header_data;
+            let b = &program_headers[second].header_data;
+
+            if overlaps(a.virtual_address, a.mem_size, b.virtual_address, b.mem_size) {
+                recorder.warning(
+                    segment_offset(second),
+                    format!("Segments {first} and {second} (PT_LOAD) overlap in memory."),
+                );
+            }
+        }
+    }
+
+    // ---------
+    // Sections.
+
+    let section_offset = |index: usize| {
+        elf_header.section_header_offset
+            + index as u64 * elf_header.section_header_entry_size as u64
+    };
+
+    // Section 0 is the null section, which describes nothing.
+    for (index, section) in section_headers.iter().enumerate().skip(1) {
+        let data = &section.header_data;
+        let offset = section_offset(index);
+        let section_type = data.type_id();
+
+        if section_type != SHT_NOBITS
+            && section_type != SHT_NULL
+            && !fits(data.offset, data.size, file_len)
+        {
+            recorder.warning(
+                offset,
+                format!(
+                    "Section {index} ({}) has contents at {:#x}..{:#x}, past the end of the file.",
+                    section.name,
+                    data.offset,
+                    data.offset.saturating_add(data.size)
+                ),
+            );
+        }
+
+        if LINKED_SECTION_TYPES.contains(&section_type)
+            && data.link as usize >= section_headers.len()
+        {
+            recorder.warning(
+                offset,
+                format!(
+                    "Section {index} ({}) links to section {}, which doesn't exist.",
+                    section.name, data.link
+                ),
+            );
+        }
+
+        if data.entry_size != 0 && !data.size.is_multiple_of(data.entry_size) {
+            recorder.warning(
+                offset,
+                format!(
+                    "Section {index} ({}) has a size of {:#x}, which is not a multiple \
+                     of its entry size {:#x}.",
+                    section.name, data.size, data.entry_size
+                ),
+            );
+        }
+
+        if data.addr_align > 1 && !data.addr_align.is_power_of_two() {
+            recorder.warning(
+                offset,
+                format!(
+                    "Section {index} ({}) has an alignment of {}, which is not a power of two.",
+                    section.name, data.addr_align
+                ),
+            );
+        } else if data.addr_align > 1 && !data.addr.is_multiple_of(data.addr_align) {
+            recorder.warning(
+                offset,
+                format!(
+                    "Section {index} ({}) is at address {:#x}, which is not {}-byte aligned.",
+                    section.name, data.addr, data.addr_align
+                ),
+            );
+        }
+    }
+}
+
+/// True if `size` bytes at `offset` are all within the file.
+fn fits(offset: u64, size: u64, file_len: u64) -> bool {
+    offset.checked_add(size).is_some_and(|end| end <= file_len)
+}
+
+/// True if the address ranges of the given starts and sizes share an address.
+fn overlaps(a_start: u64, a_size: u64, b_start: u64, b_size: u64) -> bool {
+    let a_end = a_start.saturating_add(a_size);
+    let b_end = b_start.saturating_add(b_size);
+
+    a_size != 0 && b_size != 0 && a_start < b_end && b_start < a_end
+}
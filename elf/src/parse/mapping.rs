@@ -0,0 +1,102 @@
+/// Computes which sections are contained in each segment.
+///
+/// This follows the rules readelf uses for its "Section to Segment mapping"
+/// (the `ELF_SECTION_IN_SEGMENT_STRICT` macro in binutils' `include/elf/internal.h`).
+///
+use crate::parse::types::*;
+use crate::parse::*;
+
+// Range of PT_GNU_MBIND segments.
+const PT_GNU_MBIND_LO: u32 = PT_LOOS + 0x474e555;
+const PT_GNU_MBIND_HI: u32 = PT_GNU_MBIND_LO + 4096 - 1;
+
+/// For each segment, in order, returns the indices of the sections it contains.
+pub fn section_segment_mapping(
+    program_headers: &[Elf64ProgramHeaderInfo],
+    section_headers: &[Elf64SectionHeaderInfo],
+) -> Vec<Vec<usize>> {
+    program_headers
+        .iter()
+        .map(|segment| {
+            // Section 0 is the null section, which no segment contains.
+            (1..section_headers.len())
+                .filter(|&i| {
+                    let section = &section_headers[i].header_data;
+                    !is_tbss_special(section, &segment.header_data)
+                        && section_in_segment(section, &segment.header_data)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// A .tbss section (TLS data not in the file) occupies no memory in any
+/// segment except the PT_TLS segment.
+fn is_tbss_special(section: &Elf64SectionHeaderEntry, segment: &Elf64ProgramHeaderEntry) -> bool {
+    section.flags & SHF_TLS != 0 && section.type_id() == SHT_NOBITS && segment.type_id() != PT_TLS
+}
+
+fn section_size(section: &Elf64SectionHeaderEntry, segment: &Elf64ProgramHeaderEntry) -> u64 {
+    if is_tbss_special(section, segment) {
+        0
+    } else {
+        section.size
+    }
+}
+
+/// Whether `section` lies in `segment`, ignoring the .tbss special case.
+pub fn section_in_segment(
+    section: &Elf64SectionHeaderEntry,
+    segment: &Elf64ProgramHeaderEntry,
+) -> bool {
+    let segment_type = segment.type_id();
+    let is_tls = section.flags & SHF_TLS != 0;
+    let is_alloc = section.flags & SHF_ALLOC != 0;
+    let is_nobits = section.type_id() == SHT_NOBITS;
+    let size = section_size(section, segment);
+
+    // Only PT_LOAD, PT_GNU_RELRO and PT_TLS segments can contain TLS sections;
+    // PT_TLS contains only TLS sections, and PT_PHDR contains no sections at all.
+    let tls_ok = if is_tls {
+        matches!(segment_type, PT_TLS | PT_GNU_RELRO | PT_LOAD)
+    } else {
+        segment_type != PT_TLS && segment_type != PT_PHDR
+    };
+
+    // These segment types only contain allocated sections.
+    let alloc_only = matches!(
+        segment_type,
+        PT_LOAD | PT_DYNAMIC | PT_GNU_EH_FRAME | PT_GNU_STACK | PT_GNU_RELRO | PT_GNU_SFRAME
+    ) || (PT_GNU_MBIND_LO..=PT_GNU_MBIND_HI).contains(&segment_type);
+    let alloc_ok = is_alloc || !alloc_only;
+
+    // Any section with file contents must lie within the segment's file image.
+    let offset_ok = is_nobits
+        || (section.offset >= segment.offset
+            && section.offset - segment.offset <= segment.file_size.wrapping_sub(1)
+            && (section.offset - segment.offset)
+                .checked_add(size)
+                .is_some_and(|end| end <= segment.file_size));
+
+    // Allocated sections must lie within the segment's memory image.
+    let address_ok = !is_alloc
+        || (section.addr >= segment.virtual_address
+            && section.addr - segment.virtual_address <= segment.mem_size.wrapping_sub(1)
+            && (section.addr - segment.virtual_address)
+                .checked_add(size)
+                .is_some_and(|end| end <= segment.mem_size));
+
+    // Empty sections at the very start or end of a PT_DYNAMIC or PT_NOTE
+    // segment aren't counted as part of it.
+    let empty_ok = (segment_type != PT_DYNAMIC && segment_type != PT_NOTE)
+        || section.size != 0
+        || segment.mem_size == 0
+        || ((is_nobits
+            || (section.offset > segment.offset
+                && section.offset - segment.offset < segment.file_size))
+            && (!is_alloc
+                || (section.addr > segment.virtual_address
+                    && section.addr - segment.virtual_address < segment.mem_size)));
+
+    tls_ok && alloc_ok && offset_ok && address_ok && empty_ok
+}
@@ -1,12 +1,30 @@
 /// Tools for parsing ELF files.
 ///
+pub mod attributes;
+pub mod compression;
+pub mod dynamic;
+pub mod hash;
+pub mod header;
+pub mod layout;
+pub mod mapping;
+pub mod notes;
+pub mod plt;
+pub mod relocs;
+pub mod sframe;
+pub mod strings;
+pub mod symbol_map;
+pub mod symbols;
+pub mod types;
+#[allow(clippy::empty_line_after_doc_comments)]
 mod utils;
+pub mod versions;
 
-use crate::file_read::FileReader;
-use crate::parse::utils::*;
+pub use types::{program_header_type_string, section_header_type_string};
+
+use crate::parse::strings::StringTable;
+use crate::{ElfError, Recorder};
 
 use from_bytes_macro::FromBytes;
-use std::mem::size_of;
 
 // ----------------
 // Main ELF header.
@@ -44,9 +62,17 @@ pub struct Elf64Header {
     pub section_header_names_index: u16,
 }
 
+impl Elf64Header {
+    pub fn machine_id(&self) -> u16 {
+        u16::from_le_bytes(self.machine)
+    }
+}
+
 // ---------------------
 // Program header table.
 
+pub const ELF64_PROGRAM_HEADER_LEN: usize = 56;
+
 #[derive(Debug, FromBytes)]
 pub struct Elf64ProgramHeaderEntry {
     pub segment_type: [u8; 4],
@@ -60,6 +86,28 @@ pub struct Elf64ProgramHeaderEntry {
     pub align: u64,
 }
 
+// Segment permission flags.
+pub const PF_X: u32 = 0x1;
+pub const PF_W: u32 = 0x2;
+pub const PF_R: u32 = 0x4;
+
+impl Elf64ProgramHeaderEntry {
+    pub fn type_id(&self) -> u32 {
+        u32::from_le_bytes(self.segment_type)
+    }
+
+    /// Permissions in the "RWE" form that readelf uses.
+    pub fn flags_string(&self) -> String {
+        let mut string = String::new();
+
+        string.push(if self.flags & PF_R != 0 { 'R' } else { ' ' });
+        string.push(if self.flags & PF_W != 0 { 'W' } else { ' ' });
+        string.push(if self.flags & PF_X != 0 { 'E' } else { ' ' });
+
+        string
+    }
+}
+
 pub struct Elf64ProgramHeaderInfo {
     // Raw data from file.
     pub header_data: Elf64ProgramHeaderEntry,
@@ -68,65 +116,35 @@ pub struct Elf64ProgramHeaderInfo {
     pub type_string: String,
 }
 
+/// Reads the program header table, given the bytes of the table itself.
 pub fn read_program_headers_64(
-    buffer: &[u8],
+    table: &[u8],
     elf_header: &Elf64Header,
 ) -> Vec<Elf64ProgramHeaderInfo> {
-    let mut entries = vec![];
-
-    let ph_offset = elf_header.program_header_offset as usize;
+    // Object files may have no program headers, and an entry size of 0.
     let ph_size = elf_header.program_header_entry_size as usize;
 
     // Read each entry from the program header table.
-    for i in 0..elf_header.program_header_entry_count as usize {
-        let start_offset = ph_offset + i * ph_size;
-        let end_offset = start_offset + ph_size;
-
-        let ph = Elf64ProgramHeaderEntry::parse_from_bytes(&buffer[start_offset..end_offset]);
-
-        let type_string = program_header_type_string(&ph.segment_type);
-
-        entries.push(Elf64ProgramHeaderInfo {
-            header_data: ph,
-            type_string: type_string,
-        });
-    }
-
-    entries
-}
-
-pub fn program_header_type_string(buffer: &[u8; 4]) -> String {
-    let str_val = match buffer {
-        // File bytes are little endian; hence the reverse order here.
-        b"\x00\x00\x00\x00" => "PT_NULL",
-        b"\x01\x00\x00\x00" => "PT_LOAD",
-        b"\x02\x00\x00\x00" => "PT_DYNAMIC",
-        b"\x03\x00\x00\x00" => "PT_INTERP",
-        b"\x04\x00\x00\x00" => "PT_NOTE",
-        b"\x05\x00\x00\x00" => "PT_SHLIB",
-        b"\x06\x00\x00\x00" => "PT_PHDR",
-        b"\x07\x00\x00\x00" => "PT_TLS",
-
-        _ => {
-            let buff_in_be: Vec<&u8> = buffer.iter().rev().collect();
-            let type_str = match buffer {
-                buf if buf[3] & b'\xf0' == b'\x60' => "OS_SPECIFIC",
-                buf if buf[3] & b'\xf0' == b'\x70' => "PROCESSOR_SPECIFIC",
-
-                // TODO: Look up Linux OS-specific type names.
-                _ => "OTHER",
-            };
-
-            &format!("{type_str}: {:02x?}", buff_in_be)
-        }
-    };
-
-    str_val.to_owned()
+    table
+        .chunks_exact(ph_size.max(1))
+        .take(elf_header.program_header_entry_count as usize)
+        .map(|bytes| {
+            let ph = Elf64ProgramHeaderEntry::parse_from_bytes(bytes);
+            let type_string = program_header_type_string(&ph.segment_type, elf_header.machine_id());
+
+            Elf64ProgramHeaderInfo {
+                header_data: ph,
+                type_string,
+            }
+        })
+        .collect()
 }
 
 // ---------------------
 // Section header table.
 
+pub const ELF64_SECTION_HEADER_LEN: usize = 64;
+
 #[derive(Debug, FromBytes)]
 pub struct Elf64SectionHeaderEntry {
     pub name_offset: u32,
@@ -141,6 +159,66 @@ pub struct Elf64SectionHeaderEntry {
     pub entry_size: u64,
 }
 
+// Section flags.
+pub const SHF_WRITE: u64 = 0x1;
+pub const SHF_ALLOC: u64 = 0x2;
+pub const SHF_EXECINSTR: u64 = 0x4;
+pub const SHF_MERGE: u64 = 0x10;
+pub const SHF_STRINGS: u64 = 0x20;
+pub const SHF_INFO_LINK: u64 = 0x40;
+pub const SHF_LINK_ORDER: u64 = 0x80;
+pub const SHF_OS_NONCONFORMING: u64 = 0x100;
+pub const SHF_GROUP: u64 = 0x200;
+pub const SHF_TLS: u64 = 0x400;
+pub const SHF_COMPRESSED: u64 = 0x800;
+pub const SHF_EXCLUDE: u64 = 0x80000000;
+
+impl Elf64SectionHeaderEntry {
+    pub fn type_id(&self) -> u32 {
+        u32::from_le_bytes(self.section_type)
+    }
+
+    /// Flags as the letter codes that readelf uses (see its "Key to Flags").
+    pub fn flags_string(&self) -> String {
+        let letters = [
+            (SHF_WRITE, 'W'),
+            (SHF_ALLOC, 'A'),
+            (SHF_EXECINSTR, 'X'),
+            (SHF_MERGE, 'M'),
+            (SHF_STRINGS, 'S'),
+            (SHF_INFO_LINK, 'I'),
+            (SHF_LINK_ORDER, 'L'),
+            (SHF_OS_NONCONFORMING, 'O'),
+            (SHF_GROUP, 'G'),
+            (SHF_TLS, 'T'),
+            (SHF_COMPRESSED, 'C'),
+        ];
+
+        let known = letters.iter().fold(SHF_EXCLUDE, |all, (bit, _)| all | bit);
+
+        let mut string: String = letters
+            .iter()
+            .filter(|(bit, _)| self.flags & bit != 0)
+            .map(|(_, letter)| *letter)
+            .collect();
+
+        if self.flags & 0x0ff00000 != 0 {
+            string.push('o');
+        }
+        if self.flags & 0xf0000000 & !SHF_EXCLUDE != 0 {
+            string.push('p');
+        }
+        if self.flags & SHF_EXCLUDE != 0 {
+            string.push('E');
+        }
+        if self.flags & !known & !0xfff00000 != 0 {
+            string.push('x');
+        }
+
+        string
+    }
+}
+
 #[derive(Debug)]
 pub struct Elf64SectionHeaderInfo {
     // Raw data from file.
@@ -151,105 +229,56 @@ pub struct Elf64SectionHeaderInfo {
     pub type_string: String,
 }
 
+/// Names the section header entries, using the contents of the section
+/// name string table (`e_shstrndx`). If there is no such table, `names`
+/// is empty and so are the names; a bad name is left empty if `recorder`
+/// is lenient.
 pub fn read_section_headers_64(
-    reader: &mut FileReader,
+    entries: Vec<Elf64SectionHeaderEntry>,
+    names: &StringTable,
     elf_header: &Elf64Header,
-) -> Vec<Elf64SectionHeaderInfo> {
-    let mut buffer = reader.buffer();
+    recorder: &mut Recorder,
+) -> Result<Vec<Elf64SectionHeaderInfo>, ElfError> {
+    let mut section_headers = Vec::with_capacity(entries.len());
 
-    let mut entries = read_section_header_entries_64(&buffer, &elf_header);
-    let mut headers: Vec<Elf64SectionHeaderInfo> = vec![];
+    for (index, entry) in entries.into_iter().enumerate() {
+        let type_string = section_header_type_string(&entry.section_type, elf_header.machine_id());
 
-    let mut string_table_entries: Vec<&Elf64SectionHeaderEntry> = vec![];
-
-    // Find string table entries.
-    for entry in &entries {
-        let type_string = section_header_type_string(&entry.section_type);
-        if type_string == "SHT_STRTAB" {
-            string_table_entries.push(entry);
-        }
-    }
+        let name = match names.get_lossy(entry.name_offset as usize) {
+            Ok(name) => name.into_owned(),
+            Err(_) if names.is_empty() => String::new(),
 
-    // NOTE: We assume the last table section entry is the one with section names.
-    // Evidence from examples suggests this, but we should find it in the docs.
+            Err(error) => {
+                let entry_offset = elf_header.section_header_offset
+                    + index as u64 * elf_header.section_header_entry_size as u64;
 
-    let section_name_table_entry = string_table_entries.last().unwrap();
-    let table_start = section_name_table_entry.offset as usize;
-    let table_end = table_start + section_name_table_entry.size as usize;
+                recorder.error(entry_offset, error.reading("Section name"))?;
 
-    reader
-        .ensure_length(table_end)
-        .expect("Not enough bytes in file to accommodate offset and size of string table entry.");
-    buffer = reader.buffer();
+                String::new()
+            }
+        };
 
-    let table_buffer = &buffer[table_start..table_end];
-
-    for entry in entries {
-        let type_string = section_header_type_string(&entry.section_type);
-        let name_offset = entry.name_offset as usize;
-        let name_string = read_string(&table_buffer, name_offset).unwrap();
-
-        headers.push(Elf64SectionHeaderInfo {
+        section_headers.push(Elf64SectionHeaderInfo {
             header_data: entry,
-            name: name_string,
+            name,
             type_string,
-        })
+        });
     }
 
-    headers
+    Ok(section_headers)
 }
 
+/// Reads the section header table, given the bytes of the table itself.
 pub fn read_section_header_entries_64(
-    buffer: &[u8],
+    table: &[u8],
     elf_header: &Elf64Header,
 ) -> Vec<Elf64SectionHeaderEntry> {
-    let mut entries = vec![];
-
-    let sh_offset = elf_header.section_header_offset as usize;
     let sh_size = elf_header.section_header_entry_size as usize;
 
     // Read each entry from the section header table.
-    for i in 0..elf_header.section_header_entry_count as usize {
-        let start_offset = sh_offset + i * sh_size;
-        let end_offset = start_offset + sh_size;
-
-        let sh = Elf64SectionHeaderEntry::parse_from_bytes(&buffer[start_offset..end_offset]);
-        entries.push(sh)
-    }
-
-    entries
-}
-
-pub fn section_header_type_string(buffer: &[u8; 4]) -> String {
-    let str_val = match buffer {
-        // File bytes are little endian; hence the reverse order here.
-        b"\x00\x00\x00\x00" => "SHT_NULL",
-        b"\x01\x00\x00\x00" => "SHT_PROGBITS",
-        b"\x02\x00\x00\x00" => "SHT_SYNTAB",
-        b"\x03\x00\x00\x00" => "SHT_STRTAB",
-        b"\x04\x00\x00\x00" => "SHT_RELA",
-        b"\x05\x00\x00\x00" => "SHT_HASH",
-        b"\x06\x00\x00\x00" => "SHT_DYNAMIC",
-        b"\x07\x00\x00\x00" => "SHT_NOTE",
-        b"\x08\x00\x00\x00" => "SHT_NOBITS",
-        b"\x09\x00\x00\x00" => "SHT_REL",
-        b"\x0A\x00\x00\x00" => "SHT_SHLIB",
-        b"\x0B\x00\x00\x00" => "SHT_DYNSYM",
-        b"\x0E\x00\x00\x00" => "SHT_INIT_ARRAY",
-        b"\x0F\x00\x00\x00" => "SHT_FINI_ARRAY",
-        b"\x10\x00\x00\x00" => "SHT_PREINIT_ARRAY",
-        b"\x11\x00\x00\x00" => "SHT_GROUP",
-        b"\x12\x00\x00\x00" => "SHT_SYMTAB_SHNDX",
-        b"\x13\x00\x00\x00" => "SHT_NUM",
-
-        // 0x60000000 and above are OS-specific.
-        buf if buf[3] & b'\xf0' >= b'\x60' => "OS_SPECIFIC",
-
-        _ => {
-            let buff_in_be: Vec<&u8> = buffer.iter().rev().collect();
-            &format!("UNRECOGNIZED TYPE: {:02x?}", buff_in_be)
-        }
-    };
-
-    str_val.to_owned()
+    table
+        .chunks_exact(sh_size.max(1))
+        .take(elf_header.section_header_entry_count as usize)
+        .map(Elf64SectionHeaderEntry::parse_from_bytes)
+        .collect()
 }
@@ -0,0 +1,445 @@
+/// Parsing for the contents of note sections and segments.
+///
+/// A note is a 12 byte header (name size, descriptor size, type), followed
+/// by the owner name and the descriptor, each padded to the note alignment.
+///
+use crate::parse::header::{EM_386, EM_AARCH64, EM_X86_64};
+use crate::parse::utils::*;
+use crate::ElfError;
+
+use std::fmt;
+
+// -----------
+// Note types.
+
+// Types for notes with owner "GNU".
+pub const NT_GNU_ABI_TAG: u32 = 1;
+pub const NT_GNU_HWCAP: u32 = 2;
+pub const NT_GNU_BUILD_ID: u32 = 3;
+pub const NT_GNU_GOLD_VERSION: u32 = 4;
+pub const NT_GNU_PROPERTY_TYPE_0: u32 = 5;
+
+// Type for notes with owner "Go".
+pub const NT_GO_BUILD_ID: u32 = 4;
+
+// Type for notes with owner "stapsdt", which describe SystemTap probes.
+pub const NT_STAPSDT: u32 = 3;
+
+// Type for notes with owner "FDO"; see https://systemd.io/ELF_PACKAGE_METADATA/.
+pub const NT_FDO_PACKAGING_METADATA: u32 = 0xcafe1a7e;
+
+// Types for entries in a NT_GNU_PROPERTY_TYPE_0 note.
+pub const GNU_PROPERTY_STACK_SIZE: u32 = 1;
+pub const GNU_PROPERTY_NO_COPY_ON_PROTECTED: u32 = 2;
+pub const GNU_PROPERTY_AARCH64_FEATURE_1_AND: u32 = 0xc0000000;
+pub const GNU_PROPERTY_X86_FEATURE_1_AND: u32 = 0xc0000002;
+pub const GNU_PROPERTY_X86_ISA_1_NEEDED: u32 = 0xc0008002;
+pub const GNU_PROPERTY_X86_ISA_1_USED: u32 = 0xc0010002;
+
+// Bits of GNU_PROPERTY_X86_FEATURE_1_AND.
+pub const GNU_PROPERTY_X86_FEATURE_1_IBT: u32 = 1 << 0;
+pub const GNU_PROPERTY_X86_FEATURE_1_SHSTK: u32 = 1 << 1;
+
+// Bits of GNU_PROPERTY_AARCH64_FEATURE_1_AND.
+pub const GNU_PROPERTY_AARCH64_FEATURE_1_BTI: u32 = 1 << 0;
+pub const GNU_PROPERTY_AARCH64_FEATURE_1_PAC: u32 = 1 << 1;
+
+// Size of the fixed note header.
+const NOTE_HEADER_LEN: usize = 12;
+
+// --------------
+// Raw note data.
+
+#[derive(Debug)]
+pub struct ElfNote<'a> {
+    /// Offset of this note within the bytes it was read from.
+    pub offset: usize,
+
+    /// Owner name, without the trailing null.
+    pub name: String,
+    pub note_type: u32,
+    pub desc: &'a [u8],
+}
+
+/// Iterates over the notes in the contents of a PT_NOTE segment
+/// or SHT_NOTE section. Stops after the first malformed note.
+pub struct NoteIterator<'a> {
+    bytes: &'a [u8],
+    align: usize,
+    position: usize,
+    failed: bool,
+}
+
+/// Returns an iterator over the notes in `bytes`, where `align` is the
+/// alignment of the segment or section the bytes came from.
+pub fn read_notes(bytes: &[u8], align: u64) -> NoteIterator<'_> {
+    // Like readelf, we accept 8-byte alignment (used by .note.gnu.property
+    // on 64-bit targets), and treat anything else as the usual 4.
+    let align = if align == 8 { 8 } else { 4 };
+
+    NoteIterator {
+        bytes,
+        align,
+        position: 0,
+        failed: false,
+    }
+}
+
+fn align_up(value: usize, align: usize) -> usize {
+    (value + align - 1) & !(align - 1)
+}
+
+impl<'a> Iterator for NoteIterator<'a> {
+    type Item = Result<ElfNote<'a>, ElfError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed || self.position + NOTE_HEADER_LEN > self.bytes.len() {
+            return None;
+        }
+
+        let start = self.position;
+        let name_size = from_le_bytes!(u32, self.bytes, start) as usize;
+        let desc_size = from_le_bytes!(u32, self.bytes, start + 4) as usize;
+        let note_type = from_le_bytes!(u32, self.bytes, start + 8);
+
+        let name_start = start + NOTE_HEADER_LEN;
+        let desc_start = start + align_up(NOTE_HEADER_LEN + name_size, self.align);
+        let desc_end = desc_start.checked_add(desc_size);
+
+        let desc_end = match desc_end {
+            Some(end) if end <= self.bytes.len() => end,
+
+            _ => {
+                self.failed = true;
+                return Some(Err(ElfError::Truncated {
+                    what: "Note",
+                    offset: start as u64,
+                    needed: (desc_start - start) as u64 + desc_size as u64,
+                }));
+            }
+        };
+
+        // Owner names are null-terminated, but we don't require it. Some
+        // (Go's) are padded with more nulls, which the name ends at too.
+        let name_bytes = &self.bytes[name_start..name_start + name_size];
+        let name_bytes = match name_bytes.iter().position(|&b| b == 0) {
+            Some(end) => &name_bytes[..end],
+            None => name_bytes,
+        };
+
+        self.position = start + align_up(desc_end - start, self.align);
+
+        Some(Ok(ElfNote {
+            offset: start,
+            name: String::from_utf8_lossy(name_bytes).to_string(),
+            note_type,
+            desc: &self.bytes[desc_start..desc_end],
+        }))
+    }
+}
+
+// ---------------------
+// Decoded descriptors.
+
+#[derive(Debug)]
+pub enum NoteDescriptor {
+    GnuAbiTag {
+        os: u32,
+        major: u32,
+        minor: u32,
+        patch: u32,
+    },
+    GnuBuildId(Vec<u8>),
+    GnuGoldVersion(String),
+    GnuProperties(Vec<GnuProperty>),
+    GoBuildId(String),
+    FdoPackagingMetadata(String),
+
+    /// A SystemTap probe: where it is, the address of .stapsdt.base when
+    /// linked (to adjust the others by if the file is prelinked), and the
+    /// counter that turns it on, if any.
+    StapSdt {
+        location: u64,
+        base: u64,
+        semaphore: u64,
+        provider: String,
+        name: String,
+        arguments: String,
+    },
+
+    /// A note type we don't decode, or a descriptor that was malformed.
+    Unknown,
+}
+
+#[derive(Debug)]
+pub enum GnuProperty {
+    StackSize(u64),
+    NoCopyOnProtected,
+    X86Feature1And(u32),
+    X86IsaNeeded(u32),
+    X86IsaUsed(u32),
+    AArch64Feature1And(u32),
+    Other { pr_type: u32, data: Vec<u8> },
+}
+
+impl ElfNote<'_> {
+    /// Decodes the descriptor for the note types we know about. Processor-specific
+    /// GNU properties are interpreted according to `machine` (the header's `e_machine`).
+    pub fn descriptor(&self, machine: u16) -> NoteDescriptor {
+        match (self.name.as_str(), self.note_type) {
+            ("GNU", NT_GNU_ABI_TAG) if self.desc.len() >= 16 => NoteDescriptor::GnuAbiTag {
+                os: from_le_bytes!(u32, self.desc, 0),
+                major: from_le_bytes!(u32, self.desc, 4),
+                minor: from_le_bytes!(u32, self.desc, 8),
+                patch: from_le_bytes!(u32, self.desc, 12),
+            },
+
+            ("GNU", NT_GNU_BUILD_ID) => NoteDescriptor::GnuBuildId(self.desc.to_vec()),
+
+            ("GNU", NT_GNU_GOLD_VERSION) => NoteDescriptor::GnuGoldVersion(desc_string(self.desc)),
+
+            ("GNU", NT_GNU_PROPERTY_TYPE_0) => match read_gnu_properties(self.desc, machine) {
+                Some(properties) => NoteDescriptor::GnuProperties(properties),
+                None => NoteDescriptor::Unknown,
+            },
+
+            ("Go", NT_GO_BUILD_ID) => NoteDescriptor::GoBuildId(desc_string(self.desc)),
+
+            ("FDO", NT_FDO_PACKAGING_METADATA) => {
+                NoteDescriptor::FdoPackagingMetadata(desc_string(self.desc))
+            }
+
+            ("stapsdt", NT_STAPSDT) => read_stapsdt(self.desc).unwrap_or(NoteDescriptor::Unknown),
+
+            _ => NoteDescriptor::Unknown,
+        }
+    }
+
+    /// Name of the note type, which depends on the owner.
+    pub fn type_string(&self) -> String {
+        let str_val = match (self.name.as_str(), self.note_type) {
+            ("GNU", NT_GNU_ABI_TAG) => "NT_GNU_ABI_TAG",
+            ("GNU", NT_GNU_HWCAP) => "NT_GNU_HWCAP",
+            ("GNU", NT_GNU_BUILD_ID) => "NT_GNU_BUILD_ID",
+            ("GNU", NT_GNU_GOLD_VERSION) => "NT_GNU_GOLD_VERSION",
+            ("GNU", NT_GNU_PROPERTY_TYPE_0) => "NT_GNU_PROPERTY_TYPE_0",
+            ("Go", NT_GO_BUILD_ID) => "NT_GO_BUILD_ID",
+            ("FDO", NT_FDO_PACKAGING_METADATA) => "NT_FDO_PACKAGING_METADATA",
+            ("stapsdt", NT_STAPSDT) => "NT_STAPSDT",
+
+            _ => &format!("UNKNOWN NOTE TYPE: {:#x}", self.note_type),
+        };
+
+        str_val.to_owned()
+    }
+}
+
+/// Descriptor bytes as a string, dropping any trailing nulls.
+fn desc_string(desc: &[u8]) -> String {
+    let end = desc.iter().rposition(|b| *b != 0).map_or(0, |i| i + 1);
+    String::from_utf8_lossy(&desc[..end]).to_string()
+}
+
+/// Reads a NT_STAPSDT descriptor: three addresses, then the provider, the
+/// probe name, and the arguments, each null-terminated. Returns None if
+/// it's too small for them.
+fn read_stapsdt(desc: &[u8]) -> Option<NoteDescriptor> {
+    if desc.len() <= 24 {
+        return None;
+    }
+
+    let mut strings = desc[24..].split_inclusive(|&b| b == 0).map(|string| {
+        let string = string.strip_suffix(&[0])?;
+        Some(String::from_utf8_lossy(string).to_string())
+    });
+
+    Some(NoteDescriptor::StapSdt {
+        location: from_le_bytes!(u64, desc, 0),
+        base: from_le_bytes!(u64, desc, 8),
+        semaphore: from_le_bytes!(u64, desc, 16),
+        provider: strings.next()??,
+        name: strings.next()??,
+        arguments: strings.next()??,
+    })
+}
+
+/// Reads the property array of a NT_GNU_PROPERTY_TYPE_0 note. Entries are
+/// 8-byte aligned in 64-bit files. Returns None if the array is malformed.
+fn read_gnu_properties(desc: &[u8], machine: u16) -> Option<Vec<GnuProperty>> {
+    let mut properties = vec![];
+    let mut position = 0;
+
+    while position + 8 <= desc.len() {
+        let pr_type = from_le_bytes!(u32, desc, position);
+        let data_size = from_le_bytes!(u32, desc, position + 4) as usize;
+
+        let data_start = position + 8;
+        let data_end = data_start.checked_add(data_size)?;
+        let data = desc.get(data_start..data_end)?;
+
+        let word = if data.len() == 4 {
+            Some(from_le_bytes!(u32, data, 0))
+        } else {
+            None
+        };
+
+        let x86 = machine == EM_X86_64 || machine == EM_386;
+        let aarch64 = machine == EM_AARCH64;
+
+        let property = match (pr_type, word) {
+            (GNU_PROPERTY_STACK_SIZE, _) if data.len() == 8 => {
+                GnuProperty::StackSize(from_le_bytes!(u64, data, 0))
+            }
+            (GNU_PROPERTY_NO_COPY_ON_PROTECTED, _) => GnuProperty::NoCopyOnProtected,
+
+            (GNU_PROPERTY_X86_FEATURE_1_AND, Some(bits)) if x86 => {
+                GnuProperty::X86Feature1And(bits)
+            }
+            (GNU_PROPERTY_X86_ISA_1_NEEDED, Some(bits)) if x86 => GnuProperty::X86IsaNeeded(bits),
+            (GNU_PROPERTY_X86_ISA_1_USED, Some(bits)) if x86 => GnuProperty::X86IsaUsed(bits),
+
+            (GNU_PROPERTY_AARCH64_FEATURE_1_AND, Some(bits)) if aarch64 => {
+                GnuProperty::AArch64Feature1And(bits)
+            }
+
+            _ => GnuProperty::Other {
+                pr_type,
+                data: data.to_vec(),
+            },
+        };
+
+        properties.push(property);
+        position = align_up(data_end, 8);
+    }
+
+    Some(properties)
+}
+
+// -----------
+// Formatting.
+
+/// Formats bytes as a lowercase hex string, as is usual for build IDs.
+pub fn hex_string(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn flag_names(bits: u32, names: &[(u32, &str)]) -> String {
+    let mut parts: Vec<String> = names
+        .iter()
+        .filter(|(bit, _)| bits & bit != 0)
+        .map(|(_, name)| name.to_string())
+        .collect();
+
+    let unknown = names.iter().fold(bits, |rest, (bit, _)| rest & !bit);
+    if unknown != 0 {
+        parts.push(format!("<unknown: {unknown:x}>"));
+    }
+
+    if parts.is_empty() {
+        "<None>".to_owned()
+    } else {
+        parts.join(", ")
+    }
+}
+
+impl fmt::Display for GnuProperty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GnuProperty::StackSize(size) => write!(f, "stack size: {size:#x}"),
+            GnuProperty::NoCopyOnProtected => write!(f, "no copy on protected"),
+
+            GnuProperty::X86Feature1And(bits) => {
+                let names = [
+                    (GNU_PROPERTY_X86_FEATURE_1_IBT, "IBT"),
+                    (GNU_PROPERTY_X86_FEATURE_1_SHSTK, "SHSTK"),
+                ];
+                write!(f, "x86 feature: {}", flag_names(*bits, &names))
+            }
+
+            GnuProperty::X86IsaNeeded(bits) | GnuProperty::X86IsaUsed(bits) => {
+                let names = [
+                    (1 << 0, "x86-64-baseline"),
+                    (1 << 1, "x86-64-v2"),
+                    (1 << 2, "x86-64-v3"),
+                    (1 << 3, "x86-64-v4"),
+                ];
+                let kind = match self {
+                    GnuProperty::X86IsaNeeded(_) => "needed",
+                    _ => "used",
+                };
+                write!(f, "x86 ISA {kind}: {}", flag_names(*bits, &names))
+            }
+
+            GnuProperty::AArch64Feature1And(bits) => {
+                let names = [
+                    (GNU_PROPERTY_AARCH64_FEATURE_1_BTI, "BTI"),
+                    (GNU_PROPERTY_AARCH64_FEATURE_1_PAC, "PAC"),
+                ];
+                write!(f, "AArch64 feature: {}", flag_names(*bits, &names))
+            }
+
+            GnuProperty::Other { pr_type, data } => {
+                write!(f, "<unknown type {pr_type:#x}: {}>", hex_string(data))
+            }
+        }
+    }
+}
+
+impl fmt::Display for NoteDescriptor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NoteDescriptor::GnuAbiTag {
+                os,
+                major,
+                minor,
+                patch,
+            } => {
+                let os_name = match os {
+                    0 => "Linux",
+                    1 => "Hurd",
+                    2 => "Solaris",
+                    3 => "FreeBSD",
+                    4 => "NetBSD",
+                    5 => "Syllable",
+                    6 => "NaCl",
+                    _ => "Unknown",
+                };
+                write!(f, "OS: {os_name}, ABI: {major}.{minor}.{patch}")
+            }
+
+            NoteDescriptor::GnuBuildId(id) => write!(f, "Build ID: {}", hex_string(id)),
+            NoteDescriptor::GnuGoldVersion(version) => write!(f, "Version: {version}"),
+
+            NoteDescriptor::GnuProperties(properties) => {
+                let lines: Vec<String> = properties.iter().map(|p| p.to_string()).collect();
+                write!(f, "Properties: {}", lines.join("\n            "))
+            }
+
+            NoteDescriptor::GoBuildId(id) => write!(f, "Go Build ID: {id}"),
+            NoteDescriptor::FdoPackagingMetadata(json) => write!(f, "Packaging Metadata: {json}"),
+
+            NoteDescriptor::StapSdt {
+                location,
+                base,
+                semaphore,
+                provider,
+                name,
+                arguments,
+            } => write!(
+                f,
+                "Probe: {provider}:{name}, location: {location:#x}, base: {base:#x}, \
+                 semaphore: {semaphore:#x}, arguments: {arguments}"
+            ),
+
+            NoteDescriptor::Unknown => write!(f, "<undecoded>"),
+        }
+    }
+}
+
+/// Returns the GNU build ID from a sequence of notes, if there is one.
+pub fn find_build_id(notes: NoteIterator) -> Option<Vec<u8>> {
+    notes
+        .filter_map(|note| note.ok())
+        .find(|note| note.name == "GNU" && note.note_type == NT_GNU_BUILD_ID)
+        .map(|note| note.desc.to_vec())
+}
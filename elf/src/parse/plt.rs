@@ -0,0 +1,152 @@
+/// Finding what the procedure linkage table's stubs call. A call to a
+/// function in another object goes to a PLT stub, which jumps through a
+/// slot in the global offset table that the dynamic linker fills in, as
+/// a dynamic relocation (in `.rela.plt`, or in `.rela.dyn` for `.plt.got`)
+/// says. So the stubs are decoded to find their slots, and the slots
+/// named after the relocations' symbols, as objdump does it.
+///
+use std::collections::BTreeMap;
+
+/// A function (or object) from another file, reached through the GOT.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Import {
+    /// The dynamic symbol's name, or for the GOT slot of an IFUNC that
+    /// the file resolves itself (R_*_IRELATIVE), `*ABS*` and the
+    /// resolver's address, as in `*ABS*+0x401136`.
+    pub name: String,
+    /// The symbol's index in .dynsym, or 0 if there's none.
+    pub symbol_index: usize,
+    /// The GOT slot that the dynamic linker fills in with its address.
+    pub got: u64,
+    /// The PLT stub that jumps through the slot, if there is one.
+    pub plt: Option<u64>,
+}
+
+/// The imports of a file, for looking up by the address of their stubs
+/// or GOT slots.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Imports {
+    /// In order of GOT slot.
+    pub imports: Vec<Import>,
+    by_address: BTreeMap<u64, usize>,
+}
+
+impl Imports {
+    /// Takes the imports by their GOT slots, and the stubs as the slots
+    /// they jump through. Stubs for slots no import has are left out.
+    pub fn new(mut imports: Vec<Import>, stubs: &[(u64, u64)]) -> Imports {
+        imports.sort_by_key(|import| import.got);
+        imports.dedup_by_key(|import| import.got);
+
+        for &(plt, got) in stubs {
+            if let Ok(index) = imports.binary_search_by_key(&got, |import| import.got) {
+                imports[index].plt.get_or_insert(plt);
+            }
+        }
+
+        let mut by_address = BTreeMap::new();
+        for (index, import) in imports.iter().enumerate() {
+            by_address.insert(import.got, index);
+            if let Some(plt) = import.plt {
+                by_address.insert(plt, index);
+            }
+        }
+
+        Imports {
+            imports,
+            by_address,
+        }
+    }
+
+    /// The import whose PLT stub or GOT slot starts at `address`.
+    pub fn lookup(&self, address: u64) -> Option<&Import> {
+        self.by_address
+            .get(&address)
+            .map(|&index| &self.imports[index])
+    }
+
+    /// The import whose PLT stub starts at `address`.
+    pub fn by_plt(&self, address: u64) -> Option<&Import> {
+        self.lookup(address)
+            .filter(|import| import.plt == Some(address))
+    }
+
+    /// The import whose GOT slot is at `address`.
+    pub fn by_got(&self, address: u64) -> Option<&Import> {
+        self.lookup(address).filter(|import| import.got == address)
+    }
+}
+
+/// The `endbr64` that starts each stub when the code is built for
+/// Indirect Branch Tracking.
+const ENDBR64: [u8; 4] = [0xf3, 0x0f, 0x1e, 0xfa];
+
+/// Finds the x86-64 stubs in a PLT section of `entry_size`-byte entries,
+/// `bytes` at `address`, as their addresses and the GOT slots that they
+/// jump through, each with a `jmp *slot(%rip)` (maybe with a `bnd`
+/// prefix, and after an `endbr64`). That covers the stubs of .plt and
+/// .plt.got, and of .plt.sec with IBT, where the stubs in .plt only push
+/// the relocation's index and jump to the first entry, as lazy binding
+/// does, and so aren't stubs as far as this goes.
+pub fn x86_64_stubs(bytes: &[u8], address: u64, entry_size: usize) -> Vec<(u64, u64)> {
+    bytes
+        .chunks_exact(entry_size.max(1))
+        .enumerate()
+        .filter_map(|(i, entry)| {
+            let start = address + (i * entry_size) as u64;
+            let mut offset = 0;
+            if entry.starts_with(&ENDBR64) {
+                offset += ENDBR64.len();
+            }
+            if entry.get(offset) == Some(&0xf2) {
+                offset += 1;
+            }
+            let displacement = entry.get(offset..offset + 6)?;
+            if displacement[..2] != [0xff, 0x25] {
+                return None;
+            }
+            let displacement = i32::from_le_bytes(displacement[2..].try_into().unwrap());
+            let next = start + offset as u64 + 6;
+            Some((start, next.wrapping_add(displacement as u64)))
+        })
+        .collect()
+}
+
+/// `bti c`, which starts each stub when the code is built for Branch
+/// Target Identification.
+const BTI_C: u32 = 0xd503245f;
+
+/// Finds the AArch64 stubs in a PLT section, `bytes` at `address`, as
+/// their addresses and the GOT slots that they load the address to jump
+/// to from: each is an `adrp x16` of the slot's page, then an
+/// `ldr x17, [x16, #offset]` of the slot (maybe after a `bti c`). Stubs
+/// may have other instructions after those (with PAC, an `autia1716`),
+/// and be of any size.
+pub fn aarch64_stubs(bytes: &[u8], address: u64) -> Vec<(u64, u64)> {
+    let words: Vec<u32> = bytes
+        .chunks_exact(4)
+        .map(|word| u32::from_le_bytes(word.try_into().unwrap()))
+        .collect();
+
+    let mut stubs = vec![];
+    for (i, pair) in words.windows(2).enumerate() {
+        let (adrp, ldr) = (pair[0], pair[1]);
+        if adrp & 0x9f00001f != 0x90000010 || ldr & 0xffc003ff != 0xf9400211 {
+            continue;
+        }
+
+        let here = address + 4 * i as u64;
+        let page = (((adrp >> 5) & 0x7ffff) << 2) | ((adrp >> 29) & 3);
+        let page = ((page as i64) << 43 >> 31) as u64;
+        let slot = (here & !0xfff)
+            .wrapping_add(page)
+            .wrapping_add((((ldr >> 10) & 0xfff) * 8) as u64);
+
+        let start = match i.checked_sub(1).map(|j| words[j]) {
+            Some(BTI_C) => here - 4,
+            _ => here,
+        };
+        stubs.push((start, slot));
+    }
+    stubs
+}
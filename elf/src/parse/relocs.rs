@@ -0,0 +1,292 @@
+/// Parsing for relocation sections (SHT_REL, SHT_RELA and SHT_RELR).
+///
+use crate::parse::header::Machine;
+use crate::parse::utils::*;
+
+use from_bytes_macro::FromBytes;
+
+pub const ELF64_REL_LEN: usize = 16;
+pub const ELF64_RELA_LEN: usize = 24;
+
+#[derive(Debug, FromBytes)]
+pub struct Elf64RelEntry {
+    pub offset: u64,
+    pub info: u64,
+}
+
+#[derive(Debug, FromBytes)]
+pub struct Elf64RelaEntry {
+    pub offset: u64,
+    pub info: u64,
+    pub addend: i64,
+}
+
+/// A relocation from either kind of section; `addend` is None for SHT_REL.
+#[derive(Debug)]
+pub struct Elf64Relocation {
+    pub offset: u64,
+    pub info: u64,
+    pub addend: Option<i64>,
+}
+
+impl Elf64Relocation {
+    /// Index into the symbol table linked from the relocation section.
+    pub fn symbol_index(&self) -> u32 {
+        (self.info >> 32) as u32
+    }
+
+    pub fn reloc_type(&self) -> u32 {
+        (self.info & 0xffffffff) as u32
+    }
+}
+
+pub fn read_relocations_64(bytes: &[u8], is_rela: bool) -> Vec<Elf64Relocation> {
+    if is_rela {
+        bytes
+            .chunks_exact(ELF64_RELA_LEN)
+            .map(|chunk| {
+                let entry = Elf64RelaEntry::parse_from_bytes(chunk);
+                Elf64Relocation {
+                    offset: entry.offset,
+                    info: entry.info,
+                    addend: Some(entry.addend),
+                }
+            })
+            .collect()
+    } else {
+        bytes
+            .chunks_exact(ELF64_REL_LEN)
+            .map(|chunk| {
+                let entry = Elf64RelEntry::parse_from_bytes(chunk);
+                Elf64Relocation {
+                    offset: entry.offset,
+                    info: entry.info,
+                    addend: None,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Decodes an SHT_RELR section into the addresses it relocates. Each even
+/// entry is an address, and each odd one a bitmap of which of the next 63
+/// words are relocated too, after the last address or the last bitmap's words.
+pub fn read_relr_64(bytes: &[u8]) -> Vec<u64> {
+    const WORD: u64 = 8;
+
+    let mut offsets = vec![];
+    let mut next = 0u64;
+
+    for chunk in bytes.chunks_exact(WORD as usize) {
+        let entry = from_le_bytes!(u64, chunk, 0);
+
+        if entry & 1 == 0 {
+            offsets.push(entry);
+            next = entry.wrapping_add(WORD);
+        } else {
+            for bit in 1..64 {
+                if entry >> bit & 1 != 0 {
+                    offsets.push(next.wrapping_add((bit - 1) * WORD));
+                }
+            }
+            next = next.wrapping_add(63 * WORD);
+        }
+    }
+
+    offsets
+}
+
+// -----------------
+// Relocation types.
+
+pub const R_X86_64_NONE: u32 = 0;
+pub const R_X86_64_64: u32 = 1;
+pub const R_X86_64_PC32: u32 = 2;
+pub const R_X86_64_GLOB_DAT: u32 = 6;
+pub const R_X86_64_JUMP_SLOT: u32 = 7;
+pub const R_X86_64_RELATIVE: u32 = 8;
+pub const R_X86_64_IRELATIVE: u32 = 37;
+
+pub const R_AARCH64_NONE: u32 = 0;
+pub const R_AARCH64_ABS64: u32 = 257;
+pub const R_AARCH64_GLOB_DAT: u32 = 1025;
+pub const R_AARCH64_JUMP_SLOT: u32 = 1026;
+pub const R_AARCH64_RELATIVE: u32 = 1027;
+pub const R_AARCH64_IRELATIVE: u32 = 1032;
+
+pub const R_RISCV_NONE: u32 = 0;
+pub const R_RISCV_64: u32 = 2;
+pub const R_RISCV_RELATIVE: u32 = 3;
+pub const R_RISCV_JUMP_SLOT: u32 = 5;
+
+const X86_64_NAMES: [&str; 44] = [
+    "R_X86_64_NONE",
+    "R_X86_64_64",
+    "R_X86_64_PC32",
+    "R_X86_64_GOT32",
+    "R_X86_64_PLT32",
+    "R_X86_64_COPY",
+    "R_X86_64_GLOB_DAT",
+    "R_X86_64_JUMP_SLOT",
+    "R_X86_64_RELATIVE",
+    "R_X86_64_GOTPCREL",
+    "R_X86_64_32",
+    "R_X86_64_32S",
+    "R_X86_64_16",
+    "R_X86_64_PC16",
+    "R_X86_64_8",
+    "R_X86_64_PC8",
+    "R_X86_64_DTPMOD64",
+    "R_X86_64_DTPOFF64",
+    "R_X86_64_TPOFF64",
+    "R_X86_64_TLSGD",
+    "R_X86_64_TLSLD",
+    "R_X86_64_DTPOFF32",
+    "R_X86_64_GOTTPOFF",
+    "R_X86_64_TPOFF32",
+    "R_X86_64_PC64",
+    "R_X86_64_GOTOFF64",
+    "R_X86_64_GOTPC32",
+    "R_X86_64_GOT64",
+    "R_X86_64_GOTPCREL64",
+    "R_X86_64_GOTPC64",
+    "R_X86_64_GOTPLT64",
+    "R_X86_64_PLTOFF64",
+    "R_X86_64_SIZE32",
+    "R_X86_64_SIZE64",
+    "R_X86_64_GOTPC32_TLSDESC",
+    "R_X86_64_TLSDESC_CALL",
+    "R_X86_64_TLSDESC",
+    "R_X86_64_IRELATIVE",
+    "R_X86_64_RELATIVE64",
+    "R_X86_64_PC32_BND",
+    "R_X86_64_PLT32_BND",
+    "R_X86_64_GOTPCRELX",
+    "R_X86_64_REX_GOTPCRELX",
+    "R_X86_64_CODE_4_GOTPCRELX",
+];
+
+fn aarch64_name(reloc_type: u32) -> Option<&'static str> {
+    let name = match reloc_type {
+        0 => "R_AARCH64_NONE",
+        257 => "R_AARCH64_ABS64",
+        258 => "R_AARCH64_ABS32",
+        259 => "R_AARCH64_ABS16",
+        260 => "R_AARCH64_PREL64",
+        261 => "R_AARCH64_PREL32",
+        262 => "R_AARCH64_PREL16",
+        263 => "R_AARCH64_MOVW_UABS_G0",
+        264 => "R_AARCH64_MOVW_UABS_G0_NC",
+        265 => "R_AARCH64_MOVW_UABS_G1",
+        266 => "R_AARCH64_MOVW_UABS_G1_NC",
+        267 => "R_AARCH64_MOVW_UABS_G2",
+        268 => "R_AARCH64_MOVW_UABS_G2_NC",
+        269 => "R_AARCH64_MOVW_UABS_G3",
+        273 => "R_AARCH64_LD_PREL_LO19",
+        274 => "R_AARCH64_ADR_PREL_LO21",
+        275 => "R_AARCH64_ADR_PREL_PG_HI21",
+        276 => "R_AARCH64_ADR_PREL_PG_HI21_NC",
+        277 => "R_AARCH64_ADD_ABS_LO12_NC",
+        278 => "R_AARCH64_LDST8_ABS_LO12_NC",
+        279 => "R_AARCH64_TSTBR14",
+        280 => "R_AARCH64_CONDBR19",
+        282 => "R_AARCH64_JUMP26",
+        283 => "R_AARCH64_CALL26",
+        284 => "R_AARCH64_LDST16_ABS_LO12_NC",
+        285 => "R_AARCH64_LDST32_ABS_LO12_NC",
+        286 => "R_AARCH64_LDST64_ABS_LO12_NC",
+        299 => "R_AARCH64_LDST128_ABS_LO12_NC",
+        311 => "R_AARCH64_ADR_GOT_PAGE",
+        312 => "R_AARCH64_LD64_GOT_LO12_NC",
+        1024 => "R_AARCH64_COPY",
+        1025 => "R_AARCH64_GLOB_DAT",
+        1026 => "R_AARCH64_JUMP_SLOT",
+        1027 => "R_AARCH64_RELATIVE",
+        1028 => "R_AARCH64_TLS_DTPMOD64",
+        1029 => "R_AARCH64_TLS_DTPREL64",
+        1030 => "R_AARCH64_TLS_TPREL64",
+        1031 => "R_AARCH64_TLSDESC",
+        1032 => "R_AARCH64_IRELATIVE",
+        _ => return None,
+    };
+
+    Some(name)
+}
+
+fn riscv_name(reloc_type: u32) -> Option<&'static str> {
+    let name = match reloc_type {
+        0 => "R_RISCV_NONE",
+        1 => "R_RISCV_32",
+        2 => "R_RISCV_64",
+        3 => "R_RISCV_RELATIVE",
+        4 => "R_RISCV_COPY",
+        5 => "R_RISCV_JUMP_SLOT",
+        6 => "R_RISCV_TLS_DTPMOD32",
+        7 => "R_RISCV_TLS_DTPMOD64",
+        8 => "R_RISCV_TLS_DTPREL32",
+        9 => "R_RISCV_TLS_DTPREL64",
+        10 => "R_RISCV_TLS_TPREL32",
+        11 => "R_RISCV_TLS_TPREL64",
+        12 => "R_RISCV_TLSDESC",
+        16 => "R_RISCV_BRANCH",
+        17 => "R_RISCV_JAL",
+        18 => "R_RISCV_CALL",
+        19 => "R_RISCV_CALL_PLT",
+        20 => "R_RISCV_GOT_HI20",
+        21 => "R_RISCV_TLS_GOT_HI20",
+        22 => "R_RISCV_TLS_GD_HI20",
+        23 => "R_RISCV_PCREL_HI20",
+        24 => "R_RISCV_PCREL_LO12_I",
+        25 => "R_RISCV_PCREL_LO12_S",
+        26 => "R_RISCV_HI20",
+        27 => "R_RISCV_LO12_I",
+        28 => "R_RISCV_LO12_S",
+        29 => "R_RISCV_TPREL_HI20",
+        30 => "R_RISCV_TPREL_LO12_I",
+        31 => "R_RISCV_TPREL_LO12_S",
+        32 => "R_RISCV_TPREL_ADD",
+        33 => "R_RISCV_ADD8",
+        34 => "R_RISCV_ADD16",
+        35 => "R_RISCV_ADD32",
+        36 => "R_RISCV_ADD64",
+        37 => "R_RISCV_SUB8",
+        38 => "R_RISCV_SUB16",
+        39 => "R_RISCV_SUB32",
+        40 => "R_RISCV_SUB64",
+        43 => "R_RISCV_ALIGN",
+        44 => "R_RISCV_RVC_BRANCH",
+        45 => "R_RISCV_RVC_JUMP",
+        51 => "R_RISCV_RELAX",
+        52 => "R_RISCV_SUB6",
+        53 => "R_RISCV_SET6",
+        54 => "R_RISCV_SET8",
+        55 => "R_RISCV_SET16",
+        56 => "R_RISCV_SET32",
+        57 => "R_RISCV_32_PCREL",
+        58 => "R_RISCV_IRELATIVE",
+        59 => "R_RISCV_PLT32",
+        60 => "R_RISCV_SET_ULEB128",
+        61 => "R_RISCV_SUB_ULEB128",
+        _ => return None,
+    };
+
+    Some(name)
+}
+
+/// Name of a relocation type, if it's one we know for the machine.
+pub fn relocation_type_name(reloc_type: u32, machine: Machine) -> Option<&'static str> {
+    match machine {
+        Machine::X86_64 => X86_64_NAMES.get(reloc_type as usize).copied(),
+        Machine::AArch64 => aarch64_name(reloc_type),
+        Machine::RiscV => riscv_name(reloc_type),
+        _ => None,
+    }
+}
+
+/// Name of a relocation type, which depends on the machine.
+pub fn relocation_type_string(reloc_type: u32, machine: Machine) -> String {
+    match relocation_type_name(reloc_type, machine) {
+        Some(name) => name.to_owned(),
+        None => format!("UNRECOGNIZED TYPE: {reloc_type:#x}"),
+    }
+}
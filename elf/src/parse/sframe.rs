@@ -0,0 +1,319 @@
+/// SFrame, the stack trace format that GNU as writes with `--gsframe`, in
+/// the .sframe section (found at run time through PT_GNU_SFRAME). It keeps
+/// only what walking the stack needs: for each stretch of a function's code,
+/// the CFA as an offset from the stack or frame pointer, and where the frame
+/// pointer and return address are saved, from the CFA.
+///
+use std::ops::Range;
+
+use crate::dwarf::reader::Reader;
+use crate::ElfError;
+
+use from_bytes_macro::FromBytes;
+
+pub const SFRAME_MAGIC: u16 = 0xdee2;
+pub const SFRAME_HEADER_LEN: usize = 28;
+
+pub const SFRAME_VERSION_1: u8 = 1;
+pub const SFRAME_VERSION_2: u8 = 2;
+
+// Header flags.
+pub const SFRAME_F_FDE_SORTED: u8 = 0x1;
+pub const SFRAME_F_FRAME_POINTER: u8 = 0x2;
+pub const SFRAME_F_FDE_FUNC_START_PCREL: u8 = 0x4;
+
+// ABIs (architectures, with their byte order).
+pub const SFRAME_ABI_AARCH64_ENDIAN_BIG: u8 = 1;
+pub const SFRAME_ABI_AARCH64_ENDIAN_LITTLE: u8 = 2;
+pub const SFRAME_ABI_AMD64_ENDIAN_LITTLE: u8 = 3;
+
+// FDE types: whether FRE start addresses are offsets in the function, or
+// in each of the blocks of code (the entries of a PLT) that it repeats for.
+pub const SFRAME_FDE_TYPE_PCINC: u8 = 0;
+pub const SFRAME_FDE_TYPE_PCMASK: u8 = 1;
+
+// FRE types, by the size of their start addresses.
+pub const SFRAME_FRE_TYPE_ADDR1: u8 = 0;
+pub const SFRAME_FRE_TYPE_ADDR2: u8 = 1;
+pub const SFRAME_FRE_TYPE_ADDR4: u8 = 2;
+
+/// The block size of version 1 PCMASK FDEs, which could only describe
+/// PLTs with 16-byte entries.
+const SFRAME_V1_REPETITION_SIZE: u8 = 16;
+
+#[derive(Debug, Clone, FromBytes)]
+pub struct SframeHeader {
+    pub magic: u16,
+    pub version: u8,
+    pub flags: u8,
+    //
+    pub abi_arch: u8,
+    /// Where the frame pointer is saved, from the CFA, for ABIs where
+    /// that's always the same (0 if FREs give it).
+    pub cfa_fixed_fp_offset: i8,
+    /// Where the return address is saved, likewise (-8 on x86-64).
+    pub cfa_fixed_ra_offset: i8,
+    pub auxiliary_header_len: u8,
+    //
+    pub fde_count: u32,
+    pub fre_count: u32,
+    pub fre_len: u32,
+    /// Where the FDEs and the FREs start, from the end of the headers.
+    pub fde_offset: u32,
+    pub fre_offset: u32,
+}
+
+/// A function descriptor entry: the code it covers, and where its FREs are.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SframeFde {
+    /// Where the entry is, in the section.
+    pub offset: usize,
+
+    pub range: Range<u64>,
+
+    /// Where its FREs start, from the start of the FREs.
+    pub fre_offset: u32,
+    pub fre_count: u32,
+
+    /// SFRAME_FRE_TYPE_*, the size of the FREs' start addresses.
+    pub fre_type: u8,
+
+    /// SFRAME_FDE_TYPE_*.
+    pub fde_type: u8,
+
+    /// The size of each block of code, for PCMASK FDEs.
+    pub repetition_size: u8,
+
+    /// Which key return addresses are signed with, on AArch64 (0 for A, 1 for B).
+    pub pauth_key: u8,
+}
+
+/// Which register the CFA is an offset from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CfaBase {
+    FramePointer,
+    StackPointer,
+}
+
+/// A frame row entry: how to find the caller's frame, from `start` until
+/// the next FRE.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SframeFre {
+    /// Where the FRE applies from, as an offset from the start of the
+    /// function (or, for PCMASK FDEs, of each block).
+    pub start: u32,
+
+    pub cfa_base: CfaBase,
+    pub cfa_offset: i32,
+
+    /// Where the return address and the frame pointer are saved, from the
+    /// CFA. The frame pointer's is None while it hasn't been saved, and the
+    /// return address's is None only in the outermost frame, whose FRE has
+    /// no offsets at all.
+    pub ra_offset: Option<i32>,
+    pub fp_offset: Option<i32>,
+
+    /// True if the return address is signed (with AArch64 pointer
+    /// authentication), and must be stripped before it's used.
+    pub mangled_ra: bool,
+}
+
+/// A .sframe section, which is loaded at `address`.
+#[derive(Debug)]
+pub struct SframeSection {
+    pub header: SframeHeader,
+    pub address: u64,
+    pub fdes: Vec<SframeFde>,
+
+    data: Vec<u8>,
+
+    /// Where the FREs start, in the section.
+    fre_start: usize,
+}
+
+/// Reads an SFrame section (versions 1 and 2, little-endian), which is
+/// loaded at `address`.
+pub fn read_sframe(data: Vec<u8>, address: u64) -> Result<SframeSection, ElfError> {
+    let mut reader = Reader::new(&data, ".sframe");
+    let header = SframeHeader::parse_from_bytes(reader.bytes(SFRAME_HEADER_LEN)?);
+
+    if header.magic != SFRAME_MAGIC {
+        return Err(reader.invalid(
+            0,
+            format!(
+                "its magic number is {:#06x}, not {SFRAME_MAGIC:#06x}",
+                header.magic
+            ),
+        ));
+    }
+
+    if !matches!(header.version, SFRAME_VERSION_1 | SFRAME_VERSION_2) {
+        return Err(ElfError::UnsupportedVersion {
+            what: ".sframe",
+            version: header.version as u16,
+        });
+    }
+
+    let headers_len = SFRAME_HEADER_LEN + header.auxiliary_header_len as usize;
+    let fde_start = headers_len + header.fde_offset as usize;
+    let fre_start = headers_len + header.fre_offset as usize;
+
+    let mut fdes = vec![];
+    reader.seek(fde_start);
+
+    for _ in 0..header.fde_count {
+        let offset = reader.offset();
+        let start = reader.u32()? as i32 as i64;
+        let size = reader.u32()?;
+        let fre_offset = reader.u32()?;
+        let fre_count = reader.u32()?;
+        let info = reader.u8()?;
+
+        // Version 2 added the repetition size, and padding after it.
+        let repetition_size = match header.version {
+            SFRAME_VERSION_1 => SFRAME_V1_REPETITION_SIZE,
+            _ => {
+                let size = reader.u8()?;
+                reader.u16()?;
+                size
+            }
+        };
+
+        // The start is from the section, or from the field itself.
+        let base = match header.flags & SFRAME_F_FDE_FUNC_START_PCREL {
+            0 => address,
+            _ => address + offset as u64,
+        };
+        let start = base.wrapping_add(start as u64);
+
+        fdes.push(SframeFde {
+            offset,
+            range: start..start.wrapping_add(size as u64),
+            fre_offset,
+            fre_count,
+            fre_type: info & 0xf,
+            fde_type: (info >> 4) & 0x1,
+            repetition_size,
+            pauth_key: (info >> 5) & 0x1,
+        });
+    }
+
+    Ok(SframeSection {
+        header,
+        address,
+        fdes,
+        data,
+        fre_start,
+    })
+}
+
+impl SframeSection {
+    /// Finds the FDE whose code includes `address`.
+    pub fn find_fde(&self, address: u64) -> Option<&SframeFde> {
+        if self.header.flags & SFRAME_F_FDE_SORTED == 0 {
+            return self.fdes.iter().find(|fde| fde.range.contains(&address));
+        }
+
+        let after = self.fdes.partition_point(|fde| fde.range.start <= address);
+
+        self.fdes
+            .get(after.checked_sub(1)?)
+            .filter(|fde| fde.range.contains(&address))
+    }
+
+    /// Reads the FREs of `fde`.
+    pub fn fres(&self, fde: &SframeFde) -> Result<Vec<SframeFre>, ElfError> {
+        let mut reader = Reader::new(&self.data, ".sframe");
+        reader.seek(self.fre_start + fde.fre_offset as usize);
+
+        let address_size = match fde.fre_type {
+            SFRAME_FRE_TYPE_ADDR1 => 1,
+            SFRAME_FRE_TYPE_ADDR2 => 2,
+            SFRAME_FRE_TYPE_ADDR4 => 4,
+            fre_type => {
+                return Err(reader.invalid(
+                    fde.offset,
+                    format!("its FRE type is {fre_type}, which isn't defined"),
+                ))
+            }
+        };
+
+        let mut fres = vec![];
+        for _ in 0..fde.fre_count {
+            let offset = reader.offset();
+            let start = reader.uint(address_size)? as u32;
+            let info = reader.u8()?;
+
+            let count = (info >> 1) & 0xf;
+            let size = match (info >> 5) & 0x3 {
+                0 => 1,
+                1 => 2,
+                2 => 4,
+                _ => return Err(reader.invalid(offset, "its offset size isn't defined".into())),
+            };
+
+            let mut offsets = vec![];
+            for _ in 0..count {
+                offsets.push(match size {
+                    1 => reader.i8()? as i32,
+                    2 => reader.u16()? as i16 as i32,
+                    _ => reader.u32()? as i32,
+                });
+            }
+
+            fres.push(self.fre(start, info, &offsets));
+        }
+
+        Ok(fres)
+    }
+
+    /// Makes an FRE from its offsets: the CFA's, then the return address's
+    /// (unless the ABI fixes it), then the frame pointer's (likewise).
+    fn fre(&self, start: u32, info: u8, offsets: &[i32]) -> SframeFre {
+        let fixed = |offset: i8| (offset != 0).then_some(offset as i32);
+        let (fixed_ra, fixed_fp) = (
+            fixed(self.header.cfa_fixed_ra_offset),
+            fixed(self.header.cfa_fixed_fp_offset),
+        );
+
+        let mut tracked = offsets.iter().skip(1).copied();
+        let ra_offset = match fixed_ra {
+            Some(_) if offsets.is_empty() => None,
+            Some(offset) => Some(offset),
+            None => tracked.next(),
+        };
+        let fp_offset = fixed_fp.or_else(|| tracked.next());
+
+        SframeFre {
+            start,
+            cfa_base: match info & 0x1 {
+                0 => CfaBase::FramePointer,
+                _ => CfaBase::StackPointer,
+            },
+            cfa_offset: offsets.first().copied().unwrap_or(0),
+            ra_offset,
+            fp_offset,
+            mangled_ra: info & 0x80 != 0,
+        }
+    }
+
+    /// Finds the FRE that applies at `address`, with its FDE.
+    pub fn find_fre(&self, address: u64) -> Result<Option<(&SframeFde, SframeFre)>, ElfError> {
+        let Some(fde) = self.find_fde(address) else {
+            return Ok(None);
+        };
+
+        let mut offset = address - fde.range.start;
+        if fde.fde_type == SFRAME_FDE_TYPE_PCMASK && fde.repetition_size != 0 {
+            offset %= fde.repetition_size as u64;
+        }
+
+        let fre = self
+            .fres(fde)?
+            .into_iter()
+            .take_while(|fre| fre.start as u64 <= offset)
+            .last();
+
+        Ok(fre.map(|fre| (fde, fre)))
+    }
+}
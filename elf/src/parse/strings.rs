@@ -0,0 +1,92 @@
+/// String tables (SHT_STRTAB sections such as .shstrtab, .strtab and .dynstr):
+/// null-terminated strings, looked up by their byte offset in the table.
+///
+use std::borrow::Cow;
+
+use crate::ElfError;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StringTable<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> StringTable<'a> {
+    pub fn new(bytes: &'a [u8]) -> StringTable<'a> {
+        StringTable { bytes }
+    }
+
+    /// The whole table, including the terminators.
+    pub fn bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// The bytes of the string at `offset`, without its terminator. Names
+    /// in ELF files are only bytes: nothing requires them to be UTF-8.
+    pub fn get_bytes(&self, offset: usize) -> Result<&'a [u8], ElfError> {
+        let invalid = || ElfError::InvalidString {
+            what: "String",
+            offset: offset as u64,
+        };
+
+        let rest = self.bytes.get(offset..).ok_or_else(invalid)?;
+        let end = rest.iter().position(|&b| b == 0).ok_or_else(invalid)?;
+
+        Ok(&rest[..end])
+    }
+
+    /// The string at `offset`, which must be UTF-8.
+    pub fn get(&self, offset: usize) -> Result<&'a str, ElfError> {
+        std::str::from_utf8(self.get_bytes(offset)?).map_err(|_| ElfError::InvalidString {
+            what: "String",
+            offset: offset as u64,
+        })
+    }
+
+    /// The string at `offset`, with any bytes that aren't UTF-8
+    /// replaced by U+FFFD, as it would be displayed.
+    pub fn get_lossy(&self, offset: usize) -> Result<Cow<'a, str>, ElfError> {
+        Ok(String::from_utf8_lossy(self.get_bytes(offset)?))
+    }
+
+    /// Iterates over the strings in the table, in order, as pairs of offset
+    /// and bytes. Bytes after the last terminator aren't a string, and are
+    /// skipped. Strings that are suffixes of others (which linkers use to
+    /// share the bytes of both) are only found by looking them up.
+    pub fn iter(&self) -> Strings<'a> {
+        Strings {
+            bytes: self.bytes,
+            offset: 0,
+        }
+    }
+}
+
+impl<'a> IntoIterator for &StringTable<'a> {
+    type Item = (usize, &'a [u8]);
+    type IntoIter = Strings<'a>;
+
+    fn into_iter(self) -> Strings<'a> {
+        self.iter()
+    }
+}
+
+pub struct Strings<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Iterator for Strings<'a> {
+    type Item = (usize, &'a [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = self.offset;
+        let rest = self.bytes.get(start..)?;
+        let end = rest.iter().position(|&b| b == 0)?;
+
+        self.offset = start + end + 1;
+        Some((start, &rest[..end]))
+    }
+}
@@ -0,0 +1,146 @@
+/// Looking up which symbol an address is in, as `main+0x1c`: by the
+/// symbols' sizes, from the symbol table and the dynamic one, and for
+/// code that no symbol covers, by the DWARF functions' address ranges.
+///
+use std::fmt;
+
+use crate::dwarf::info::Function;
+
+/// Where a name comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolSource {
+    /// .symtab or .dynsym.
+    SymbolTable,
+    /// A DW_TAG_subprogram entry in .debug_info.
+    DebugInfo,
+}
+
+/// What an address was found in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Symbolized {
+    pub name: String,
+    /// Where the symbol (or the function's range) starts.
+    pub address: u64,
+    /// How far into it the address is.
+    pub offset: u64,
+    pub source: SymbolSource,
+}
+
+impl fmt::Display for Symbolized {
+    /// Writes the name and the offset, as in `main+0x1c`, or just the name
+    /// at the start.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.offset {
+            0 => write!(f, "{}", self.name),
+            offset => write!(f, "{}+{offset:#x}", self.name),
+        }
+    }
+}
+
+/// A symbol with the addresses it covers, for `SymbolMap::new`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MapSymbol {
+    pub name: String,
+    pub start: u64,
+    /// Its size; for a symbol with no size, up to the next symbol in its
+    /// section, or the section's end.
+    pub end: u64,
+    /// Of symbols at the same address, the one with the lowest rank wins.
+    pub rank: u8,
+}
+
+/// The names of a file's addresses, indexed for looking them up.
+#[derive(Debug, Clone, Default)]
+pub struct SymbolMap {
+    /// In order of start, then rank.
+    symbols: Vec<MapSymbol>,
+    /// The furthest end of any symbol up to each one, so that a search
+    /// back through the symbols knows when to stop.
+    furthest: Vec<u64>,
+    /// The ranges of DWARF functions, in order of start, with the
+    /// function's name.
+    functions: Vec<(u64, u64, String)>,
+}
+
+impl SymbolMap {
+    /// Indexes the symbols, and the functions, which name the code that
+    /// none of the symbols cover. Functions are named by their linkage
+    /// names, as symbols are; those with no name at all are left out.
+    pub fn new(mut symbols: Vec<MapSymbol>, functions: &[Function]) -> SymbolMap {
+        symbols.retain(|symbol| symbol.start < symbol.end);
+        symbols.sort_by(|a, b| (a.start, a.rank, &a.name).cmp(&(b.start, b.rank, &b.name)));
+        symbols.dedup_by(|a, b| a.start == b.start && a.end == b.end && a.name == b.name);
+
+        let furthest = symbols
+            .iter()
+            .scan(0, |furthest, symbol| {
+                *furthest = symbol.end.max(*furthest);
+                Some(*furthest)
+            })
+            .collect();
+
+        let mut ranges = vec![];
+        for function in functions {
+            let Some(name) = function.linkage_name.as_ref().or(function.name.as_ref()) else {
+                continue;
+            };
+            for range in &function.ranges {
+                ranges.push((range.start, range.end, name.clone()));
+            }
+        }
+        ranges.sort();
+
+        SymbolMap {
+            symbols,
+            furthest,
+            functions: ranges,
+        }
+    }
+
+    /// The symbol that covers `address`: of those that do, the one that
+    /// starts closest before it. Failing that, the DWARF function whose
+    /// code it's in.
+    pub fn lookup(&self, address: u64) -> Option<Symbolized> {
+        let after = self
+            .symbols
+            .partition_point(|symbol| symbol.start <= address);
+        let mut best: Option<&MapSymbol> = None;
+        for index in (0..after).rev() {
+            if self.furthest[index] <= address {
+                break;
+            }
+            let symbol = &self.symbols[index];
+            if address >= symbol.end {
+                continue;
+            }
+            // Going back, a symbol at the same address as the best so far
+            // has a lower rank, and wins.
+            match best {
+                Some(best) if best.start != symbol.start => break,
+                _ => best = Some(symbol),
+            }
+        }
+        if let Some(symbol) = best {
+            return Some(Symbolized {
+                name: symbol.name.clone(),
+                address: symbol.start,
+                offset: address - symbol.start,
+                source: SymbolSource::SymbolTable,
+            });
+        }
+
+        let after = self
+            .functions
+            .partition_point(|(start, _, _)| *start <= address);
+        self.functions[..after]
+            .iter()
+            .rev()
+            .find(|(_, end, _)| address < *end)
+            .map(|(start, _, name)| Symbolized {
+                name: name.clone(),
+                address: *start,
+                offset: address - start,
+                source: SymbolSource::DebugInfo,
+            })
+    }
+}
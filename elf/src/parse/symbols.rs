@@ -0,0 +1,151 @@
+/// Parsing for symbol tables (SHT_SYMTAB and SHT_DYNSYM sections).
+///
+use crate::parse::strings::StringTable;
+
+use from_bytes_macro::FromBytes;
+
+pub const ELF64_SYMBOL_LEN: usize = 24;
+
+#[derive(Debug, FromBytes)]
+pub struct Elf64SymbolEntry {
+    pub name_offset: u32,
+    pub info: u8,
+    pub other: u8,
+    pub section_index: u16,
+    pub value: u64,
+    pub size: u64,
+}
+
+// Symbol types (low four bits of `info`).
+pub const STT_NOTYPE: u8 = 0;
+pub const STT_OBJECT: u8 = 1;
+pub const STT_FUNC: u8 = 2;
+pub const STT_SECTION: u8 = 3;
+pub const STT_FILE: u8 = 4;
+pub const STT_COMMON: u8 = 5;
+pub const STT_TLS: u8 = 6;
+pub const STT_GNU_IFUNC: u8 = 10;
+
+// Symbol bindings (high four bits of `info`).
+pub const STB_LOCAL: u8 = 0;
+pub const STB_GLOBAL: u8 = 1;
+pub const STB_WEAK: u8 = 2;
+pub const STB_GNU_UNIQUE: u8 = 10;
+
+// Symbol visibilities (low two bits of `other`).
+pub const STV_DEFAULT: u8 = 0;
+pub const STV_INTERNAL: u8 = 1;
+pub const STV_HIDDEN: u8 = 2;
+pub const STV_PROTECTED: u8 = 3;
+
+// Special section indices.
+pub const SHN_UNDEF: u16 = 0;
+pub const SHN_LORESERVE: u16 = 0xff00;
+pub const SHN_ABS: u16 = 0xfff1;
+pub const SHN_COMMON: u16 = 0xfff2;
+pub const SHN_XINDEX: u16 = 0xffff;
+
+impl Elf64SymbolEntry {
+    pub fn symbol_type(&self) -> u8 {
+        self.info & 0xf
+    }
+
+    pub fn bind(&self) -> u8 {
+        self.info >> 4
+    }
+
+    pub fn visibility(&self) -> u8 {
+        self.other & 0x3
+    }
+}
+
+#[derive(Debug)]
+pub struct Elf64SymbolInfo {
+    // Raw data from file.
+    pub symbol_data: Elf64SymbolEntry,
+
+    // Structured, extracted data.
+    pub name: String,
+}
+
+/// Whether a symbol marks where code or data starts (`$x` and `$d` on
+/// AArch64, for example) rather than naming anything.
+pub fn is_mapping_symbol(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next() == Some('$')
+        && matches!(chars.next(), Some('a' | 'd' | 't' | 'x'))
+        && matches!(chars.next(), None | Some('.'))
+}
+
+/// Reads the entries of a symbol table, given the bytes of the table
+/// and of its linked string table.
+pub fn read_symbols_64(table: &[u8], string_table: &StringTable) -> Vec<Elf64SymbolInfo> {
+    table
+        .chunks_exact(ELF64_SYMBOL_LEN)
+        .map(|bytes| {
+            let symbol = Elf64SymbolEntry::parse_from_bytes(bytes);
+            let name = string_table
+                .get_lossy(symbol.name_offset as usize)
+                .unwrap_or_default()
+                .into_owned();
+
+            Elf64SymbolInfo {
+                symbol_data: symbol,
+                name,
+            }
+        })
+        .collect()
+}
+
+pub fn symbol_type_string(symbol_type: u8) -> String {
+    let str_val = match symbol_type {
+        STT_NOTYPE => "STT_NOTYPE",
+        STT_OBJECT => "STT_OBJECT",
+        STT_FUNC => "STT_FUNC",
+        STT_SECTION => "STT_SECTION",
+        STT_FILE => "STT_FILE",
+        STT_COMMON => "STT_COMMON",
+        STT_TLS => "STT_TLS",
+        STT_GNU_IFUNC => "STT_GNU_IFUNC",
+
+        _ => &format!("UNRECOGNIZED TYPE: {symbol_type}"),
+    };
+
+    str_val.to_owned()
+}
+
+pub fn symbol_bind_string(bind: u8) -> String {
+    let str_val = match bind {
+        STB_LOCAL => "STB_LOCAL",
+        STB_GLOBAL => "STB_GLOBAL",
+        STB_WEAK => "STB_WEAK",
+        STB_GNU_UNIQUE => "STB_GNU_UNIQUE",
+
+        _ => &format!("UNRECOGNIZED BINDING: {bind}"),
+    };
+
+    str_val.to_owned()
+}
+
+pub fn symbol_visibility_string(visibility: u8) -> String {
+    let str_val = match visibility {
+        STV_DEFAULT => "STV_DEFAULT",
+        STV_INTERNAL => "STV_INTERNAL",
+        STV_HIDDEN => "STV_HIDDEN",
+        _ => "STV_PROTECTED",
+    };
+
+    str_val.to_owned()
+}
+
+/// Describes a symbol's section index, which may be one of the special values.
+pub fn symbol_section_string(section_index: u16) -> String {
+    match section_index {
+        SHN_UNDEF => "UND".to_owned(),
+        SHN_ABS => "ABS".to_owned(),
+        SHN_COMMON => "COM".to_owned(),
+        SHN_XINDEX => "XINDEX".to_owned(),
+        i if i >= SHN_LORESERVE => format!("RSV[{i:#x}]"),
+        i => i.to_string(),
+    }
+}
@@ -0,0 +1,341 @@
+/// Names for program header (segment) and section header types.
+///
+/// Types in the OS-specific ranges are named from the GNU and other common
+/// extensions; types in the processor-specific range depend on `e_machine`.
+///
+use super::header::{EM_AARCH64, EM_ARM, EM_MIPS, EM_RISCV, EM_X86_64};
+
+// ---------------------
+// Program header types.
+
+pub const PT_NULL: u32 = 0;
+pub const PT_LOAD: u32 = 1;
+pub const PT_DYNAMIC: u32 = 2;
+pub const PT_INTERP: u32 = 3;
+pub const PT_NOTE: u32 = 4;
+pub const PT_SHLIB: u32 = 5;
+pub const PT_PHDR: u32 = 6;
+pub const PT_TLS: u32 = 7;
+
+pub const PT_LOOS: u32 = 0x60000000;
+pub const PT_HIOS: u32 = 0x6fffffff;
+pub const PT_LOPROC: u32 = 0x70000000;
+pub const PT_HIPROC: u32 = 0x7fffffff;
+
+pub const PT_GNU_EH_FRAME: u32 = 0x6474e550;
+pub const PT_GNU_STACK: u32 = 0x6474e551;
+pub const PT_GNU_RELRO: u32 = 0x6474e552;
+pub const PT_GNU_PROPERTY: u32 = 0x6474e553;
+pub const PT_GNU_SFRAME: u32 = 0x6474e554;
+pub const PT_SUNW_UNWIND: u32 = 0x6464e550;
+pub const PT_OPENBSD_MUTABLE: u32 = 0x65a3dbe5;
+pub const PT_OPENBSD_RANDOMIZE: u32 = 0x65a3dbe6;
+pub const PT_OPENBSD_WXNEEDED: u32 = 0x65a3dbe7;
+pub const PT_OPENBSD_NOBTCFI: u32 = 0x65a3dbe8;
+pub const PT_OPENBSD_SYSCALLS: u32 = 0x65a3dbe9;
+pub const PT_OPENBSD_BOOTDATA: u32 = 0x65a41be6;
+
+pub const PT_ARM_EXIDX: u32 = 0x70000001;
+
+pub const PT_AARCH64_ARCHEXT: u32 = 0x70000000;
+pub const PT_AARCH64_MEMTAG_MTE: u32 = 0x70000002;
+
+pub const PT_MIPS_REGINFO: u32 = 0x70000000;
+pub const PT_MIPS_RTPROC: u32 = 0x70000001;
+pub const PT_MIPS_OPTIONS: u32 = 0x70000002;
+pub const PT_MIPS_ABIFLAGS: u32 = 0x70000003;
+
+pub const PT_RISCV_ATTRIBUTES: u32 = 0x70000003;
+
+pub fn program_header_type_string(buffer: &[u8; 4], machine: u16) -> String {
+    let segment_type = u32::from_le_bytes(*buffer);
+
+    let str_val = match segment_type {
+        PT_NULL => "PT_NULL",
+        PT_LOAD => "PT_LOAD",
+        PT_DYNAMIC => "PT_DYNAMIC",
+        PT_INTERP => "PT_INTERP",
+        PT_NOTE => "PT_NOTE",
+        PT_SHLIB => "PT_SHLIB",
+        PT_PHDR => "PT_PHDR",
+        PT_TLS => "PT_TLS",
+
+        PT_GNU_EH_FRAME => "PT_GNU_EH_FRAME",
+        PT_GNU_STACK => "PT_GNU_STACK",
+        PT_GNU_RELRO => "PT_GNU_RELRO",
+        PT_GNU_PROPERTY => "PT_GNU_PROPERTY",
+        PT_GNU_SFRAME => "PT_GNU_SFRAME",
+        PT_SUNW_UNWIND => "PT_SUNW_UNWIND",
+        PT_OPENBSD_MUTABLE => "PT_OPENBSD_MUTABLE",
+        PT_OPENBSD_RANDOMIZE => "PT_OPENBSD_RANDOMIZE",
+        PT_OPENBSD_WXNEEDED => "PT_OPENBSD_WXNEEDED",
+        PT_OPENBSD_NOBTCFI => "PT_OPENBSD_NOBTCFI",
+        PT_OPENBSD_SYSCALLS => "PT_OPENBSD_SYSCALLS",
+        PT_OPENBSD_BOOTDATA => "PT_OPENBSD_BOOTDATA",
+
+        PT_LOPROC..=PT_HIPROC => match processor_program_header_type(segment_type, machine) {
+            Some(name) => name,
+            None => &format!("PROCESSOR_SPECIFIC: {segment_type:#010x}"),
+        },
+
+        PT_LOOS..=PT_HIOS => &format!("OS_SPECIFIC: {segment_type:#010x}"),
+
+        _ => &format!("OTHER: {segment_type:#010x}"),
+    };
+
+    str_val.to_owned()
+}
+
+fn processor_program_header_type(segment_type: u32, machine: u16) -> Option<&'static str> {
+    let name = match (machine, segment_type) {
+        (EM_ARM, PT_ARM_EXIDX) => "PT_ARM_EXIDX",
+
+        (EM_AARCH64, PT_AARCH64_ARCHEXT) => "PT_AARCH64_ARCHEXT",
+        (EM_AARCH64, PT_AARCH64_MEMTAG_MTE) => "PT_AARCH64_MEMTAG_MTE",
+
+        (EM_MIPS, PT_MIPS_REGINFO) => "PT_MIPS_REGINFO",
+        (EM_MIPS, PT_MIPS_RTPROC) => "PT_MIPS_RTPROC",
+        (EM_MIPS, PT_MIPS_OPTIONS) => "PT_MIPS_OPTIONS",
+        (EM_MIPS, PT_MIPS_ABIFLAGS) => "PT_MIPS_ABIFLAGS",
+
+        (EM_RISCV, PT_RISCV_ATTRIBUTES) => "PT_RISCV_ATTRIBUTES",
+
+        _ => return None,
+    };
+
+    Some(name)
+}
+
+// ---------------------
+// Section header types.
+
+pub const SHT_NULL: u32 = 0;
+pub const SHT_PROGBITS: u32 = 1;
+pub const SHT_SYMTAB: u32 = 2;
+pub const SHT_STRTAB: u32 = 3;
+pub const SHT_RELA: u32 = 4;
+pub const SHT_HASH: u32 = 5;
+pub const SHT_DYNAMIC: u32 = 6;
+pub const SHT_NOTE: u32 = 7;
+pub const SHT_NOBITS: u32 = 8;
+pub const SHT_REL: u32 = 9;
+pub const SHT_SHLIB: u32 = 10;
+pub const SHT_DYNSYM: u32 = 11;
+pub const SHT_INIT_ARRAY: u32 = 14;
+pub const SHT_FINI_ARRAY: u32 = 15;
+pub const SHT_PREINIT_ARRAY: u32 = 16;
+pub const SHT_GROUP: u32 = 17;
+pub const SHT_SYMTAB_SHNDX: u32 = 18;
+pub const SHT_RELR: u32 = 19;
+
+pub const SHT_LOOS: u32 = 0x60000000;
+pub const SHT_HIOS: u32 = 0x6fffffff;
+pub const SHT_LOPROC: u32 = 0x70000000;
+pub const SHT_HIPROC: u32 = 0x7fffffff;
+pub const SHT_LOUSER: u32 = 0x80000000;
+pub const SHT_HIUSER: u32 = 0xffffffff;
+
+pub const SHT_ANDROID_REL: u32 = 0x60000001;
+pub const SHT_ANDROID_RELA: u32 = 0x60000002;
+pub const SHT_LLVM_ODRTAB: u32 = 0x6fff4c00;
+pub const SHT_LLVM_LINKER_OPTIONS: u32 = 0x6fff4c01;
+pub const SHT_LLVM_ADDRSIG: u32 = 0x6fff4c03;
+pub const SHT_LLVM_DEPENDENT_LIBRARIES: u32 = 0x6fff4c04;
+pub const SHT_LLVM_SYMPART: u32 = 0x6fff4c05;
+pub const SHT_LLVM_PART_EHDR: u32 = 0x6fff4c06;
+pub const SHT_LLVM_PART_PHDR: u32 = 0x6fff4c07;
+pub const SHT_LLVM_BB_ADDR_MAP: u32 = 0x6fff4c0a;
+pub const SHT_GNU_INCREMENTAL_INPUTS: u32 = 0x6fff4700;
+pub const SHT_GNU_SFRAME: u32 = 0x6ffffff4;
+pub const SHT_GNU_ATTRIBUTES: u32 = 0x6ffffff5;
+pub const SHT_GNU_HASH: u32 = 0x6ffffff6;
+pub const SHT_GNU_LIBLIST: u32 = 0x6ffffff7;
+pub const SHT_CHECKSUM: u32 = 0x6ffffff8;
+pub const SHT_SUNW_MOVE: u32 = 0x6ffffffa;
+pub const SHT_SUNW_COMDAT: u32 = 0x6ffffffb;
+pub const SHT_SUNW_SYMINFO: u32 = 0x6ffffffc;
+pub const SHT_GNU_VERDEF: u32 = 0x6ffffffd;
+pub const SHT_GNU_VERNEED: u32 = 0x6ffffffe;
+pub const SHT_GNU_VERSYM: u32 = 0x6fffffff;
+
+pub const SHT_ARM_EXIDX: u32 = 0x70000001;
+pub const SHT_ARM_PREEMPTMAP: u32 = 0x70000002;
+pub const SHT_ARM_ATTRIBUTES: u32 = 0x70000003;
+pub const SHT_ARM_DEBUGOVERLAY: u32 = 0x70000004;
+pub const SHT_ARM_OVERLAYSECTION: u32 = 0x70000005;
+
+pub const SHT_AARCH64_ATTRIBUTES: u32 = 0x70000003;
+pub const SHT_AARCH64_AUTH_RELR: u32 = 0x70000004;
+pub const SHT_AARCH64_MEMTAG_GLOBALS_STATIC: u32 = 0x70000007;
+pub const SHT_AARCH64_MEMTAG_GLOBALS_DYNAMIC: u32 = 0x70000008;
+
+pub const SHT_MIPS_LIBLIST: u32 = 0x70000000;
+pub const SHT_MIPS_MSYM: u32 = 0x70000001;
+pub const SHT_MIPS_CONFLICT: u32 = 0x70000002;
+pub const SHT_MIPS_GPTAB: u32 = 0x70000003;
+pub const SHT_MIPS_UCODE: u32 = 0x70000004;
+pub const SHT_MIPS_DEBUG: u32 = 0x70000005;
+pub const SHT_MIPS_REGINFO: u32 = 0x70000006;
+pub const SHT_MIPS_PACKAGE: u32 = 0x70000007;
+pub const SHT_MIPS_PACKSYM: u32 = 0x70000008;
+pub const SHT_MIPS_RELD: u32 = 0x70000009;
+pub const SHT_MIPS_IFACE: u32 = 0x7000000b;
+pub const SHT_MIPS_CONTENT: u32 = 0x7000000c;
+pub const SHT_MIPS_OPTIONS: u32 = 0x7000000d;
+pub const SHT_MIPS_SHDR: u32 = 0x70000010;
+pub const SHT_MIPS_FDESC: u32 = 0x70000011;
+pub const SHT_MIPS_EXTSYM: u32 = 0x70000012;
+pub const SHT_MIPS_DENSE: u32 = 0x70000013;
+pub const SHT_MIPS_PDESC: u32 = 0x70000014;
+pub const SHT_MIPS_LOCSYM: u32 = 0x70000015;
+pub const SHT_MIPS_AUXSYM: u32 = 0x70000016;
+pub const SHT_MIPS_OPTSYM: u32 = 0x70000017;
+pub const SHT_MIPS_LOCSTR: u32 = 0x70000018;
+pub const SHT_MIPS_LINE: u32 = 0x70000019;
+pub const SHT_MIPS_RFDESC: u32 = 0x7000001a;
+pub const SHT_MIPS_DELTASYM: u32 = 0x7000001b;
+pub const SHT_MIPS_DELTAINST: u32 = 0x7000001c;
+pub const SHT_MIPS_DELTACLASS: u32 = 0x7000001d;
+pub const SHT_MIPS_DWARF: u32 = 0x7000001e;
+pub const SHT_MIPS_DELTADECL: u32 = 0x7000001f;
+pub const SHT_MIPS_SYMBOL_LIB: u32 = 0x70000020;
+pub const SHT_MIPS_EVENTS: u32 = 0x70000021;
+pub const SHT_MIPS_TRANSLATE: u32 = 0x70000022;
+pub const SHT_MIPS_PIXIE: u32 = 0x70000023;
+pub const SHT_MIPS_XLATE: u32 = 0x70000024;
+pub const SHT_MIPS_XLATE_DEBUG: u32 = 0x70000025;
+pub const SHT_MIPS_WHIRL: u32 = 0x70000026;
+pub const SHT_MIPS_EH_REGION: u32 = 0x70000027;
+pub const SHT_MIPS_XLATE_OLD: u32 = 0x70000028;
+pub const SHT_MIPS_PDR_EXCEPTION: u32 = 0x70000029;
+pub const SHT_MIPS_ABIFLAGS: u32 = 0x7000002a;
+pub const SHT_MIPS_XHASH: u32 = 0x7000002b;
+
+pub const SHT_RISCV_ATTRIBUTES: u32 = 0x70000003;
+
+pub const SHT_X86_64_UNWIND: u32 = 0x70000001;
+
+pub fn section_header_type_string(buffer: &[u8; 4], machine: u16) -> String {
+    let section_type = u32::from_le_bytes(*buffer);
+
+    let str_val = match section_type {
+        SHT_NULL => "SHT_NULL",
+        SHT_PROGBITS => "SHT_PROGBITS",
+        SHT_SYMTAB => "SHT_SYMTAB",
+        SHT_STRTAB => "SHT_STRTAB",
+        SHT_RELA => "SHT_RELA",
+        SHT_HASH => "SHT_HASH",
+        SHT_DYNAMIC => "SHT_DYNAMIC",
+        SHT_NOTE => "SHT_NOTE",
+        SHT_NOBITS => "SHT_NOBITS",
+        SHT_REL => "SHT_REL",
+        SHT_SHLIB => "SHT_SHLIB",
+        SHT_DYNSYM => "SHT_DYNSYM",
+        SHT_INIT_ARRAY => "SHT_INIT_ARRAY",
+        SHT_FINI_ARRAY => "SHT_FINI_ARRAY",
+        SHT_PREINIT_ARRAY => "SHT_PREINIT_ARRAY",
+        SHT_GROUP => "SHT_GROUP",
+        SHT_SYMTAB_SHNDX => "SHT_SYMTAB_SHNDX",
+        SHT_RELR => "SHT_RELR",
+
+        SHT_ANDROID_REL => "SHT_ANDROID_REL",
+        SHT_ANDROID_RELA => "SHT_ANDROID_RELA",
+        SHT_LLVM_ODRTAB => "SHT_LLVM_ODRTAB",
+        SHT_LLVM_LINKER_OPTIONS => "SHT_LLVM_LINKER_OPTIONS",
+        SHT_LLVM_ADDRSIG => "SHT_LLVM_ADDRSIG",
+        SHT_LLVM_DEPENDENT_LIBRARIES => "SHT_LLVM_DEPENDENT_LIBRARIES",
+        SHT_LLVM_SYMPART => "SHT_LLVM_SYMPART",
+        SHT_LLVM_PART_EHDR => "SHT_LLVM_PART_EHDR",
+        SHT_LLVM_PART_PHDR => "SHT_LLVM_PART_PHDR",
+        SHT_LLVM_BB_ADDR_MAP => "SHT_LLVM_BB_ADDR_MAP",
+        SHT_GNU_INCREMENTAL_INPUTS => "SHT_GNU_INCREMENTAL_INPUTS",
+        SHT_GNU_SFRAME => "SHT_GNU_SFRAME",
+        SHT_GNU_ATTRIBUTES => "SHT_GNU_ATTRIBUTES",
+        SHT_GNU_HASH => "SHT_GNU_HASH",
+        SHT_GNU_LIBLIST => "SHT_GNU_LIBLIST",
+        SHT_CHECKSUM => "SHT_CHECKSUM",
+        SHT_SUNW_MOVE => "SHT_SUNW_move",
+        SHT_SUNW_COMDAT => "SHT_SUNW_COMDAT",
+        SHT_SUNW_SYMINFO => "SHT_SUNW_syminfo",
+        SHT_GNU_VERDEF => "SHT_GNU_verdef",
+        SHT_GNU_VERNEED => "SHT_GNU_verneed",
+        SHT_GNU_VERSYM => "SHT_GNU_versym",
+
+        SHT_LOPROC..=SHT_HIPROC => match processor_section_header_type(section_type, machine) {
+            Some(name) => name,
+            None => &format!("PROCESSOR_SPECIFIC: {section_type:#010x}"),
+        },
+
+        SHT_LOOS..=SHT_HIOS => &format!("OS_SPECIFIC: {section_type:#010x}"),
+        SHT_LOUSER..=SHT_HIUSER => &format!("USER_SPECIFIC: {section_type:#010x}"),
+
+        _ => &format!("UNRECOGNIZED TYPE: {section_type:#010x}"),
+    };
+
+    str_val.to_owned()
+}
+
+fn processor_section_header_type(section_type: u32, machine: u16) -> Option<&'static str> {
+    let name = match (machine, section_type) {
+        (EM_ARM, SHT_ARM_EXIDX) => "SHT_ARM_EXIDX",
+        (EM_ARM, SHT_ARM_PREEMPTMAP) => "SHT_ARM_PREEMPTMAP",
+        (EM_ARM, SHT_ARM_ATTRIBUTES) => "SHT_ARM_ATTRIBUTES",
+        (EM_ARM, SHT_ARM_DEBUGOVERLAY) => "SHT_ARM_DEBUGOVERLAY",
+        (EM_ARM, SHT_ARM_OVERLAYSECTION) => "SHT_ARM_OVERLAYSECTION",
+
+        (EM_AARCH64, SHT_AARCH64_ATTRIBUTES) => "SHT_AARCH64_ATTRIBUTES",
+        (EM_AARCH64, SHT_AARCH64_AUTH_RELR) => "SHT_AARCH64_AUTH_RELR",
+        (EM_AARCH64, SHT_AARCH64_MEMTAG_GLOBALS_STATIC) => "SHT_AARCH64_MEMTAG_GLOBALS_STATIC",
+        (EM_AARCH64, SHT_AARCH64_MEMTAG_GLOBALS_DYNAMIC) => "SHT_AARCH64_MEMTAG_GLOBALS_DYNAMIC",
+
+        (EM_MIPS, SHT_MIPS_LIBLIST) => "SHT_MIPS_LIBLIST",
+        (EM_MIPS, SHT_MIPS_MSYM) => "SHT_MIPS_MSYM",
+        (EM_MIPS, SHT_MIPS_CONFLICT) => "SHT_MIPS_CONFLICT",
+        (EM_MIPS, SHT_MIPS_GPTAB) => "SHT_MIPS_GPTAB",
+        (EM_MIPS, SHT_MIPS_UCODE) => "SHT_MIPS_UCODE",
+        (EM_MIPS, SHT_MIPS_DEBUG) => "SHT_MIPS_DEBUG",
+        (EM_MIPS, SHT_MIPS_REGINFO) => "SHT_MIPS_REGINFO",
+        (EM_MIPS, SHT_MIPS_PACKAGE) => "SHT_MIPS_PACKAGE",
+        (EM_MIPS, SHT_MIPS_PACKSYM) => "SHT_MIPS_PACKSYM",
+        (EM_MIPS, SHT_MIPS_RELD) => "SHT_MIPS_RELD",
+        (EM_MIPS, SHT_MIPS_IFACE) => "SHT_MIPS_IFACE",
+        (EM_MIPS, SHT_MIPS_CONTENT) => "SHT_MIPS_CONTENT",
+        (EM_MIPS, SHT_MIPS_OPTIONS) => "SHT_MIPS_OPTIONS",
+        (EM_MIPS, SHT_MIPS_SHDR) => "SHT_MIPS_SHDR",
+        (EM_MIPS, SHT_MIPS_FDESC) => "SHT_MIPS_FDESC",
+        (EM_MIPS, SHT_MIPS_EXTSYM) => "SHT_MIPS_EXTSYM",
+        (EM_MIPS, SHT_MIPS_DENSE) => "SHT_MIPS_DENSE",
+        (EM_MIPS, SHT_MIPS_PDESC) => "SHT_MIPS_PDESC",
+        (EM_MIPS, SHT_MIPS_LOCSYM) => "SHT_MIPS_LOCSYM",
+        (EM_MIPS, SHT_MIPS_AUXSYM) => "SHT_MIPS_AUXSYM",
+        (EM_MIPS, SHT_MIPS_OPTSYM) => "SHT_MIPS_OPTSYM",
+        (EM_MIPS, SHT_MIPS_LOCSTR) => "SHT_MIPS_LOCSTR",
+        (EM_MIPS, SHT_MIPS_LINE) => "SHT_MIPS_LINE",
+        (EM_MIPS, SHT_MIPS_RFDESC) => "SHT_MIPS_RFDESC",
+        (EM_MIPS, SHT_MIPS_DELTASYM) => "SHT_MIPS_DELTASYM",
+        (EM_MIPS, SHT_MIPS_DELTAINST) => "SHT_MIPS_DELTAINST",
+        (EM_MIPS, SHT_MIPS_DELTACLASS) => "SHT_MIPS_DELTACLASS",
+        (EM_MIPS, SHT_MIPS_DWARF) => "SHT_MIPS_DWARF",
+        (EM_MIPS, SHT_MIPS_DELTADECL) => "SHT_MIPS_DELTADECL",
+        (EM_MIPS, SHT_MIPS_SYMBOL_LIB) => "SHT_MIPS_SYMBOL_LIB",
+        (EM_MIPS, SHT_MIPS_EVENTS) => "SHT_MIPS_EVENTS",
+        (EM_MIPS, SHT_MIPS_TRANSLATE) => "SHT_MIPS_TRANSLATE",
+        (EM_MIPS, SHT_MIPS_PIXIE) => "SHT_MIPS_PIXIE",
+        (EM_MIPS, SHT_MIPS_XLATE) => "SHT_MIPS_XLATE",
+        (EM_MIPS, SHT_MIPS_XLATE_DEBUG) => "SHT_MIPS_XLATE_DEBUG",
+        (EM_MIPS, SHT_MIPS_WHIRL) => "SHT_MIPS_WHIRL",
+        (EM_MIPS, SHT_MIPS_EH_REGION) => "SHT_MIPS_EH_REGION",
+        (EM_MIPS, SHT_MIPS_XLATE_OLD) => "SHT_MIPS_XLATE_OLD",
+        (EM_MIPS, SHT_MIPS_PDR_EXCEPTION) => "SHT_MIPS_PDR_EXCEPTION",
+        (EM_MIPS, SHT_MIPS_ABIFLAGS) => "SHT_MIPS_ABIFLAGS",
+        (EM_MIPS, SHT_MIPS_XHASH) => "SHT_MIPS_XHASH",
+
+        (EM_RISCV, SHT_RISCV_ATTRIBUTES) => "SHT_RISCV_ATTRIBUTES",
+
+        (EM_X86_64, SHT_X86_64_UNWIND) => "SHT_X86_64_UNWIND",
+
+        _ => return None,
+    };
+
+    Some(name)
+}
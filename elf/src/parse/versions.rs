@@ -0,0 +1,426 @@
+/// Parsing for GNU symbol versioning: the version of each dynamic symbol
+/// (SHT_GNU_VERSYM), the versions needed from other files (SHT_GNU_VERNEED),
+/// and the versions this file defines (SHT_GNU_VERDEF).
+///
+use std::cmp::Ordering;
+
+use crate::parse::strings::StringTable;
+use crate::parse::symbols::*;
+use crate::parse::utils::*;
+use crate::ElfError;
+
+use from_bytes_macro::FromBytes;
+
+pub const ELF64_VERNEED_LEN: usize = 16;
+pub const ELF64_VERNAUX_LEN: usize = 16;
+pub const ELF64_VERDEF_LEN: usize = 20;
+pub const ELF64_VERDAUX_LEN: usize = 8;
+
+// Parts of a versym entry.
+pub const VERSYM_HIDDEN: u16 = 0x8000;
+pub const VERSYM_VERSION: u16 = 0x7fff;
+
+// Reserved version indices.
+pub const VER_NDX_LOCAL: u16 = 0;
+pub const VER_NDX_GLOBAL: u16 = 1;
+
+// Version flags.
+pub const VER_FLG_BASE: u16 = 0x1;
+pub const VER_FLG_WEAK: u16 = 0x2;
+pub const VER_FLG_INFO: u16 = 0x4;
+
+#[derive(Debug, FromBytes)]
+pub struct Elf64VerneedEntry {
+    pub version: u16,
+    pub count: u16,
+    pub file_offset: u32,
+    pub aux_offset: u32,
+    pub next_offset: u32,
+}
+
+#[derive(Debug, FromBytes)]
+pub struct Elf64VernauxEntry {
+    pub hash: u32,
+    pub flags: u16,
+    pub other: u16,
+    pub name_offset: u32,
+    pub next_offset: u32,
+}
+
+#[derive(Debug, FromBytes)]
+pub struct Elf64VerdefEntry {
+    pub version: u16,
+    pub flags: u16,
+    pub index: u16,
+    pub count: u16,
+    pub hash: u32,
+    pub aux_offset: u32,
+    pub next_offset: u32,
+}
+
+#[derive(Debug, FromBytes)]
+pub struct Elf64VerdauxEntry {
+    pub name_offset: u32,
+    pub next_offset: u32,
+}
+
+// ------------------------------
+// Needed and defined versions.
+
+/// A file named in SHT_GNU_VERNEED, with the versions needed from it.
+#[derive(Debug)]
+pub struct VersionNeed {
+    pub file: String,
+    pub versions: Vec<NeededVersion>,
+
+    /// The revision of the structure (always 1).
+    pub version: u16,
+
+    /// Where the entry is, in the section.
+    pub offset: usize,
+}
+
+#[derive(Debug)]
+pub struct NeededVersion {
+    pub name: String,
+    pub name_offset: u32,
+    pub hash: u32,
+    pub flags: u16,
+
+    /// The versym value that refers to this version.
+    pub index: u16,
+
+    /// Where the auxiliary entry is, in the section.
+    pub offset: usize,
+}
+
+/// A version from SHT_GNU_VERDEF. The first name is the version's own,
+/// and any others are the versions it inherits from.
+#[derive(Debug)]
+pub struct VersionDef {
+    pub flags: u16,
+    pub index: u16,
+    pub hash: u32,
+    pub names: Vec<String>,
+    pub name_offset: u32,
+
+    /// The revision of the structure (always 1).
+    pub version: u16,
+
+    /// Where the entry, and the auxiliary entry of each name, are in the section.
+    pub offset: usize,
+    pub name_entry_offsets: Vec<usize>,
+}
+
+/// Reads the versym table: one entry for each symbol in the dynamic symbol table.
+pub fn read_versym_64(bytes: &[u8]) -> Vec<u16> {
+    bytes
+        .chunks_exact(2)
+        .map(|chunk| from_le_bytes!(u16, chunk, 0))
+        .collect()
+}
+
+/// Returns the bytes of `len` starting at `offset`, or an error naming `what`.
+fn checked_range<'a>(
+    bytes: &'a [u8],
+    offset: usize,
+    len: usize,
+    what: &'static str,
+) -> Result<&'a [u8], ElfError> {
+    offset
+        .checked_add(len)
+        .and_then(|end| bytes.get(offset..end))
+        .ok_or(ElfError::Truncated {
+            what,
+            offset: offset as u64,
+            needed: len as u64,
+        })
+}
+
+/// Reads the `count` entries of a SHT_GNU_VERNEED section (`count` is the
+/// section's `info` field), with names from its linked string table.
+pub fn read_verneed_64(
+    bytes: &[u8],
+    count: usize,
+    string_table: &StringTable,
+) -> Result<Vec<VersionNeed>, ElfError> {
+    let mut needs = vec![];
+    let mut offset = 0;
+
+    for _ in 0..count {
+        let entry = Elf64VerneedEntry::parse_from_bytes(checked_range(
+            bytes,
+            offset,
+            ELF64_VERNEED_LEN,
+            "Version need",
+        )?);
+
+        let mut versions = vec![];
+        let mut aux_offset = offset + entry.aux_offset as usize;
+
+        for _ in 0..entry.count {
+            let aux = Elf64VernauxEntry::parse_from_bytes(checked_range(
+                bytes,
+                aux_offset,
+                ELF64_VERNAUX_LEN,
+                "Version need auxiliary entry",
+            )?);
+
+            versions.push(NeededVersion {
+                name: string_table
+                    .get_lossy(aux.name_offset as usize)
+                    .unwrap_or_default()
+                    .into_owned(),
+                name_offset: aux.name_offset,
+                hash: aux.hash,
+                flags: aux.flags,
+                index: aux.other,
+                offset: aux_offset,
+            });
+
+            if aux.next_offset == 0 {
+                break;
+            }
+            aux_offset += aux.next_offset as usize;
+        }
+
+        needs.push(VersionNeed {
+            file: string_table
+                .get_lossy(entry.file_offset as usize)
+                .unwrap_or_default()
+                .into_owned(),
+            versions,
+            version: entry.version,
+            offset,
+        });
+
+        if entry.next_offset == 0 {
+            break;
+        }
+        offset += entry.next_offset as usize;
+    }
+
+    Ok(needs)
+}
+
+/// Reads the `count` entries of a SHT_GNU_VERDEF section (`count` is the
+/// section's `info` field), with names from its linked string table.
+pub fn read_verdef_64(
+    bytes: &[u8],
+    count: usize,
+    string_table: &StringTable,
+) -> Result<Vec<VersionDef>, ElfError> {
+    let mut defs = vec![];
+    let mut offset = 0;
+
+    for _ in 0..count {
+        let entry = Elf64VerdefEntry::parse_from_bytes(checked_range(
+            bytes,
+            offset,
+            ELF64_VERDEF_LEN,
+            "Version definition",
+        )?);
+
+        let mut names = vec![];
+        let mut name_offset = 0;
+        let mut name_entry_offsets = vec![];
+        let mut aux_offset = offset + entry.aux_offset as usize;
+
+        for i in 0..entry.count {
+            let aux = Elf64VerdauxEntry::parse_from_bytes(checked_range(
+                bytes,
+                aux_offset,
+                ELF64_VERDAUX_LEN,
+                "Version definition auxiliary entry",
+            )?);
+
+            if i == 0 {
+                name_offset = aux.name_offset;
+            }
+            name_entry_offsets.push(aux_offset);
+            names.push(
+                string_table
+                    .get_lossy(aux.name_offset as usize)
+                    .unwrap_or_default()
+                    .into_owned(),
+            );
+
+            if aux.next_offset == 0 {
+                break;
+            }
+            aux_offset += aux.next_offset as usize;
+        }
+
+        defs.push(VersionDef {
+            flags: entry.flags,
+            index: entry.index,
+            hash: entry.hash,
+            names,
+            name_offset,
+            version: entry.version,
+            offset,
+            name_entry_offsets,
+        });
+
+        if entry.next_offset == 0 {
+            break;
+        }
+        offset += entry.next_offset as usize;
+    }
+
+    Ok(defs)
+}
+
+// ---------------------
+// Versions of symbols.
+
+/// The versioning sections of a file, read together so that
+/// each dynamic symbol's version can be looked up.
+#[derive(Debug, Default)]
+pub struct SymbolVersions {
+    pub versym: Vec<u16>,
+    pub needs: Vec<VersionNeed>,
+    pub defs: Vec<VersionDef>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct SymbolVersion<'a> {
+    pub name: &'a str,
+
+    /// The version index, without the hidden bit.
+    pub index: u16,
+
+    /// True for a version needed from another file (the symbol is
+    /// undefined here, or copied into this file), false for one defined here.
+    pub needed: bool,
+
+    /// True if the symbol is hidden, i.e. not the default version of its name.
+    pub hidden: bool,
+}
+
+impl SymbolVersion<'_> {
+    /// The suffix for the symbol's name, as readelf and the linker write it:
+    /// "@@" before the default version of a defined symbol, and "@" otherwise.
+    pub fn suffix(&self) -> String {
+        if self.needed || self.hidden {
+            format!("@{}", self.name)
+        } else {
+            format!("@@{}", self.name)
+        }
+    }
+}
+
+impl SymbolVersions {
+    /// Finds the version of the dynamic symbol at `index`, following readelf's rules:
+    /// a defined symbol looks in the definitions first, except that the base version
+    /// (the file's own name) isn't shown; anything else looks in the needed versions.
+    pub fn symbol_version(
+        &self,
+        index: usize,
+        symbol: &Elf64SymbolEntry,
+    ) -> Option<SymbolVersion<'_>> {
+        let versym = *self.versym.get(index)?;
+        if versym == VER_NDX_LOCAL {
+            return None;
+        }
+
+        let version = versym & VERSYM_VERSION;
+        let hidden = versym & VERSYM_HIDDEN != 0;
+
+        if symbol.section_index != SHN_UNDEF && versym != (VERSYM_HIDDEN | VER_NDX_GLOBAL) {
+            if let Some(def) = self.defs.iter().find(|def| def.index == version) {
+                if def.index == VER_NDX_GLOBAL && def.flags == VER_FLG_BASE {
+                    return None;
+                }
+
+                // A version's own symbol (which has the version as its name) isn't annotated.
+                if symbol.name_offset != def.name_offset {
+                    return Some(SymbolVersion {
+                        name: def.names.first().map_or("", |name| name.as_str()),
+                        index: version,
+                        needed: false,
+                        hidden,
+                    });
+                }
+            }
+        }
+
+        self.needs
+            .iter()
+            .flat_map(|need| &need.versions)
+            .find(|needed| needed.index == versym)
+            .map(|needed| SymbolVersion {
+                name: &needed.name,
+                index: version,
+                needed: true,
+                hidden,
+            })
+    }
+}
+
+// ----------------------
+// Comparing versions.
+
+/// Splits a version name like "GLIBC_2.2.5" into its family ("GLIBC") and
+/// its number ("2.2.5"). A name without a number, like "GLIBC_PRIVATE",
+/// is a family of its own, with an empty number.
+pub fn split_version_name(name: &str) -> (&str, &str) {
+    match name.rfind('_') {
+        Some(at) if name[at + 1..].starts_with(|c: char| c.is_ascii_digit()) => {
+            (&name[..at], &name[at + 1..])
+        }
+        _ => (name, ""),
+    }
+}
+
+/// Orders version names by family, and then by number, comparing each
+/// dot-separated part of the number by value: "GLIBC_2.34" comes after
+/// "GLIBC_2.4", and "ALSA_0.9.0rc4" after "ALSA_0.9".
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    let (a_family, a_number) = split_version_name(a);
+    let (b_family, b_number) = split_version_name(b);
+
+    a_family.cmp(b_family).then_with(|| {
+        let a_parts = a_number.split('.').filter(|part| !part.is_empty());
+        let b_parts = b_number.split('.').filter(|part| !part.is_empty());
+
+        a_parts.map(number_part).cmp(b_parts.map(number_part))
+    })
+}
+
+/// Splits one part of a version number into its leading digits, by
+/// value, and whatever follows them ("0rc4" is 0 and "rc4").
+fn number_part(part: &str) -> (u64, &str) {
+    let digits = part.len() - part.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    let value = part[..digits].parse().unwrap_or(0);
+
+    (value, &part[digits..])
+}
+
+impl VersionNeed {
+    /// The newest version needed from the file in each family of versions
+    /// (so one for GLIBCXX and one for CXXABI from libstdc++), in the order
+    /// the families first appear. These are the versions the file must
+    /// provide for this one to load.
+    pub fn newest_versions(&self) -> Vec<&NeededVersion> {
+        let mut newest: Vec<&NeededVersion> = vec![];
+
+        for version in &self.versions {
+            let family = split_version_name(&version.name).0;
+
+            match newest
+                .iter_mut()
+                .find(|other| split_version_name(&other.name).0 == family)
+            {
+                Some(other) => {
+                    if compare_versions(&version.name, &other.name) == Ordering::Greater {
+                        *other = version;
+                    }
+                }
+                None => newest.push(version),
+            }
+        }
+
+        newest
+    }
+}
@@ -0,0 +1,64 @@
+# Code that a linear sweep would get wrong, for the control flow tests:
+# a jump table and a string in .text, and functions with no symbols.
+	.text
+	.globl	_start
+_start:
+	mov	$3, %edi
+	call	classify
+	mov	%eax, %edi
+	call	count
+	call	.Lhelper
+	jmp	fail
+
+	.globl	classify
+	.type	classify, @function
+classify:
+	cmp	$3, %edi
+	ja	.Ldefault
+	jmp	*.Ltable(,%rdi,8)
+	.p2align 3
+.Ltable:
+	.quad	.Lzero, .Lone, .Ltwo, .Lthree
+.Lzero:
+	xor	%eax, %eax
+	ret
+.Lone:
+	mov	$1, %eax
+	ret
+.Ltwo:
+.Lthree:
+	mov	$2, %eax
+	ret
+.Ldefault:
+	jmp	count
+	.size	classify, .-classify
+
+	.globl	count
+	.type	count, @function
+count:
+	xor	%eax, %eax
+	test	%edi, %edi
+	jle	.Lend
+.Lloop:
+	add	%edi, %eax
+	dec	%edi
+	jne	.Lloop
+.Lend:
+	ret
+	.size	count, .-count
+
+.Lmessage:
+	.ascii	"\x0f\x0b\xe8 not code\n"
+
+.Lhelper:
+	lea	.Lmessage(%rip), %rsi
+	ret
+
+	.globl	fail
+	.type	fail, @function
+fail:
+	mov	$1, %edi
+	mov	$60, %eax
+	syscall
+	ud2
+	.size	fail, .-fail
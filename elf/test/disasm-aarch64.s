@@ -0,0 +1,151 @@
+// Instructions from each part of the AArch64 decoder, for the disasm tests.
+	.text
+	.globl	_start
+	.type	_start, %function
+_start:
+	mov	x29, #0
+	mov	x0, sp
+	adrp	x1, message
+	add	x1, x1, :lo12:message
+	bl	sum
+	mov	x8, #93
+	svc	#0
+	brk	#0x3e8
+	.size	_start, .-_start
+
+	.type	sum, %function
+sum:
+	stp	x29, x30, [sp, #-16]!
+	mov	x29, sp
+	mov	w2, wzr
+	cbz	x1, .Ldone
+.Lloop:
+	ldrb	w3, [x0, x1]
+	add	w2, w2, w3
+	subs	x1, x1, #1
+	b.ne	.Lloop
+.Ldone:
+	mov	w0, w2
+	ldp	x29, x30, [sp], #16
+	ret
+	.size	sum, .-sum
+
+	.type	integer, %function
+integer:
+	add	x0, x1, #1, lsl #12
+	cmp	w2, #4095
+	and	x3, x4, #0xff00ff00ff00ff00
+	movk	x5, #0xbeef, lsl #16
+	mov	w6, #-2
+	ubfx	x7, x8, #4, #8
+	lsl	w9, w10, #3
+	extr	x11, x12, x13, #17
+	orr	x14, x15, x16, lsr #2
+	sub	x17, sp, w18, uxtw #2
+	adcs	w19, w20, w21
+	ccmp	x22, #5, #2, ge
+	csinc	w23, w24, w25, lt
+	cset	x26, eq
+	madd	x27, x28, x0, x1
+	umulh	x2, x3, x4
+	sdiv	w5, w6, w7
+	rev	x8, x9
+	clz	w10, w11
+	.size	integer, .-integer
+
+	.type	memory, %function
+memory:
+	ldr	x0, [x1, #8]
+	ldr	w2, [x3, x4, lsl #2]
+	ldrsw	x5, [x6, w7, sxtw]
+	str	q8, [sp, #-32]!
+	ldur	d9, [x10, #-3]
+	ldrh	w11, [x12], #2
+	ldxr	x13, [x14]
+	stlxr	w15, w16, [x17]
+	ldar	w18, [x19]
+	ldaxp	x20, x21, [x22]
+	ldnp	q0, q1, [x23, #64]
+	prfm	pldl1keep, [x24]
+	ldr	x25, .Lliteral
+	ld1	{ v0.16b, v1.16b }, [x0], #32
+	st4	{ v4.s, v5.s, v6.s, v7.s }[1], [x1]
+	ld1r	{ v2.8h }, [x2], x3
+	ret
+.Lliteral:
+	.quad	0x1122334455667788
+	.size	memory, .-memory
+
+	.type	control, %function
+control:
+	tbz	w0, #5, control
+	tbnz	x1, #63, .Lfar
+	cbnz	w2, control
+	b.hi	control
+	br	x3
+	blr	x4
+	b	sum
+.Lfar:
+	ret	x5
+	.size	control, .-control
+
+	.type	system, %function
+system:
+	nop
+	yield
+	dmb	ish
+	dsb	sy
+	isb
+	mrs	x0, tpidr_el0
+	msr	nzcv, x1
+	msr	daifset, #2
+	dc	civac, x2
+	ic	iallu
+	hint	#34
+	hvc	#1
+	.size	system, .-system
+
+	.type	floating, %function
+floating:
+	fadd	s0, s1, s2
+	fmadd	d3, d4, d5, d6
+	fcmp	d7, #0.0
+	fcsel	s8, s9, s10, gt
+	fmov	d11, #-1.25
+	fmov	x12, d13
+	fcvtzs	w14, s15
+	scvtf	d16, x17, #10
+	fcvt	s18, h19
+	frintm	d20, d21
+	.size	floating, .-floating
+
+	.type	vectors, %function
+vectors:
+	add	v0.4s, v1.4s, v2.4s
+	fmla	v3.2d, v4.2d, v5.d[1]
+	umull2	v6.4s, v7.8h, v8.8h
+	xtn	v9.8b, v10.8h
+	addv	h11, v12.8h
+	movi	v13.2d, #0xff00ff00ff00ff00
+	mvni	v14.4h, #3, lsl #8
+	ushr	v15.16b, v16.16b, #3
+	sshll2	v17.2d, v18.4s, #0
+	mov	v19.16b, v20.16b
+	dup	v21.4s, w22
+	mov	w23, v24.s[2]
+	ins	v25.h[7], v26.h[0]
+	ext	v27.16b, v28.16b, v29.16b, #12
+	tbl	v30.16b, { v0.16b, v1.16b, v2.16b }, v31.16b
+	zip1	v0.8h, v1.8h, v2.8h
+	cmeq	v3.2s, v4.2s, #0
+	fcvtn2	v5.4s, v6.2d
+	sqdmulh	s7, s8, v9.s[3]
+	faddp	d10, v11.2d
+	.inst	0xffffffff
+	.size	vectors, .-vectors
+
+	.section .rodata
+	.type	message, %object
+message:
+	.string	"aarch64"
+	.size	message, .-message
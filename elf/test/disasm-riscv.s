@@ -0,0 +1,160 @@
+// Instructions from each part of the RISC-V decoder, for the disasm tests.
+	.attribute arch, "rv64i2p0_m2p0_a2p0_f2p0_d2p0_c2p0"
+	.text
+	.globl	_start
+	.type	_start, @function
+_start:
+	li	s0, 0
+	mv	a0, sp
+	lui	a1, %hi(message)
+	addi	a1, a1, %lo(message)
+	jal	sum
+	li	a7, 93
+	ecall
+	ebreak
+	.size	_start, .-_start
+
+	.type	sum, @function
+sum:
+	addi	sp, sp, -16
+	sd	ra, 8(sp)
+	li	a2, 0
+	beqz	a1, .Ldone
+.Lloop:
+	add	t0, a0, a1
+	lbu	t1, -1(t0)
+	addw	a2, a2, t1
+	addi	a1, a1, -1
+	bnez	a1, .Lloop
+.Ldone:
+	mv	a0, a2
+	ld	ra, 8(sp)
+	addi	sp, sp, 16
+	ret
+	.size	sum, .-sum
+
+	.type	integer, @function
+integer:
+	.option	push
+	.option	norvc
+	addi	a0, a1, -2048
+	lui	a2, 0x12345
+	auipc	a3, 0xfffff
+	slli	a4, a5, 63
+	srai	a4, a5, 3
+	sraiw	a4, a5, 31
+	sext.w	a0, a1
+	not	a0, a1
+	neg	a0, a1
+	seqz	a0, a1
+	snez	a0, a1
+	sltz	a0, a1
+	sgtz	a0, a1
+	mulh	a0, a1, a2
+	divuw	a0, a1, a2
+	remu	a0, a1, a2
+	lwu	a0, 2047(a1)
+	sh	a0, -2048(a1)
+	.option	pop
+	.size	integer, .-integer
+
+	.type	control, @function
+control:
+	.option	push
+	.option	norvc
+	beq	a0, a1, control
+	bltu	a0, a1, control
+	bgez	a0, control
+	blez	a0, control
+	jal	t0, control
+	j	control
+	jr	a0
+	jalr	a0
+	jalr	t0, 16(a0)
+	ret
+	.option	pop
+	.size	control, .-control
+
+	.type	system, @function
+system:
+	csrr	a0, mstatus
+	csrw	mtvec, a1
+	csrrs	a0, mie, a1
+	csrsi	mip, 8
+	csrrci	a0, 0x7c0, 31
+	rdcycle	a0
+	frflags	a0
+	fsrmi	3
+	fence
+	fence	rw, w
+	fence.tso
+	fence.i
+	wfi
+	mret
+	unimp
+	sfence.vma	a0, a1
+	.size	system, .-system
+
+	.type	atomic, @function
+atomic:
+	lr.w.aq	a0, (a1)
+	sc.d.rl	a0, a1, (a2)
+	amoadd.d.aqrl	a0, a1, (a2)
+	amomaxu.w	a0, a1, (a2)
+	.size	atomic, .-atomic
+
+	.type	float, @function
+float:
+	.option	push
+	.option	norvc
+	flw	ft0, 4(a0)
+	fsd	fa1, -8(sp)
+	fadd.s	fa0, fa1, fa2
+	fmul.d	fa0, fa1, fa2, rtz
+	fmadd.d	fa0, fa1, fa2, fa3
+	fsqrt.s	ft1, ft2
+	fmv.s	fa0, fa1
+	fneg.d	fa0, fa1
+	fabs.s	fa0, fa1
+	fsgnjx.d	fa0, fa1, fa2
+	fmin.d	fa0, fa1, fa2
+	feq.s	a0, fa0, fa1
+	fcvt.w.s	a0, fa0, rtz
+	fcvt.d.l	fa0, a0
+	fcvt.d.w	fa0, a0
+	fcvt.s.d	fa0, fa1
+	fcvt.d.s	fa0, fa1
+	fmv.x.d	a0, fa0
+	fmv.w.x	fa0, a0
+	fclass.d	a0, fa0
+	.option	pop
+	.size	float, .-float
+
+	.type	compressed, @function
+compressed:
+	c.addi4spn	a0, sp, 16
+	c.lw	a1, 4(a0)
+	c.ld	a2, 8(a0)
+	c.fsd	fa0, 16(a1)
+	c.lui	a0, 0x1f
+	c.addi16sp	sp, -64
+	c.srli	a0, 3
+	c.andi	a1, -1
+	c.subw	a0, a1
+	c.slli	a2, 4
+	c.ldsp	a3, 24(sp)
+	c.swsp	a4, 12(sp)
+	c.jr	a5
+	c.jalr	a5
+	c.mv	a0, a6
+	c.add	a0, a6
+	c.j	compressed
+	c.bnez	a0, compressed
+	.2byte	0x4005
+	.2byte	0x0000
+	.4byte	0xffffffff
+	.size	compressed, .-compressed
+
+	.section	.rodata
+message:
+	.ascii	"hello"
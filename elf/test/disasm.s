@@ -0,0 +1,85 @@
+# Instructions from each part of the x86-64 decoder, for the disasm tests.
+	.text
+	.globl	_start
+_start:
+	xor	%ebp, %ebp
+	mov	%rsp, %rdi
+	lea	message(%rip), %rsi
+	call	sum
+	mov	%eax, %edi
+	mov	$60, %eax
+	syscall
+	hlt
+
+	.globl	sum
+	.type	sum, @function
+sum:
+	push	%rbp
+	mov	%rsp, %rbp
+	xor	%eax, %eax
+	test	%rsi, %rsi
+	je	.Ldone
+.Lloop:
+	movzbl	(%rdi,%rsi,1), %ecx
+	add	%ecx, %eax
+	dec	%rsi
+	jne	.Lloop
+.Ldone:
+	pop	%rbp
+	ret
+	.size	sum, .-sum
+
+	.type	strings, @function
+strings:
+	cld
+	rep movsb
+	repne scasb
+	lock cmpxchg %rcx, (%rdx)
+	xchg	%rax, %rbx
+	cmovge	0x10(%rax,%rbx,8), %r12
+	imul	$100, %r8d, %r9d
+	shl	%cl, %r10w
+	movsbw	%al, %dx
+	bswap	%r11
+	jmp	*table(,%rax,8)
+	.size	strings, .-strings
+
+	.type	vectors, @function
+vectors:
+	movaps	%xmm1, %xmm2
+	pxor	%xmm0, %xmm0
+	pshufd	$0x1b, (%rax), %xmm3
+	cvtsi2sd %rax, %xmm4
+	vaddps	%ymm1, %ymm2, %ymm3
+	vpbroadcastd %xmm0, %ymm15
+	vfmadd231pd (%rdi), %xmm1, %xmm2
+	vpaddd	%zmm1, %zmm2, %zmm3{%k1}{z}
+	vmovdqu64 0x40(%rsi), %zmm4
+	kmovw	%k1, %eax
+	ret
+	.size	vectors, .-vectors
+
+	.type	floats, @function
+floats:
+	fldl	8(%rsp)
+	fmul	%st(1), %st
+	fstpl	(%rsp)
+	fnstcw	(%rax)
+	fwait
+	ret
+	.size	floats, .-floats
+
+	.p2align 4
+	.type	nops, @function
+nops:
+	endbr64
+	nopw	0x0(%rax,%rax,1)
+	ret
+	.size	nops, .-nops
+
+	.section .rodata
+	.align 8
+table:
+	.quad	_start, sum
+message:
+	.string	"hello"
@@ -0,0 +1,42 @@
+/* Functions whose call frames are described in different ways, for the
+ * call frame information tests. */
+#include <stdio.h>
+#include <stdlib.h>
+
+/* Saves callee-saved registers, so its FDE has rules for them. */
+__attribute__((noinline)) long mix(long *values, int count)
+{
+    long a = 1, b = 2, c = 3, d = 4, e = 5;
+
+    for (int i = 0; i < count; i++) {
+        a += values[i] * b;
+        b ^= a >> 3;
+        c += b * d;
+        d -= c + e;
+        e += printf("%ld\n", a + d);
+
+        /* Returning from the middle of the function makes gcc describe
+           the epilogue with DW_CFA_remember_state and restore_state. */
+        if (e > 1000)
+            return e;
+    }
+
+    return a + b + c + d + e;
+}
+
+/* Uses a frame pointer, for the variable-length array. */
+__attribute__((noinline)) long sum(int count)
+{
+    long values[count];
+
+    for (int i = 0; i < count; i++)
+        values[i] = i * 3;
+
+    return mix(values, count);
+}
+
+int main(int argc, char **argv)
+{
+    printf("%ld\n", sum(argc + 4));
+    return 0;
+}
@@ -0,0 +1,79 @@
+/* Source for the layouts-dwarf* fixtures, which test reading functions and types. */
+#include <stdio.h>
+#include <stdlib.h>
+
+struct point {
+    int x;
+    int y;
+};
+
+typedef struct point point_t;
+
+/* A char before a long leaves a hole, and a char at the end leaves padding. */
+struct holes {
+    char tag;
+    long value;
+    short count;
+    point_t origin;
+    char last;
+};
+
+struct flags {
+    unsigned ready : 1;
+    unsigned mode : 3;
+    unsigned char level;
+    unsigned long big : 40;
+};
+
+union number {
+    int i;
+    double d;
+    char bytes[8];
+};
+
+struct callbacks {
+    const char *name;
+    int (*compare)(const void *, const void *);
+    void (*done)(void);
+    struct point corners[2][2];
+    union number value;
+    volatile int *const counter;
+    struct holes *next;
+    char line[80];
+};
+
+struct flags global_flags;
+union number global_number;
+struct callbacks *global_callbacks;
+
+static int compare_points(const void *a, const void *b)
+{
+    const struct point *p = a, *q = b;
+
+    return p->x - q->x;
+}
+
+__attribute__((noinline)) long sum_holes(const struct holes *holes, int n)
+{
+    long total = 0;
+
+    for (int i = 0; i < n; i++) {
+        if (holes[i].tag == 0)
+            abort();
+        total += holes[i].value + holes[i].count + holes[i].origin.y;
+    }
+
+    return total;
+}
+
+int main(int argc, char **argv)
+{
+    struct holes holes[4] = {{1, 2, 3, {4, 5}, 6}};
+    struct point points[3] = {{3, 0}, {1, 0}, {2, 0}};
+
+    qsort(points, 3, sizeof(struct point), compare_points);
+    global_flags.mode = argc;
+    printf("%ld %d\n", sum_holes(holes, argc), points[0].x);
+
+    return global_callbacks != NULL;
+}
@@ -0,0 +1,30 @@
+/* Source for the lines-dwarf* fixtures, which test line-number decoding. */
+#include <stdio.h>
+
+#include "lines.h"
+
+static int collatz_steps(long n)
+{
+    int steps = 0;
+
+    while (n != 1) {
+        if (n % 2 == 0)
+            n /= 2;
+        else
+            n = 3 * n + 1;
+        steps++;
+    }
+
+    return steps;
+}
+
+int main(int argc, char **argv)
+{
+    long total = 0;
+
+    for (int i = 1; i < argc + 20; i++)
+        total += collatz_steps(i) + square(i);
+
+    printf("%ld\n", total);
+    return 0;
+}
@@ -0,0 +1,5 @@
+/* An inline function, so that the line program switches files. */
+static inline long square(long x)
+{
+    return x * x;
+}
@@ -0,0 +1,16 @@
+/* Source for the long-versions fixture: a shared library whose symbols'
+ * versions leave no room, or less than none, for their names in readelf's
+ * 21 columns. Built with:
+ *
+ *     gcc -shared -fPIC -nostdlib -O2 -Wl,--version-script=long-versions.map \
+ *         -o long-versions long-versions.c
+ */
+int filled(void)
+{
+    return 1;
+}
+
+int overflowed(void)
+{
+    return 2;
+}
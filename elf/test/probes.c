@@ -0,0 +1,45 @@
+/* Source for the probes fixture: a program with SystemTap probes, whose
+ * descriptors are NT_STAPSDT notes in .note.stapsdt. The notes are written
+ * as <sys/sdt.h> writes them, so that the header isn't needed. Built with:
+ *
+ *     gcc -O2 -o probes probes.c
+ */
+
+/* A probe at this point in the code: a nop, with a note giving its
+ * location, the base that prelinking moves it by, the semaphore that
+ * turns it on, and where to find its arguments. */
+#define PROBE(provider, name, semaphore, arguments, ...)                    \
+    __asm__ __volatile__(                                                  \
+        "990: nop\n"                                                       \
+        ".pushsection .note.stapsdt,\"?\",\"note\"\n"                      \
+        ".balign 4\n"                                                      \
+        ".4byte 992f-991f, 994f-993f, 3\n"                                 \
+        "991: .asciz \"stapsdt\"\n"                                        \
+        "992: .balign 4\n"                                                 \
+        "993: .8byte 990b\n"                                               \
+        ".8byte _.stapsdt.base\n"                                          \
+        ".8byte " semaphore "\n"                                           \
+        ".asciz \"" provider "\"\n"                                        \
+        ".asciz \"" name "\"\n"                                            \
+        ".asciz \"" arguments "\"\n"                                       \
+        "994: .balign 4\n"                                                 \
+        ".popsection\n"                                                    \
+        ".ifndef _.stapsdt.base\n"                                         \
+        ".pushsection .stapsdt.base,\"aG\",\"progbits\",.stapsdt.base,comdat\n" \
+        ".weak _.stapsdt.base\n"                                           \
+        ".hidden _.stapsdt.base\n"                                         \
+        "_.stapsdt.base: .space 1\n"                                       \
+        ".size _.stapsdt.base, 1\n"                                        \
+        ".popsection\n"                                                    \
+        ".endif\n"                                                         \
+        :: __VA_ARGS__)
+
+unsigned short probes_start_semaphore __attribute__((section(".probes")));
+
+int main(int argc, char **argv)
+{
+    PROBE("probes", "start", "probes_start_semaphore", "-4@%0 8@%1",
+          "nor"(argc), "nor"(argv));
+    PROBE("probes", "done", "0", "");
+    return 0;
+}
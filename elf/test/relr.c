@@ -0,0 +1,15 @@
+/* Source for the relr fixture: a PIE whose relative relocations are packed
+ * into an SHT_RELR section, both as addresses and as bitmaps. Built with:
+ *
+ *     gcc -O2 -fPIE -pie -Wl,-z,pack-relative-relocs -o relr relr.c
+ */
+static int a, b, c, d, e;
+
+/* Consecutive pointers, with a gap, are encoded as a bitmap. */
+int *pointers[] = { &a, &b, &c, &d, &e, 0, &a, &b };
+int *other[] = { &c };
+
+int main(void)
+{
+    return *pointers[0];
+}
@@ -0,0 +1,32 @@
+// Source for the tiny-rust fixture, whose DWARF 5 (from LLVM) uses the forms
+// that index into .debug_str_offsets, .debug_addr, and .debug_rnglists.
+#![no_std]
+#![no_main]
+
+pub struct Sample {
+    pub flag: u8,
+    pub value: u64,
+    pub small: u16,
+}
+
+#[inline(never)]
+#[no_mangle]
+pub extern "C" fn total(sample: &Sample) -> u64 {
+    sample.flag as u64 + sample.value + sample.small as u64
+}
+
+#[no_mangle]
+pub extern "C" fn _start() -> ! {
+    let sample = Sample {
+        flag: 1,
+        value: 2,
+        small: 3,
+    };
+    core::hint::black_box(total(core::hint::black_box(&sample)));
+    loop {}
+}
+
+#[panic_handler]
+fn panic(_: &core::panic::PanicInfo) -> ! {
+    loop {}
+}
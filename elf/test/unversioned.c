@@ -0,0 +1,14 @@
+/* Source for the unversioned fixture: a shared library that links against
+ * nothing, so none of its dynamic symbols have versions. Built with:
+ *
+ *     gcc -shared -fPIC -nostdlib -O2 -o unversioned unversioned.c
+ */
+extern int counter;
+extern int scale(int value);
+
+int offset = 1;
+
+int adjust(int value)
+{
+    return scale(value) + counter + offset;
+}
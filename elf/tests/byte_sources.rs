@@ -0,0 +1,76 @@
+/// Checks that each `ByteSource` backend returns the same bytes, including
+/// ranges that span cache pages and requests past the end of the file.
+///
+mod common;
+
+use common::fixture;
+
+use std::fs::File;
+
+use elf::file_read::*;
+use elf::ElfFile;
+
+#[test]
+fn backends_agree_on_every_section() {
+    for name in ["forwarding", "AudioPlayer"] {
+        let contents = std::fs::read(fixture(name)).unwrap();
+
+        let mut mapped = ElfFile::open(&fixture(name)).unwrap();
+        let mut in_memory = ElfFile::from_bytes(&contents).unwrap();
+
+        // A cache of a few pages forces evictions, and both kinds of multi-page read.
+        let seek = SeekSource::with_cache_pages(File::open(fixture(name)).unwrap(), 8).unwrap();
+        let mut sought = ElfFile::from_source(Box::new(seek)).unwrap();
+
+        assert_eq!(mapped.section_headers.len(), sought.section_headers.len());
+        assert_eq!(
+            mapped.section_headers.len(),
+            in_memory.section_headers.len()
+        );
+
+        for index in 0..mapped.section_headers.len() {
+            let expected = in_memory.section_bytes(index).unwrap().to_vec();
+
+            assert_eq!(mapped.section_bytes(index).unwrap(), expected);
+            assert_eq!(sought.section_bytes(index).unwrap(), expected);
+        }
+    }
+}
+
+#[test]
+fn seek_source_reads_across_pages() {
+    let contents = std::fs::read(fixture("forwarding")).unwrap();
+    let mut source =
+        SeekSource::with_cache_pages(File::open(fixture("forwarding")).unwrap(), 2).unwrap();
+
+    for (offset, size) in [
+        (0, 1),
+        (4090, 12),
+        (4096, 4096),
+        (100, 20000),
+        (4095, 1),
+        (0, 0),
+    ] {
+        let expected = &contents[offset..offset + size];
+        assert_eq!(source.bytes(offset as u64, size as u64).unwrap(), expected);
+    }
+}
+
+#[test]
+fn reads_past_the_end_fail() {
+    let contents = std::fs::read(fixture("forwarding")).unwrap();
+    let len = contents.len() as u64;
+
+    let mut sources: Vec<Box<dyn ByteSource>> = vec![
+        Box::new(SliceSource::new(&contents)),
+        Box::new(MmapSource::new(&File::open(fixture("forwarding")).unwrap()).unwrap()),
+        Box::new(SeekSource::new(File::open(fixture("forwarding")).unwrap()).unwrap()),
+    ];
+
+    for source in &mut sources {
+        assert_eq!(source.len(), len);
+        assert!(source.bytes(len - 4, 4).is_ok());
+        assert!(source.bytes(len - 4, 5).is_err());
+        assert!(source.bytes(u64::MAX, 2).is_err());
+    }
+}
@@ -0,0 +1,255 @@
+/// Checks reading call frame information from .eh_frame and .debug_frame,
+/// finding FDEs through .eh_frame_hdr, and unwinding with the rules. The
+/// fixtures are built from `test/frames.c`, in `test/`:
+///
+///     P="-O2 -fdebug-prefix-map=$PWD=/src"
+///     gcc -g $P frames.c -o frames
+///     gcc -g -fno-asynchronous-unwind-tables $P frames.c -o frames-debug-frame
+///
+/// and the golden file with GNU readelf 2.40:
+///
+///     readelf -wF frames-debug-frame > golden/frames-debug-frame-frames.txt
+///
+mod common;
+
+use common::{fixture, read_fixture, run_ok};
+
+use std::collections::HashMap;
+
+use elf::dwarf::cfa::{CfaRule, RegisterRule};
+use elf::dwarf::expression::Registers;
+use elf::dwarf::frame::{FrameEntry, FrameSection, DW_EH_PE_PCREL, DW_EH_PE_SDATA4};
+use elf::{ElfError, ElfFile};
+
+// Where the functions are, in both fixtures.
+const PLT: u64 = 0x1020;
+const MAIN: u64 = 0x1050;
+const MIX: u64 = 0x1170;
+const SUM: u64 = 0x1240;
+
+// The x86-64 DWARF numbers of the registers used here.
+const RBX: u16 = 3;
+const RBP: u16 = 6;
+const RSP: u16 = 7;
+const RIP: u16 = 16;
+
+#[test]
+fn reads_cies_and_fdes() {
+    let mut elf_file = ElfFile::open(&fixture("frames")).unwrap();
+    let eh_frame = elf_file
+        .call_frame_info(FrameSection::EhFrame)
+        .unwrap()
+        .unwrap();
+
+    let entries = eh_frame.entries().unwrap();
+    let FrameEntry::Cie(cie) = &entries[0] else {
+        panic!("The first entry isn't a CIE: {entries:?}");
+    };
+    assert_eq!(cie.augmentation, "zR");
+    assert_eq!(cie.fde_encoding, DW_EH_PE_PCREL | DW_EH_PE_SDATA4);
+    assert_eq!(
+        (
+            cie.code_alignment_factor,
+            cie.data_alignment_factor,
+            cie.return_address_register
+        ),
+        (1, -8, RIP)
+    );
+    assert!(matches!(entries.last(), Some(FrameEntry::Terminator(_))));
+
+    // crt1.o's _start has an FDE, as does each part of the PLT.
+    let mut starts: Vec<_> = eh_frame
+        .fdes()
+        .unwrap()
+        .iter()
+        .map(|fde| fde.range.start)
+        .collect();
+    starts.sort();
+    assert_eq!(starts, [PLT, PLT + 0x20, MAIN, 0x1080, MIX, SUM]);
+
+    // Without asynchronous unwind tables, .debug_frame only has the
+    // functions compiled from frames.c, in the order they were emitted.
+    let mut elf_file = ElfFile::open(&fixture("frames-debug-frame")).unwrap();
+    let debug_frame = elf_file
+        .call_frame_info(FrameSection::DebugFrame)
+        .unwrap()
+        .unwrap();
+    let ranges: Vec<_> = debug_frame
+        .fdes()
+        .unwrap()
+        .into_iter()
+        .map(|fde| (fde.range.start, fde.range.end))
+        .collect();
+    assert_eq!(ranges, [(MIX, 0x1238), (SUM, 0x1287), (MAIN, 0x1074)]);
+}
+
+#[test]
+fn searches_eh_frame_hdr() {
+    let mut elf_file = ElfFile::open(&fixture("frames")).unwrap();
+    let eh_frame = elf_file
+        .call_frame_info(FrameSection::EhFrame)
+        .unwrap()
+        .unwrap();
+    let hdr = elf_file.eh_frame_hdr().unwrap().unwrap();
+
+    assert_eq!(hdr.eh_frame_address, eh_frame.address());
+
+    // Each FDE is found by its start, and by the end of its code.
+    let fdes = eh_frame.fdes().unwrap();
+    assert_eq!(hdr.table.len(), fdes.len());
+
+    for fde in &fdes {
+        let address = eh_frame.address() + fde.offset;
+
+        assert_eq!(hdr.search(fde.range.start), Some(address));
+        assert_eq!(hdr.search(fde.range.end - 1), Some(address));
+    }
+    assert_eq!(hdr.search(PLT - 1), None);
+}
+
+#[test]
+fn finds_the_rules_at_addresses() {
+    for name in ["frames", "frames-debug-frame"] {
+        let mut elf_file = ElfFile::open(&fixture(name)).unwrap();
+        let cfa =
+            |elf_file: &mut ElfFile, address| elf_file.unwind_row(address).unwrap().unwrap().cfa;
+        let rsp = |offset| CfaRule::RegisterOffset {
+            register: RSP,
+            offset,
+        };
+
+        // Through the pushes of mix's prologue, and back through its
+        // epilogue, to what was remembered from before it.
+        assert_eq!(cfa(&mut elf_file, MIX), rsp(8), "{name}");
+        assert_eq!(cfa(&mut elf_file, MIX + 0x0a), rsp(56), "{name}");
+        assert_eq!(cfa(&mut elf_file, MIX + 0x10), rsp(80), "{name}");
+        assert_eq!(cfa(&mut elf_file, MIX + 0xa8), rsp(16), "{name}");
+        assert_eq!(cfa(&mut elf_file, MIX + 0xb0), rsp(80), "{name}");
+
+        // sum has a frame pointer.
+        let row = elf_file.unwind_row(SUM + 0x20).unwrap().unwrap();
+        assert_eq!(
+            row.cfa,
+            CfaRule::RegisterOffset {
+                register: RBP,
+                offset: 16
+            }
+        );
+        assert_eq!(row.registers[&RBP], RegisterRule::Offset(-16));
+        assert_eq!(row.registers[&RIP], RegisterRule::Offset(-8));
+
+        assert_eq!(elf_file.unwind_row(0x1000).unwrap(), None);
+    }
+}
+
+/// Reads memory from a map of 8-byte values.
+fn memory(values: &HashMap<u64, u64>) -> impl Fn(u64, usize) -> Option<u64> + '_ {
+    |address, size| {
+        assert_eq!(size, 8);
+        values.get(&address).copied()
+    }
+}
+
+#[test]
+fn unwinds_through_saved_registers() {
+    let mut elf_file = ElfFile::open(&fixture("frames")).unwrap();
+
+    // Stopped in mix, which sum called (to return to 0x1285), which main
+    // called (to return to 0x105c). mix has pushed six registers, and made
+    // room for three more, so the CFA is 80 bytes above the stack pointer.
+    let mut stack = HashMap::from([
+        (0x7fe0 + 0x50 - 8, 0x1285),  // mix's return address
+        (0x7fe0 + 0x50 - 48, 0x8100), // sum's rbp
+        (0x7fe0 + 0x50 - 56, 0xb0b0), // sum's rbx
+        (0x8100 + 8, 0x105c),         // sum's return address
+        (0x8100, 0x8200),             // main's rbp
+    ]);
+    // sum's r12 to r15.
+    for offset in [16, 24, 32, 40] {
+        stack.insert(0x7fe0 + 0x50 - offset, 0);
+    }
+    let registers = Registers::from([(RSP, 0x7fe0), (RIP, MIX + 0x20), (RBX, 1), (RBP, 2)]);
+
+    let row = elf_file.unwind_row(MIX + 0x20).unwrap().unwrap();
+    let caller = row.unwind(&registers, RSP, &memory(&stack)).unwrap();
+    assert_eq!(
+        (caller[&RSP], caller[&RIP], caller[&RBX], caller[&RBP]),
+        (0x8030, 0x1285, 0xb0b0, 0x8100)
+    );
+
+    // A return address is after the call, so it's looked up one before.
+    let row = elf_file.unwind_row(caller[&RIP] - 1).unwrap().unwrap();
+    let caller = row.unwind(&caller, RSP, &memory(&stack)).unwrap();
+    assert_eq!(
+        (caller[&RSP], caller[&RIP], caller[&RBP]),
+        (0x8110, 0x105c, 0x8200)
+    );
+
+    // Without the saved values, unwinding can't go on.
+    let error = row
+        .unwind(&caller, RSP, &memory(&HashMap::new()))
+        .unwrap_err();
+    assert!(matches!(error, ElfError::Unavailable { .. }));
+}
+
+#[test]
+fn evaluates_cfa_expressions() {
+    let mut elf_file = ElfFile::open(&fixture("frames")).unwrap();
+
+    // A PLT entry's CFA is 8 bytes higher after its push, 11 bytes in.
+    let entry = PLT + 0x10;
+    let row = elf_file.unwind_row(entry).unwrap().unwrap();
+    assert!(matches!(row.cfa, CfaRule::Expression(_)));
+
+    let no_memory = HashMap::new();
+    for (address, cfa) in [(entry, 0x7f08), (entry + 10, 0x7f08), (entry + 11, 0x7f10)] {
+        let registers = Registers::from([(RSP, 0x7f00), (RIP, address)]);
+        assert_eq!(
+            row.cfa(&registers, &memory(&no_memory)).unwrap(),
+            cfa,
+            "{address:#x}"
+        );
+    }
+}
+
+#[test]
+fn reports_unsupported_cie_versions() {
+    let mut contents = read_fixture("frames");
+    let offset = {
+        let elf_file = ElfFile::from_bytes(&contents).unwrap();
+        let index = elf_file.section_index_by_name(".eh_frame").unwrap();
+
+        elf_file.section(index).unwrap().offset as usize
+    };
+
+    // The version follows the length and the CIE ID.
+    contents[offset + 8] = 2;
+
+    let eh_frame = ElfFile::from_bytes(&contents)
+        .unwrap()
+        .call_frame_info(FrameSection::EhFrame)
+        .unwrap()
+        .unwrap();
+    assert!(matches!(
+        eh_frame.entries(),
+        Err(ElfError::UnsupportedVersion { version: 2, .. })
+    ));
+}
+
+#[test]
+fn unwind_prints_tables_like_readelf() {
+    let expected =
+        std::fs::read_to_string(fixture("golden/frames-debug-frame-frames.txt")).unwrap();
+
+    assert_eq!(run_ok(&["unwind", "frames-debug-frame"]), expected);
+}
+
+#[test]
+fn unwind_prints_the_rules_at_addresses() {
+    assert_eq!(
+        run_ok(&["unwind", "frames", "1260", "0x1035", "0x1000"]),
+        "0x0000000000001260: CFA=rbp+16 rbp=c-16 ra=c-8\n\
+         0x0000000000001035: CFA=exp ra=c-8\n\
+         0x0000000000001000: no unwind information\n"
+    );
+}
@@ -0,0 +1,130 @@
+/// Checks the command line: choosing the parts to print, the default of
+/// printing everything, when output is colored, reporting each file that
+/// can't be read while going on with the others, and stopping quietly when
+/// the output is closed.
+///
+mod common;
+
+use std::process::{Command, Stdio};
+
+use common::{fixture, run, run_ok};
+
+/// The titles of the parts printed, in order.
+fn parts(output: &str) -> Vec<&str> {
+    output
+        .lines()
+        .filter_map(|line| line.strip_prefix(">> ")?.strip_suffix(" <<"))
+        .collect()
+}
+
+#[test]
+fn prints_only_the_selected_parts() {
+    let output = run_ok(&["--header", "forwarding"]);
+    assert_eq!(parts(&output), ["ELF main header."]);
+    assert!(output.contains("DYN (Position-Independent Executable file)"));
+
+    let output = run_ok(&["--notes", "--segments", "forwarding"]);
+    assert_eq!(
+        parts(&output),
+        ["Program headers.", "Section to segment mapping.", "Notes."]
+    );
+    assert!(output.contains("Build ID: d0d35692995d1d208dca3f7ca6060c4427efd008"));
+}
+
+#[test]
+fn prints_everything_by_default() {
+    let everything = run_ok(&["forwarding"]);
+    assert_eq!(everything, run_ok(&["--all", "forwarding"]));
+    assert_eq!(everything, run_ok(&["--all", "--header", "forwarding"]));
+
+    assert_eq!(
+        parts(&everything),
+        [
+            "ELF main header.",
+            "Program headers.",
+            "Section to segment mapping.",
+            "Section headers.",
+            "Dynamic section.",
+            "Relocations.",
+            "Symbol tables.",
+            "Symbol versions.",
+            "Notes.",
+        ]
+    );
+}
+
+#[test]
+fn colors_only_when_asked_or_on_a_terminal() {
+    // The tests' output isn't a terminal.
+    assert!(!run_ok(&["--header", "forwarding"]).contains('\x1b'));
+    assert!(!run_ok(&["--header", "--color", "never", "forwarding"]).contains('\x1b'));
+    assert!(run_ok(&["--header", "--color", "always", "forwarding"]).contains('\x1b'));
+}
+
+#[test]
+fn names_each_file_when_given_several() {
+    let output = run_ok(&["--header", "forwarding", "unversioned"]);
+
+    let files: Vec<_> = output
+        .lines()
+        .filter(|line| line.starts_with("File: "))
+        .collect();
+    assert_eq!(files, ["File: forwarding", "File: unversioned"]);
+    assert_eq!(parts(&output).len(), 2);
+
+    // With one file, there's no need.
+    assert!(!run_ok(&["--header", "forwarding"]).contains("File: "));
+}
+
+#[test]
+fn reports_each_failing_file_and_goes_on() {
+    let output = run(&["--header", "missing", "frames.c", "forwarding"]);
+    assert!(!output.status.success());
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let errors: Vec<_> = stderr.lines().collect();
+    assert_eq!(
+        errors,
+        [
+            "error: missing: I/O error: No such file or directory (os error 2).",
+            "error: frames.c: Parse failed: Bad file format.",
+        ]
+    );
+
+    // The file that could be read was still printed.
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("File: forwarding"));
+    assert_eq!(parts(&stdout), ["ELF main header."]);
+}
+
+#[test]
+fn needs_a_file() {
+    let output = run(&["--header"]);
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("<FILES>"));
+}
+
+#[test]
+fn stops_quietly_when_the_output_is_closed() {
+    for args in [
+        &["unwind", "frames"][..],
+        &["--readelf-compat", "forwarding"],
+        &["disasm", "forwarding"],
+    ] {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_elf"))
+            .args(args)
+            .current_dir(fixture(""))
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+
+        // Nothing is read, as when the output goes to `head -0`.
+        drop(child.stdout.take());
+        let output = child.wait_with_output().unwrap();
+
+        assert!(output.status.success(), "{args:?} failed");
+        assert_eq!(String::from_utf8_lossy(&output.stderr), "", "{args:?}");
+    }
+}
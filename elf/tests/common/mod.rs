@@ -0,0 +1,43 @@
+/// Helpers shared by the integration tests: finding the fixtures in
+/// `test/`, and running the `elf` binary on them. Each test file uses
+/// only some of them, hence the `allow(dead_code)`s.
+///
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+/// The path of a fixture, relative to `test/`.
+#[allow(dead_code)]
+pub fn fixture(path: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("test")
+        .join(path)
+}
+
+/// The contents of a fixture.
+#[allow(dead_code)]
+pub fn read_fixture(path: &str) -> Vec<u8> {
+    std::fs::read(fixture(path)).unwrap()
+}
+
+/// Runs the tool from `test/`, so that fixtures can be given by name and
+/// the paths it prints don't depend on where the tree is.
+#[allow(dead_code)]
+pub fn run(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_elf"))
+        .args(args)
+        .current_dir(fixture(""))
+        .output()
+        .expect("Failed to run elf.")
+}
+
+/// Runs the tool, checks that it succeeded, and returns what it printed.
+#[allow(dead_code)]
+pub fn run_ok(args: &[&str]) -> String {
+    let output = run(args);
+    assert!(
+        output.status.success(),
+        "elf failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
@@ -0,0 +1,102 @@
+/// Checks that compressed debug sections are decompressed transparently. The
+/// fixtures are `test/forwarding` with its debug sections compressed:
+///
+///     objcopy --compress-debug-sections=zlib test/forwarding test/forwarding-zlib
+///     objcopy --compress-debug-sections=zstd test/forwarding test/forwarding-zstd
+///     objcopy --compress-debug-sections=zlib-gnu test/forwarding test/forwarding-zdebug
+///
+mod common;
+
+use common::fixture;
+
+use elf::parse::compression::CompressionFormat;
+use elf::{ElfError, ElfFile};
+
+const DEBUG_SECTIONS: [&str; 7] = [
+    ".debug_aranges",
+    ".debug_info",
+    ".debug_abbrev",
+    ".debug_line",
+    ".debug_str",
+    ".debug_line_str",
+    ".debug_rnglists",
+];
+
+#[test]
+fn decompresses_every_debug_section() {
+    let mut original = ElfFile::open(&fixture("forwarding")).unwrap();
+
+    for (name, format) in [
+        ("forwarding-zlib", CompressionFormat::Zlib),
+        ("forwarding-zstd", CompressionFormat::Zstd),
+        ("forwarding-zdebug", CompressionFormat::GnuZlib),
+    ] {
+        let mut compressed = ElfFile::open(&fixture(name)).unwrap();
+
+        for section in DEBUG_SECTIONS {
+            let expected = original.debug_section_data(section).unwrap().unwrap();
+            let expected = expected.to_vec();
+
+            let actual = compressed.debug_section_data(section).unwrap().unwrap();
+            assert_eq!(actual.to_vec(), expected, "{section} in {name}");
+
+            let gnu_name = section.replacen(".debug", ".zdebug", 1);
+            let index = compressed
+                .section_index_by_name(section)
+                .or_else(|| compressed.section_index_by_name(&gnu_name))
+                .unwrap();
+
+            // objcopy leaves sections alone that compression wouldn't make smaller.
+            let Some(compression) = compressed.section_compression(index).unwrap() else {
+                continue;
+            };
+
+            assert_eq!(compression.format, format);
+            assert_eq!(compression.uncompressed_size, expected.len() as u64);
+            assert_eq!(
+                compression.compressed_size,
+                compressed.section(index).unwrap().size
+            );
+        }
+    }
+}
+
+#[test]
+fn uncompressed_sections_are_left_alone() {
+    let mut elf_file = ElfFile::open(&fixture("forwarding-zlib")).unwrap();
+
+    let text = elf_file.section_index_by_name(".text").unwrap();
+    assert_eq!(elf_file.section_compression(text).unwrap(), None);
+
+    let bytes = elf_file.section_bytes(text).unwrap().to_vec();
+    assert_eq!(*elf_file.section_data(text).unwrap(), bytes);
+}
+
+#[test]
+fn reports_corrupt_compressed_data() {
+    let mut contents = std::fs::read(fixture("forwarding-zstd")).unwrap();
+    let (index, offset) = {
+        let elf_file = ElfFile::from_bytes(&contents).unwrap();
+        let index = elf_file.section_index_by_name(".debug_info").unwrap();
+
+        (index, elf_file.section(index).unwrap().offset as usize)
+    };
+
+    // Garble the stream just after the 24-byte compression header.
+    contents[offset + 24..offset + 40].fill(0xff);
+
+    assert!(matches!(
+        ElfFile::from_bytes(&contents).unwrap().section_data(index),
+        Err(ElfError::Decompression { .. })
+    ));
+
+    // An unknown compression type.
+    contents[offset] = 9;
+
+    assert!(matches!(
+        ElfFile::from_bytes(&contents).unwrap().section_data(index),
+        Err(ElfError::UnsupportedCompression {
+            compression_type: 9
+        })
+    ));
+}
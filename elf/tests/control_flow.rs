@@ -0,0 +1,168 @@
+/// Checks recursive-descent disassembly and the `cfg` command. The fixture
+/// is assembled from `test/cfg.s`, in `test/`, with GNU as and ld 2.40:
+///
+///     as cfg.s -o cfg.o && ld -nostdlib cfg.o -o cfg
+///
+/// It has a jump table and a string in `.text`, which a linear sweep
+/// decodes as instructions, and a function with no symbol. The golden file
+/// is our own output, checked by hand.
+///
+mod common;
+
+use common::{fixture, run, run_ok};
+
+use elf::disasm::cfg::{ControlFlow, Edge, EdgeKind};
+use elf::disasm::{Disassembler, Syntax};
+use elf::ElfFile;
+
+fn control_flow(path: &str) -> ControlFlow {
+    let mut elf_file = ElfFile::open(&fixture(path)).unwrap();
+    let disassembler = Disassembler::for_file(&mut elf_file, Syntax::Att).unwrap();
+    ControlFlow::for_file(&mut elf_file, disassembler).unwrap()
+}
+
+fn no_symbols(_: u64) -> Option<String> {
+    None
+}
+
+#[test]
+fn finds_functions_from_symbols_and_calls() {
+    let control_flow = control_flow("cfg");
+    let functions: Vec<(&str, u64)> = control_flow
+        .functions
+        .iter()
+        .map(|function| (function.name.as_str(), function.address))
+        .collect();
+    assert_eq!(
+        functions,
+        [
+            ("_start", 0x401000),
+            ("classify", 0x401018),
+            ("count", 0x401059),
+            ("sub_401073", 0x401073),
+            ("fail", 0x40107b),
+        ]
+    );
+
+    // Calls, and the jump to `fail` that never comes back.
+    let start = control_flow.function("_start").unwrap();
+    assert_eq!(start.calls, [0x401018, 0x401059, 0x401073, 0x40107b]);
+    assert_eq!(start.blocks.len(), 1);
+}
+
+#[test]
+fn splits_functions_into_basic_blocks() {
+    let control_flow = control_flow("cfg");
+    let count = control_flow.function("count").unwrap();
+    let starts: Vec<u64> = count.blocks.iter().map(|block| block.start).collect();
+    assert_eq!(starts, [0x401059, 0x40105f, 0x401065]);
+
+    let branch = |target, kind| Edge { target, kind };
+    let root = count.block(0x401059).unwrap();
+    assert_eq!(root.end(), 0x40105f);
+    assert_eq!(
+        root.successors,
+        [
+            branch(0x401065, EdgeKind::Taken),
+            branch(0x40105f, EdgeKind::NotTaken)
+        ]
+    );
+    let body = count.block(0x40105f).unwrap();
+    assert_eq!(
+        body.successors,
+        [
+            branch(0x40105f, EdgeKind::Taken),
+            branch(0x401065, EdgeKind::NotTaken)
+        ]
+    );
+    assert!(count.block(0x401065).unwrap().successors.is_empty());
+    assert!(count.block(0x401060).is_none());
+}
+
+#[test]
+fn skips_data_between_code() {
+    let control_flow = control_flow("cfg");
+
+    // The jump table after the indirect jump isn't decoded, and neither are
+    // its cases, which nothing else reaches.
+    let classify = control_flow.function("classify").unwrap();
+    let starts: Vec<u64> = classify.blocks.iter().map(|block| block.start).collect();
+    assert_eq!(starts, [0x401018, 0x40101d, 0x401057]);
+    assert_eq!(classify.calls, [0x401059]);
+
+    // Nor is the string after `count`, which starts with `ud2` and a call.
+    let addresses: Vec<u64> = control_flow
+        .functions
+        .iter()
+        .flat_map(|function| &function.blocks)
+        .flat_map(|block| &block.instructions)
+        .map(|instruction| instruction.address())
+        .collect();
+    assert!(addresses
+        .iter()
+        .all(|address| !(0x401066..0x401073).contains(address)));
+    assert!(addresses.windows(2).all(|pair| pair[0] < pair[1]));
+}
+
+#[test]
+fn follows_aarch64_and_riscv_code() {
+    for path in ["disasm-aarch64", "disasm-riscv"] {
+        let control_flow = control_flow(path);
+        let sum = control_flow.function("sum").unwrap();
+        assert_eq!(sum.blocks.len(), 3, "{path}");
+        assert!(control_flow
+            .function("_start")
+            .unwrap()
+            .calls
+            .contains(&sum.address));
+    }
+}
+
+#[test]
+fn writes_graphs_in_dot() {
+    let control_flow = control_flow("cfg");
+    let dot = control_flow.function("count").unwrap().to_dot(&no_symbols);
+    assert_eq!(
+        dot,
+        "digraph \"count\" {\n\
+        \x20   node [shape=box, fontname=\"monospace\"];\n\
+        \x20   \"0x401059\" [label=\"401059: xor    %eax,%eax\\l\
+        40105b: test   %edi,%edi\\l40105d: jle    0x401065\\l\"];\n\
+        \x20   \"0x40105f\" [label=\"40105f: add    %edi,%eax\\l\
+        401061: dec    %edi\\l401063: jne    0x40105f\\l\"];\n\
+        \x20   \"0x401065\" [label=\"401065: ret\\l\"];\n\
+        \x20   \"0x401059\" -> \"0x401065\" [color=green];\n\
+        \x20   \"0x401059\" -> \"0x40105f\" [color=red];\n\
+        \x20   \"0x40105f\" -> \"0x40105f\" [color=green];\n\
+        \x20   \"0x40105f\" -> \"0x401065\" [color=red];\n\
+        }\n"
+    );
+}
+
+#[test]
+fn cfg_prints_blocks() {
+    let expected = std::fs::read_to_string(fixture("golden/cfg.txt")).unwrap();
+    assert_eq!(run_ok(&["cfg", "cfg"]), expected);
+}
+
+#[test]
+fn cfg_picks_functions() {
+    let output = run_ok(&["cfg", "cfg", "-f", "fail", "--function", "sub_401073"]);
+    assert!(output.starts_with("\nfail at 0x40107b, 1 block\n"));
+    assert!(output.contains("\nsub_401073 at 0x401073, 1 block\n"));
+    assert!(!output.contains("count at"));
+
+    let dot = run_ok(&["cfg", "cfg", "-f", "count", "--dot"]);
+    assert!(dot.starts_with("digraph \"count\" {\n"));
+    assert!(dot.contains("jle    401065 <count+0xc>\\l"));
+
+    let intel = run_ok(&["cfg", "cfg", "-f", "count", "--intel"]);
+    assert!(intel.contains("401059:\txor    eax,eax\n"));
+}
+
+#[test]
+fn cfg_fails_on_unknown_functions() {
+    let output = run(&["cfg", "cfg", "-f", "nothing"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("No function named nothing."));
+}
@@ -0,0 +1,286 @@
+/// Checks reading the tree of entries in .debug_info, and the functions and
+/// type layouts found in it. The fixtures are built in `test/`, from
+/// `layouts.c` with gcc, and from `tiny-rust.rs` with rustc, whose DWARF 5
+/// uses the forms that index into .debug_str_offsets, .debug_addr, and
+/// .debug_rnglists:
+///
+///     P="-O2 -fdebug-prefix-map=$PWD=/src"
+///     gcc -g -gdwarf-4 $P layouts.c -o layouts-dwarf4
+///     gcc -g -gdwarf-5 $P layouts.c -o layouts-dwarf5
+///     rustc -g -C dwarf-version=5 -C panic=abort -C opt-level=1 \
+///         -C link-arg=-nostartfiles -C link-arg=-static \
+///         --remap-path-prefix=$PWD=/src tiny-rust.rs -o tiny-rust
+///
+mod common;
+
+use common::{fixture, run};
+
+use elf::dwarf::info::DebugInfo;
+use elf::dwarf::layout::{Hole, Layout};
+use elf::dwarf::unit::AttributeValue;
+use elf::dwarf::{DW_AT_COMP_DIR, DW_TAG_COMPILE_UNIT, DW_TAG_STRUCTURE_TYPE};
+use elf::{ElfError, ElfFile};
+
+fn debug_info(name: &str) -> DebugInfo {
+    ElfFile::open(&fixture(name)).unwrap().debug_info().unwrap()
+}
+
+/// The ranges of each function, as (start, end) pairs.
+fn function_ranges(debug_info: &DebugInfo) -> Vec<(String, Vec<(u64, u64)>)> {
+    debug_info
+        .functions()
+        .unwrap()
+        .into_iter()
+        .map(|function| {
+            let ranges = function.ranges.iter().map(|r| (r.start, r.end)).collect();
+            (function.name.unwrap(), ranges)
+        })
+        .collect()
+}
+
+fn layout(debug_info: &DebugInfo, name: &str) -> Layout {
+    debug_info
+        .layouts()
+        .into_iter()
+        .find(|layout| layout.name.as_deref() == Some(name))
+        .unwrap()
+}
+
+#[test]
+fn reads_the_tree_of_entries() {
+    for (name, version) in [("layouts-dwarf4", 4), ("layouts-dwarf5", 5)] {
+        let debug_info = debug_info(name);
+        let unit = &debug_info.units()[0];
+        let root = unit.root().unwrap();
+
+        assert_eq!(unit.header.version, version);
+        assert_eq!(root.tag, DW_TAG_COMPILE_UNIT);
+        assert_eq!(root.name(), Some("layouts.c"));
+        assert_eq!(
+            root.attribute(DW_AT_COMP_DIR),
+            Some(&AttributeValue::String("/src".to_owned()))
+        );
+
+        // The structure's members are its children, and it's theirs
+        // parent, and can be found by its offset.
+        let point = unit
+            .children(root)
+            .find(|entry| entry.tag == DW_TAG_STRUCTURE_TYPE && entry.name() == Some("point"))
+            .unwrap();
+        let members: Vec<_> = unit.children(point).map(|entry| entry.name()).collect();
+        assert_eq!(members, [Some("x"), Some("y")]);
+
+        let x = unit.children(point).next().unwrap();
+        assert_eq!(unit.parent(x), Some(point));
+        assert_eq!(debug_info.entry_at(point.offset).unwrap().1, point);
+    }
+}
+
+#[test]
+fn lists_functions_with_their_ranges() {
+    for name in ["layouts-dwarf4", "layouts-dwarf5"] {
+        let debug_info = debug_info(name);
+
+        // sum_holes has a cold part (from a range list), before main.
+        assert_eq!(
+            function_ranges(&debug_info),
+            [
+                (
+                    "sum_holes".to_owned(),
+                    vec![(0x1070, 0x1076), (0x1250, 0x129e)]
+                ),
+                ("main".to_owned(), vec![(0x1080, 0x1145)]),
+                ("compare_points".to_owned(), vec![(0x1240, 0x1245)]),
+            ],
+            "{name}"
+        );
+
+        let external: Vec<_> = debug_info
+            .functions()
+            .unwrap()
+            .iter()
+            .map(|function| function.external)
+            .collect();
+        assert_eq!(external, [true, true, false]);
+    }
+}
+
+#[test]
+fn resolves_indexed_forms() {
+    let debug_info = debug_info("tiny-rust");
+
+    for unit in debug_info.units() {
+        for attribute in unit.entries.iter().flat_map(|entry| &entry.attributes) {
+            assert!(!matches!(
+                attribute.value,
+                AttributeValue::StringIndex(_)
+                    | AttributeValue::AddressIndex(_)
+                    | AttributeValue::RangeListIndex(_)
+                    | AttributeValue::LocationListIndex(_)
+            ));
+        }
+    }
+
+    assert_eq!(
+        function_ranges(&debug_info),
+        [
+            ("_start".to_owned(), vec![(0x201220, 0x201262)]),
+            ("total".to_owned(), vec![(0x201270, 0x20127f)]),
+        ]
+    );
+
+    // Rust reorders the fields, and the layout puts them in order.
+    let sample = layout(&debug_info, "Sample");
+    let members: Vec<_> = sample
+        .members
+        .iter()
+        .map(|member| {
+            (
+                member.type_name.as_str(),
+                member.name.as_str(),
+                member.byte_offset(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        members,
+        [("u64", "value", 0), ("u16", "small", 8), ("u8", "flag", 10)]
+    );
+    assert_eq!(sample.padding_bits(), 5 * 8);
+}
+
+#[test]
+fn finds_holes_and_padding() {
+    for name in ["layouts-dwarf4", "layouts-dwarf5"] {
+        let debug_info = debug_info(name);
+
+        let holes = layout(&debug_info, "holes");
+        assert_eq!(holes.size, 32);
+        assert_eq!(
+            holes.holes(),
+            [
+                Hole {
+                    before: 1,
+                    bits: 56
+                },
+                Hole {
+                    before: 3,
+                    bits: 16
+                }
+            ]
+        );
+        assert_eq!(holes.padding_bits(), 24);
+
+        // Version 4 gives bit offsets from the top of the storage unit,
+        // and version 5 from the start of the structure.
+        let flags = layout(&debug_info, "flags");
+        let bits: Vec<_> = flags
+            .members
+            .iter()
+            .map(|member| (member.bit_offset, member.bit_size, member.is_bit_field))
+            .collect();
+        assert_eq!(
+            bits,
+            [(0, 1, true), (1, 3, true), (8, 8, false), (16, 40, true)],
+            "{name}"
+        );
+        assert_eq!(flags.holes(), [Hole { before: 2, bits: 4 }]);
+
+        let union = layout(&debug_info, "number");
+        assert_eq!((union.kind, union.size), ("union", 8));
+        assert!(union.holes().is_empty());
+    }
+}
+
+#[test]
+fn writes_types_as_declarations() {
+    let debug_info = debug_info("layouts-dwarf5");
+
+    let members: Vec<_> = layout(&debug_info, "callbacks")
+        .members
+        .into_iter()
+        .map(|member| (member.type_name, member.name))
+        .collect();
+
+    let expected = [
+        ("const char *", "name"),
+        ("int", "(*compare)(const void *, const void *)"),
+        ("void", "(*done)(void)"),
+        ("struct point", "corners[2][2]"),
+        ("union number", "value"),
+        ("volatile int *const", "counter"),
+        ("struct holes *", "next"),
+        ("char", "line[80]"),
+    ];
+    assert_eq!(
+        members,
+        expected.map(|(type_name, name)| (type_name.to_owned(), name.to_owned()))
+    );
+}
+
+#[test]
+fn layout_prints_like_pahole() {
+    let output = run(&["layout", "layouts-dwarf5", "holes"]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "struct holes {\n\
+         \tchar                       tag;                  /*     0     1 */\n\
+         \n\
+         \t/* XXX 7 bytes hole, try to pack */\n\
+         \n\
+         \tlong int                   value;                /*     8     8 */\n\
+         \tshort int                  count;                /*    16     2 */\n\
+         \n\
+         \t/* XXX 2 bytes hole, try to pack */\n\
+         \n\
+         \tpoint_t                    origin;               /*    20     8 */\n\
+         \tchar                       last;                 /*    28     1 */\n\
+         \n\
+         \t/* size: 32, cachelines: 1, members: 5 */\n\
+         \t/* sum members: 20, holes: 2, sum holes: 9 */\n\
+         \t/* padding: 3 */\n\
+         \t/* last cacheline: 32 bytes */\n\
+         };\n"
+    );
+
+    let output = run(&["layout", "layouts-dwarf5", "missing"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .contains("No structure, class, or union named missing."));
+}
+
+#[test]
+fn functions_lists_each_range() {
+    let output = run(&["functions", "layouts-dwarf4"]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "0x0000000000001070      6  sum_holes\n\
+         0x0000000000001080    197  main\n\
+         0x0000000000001240      5  compare_points\n\
+         0x0000000000001250     78  sum_holes\n"
+    );
+}
+
+#[test]
+fn reports_undefined_abbreviations() {
+    let mut contents = std::fs::read(fixture("layouts-dwarf5")).unwrap();
+    let offset = {
+        let elf_file = ElfFile::from_bytes(&contents).unwrap();
+        let index = elf_file.section_index_by_name(".debug_info").unwrap();
+
+        elf_file.section(index).unwrap().offset as usize
+    };
+
+    // The root entry's abbreviation code follows the 12-byte version 5 header.
+    contents[offset + 12] = 0x7f;
+
+    let error = ElfFile::from_bytes(&contents)
+        .unwrap()
+        .debug_info()
+        .unwrap_err();
+    assert!(matches!(error, ElfError::InvalidData { offset: 12, .. }));
+}
@@ -0,0 +1,148 @@
+/// Checks demangling C++ names, and Rust's legacy and v0 names, against
+/// what GNU's demangler makes of them, as in:
+///
+///     echo _ZNKSt13runtime_error4whatEv | c++filt
+///
+/// `readelf_compat.rs` checks `--demangle` against `readelf -C`.
+///
+use std::borrow::Cow;
+
+use elf::demangle::{demangle, demangle_symbol};
+
+fn demangled(mangled: &str) -> String {
+    demangle(mangled).unwrap_or_else(|| panic!("{mangled} wasn't demangled"))
+}
+
+#[test]
+fn demangles_cpp_names() {
+    let cases = [
+        (
+            "_ZNSt6vectorIiSaIiEE9push_backEOi",
+            "std::vector<int, std::allocator<int> >::push_back(int&&)",
+        ),
+        (
+            "_ZNKSt13runtime_error4whatEv",
+            "std::runtime_error::what() const",
+        ),
+        (
+            "_ZTVN10__cxxabiv117__class_type_infoE",
+            "vtable for __cxxabiv1::__class_type_info",
+        ),
+        (
+            "_ZZ4mainENKUlvE_clEv",
+            "main::{lambda()#1}::operator()() const",
+        ),
+        ("_ZN3fooC2Ev", "foo::foo()"),
+    ];
+    for (mangled, expected) in cases {
+        assert_eq!(demangled(mangled), expected);
+    }
+}
+
+#[test]
+fn resolves_template_parameters_where_theyre_used() {
+    // `T_` in the return type is `std::move`'s argument.
+    assert_eq!(
+        demangled("_ZSt4moveIRSt6vectorIiSaIiEEEONSt16remove_referenceIT_E4typeEOS5_"),
+        "std::remove_reference<std::vector<int, std::allocator<int> >&>::type&& \
+         std::move<std::vector<int, std::allocator<int> >&>(std::vector<int, std::allocator<int> >&)"
+    );
+    assert_eq!(
+        demangled(
+            "_ZSt12construct_atIN3kfr11file_readerIvEEJRP8_IO_FILEEEDTgsnwcvPvLi0E_T_\
+             pispcl7declvalIT0_EEEEPS7_DpOS8_"
+        ),
+        "decltype (::new ((void*)(0)) kfr::file_reader<void>((declval<_IO_FILE*&>)())) \
+         std::construct_at<kfr::file_reader<void>, _IO_FILE*&>(kfr::file_reader<void>*, _IO_FILE*&)"
+    );
+}
+
+#[test]
+fn demangles_rust_legacy_names() {
+    assert_eq!(
+        demangled("_ZN4core3fmt5write17h0123456789abcdefE"),
+        "core::fmt::write"
+    );
+    assert_eq!(
+        demangled(
+            "_ZN4core3ptr85drop_in_place$LT$std..rt..lang_start$LT$$LP$$RP$$GT$\
+             ..$u7b$$u7b$closure$u7d$$u7d$$GT$17h0123456789abcdefE"
+        ),
+        "core::ptr::drop_in_place<std::rt::lang_start<()>::{{closure}}>"
+    );
+
+    // Without a hash, it's a C++ name.
+    assert_eq!(
+        demangled("_ZN3foo3bar17h0000000000000000E"),
+        "foo::bar::h0000000000000000"
+    );
+}
+
+#[test]
+fn demangles_rust_v0_names() {
+    let cases = [
+        ("_RNvCs1234_7mycrate3foo", "mycrate::foo"),
+        (
+            "_RNvMNtCs1234_7mycrate3fooNtB2_3Bar3new",
+            "<mycrate::foo::Bar>::new",
+        ),
+        (
+            "_RNvYNtC3foo3BazNtC3foo5Trait3qux",
+            "<foo::Baz as foo::Trait>::qux",
+        ),
+        (
+            "_RINvC3foo3barKpKhff_Kan1_Kb1_EB2_",
+            "foo::bar::<_, 255, -1, true>",
+        ),
+        (
+            "_RINvC3foo3barFG_RL0_hEuEB3_",
+            "foo::bar::<for<'a> fn(&'a u8)>",
+        ),
+        ("_RNvNCNvC3foo3bar0s_3baz", "foo::bar::{closure#0}::baz"),
+        ("_RNvC3foou8gdabazuh", "foo::üððïöú"),
+    ];
+    for (mangled, expected) in cases {
+        assert_eq!(demangled(mangled), expected);
+    }
+}
+
+#[test]
+fn leaves_other_names_alone() {
+    for name in ["main", "_Zfoo", "_ZN3foo", "_RNvC3foo", "_R"] {
+        assert_eq!(demangle(name), None, "{name}");
+    }
+}
+
+#[test]
+fn keeps_version_suffixes() {
+    assert_eq!(
+        demangle_symbol("_ZNKSt13runtime_error4whatEv@GLIBCXX_3.4"),
+        "std::runtime_error::what() const@GLIBCXX_3.4"
+    );
+    assert_eq!(demangle_symbol("_ZdlPv@plt"), "operator delete(void*)@plt");
+    assert!(matches!(
+        demangle_symbol("printf@@GLIBC_2.2.5"),
+        Cow::Borrowed("printf@@GLIBC_2.2.5")
+    ));
+}
+
+#[test]
+fn gives_up_on_names_nested_too_deeply() {
+    // Packs of packs, each a level deeper.
+    let packs = format!("_Z1fI{}iE", "I".repeat(30_000));
+    assert_eq!(demangle_symbol(&packs), packs);
+
+    // A prefix of that many names, each the scope of the next, or a name
+    // with that many ABI tags or clone suffixes, each wrapping the last.
+    for name in [
+        format!("_ZN{}E", "1a".repeat(100_000)),
+        format!("_ZN1a{}E", "B1a".repeat(100_000)),
+        format!("_Z1fv{}", ".a".repeat(100_000)),
+    ] {
+        assert_eq!(demangle_symbol(&name), name);
+    }
+
+    // A hundred scopes is still a name.
+    let scopes = format!("_ZN{}E", "1a".repeat(100));
+    assert_eq!(demangled(&scopes), vec!["a"; 100].join("::"));
+}
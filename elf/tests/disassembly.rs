@@ -0,0 +1,375 @@
+/// Checks the x86-64 instruction decoder, and the `disasm` command against
+/// GNU objdump. The fixture is assembled from `test/disasm.s`, in `test/`,
+/// with GNU as and ld 2.40:
+///
+///     as disasm.s -o disasm.o && ld -nostdlib disasm.o -o disasm
+///
+/// and the golden files with GNU objdump 2.40:
+///
+///     objdump -d disasm > golden/disasm-att.txt
+///     objdump -d -M intel disasm > golden/disasm-intel.txt
+///
+/// The AArch64 decoder is checked the same way against llvm-objdump 14,
+/// on an object file assembled from `test/disasm-aarch64.s` (there being
+/// no AArch64 linker to hand):
+///
+///     llvm-mc -triple=aarch64 -filetype=obj disasm-aarch64.s -o disasm-aarch64
+///
+/// llvm-objdump lays out its lines differently, so the golden file is our
+/// own output, with each instruction as `llvm-objdump -d` writes it. So is
+/// the RISC-V one, from `test/disasm-riscv.s`:
+///
+///     llvm-mc -triple=riscv64 -mattr=+m,+a,+f,+d,+c -filetype=obj \
+///         disasm-riscv.s -o disasm-riscv
+///
+mod common;
+
+use common::{fixture, run, run_ok};
+
+use elf::disasm::x86::{decode, Hint, Instruction, Operand, Register};
+use elf::disasm::{aarch64, riscv, Flow, Syntax};
+use elf::parse::attributes::{AttributeValue, TAG_RISCV_ARCH};
+use elf::ElfFile;
+
+fn no_symbols(_: u64) -> Option<String> {
+    None
+}
+
+/// Decodes `bytes` at 0x1000, and writes the instruction in both syntaxes.
+fn disassemble(bytes: &[u8]) -> (usize, String, String) {
+    let instruction = decode(bytes, 0x1000);
+    (
+        instruction.len,
+        instruction.format(Syntax::Att, &no_symbols),
+        instruction.format(Syntax::Intel, &no_symbols),
+    )
+}
+
+#[test]
+fn decodes_operands_in_both_syntaxes() {
+    assert_eq!(
+        disassemble(&[0x4c, 0x0f, 0x4d, 0x64, 0xd8, 0x10]),
+        (
+            6,
+            "cmovge 0x10(%rax,%rbx,8),%r12".to_owned(),
+            "cmovge r12,QWORD PTR [rax+rbx*8+0x10]".to_owned()
+        )
+    );
+    assert_eq!(
+        disassemble(&[0xc7, 0x44, 0x24, 0x08, 0x2a, 0x00, 0x00, 0x00]),
+        (
+            8,
+            "movl   $0x2a,0x8(%rsp)".to_owned(),
+            "mov    DWORD PTR [rsp+0x8],0x2a".to_owned()
+        )
+    );
+    assert_eq!(
+        disassemble(&[0x62, 0xf1, 0x6d, 0xc9, 0xfe, 0xd9]),
+        (
+            6,
+            "vpaddd %zmm1,%zmm2,%zmm3{%k1}{z}".to_owned(),
+            "vpaddd zmm3{k1}{z},zmm2,zmm1".to_owned()
+        )
+    );
+}
+
+#[test]
+fn shows_prefixes_as_objdump_does() {
+    let att = |bytes: &[u8]| disassemble(bytes).1;
+
+    assert_eq!(att(&[0xf3, 0xa4]), "rep movsb %ds:(%rsi),%es:(%rdi)");
+    assert_eq!(
+        att(&[0xf0, 0x48, 0x0f, 0xb1, 0x0a]),
+        "lock cmpxchg %rcx,(%rdx)"
+    );
+    assert_eq!(
+        att(&[0xf2, 0xf0, 0x01, 0x07]),
+        "xacquire lock add %eax,(%rdi)"
+    );
+    assert_eq!(att(&[0xf3, 0x89, 0x07]), "xrelease mov %eax,(%rdi)");
+    // An operand size prefix that a jump doesn't use.
+    assert_eq!(att(&[0x66, 0xeb, 0x00]), "data16 jmp 0x1003");
+    assert_eq!(att(&[0xf3, 0x0f, 0x1e, 0xfa]), "endbr64");
+}
+
+#[test]
+fn writes_branch_hints() {
+    assert_eq!(
+        disassemble(&[0x3e, 0x74, 0x00]),
+        (3, "je,pt  0x1003".to_owned(), "ds je  0x1003".to_owned())
+    );
+    assert_eq!(
+        disassemble(&[0xf2, 0x2e, 0x74, 0x00]),
+        (
+            4,
+            "bnd je,pn 0x1004".to_owned(),
+            "bnd cs je 0x1004".to_owned()
+        )
+    );
+
+    // The operand size prefix is used (for a 16-bit displacement), so the
+    // hint's prefix is the only one shown.
+    let instruction = decode(
+        &[0x66, 0x2e, 0x0f, 0x80, 0x84, 0x00, 0x00, 0x00, 0x00],
+        0x1000,
+    );
+    assert_eq!(instruction.hint, Some(Hint::NotTaken));
+    assert_eq!(
+        disassemble(&[0x66, 0x2e, 0x0f, 0x80, 0x84, 0x00, 0x00, 0x00, 0x00]),
+        (6, "jo,pn  0x108a".to_owned(), "cs jo  0x108a".to_owned())
+    );
+}
+
+#[test]
+fn marks_what_cannot_be_decoded() {
+    let instruction = decode(&[0x0f, 0xb2, 0xc0], 0);
+    assert!(instruction.is_bad());
+    assert_eq!(instruction.len, 2);
+
+    // An instruction cut short takes a byte, as objdump's `.byte` does.
+    assert_eq!(disassemble(&[0x48, 0x8b]).0, 1);
+    assert_eq!(disassemble(&[0xe8, 0x00]).1, ".byte 0xe8");
+    assert_eq!(disassemble(&[]).0, 0);
+}
+
+#[test]
+fn finds_where_control_goes() {
+    let flow = |bytes: &[u8]| decode(bytes, 0x1000).flow;
+
+    assert_eq!(flow(&[0x90]), Flow::Next);
+    assert_eq!(flow(&[0xe8, 0x0b, 0x00, 0x00, 0x00]), Flow::Call(0x1010));
+    assert_eq!(flow(&[0xeb, 0xfe]), Flow::Jump(0x1000));
+    assert_eq!(flow(&[0x74, 0x10]), Flow::Branch(0x1012));
+    assert_eq!(flow(&[0xff, 0xe0]), Flow::IndirectJump);
+    assert_eq!(flow(&[0xff, 0x15, 0, 0, 0, 0]), Flow::IndirectCall);
+    assert_eq!(flow(&[0xc3]), Flow::Return);
+    assert_eq!(flow(&[0xf4]), Flow::Stop);
+}
+
+#[test]
+fn finds_rip_relative_addresses() {
+    let instruction: Instruction = decode(&[0x48, 0x8d, 0x35, 0x04, 0x10, 0x00, 0x00], 0x401005);
+
+    assert_eq!(instruction.rip_target(), Some(0x402010));
+    assert_eq!(
+        instruction.operands[0],
+        Operand::Register(Register::Gpr { number: 6, size: 8 })
+    );
+    assert_eq!(
+        instruction.format(Syntax::Att, &|address| Some(format!("{address:x}"))),
+        "lea    0x1004(%rip),%rsi        # 402010 <402010>"
+    );
+}
+
+/// Decodes a word at 0x1000, as AArch64 code, and writes it out.
+fn disassemble_aarch64(word: u32) -> String {
+    aarch64::decode(&word.to_le_bytes(), 0x1000).format(&no_symbols)
+}
+
+#[test]
+fn decodes_aarch64_instructions() {
+    assert_eq!(
+        disassemble_aarch64(0xa9bf7bfd),
+        "stp\tx29, x30, [sp, #-16]!"
+    );
+    assert_eq!(disassemble_aarch64(0x38616803), "ldrb\tw3, [x0, x1]");
+    assert_eq!(
+        disassemble_aarch64(0x92089c83),
+        "and\tx3, x4, #0xff00ff00ff00ff00"
+    );
+    assert_eq!(disassemble_aarch64(0xd53bd040), "mrs\tx0, TPIDR_EL0");
+    assert_eq!(disassemble_aarch64(0x1e7e900b), "fmov\td11, #-1.25000000");
+    assert_eq!(
+        disassemble_aarch64(0x4e1f401e),
+        "tbl\tv30.16b, { v0.16b, v1.16b, v2.16b }, v31.16b"
+    );
+    // llvm-objdump notes what a shifted immediate comes to.
+    assert_eq!(
+        disassemble_aarch64(0x91400420),
+        "add\tx0, x1, #1, lsl #12     // =4096"
+    );
+}
+
+#[test]
+fn marks_what_aarch64_cannot_decode() {
+    let instruction = aarch64::decode(&0xffffffffu32.to_le_bytes(), 0);
+    assert!(instruction.is_bad());
+    assert_eq!(instruction.len, 4);
+    assert_eq!(aarch64::decode(&[0x1f, 0x20], 0).len, 2);
+}
+
+#[test]
+fn finds_where_aarch64_control_goes() {
+    let flow = |word: u32| aarch64::decode(&word.to_le_bytes(), 0x1000).flow;
+
+    assert_eq!(flow(0xd503201f), Flow::Next);
+    assert_eq!(flow(0x94000004), Flow::Call(0x1010));
+    assert_eq!(flow(0x17ffffff), Flow::Jump(0xffc));
+    assert_eq!(flow(0x54ffffa1), Flow::Branch(0xff4));
+    assert_eq!(flow(0xb40000a1), Flow::Branch(0x1014));
+    assert_eq!(flow(0xd61f0060), Flow::IndirectJump);
+    assert_eq!(flow(0xd63f0080), Flow::IndirectCall);
+    assert_eq!(flow(0xd65f03c0), Flow::Return);
+    assert_eq!(flow(0xd4207d00), Flow::Stop);
+}
+
+/// Decodes `bytes` at 0x1000, as RV64GC code, and writes the instruction.
+fn disassemble_riscv(bytes: &[u8]) -> String {
+    riscv::decode(bytes, 0x1000, &riscv::Isa::RV64GC).format(&no_symbols)
+}
+
+#[test]
+fn decodes_riscv_instructions() {
+    assert_eq!(
+        disassemble_riscv(&0xfe010113u32.to_le_bytes()),
+        "addi\tsp, sp, -32"
+    );
+    assert_eq!(
+        disassemble_riscv(&0x00b12423u32.to_le_bytes()),
+        "sw\ta1, 8(sp)"
+    );
+    assert_eq!(
+        disassemble_riscv(&0x0405252fu32.to_le_bytes()),
+        "amoadd.w.aq\ta0, zero, (a0)"
+    );
+    assert_eq!(
+        disassemble_riscv(&0xc0051553u32.to_le_bytes()),
+        "fcvt.w.s\ta0, fa0, rtz"
+    );
+    assert_eq!(
+        disassemble_riscv(&0x30002573u32.to_le_bytes()),
+        "csrr\ta0, mstatus"
+    );
+    // Compressed instructions are written as what they expand to.
+    assert_eq!(disassemble_riscv(&[0x41, 0x11]), "addi\tsp, sp, -16");
+    assert_eq!(disassemble_riscv(&[0x82, 0x80]), "ret");
+    assert_eq!(disassemble_riscv(&[0x05, 0x40]), "c.li\tzero, 1");
+
+    // The upper halves of counters are only there on RV32, whose jumps
+    // wrap around at 32 bits.
+    let rv32 = riscv::Isa::parse("rv32imafdc").unwrap();
+    let on_rv32 = |word: u32| riscv::decode(&word.to_le_bytes(), 0x10, &rv32);
+    assert_eq!(on_rv32(0xc8002573).format(&no_symbols), "rdcycleh\ta0");
+    assert_eq!(on_rv32(0xfe1ff06f).format(&no_symbols), "j\t0xfffffff0");
+    assert_eq!(
+        disassemble_riscv(&0xc8002573u32.to_le_bytes()),
+        "csrr\ta0, 3200"
+    );
+}
+
+#[test]
+fn marks_what_riscv_cannot_decode() {
+    let instruction = riscv::decode(&0xffffffffu32.to_le_bytes(), 0, &riscv::Isa::RV64GC);
+    assert!(instruction.is_bad());
+    assert_eq!(instruction.len, 4);
+    assert_eq!(riscv::decode(&[0x13, 0x05], 0, &riscv::Isa::RV64GC).len, 2);
+
+    // Instructions of extensions the code doesn't use don't decode.
+    let rv64i = riscv::Isa::parse("rv64i2p0").unwrap();
+    assert!(riscv::decode(&0x02b50533u32.to_le_bytes(), 0, &rv64i).is_bad());
+    let compressed = riscv::decode(&[0x41, 0x11], 0, &rv64i);
+    assert!(compressed.is_bad());
+    assert_eq!(compressed.len, 2);
+}
+
+#[test]
+fn reads_riscv_isa_strings() {
+    let isa = riscv::Isa::parse("rv64i2p1_m2p0_a2p1_c2p0_zicsr2p0").unwrap();
+    assert_eq!(
+        isa,
+        riscv::Isa {
+            xlen: 64,
+            m: true,
+            a: true,
+            f: false,
+            d: false,
+            c: true,
+            zicsr: true,
+            zifencei: false,
+        }
+    );
+    assert_eq!(riscv::Isa::parse("rv64gc"), Some(riscv::Isa::RV64GC));
+    assert_eq!(riscv::Isa::parse("rv32imac").unwrap().xlen, 32);
+    assert_eq!(riscv::Isa::parse("x86_64"), None);
+}
+
+#[test]
+fn reads_riscv_attributes() {
+    let mut elf_file = ElfFile::open(&fixture("disasm-riscv")).unwrap();
+    let subsections = elf_file.build_attributes().unwrap();
+
+    assert_eq!(subsections.len(), 1);
+    assert_eq!(subsections[0].vendor, "riscv");
+    assert_eq!(
+        subsections[0].get(TAG_RISCV_ARCH),
+        Some(&AttributeValue::String(
+            "rv64i2p0_m2p0_a2p0_f2p0_d2p0_c2p0".to_owned()
+        ))
+    );
+    assert_eq!(
+        riscv::Isa::for_file(&mut elf_file).unwrap(),
+        riscv::Isa::RV64GC
+    );
+}
+
+#[test]
+fn finds_where_riscv_control_goes() {
+    let flow = |bytes: &[u8]| riscv::decode(bytes, 0x1000, &riscv::Isa::RV64GC).flow;
+
+    assert_eq!(flow(&0x00000013u32.to_le_bytes()), Flow::Next);
+    assert_eq!(flow(&0x010000efu32.to_le_bytes()), Flow::Call(0x1010));
+    assert_eq!(flow(&0xffdff06fu32.to_le_bytes()), Flow::Jump(0xffc));
+    assert_eq!(flow(&0xfe050ae3u32.to_le_bytes()), Flow::Branch(0xff4));
+    assert_eq!(flow(&[0x01, 0xc9]), Flow::Branch(0x1010));
+    assert_eq!(flow(&[0x02, 0x85]), Flow::IndirectJump);
+    assert_eq!(flow(&[0x82, 0x95]), Flow::IndirectCall);
+    assert_eq!(flow(&[0x82, 0x80]), Flow::Return);
+    assert_eq!(flow(&0x00100073u32.to_le_bytes()), Flow::Stop);
+}
+
+// The fixture is run from `test/`, so that the path it prints matches.
+
+#[test]
+fn disasm_matches_objdump() {
+    let expected = std::fs::read_to_string(fixture("golden/disasm-att.txt")).unwrap();
+    assert_eq!(run_ok(&["disasm", "disasm"]), expected);
+}
+
+#[test]
+fn disasm_matches_objdump_in_intel_syntax() {
+    let expected = std::fs::read_to_string(fixture("golden/disasm-intel.txt")).unwrap();
+    assert_eq!(run_ok(&["disasm", "--intel", "disasm"]), expected);
+}
+
+#[test]
+fn disasm_matches_llvm_objdump_on_aarch64() {
+    let expected = std::fs::read_to_string(fixture("golden/disasm-aarch64.txt")).unwrap();
+    let output = run_ok(&["disasm", "disasm-aarch64"]);
+    assert_eq!(output, expected);
+
+    // The literal pool after `memory` is data, by its `$d` mapping symbol.
+    assert!(output.contains("  dc:\t55667788 \t.word\t0x55667788\n"));
+}
+
+#[test]
+fn disasm_matches_llvm_objdump_on_riscv() {
+    let expected = std::fs::read_to_string(fixture("golden/disasm-riscv.txt")).unwrap();
+    let output = run_ok(&["disasm", "disasm-riscv"]);
+    assert_eq!(output, expected);
+
+    // Compressed instructions are padded to the same width.
+    assert!(output.contains("  1a:\t1141                \taddi\tsp, sp, -16\n"));
+}
+
+#[test]
+fn disasm_picks_sections_by_name() {
+    let output = run_ok(&["disasm", "-j", ".rodata", "disasm"]);
+
+    assert!(output.contains("Disassembly of section .rodata:"));
+    assert!(output.contains("<table>:"));
+    assert!(!output.contains("Disassembly of section .text:"));
+
+    let output = run(&["disasm", "-j", ".nothing", "disasm"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("No section named .nothing."));
+}
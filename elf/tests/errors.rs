@@ -0,0 +1,88 @@
+/// Checks that malformed and unsupported files fail with the
+/// `ElfError` variant that describes what's wrong with them.
+///
+mod common;
+
+use common::read_fixture;
+
+use elf::parse::types::SHT_SYMTAB;
+use elf::{ElfError, ElfFile};
+
+#[test]
+fn rejects_files_that_are_not_elf() {
+    assert!(matches!(
+        ElfFile::from_bytes(b"#!/bin/sh\n"),
+        Err(ElfError::BadMagic)
+    ));
+    assert!(matches!(ElfFile::from_bytes(b""), Err(ElfError::BadMagic)));
+}
+
+#[test]
+fn rejects_unsupported_classes() {
+    let mut contents = read_fixture("forwarding");
+    contents[4] = 1; // ELFCLASS32
+
+    assert!(matches!(
+        ElfFile::from_bytes(&contents),
+        Err(ElfError::UnsupportedClass {
+            class: 1,
+            endianness: 1
+        })
+    ));
+}
+
+#[test]
+fn reports_truncated_files() {
+    let contents = read_fixture("forwarding");
+
+    assert!(matches!(
+        ElfFile::from_bytes(&contents[..40]),
+        Err(ElfError::Truncated {
+            what: "ELF header",
+            offset: 0,
+            needed: 64
+        })
+    ));
+    assert!(matches!(
+        ElfFile::from_bytes(&contents[..100]),
+        Err(ElfError::Truncated {
+            what: "Program header table",
+            offset: 0x40,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn reports_invalid_section_indices() {
+    let contents = read_fixture("forwarding");
+    let mut elf_file = ElfFile::from_bytes(&contents).unwrap();
+    let count = elf_file.section_headers.len();
+
+    assert!(matches!(
+        elf_file.section_bytes(count),
+        Err(ElfError::InvalidIndex { what: "section", index }) if index == count
+    ));
+}
+
+#[test]
+fn reports_symbols_out_of_range() {
+    let contents = read_fixture("forwarding");
+    let mut elf_file = ElfFile::from_bytes(&contents).unwrap();
+    let table = elf_file.section_indices_by_type(SHT_SYMTAB)[0];
+
+    assert!(matches!(
+        elf_file.symbol(table, usize::MAX),
+        Err(ElfError::InvalidIndex {
+            what: "symbol",
+            index: usize::MAX
+        })
+    ));
+
+    // The entry's offset in the file doesn't fit in 64 bits.
+    elf_file.section_headers[table].header_data.offset = u64::MAX - 8;
+    assert!(matches!(
+        elf_file.symbol(table, 1),
+        Err(ElfError::Truncated { what: "Symbol", offset, .. }) if offset == u64::MAX - 8
+    ));
+}
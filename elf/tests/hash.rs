@@ -0,0 +1,132 @@
+/// Checks symbol lookup through the GNU and SysV hash tables, and the check
+/// for tables that disagree with the symbols. The fixture is a small library
+/// with both kinds of table:
+///
+///     printf '%s\n' 'int counter;' \
+///         'int add(int a, int b) { return a + b; }' \
+///         'int sub(int a, int b) { return a - b; }' \
+///         'int mul(int a, int b) { return a * b; }' \
+///         'void count(void) { counter++; }' \
+///         | gcc -shared -fPIC -Wl,--hash-style=both -x c - -o test/libhash
+///     strip test/libhash
+///
+mod common;
+
+use common::read_fixture;
+
+use elf::parse::hash::{gnu_hash, sysv_hash};
+use elf::parse::types::{SHT_GNU_HASH, SHT_HASH, SHT_PROGBITS};
+use elf::{ElfFile, Severity};
+
+const DEFINED: [(&str, usize); 5] = [
+    ("mul", 5),
+    ("add", 6),
+    ("count", 7),
+    ("counter", 8),
+    ("sub", 9),
+];
+
+/// Changes the type of the sections of type `from` to `to`, to hide them.
+fn retype_sections(contents: &mut [u8], from: u32, to: u32) {
+    let section_header_offset = u64::from_le_bytes(contents[0x28..0x30].try_into().unwrap());
+    let indices = ElfFile::from_bytes(contents)
+        .unwrap()
+        .section_indices_by_type(from);
+
+    for index in indices {
+        let entry = section_header_offset as usize + index * 64;
+        contents[entry + 4..entry + 8].copy_from_slice(&to.to_le_bytes());
+    }
+}
+
+fn assert_finds_defined_symbols(contents: &[u8]) {
+    let mut elf_file = ElfFile::from_bytes(contents).unwrap();
+
+    for (name, index) in DEFINED {
+        let (found, symbol) = elf_file.lookup_dynamic_symbol(name).unwrap().unwrap();
+
+        assert_eq!((found, symbol.name.as_str()), (index, name));
+    }
+
+    // Undefined symbols are in .dynsym, but aren't found.
+    assert!(elf_file
+        .lookup_dynamic_symbol("__cxa_finalize")
+        .unwrap()
+        .is_none());
+    assert!(elf_file.lookup_dynamic_symbol("missing").unwrap().is_none());
+}
+
+#[test]
+fn hash_functions_match_the_abi() {
+    assert_eq!(sysv_hash(b""), 0);
+    assert_eq!(sysv_hash(b"printf"), 0x077905a6);
+    assert_eq!(sysv_hash(b"flapenguin.me"), 0x03987915);
+
+    assert_eq!(gnu_hash(b""), 0x1505);
+    assert_eq!(gnu_hash(b"printf"), 0x156b2bb8);
+    assert_eq!(gnu_hash(b"flapenguin.me"), 0x8ae9f18e);
+}
+
+#[test]
+fn looks_up_symbols_through_either_table() {
+    let mut contents = read_fixture("libhash");
+    assert_finds_defined_symbols(&contents);
+
+    // With only the SysV table, and then with neither.
+    retype_sections(&mut contents, SHT_GNU_HASH, SHT_PROGBITS);
+    assert_finds_defined_symbols(&contents);
+
+    retype_sections(&mut contents, SHT_HASH, SHT_PROGBITS);
+    assert_finds_defined_symbols(&contents);
+}
+
+#[test]
+fn reports_tables_that_disagree_with_the_symbols() {
+    let mut contents = read_fixture("libhash");
+    assert!(ElfFile::from_bytes(&contents)
+        .unwrap()
+        .check_hash_tables()
+        .is_empty());
+
+    // Rename "add" to "adx", as if the string table had been patched.
+    let (dynstr, offsets) = {
+        let elf_file = ElfFile::from_bytes(&contents).unwrap();
+        let dynstr = elf_file.section_index_by_name(".dynstr").unwrap();
+        let offsets: Vec<u64> = [".gnu.hash", ".hash"]
+            .map(|name| {
+                let index = elf_file.section_index_by_name(name).unwrap();
+                elf_file.section(index).unwrap().offset
+            })
+            .to_vec();
+
+        (elf_file.section(dynstr).unwrap().offset as usize, offsets)
+    };
+
+    let at = dynstr
+        + contents[dynstr..]
+            .windows(5)
+            .position(|window| window == b"\0add\0")
+            .unwrap();
+    contents[at + 3] = b'x';
+
+    let mut elf_file = ElfFile::from_bytes(&contents).unwrap();
+    let diagnostics = elf_file.check_hash_tables();
+
+    assert_eq!(diagnostics.len(), 2);
+    assert!(diagnostics.iter().all(|d| d.severity == Severity::Warning));
+
+    assert_eq!(diagnostics[0].offset, offsets[0]);
+    assert!(diagnostics[0]
+        .message
+        .starts_with(".gnu.hash: Symbol 6 (adx) has the hash"));
+
+    assert_eq!(diagnostics[1].offset, offsets[1]);
+    assert_eq!(
+        diagnostics[1].message,
+        ".hash: Symbol 6 (adx) can't be found through the hash table."
+    );
+
+    // The dynamic linker wouldn't find it either.
+    assert!(elf_file.lookup_dynamic_symbol("adx").unwrap().is_none());
+    assert!(elf_file.lookup_dynamic_symbol("sub").unwrap().is_some());
+}
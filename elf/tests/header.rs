@@ -0,0 +1,141 @@
+/// Checks decoding the main header: the object type, OS/ABI and machine,
+/// and the processor-specific flags, in readelf's wording.
+///
+mod common;
+
+use common::fixture;
+
+use elf::parse::header::*;
+use elf::ElfFile;
+
+fn flags(machine: Machine, flags: u32) -> String {
+    flags_descriptions(machine, flags).join(", ")
+}
+
+#[test]
+fn decodes_the_fixtures_headers() {
+    let mut elf_file = ElfFile::open(&fixture("forwarding")).unwrap();
+    assert_eq!(elf_file.header.object_type(), ObjectType::Dynamic);
+    assert_eq!(elf_file.header.machine_type(), Machine::X86_64);
+    assert!(elf_file.header.flags_descriptions().is_empty());
+
+    // A dynamic object is a PIE if its dynamic section says so.
+    let is_pie = elf_file.is_pie().unwrap();
+    assert!(is_pie);
+    assert_eq!(
+        object_type_string(&elf_file.header, is_pie),
+        "DYN (Position-Independent Executable file)"
+    );
+
+    let mut elf_file = ElfFile::open(&fixture("unversioned")).unwrap();
+    let is_pie = elf_file.is_pie().unwrap();
+    assert_eq!(
+        object_type_string(&elf_file.header, is_pie),
+        "DYN (Shared object file)"
+    );
+
+    let elf_file = ElfFile::open(&fixture("disasm-riscv")).unwrap();
+    assert_eq!(elf_file.header.object_type(), ObjectType::Relocatable);
+    assert_eq!(elf_file.header.machine_type().description(), "RISC-V");
+    assert_eq!(
+        elf_file.header.flags_descriptions(),
+        ["RVC", "soft-float ABI"]
+    );
+}
+
+#[test]
+fn decodes_object_types() {
+    assert_eq!(ObjectType::from(ET_CORE).to_string(), "CORE (Core file)");
+    assert_eq!(ObjectType::from(0xfe01).to_string(), "OS Specific: (fe01)");
+    assert_eq!(
+        ObjectType::from(0xff02).to_string(),
+        "Processor Specific: (ff02)"
+    );
+    assert_eq!(ObjectType::from(9).to_string(), "<unknown>: 9");
+}
+
+#[test]
+fn describes_os_abis_by_machine() {
+    assert_eq!(OsAbi::from(3).description(Machine::X86_64), "UNIX - GNU");
+    assert_eq!(
+        OsAbi::from(255).description(Machine::X86_64),
+        "Standalone App"
+    );
+
+    // Values from 64 up mean different things on different machines.
+    assert_eq!(OsAbi::from(64), OsAbi::ProcessorSpecific(64));
+    assert_eq!(OsAbi::from(64).description(Machine::Arm), "ARM EABI");
+    assert_eq!(OsAbi::from(64).description(Machine::AmdGpu), "AMD HSA");
+    assert_eq!(
+        OsAbi::from(64).description(Machine::X86_64),
+        "<unknown: 40>"
+    );
+    assert_eq!(OsAbi::from(40).description(Machine::Arm), "<unknown: 28>");
+}
+
+#[test]
+fn describes_machines() {
+    assert_eq!(Machine::from(EM_AARCH64), Machine::AArch64);
+    assert_eq!(Machine::from(EM_AARCH64).id(), EM_AARCH64);
+    assert_eq!(Machine::from(0x1234), Machine::Unknown(0x1234));
+    assert_eq!(Machine::from(0x1234).description(), "<unknown>: 0x1234");
+}
+
+#[test]
+fn decodes_arm_flags() {
+    assert_eq!(
+        flags(Machine::Arm, 0x05000400),
+        "Version5 EABI, hard-float ABI"
+    );
+    assert_eq!(
+        flags(Machine::Arm, 0x05000200),
+        "Version5 EABI, soft-float ABI"
+    );
+    assert_eq!(flags(Machine::Arm, 0x04800000), "Version4 EABI, BE8");
+
+    // The float ABI bits only mean that from version 5.
+    assert_eq!(flags(Machine::Arm, 0x04000400), "Version4 EABI");
+
+    assert_eq!(
+        flags(Machine::Arm, 0x00000004),
+        "GNU EABI, interworking enabled, uses APCS/32"
+    );
+    assert_eq!(
+        flags(Machine::Arm, 0x00000028),
+        "GNU EABI, uses APCS/26, position independent"
+    );
+    assert_eq!(flags(Machine::Arm, 0x07000000), "<unrecognized EABI>");
+}
+
+#[test]
+fn decodes_mips_flags() {
+    assert_eq!(
+        flags(Machine::Mips, 0x70001007),
+        "noreorder, pic, cpic, o32, mips32r2"
+    );
+    assert_eq!(
+        flags(Machine::Mips, 0xa08b0420),
+        "abi2, nan2008, octeon, mips64r6"
+    );
+    assert_eq!(flags(Machine::Mips, 0x00000000), "mips1");
+    assert_eq!(
+        flags(Machine::Mips, 0xf07f5000),
+        "unknown CPU, unknown ABI, unknown ISA"
+    );
+}
+
+#[test]
+fn decodes_riscv_flags() {
+    assert_eq!(flags(Machine::RiscV, 0x0005), "RVC, double-float ABI");
+    assert_eq!(flags(Machine::RiscV, 0x0002), "single-float ABI");
+    assert_eq!(
+        flags(Machine::RiscV, 0x001f),
+        "RVC, quad-float ABI, RVE, TSO"
+    );
+}
+
+#[test]
+fn other_machines_have_no_flags() {
+    assert_eq!(flags(Machine::X86_64, 0xffffffff), "");
+    assert_eq!(flags(Machine::AArch64, 0x1), "");
+}
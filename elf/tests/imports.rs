@@ -0,0 +1,149 @@
+/// Checks finding imports through the PLT and GOT, and naming them in the
+/// `disasm` command as GNU objdump does. The fixtures are `test/frames`,
+/// with lazy binding, and `test/forwarding`, a C++ program built for
+/// Indirect Branch Tracking, whose stubs are in .plt.sec. The golden files are
+/// from GNU objdump 2.40, in `test/`:
+///
+///     objdump -d frames > golden/disasm-frames.txt
+///     objdump -d forwarding > golden/disasm-forwarding.txt
+///
+mod common;
+
+use common::{fixture, run_ok};
+
+use elf::parse::plt::{aarch64_stubs, x86_64_stubs, Import};
+use elf::ElfFile;
+
+#[test]
+fn finds_imports_through_the_plt() {
+    let mut elf_file = ElfFile::open(&fixture("frames")).unwrap();
+    let imports = elf_file.imports().unwrap();
+
+    // A stub in .plt, and one in .plt.got for a GLOB_DAT slot.
+    let printf = imports.lookup(0x1030).unwrap();
+    assert_eq!(
+        *printf,
+        Import {
+            name: "printf".to_owned(),
+            symbol_index: 3,
+            got: 0x4000,
+            plt: Some(0x1030),
+        }
+    );
+    assert_eq!(imports.by_got(0x4000), Some(printf));
+    assert_eq!(imports.by_plt(0x4000), None);
+    assert_eq!(imports.by_plt(0x1040).unwrap().name, "__cxa_finalize");
+
+    // Imports without stubs, and the first entry of .plt, which isn't one.
+    let start_main = imports.by_got(0x3fc0).unwrap();
+    assert_eq!(
+        (start_main.name.as_str(), start_main.plt),
+        ("__libc_start_main", None)
+    );
+    assert_eq!(imports.lookup(0x1020), None);
+    assert_eq!(imports.imports.len(), 6);
+}
+
+#[test]
+fn finds_stubs_in_plt_sec() {
+    let mut elf_file = ElfFile::open(&fixture("forwarding")).unwrap();
+    let imports = elf_file.imports().unwrap();
+
+    let stubs: Vec<(&str, u64)> = imports
+        .imports
+        .iter()
+        .filter_map(|import| Some((import.name.as_str(), import.plt?)))
+        .collect();
+    assert_eq!(
+        stubs,
+        [
+            ("__cxa_atexit", 0x1090),
+            (
+                "_ZStlsISt11char_traitsIcEERSt13basic_ostreamIcT_ES5_PKc",
+                0x10a0
+            ),
+            ("_ZNSolsEPFRSoS_E", 0x10b0),
+            ("__stack_chk_fail", 0x10c0),
+            ("_ZNSt8ios_base4InitC1Ev", 0x10d0),
+            ("__cxa_finalize", 0x1080),
+        ]
+    );
+    // The lazy stubs in .plt only push an index and jump to the first.
+    assert_eq!(imports.lookup(0x1030), None);
+}
+
+#[test]
+fn decodes_x86_64_stubs() {
+    let plt = [
+        // The first entry: push and jmp through the GOT's reserved slots.
+        0xff, 0x35, 0xe2, 0x2f, 0x00, 0x00, 0xff, 0x25, 0xe4, 0x2f, 0x00, 0x00, 0x0f, 0x1f, 0x40,
+        0x00, // jmp *0x2fe2(%rip)
+        0xff, 0x25, 0xe2, 0x2f, 0x00, 0x00, 0x68, 0x00, 0x00, 0x00, 0x00, 0xe9, 0xe0, 0xff, 0xff,
+        0xff, // endbr64; bnd jmp *-0x10(%rip)
+        0xf3, 0x0f, 0x1e, 0xfa, 0xf2, 0xff, 0x25, 0xf0, 0xff, 0xff, 0xff, 0x0f, 0x1f, 0x44, 0x00,
+        0x00,
+    ];
+    assert_eq!(
+        x86_64_stubs(&plt, 0x1000, 16),
+        [(0x1010, 0x3ff8), (0x1020, 0x101b)]
+    );
+}
+
+#[test]
+fn decodes_aarch64_stubs() {
+    let words: [u32; 15] = [
+        // The first entry, through the GOT's reserved slot.
+        0xa9bf7bf0, // stp x16, x30, [sp, #-16]!
+        0xb0000010, // adrp x16, 0x401000
+        0xf9400a11, // ldr x17, [x16, #16]
+        0x91004210, // add x16, x16, #16
+        0xd61f0220, // br x17
+        // With BTI.
+        0xd503245f, // bti c
+        0xb0000010, // adrp x16, 0x401000
+        0xf9400e11, // ldr x17, [x16, #24]
+        0x91006210, // add x16, x16, #24
+        0xd61f0220, // br x17
+        // With PAC, and a slot two pages back.
+        0xd0fffff0, // adrp x16, 0x3fe000
+        0xf947fe11, // ldr x17, [x16, #4088]
+        0x913fe210, // add x16, x16, #4088
+        0xd503219f, // autia1716
+        0xd61f0220, // br x17
+    ];
+    let bytes: Vec<u8> = words.iter().flat_map(|word| word.to_le_bytes()).collect();
+    assert_eq!(
+        aarch64_stubs(&bytes, 0x400100),
+        [
+            (0x400104, 0x401010),
+            (0x400114, 0x401018),
+            (0x400128, 0x3feff8)
+        ]
+    );
+}
+
+#[test]
+fn has_no_imports_for_other_machines() {
+    let mut elf_file = ElfFile::open(&fixture("disasm-riscv")).unwrap();
+    assert!(elf_file.imports().unwrap().imports.is_empty());
+
+    // Nor for a static executable.
+    let mut elf_file = ElfFile::open(&fixture("disasm")).unwrap();
+    assert!(elf_file.imports().unwrap().imports.is_empty());
+}
+
+#[test]
+fn disasm_names_plt_stubs_as_objdump_does() {
+    let expected = std::fs::read_to_string(fixture("golden/disasm-frames.txt")).unwrap();
+    assert_eq!(run_ok(&["disasm", "frames"]), expected);
+
+    let expected = std::fs::read_to_string(fixture("golden/disasm-forwarding.txt")).unwrap();
+    assert_eq!(run_ok(&["disasm", "forwarding"]), expected);
+}
+
+#[test]
+fn cfg_names_plt_stubs() {
+    let output = run_ok(&["cfg", "frames", "-f", "main"]);
+    assert!(output.contains("calls 0x1030 <printf@plt>"));
+    assert!(run_ok(&["cfg", "frames", "-f", "printf@plt"]).contains("printf@plt at 0x1030"));
+}
@@ -0,0 +1,113 @@
+/// Checks that lenient parsing gets past damaged headers, recording
+/// diagnostics for them, where strict parsing stops with an error.
+///
+mod common;
+
+use common::read_fixture;
+
+use elf::{ElfError, ElfFile, Severity};
+
+fn read_u64(bytes: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap())
+}
+
+#[test]
+fn well_formed_files_have_no_diagnostics() {
+    for name in ["forwarding", "AudioPlayer"] {
+        let contents = read_fixture(name);
+
+        assert!(ElfFile::from_bytes_lenient(&contents)
+            .unwrap()
+            .diagnostics
+            .is_empty());
+        assert!(ElfFile::from_bytes(&contents)
+            .unwrap()
+            .diagnostics
+            .is_empty());
+    }
+}
+
+#[test]
+fn bad_section_names_are_left_empty() {
+    let mut contents = read_fixture("forwarding");
+    let section_header_offset = read_u64(&contents, 0x28) as usize;
+
+    // Point the name of section 3 far past the end of the string table.
+    let entry = section_header_offset + 3 * 64;
+    contents[entry..entry + 4].copy_from_slice(&0xffffffu32.to_le_bytes());
+
+    assert!(matches!(
+        ElfFile::from_bytes(&contents),
+        Err(ElfError::InvalidString {
+            offset: 0xffffff,
+            ..
+        })
+    ));
+
+    let elf_file = ElfFile::from_bytes_lenient(&contents).unwrap();
+
+    assert_eq!(elf_file.section_headers[3].name, "");
+    assert_eq!(elf_file.section_headers[4].name, ".note.ABI-tag");
+
+    assert_eq!(elf_file.diagnostics.len(), 1);
+    assert_eq!(elf_file.diagnostics[0].severity, Severity::Error);
+    assert_eq!(elf_file.diagnostics[0].offset, entry as u64);
+}
+
+#[test]
+fn truncated_section_header_tables_are_read_as_far_as_they_go() {
+    let contents = read_fixture("forwarding");
+    let section_header_offset = read_u64(&contents, 0x28) as usize;
+
+    // Cut the file off in the middle of section header 20.
+    let truncated = &contents[..section_header_offset + 20 * 64 + 10];
+
+    assert!(matches!(
+        ElfFile::from_bytes(truncated),
+        Err(ElfError::Truncated {
+            what: "Section header table",
+            ..
+        })
+    ));
+
+    let elf_file = ElfFile::from_bytes_lenient(truncated).unwrap();
+
+    assert_eq!(elf_file.section_headers.len(), 20);
+    assert_eq!(elf_file.program_headers.len(), 13);
+
+    // The string table is section 37, so the names are gone too.
+    let messages: Vec<&str> = elf_file
+        .diagnostics
+        .iter()
+        .map(|diagnostic| diagnostic.message.as_str())
+        .collect();
+
+    assert!(messages[0].starts_with("Section header table"));
+    assert!(messages
+        .iter()
+        .any(|m| m.contains("index 37 is out of range")));
+}
+
+#[test]
+fn overlapping_segments_are_reported() {
+    let mut contents = read_fixture("forwarding");
+
+    // Move the second PT_LOAD segment (segment 3) into the first one.
+    let entry = 0x40 + 3 * 56;
+    contents[entry + 16..entry + 24].copy_from_slice(&0x100u64.to_le_bytes());
+
+    let elf_file = ElfFile::from_bytes_lenient(&contents).unwrap();
+
+    assert_eq!(elf_file.diagnostics.len(), 1);
+    assert_eq!(elf_file.diagnostics[0].severity, Severity::Warning);
+    assert_eq!(
+        elf_file.diagnostics[0].message,
+        "Segments 2 and 3 (PT_LOAD) overlap in memory."
+    );
+
+    // Strict parsing doesn't look for problems that don't stop it.
+    assert!(ElfFile::from_bytes(&contents)
+        .unwrap()
+        .diagnostics
+        .is_empty());
+}
@@ -0,0 +1,122 @@
+/// Checks the decoding of DWARF line programs, and looking up addresses in
+/// them. The fixtures are built from `test/lines.c` (which includes
+/// `test/lines.h`) with each version of the line table, in `test/`:
+///
+///     P="-O1 -fdebug-prefix-map=$PWD=/src"
+///     gcc -g -gdwarf-2 -gno-as-loc-support $P lines.c -o lines-dwarf2
+///     gcc -g -gdwarf-3 $P lines.c -o lines-dwarf3
+///     gcc -g -gdwarf-4 $P lines.c -o lines-dwarf4
+///     gcc -g -gdwarf-5 -gdwarf64 -gno-as-loc-support $P lines.c -o lines-dwarf64
+///
+/// (gcc's assembler directives would otherwise give a version 3 line table
+/// for `-gdwarf-2`, and a 32-bit one for `-gdwarf64`.)
+///
+mod common;
+
+use common::{fixture, read_fixture, run_ok};
+
+use elf::dwarf::line::SourceLocation;
+use elf::{ElfError, ElfFile};
+
+/// Each fixture, with its line table version and offset size.
+const FIXTURES: [(&str, u16, usize); 4] = [
+    ("lines-dwarf2", 2, 4),
+    ("lines-dwarf3", 3, 4),
+    ("lines-dwarf4", 4, 4),
+    ("lines-dwarf64", 5, 8),
+];
+
+fn location(elf_file: &mut ElfFile, address: u64) -> Option<(String, u64)> {
+    elf_file
+        .source_location(address)
+        .unwrap()
+        .map(|SourceLocation { file, line, .. }| (file, line))
+}
+
+#[test]
+fn reads_every_line_table_version() {
+    for (name, version, offset_size) in FIXTURES {
+        let mut elf_file = ElfFile::open(&fixture(name)).unwrap();
+
+        let table = elf_file.line_table().unwrap();
+        let header = &table.programs()[0].header;
+        assert_eq!(
+            (header.version, header.offset_size),
+            (version, offset_size),
+            "{name}"
+        );
+
+        // Before version 5, the compilation directory comes from .debug_info.
+        let at = |file: &str, line| Some((format!("/src/{file}"), line));
+
+        // The start of main, the loop in collatz_steps (inlined into it),
+        // and square from the header.
+        assert_eq!(location(&mut elf_file, 0x1139), at("lines.c", 22), "{name}");
+        assert_eq!(location(&mut elf_file, 0x1160), at("lines.c", 10), "{name}");
+        assert_eq!(location(&mut elf_file, 0x1179), at("lines.h", 4), "{name}");
+
+        // The PLT, which has no line information.
+        assert_eq!(location(&mut elf_file, 0x1030), None, "{name}");
+    }
+}
+
+#[test]
+fn reads_columns_and_discriminators() {
+    let mut elf_file = ElfFile::open(&fixture("lines-dwarf4")).unwrap();
+
+    let location = elf_file.source_location(0x1180).unwrap().unwrap();
+    assert_eq!((location.line, location.column), (26, 35));
+
+    let table = elf_file.line_table().unwrap();
+    assert!(table.programs()[0]
+        .rows
+        .iter()
+        .any(|row| row.discriminator != 0));
+}
+
+#[test]
+fn files_without_line_programs_have_no_locations() {
+    let mut elf_file = ElfFile::open(&fixture("libhash")).unwrap();
+
+    assert!(elf_file.line_table().unwrap().programs().is_empty());
+    assert_eq!(elf_file.source_location(0x1100).unwrap(), None);
+}
+
+#[test]
+fn reports_unsupported_versions() {
+    let mut contents = read_fixture("lines-dwarf4");
+    let offset = {
+        let elf_file = ElfFile::from_bytes(&contents).unwrap();
+        let index = elf_file.section_index_by_name(".debug_line").unwrap();
+
+        elf_file.section(index).unwrap().offset as usize
+    };
+
+    // The version follows the 32-bit unit length.
+    contents[offset + 4..offset + 6].copy_from_slice(&9u16.to_le_bytes());
+
+    assert!(matches!(
+        ElfFile::from_bytes(&contents).unwrap().line_table(),
+        Err(ElfError::UnsupportedVersion { version: 9, .. })
+    ));
+}
+
+#[test]
+fn addr2line_prints_locations() {
+    let output = run_ok(&[
+        "addr2line",
+        "--addresses",
+        "--column",
+        "lines-dwarf64",
+        "0x1139",
+        "1180",
+        "0x1030",
+    ]);
+
+    assert_eq!(
+        output,
+        "0x0000000000001139\n/src/lines.c:22:0\n\
+         0x0000000000001180\n/src/lines.c:26:18\n\
+         0x0000000000001030\n??:?\n"
+    );
+}
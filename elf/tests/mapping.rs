@@ -0,0 +1,195 @@
+/// Checks which sections are counted as part of each segment: a fixture's
+/// mapping against what `readelf -l` shows for it, and the edge cases of the
+/// rules: strict containment, .tbss, empty sections at the edges of
+/// PT_DYNAMIC and PT_NOTE segments, and PT_PHDR.
+///
+mod common;
+
+use common::fixture;
+
+use elf::parse::mapping::{section_in_segment, section_segment_mapping};
+use elf::parse::types::*;
+use elf::parse::{
+    Elf64ProgramHeaderEntry, Elf64ProgramHeaderInfo, Elf64SectionHeaderEntry,
+    Elf64SectionHeaderInfo, SHF_ALLOC, SHF_TLS, SHF_WRITE,
+};
+use elf::ElfFile;
+
+/// A segment with the same layout in the file and in memory, from 0x1000.
+fn segment(segment_type: u32, size: u64) -> Elf64ProgramHeaderEntry {
+    Elf64ProgramHeaderEntry {
+        segment_type: segment_type.to_le_bytes(),
+        flags: 0,
+        offset: 0x1000,
+        virtual_address: 0x1000,
+        physical_address: 0x1000,
+        file_size: size,
+        mem_size: size,
+        align: 0x1000,
+    }
+}
+
+/// A section at `start`, in the file and in memory.
+fn section(section_type: u32, flags: u64, start: u64, size: u64) -> Elf64SectionHeaderEntry {
+    Elf64SectionHeaderEntry {
+        name_offset: 0,
+        section_type: section_type.to_le_bytes(),
+        flags,
+        addr: start,
+        offset: start,
+        size,
+        link: 0,
+        info: 0,
+        addr_align: 1,
+        entry_size: 0,
+    }
+}
+
+fn progbits(start: u64, size: u64) -> Elf64SectionHeaderEntry {
+    section(SHT_PROGBITS, SHF_ALLOC, start, size)
+}
+
+#[test]
+fn maps_the_fixtures_sections_like_readelf() {
+    let elf_file = ElfFile::open(&fixture("forwarding")).unwrap();
+    let mapping: Vec<Vec<&str>> =
+        section_segment_mapping(&elf_file.program_headers, &elf_file.section_headers)
+            .iter()
+            .map(|sections| {
+                sections
+                    .iter()
+                    .map(|&i| elf_file.section_headers[i].name.as_str())
+                    .collect()
+            })
+            .collect();
+
+    let expected: [&[&str]; 13] = [
+        &[],
+        &[".interp"],
+        &[
+            ".interp",
+            ".note.gnu.property",
+            ".note.gnu.build-id",
+            ".note.ABI-tag",
+            ".gnu.hash",
+            ".dynsym",
+            ".dynstr",
+            ".gnu.version",
+            ".gnu.version_r",
+            ".rela.dyn",
+            ".rela.plt",
+        ],
+        &[".init", ".plt", ".plt.got", ".plt.sec", ".text", ".fini"],
+        &[".rodata", ".eh_frame_hdr", ".eh_frame"],
+        &[
+            ".init_array",
+            ".fini_array",
+            ".dynamic",
+            ".got",
+            ".data",
+            ".bss",
+        ],
+        &[".dynamic"],
+        &[".note.gnu.property"],
+        &[".note.gnu.build-id", ".note.ABI-tag"],
+        &[".note.gnu.property"],
+        &[".eh_frame_hdr"],
+        &[],
+        &[".init_array", ".fini_array", ".dynamic", ".got"],
+    ];
+    assert_eq!(mapping, expected);
+}
+
+#[test]
+fn sections_must_lie_strictly_inside() {
+    let load = segment(PT_LOAD, 0x100);
+
+    assert!(section_in_segment(&progbits(0x1000, 0x100), &load));
+    assert!(section_in_segment(&progbits(0x10f0, 0x10), &load));
+
+    // Ending one byte past the segment, or starting at its end.
+    assert!(!section_in_segment(&progbits(0x10f0, 0x11), &load));
+    assert!(!section_in_segment(&progbits(0x1100, 0), &load));
+    assert!(!section_in_segment(&progbits(0xfff, 0x10), &load));
+
+    // A section that isn't allocated isn't part of a loadable segment.
+    let comment = section(SHT_PROGBITS, 0, 0x1000, 0x10);
+    assert!(!section_in_segment(&comment, &load));
+
+    // A .bss only has to fit in memory, not in the file.
+    let mut load = segment(PT_LOAD, 0x100);
+    load.file_size = 0x80;
+    let bss = section(SHT_NOBITS, SHF_ALLOC | SHF_WRITE, 0x1080, 0x80);
+    assert!(section_in_segment(&bss, &load));
+    assert!(!section_in_segment(&progbits(0x1080, 0x80), &load));
+}
+
+#[test]
+fn tbss_is_only_part_of_the_tls_segment() {
+    let program_headers =
+        [PT_TLS, PT_LOAD, PT_GNU_RELRO].map(|segment_type| Elf64ProgramHeaderInfo {
+            header_data: segment(segment_type, 0x100),
+            type_string: String::new(),
+        });
+
+    // .tdata, then .tbss, then ordinary data at the address .tbss would
+    // take up if it were in memory.
+    let sections = [
+        section(SHT_NULL, 0, 0, 0),
+        section(SHT_PROGBITS, SHF_ALLOC | SHF_WRITE | SHF_TLS, 0x1000, 0x10),
+        section(SHT_NOBITS, SHF_ALLOC | SHF_WRITE | SHF_TLS, 0x1010, 0x20),
+        section(SHT_PROGBITS, SHF_ALLOC | SHF_WRITE, 0x1010, 0x20),
+    ];
+    let section_headers = sections.map(|header_data| Elf64SectionHeaderInfo {
+        header_data,
+        name: String::new(),
+        type_string: String::new(),
+    });
+
+    assert_eq!(
+        section_segment_mapping(&program_headers, &section_headers),
+        [vec![1, 2], vec![1, 3], vec![1, 3]]
+    );
+}
+
+#[test]
+fn tls_sections_only_go_in_some_segments() {
+    let tdata = section(SHT_PROGBITS, SHF_ALLOC | SHF_WRITE | SHF_TLS, 0x1000, 0x10);
+    assert!(section_in_segment(&tdata, &segment(PT_TLS, 0x100)));
+    assert!(!section_in_segment(&tdata, &segment(PT_DYNAMIC, 0x100)));
+
+    // And the PT_TLS segment only has TLS sections.
+    assert!(!section_in_segment(
+        &progbits(0x1000, 0x10),
+        &segment(PT_TLS, 0x100)
+    ));
+}
+
+#[test]
+fn empty_sections_at_the_edges_of_notes_are_left_out() {
+    for segment_type in [PT_NOTE, PT_DYNAMIC] {
+        let notes = segment(segment_type, 0x100);
+
+        assert!(!section_in_segment(&progbits(0x1000, 0), &notes));
+        assert!(section_in_segment(&progbits(0x1080, 0), &notes));
+        assert!(!section_in_segment(&progbits(0x1100, 0), &notes));
+        assert!(section_in_segment(&progbits(0x1000, 0x100), &notes));
+    }
+
+    // In other segments, an empty section at the start counts.
+    assert!(section_in_segment(
+        &progbits(0x1000, 0),
+        &segment(PT_LOAD, 0x100)
+    ));
+}
+
+#[test]
+fn the_program_header_segment_contains_no_sections() {
+    let phdr = segment(PT_PHDR, 0x100);
+
+    assert!(!section_in_segment(&progbits(0x1000, 0x40), &phdr));
+    assert!(section_in_segment(
+        &progbits(0x1000, 0x40),
+        &segment(PT_LOAD, 0x100)
+    ));
+}
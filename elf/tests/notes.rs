@@ -0,0 +1,262 @@
+/// Checks reading notes and decoding their descriptors: the GNU ABI tag,
+/// build ID and properties, SystemTap probes, and naming the types that
+/// aren't decoded. The fixtures' notes are compared with what `readelf -n`
+/// shows for them.
+///
+mod common;
+
+use common::fixture;
+
+use elf::parse::header::{EM_AARCH64, EM_X86_64};
+use elf::parse::notes::*;
+use elf::{ElfError, ElfFile};
+
+/// Encodes a note, with its name and descriptor padded to `align`.
+fn encode_note(name: &str, note_type: u32, desc: &[u8], align: usize) -> Vec<u8> {
+    let pad = |bytes: &mut Vec<u8>| bytes.resize(bytes.len().div_ceil(align) * align, 0);
+
+    let mut bytes = vec![];
+    bytes.extend((name.len() as u32 + 1).to_le_bytes());
+    bytes.extend((desc.len() as u32).to_le_bytes());
+    bytes.extend(note_type.to_le_bytes());
+    bytes.extend(name.as_bytes());
+    bytes.push(0);
+    pad(&mut bytes);
+    bytes.extend(desc);
+    pad(&mut bytes);
+    bytes
+}
+
+/// Encodes a GNU property, padded to 8 bytes.
+fn encode_property(pr_type: u32, data: &[u8]) -> Vec<u8> {
+    let mut bytes = vec![];
+    bytes.extend(pr_type.to_le_bytes());
+    bytes.extend((data.len() as u32).to_le_bytes());
+    bytes.extend(data);
+    bytes.resize(bytes.len().div_ceil(8) * 8, 0);
+    bytes
+}
+
+/// Reads the only note in `bytes`.
+fn only_note(bytes: &[u8], align: u64) -> ElfNote<'_> {
+    let mut notes: Vec<ElfNote> = read_notes(bytes, align).map(Result::unwrap).collect();
+    assert_eq!(notes.len(), 1);
+    notes.remove(0)
+}
+
+#[test]
+fn decodes_the_notes_of_a_file() {
+    let mut elf_file = ElfFile::open(&fixture("forwarding")).unwrap();
+    let mut decoded = vec![];
+
+    for name in [".note.gnu.property", ".note.gnu.build-id", ".note.ABI-tag"] {
+        let index = elf_file.section_index_by_name(name).unwrap();
+        let align = elf_file.section(index).unwrap().addr_align;
+
+        for note in read_notes(elf_file.section_bytes(index).unwrap(), align) {
+            let note = note.unwrap();
+            assert_eq!(note.name, "GNU");
+            decoded.push((note.type_string(), note.descriptor(EM_X86_64).to_string()));
+        }
+    }
+
+    assert_eq!(
+        decoded,
+        [
+            (
+                "NT_GNU_PROPERTY_TYPE_0".to_owned(),
+                "Properties: x86 feature: IBT, SHSTK\n            x86 ISA needed: x86-64-baseline"
+                    .to_owned()
+            ),
+            (
+                "NT_GNU_BUILD_ID".to_owned(),
+                "Build ID: d0d35692995d1d208dca3f7ca6060c4427efd008".to_owned()
+            ),
+            (
+                "NT_GNU_ABI_TAG".to_owned(),
+                "OS: Linux, ABI: 3.2.0".to_owned()
+            ),
+        ]
+    );
+
+    assert_eq!(
+        elf_file.build_id().unwrap().map(|id| hex_string(&id)),
+        Some("d0d35692995d1d208dca3f7ca6060c4427efd008".to_owned())
+    );
+}
+
+#[test]
+fn decodes_the_abi_tag() {
+    let desc: Vec<u8> = [3u32, 14, 1, 0]
+        .iter()
+        .flat_map(|n| n.to_le_bytes())
+        .collect();
+    let bytes = encode_note("GNU", NT_GNU_ABI_TAG, &desc, 4);
+    let note = only_note(&bytes, 4);
+
+    assert!(matches!(
+        note.descriptor(EM_X86_64),
+        NoteDescriptor::GnuAbiTag {
+            os: 3,
+            major: 14,
+            minor: 1,
+            patch: 0
+        }
+    ));
+    assert_eq!(
+        note.descriptor(EM_X86_64).to_string(),
+        "OS: FreeBSD, ABI: 14.1.0"
+    );
+
+    // Too short to hold the four words.
+    let bytes = encode_note("GNU", NT_GNU_ABI_TAG, &desc[..12], 4);
+    assert!(matches!(
+        only_note(&bytes, 4).descriptor(EM_X86_64),
+        NoteDescriptor::Unknown
+    ));
+}
+
+#[test]
+fn decodes_build_ids() {
+    let bytes = encode_note("GNU", NT_GNU_BUILD_ID, &[0xde, 0xad, 0xbe, 0xef, 0x01], 4);
+    assert_eq!(
+        only_note(&bytes, 4).descriptor(EM_X86_64).to_string(),
+        "Build ID: deadbeef01"
+    );
+    assert_eq!(
+        find_build_id(read_notes(&bytes, 4)),
+        Some(vec![0xde, 0xad, 0xbe, 0xef, 0x01])
+    );
+
+    // Go has its own kind of build ID, which isn't the GNU one.
+    let bytes = encode_note("Go", NT_GO_BUILD_ID, b"abc\0", 4);
+    assert_eq!(find_build_id(read_notes(&bytes, 4)), None);
+    assert_eq!(
+        only_note(&bytes, 4).descriptor(EM_X86_64).to_string(),
+        "Go Build ID: abc"
+    );
+}
+
+#[test]
+fn ends_owner_names_at_the_first_null() {
+    // Go's linker pads the owner name to four bytes, and counts the padding.
+    let mut bytes = vec![];
+    bytes.extend(4u32.to_le_bytes());
+    bytes.extend(4u32.to_le_bytes());
+    bytes.extend(NT_GO_BUILD_ID.to_le_bytes());
+    bytes.extend(b"Go\0\0abc\0");
+    let note = only_note(&bytes, 4);
+
+    assert_eq!(note.name, "Go");
+    assert_eq!(note.descriptor(EM_X86_64).to_string(), "Go Build ID: abc");
+}
+
+#[test]
+fn decodes_systemtap_probes() {
+    let mut desc = vec![];
+    for address in [0x1040u64, 0x2004, 0x4010] {
+        desc.extend(address.to_le_bytes());
+    }
+    desc.extend(b"probes\0start\0-4@%edi 8@%rsi\0");
+    let bytes = encode_note("stapsdt", NT_STAPSDT, &desc, 4);
+    let note = only_note(&bytes, 4);
+
+    assert_eq!(note.type_string(), "NT_STAPSDT");
+    assert_eq!(
+        note.descriptor(EM_X86_64).to_string(),
+        "Probe: probes:start, location: 0x1040, base: 0x2004, semaphore: 0x4010, \
+         arguments: -4@%edi 8@%rsi"
+    );
+
+    // The arguments have to end in a null, as the names do.
+    desc.pop();
+    let bytes = encode_note("stapsdt", NT_STAPSDT, &desc, 4);
+    assert!(matches!(
+        only_note(&bytes, 4).descriptor(EM_X86_64),
+        NoteDescriptor::Unknown
+    ));
+}
+
+#[test]
+fn decodes_properties_for_the_machine() {
+    let mut desc = encode_property(GNU_PROPERTY_X86_FEATURE_1_AND, &3u32.to_le_bytes());
+    desc.extend(encode_property(
+        GNU_PROPERTY_X86_ISA_1_USED,
+        &0x5u32.to_le_bytes(),
+    ));
+    desc.extend(encode_property(
+        GNU_PROPERTY_STACK_SIZE,
+        &0x800000u64.to_le_bytes(),
+    ));
+    desc.extend(encode_property(GNU_PROPERTY_NO_COPY_ON_PROTECTED, &[]));
+    let bytes = encode_note("GNU", NT_GNU_PROPERTY_TYPE_0, &desc, 8);
+    let note = only_note(&bytes, 8);
+
+    assert_eq!(
+        note.descriptor(EM_X86_64).to_string(),
+        "Properties: x86 feature: IBT, SHSTK\n            \
+         x86 ISA used: x86-64-baseline, x86-64-v3\n            \
+         stack size: 0x800000\n            \
+         no copy on protected"
+    );
+
+    // The same bits mean something else, or nothing, on other machines.
+    let desc = encode_property(GNU_PROPERTY_AARCH64_FEATURE_1_AND, &0x7u32.to_le_bytes());
+    let bytes = encode_note("GNU", NT_GNU_PROPERTY_TYPE_0, &desc, 8);
+    assert_eq!(
+        only_note(&bytes, 8).descriptor(EM_AARCH64).to_string(),
+        "Properties: AArch64 feature: BTI, PAC, <unknown: 4>"
+    );
+    assert_eq!(
+        only_note(&bytes, 8).descriptor(EM_X86_64).to_string(),
+        "Properties: <unknown type 0xc0000000: 07000000>"
+    );
+
+    // A property running past the end of the descriptor.
+    let mut desc = encode_property(GNU_PROPERTY_X86_FEATURE_1_AND, &3u32.to_le_bytes());
+    desc[4] = 0x40;
+    let bytes = encode_note("GNU", NT_GNU_PROPERTY_TYPE_0, &desc, 8);
+    assert!(matches!(
+        only_note(&bytes, 8).descriptor(EM_X86_64),
+        NoteDescriptor::Unknown
+    ));
+}
+
+#[test]
+fn names_types_that_arent_decoded() {
+    let bytes = encode_note("GNU", NT_GNU_HWCAP, &[1, 0, 0, 0], 4);
+    let note = only_note(&bytes, 4);
+
+    assert_eq!(note.type_string(), "NT_GNU_HWCAP");
+    assert!(matches!(
+        note.descriptor(EM_X86_64),
+        NoteDescriptor::Unknown
+    ));
+
+    // Types are named by owner.
+    let bytes = encode_note("Xen", NT_GNU_HWCAP, &[], 4);
+    assert_eq!(only_note(&bytes, 4).type_string(), "UNKNOWN NOTE TYPE: 0x2");
+}
+
+#[test]
+fn reads_consecutive_notes_and_stops_at_a_truncated_one() {
+    let mut bytes = encode_note("GNU", NT_GNU_BUILD_ID, &[1, 2, 3], 4);
+    bytes.extend(encode_note("FDO", NT_FDO_PACKAGING_METADATA, b"{}\0", 4));
+    let third = bytes.len();
+    bytes.extend(encode_note("GNU", NT_GNU_BUILD_ID, &[1; 20], 4));
+    bytes.truncate(bytes.len() - 4);
+
+    let notes: Vec<_> = read_notes(&bytes, 4).collect();
+    assert_eq!(notes.len(), 3);
+
+    let fdo = notes[1].as_ref().unwrap();
+    assert_eq!((fdo.offset, fdo.name.as_str()), (20, "FDO"));
+    assert_eq!(
+        fdo.descriptor(EM_X86_64).to_string(),
+        "Packaging Metadata: {}"
+    );
+    assert!(matches!(
+        notes[2],
+        Err(ElfError::Truncated { offset, .. }) if offset == third as u64
+    ));
+}
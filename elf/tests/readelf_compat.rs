@@ -0,0 +1,92 @@
+/// Compares the output of `--readelf-compat` with golden files produced by
+/// GNU readelf 2.40 from the fixtures in `test/`. To regenerate them:
+///
+///     readelf -h -l -S -s -d -r -n -V test/forwarding > test/golden/forwarding.txt
+///     readelf -h -l -S -s -d -r -n -V test/AudioPlayer > test/golden/AudioPlayer.txt
+///     readelf -h -l -S -s -d -r -n -V test/unversioned > test/golden/unversioned.txt
+///     readelf -h -l -S -s -d -r -n -V test/relr > test/golden/relr.txt
+///     readelf -h -l -S -s -d -r -n -V test/long-versions > test/golden/long-versions.txt
+///     readelf -h -l -S -s -d -r -n -V test/probes > test/golden/probes.txt
+///     readelf -l -S test/forwarding > test/golden/forwarding-segments-sections.txt
+///     readelf -C -s -r test/AudioPlayer > test/golden/AudioPlayer-demangled.txt
+///
+mod common;
+
+use common::{fixture, run_ok};
+
+/// Runs the tool on a fixture and checks its output against a golden file.
+fn assert_matches_golden(file: &str, args: &[&str], golden: &str) {
+    let mut all_args = vec!["--readelf-compat"];
+    all_args.extend(args);
+    all_args.push(file);
+
+    let expected = std::fs::read_to_string(fixture(&format!("golden/{golden}"))).unwrap();
+    let actual = run_ok(&all_args);
+
+    if actual != expected {
+        // Point at the first line that differs, rather than dumping both outputs.
+        let line = expected
+            .lines()
+            .zip(actual.lines())
+            .position(|(e, a)| e != a)
+            .unwrap_or(expected.lines().count().min(actual.lines().count()));
+
+        panic!(
+            "Output differs from {golden} at line {}:\n  expected: {:?}\n  actual:   {:?}",
+            line + 1,
+            expected.lines().nth(line),
+            actual.lines().nth(line)
+        );
+    }
+}
+
+#[test]
+fn forwarding_all_parts() {
+    assert_matches_golden("forwarding", &[], "forwarding.txt");
+}
+
+#[test]
+fn audio_player_all_parts() {
+    assert_matches_golden("AudioPlayer", &[], "AudioPlayer.txt");
+}
+
+#[test]
+fn unversioned_all_parts() {
+    assert_matches_golden("unversioned", &[], "unversioned.txt");
+}
+
+/// Relative relocations packed into SHT_RELR are decoded from their bitmaps.
+#[test]
+fn relr_all_parts() {
+    assert_matches_golden("relr", &[], "relr.txt");
+}
+
+/// Versions that take up all of the name's width, or more than all of it.
+#[test]
+fn long_versions_all_parts() {
+    assert_matches_golden("long-versions", &[], "long-versions.txt");
+}
+
+/// SystemTap probes, whose notes are decoded into their fields.
+#[test]
+fn probes_all_parts() {
+    assert_matches_golden("probes", &[], "probes.txt");
+}
+
+#[test]
+fn forwarding_segments_and_sections() {
+    assert_matches_golden(
+        "forwarding",
+        &["--segments", "--sections"],
+        "forwarding-segments-sections.txt",
+    );
+}
+
+#[test]
+fn audio_player_demangled_symbols_and_relocations() {
+    assert_matches_golden(
+        "AudioPlayer",
+        &["--demangle", "--symbols", "--relocs"],
+        "AudioPlayer-demangled.txt",
+    );
+}
@@ -0,0 +1,124 @@
+/// Checks the contents returned for sections and segments, including the
+/// zeros of NOBITS sections and of segments that are larger in memory, and
+/// the `dump` command that writes them out. The hex dump golden file is the
+/// body of `readelf -x .rodata test/forwarding`, without its heading:
+///
+///     readelf -x .rodata test/forwarding | sed '1,2d;$d' > test/golden/forwarding-rodata-hex.txt
+///
+mod common;
+
+use common::{fixture, read_fixture, run};
+
+use std::path::PathBuf;
+
+use elf::parse::types::PT_LOAD;
+use elf::{ElfError, ElfFile};
+
+/// Runs `elf dump` on `forwarding`, and returns what it wrote.
+fn run_dump(args: &[&str]) -> Vec<u8> {
+    let mut all_args = vec!["dump"];
+    all_args.extend(args);
+    all_args.push("forwarding");
+
+    let output = run(&all_args);
+    assert!(
+        output.status.success(),
+        "elf failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    output.stdout
+}
+
+#[test]
+fn nobits_sections_are_zeros() {
+    let mut elf_file = ElfFile::open(&fixture("forwarding")).unwrap();
+    let bss = elf_file.section_index_by_name(".bss").unwrap();
+    let size = elf_file.section(bss).unwrap().size as usize;
+
+    assert!(elf_file.section_bytes(bss).unwrap().is_empty());
+    assert_eq!(*elf_file.section_data(bss).unwrap(), vec![0; size]);
+
+    let text = elf_file.section_index_by_name(".text").unwrap();
+    let bytes = elf_file.section_bytes(text).unwrap().to_vec();
+    assert_eq!(*elf_file.section_data(text).unwrap(), bytes);
+}
+
+#[test]
+fn segments_are_zero_filled_to_their_size_in_memory() {
+    let mut elf_file = ElfFile::open(&fixture("forwarding")).unwrap();
+
+    for index in 0..elf_file.program_headers.len() {
+        let segment = elf_file.segment(index).unwrap();
+        let (offset, file_size, mem_size) = (segment.offset, segment.file_size, segment.mem_size);
+
+        let file_bytes = elf_file.bytes(offset, file_size).unwrap().to_vec();
+        let data = elf_file.segment_data(index).unwrap();
+
+        assert_eq!(data.len() as u64, mem_size.max(file_size));
+        assert_eq!(data[..file_bytes.len()], file_bytes);
+        assert!(data[file_bytes.len()..].iter().all(|&b| b == 0));
+    }
+
+    // The last PT_LOAD segment holds .bss, so it's larger in memory.
+    let segment = elf_file.segment(5).unwrap();
+    assert_eq!(segment.type_id(), PT_LOAD);
+    assert!(segment.mem_size > segment.file_size);
+
+    assert!(elf_file.segment_data(13).is_err());
+}
+
+#[test]
+fn refuses_to_fill_sizes_too_large_for_memory() {
+    let mut contents = read_fixture("forwarding");
+    let (bss_header, segment_header) = {
+        let elf_file = ElfFile::from_bytes(&contents).unwrap();
+        let bss = elf_file.section_index_by_name(".bss").unwrap();
+        let header = &elf_file.header;
+
+        (
+            header.section_header_offset as usize + bss * 64,
+            header.program_header_offset as usize + 5 * 56,
+        )
+    };
+
+    // A section header's size is 32 bytes in, and a program header's
+    // size in memory 40 bytes in.
+    let huge = 0x7000_0000_0000_0000u64.to_le_bytes();
+    contents[bss_header + 32..bss_header + 40].copy_from_slice(&huge);
+    contents[segment_header + 40..segment_header + 48].copy_from_slice(&huge);
+
+    let mut elf_file = ElfFile::from_bytes(&contents).unwrap();
+    let bss = elf_file.section_index_by_name(".bss").unwrap();
+
+    assert!(matches!(
+        elf_file.section_data(bss),
+        Err(ElfError::InvalidData { offset, .. }) if offset == bss_header as u64
+    ));
+    assert!(matches!(
+        elf_file.segment_data(5),
+        Err(ElfError::InvalidData { offset, .. }) if offset == segment_header as u64
+    ));
+
+    // The bytes that are in the file can still be read.
+    assert!(elf_file.section_bytes(bss).unwrap().is_empty());
+    assert!(elf_file.segment_data(4).is_ok());
+}
+
+#[test]
+fn dumps_raw_bytes_and_hex() {
+    let mut elf_file = ElfFile::open(&fixture("forwarding")).unwrap();
+    let rodata = elf_file.section_index_by_name(".rodata").unwrap();
+    let expected = elf_file.section_data(rodata).unwrap().to_vec();
+
+    // By name, by index, and to a file.
+    assert_eq!(run_dump(&["--section", ".rodata"]), expected);
+    assert_eq!(run_dump(&["--section", &rodata.to_string()]), expected);
+
+    let output = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("rodata.bin");
+    run_dump(&["--section", ".rodata", "-o", output.to_str().unwrap()]);
+    assert_eq!(std::fs::read(&output).unwrap(), expected);
+
+    let golden = std::fs::read(fixture("golden/forwarding-rodata-hex.txt")).unwrap();
+    assert_eq!(run_dump(&["--section", ".rodata", "--hex"]), golden);
+}
@@ -0,0 +1,203 @@
+/// Checks reading SFrame stack trace information, against the call frame
+/// information in .eh_frame for the same code. The fixture is built from
+/// `test/frames.c`, in `test/`, with GNU as 2.40 (which writes version 1):
+///
+///     P="-O2 -fdebug-prefix-map=$PWD=/src"
+///     gcc -g -Wa,--gsframe $P frames.c -o frames-sframe
+///
+/// and the golden file with GNU readelf 2.40:
+///
+///     readelf --sframe frames-sframe > golden/frames-sframe-sframe.txt
+///
+mod common;
+
+use common::{fixture, run_ok};
+
+use std::collections::BTreeMap;
+
+use elf::dwarf::cfa::{CfaRule, RegisterRule};
+use elf::parse::sframe::*;
+use elf::{ElfError, ElfFile};
+
+const PLT: u64 = 0x1020;
+const MAIN: u64 = 0x1050;
+const MIX: u64 = 0x1170;
+const SUM: u64 = 0x1240;
+
+// The x86-64 DWARF numbers of the registers used here.
+const RBP: u16 = 6;
+const RSP: u16 = 7;
+const RIP: u16 = 16;
+
+#[test]
+fn reads_the_header_and_fdes() {
+    let mut elf_file = ElfFile::open(&fixture("frames-sframe")).unwrap();
+    let sframe = elf_file.sframe().unwrap().unwrap();
+
+    assert_eq!(sframe.header.version, SFRAME_VERSION_1);
+    assert_eq!(sframe.header.abi_arch, SFRAME_ABI_AMD64_ENDIAN_LITTLE);
+    assert_eq!(sframe.header.flags, SFRAME_F_FDE_SORTED);
+    assert_eq!(sframe.header.cfa_fixed_ra_offset, -8);
+
+    // The PLT's first entry, the rest of its entries, and the functions.
+    let ranges: Vec<_> = sframe.fdes.iter().map(|fde| fde.range.clone()).collect();
+    assert_eq!(
+        ranges,
+        [
+            PLT..PLT + 0x10,
+            PLT + 0x10..PLT + 0x20,
+            MAIN..0x1074,
+            MIX..0x1238,
+            SUM..0x1287
+        ]
+    );
+    assert_eq!(sframe.fdes[1].fde_type, SFRAME_FDE_TYPE_PCMASK);
+
+    let fres = sframe.fres(&sframe.fdes[4]).unwrap();
+    assert_eq!(
+        fres[2],
+        SframeFre {
+            start: 0x16,
+            cfa_base: CfaBase::FramePointer,
+            cfa_offset: 16,
+            ra_offset: Some(-8),
+            fp_offset: Some(-16),
+            mangled_ra: false,
+        }
+    );
+}
+
+/// Checks that SFrame gives the same CFA, and the same places for the
+/// return address and frame pointer, as .eh_frame at every address.
+#[test]
+fn agrees_with_eh_frame() {
+    let mut elf_file = ElfFile::open(&fixture("frames-sframe")).unwrap();
+    let sframe = elf_file.sframe().unwrap().unwrap();
+
+    for fde in &sframe.fdes {
+        for address in fde.range.clone() {
+            let (_, fre) = sframe.find_fre(address).unwrap().unwrap();
+            let row = elf_file.unwind_row(address).unwrap().unwrap();
+
+            let base = match fre.cfa_base {
+                CfaBase::StackPointer => RSP,
+                CfaBase::FramePointer => RBP,
+            };
+            match &row.cfa {
+                CfaRule::RegisterOffset { register, offset } => {
+                    assert_eq!((*register, *offset), (base, fre.cfa_offset as i64));
+                }
+
+                // In the PLT, evaluate it for some value of the stack pointer.
+                CfaRule::Expression(_) => {
+                    let registers = BTreeMap::from([(RSP, 0x7f00), (RIP, address)]);
+                    let cfa = row.cfa(&registers, &|_, _| None).unwrap();
+                    assert_eq!(base, RSP);
+                    assert_eq!(cfa, 0x7f00 + fre.cfa_offset as u64, "{address:#x}");
+                }
+            }
+
+            let saved = |register| match row.registers.get(&register) {
+                Some(RegisterRule::Offset(offset)) => Some(*offset as i32),
+                _ => None,
+            };
+            assert_eq!(saved(RIP), fre.ra_offset, "{address:#x}");
+            assert_eq!(saved(RBP), fre.fp_offset, "{address:#x}");
+        }
+    }
+}
+
+/// An AArch64 section, in version 2, whose one function's FRE tracks
+/// the return address (signed) and the frame pointer.
+fn aarch64_sframe() -> Vec<u8> {
+    let mut bytes = vec![];
+    bytes.extend(SFRAME_MAGIC.to_le_bytes());
+    bytes.extend([SFRAME_VERSION_2, SFRAME_F_FDE_SORTED]);
+    bytes.extend([SFRAME_ABI_AARCH64_ENDIAN_LITTLE, 0, 0, 0]);
+    // One FDE, with one FRE of 5 bytes; the FREs follow the 20-byte FDE.
+    for value in [1u32, 1, 5, 0, 20] {
+        bytes.extend(value.to_le_bytes());
+    }
+
+    // The function is 0x40 bytes, 0x100 after the section.
+    bytes.extend(0x100i32.to_le_bytes());
+    bytes.extend([0x40, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0]);
+    bytes.extend([SFRAME_FRE_TYPE_ADDR1, 0, 0, 0]);
+
+    // From 8 bytes in: the CFA is fp+16, and the return address and the
+    // frame pointer are at -8 and -16, with one-byte offsets.
+    bytes.extend([8, 0b1000_0110, 16, -8i8 as u8, -16i8 as u8]);
+    bytes
+}
+
+#[test]
+fn reads_aarch64_offsets() {
+    let sframe = read_sframe(aarch64_sframe(), 0x4000).unwrap();
+
+    assert_eq!(sframe.fdes[0].range, 0x4100..0x4140);
+    assert_eq!(sframe.find_fre(0x4104).unwrap(), None);
+    assert_eq!(sframe.find_fre(0x4140).unwrap(), None);
+
+    let (_, fre) = sframe.find_fre(0x4108).unwrap().unwrap();
+    assert_eq!(
+        fre,
+        SframeFre {
+            start: 8,
+            cfa_base: CfaBase::FramePointer,
+            cfa_offset: 16,
+            ra_offset: Some(-8),
+            fp_offset: Some(-16),
+            mangled_ra: true,
+        }
+    );
+}
+
+#[test]
+fn reports_bad_headers() {
+    let mut bytes = aarch64_sframe();
+    bytes[2] = 3;
+    assert!(matches!(
+        read_sframe(bytes, 0),
+        Err(ElfError::UnsupportedVersion { version: 3, .. })
+    ));
+
+    let mut bytes = aarch64_sframe();
+    bytes[0] = 0;
+    assert!(matches!(
+        read_sframe(bytes, 0),
+        Err(ElfError::InvalidData { offset: 0, .. })
+    ));
+
+    // Without the FRE.
+    let mut bytes = aarch64_sframe();
+    bytes.truncate(bytes.len() - 3);
+    let sframe = read_sframe(bytes, 0).unwrap();
+    assert!(matches!(
+        sframe.fres(&sframe.fdes[0]),
+        Err(ElfError::Truncated { .. })
+    ));
+}
+
+#[test]
+fn unwind_prints_sframe_like_readelf() {
+    let expected = std::fs::read_to_string(fixture("golden/frames-sframe-sframe.txt")).unwrap();
+
+    assert_eq!(run_ok(&["unwind", "--sframe", "frames-sframe"]), expected);
+}
+
+#[test]
+fn unwind_prints_sframe_rows_at_addresses() {
+    assert_eq!(
+        run_ok(&[
+            "unwind",
+            "--sframe",
+            "frames-sframe",
+            "1190",
+            "0x103b",
+            "0x1000",
+        ]),
+        "0x0000000000001190: CFA=sp+80 fp=c-48 ra=c-8\n\
+         0x000000000000103b: CFA=sp+16 fp=u ra=c-8\n\
+         0x0000000000001000: no SFrame information\n"
+    );
+}
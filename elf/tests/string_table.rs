@@ -0,0 +1,105 @@
+/// Checks string table lookups at the edges of the table: offsets past the
+/// end, unterminated strings, names that aren't UTF-8, and long strings.
+///
+mod common;
+
+use common::fixture;
+
+use elf::parse::strings::StringTable;
+use elf::parse::types::SHT_STRTAB;
+use elf::{ElfError, ElfFile};
+
+#[test]
+fn looks_up_strings_and_suffixes() {
+    let table = StringTable::new(b"\0.text\0.rela.text\0");
+
+    assert_eq!(table.get(0).unwrap(), "");
+    assert_eq!(table.get(1).unwrap(), ".text");
+    assert_eq!(table.get(7).unwrap(), ".rela.text");
+
+    // Linkers share the tail of one string as another.
+    assert_eq!(table.get(12).unwrap(), ".text");
+    assert_eq!(table.get(17).unwrap(), "");
+}
+
+#[test]
+fn rejects_offsets_out_of_range() {
+    let table = StringTable::new(b"\0abc\0");
+
+    for offset in [5, 6, usize::MAX] {
+        assert!(matches!(
+            table.get(offset),
+            Err(ElfError::InvalidString { offset: o, .. }) if o == offset as u64
+        ));
+    }
+
+    let empty = StringTable::new(b"");
+    assert!(empty.get(0).is_err());
+    assert_eq!(empty.iter().count(), 0);
+}
+
+#[test]
+fn rejects_unterminated_strings() {
+    let table = StringTable::new(b"\0abc\0def");
+
+    assert_eq!(table.get(1).unwrap(), "abc");
+    assert!(table.get(5).is_err());
+    assert!(table.get_bytes(6).is_err());
+
+    // The unterminated tail isn't a string.
+    let strings: Vec<_> = table.iter().collect();
+    assert_eq!(strings, [(0, &b""[..]), (1, &b"abc"[..])]);
+}
+
+#[test]
+fn gives_raw_bytes_of_names_that_are_not_utf8() {
+    let table = StringTable::new(b"\0caf\xe9\0");
+
+    assert_eq!(table.get_bytes(1).unwrap(), b"caf\xe9");
+    assert_eq!(table.get_lossy(1).unwrap(), "caf\u{fffd}");
+    assert!(matches!(
+        table.get(1),
+        Err(ElfError::InvalidString { offset: 1, .. })
+    ));
+}
+
+#[test]
+fn reads_long_strings_late_in_a_table() {
+    let long = "x".repeat(10_000);
+
+    let mut bytes = vec![0; 100_000];
+    bytes.extend_from_slice(long.as_bytes());
+    bytes.push(0);
+
+    let table = StringTable::new(&bytes);
+    assert_eq!(table.get(100_000).unwrap(), long);
+    assert_eq!(table.get(109_999).unwrap(), "x");
+}
+
+#[test]
+fn iterates_over_every_section_name() {
+    let path = fixture("forwarding");
+    let mut elf_file = ElfFile::open(&path).unwrap();
+
+    let names_index = elf_file.header.section_header_names_index as usize;
+    assert_eq!(elf_file.section(names_index).unwrap().type_id(), SHT_STRTAB);
+
+    let section_names: Vec<String> = elf_file
+        .section_headers
+        .iter()
+        .map(|section| section.name.clone())
+        .collect();
+
+    let table = elf_file.string_table(names_index).unwrap();
+    let strings: Vec<String> = table
+        .iter()
+        .map(|(_, bytes)| String::from_utf8(bytes.to_vec()).unwrap())
+        .collect();
+
+    // Names like .text may only appear as the tail of .rela.text, so
+    // each section's name is either a string or the end of one.
+    for name in &section_names {
+        assert!(strings.iter().any(|string| string.ends_with(name.as_str())));
+    }
+    assert_eq!(strings[0], "");
+}
@@ -0,0 +1,153 @@
+/// Checks naming addresses after the symbols they're in, and the
+/// `symbolize` command. The fixtures are `test/frames` and a copy of it
+/// without its symbol table, in `test/`, for the DWARF functions:
+///
+///     strip --strip-all --keep-section='.debug_*' frames -o frames-nosyms
+///
+mod common;
+
+use common::{fixture, read_fixture};
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use elf::parse::symbol_map::{MapSymbol, SymbolMap, SymbolSource, Symbolized};
+use elf::ElfFile;
+
+fn name(map: &SymbolMap, address: u64) -> Option<String> {
+    map.lookup(address).map(|symbol| symbol.to_string())
+}
+
+#[test]
+fn finds_the_symbol_an_address_is_in() {
+    let map = ElfFile::open(&fixture("frames"))
+        .unwrap()
+        .symbol_map()
+        .unwrap();
+
+    assert_eq!(
+        map.lookup(0x1175),
+        Some(Symbolized {
+            name: "mix".to_owned(),
+            address: 0x1170,
+            offset: 5,
+            source: SymbolSource::SymbolTable,
+        })
+    );
+    assert_eq!(name(&map, 0x1170).as_deref(), Some("mix"));
+    assert_eq!(name(&map, 0x1286).as_deref(), Some("sum+0x46"));
+
+    // Past the end of `sum`, which has a size, but in `_fini`, which
+    // hasn't, and so goes on to the end of its section.
+    assert_eq!(name(&map, 0x1287), None);
+    assert_eq!(name(&map, 0x1290).as_deref(), Some("_fini+0x8"));
+    assert_eq!(
+        name(&map, 0x10d0).as_deref(),
+        Some("deregister_tm_clones+0x20")
+    );
+    assert_eq!(name(&map, 0), None);
+}
+
+#[test]
+fn sections_running_past_the_address_space_end_with_it() {
+    let mut contents = read_fixture("frames");
+    let size_offset = {
+        let elf_file = ElfFile::from_bytes(&contents).unwrap();
+        let fini = elf_file.section_index_by_name(".fini").unwrap();
+
+        // The size is 32 bytes into the section header.
+        elf_file.header.section_header_offset as usize + fini * 64 + 32
+    };
+    contents[size_offset..size_offset + 8].copy_from_slice(&u64::MAX.to_le_bytes());
+
+    let map = ElfFile::from_bytes(&contents)
+        .unwrap()
+        .symbol_map()
+        .unwrap();
+    assert_eq!(name(&map, 0x1290).as_deref(), Some("_fini+0x8"));
+    assert_eq!(
+        name(&map, u64::MAX - 1).as_deref(),
+        Some("_fini+0xffffffffffffed76")
+    );
+}
+
+#[test]
+fn falls_back_to_dwarf_functions() {
+    let mut elf_file = ElfFile::open(&fixture("frames-nosyms")).unwrap();
+    assert_eq!(
+        elf_file.symbolize(0x1055).unwrap(),
+        Some(Symbolized {
+            name: "main".to_owned(),
+            address: 0x1050,
+            offset: 5,
+            source: SymbolSource::DebugInfo,
+        })
+    );
+
+    // Nothing in the DWARF covers the C runtime's code.
+    assert_eq!(elf_file.symbolize(0x10b0).unwrap(), None);
+}
+
+#[test]
+fn picks_the_closest_symbol_that_covers_an_address() {
+    let symbol = |name: &str, start, end, rank| MapSymbol {
+        name: name.to_owned(),
+        start,
+        end,
+        rank,
+    };
+    let map = SymbolMap::new(
+        vec![
+            symbol("outer", 0x100, 0x200, 0),
+            symbol("inner", 0x140, 0x150, 2),
+            symbol("alias", 0x140, 0x150, 1),
+            symbol("empty", 0x180, 0x180, 0),
+        ],
+        &[],
+    );
+
+    assert_eq!(name(&map, 0x145).as_deref(), Some("alias+0x5"));
+    assert_eq!(name(&map, 0x160).as_deref(), Some("outer+0x60"));
+    assert_eq!(name(&map, 0x180).as_deref(), Some("outer+0x80"));
+    assert_eq!(name(&map, 0x200), None);
+}
+
+fn symbolize(args: &[&str], input: &str) -> String {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_elf"))
+        .arg("symbolize")
+        .args(args)
+        .current_dir(fixture(""))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to run elf.");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn symbolize_reads_addresses_from_stdin() {
+    assert_eq!(
+        symbolize(
+            &["frames"],
+            "0x1170\n1245\n\n  0x4010 \nnot an address\n0\n"
+        ),
+        "0x1170 mix\n0x1245 sum+0x5\n0x4010 __dso_handle\nnot an address ??\n0x0 ??\n"
+    );
+}
+
+#[test]
+fn symbolize_takes_addresses_as_arguments() {
+    assert_eq!(
+        symbolize(&["frames-nosyms", "0x1055", "1175"], ""),
+        "0x1055 main+0x5\n0x1175 mix+0x5\n"
+    );
+}
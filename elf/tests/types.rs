@@ -0,0 +1,87 @@
+/// Checks the names given to segment and section types: the standard ones,
+/// the GNU and other OS-specific extensions, and the processor-specific
+/// ones, which depend on the machine.
+///
+use elf::parse::header::{EM_AARCH64, EM_ARM, EM_MIPS, EM_RISCV, EM_X86_64};
+use elf::parse::types::*;
+use elf::parse::{program_header_type_string, section_header_type_string};
+
+fn segment(segment_type: u32, machine: u16) -> String {
+    program_header_type_string(&segment_type.to_le_bytes(), machine)
+}
+
+fn section(section_type: u32, machine: u16) -> String {
+    section_header_type_string(&section_type.to_le_bytes(), machine)
+}
+
+#[test]
+fn names_segment_types() {
+    assert_eq!(segment(PT_LOAD, EM_X86_64), "PT_LOAD");
+    assert_eq!(segment(PT_TLS, EM_X86_64), "PT_TLS");
+    assert_eq!(segment(PT_GNU_RELRO, EM_X86_64), "PT_GNU_RELRO");
+    assert_eq!(segment(PT_GNU_SFRAME, EM_AARCH64), "PT_GNU_SFRAME");
+    assert_eq!(
+        segment(PT_OPENBSD_RANDOMIZE, EM_X86_64),
+        "PT_OPENBSD_RANDOMIZE"
+    );
+
+    assert_eq!(segment(0x60000123, EM_X86_64), "OS_SPECIFIC: 0x60000123");
+    assert_eq!(segment(0x12345678, EM_X86_64), "OTHER: 0x12345678");
+}
+
+#[test]
+fn names_processor_specific_segment_types_by_machine() {
+    // 0x70000001 is EXIDX on ARM, but means nothing on x86-64.
+    assert_eq!(segment(PT_ARM_EXIDX, EM_ARM), "PT_ARM_EXIDX");
+    assert_eq!(segment(PT_MIPS_RTPROC, EM_MIPS), "PT_MIPS_RTPROC");
+    assert_eq!(
+        segment(PT_ARM_EXIDX, EM_X86_64),
+        "PROCESSOR_SPECIFIC: 0x70000001"
+    );
+
+    // 0x70000003 is two different things.
+    assert_eq!(segment(0x70000003, EM_MIPS), "PT_MIPS_ABIFLAGS");
+    assert_eq!(segment(0x70000003, EM_RISCV), "PT_RISCV_ATTRIBUTES");
+    assert_eq!(
+        segment(PT_AARCH64_MEMTAG_MTE, EM_AARCH64),
+        "PT_AARCH64_MEMTAG_MTE"
+    );
+}
+
+#[test]
+fn names_section_types() {
+    assert_eq!(section(SHT_PROGBITS, EM_X86_64), "SHT_PROGBITS");
+    assert_eq!(section(SHT_NOBITS, EM_X86_64), "SHT_NOBITS");
+    assert_eq!(section(SHT_RELR, EM_X86_64), "SHT_RELR");
+    assert_eq!(section(SHT_GNU_HASH, EM_X86_64), "SHT_GNU_HASH");
+    assert_eq!(section(SHT_GNU_SFRAME, EM_X86_64), "SHT_GNU_SFRAME");
+    assert_eq!(section(SHT_LLVM_ADDRSIG, EM_X86_64), "SHT_LLVM_ADDRSIG");
+
+    // The versioning types keep their mixed-case names.
+    assert_eq!(section(SHT_GNU_VERSYM, EM_X86_64), "SHT_GNU_versym");
+    assert_eq!(section(SHT_GNU_VERNEED, EM_X86_64), "SHT_GNU_verneed");
+
+    assert_eq!(section(0x60000123, EM_X86_64), "OS_SPECIFIC: 0x60000123");
+    assert_eq!(section(0x80000001, EM_X86_64), "USER_SPECIFIC: 0x80000001");
+    assert_eq!(section(0x20, EM_X86_64), "UNRECOGNIZED TYPE: 0x00000020");
+}
+
+#[test]
+fn names_processor_specific_section_types_by_machine() {
+    assert_eq!(section(0x70000001, EM_X86_64), "SHT_X86_64_UNWIND");
+    assert_eq!(section(0x70000001, EM_ARM), "SHT_ARM_EXIDX");
+    assert_eq!(
+        section(0x70000001, EM_RISCV),
+        "PROCESSOR_SPECIFIC: 0x70000001"
+    );
+
+    assert_eq!(
+        section(SHT_RISCV_ATTRIBUTES, EM_RISCV),
+        "SHT_RISCV_ATTRIBUTES"
+    );
+    assert_eq!(section(SHT_MIPS_DWARF, EM_MIPS), "SHT_MIPS_DWARF");
+    assert_eq!(
+        section(SHT_AARCH64_ATTRIBUTES, EM_AARCH64),
+        "SHT_AARCH64_ATTRIBUTES"
+    );
+}
@@ -0,0 +1,96 @@
+/// Checks symbol versioning: the version of each dynamic symbol, the versions
+/// needed from each library, and which of those is the newest. The expected
+/// values are those of `readelf -V` on the fixtures.
+///
+mod common;
+
+use common::fixture;
+
+use std::cmp::Ordering;
+
+use elf::parse::types::SHT_DYNSYM;
+use elf::parse::versions::{compare_versions, split_version_name};
+use elf::ElfFile;
+
+#[test]
+fn orders_versions_by_number() {
+    assert_eq!(split_version_name("GLIBC_2.2.5"), ("GLIBC", "2.2.5"));
+    assert_eq!(split_version_name("GLIBC_PRIVATE"), ("GLIBC_PRIVATE", ""));
+
+    assert_eq!(
+        compare_versions("GLIBC_2.34", "GLIBC_2.4"),
+        Ordering::Greater
+    );
+    assert_eq!(
+        compare_versions("GLIBC_2.2.5", "GLIBC_2.2"),
+        Ordering::Greater
+    );
+    assert_eq!(
+        compare_versions("GLIBC_2.14", "GLIBC_2.14"),
+        Ordering::Equal
+    );
+    assert_eq!(
+        compare_versions("ALSA_0.9.0rc4", "ALSA_0.9"),
+        Ordering::Greater
+    );
+    assert_eq!(
+        compare_versions("GLIBCXX_3.4.9", "GLIBCXX_3.4.10"),
+        Ordering::Less
+    );
+}
+
+#[test]
+fn finds_the_newest_version_needed_from_each_library() {
+    let mut elf_file = ElfFile::open(&fixture("AudioPlayer")).unwrap();
+    let versions = elf_file.symbol_versions().unwrap().unwrap();
+
+    let newest: Vec<(&str, Vec<&str>)> = versions
+        .needs
+        .iter()
+        .map(|need| {
+            let names = need
+                .newest_versions()
+                .iter()
+                .map(|v| v.name.as_str())
+                .collect();
+            (need.file.as_str(), names)
+        })
+        .collect();
+
+    assert_eq!(
+        newest,
+        [
+            ("libgcc_s.so.1", vec!["GCC_3.0"]),
+            ("libasound.so.2", vec!["ALSA_0.9.0rc4"]),
+            ("libstdc++.so.6", vec!["GLIBCXX_3.4.31", "CXXABI_1.3.9"]),
+            ("libc.so.6", vec!["GLIBC_2.34"]),
+        ]
+    );
+}
+
+#[test]
+fn annotates_dynamic_symbols_with_versions() {
+    let mut elf_file = ElfFile::open(&fixture("forwarding")).unwrap();
+    let versions = elf_file.symbol_versions().unwrap().unwrap();
+
+    let dynsym = elf_file.section_indices_by_type(SHT_DYNSYM)[0];
+    let symbols = elf_file.symbols(dynsym).unwrap();
+
+    let names: Vec<String> = symbols
+        .iter()
+        .enumerate()
+        .map(
+            |(i, symbol)| match versions.symbol_version(i, &symbol.symbol_data) {
+                Some(version) => format!("{}{}", symbol.name, version.suffix()),
+                None => symbol.name.clone(),
+            },
+        )
+        .collect();
+
+    assert_eq!(names[2], "__libc_start_main@GLIBC_2.34");
+    assert_eq!(names[6], "__stack_chk_fail@GLIBC_2.4");
+    assert_eq!(names[9], "__gmon_start__");
+
+    // std::cout is defined here, but copied from libstdc++, so it's still needed.
+    assert_eq!(names[13], "_ZSt4cout@GLIBCXX_3.4");
+}
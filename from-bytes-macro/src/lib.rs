@@ -57,19 +57,19 @@ pub fn parse(item: TokenStream) -> TokenStream {
         } else {
             let last_current = current_byte;
             match field_type_name(f).as_str() {
-                "u8" => {
+                "u8" | "i8" => {
                     current_byte += 1;
                 }
 
-                "u16" => {
+                "u16" | "i16" => {
                     current_byte += 2;
                 }
 
-                "u32" => {
+                "u32" | "i32" => {
                     current_byte += 4;
                 }
 
-                "u64" => {
+                "u64" | "i64" => {
                     current_byte += 8;
                 }
 